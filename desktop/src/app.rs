@@ -12,6 +12,7 @@ use winit::window::WindowId;
 
 use crate::cef;
 use crate::consts::CEF_MESSAGE_LOOP_MAX_ITERATIONS;
+use crate::dirs::app_render_cache_dir;
 use crate::event::{AppEvent, AppEventScheduler};
 use crate::persist::PersistentData;
 use crate::render::{RenderError, RenderState};
@@ -472,7 +473,7 @@ impl ApplicationHandler for App {
 
 		self.resize();
 
-		self.desktop_wrapper.init(self.wgpu_context.clone());
+		self.desktop_wrapper.init(self.wgpu_context.clone(), app_render_cache_dir());
 
 		#[cfg(target_os = "windows")]
 		let platform = Platform::Windows;
@@ -1,7 +1,7 @@
 use std::fs::create_dir_all;
 use std::path::PathBuf;
 
-use crate::consts::{APP_DIRECTORY_NAME, APP_DOCUMENTS_DIRECTORY_NAME};
+use crate::consts::{APP_DIRECTORY_NAME, APP_DOCUMENTS_DIRECTORY_NAME, APP_RENDER_CACHE_DIRECTORY_NAME};
 
 pub(crate) fn ensure_dir_exists(path: &PathBuf) {
 	if !path.exists() {
@@ -20,3 +20,9 @@ pub(crate) fn app_autosave_documents_dir() -> PathBuf {
 	ensure_dir_exists(&path);
 	path
 }
+
+pub(crate) fn app_render_cache_dir() -> PathBuf {
+	let path = dirs::cache_dir().expect("Failed to get cache directory").join(APP_DIRECTORY_NAME).join(APP_RENDER_CACHE_DIRECTORY_NAME);
+	ensure_dir_exists(&path);
+	path
+}
@@ -1,6 +1,9 @@
 use graph_craft::wasm_application_io::WasmApplicationIo;
 use graphite_editor::application::Editor;
 use graphite_editor::messages::prelude::{FrontendMessage, Message};
+use graphite_editor::node_graph_executor::{DISK_CACHE_LIMIT_DEFAULT, DiskCache};
+use std::path::PathBuf;
+use std::sync::Arc;
 
 // TODO: Remove usage of this reexport in desktop create and remove this line
 pub use graphene_std::Color;
@@ -31,9 +34,18 @@ impl DesktopWrapper {
 		Self { editor: Editor::new() }
 	}
 
-	pub fn init(&self, wgpu_context: WgpuContext) {
+	pub fn init(&self, wgpu_context: WgpuContext, render_cache_dir: PathBuf) {
 		let application_io = WasmApplicationIo::new_with_context(wgpu_context);
 		futures::executor::block_on(graphite_editor::node_graph_executor::replace_application_io(application_io));
+
+		let disk_cache = match DiskCache::open(&render_cache_dir, DISK_CACHE_LIMIT_DEFAULT) {
+			Ok(disk_cache) => Some(Arc::new(disk_cache)),
+			Err(error) => {
+				tracing::error!("Failed to open the render cache at {}: {error}", render_cache_dir.display());
+				None
+			}
+		};
+		futures::executor::block_on(graphite_editor::node_graph_executor::replace_disk_cache(disk_cache));
 	}
 
 	pub fn dispatch(&mut self, message: DesktopWrapperMessage) -> Vec<DesktopFrontendMessage> {
@@ -146,6 +146,7 @@ pub const COLOR_OVERLAY_BLUE_50: &str = "#00a8ff80";
 pub const COLOR_OVERLAY_YELLOW: &str = "#ffc848";
 pub const COLOR_OVERLAY_YELLOW_DULL: &str = "#d7ba8b";
 pub const COLOR_OVERLAY_GREEN: &str = "#63ce63";
+pub const COLOR_OVERLAY_CYAN: &str = "#00d8d8";
 pub const COLOR_OVERLAY_RED: &str = "#ef5454";
 pub const COLOR_OVERLAY_GRAY: &str = "#cccccc";
 pub const COLOR_OVERLAY_GRAY_25: &str = "#cccccc40";
@@ -155,7 +156,15 @@ pub const COLOR_OVERLAY_BLACK_75: &str = "#000000bf";
 // DOCUMENT
 pub const FILE_EXTENSION: &str = "graphite";
 pub const DEFAULT_DOCUMENT_NAME: &str = "Untitled Document";
-pub const MAX_UNDO_HISTORY_LEN: usize = 100; // TODO: Add this to user preferences
+pub const MAX_SELECTION_HISTORY_LEN: usize = 100;
+pub const UNDO_HISTORY_LENGTH_DEFAULT: u32 = 100;
+pub const UNDO_HISTORY_LENGTH_MAX: u32 = 1000;
+pub const UNDO_HISTORY_MEMORY_BUDGET_DEFAULT_MB: u32 = 1024;
+pub const UNDO_HISTORY_MEMORY_BUDGET_MIN_MB: u32 = 64;
+pub const UNDO_HISTORY_MEMORY_BUDGET_MAX_MB: u32 = 16384;
+pub const NODE_CACHE_MEMORY_BUDGET_DEFAULT_MB: u32 = 512;
+pub const NODE_CACHE_MEMORY_BUDGET_MIN_MB: u32 = 64;
+pub const NODE_CACHE_MEMORY_BUDGET_MAX_MB: u32 = 16384;
 pub const AUTO_SAVE_TIMEOUT_SECONDS: u64 = 1;
 
 // INPUT
@@ -210,10 +210,11 @@ impl Dispatcher {
 				Message::KeyMapping(message) => {
 					let input = &self.message_handlers.input_preprocessor_message_handler;
 					let actions = self.collect_actions();
+					let preferences = &self.message_handlers.preferences_message_handler;
 
 					self.message_handlers
 						.key_mapping_message_handler
-						.process_message(message, &mut queue, KeyMappingMessageContext { input, actions });
+						.process_message(message, &mut queue, KeyMappingMessageContext { input, actions, preferences });
 				}
 				Message::Layout(message) => {
 					let action_input_mapping = &|action_to_find: &MessageDiscriminant| self.message_handlers.key_mapping_message_handler.action_input_mapping(action_to_find);
@@ -240,6 +241,7 @@ impl Dispatcher {
 					let menu_bar_message_handler = &mut self.message_handlers.menu_bar_message_handler;
 
 					menu_bar_message_handler.data_panel_open = self.message_handlers.portfolio_message_handler.data_panel_open;
+					menu_bar_message_handler.history_panel_open = self.message_handlers.portfolio_message_handler.history_panel_open;
 					menu_bar_message_handler.layers_panel_open = self.message_handlers.portfolio_message_handler.layers_panel_open;
 					menu_bar_message_handler.properties_panel_open = self.message_handlers.portfolio_message_handler.properties_panel_open;
 					menu_bar_message_handler.message_logging_verbosity = self.message_handlers.debug_message_handler.message_logging_verbosity;
@@ -258,20 +260,28 @@ impl Dispatcher {
 						menu_bar_message_handler.canvas_tilted = document.document_ptz.tilt() != 0.;
 						menu_bar_message_handler.canvas_flipped = document.document_ptz.flip;
 						menu_bar_message_handler.rulers_visible = document.rulers_visible;
+						menu_bar_message_handler.guides_visible = document.guides.visible;
+						menu_bar_message_handler.guides_locked = document.guides.locked;
+						menu_bar_message_handler.has_guides = !document.guides.guides.is_empty();
 						menu_bar_message_handler.node_graph_open = document.is_graph_overlay_open();
 						menu_bar_message_handler.has_selected_nodes = selected_nodes.selected_nodes().next().is_some();
 						menu_bar_message_handler.has_selected_layers = selected_nodes.selected_visible_layers(&document.network_interface).next().is_some();
 						menu_bar_message_handler.has_selection_history = (!metadata.selection_undo_history.is_empty(), !metadata.selection_redo_history.is_empty());
+						menu_bar_message_handler.has_pixel_selection = !document.pixel_selection.is_empty();
 						menu_bar_message_handler.make_path_editable_is_allowed = make_path_editable_is_allowed(&mut document.network_interface).is_some();
 					} else {
 						menu_bar_message_handler.has_active_document = false;
 						menu_bar_message_handler.canvas_tilted = false;
 						menu_bar_message_handler.canvas_flipped = false;
 						menu_bar_message_handler.rulers_visible = false;
+						menu_bar_message_handler.guides_visible = false;
+						menu_bar_message_handler.guides_locked = false;
+						menu_bar_message_handler.has_guides = false;
 						menu_bar_message_handler.node_graph_open = false;
 						menu_bar_message_handler.has_selected_nodes = false;
 						menu_bar_message_handler.has_selected_layers = false;
 						menu_bar_message_handler.has_selection_history = (false, false);
+						menu_bar_message_handler.has_pixel_selection = false;
 						menu_bar_message_handler.make_path_editable_is_allowed = false;
 					}
 
@@ -338,6 +348,7 @@ impl Dispatcher {
 			&& !document.graph_view_overlay_open
 		{
 			list.extend(self.message_handlers.tool_message_handler.actions_with_preferences(&self.message_handlers.preferences_message_handler));
+			list.extend(document.actions_with_input(&self.message_handlers.input_preprocessor_message_handler));
 		}
 		list.extend(self.message_handlers.portfolio_message_handler.actions());
 		list
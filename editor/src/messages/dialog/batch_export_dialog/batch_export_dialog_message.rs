@@ -0,0 +1,15 @@
+use crate::messages::frontend::utility_types::{ExportPreset, ExportTarget};
+use crate::messages::portfolio::document::utility_types::misc::SliceId;
+use crate::messages::prelude::*;
+
+#[impl_message(Message, DialogMessage, BatchExportDialog)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum BatchExportDialogMessage {
+	AddPreset { target: ExportTarget },
+	RemovePreset { target: ExportTarget, index: usize },
+	UpdatePreset { target: ExportTarget, index: usize, preset: ExportPreset },
+	AddSelectedLayers,
+	AddSlice,
+	DeleteSlice { id: SliceId },
+	RenameSlice { id: SliceId, name: String },
+}
@@ -0,0 +1,226 @@
+use crate::messages::frontend::utility_types::{ExportPreset, ExportTarget, FileType};
+use crate::messages::layout::utility_types::widget_prelude::*;
+use crate::messages::prelude::*;
+
+#[derive(ExtractField)]
+pub struct BatchExportDialogMessageContext<'a> {
+	pub portfolio: &'a PortfolioMessageHandler,
+}
+
+/// A dialog for attaching export presets to the artboards, layers, and slices of the document, and exporting all of them at once.
+#[derive(Debug, Clone, Default, ExtractField)]
+pub struct BatchExportDialogMessageHandler {
+	/// The items of the active document which can have presets, with their names and presets, as of when the dialog was last opened or changed.
+	pub targets: Vec<(ExportTarget, String, Vec<ExportPreset>)>,
+	pub has_selection: bool,
+}
+
+#[message_handler_data]
+impl MessageHandler<BatchExportDialogMessage, BatchExportDialogMessageContext<'_>> for BatchExportDialogMessageHandler {
+	fn process_message(&mut self, message: BatchExportDialogMessage, responses: &mut VecDeque<Message>, context: BatchExportDialogMessageContext) {
+		let BatchExportDialogMessageContext { portfolio } = context;
+
+		match message {
+			BatchExportDialogMessage::AddPreset { target } => self.edit_presets(target, responses, |presets| presets.push(ExportPreset::default())),
+			BatchExportDialogMessage::RemovePreset { target, index } => self.edit_presets(target, responses, |presets| {
+				if index < presets.len() {
+					presets.remove(index);
+				}
+			}),
+			BatchExportDialogMessage::UpdatePreset { target, index, preset } => self.edit_presets(target, responses, |presets| {
+				if let Some(existing) = presets.get_mut(index) {
+					*existing = preset;
+				}
+			}),
+			BatchExportDialogMessage::AddSelectedLayers => {
+				let Some(document) = portfolio.active_document() else { return };
+
+				for layer in document.network_interface.selected_nodes().selected_layers(document.metadata()) {
+					if document.network_interface.export_presets(&layer.to_node(), &[]).is_empty() {
+						responses.add(DocumentMessage::SetExportPresets {
+							target: ExportTarget::Layer(layer),
+							presets: vec![ExportPreset::default()],
+						});
+					}
+				}
+				responses.add(DialogMessage::RequestBatchExportDialog);
+			}
+			BatchExportDialogMessage::AddSlice => {
+				responses.add(DocumentMessage::AddExportSlice);
+				responses.add(DialogMessage::RequestBatchExportDialog);
+			}
+			BatchExportDialogMessage::DeleteSlice { id } => {
+				responses.add(DocumentMessage::DeleteExportSlice { id });
+				responses.add(DialogMessage::RequestBatchExportDialog);
+			}
+			BatchExportDialogMessage::RenameSlice { id, name } => {
+				responses.add(DocumentMessage::RenameExportSlice { id, name });
+				responses.add(DialogMessage::RequestBatchExportDialog);
+			}
+		}
+	}
+
+	advertise_actions! {BatchExportDialogUpdate;}
+}
+
+impl BatchExportDialogMessageHandler {
+	/// Applies a change to the presets of one target, both in the dialog and in the document.
+	fn edit_presets(&mut self, target: ExportTarget, responses: &mut VecDeque<Message>, edit: impl FnOnce(&mut Vec<ExportPreset>)) {
+		let Some((_, _, presets)) = self.targets.iter_mut().find(|(candidate, _, _)| *candidate == target) else {
+			return;
+		};
+
+		edit(presets);
+		responses.add(DocumentMessage::SetExportPresets { target, presets: presets.clone() });
+		self.send_dialog_to_frontend(responses);
+	}
+
+	fn preset_row(target: ExportTarget, index: usize, preset: &ExportPreset) -> Vec<WidgetInstance> {
+		let update = move |preset: ExportPreset| -> Message { BatchExportDialogMessage::UpdatePreset { target, index, preset }.into() };
+
+		let file_types = [(FileType::Png, "PNG"), (FileType::Jpg, "JPG"), (FileType::Svg, "SVG")]
+			.into_iter()
+			.map(|(file_type, name)| {
+				let preset = ExportPreset { file_type, ..preset.clone() };
+				RadioEntryData::new(format!("{file_type:?}")).label(name).on_update(move |_| update(preset.clone()))
+			})
+			.collect();
+
+		let mut widgets = vec![
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			RadioInput::new(file_types).selected_index(Some(preset.file_type as u32)).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+		];
+
+		for scale in ExportPreset::SCALE_CHOICES {
+			let checked = preset.scales.contains(&scale);
+			let preset = preset.clone();
+			let checkbox_id = CheckboxId::new();
+			widgets.extend([
+				CheckboxInput::new(checked)
+					.disabled(preset.file_type == FileType::Svg)
+					.on_update(move |checkbox_input: &CheckboxInput| {
+						let mut preset = preset.clone();
+						preset.scales.retain(|&existing| existing != scale);
+						if checkbox_input.checked {
+							preset.scales.push(scale);
+						}
+						update(preset)
+					})
+					.for_label(checkbox_id)
+					.widget_instance(),
+				TextLabel::new(format!("{scale}x")).for_checkbox(checkbox_id).widget_instance(),
+				Separator::new(SeparatorStyle::Related).widget_instance(),
+			]);
+		}
+
+		let suffix_preset = preset.clone();
+		let transparency_preset = preset.clone();
+		let checkbox_id = CheckboxId::new();
+		widgets.extend([
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			TextInput::new(&preset.suffix)
+				.placeholder(Some("Suffix".to_string()))
+				.tooltip_description("Text added to the end of the file name.")
+				.min_width(100)
+				.on_update(move |text_input: &TextInput| {
+					update(ExportPreset {
+						suffix: text_input.value.clone(),
+						..suffix_preset.clone()
+					})
+				})
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			CheckboxInput::new(preset.transparent_background)
+				.disabled(preset.file_type == FileType::Jpg)
+				.on_update(move |checkbox_input: &CheckboxInput| {
+					update(ExportPreset {
+						transparent_background: checkbox_input.checked,
+						..transparency_preset.clone()
+					})
+				})
+				.for_label(checkbox_id)
+				.widget_instance(),
+			TextLabel::new("Transparency").for_checkbox(checkbox_id).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			IconButton::new("Trash", 16)
+				.tooltip_label("Remove Preset")
+				.on_update(move |_| BatchExportDialogMessage::RemovePreset { target, index }.into())
+				.widget_instance(),
+		]);
+
+		widgets
+	}
+}
+
+impl DialogLayoutHolder for BatchExportDialogMessageHandler {
+	const ICON: &'static str = "File";
+	const TITLE: &'static str = "Export All";
+
+	fn layout_buttons(&self) -> Layout {
+		let has_presets = self.targets.iter().any(|(_, _, presets)| !presets.is_empty());
+
+		let widgets = vec![
+			TextButton::new("Export All")
+				.emphasized(true)
+				.disabled(!has_presets)
+				.on_update(|_| {
+					DialogMessage::CloseDialogAndThen {
+						followups: vec![PortfolioMessage::SubmitBatchExport.into()],
+					}
+					.into()
+				})
+				.widget_instance(),
+			TextButton::new("Close").on_update(|_| FrontendMessage::DisplayDialogDismiss.into()).widget_instance(),
+		];
+
+		Layout(vec![LayoutGroup::Row { widgets }])
+	}
+}
+
+impl LayoutHolder for BatchExportDialogMessageHandler {
+	fn layout(&self) -> Layout {
+		let mut rows = vec![vec![
+			TextButton::new("Add Selected Layers")
+				.tooltip_description("Give each selected layer a preset so it's included in Export All.")
+				.disabled(!self.has_selection)
+				.on_update(|_| BatchExportDialogMessage::AddSelectedLayers.into())
+				.widget_instance(),
+			TextButton::new("Add Slice")
+				.tooltip_description("Add a slice covering the selected layers, or all artwork if nothing is selected, to export that region by itself.")
+				.on_update(|_| BatchExportDialogMessage::AddSlice.into())
+				.widget_instance(),
+		]];
+
+		for (target, name, presets) in &self.targets {
+			let target = *target;
+
+			let mut header = match target {
+				ExportTarget::Layer(_) => vec![TextLabel::new(name).bold(true).widget_instance()],
+				ExportTarget::Slice(id) => vec![
+					TextInput::new(name)
+						.tooltip_label("Slice Name")
+						.on_update(move |text_input: &TextInput| BatchExportDialogMessage::RenameSlice { id, name: text_input.value.clone() }.into())
+						.widget_instance(),
+					Separator::new(SeparatorStyle::Related).widget_instance(),
+					IconButton::new("Trash", 16)
+						.tooltip_label("Delete Slice")
+						.on_update(move |_| BatchExportDialogMessage::DeleteSlice { id }.into())
+						.widget_instance(),
+				],
+			};
+			header.extend([
+				Separator::new(SeparatorStyle::Related).widget_instance(),
+				IconButton::new("Add", 16)
+					.tooltip_label("Add Preset")
+					.on_update(move |_| BatchExportDialogMessage::AddPreset { target }.into())
+					.widget_instance(),
+			]);
+			rows.push(header);
+
+			rows.extend(presets.iter().enumerate().map(|(index, preset)| Self::preset_row(target, index, preset)));
+		}
+
+		Layout(rows.into_iter().map(|widgets| LayoutGroup::Row { widgets }).collect())
+	}
+}
@@ -5,6 +5,8 @@ use crate::messages::prelude::*;
 pub enum DialogMessage {
 	// Sub-messages
 	#[child]
+	BatchExportDialog(BatchExportDialogMessage),
+	#[child]
 	ExportDialog(ExportDialogMessage),
 	#[child]
 	NewDocumentDialog(NewDocumentDialogMessage),
@@ -25,6 +27,7 @@ pub enum DialogMessage {
 		localized_commit_date: String,
 		localized_commit_year: String,
 	},
+	RequestBatchExportDialog,
 	RequestDemoArtworkDialog,
 	RequestExportDialog,
 	RequestLicensesDialogWithLocalizedCommitDate {
@@ -13,6 +13,7 @@ pub struct DialogMessageContext<'a> {
 /// Stores the dialogs which require state. These are the ones that have their own message handlers, and are not the ones defined in `simple_dialogs`.
 #[derive(Debug, Default, Clone, ExtractField)]
 pub struct DialogMessageHandler {
+	batch_export_dialog: BatchExportDialogMessageHandler,
 	export_dialog: ExportDialogMessageHandler,
 	new_document_dialog: NewDocumentDialogMessageHandler,
 	preferences_dialog: PreferencesDialogMessageHandler,
@@ -24,9 +25,17 @@ impl MessageHandler<DialogMessage, DialogMessageContext<'_>> for DialogMessageHa
 		let DialogMessageContext { portfolio, preferences } = context;
 
 		match message {
+			DialogMessage::BatchExportDialog(message) => self.batch_export_dialog.process_message(message, responses, BatchExportDialogMessageContext { portfolio }),
 			DialogMessage::ExportDialog(message) => self.export_dialog.process_message(message, responses, ExportDialogMessageContext { portfolio }),
 			DialogMessage::NewDocumentDialog(message) => self.new_document_dialog.process_message(message, responses, ()),
-			DialogMessage::PreferencesDialog(message) => self.preferences_dialog.process_message(message, responses, PreferencesDialogMessageContext { preferences }),
+			DialogMessage::PreferencesDialog(message) => self.preferences_dialog.process_message(
+				message,
+				responses,
+				PreferencesDialogMessageContext {
+					preferences,
+					memo_cache_stats: portfolio.executor.memo_cache_stats(),
+				},
+			),
 
 			DialogMessage::CloseAllDocumentsWithConfirmation => {
 				let dialog = simple_dialogs::CloseAllDocumentsDialog {
@@ -67,6 +76,13 @@ impl MessageHandler<DialogMessage, DialogMessageContext<'_>> for DialogMessageHa
 				let dialog = DemoArtworkDialog;
 				dialog.send_dialog_to_frontend(responses);
 			}
+			DialogMessage::RequestBatchExportDialog => {
+				if let Some(document) = portfolio.active_document() {
+					self.batch_export_dialog.targets = document.export_preset_targets().into_iter().map(|(target, name, presets)| (target, name, presets.to_vec())).collect();
+					self.batch_export_dialog.has_selection = document.network_interface.selected_nodes().selected_layers(document.metadata()).next().is_some();
+					self.batch_export_dialog.send_dialog_to_frontend(responses);
+				}
+			}
 			DialogMessage::RequestExportDialog => {
 				if let Some(document) = portfolio.active_document() {
 					let artboards = document
@@ -107,7 +123,10 @@ impl MessageHandler<DialogMessage, DialogMessageContext<'_>> for DialogMessageHa
 				self.new_document_dialog.send_dialog_to_frontend(responses);
 			}
 			DialogMessage::RequestPreferencesDialog => {
-				self.preferences_dialog = PreferencesDialogMessageHandler {};
+				self.preferences_dialog = PreferencesDialogMessageHandler {
+					memo_cache_stats: portfolio.executor.memo_cache_stats(),
+					..Default::default()
+				};
 				self.preferences_dialog.send_dialog_to_frontend(responses, preferences);
 			}
 		}
@@ -115,6 +134,7 @@ impl MessageHandler<DialogMessage, DialogMessageContext<'_>> for DialogMessageHa
 
 	advertise_actions!(DialogMessageDiscriminant;
 		CloseAllDocumentsWithConfirmation,
+		RequestBatchExportDialog,
 		RequestExportDialog,
 		RequestNewDocumentDialog,
 		RequestPreferencesDialog,
@@ -1,5 +1,6 @@
 use crate::messages::frontend::utility_types::{ExportBounds, FileType};
 use crate::messages::prelude::*;
+use graphene_std::raster_types::color_management::ColorSpace;
 
 #[impl_message(Message, DialogMessage, ExportDialog)]
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -8,6 +9,8 @@ pub enum ExportDialogMessage {
 	ScaleFactor { factor: f64 },
 	TransparentBackground { transparent: bool },
 	ExportBounds { bounds: ExportBounds },
+	ColorProfile { color_profile: ColorSpace },
+	EmbedColorProfile { embed: bool },
 
 	Submit,
 }
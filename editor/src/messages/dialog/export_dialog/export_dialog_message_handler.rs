@@ -2,6 +2,8 @@ use crate::messages::frontend::utility_types::{ExportBounds, FileType};
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::prelude::*;
+use graphene_std::choice_type::ChoiceTypeStatic;
+use graphene_std::raster_types::color_management::ColorSpace;
 
 #[derive(ExtractField)]
 pub struct ExportDialogMessageContext<'a> {
@@ -15,6 +17,8 @@ pub struct ExportDialogMessageHandler {
 	pub scale_factor: f64,
 	pub bounds: ExportBounds,
 	pub transparent_background: bool,
+	pub color_profile: ColorSpace,
+	pub embed_color_profile: bool,
 	pub artboards: HashMap<LayerNodeIdentifier, String>,
 	pub has_selection: bool,
 }
@@ -26,6 +30,8 @@ impl Default for ExportDialogMessageHandler {
 			scale_factor: 1.,
 			bounds: Default::default(),
 			transparent_background: false,
+			color_profile: ColorSpace::Srgb,
+			embed_color_profile: true,
 			artboards: Default::default(),
 			has_selection: false,
 		}
@@ -42,6 +48,8 @@ impl MessageHandler<ExportDialogMessage, ExportDialogMessageContext<'_>> for Exp
 			ExportDialogMessage::ScaleFactor { factor } => self.scale_factor = factor,
 			ExportDialogMessage::TransparentBackground { transparent } => self.transparent_background = transparent,
 			ExportDialogMessage::ExportBounds { bounds } => self.bounds = bounds,
+			ExportDialogMessage::ColorProfile { color_profile } => self.color_profile = color_profile,
+			ExportDialogMessage::EmbedColorProfile { embed } => self.embed_color_profile = embed,
 
 			ExportDialogMessage::Submit => {
 				let artboard_name = match self.bounds {
@@ -56,6 +64,8 @@ impl MessageHandler<ExportDialogMessage, ExportDialogMessageContext<'_>> for Exp
 					transparent_background: self.file_type != FileType::Jpg && self.transparent_background,
 					artboard_name,
 					artboard_count: self.artboards.len(),
+					color_profile: self.color_profile,
+					embed_color_profile: self.embed_color_profile,
 				})
 			}
 		}
@@ -168,11 +178,45 @@ impl LayoutHolder for ExportDialogMessageHandler {
 				.widget_instance(),
 		];
 
+		let color_profile_entries = vec![
+			ColorSpace::list()
+				.iter()
+				.flat_map(|group| group.iter())
+				.map(|(color_profile, metadata)| {
+					let color_profile = *color_profile;
+					MenuListEntry::new(metadata.name.as_ref())
+						.label(metadata.label.as_ref())
+						.on_commit(move |_| ExportDialogMessage::ColorProfile { color_profile }.into())
+				})
+				.collect(),
+		];
+		let color_profile = vec![
+			TextLabel::new("Color Profile").table_align(true).min_width(100).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			DropdownInput::new(color_profile_entries)
+				.selected_index(Some(self.color_profile as u32))
+				.disabled(self.file_type == FileType::Svg)
+				.widget_instance(),
+		];
+
+		let checkbox_id = CheckboxId::new();
+		let embed_color_profile = vec![
+			TextLabel::new("Embed Profile").table_align(true).min_width(100).for_checkbox(checkbox_id).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			CheckboxInput::new(self.embed_color_profile)
+				.disabled(self.file_type == FileType::Svg)
+				.on_update(move |value: &CheckboxInput| ExportDialogMessage::EmbedColorProfile { embed: value.checked }.into())
+				.for_label(checkbox_id)
+				.widget_instance(),
+		];
+
 		Layout(vec![
 			LayoutGroup::Row { widgets: export_type },
 			LayoutGroup::Row { widgets: resolution },
 			LayoutGroup::Row { widgets: export_area },
 			LayoutGroup::Row { widgets: transparent_background },
+			LayoutGroup::Row { widgets: color_profile },
+			LayoutGroup::Row { widgets: embed_color_profile },
 		])
 	}
 }
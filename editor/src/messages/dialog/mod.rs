@@ -8,6 +8,7 @@
 mod dialog_message;
 mod dialog_message_handler;
 
+pub mod batch_export_dialog;
 pub mod export_dialog;
 pub mod new_document_dialog;
 pub mod preferences_dialog;
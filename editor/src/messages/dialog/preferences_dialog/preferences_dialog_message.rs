@@ -1,7 +1,13 @@
+use crate::messages::input_mapper::utility_types::input_keyboard::Key;
 use crate::messages::prelude::*;
 
 #[impl_message(Message, DialogMessage, PreferencesDialog)]
-#[derive(Eq, PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum PreferencesDialogMessage {
 	Confirm,
+	Update,
+	ShortcutSearch { search: String },
+	ShortcutAction { action: Option<Box<Message>> },
+	ShortcutKey { key: Key },
+	ShortcutModifier { modifier: Key, held: bool },
 }
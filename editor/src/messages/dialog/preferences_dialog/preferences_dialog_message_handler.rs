@@ -1,25 +1,62 @@
-use crate::consts::{VIEWPORT_ZOOM_WHEEL_RATE, VIEWPORT_ZOOM_WHEEL_RATE_CHANGE};
+use crate::consts::{
+	NODE_CACHE_MEMORY_BUDGET_DEFAULT_MB, NODE_CACHE_MEMORY_BUDGET_MAX_MB, NODE_CACHE_MEMORY_BUDGET_MIN_MB, UNDO_HISTORY_LENGTH_DEFAULT, UNDO_HISTORY_LENGTH_MAX, UNDO_HISTORY_MEMORY_BUDGET_DEFAULT_MB,
+	UNDO_HISTORY_MEMORY_BUDGET_MAX_MB, UNDO_HISTORY_MEMORY_BUDGET_MIN_MB, VIEWPORT_ZOOM_WHEEL_RATE, VIEWPORT_ZOOM_WHEEL_RATE_CHANGE,
+};
+use crate::messages::input_mapper::utility_types::input_keyboard::Key;
+use crate::messages::input_mapper::utility_types::misc::Mapping;
+use crate::messages::input_mapper::utility_types::user_keymap::{KeyBindingChange, KeyboardShortcut, UserKeyBinding, action_label, action_shortcuts, customizable_actions, shortcut_keys};
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::utility_types::wires::GraphWireStyle;
 use crate::messages::preferences::SelectionMode;
 use crate::messages::prelude::*;
+use graphene_std::memo::MemoCacheStats;
 
 #[derive(ExtractField)]
 pub struct PreferencesDialogMessageContext<'a> {
 	pub preferences: &'a PreferencesMessageHandler,
+	pub memo_cache_stats: MemoCacheStats,
 }
 
+/// The most actions listed at once in the keyboard shortcuts section, so the search needs to be narrowed down to find the others.
+const SHORTCUT_SEARCH_RESULTS_MAX: usize = 10;
+
 /// A dialog to allow users to customize Graphite editor options
 #[derive(Debug, Clone, Default, ExtractField)]
-pub struct PreferencesDialogMessageHandler {}
+pub struct PreferencesDialogMessageHandler {
+	/// Filters the actions listed in the keyboard shortcuts section.
+	shortcut_search: String,
+	/// The action whose keyboard shortcuts are being edited.
+	shortcut_action: Option<Message>,
+	/// The key of the shortcut being assigned to the action.
+	shortcut_key: Option<Key>,
+	/// The modifier keys of the shortcut being assigned to the action.
+	shortcut_modifiers: Vec<Key>,
+	/// The usage of the node caches as of the latest graph execution, shown below the node cache memory preference.
+	pub memo_cache_stats: MemoCacheStats,
+}
 
 #[message_handler_data]
 impl MessageHandler<PreferencesDialogMessage, PreferencesDialogMessageContext<'_>> for PreferencesDialogMessageHandler {
 	fn process_message(&mut self, message: PreferencesDialogMessage, responses: &mut VecDeque<Message>, context: PreferencesDialogMessageContext) {
-		let PreferencesDialogMessageContext { preferences } = context;
+		let PreferencesDialogMessageContext { preferences, memo_cache_stats } = context;
+		self.memo_cache_stats = memo_cache_stats;
 
 		match message {
 			PreferencesDialogMessage::Confirm => {}
+			PreferencesDialogMessage::Update => {}
+			PreferencesDialogMessage::ShortcutSearch { search } => self.shortcut_search = search,
+			PreferencesDialogMessage::ShortcutAction { action } => {
+				self.shortcut_action = action.map(|action| *action);
+				self.shortcut_key = None;
+				self.shortcut_modifiers.clear();
+			}
+			PreferencesDialogMessage::ShortcutKey { key } => self.shortcut_key = Some(key),
+			PreferencesDialogMessage::ShortcutModifier { modifier, held } => {
+				self.shortcut_modifiers.retain(|&existing| existing != modifier);
+				if held {
+					self.shortcut_modifiers.push(modifier);
+				}
+			}
 		}
 
 		self.send_dialog_to_frontend(responses, preferences);
@@ -152,7 +189,127 @@ impl PreferencesDialogMessageHandler {
 				selection_mode,
 			];
 
-			rows.extend_from_slice(&[header, selection_label, selection_mode]);
+			let undo_history_length_description = "The number of steps that can be undone in each document. Fewer steps use less memory.";
+			let undo_history_length_label = vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				TextLabel::new("Undo Steps")
+					.tooltip_label("Undo Steps")
+					.tooltip_description(undo_history_length_description)
+					.widget_instance(),
+			];
+			let undo_history_length = vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				NumberInput::new(Some(preferences.undo_history_length as f64))
+					.tooltip_label("Undo Steps")
+					.tooltip_description(undo_history_length_description)
+					.int()
+					.min(1.)
+					.max(UNDO_HISTORY_LENGTH_MAX as f64)
+					.on_update(|number_input: &NumberInput| {
+						let length = number_input.value.map_or(UNDO_HISTORY_LENGTH_DEFAULT, |value| value as u32);
+						PreferencesMessage::UndoHistoryLength { length }.into()
+					})
+					.widget_instance(),
+			];
+
+			let undo_history_memory_description = "
+			The approximate amount of memory that the undo history of each document may use. Once exceeded, the oldest steps are discarded.\n\
+			\n\
+			Edits to large images use the most memory. Snapshots in the History panel also count toward this amount, but are only discarded when deleted.
+			"
+			.trim();
+			let undo_history_memory_label = vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				TextLabel::new("Undo Memory")
+					.tooltip_label("Undo Memory")
+					.tooltip_description(undo_history_memory_description)
+					.widget_instance(),
+			];
+			let undo_history_memory = vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				NumberInput::new(Some(preferences.undo_history_memory_budget as f64))
+					.tooltip_label("Undo Memory")
+					.tooltip_description(undo_history_memory_description)
+					.int()
+					.min(UNDO_HISTORY_MEMORY_BUDGET_MIN_MB as f64)
+					.max(UNDO_HISTORY_MEMORY_BUDGET_MAX_MB as f64)
+					.step(64.)
+					.unit(" MB")
+					.on_update(|number_input: &NumberInput| {
+						let megabytes = number_input.value.map_or(UNDO_HISTORY_MEMORY_BUDGET_DEFAULT_MB, |value| value as u32);
+						PreferencesMessage::UndoHistoryMemoryBudget { megabytes }.into()
+					})
+					.widget_instance(),
+			];
+
+			let node_cache_memory_description = "
+			The approximate amount of memory shared by the caches of nodes which keep several of their outputs, such as Boolean Operation and Blur. Once exceeded, the least recently used outputs are discarded and recomputed when needed again.\n\
+			\n\
+			More memory keeps animations, instances, and artboards responsive when switching between them.
+			"
+			.trim();
+			let node_cache_memory_label = vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				TextLabel::new("Node Cache Memory")
+					.tooltip_label("Node Cache Memory")
+					.tooltip_description(node_cache_memory_description)
+					.widget_instance(),
+			];
+			let node_cache_memory = vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				NumberInput::new(Some(preferences.node_cache_memory_budget as f64))
+					.tooltip_label("Node Cache Memory")
+					.tooltip_description(node_cache_memory_description)
+					.int()
+					.min(NODE_CACHE_MEMORY_BUDGET_MIN_MB as f64)
+					.max(NODE_CACHE_MEMORY_BUDGET_MAX_MB as f64)
+					.step(64.)
+					.unit(" MB")
+					.on_update(|number_input: &NumberInput| {
+						let megabytes = number_input.value.map_or(NODE_CACHE_MEMORY_BUDGET_DEFAULT_MB, |value| value as u32);
+						PreferencesMessage::NodeCacheMemoryBudget { megabytes }.into()
+					})
+					.widget_instance(),
+			];
+
+			let MemoCacheStats {
+				budget,
+				used,
+				entries,
+				hits,
+				misses,
+				evictions,
+			} = self.memo_cache_stats;
+			let megabytes = |bytes: usize| bytes as f64 / (1024. * 1024.);
+			let node_cache_usage = vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				TextLabel::new(format!("{:.0} of {:.0} MB used by {entries} cached outputs", megabytes(used), megabytes(budget)))
+					.tooltip_label("Node Cache Usage")
+					.tooltip_description(format!(
+						"As of the latest graph execution: {hits} cache hits, {misses} misses, and {evictions} outputs evicted to stay within the budget."
+					))
+					.widget_instance(),
+			];
+
+			rows.extend_from_slice(&[
+				header,
+				selection_label,
+				selection_mode,
+				undo_history_length_label,
+				undo_history_length,
+				undo_history_memory_label,
+				undo_history_memory,
+				node_cache_memory_label,
+				node_cache_memory,
+				node_cache_usage,
+			]);
 		}
 
 		// =========
@@ -258,7 +415,7 @@ impl PreferencesDialogMessageHandler {
 
 			let checkbox_id = CheckboxId::new();
 			let brush_tool_description = "
-			Enable the Brush tool to support basic raster-based layer painting.\n\
+			Enable the Brush tool to support basic raster-based layer painting, along with the Heal, Clone, and Patch tools for retouching raster layers.\n\
 			\n\
 			This legacy experimental tool has performance and quality limitations and is slated for replacement in future versions of Graphite that will focus on raster graphics editing.\n\
 			\n\
@@ -294,13 +451,228 @@ impl PreferencesDialogMessageHandler {
 		})
 	}
 
-	fn layout_column_2(&self) -> Layout {
-		Layout::default()
+	fn layout_column_2(&self, preferences: &PreferencesMessageHandler) -> Layout {
+		let mut rows = Vec::new();
+
+		// ==================
+		// KEYBOARD SHORTCUTS
+		// ==================
+		let header = vec![TextLabel::new("Keyboard Shortcuts").italic(true).widget_instance()];
+
+		let search = vec![
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			TextInput::new(&self.shortcut_search)
+				.placeholder(Some("Search actions".to_string()))
+				.tooltip_label("Search Actions")
+				.tooltip_description("Find the actions whose keyboard shortcuts you want to change.")
+				.on_update(|text_input: &TextInput| PreferencesDialogMessage::ShortcutSearch { search: text_input.value.clone() }.into())
+				.widget_instance(),
+		];
+		rows.extend_from_slice(&[header, search]);
+
+		let mapping = preferences.key_mapping();
+		let shortcuts_label = |action: &Message| {
+			let shortcuts = action_shortcuts(&mapping, action);
+			if shortcuts.is_empty() {
+				"None".to_string()
+			} else {
+				shortcuts.iter().map(|shortcut| shortcut.keys_group().to_string()).collect::<Vec<_>>().join(", ")
+			}
+		};
+
+		// Actions whose shortcuts were all removed by the user are still listed, since they're found in the built-in mapping
+		let search = self.shortcut_search.to_lowercase();
+		let actions = customizable_actions(&preferences.mapping_variant().into())
+			.into_iter()
+			.map(|action| (action_label(&action), action))
+			.filter(|(label, _)| label.to_lowercase().contains(&search));
+		for (label, action) in actions.take(SHORTCUT_SEARCH_RESULTS_MAX) {
+			let selected = self.shortcut_action.as_ref() == Some(&action);
+			let shortcuts = shortcuts_label(&action);
+			let select = if selected { None } else { Some(Box::new(action)) };
+			rows.push(vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				TextButton::new(label)
+					.flush(true)
+					.emphasized(selected)
+					.on_update(move |_| PreferencesDialogMessage::ShortcutAction { action: select.clone() }.into())
+					.widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				TextLabel::new(shortcuts).italic(true).widget_instance(),
+			]);
+		}
+
+		if let Some(action) = &self.shortcut_action {
+			rows.extend(self.shortcut_editor_rows(action, preferences, &mapping));
+		}
+
+		let keymap_buttons = vec![
+			TextButton::new("Import…")
+				.tooltip_description("Load keyboard shortcuts from a keymap file, replacing your current changes.")
+				.on_update(|_| PreferencesMessage::ImportKeymap.into())
+				.widget_instance(),
+			TextButton::new("Export…")
+				.tooltip_description("Save your changes to the keyboard shortcuts as a keymap file which can be imported elsewhere.")
+				.disabled(preferences.keymap.bindings.is_empty())
+				.on_update(|_| PreferencesMessage::ExportKeymap.into())
+				.widget_instance(),
+			TextButton::new("Reset All Shortcuts")
+				.disabled(preferences.keymap.bindings.is_empty())
+				.on_update(|_| PreferencesMessage::ResetKeyBindings { action: None }.into())
+				.widget_instance(),
+		];
+		rows.push(keymap_buttons);
+
+		// ==============
+		// NODE LIBRARIES
+		// ==============
+		rows.push(vec![TextLabel::new("Node Libraries").italic(true).widget_instance()]);
+
+		for library in &preferences.node_libraries {
+			let name = library.name.clone();
+			let nodes = match library.nodes.len() {
+				1 => "1 node".to_string(),
+				count => format!("{count} nodes"),
+			};
+			rows.push(vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				TextLabel::new(format!("{} (version {})", library.name, library.version)).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				TextLabel::new(nodes).italic(true).widget_instance(),
+				Separator::new(SeparatorStyle::Related).widget_instance(),
+				IconButton::new("Trash", 16)
+					.tooltip_label("Remove Node Library")
+					.tooltip_description("Remove the library's nodes from the node catalog. Nodes already placed in documents are kept.")
+					.on_update(move |_| PreferencesMessage::RemoveNodeLibrary { name: name.clone() }.into())
+					.widget_instance(),
+			]);
+		}
+
+		rows.push(vec![
+			TextButton::new("Import…")
+				.tooltip_description("Add the nodes of a node library file to the node catalog. Importing a newer version of a library replaces the older one.")
+				.on_update(|_| PreferencesMessage::ImportNodeLibrary.into())
+				.widget_instance(),
+		]);
+
+		Layout(rows.into_iter().map(|r| LayoutGroup::Row { widgets: r }).collect())
+	}
+
+	/// The rows for editing the shortcuts of the selected action: its current shortcuts, the inputs for assigning a new one, and any conflicts.
+	fn shortcut_editor_rows(&self, action: &Message, preferences: &PreferencesMessageHandler, mapping: &Mapping) -> Vec<Vec<WidgetInstance>> {
+		let mut rows = vec![vec![TextLabel::new(action_label(action)).bold(true).widget_instance()]];
+
+		for shortcut in action_shortcuts(mapping, action) {
+			let binding = UserKeyBinding {
+				action: action.clone(),
+				shortcut: shortcut.clone(),
+				change: KeyBindingChange::Remove,
+			};
+			rows.push(vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				TextLabel::new(shortcut.keys_group().to_string()).widget_instance(),
+				Separator::new(SeparatorStyle::Related).widget_instance(),
+				IconButton::new("Trash", 16)
+					.tooltip_label("Remove Shortcut")
+					.on_update(move |_| PreferencesMessage::SetKeyBinding { binding: Box::new(binding.clone()) }.into())
+					.widget_instance(),
+			]);
+		}
+
+		// Inputs for the new shortcut
+		let keys = shortcut_keys().collect::<Vec<_>>();
+		let key_entries = vec![
+			keys.iter()
+				.map(|&key| {
+					MenuListEntry::new(format!("{key:?}"))
+						.label(key.to_string())
+						.on_commit(move |_| PreferencesDialogMessage::ShortcutKey { key }.into())
+				})
+				.collect::<Vec<_>>(),
+		];
+		let selected_key = self.shortcut_key.and_then(|key| keys.iter().position(|&candidate| candidate == key));
+
+		let mut new_shortcut = vec![Separator::new(SeparatorStyle::Unrelated).widget_instance(), Separator::new(SeparatorStyle::Unrelated).widget_instance()];
+		for modifier in KeyboardShortcut::MODIFIERS {
+			let checkbox_id = CheckboxId::new();
+			new_shortcut.extend([
+				CheckboxInput::new(self.shortcut_modifiers.contains(&modifier))
+					.on_update(move |checkbox_input: &CheckboxInput| {
+						PreferencesDialogMessage::ShortcutModifier {
+							modifier,
+							held: checkbox_input.checked,
+						}
+						.into()
+					})
+					.for_label(checkbox_id)
+					.widget_instance(),
+				TextLabel::new(modifier.to_string()).for_checkbox(checkbox_id).widget_instance(),
+				Separator::new(SeparatorStyle::Related).widget_instance(),
+			]);
+		}
+		new_shortcut.push(
+			DropdownInput::new(key_entries)
+				.selected_index(selected_key.map(|index| index as u32))
+				.tooltip_label("Key")
+				.widget_instance(),
+		);
+		rows.push(new_shortcut);
+
+		let binding = |change| {
+			self.shortcut_key.map(|key| UserKeyBinding {
+				action: action.clone(),
+				shortcut: KeyboardShortcut {
+					key,
+					modifiers: self.shortcut_modifiers.clone(),
+				},
+				change,
+			})
+		};
+		let set_binding_button = |label: &str, description: &str, change| {
+			let binding = binding(change);
+			TextButton::new(label)
+				.tooltip_description(description)
+				.disabled(binding.is_none())
+				.on_update(move |_| match &binding {
+					Some(binding) => PreferencesMessage::SetKeyBinding { binding: Box::new(binding.clone()) }.into(),
+					None => Message::NoOp,
+				})
+				.widget_instance()
+		};
+		let reset_action = Box::new(action.clone());
+		rows.push(vec![
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			set_binding_button("Replace", "Make this the action's only shortcut.", KeyBindingChange::Replace),
+			set_binding_button("Add", "Add this shortcut alongside the action's existing shortcuts.", KeyBindingChange::Add),
+			TextButton::new("Reset")
+				.tooltip_description("Restore the action's built-in shortcuts.")
+				.disabled(preferences.keymap.action_bindings(action).next().is_none())
+				.on_update(move |_| PreferencesMessage::ResetKeyBindings { action: Some(reset_action.clone()) }.into())
+				.widget_instance(),
+		]);
+
+		// Warn about the shortcuts that are also bound to other actions
+		for conflict in preferences.keymap.conflicts(mapping).into_iter().filter(|conflict| conflict.action == *action) {
+			let others = conflict.conflicting_actions.iter().map(action_label).collect::<Vec<_>>().join(", ");
+			rows.push(vec![
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+				IconLabel::new("Warning").widget_instance(),
+				TextLabel::new(format!("{} is also bound to {others}", conflict.shortcut.keys_group())).widget_instance(),
+			]);
+		}
+
+		rows
 	}
 
-	fn send_layout_column_2(&self, responses: &mut VecDeque<Message>, layout_target: LayoutTarget) {
+	fn send_layout_column_2(&self, responses: &mut VecDeque<Message>, layout_target: LayoutTarget, preferences: &PreferencesMessageHandler) {
 		responses.add(LayoutMessage::SendLayout {
-			layout: self.layout_column_2(),
+			layout: self.layout_column_2(preferences),
 			layout_target,
 		});
 	}
@@ -331,7 +703,7 @@ impl PreferencesDialogMessageHandler {
 
 	pub fn send_dialog_to_frontend(&self, responses: &mut VecDeque<Message>, preferences: &PreferencesMessageHandler) {
 		self.send_layout(responses, LayoutTarget::DialogColumn1, preferences);
-		self.send_layout_column_2(responses, LayoutTarget::DialogColumn2);
+		self.send_layout_column_2(responses, LayoutTarget::DialogColumn2, preferences);
 		self.send_layout_buttons(responses, LayoutTarget::DialogButtons);
 		responses.add(FrontendMessage::DisplayDialog {
 			icon: Self::ICON.into(),
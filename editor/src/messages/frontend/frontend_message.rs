@@ -3,7 +3,7 @@ use crate::messages::app_window::app_window_message_handler::AppWindowPlatform;
 use crate::messages::input_mapper::utility_types::misc::ActionShortcut;
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::node_graph::utility_types::{
-	BoxSelection, ContextMenuInformation, FrontendClickTargets, FrontendGraphInput, FrontendGraphOutput, FrontendNode, FrontendNodeType, NodeGraphErrorDiagnostic, Transform,
+	BoxSelection, ContextMenuInformation, FrontendClickTargets, FrontendGraphInput, FrontendGraphOutput, FrontendNode, FrontendNodeProfile, FrontendNodeType, NodeGraphErrorDiagnostic, Transform,
 };
 use crate::messages::portfolio::document::utility_types::nodes::{JsRawBuffer, LayerPanelEntry, RawBuffer};
 use crate::messages::portfolio::document::utility_types::wires::{WirePath, WirePathUpdate};
@@ -106,6 +106,8 @@ pub enum FrontendMessage {
 		url: String,
 	},
 	TriggerImport,
+	TriggerImportKeymap,
+	TriggerImportNodeLibrary,
 	TriggerPersistenceRemoveDocument {
 		#[serde(rename = "documentId")]
 		document_id: DocumentId,
@@ -185,6 +187,9 @@ pub enum FrontendMessage {
 	UpdateDataPanelState {
 		open: bool,
 	},
+	UpdateHistoryPanelState {
+		open: bool,
+	},
 	UpdatePropertiesPanelState {
 		open: bool,
 	},
@@ -194,6 +199,9 @@ pub enum FrontendMessage {
 	UpdateDataPanelLayout {
 		diff: Vec<WidgetDiff>,
 	},
+	UpdateHistoryPanelLayout {
+		diff: Vec<WidgetDiff>,
+	},
 	UpdateImportReorderIndex {
 		#[serde(rename = "importIndex")]
 		index: Option<usize>,
@@ -304,6 +312,9 @@ pub enum FrontendMessage {
 		id: NodeId,
 		value: String,
 	},
+	UpdateNodeProfiles {
+		profiles: Vec<FrontendNodeProfile>,
+	},
 	UpdateOpenDocumentsList {
 		#[serde(rename = "openDocuments")]
 		open_documents: Vec<OpenDocument>,
@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::misc::SliceId;
 use crate::messages::prelude::*;
+pub use graphene_std::application_io::export_preset::{ExportPreset, FileType};
 
 #[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct OpenDocument {
@@ -37,28 +39,20 @@ pub enum MouseCursorIcon {
 	Rotate,
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
-pub enum FileType {
-	#[default]
-	Png,
-	Jpg,
-	Svg,
-}
-
-impl FileType {
-	pub fn to_mime(self) -> &'static str {
-		match self {
-			FileType::Png => "image/png",
-			FileType::Jpg => "image/jpeg",
-			FileType::Svg => "image/svg+xml",
-		}
-	}
-}
-
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum ExportBounds {
 	#[default]
 	AllArtwork,
 	Selection,
 	Artboard(LayerNodeIdentifier),
+	/// The bounds of a layer other than an artboard. Everything visible within them is exported, not only the layer itself.
+	Layer(LayerNodeIdentifier),
+	Slice(SliceId),
+}
+
+/// The document item that a list of export presets is attached to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ExportTarget {
+	Layer(LayerNodeIdentifier),
+	Slice(SliceId),
 }
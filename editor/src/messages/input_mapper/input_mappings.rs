@@ -11,6 +11,8 @@ use crate::messages::portfolio::document::utility_types::misc::GroupFolderType;
 use crate::messages::portfolio::utility_types::KeyboardPlatformLayout;
 use crate::messages::prelude::*;
 use crate::messages::tool::tool_messages::brush_tool::BrushToolMessageOptionsUpdate;
+use crate::messages::tool::tool_messages::clone_tool::CloneToolMessageOptionsUpdate;
+use crate::messages::tool::tool_messages::heal_tool::HealToolMessageOptionsUpdate;
 use crate::messages::tool::tool_messages::select_tool::SelectToolPointerKeys;
 use glam::DVec2;
 
@@ -47,6 +49,13 @@ pub fn input_mappings() -> Mapping {
 		entry!(KeyDown(MouseMiddle); action_dispatch=NavigationMessage::EndCanvasPTZWithClick { commit_key: MouseMiddle }),
 		entry!(KeyDown(MouseRight); action_dispatch=NavigationMessage::EndCanvasPTZWithClick { commit_key: MouseRight }),
 		//
+		// DocumentMessage
+		entry!(KeyDown(MouseLeft); action_dispatch=DocumentMessage::DragGuide),
+		entry!(PointerMove; refresh_keys=[Control, Shift], action_dispatch=DocumentMessage::GuideDragMove { rotate: Control, snap_angle: Shift }),
+		entry!(KeyUp(MouseLeft); action_dispatch=DocumentMessage::GuideDragStop),
+		entry!(KeyDown(MouseRight); action_dispatch=DocumentMessage::GuideDragAbort),
+		entry!(KeyDown(Escape); action_dispatch=DocumentMessage::GuideDragAbort),
+		//
 		// ===============
 		// NORMAL PRIORITY
 		// ===============
@@ -143,6 +152,15 @@ pub fn input_mappings() -> Mapping {
 		entry!(KeyDown(MouseRight); action_dispatch=ArtboardToolMessage::Abort),
 		entry!(KeyDown(Escape); action_dispatch=ArtboardToolMessage::Abort),
 		//
+		// SliceToolMessage
+		entry!(KeyDown(MouseLeft); action_dispatch=SliceToolMessage::PointerDown),
+		entry!(PointerMove; refresh_keys=[Shift, Alt], action_dispatch=SliceToolMessage::PointerMove { constrain_axis_or_aspect: Shift, center: Alt }),
+		entry!(KeyUp(MouseLeft); action_dispatch=SliceToolMessage::PointerUp),
+		entry!(KeyDown(Delete); action_dispatch=SliceToolMessage::DeleteSelected),
+		entry!(KeyDown(Backspace); action_dispatch=SliceToolMessage::DeleteSelected),
+		entry!(KeyDown(MouseRight); action_dispatch=SliceToolMessage::Abort),
+		entry!(KeyDown(Escape); action_dispatch=SliceToolMessage::Abort),
+		//
 		// NavigateToolMessage
 		entry!(KeyDown(MouseLeft); action_dispatch=NavigateToolMessage::ZoomCanvasBegin),
 		entry!(KeyDown(MouseLeft); modifiers=[Alt], action_dispatch=NavigateToolMessage::TiltCanvasBegin),
@@ -308,8 +326,42 @@ pub fn input_mappings() -> Mapping {
 		entry!(KeyDown(MouseRight); action_dispatch=BrushToolMessage::Abort),
 		entry!(KeyDown(Escape); action_dispatch=BrushToolMessage::Abort),
 		//
+		// CloneToolMessage
+		entry!(PointerMove; action_dispatch=CloneToolMessage::PointerMove),
+		entry!(KeyDown(MouseLeft); modifiers=[Alt], action_dispatch=CloneToolMessage::SetSource),
+		entry!(KeyDown(MouseLeft); action_dispatch=CloneToolMessage::DragStart),
+		entry!(KeyUp(MouseLeft); action_dispatch=CloneToolMessage::DragStop),
+		entry!(KeyDown(BracketLeft); action_dispatch=CloneToolMessage::UpdateOptions { options: CloneToolMessageOptionsUpdate::ChangeDiameter(-BRUSH_SIZE_CHANGE_KEYBOARD) }),
+		entry!(KeyDown(BracketRight); action_dispatch=CloneToolMessage::UpdateOptions { options: CloneToolMessageOptionsUpdate::ChangeDiameter(BRUSH_SIZE_CHANGE_KEYBOARD) }),
+		entry!(KeyDown(MouseRight); action_dispatch=CloneToolMessage::Abort),
+		entry!(KeyDown(Escape); action_dispatch=CloneToolMessage::Abort),
+		//
+		// HealToolMessage
+		entry!(PointerMove; action_dispatch=HealToolMessage::PointerMove),
+		entry!(KeyDown(MouseLeft); action_dispatch=HealToolMessage::DragStart),
+		entry!(KeyUp(MouseLeft); action_dispatch=HealToolMessage::DragStop),
+		entry!(KeyDown(BracketLeft); action_dispatch=HealToolMessage::UpdateOptions { options: HealToolMessageOptionsUpdate::ChangeDiameter(-BRUSH_SIZE_CHANGE_KEYBOARD) }),
+		entry!(KeyDown(BracketRight); action_dispatch=HealToolMessage::UpdateOptions { options: HealToolMessageOptionsUpdate::ChangeDiameter(BRUSH_SIZE_CHANGE_KEYBOARD) }),
+		entry!(KeyDown(MouseRight); action_dispatch=HealToolMessage::Abort),
+		entry!(KeyDown(Escape); action_dispatch=HealToolMessage::Abort),
+		//
+		// PatchToolMessage
+		entry!(PointerMove; action_dispatch=PatchToolMessage::PointerMove),
+		entry!(KeyDown(MouseLeft); action_dispatch=PatchToolMessage::DragStart),
+		entry!(KeyUp(MouseLeft); action_dispatch=PatchToolMessage::DragStop),
+		entry!(KeyDown(MouseRight); action_dispatch=PatchToolMessage::Abort),
+		entry!(KeyDown(Escape); action_dispatch=PatchToolMessage::Abort),
+		//
+		// PixelSelectToolMessage
+		entry!(PointerMove; action_dispatch=PixelSelectToolMessage::PointerMove),
+		entry!(KeyDown(MouseLeft); action_dispatch=PixelSelectToolMessage::DragStart { add: Shift, subtract: Alt }),
+		entry!(KeyUp(MouseLeft); action_dispatch=PixelSelectToolMessage::DragStop),
+		entry!(KeyDown(MouseRight); action_dispatch=PixelSelectToolMessage::Abort),
+		entry!(KeyDown(Escape); action_dispatch=PixelSelectToolMessage::Abort),
+		//
 		// ToolMessage
 		entry!(KeyDown(KeyV); action_dispatch=ToolMessage::ActivateToolSelect),
+		entry!(KeyDown(KeyK); action_dispatch=ToolMessage::ActivateToolSlice),
 		entry!(KeyDown(KeyZ); action_dispatch=ToolMessage::ActivateToolNavigate),
 		entry!(KeyDown(KeyI); action_dispatch=ToolMessage::ActivateToolEyedropper),
 		entry!(KeyDown(KeyT); action_dispatch=ToolMessage::ActivateToolText),
@@ -323,6 +375,9 @@ pub fn input_mappings() -> Mapping {
 		entry!(KeyDown(KeyE); action_dispatch=ToolMessage::ActivateToolShapeEllipse),
 		entry!(KeyDown(KeyY); action_dispatch=ToolMessage::ActivateToolShape),
 		entry!(KeyDown(KeyB); action_dispatch=ToolMessage::ActivateToolBrush),
+		entry!(KeyDown(KeyJ); action_dispatch=ToolMessage::ActivateToolHeal),
+		entry!(KeyDown(KeyC); action_dispatch=ToolMessage::ActivateToolClone),
+		entry!(KeyDown(KeyW); action_dispatch=ToolMessage::ActivateToolPixelSelect),
 		entry!(KeyDown(KeyD); action_dispatch=ToolMessage::ResetColors),
 		entry!(KeyDown(KeyX); modifiers=[Shift], action_dispatch=ToolMessage::SwapColors),
 		entry!(KeyDown(KeyC); modifiers=[Alt], action_dispatch=ToolMessage::SelectRandomWorkingColor { primary: true }),
@@ -339,12 +394,16 @@ pub fn input_mappings() -> Mapping {
 		entry!(KeyDown(KeyH); modifiers=[Accel], action_dispatch=DocumentMessage::ToggleSelectedVisibility),
 		entry!(KeyDown(KeyL); modifiers=[Accel], action_dispatch=DocumentMessage::ToggleSelectedLocked),
 		entry!(KeyDown(KeyG); modifiers=[Alt], action_dispatch=DocumentMessage::ToggleGridVisibility),
+		entry!(KeyDown(Semicolon); modifiers=[Accel, Alt], action_dispatch=DocumentMessage::ToggleGuidesLocked),
+		entry!(KeyDown(Semicolon); modifiers=[Accel], action_dispatch=DocumentMessage::ToggleGuidesVisibility),
 		entry!(KeyDown(KeyZ); modifiers=[Accel, Shift], canonical, action_dispatch=DocumentMessage::Redo),
 		entry!(KeyDown(KeyY); modifiers=[Accel], action_dispatch=DocumentMessage::Redo),
 		entry!(KeyDown(KeyZ); modifiers=[Accel], action_dispatch=DocumentMessage::Undo),
 		entry!(KeyDown(KeyA); modifiers=[Accel], action_dispatch=DocumentMessage::SelectAllLayers),
 		entry!(KeyDown(KeyA); modifiers=[Accel, Shift], canonical, action_dispatch=DocumentMessage::DeselectAllLayers),
 		entry!(KeyDown(KeyA); modifiers=[Alt], action_dispatch=DocumentMessage::DeselectAllLayers),
+		entry!(KeyDown(KeyD); modifiers=[Accel, Shift], action_dispatch=DocumentMessage::DeselectPixels),
+		entry!(KeyDown(KeyI); modifiers=[Accel, Shift], action_dispatch=DocumentMessage::InvertPixelSelection),
 		entry!(KeyDown(KeyS); modifiers=[Accel], action_dispatch=DocumentMessage::SaveDocument),
 		entry!(KeyDown(KeyS); modifiers=[Accel, Shift], action_dispatch=DocumentMessage::SaveDocumentAs),
 		entry!(KeyDown(KeyD); modifiers=[Accel], canonical, action_dispatch=DocumentMessage::DuplicateSelectedLayers),
@@ -437,9 +496,11 @@ pub fn input_mappings() -> Mapping {
 		entry!(KeyDown(KeyC); modifiers=[Accel], action_dispatch=PortfolioMessage::Copy { clipboard: Clipboard::Device }),
 		entry!(KeyDown(KeyR); modifiers=[Alt], action_dispatch=PortfolioMessage::ToggleRulers),
 		entry!(KeyDown(KeyD); modifiers=[Alt], action_dispatch=PortfolioMessage::ToggleDataPanelOpen),
+		entry!(KeyDown(KeyH); modifiers=[Alt], action_dispatch=PortfolioMessage::ToggleHistoryPanelOpen),
 		//
 		// DialogMessage
 		entry!(KeyDown(KeyE); modifiers=[Accel], action_dispatch=DialogMessage::RequestExportDialog),
+		entry!(KeyDown(KeyE); modifiers=[Accel, Shift], action_dispatch=DialogMessage::RequestBatchExportDialog),
 		entry!(KeyDown(KeyN); modifiers=[Accel], action_dispatch=DialogMessage::RequestNewDocumentDialog),
 		entry!(KeyDown(Comma); modifiers=[Accel], action_dispatch=DialogMessage::RequestPreferencesDialog),
 		//
@@ -1,11 +1,13 @@
 use crate::messages::input_mapper::input_mapper_message_handler::InputMapperMessageContext;
 use crate::messages::input_mapper::utility_types::input_keyboard::KeysGroup;
+use crate::messages::input_mapper::utility_types::misc::Mapping;
 use crate::messages::prelude::*;
 
 #[derive(ExtractField)]
 pub struct KeyMappingMessageContext<'a> {
 	pub input: &'a InputPreprocessorMessageHandler,
 	pub actions: ActionList,
+	pub preferences: &'a PreferencesMessageHandler,
 }
 
 #[derive(Debug, Default, ExtractField)]
@@ -16,14 +18,18 @@ pub struct KeyMappingMessageHandler {
 #[message_handler_data]
 impl MessageHandler<KeyMappingMessage, KeyMappingMessageContext<'_>> for KeyMappingMessageHandler {
 	fn process_message(&mut self, message: KeyMappingMessage, responses: &mut VecDeque<Message>, context: KeyMappingMessageContext) {
-		let KeyMappingMessageContext { input, actions } = context;
+		let KeyMappingMessageContext { input, actions, preferences } = context;
 
 		match message {
 			// Sub-messages
 			KeyMappingMessage::Lookup(input_message) => self.mapping_handler.process_message(input_message, responses, InputMapperMessageContext { input, actions }),
 
 			// Messages
-			KeyMappingMessage::ModifyMapping { mapping } => self.mapping_handler.set_mapping(mapping.into()),
+			KeyMappingMessage::ModifyMapping { mapping } => {
+				let mut mapping: Mapping = mapping.into();
+				preferences.keymap.apply(&mut mapping);
+				self.mapping_handler.set_mapping(mapping);
+			}
 		}
 	}
 	advertise_actions!();
@@ -39,6 +39,27 @@ impl Mapping {
 		list.push(new_entry);
 	}
 
+	/// Adds an entry ahead of the existing entries with as many or fewer modifiers, so it takes precedence over those bound to the same input.
+	pub fn add_with_priority(&mut self, new_entry: MappingEntry) {
+		let list = self.associated_entries_mut(&new_entry.input);
+		let modifier_count = new_entry.modifiers.count_ones();
+		let index = list.0.iter().position(|entry| entry.modifiers.count_ones() <= modifier_count).unwrap_or(list.0.len());
+		list.0.insert(index, new_entry);
+	}
+
+	/// Removes every entry which dispatches the given action when a key is pressed.
+	pub fn remove_key_bindings(&mut self, action: &Message) {
+		for list in self.key_down.iter_mut().chain(self.key_down_no_repeat.iter_mut()) {
+			list.0.retain(|entry| entry.action != *action);
+		}
+	}
+
+	/// Removes the entries matching the target entry's action, input, and modifiers, regardless of whether they're canonical.
+	pub fn remove_key_binding(&mut self, target_entry: &MappingEntry) {
+		let list = self.associated_entries_mut(&target_entry.input);
+		list.0.retain(|entry| entry.action != target_entry.action || entry.modifiers != target_entry.modifiers);
+	}
+
 	fn associated_entries(&self, message: &InputMapperMessage) -> &KeyMappingEntries {
 		match message {
 			InputMapperMessage::KeyDown(key) => &self.key_down[*key as usize],
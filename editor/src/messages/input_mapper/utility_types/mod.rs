@@ -2,3 +2,4 @@ pub mod input_keyboard;
 pub mod input_mouse;
 pub mod macros;
 pub mod misc;
+pub mod user_keymap;
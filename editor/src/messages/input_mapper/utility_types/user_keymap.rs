@@ -0,0 +1,340 @@
+use super::input_keyboard::{Key, KeyStates, KeysGroup, NUMBER_OF_KEYS};
+use super::misc::{Mapping, MappingEntry};
+use crate::messages::prelude::*;
+use crate::utility_types::split_camel_case;
+
+/// The version of the keymap file format, increased whenever it changes in a way that older versions of the editor can't read.
+const KEYMAP_FILE_VERSION: u32 = 1;
+
+/// The file name suggested when exporting a keymap file.
+pub const KEYMAP_FILE_NAME: &str = "graphite-keymap.json";
+
+/// A key pressed while holding down a set of modifier keys.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct KeyboardShortcut {
+	pub key: Key,
+	pub modifiers: Vec<Key>,
+}
+
+impl KeyboardShortcut {
+	/// The modifier keys which can be held down as part of a user-defined shortcut. `Accel` is Ctrl on Windows and Linux, and Cmd on Mac.
+	pub const MODIFIERS: [Key; 3] = [Key::Accel, Key::Shift, Key::Alt];
+
+	/// Every modifier key which may be part of a built-in shortcut, in the order they're shown in the UI.
+	const MODIFIER_ORDER: [Key; 6] = [Key::Accel, Key::Control, Key::Command, Key::Meta, Key::Shift, Key::Alt];
+
+	/// The keys in the order they're shown in the UI, with the modifiers first.
+	pub fn keys_group(&self) -> KeysGroup {
+		let modifiers = Self::MODIFIER_ORDER.into_iter().filter(|modifier| self.modifiers.contains(modifier));
+		KeysGroup(modifiers.chain(std::iter::once(self.key)).collect())
+	}
+
+	fn modifier_states(&self) -> KeyStates {
+		let mut states = KeyStates::new();
+		for &modifier in &self.modifiers {
+			states.set(modifier as usize);
+		}
+		states
+	}
+
+	fn mapping_entry(&self, action: Message, canonical: bool) -> MappingEntry {
+		MappingEntry {
+			action,
+			input: InputMapperMessage::KeyDown(self.key),
+			modifiers: self.modifier_states(),
+			canonical,
+		}
+	}
+}
+
+/// How a user-defined key binding changes the built-in key bindings of its action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum KeyBindingChange {
+	/// The shortcut is bound to the action in addition to its built-in shortcuts.
+	Add,
+	/// The built-in binding of the shortcut to the action is removed.
+	Remove,
+	/// The shortcut becomes the action's only key binding, replacing all of its built-in key bindings.
+	Replace,
+}
+
+/// A user's change to the key bindings of a single action.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UserKeyBinding {
+	/// The message dispatched by the key binding. Its discriminant is the action, which must be available for the shortcut to take effect.
+	pub action: Message,
+	pub shortcut: KeyboardShortcut,
+	pub change: KeyBindingChange,
+}
+
+/// A shortcut which the user bound to an action, but which is also bound to other actions. Only one of them is dispatched when the actions are available at the same time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeymapConflict {
+	pub shortcut: KeyboardShortcut,
+	pub action: Message,
+	pub conflicting_actions: Vec<Message>,
+}
+
+/// The user's changes to the key bindings, which are layered on top of the [`Mapping`] built into the editor.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct UserKeymap {
+	/// The changes are applied in order, so later ones take precedence over earlier ones.
+	#[serde(deserialize_with = "deserialize_bindings")]
+	pub bindings: Vec<UserKeyBinding>,
+}
+
+/// The contents of an exported keymap file.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct KeymapFile {
+	version: u32,
+	#[serde(deserialize_with = "deserialize_bindings")]
+	bindings: Vec<UserKeyBinding>,
+}
+
+/// Skips the bindings which can't be read, such as those for actions that were removed from the editor, rather than discarding the whole keymap.
+fn deserialize_bindings<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<UserKeyBinding>, D::Error> {
+	let values = <Vec<serde_json::Value> as serde::Deserialize>::deserialize(deserializer)?;
+	Ok(values.into_iter().filter_map(|value| serde_json::from_value(value).ok()).collect())
+}
+
+impl UserKeymap {
+	/// Applies the user's changes on top of the built-in key bindings. Added shortcuts take precedence over built-in ones for the same keys.
+	pub fn apply(&self, mapping: &mut Mapping) {
+		for binding in &self.bindings {
+			match binding.change {
+				KeyBindingChange::Add => mapping.add_with_priority(binding.shortcut.mapping_entry(binding.action.clone(), false)),
+				KeyBindingChange::Remove => mapping.remove_key_binding(&binding.shortcut.mapping_entry(binding.action.clone(), false)),
+				KeyBindingChange::Replace => {
+					mapping.remove_key_bindings(&binding.action);
+					mapping.add_with_priority(binding.shortcut.mapping_entry(binding.action.clone(), true));
+				}
+			}
+		}
+	}
+
+	/// Records a change to an action's key bindings, dropping earlier changes that it makes redundant.
+	pub fn set_binding(&mut self, binding: UserKeyBinding) {
+		let same_shortcut = |existing: &UserKeyBinding| existing.action == binding.action && existing.shortcut == binding.shortcut;
+
+		match binding.change {
+			// Replacing makes every earlier change to the action irrelevant
+			KeyBindingChange::Replace => self.bindings.retain(|existing| existing.action != binding.action),
+			KeyBindingChange::Add | KeyBindingChange::Remove => {
+				// Adding a shortcut that was removed (or removing one that was added) just cancels out the earlier change
+				let opposite = if binding.change == KeyBindingChange::Add {
+					KeyBindingChange::Remove
+				} else {
+					KeyBindingChange::Add
+				};
+				if let Some(index) = self.bindings.iter().position(|existing| same_shortcut(existing) && existing.change == opposite) {
+					self.bindings.remove(index);
+					return;
+				}
+				if self.bindings.iter().any(|existing| same_shortcut(existing) && existing.change == binding.change) {
+					return;
+				}
+			}
+		}
+
+		self.bindings.push(binding);
+	}
+
+	/// Discards the user's changes to an action's key bindings, restoring its built-in shortcuts.
+	pub fn reset_action(&mut self, action: &Message) {
+		self.bindings.retain(|binding| binding.action != *action);
+	}
+
+	/// The user's changes to an action's key bindings.
+	pub fn action_bindings<'a>(&'a self, action: &'a Message) -> impl Iterator<Item = &'a UserKeyBinding> {
+		self.bindings.iter().filter(move |binding| binding.action == *action)
+	}
+
+	/// Finds the shortcuts added by the user which the given mapping, with this keymap already applied, also binds to other actions.
+	pub fn conflicts(&self, mapping: &Mapping) -> Vec<KeymapConflict> {
+		self.bindings
+			.iter()
+			.filter(|binding| binding.change != KeyBindingChange::Remove)
+			.filter_map(|binding| {
+				let entry = binding.shortcut.mapping_entry(binding.action.clone(), false);
+				let mut conflicting_actions: Vec<Message> = Vec::new();
+				for other in &mapping.key_down[binding.shortcut.key as usize].0 {
+					if other.modifiers == entry.modifiers && other.action != binding.action && !conflicting_actions.contains(&other.action) {
+						conflicting_actions.push(other.action.clone());
+					}
+				}
+
+				(!conflicting_actions.is_empty()).then(|| KeymapConflict {
+					shortcut: binding.shortcut.clone(),
+					action: binding.action.clone(),
+					conflicting_actions,
+				})
+			})
+			.collect()
+	}
+
+	/// Serializes the keymap into the contents of a keymap file which can be shared between installations of the editor.
+	pub fn to_file_contents(&self) -> String {
+		let file = KeymapFile {
+			version: KEYMAP_FILE_VERSION,
+			bindings: self.bindings.clone(),
+		};
+		serde_json::to_string_pretty(&file).unwrap_or_default()
+	}
+
+	/// Reads a keymap from the contents of a keymap file.
+	pub fn from_file_contents(contents: &str) -> Result<Self, String> {
+		let file: KeymapFile = serde_json::from_str(contents).map_err(|error| format!("The file is not a valid keymap: {error}"))?;
+		if file.version > KEYMAP_FILE_VERSION {
+			return Err(format!("The keymap was made with a newer version of Graphite (keymap format version {}).", file.version));
+		}
+
+		Ok(Self { bindings: file.bindings })
+	}
+}
+
+/// The shortcuts which the given mapping binds to an action.
+pub fn action_shortcuts(mapping: &Mapping, action: &Message) -> Vec<KeyboardShortcut> {
+	let mut shortcuts = Vec::new();
+	for key in shortcut_keys() {
+		for entry in mapping.key_down[key as usize].0.iter().filter(|entry| entry.action == *action) {
+			let modifiers = KeyboardShortcut::MODIFIER_ORDER.into_iter().filter(|&modifier| entry.modifiers.key(modifier)).collect();
+			let shortcut = KeyboardShortcut { key, modifiers };
+			if !shortcuts.contains(&shortcut) {
+				shortcuts.push(shortcut);
+			}
+		}
+	}
+	shortcuts
+}
+
+/// The keys which can be pressed as part of a user-defined shortcut, excluding modifiers, mouse buttons, and keys that only exist for labeling shortcuts.
+pub fn shortcut_keys() -> impl Iterator<Item = Key> {
+	(0..NUMBER_OF_KEYS as u8).filter_map(|index| Key::try_from(index).ok()).filter(|key| {
+		!matches!(
+			key,
+			Key::Alt
+				| Key::Meta | Key::Shift
+				| Key::Control
+				| Key::Command
+				| Key::Accel | Key::Fn
+				| Key::FnLock
+				| Key::Unidentified
+				| Key::MouseLeft
+				| Key::MouseRight
+				| Key::MouseMiddle
+				| Key::MouseBack
+				| Key::MouseForward
+				| Key::FakeKeyPlus
+				| Key::FakeKeyNumbers
+		)
+	})
+}
+
+/// The actions which the given mapping binds to shortcut keys, sorted by their labels.
+pub fn customizable_actions(mapping: &Mapping) -> Vec<Message> {
+	let keys = shortcut_keys().collect::<Vec<_>>();
+
+	let mut actions: Vec<Message> = Vec::new();
+	for key in keys {
+		for entry in &mapping.key_down[key as usize].0 {
+			if !actions.contains(&entry.action) {
+				actions.push(entry.action.clone());
+			}
+		}
+	}
+
+	actions.sort_by_cached_key(action_label);
+	actions
+}
+
+/// A readable name for an action, such as "Delete Selected Layers (Document)".
+pub fn action_label(action: &Message) -> String {
+	let name = action.to_discriminant().local_name();
+	let mut segments = name.rsplit('.');
+	let action_name = split_camel_case(segments.next().unwrap_or_default());
+
+	match segments.next() {
+		Some(parent) => format!("{action_name} ({})", split_camel_case(parent)),
+		None => action_name,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::messages::input_mapper::key_mapping::MappingVariant;
+
+	fn shortcut(key: Key, modifiers: &[Key]) -> KeyboardShortcut {
+		KeyboardShortcut { key, modifiers: modifiers.to_vec() }
+	}
+
+	#[test]
+	fn replace_overrides_built_in_shortcut() {
+		let action: Message = DocumentMessage::DeleteSelectedLayers.into();
+		let mut keymap = UserKeymap::default();
+		keymap.set_binding(UserKeyBinding {
+			action: action.clone(),
+			shortcut: shortcut(Key::KeyK, &[Key::Accel, Key::Shift]),
+			change: KeyBindingChange::Replace,
+		});
+
+		let mut mapping = Mapping::from(MappingVariant::Default);
+		keymap.apply(&mut mapping);
+
+		assert_eq!(action_shortcuts(&mapping, &action), vec![shortcut(Key::KeyK, &[Key::Accel, Key::Shift])]);
+		assert!(mapping.key_down[Key::Delete as usize].0.iter().all(|entry| entry.action != action));
+	}
+
+	#[test]
+	fn adding_a_removed_shortcut_cancels_out() {
+		let action: Message = DocumentMessage::DeleteSelectedLayers.into();
+		let binding = |change| UserKeyBinding {
+			action: action.clone(),
+			shortcut: shortcut(Key::Delete, &[]),
+			change,
+		};
+
+		let mut keymap = UserKeymap::default();
+		keymap.set_binding(binding(KeyBindingChange::Remove));
+		assert_eq!(keymap.bindings.len(), 1);
+		keymap.set_binding(binding(KeyBindingChange::Add));
+		assert!(keymap.bindings.is_empty());
+	}
+
+	#[test]
+	fn conflicting_shortcut_is_reported() {
+		let mut keymap = UserKeymap::default();
+		keymap.set_binding(UserKeyBinding {
+			action: DocumentMessage::DeleteSelectedLayers.into(),
+			shortcut: shortcut(Key::KeyG, &[Key::Accel]),
+			change: KeyBindingChange::Add,
+		});
+
+		let mut mapping = Mapping::from(MappingVariant::Default);
+		keymap.apply(&mut mapping);
+
+		let conflicts = keymap.conflicts(&mapping);
+		assert_eq!(conflicts.len(), 1);
+		assert!(
+			conflicts[0]
+				.conflicting_actions
+				.iter()
+				.any(|action| matches!(action, Message::Portfolio(PortfolioMessage::Document(DocumentMessage::GroupSelectedLayers { .. }))))
+		);
+	}
+
+	#[test]
+	fn keymap_file_round_trip() {
+		let mut keymap = UserKeymap::default();
+		keymap.set_binding(UserKeyBinding {
+			action: PortfolioMessage::ToggleRulers.into(),
+			shortcut: shortcut(Key::KeyR, &[Key::Accel, Key::Alt]),
+			change: KeyBindingChange::Replace,
+		});
+
+		let contents = keymap.to_file_contents();
+		assert_eq!(UserKeymap::from_file_contents(&contents), Ok(keymap));
+		assert!(UserKeymap::from_file_contents("not a keymap").is_err());
+	}
+}
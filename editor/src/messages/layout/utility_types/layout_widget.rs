@@ -32,6 +32,8 @@ pub enum LayoutTarget {
 	DialogColumn2,
 	/// Contains the widgets located directly above the canvas to the right, for example the zoom in and out buttons.
 	DocumentBar,
+	/// The list of undo history steps and named snapshots in the History panel.
+	HistoryPanel,
 	/// Controls for adding, grouping, and deleting layers at the bottom of the Layers panel.
 	LayersPanelBottomBar,
 	/// Blending options at the top of the Layers panel.
@@ -11,14 +11,19 @@ pub struct MenuBarMessageHandler {
 	pub canvas_tilted: bool,
 	pub canvas_flipped: bool,
 	pub rulers_visible: bool,
+	pub guides_visible: bool,
+	pub guides_locked: bool,
+	pub has_guides: bool,
 	pub node_graph_open: bool,
 	pub has_selected_nodes: bool,
 	pub has_selected_layers: bool,
 	pub has_selection_history: (bool, bool),
+	pub has_pixel_selection: bool,
 	pub message_logging_verbosity: MessageLoggingVerbosity,
 	pub reset_node_definitions_on_open: bool,
 	pub make_path_editable_is_allowed: bool,
 	pub data_panel_open: bool,
+	pub history_panel_open: bool,
 	pub layers_panel_open: bool,
 	pub properties_panel_open: bool,
 }
@@ -45,6 +50,7 @@ impl LayoutHolder for MenuBarMessageHandler {
 		let has_selected_nodes = self.has_selected_nodes;
 		let has_selected_layers = self.has_selected_layers;
 		let has_selection_history = self.has_selection_history;
+		let has_pixel_selection = self.has_pixel_selection;
 		let message_logging_verbosity_off = self.message_logging_verbosity == MessageLoggingVerbosity::Off;
 		let message_logging_verbosity_names = self.message_logging_verbosity == MessageLoggingVerbosity::Names;
 		let message_logging_verbosity_contents = self.message_logging_verbosity == MessageLoggingVerbosity::Contents;
@@ -162,12 +168,27 @@ impl LayoutHolder for MenuBarMessageHandler {
 							.icon("FileImport")
 							.tooltip_shortcut(action_shortcut!(PortfolioMessageDiscriminant::Import))
 							.on_commit(|_| PortfolioMessage::Import.into()),
+						MenuListEntry::new("Import Node Library…")
+							.label("Import Node Library…")
+							.icon("FileImport")
+							.on_commit(|_| PreferencesMessage::ImportNodeLibrary.into()),
 						MenuListEntry::new("Export…")
 							.label("Export…")
 							.icon("FileExport")
 							.tooltip_shortcut(action_shortcut!(DialogMessageDiscriminant::RequestExportDialog))
 							.on_commit(|_| DialogMessage::RequestExportDialog.into())
 							.disabled(no_active_document),
+						MenuListEntry::new("Export All…")
+							.label("Export All…")
+							.icon("FileExport")
+							.tooltip_shortcut(action_shortcut!(DialogMessageDiscriminant::RequestBatchExportDialog))
+							.on_commit(|_| DialogMessage::RequestBatchExportDialog.into())
+							.disabled(no_active_document),
+						MenuListEntry::new("Export Node Library…")
+							.label("Export Node Library…")
+							.icon("FileExport")
+							.on_commit(|_| DocumentMessage::ExportNodeLibrary.into())
+							.disabled(no_active_document),
 					],
 					#[cfg(not(target_os = "macos"))]
 					vec![preferences],
@@ -264,6 +285,11 @@ impl LayoutHolder for MenuBarMessageHandler {
 							.tooltip_shortcut(action_shortcut!(DocumentMessageDiscriminant::UngroupSelectedLayers))
 							.on_commit(|_| DocumentMessage::UngroupSelectedLayers.into())
 							.disabled(no_active_document || !has_selected_layers),
+						MenuListEntry::new("Create Symbol")
+							.label("Create Symbol")
+							.icon("Link")
+							.on_commit(|_| DocumentMessage::CreateSymbolFromSelectedLayers.into())
+							.disabled(no_active_document || !has_selected_layers),
 					],
 					vec![
 						MenuListEntry::new("Hide/Show")
@@ -526,6 +552,11 @@ impl LayoutHolder for MenuBarMessageHandler {
 							.tooltip_shortcut(action_shortcut!(DocumentMessageDiscriminant::SelectParentLayer))
 							.on_commit(|_| DocumentMessage::SelectParentLayer.into())
 							.disabled(no_active_document || !has_selected_nodes),
+						MenuListEntry::new("Select Similar")
+							.label("Select Similar")
+							.tooltip_shortcut(action_shortcut!(DocumentMessageDiscriminant::SelectSimilarLayers))
+							.on_commit(|_| DocumentMessage::SelectSimilarLayers.into())
+							.disabled(no_active_document || !has_selected_nodes),
 					],
 					vec![
 						MenuListEntry::new("Previous Selection")
@@ -541,6 +572,40 @@ impl LayoutHolder for MenuBarMessageHandler {
 							.on_commit(|_| DocumentMessage::SelectionStepForward.into())
 							.disabled(!has_selection_history.1),
 					],
+					vec![
+						MenuListEntry::new("Deselect Pixels")
+							.label("Deselect Pixels")
+							.icon("DeselectAll")
+							.tooltip_shortcut(action_shortcut!(DocumentMessageDiscriminant::DeselectPixels))
+							.on_commit(|_| DocumentMessage::DeselectPixels.into())
+							.disabled(!has_pixel_selection),
+						MenuListEntry::new("Invert Pixel Selection")
+							.label("Invert Pixel Selection")
+							.icon("Reverse")
+							.tooltip_shortcut(action_shortcut!(DocumentMessageDiscriminant::InvertPixelSelection))
+							.on_commit(|_| DocumentMessage::InvertPixelSelection.into())
+							.disabled(!has_pixel_selection),
+						MenuListEntry::new("Pixel Selection from Path")
+							.label("Pixel Selection from Path")
+							.icon("NodeShape")
+							.on_commit(|_| DocumentMessage::PixelSelectionFromPath.into())
+							.disabled(no_active_document || !has_selected_layers),
+						MenuListEntry::new("Pixel Selection to Path")
+							.label("Pixel Selection to Path")
+							.icon("VectorPathTool")
+							.on_commit(|_| DocumentMessage::PixelSelectionToPath.into())
+							.disabled(!has_pixel_selection || !has_selected_layers),
+						MenuListEntry::new("Save Pixel Selection as Mask")
+							.label("Save Pixel Selection as Mask")
+							.icon("NodeMask")
+							.on_commit(|_| DocumentMessage::SavePixelSelectionAsMask.into())
+							.disabled(!has_pixel_selection || !has_selected_layers),
+						MenuListEntry::new("Confine to Pixel Selection")
+							.label("Confine to Pixel Selection")
+							.icon("NodeMagicWand")
+							.on_commit(|_| DocumentMessage::ConfineToPixelSelection.into())
+							.disabled(!has_pixel_selection || !has_selected_layers),
+					],
 				])
 				.widget_instance(),
 			TextButton::new("View")
@@ -614,6 +679,23 @@ impl LayoutHolder for MenuBarMessageHandler {
 							.tooltip_shortcut(action_shortcut!(PortfolioMessageDiscriminant::ToggleRulers))
 							.on_commit(|_| PortfolioMessage::ToggleRulers.into())
 							.disabled(no_active_document),
+						MenuListEntry::new("Guides")
+							.label("Guides")
+							.icon(if self.guides_visible { "CheckboxChecked" } else { "CheckboxUnchecked" })
+							.tooltip_shortcut(action_shortcut!(DocumentMessageDiscriminant::ToggleGuidesVisibility))
+							.on_commit(|_| DocumentMessage::ToggleGuidesVisibility.into())
+							.disabled(no_active_document),
+						MenuListEntry::new("Lock Guides")
+							.label("Lock Guides")
+							.icon(if self.guides_locked { "CheckboxChecked" } else { "CheckboxUnchecked" })
+							.tooltip_shortcut(action_shortcut!(DocumentMessageDiscriminant::ToggleGuidesLocked))
+							.on_commit(|_| DocumentMessage::ToggleGuidesLocked.into())
+							.disabled(no_active_document),
+						MenuListEntry::new("Clear Guides")
+							.label("Clear Guides")
+							.icon("Trash")
+							.on_commit(|_| DocumentMessage::ClearGuides.into())
+							.disabled(no_active_document || !self.has_guides),
 					],
 				])
 				.widget_instance(),
@@ -639,6 +721,11 @@ impl LayoutHolder for MenuBarMessageHandler {
 							.icon(if self.data_panel_open { "CheckboxChecked" } else { "CheckboxUnchecked" })
 							.tooltip_shortcut(action_shortcut!(PortfolioMessageDiscriminant::ToggleDataPanelOpen))
 							.on_commit(|_| PortfolioMessage::ToggleDataPanelOpen.into()),
+						MenuListEntry::new("History")
+							.label("History")
+							.icon(if self.history_panel_open { "CheckboxChecked" } else { "CheckboxUnchecked" })
+							.tooltip_shortcut(action_shortcut!(PortfolioMessageDiscriminant::ToggleHistoryPanelOpen))
+							.on_commit(|_| PortfolioMessage::ToggleHistoryPanelOpen.into()),
 					],
 				])
 				.widget_instance(),
@@ -10,12 +10,14 @@ use graphene_std::Color;
 use graphene_std::Context;
 use graphene_std::gradient::GradientStops;
 use graphene_std::memo::IORecord;
+use graphene_std::raster::image_statistics::{ChannelStatistics, HistogramChannel, channel_statistics, histogram_counts};
 use graphene_std::raster_types::{CPU, GPU, Raster};
 use graphene_std::table::Table;
 use graphene_std::vector::Vector;
 use graphene_std::vector::style::{Fill, FillChoice};
 use graphene_std::{Artboard, Graphic};
 use std::any::Any;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(ExtractField)]
@@ -31,6 +33,31 @@ pub struct DataPanelMessageHandler {
 	introspected_data: Option<Arc<dyn Any + Send + Sync>>,
 	element_path: Vec<usize>,
 	active_vector_table_tab: VectorTableTab,
+	/// The histogram and statistics of each raster shown from the introspected data, keyed by the address of its pixels, so redrawing doesn't rescan them.
+	raster_summaries: HashMap<usize, RasterSummary>,
+}
+
+#[derive(Debug, Clone)]
+struct RasterSummary {
+	histogram_svg_data_url: String,
+	statistics: Vec<(HistogramChannel, Option<ChannelStatistics>)>,
+}
+
+impl RasterSummary {
+	fn new(pixels: &[Color]) -> Self {
+		let channels = [
+			HistogramChannel::Luminance,
+			HistogramChannel::Red,
+			HistogramChannel::Green,
+			HistogramChannel::Blue,
+			HistogramChannel::Alpha,
+		];
+
+		Self {
+			histogram_svg_data_url: histogram_svg_data_url(pixels),
+			statistics: channels.into_iter().map(|channel| (channel, channel_statistics(pixels.iter().copied(), channel))).collect(),
+		}
+	}
 }
 
 #[message_handler_data]
@@ -40,11 +67,13 @@ impl MessageHandler<DataPanelMessage, DataPanelMessageContext<'_>> for DataPanel
 			DataPanelMessage::UpdateLayout { mut inspect_result } => {
 				self.introspected_node = Some(inspect_result.inspect_node);
 				self.introspected_data = inspect_result.take_data();
+				self.raster_summaries.clear();
 				self.update_layout(responses, context);
 			}
 			DataPanelMessage::ClearLayout => {
 				self.introspected_node = None;
 				self.introspected_data = None;
+				self.raster_summaries.clear();
 				self.element_path.clear();
 				self.active_vector_table_tab = VectorTableTab::default();
 				self.update_layout(responses, context);
@@ -80,6 +109,7 @@ impl DataPanelMessageHandler {
 			desired_path: &mut self.element_path,
 			breadcrumbs: Vec::new(),
 			vector_table_tab: self.active_vector_table_tab,
+			raster_summaries: &mut self.raster_summaries,
 		};
 
 		// Main data visualization
@@ -143,6 +173,7 @@ struct LayoutData<'a> {
 	desired_path: &'a mut Vec<usize>,
 	breadcrumbs: Vec<String>,
 	vector_table_tab: VectorTableTab,
+	raster_summaries: &'a mut HashMap<usize, RasterSummary>,
 }
 
 macro_rules! generate_layout_downcast {
@@ -167,6 +198,7 @@ fn generate_layout(introspected_data: &Arc<dyn std::any::Any + Send + Sync + 'st
 		Table<Color>,
 		Table<GradientStops>,
 		Vec<String>,
+		Vec<f64>,
 		f64,
 		u32,
 		u64,
@@ -502,7 +534,7 @@ impl TableRowLayout for Raster<CPU> {
 	fn identifier(&self) -> String {
 		format!("Raster ({}x{})", self.width, self.height)
 	}
-	fn element_page(&self, _data: &mut LayoutData) -> Vec<LayoutGroup> {
+	fn element_page(&self, data: &mut LayoutData) -> Vec<LayoutGroup> {
 		let raster = self.data();
 
 		if raster.width == 0 || raster.height == 0 {
@@ -522,10 +554,66 @@ impl TableRowLayout for Raster<CPU> {
 		});
 
 		let widgets = vec![ImageLabel::new(base64_string).widget_instance()];
-		vec![LayoutGroup::Row { widgets }]
+		let summary = data.raster_summaries.entry(raster.data.as_ptr() as usize).or_insert_with(|| RasterSummary::new(&raster.data));
+		let histogram = vec![ImageLabel::new(summary.histogram_svg_data_url.clone()).width(Some("256px".to_string())).widget_instance()];
+
+		let mut rows = summary
+			.statistics
+			.iter()
+			.map(|&(channel, statistics)| {
+				let format = |value: Option<f64>| value.map_or_else(|| "-".to_string(), |value| format!("{:.1}%", value * 100.));
+
+				vec![
+					TextLabel::new(format!("{channel:?}")).narrow(true).widget_instance(),
+					TextLabel::new(format(statistics.map(|statistics| statistics.mean))).narrow(true).widget_instance(),
+					TextLabel::new(format(statistics.map(|statistics| statistics.minimum))).narrow(true).widget_instance(),
+					TextLabel::new(format(statistics.map(|statistics| statistics.maximum))).narrow(true).widget_instance(),
+				]
+			})
+			.collect::<Vec<_>>();
+		rows.insert(0, column_headings(&["", "mean", "minimum", "maximum"]));
+
+		vec![LayoutGroup::Row { widgets }, LayoutGroup::Row { widgets: histogram }, LayoutGroup::Table { rows, unstyled: false }]
 	}
 }
 
+/// Draws the luminance and RGB histograms of the pixels as an SVG, encoded as a data URL for display in an `ImageLabel`.
+fn histogram_svg_data_url(pixels: &[Color]) -> String {
+	use base64::Engine;
+
+	const BINS: usize = 256;
+	const HEIGHT: f64 = 100.;
+
+	let channels = [
+		(HistogramChannel::Red, "#ff4040"),
+		(HistogramChannel::Green, "#40ff40"),
+		(HistogramChannel::Blue, "#4040ff"),
+		(HistogramChannel::Luminance, "#ffffff"),
+	]
+	.map(|(channel, color)| (histogram_counts(pixels.iter().copied(), channel, BINS), color));
+	let tallest = channels.iter().flat_map(|(counts, _)| counts.iter().copied()).max().unwrap_or(0).max(1) as f64;
+
+	let mut svg = format!(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {BINS} {HEIGHT}" preserveAspectRatio="none">"#);
+	for (counts, color) in channels {
+		let points = counts
+			.iter()
+			.enumerate()
+			.map(|(bin, &count)| format!("{},{:.2}", bin as f64 + 0.5, HEIGHT * (1. - count as f64 / tallest)))
+			.collect::<Vec<_>>()
+			.join(" ");
+		svg.push_str(&format!(
+			r#"<polygon points="0,{HEIGHT} {points} {BINS},{HEIGHT}" fill="{color}" fill-opacity="0.3" stroke="{color}" stroke-width="0.5" />"#
+		));
+	}
+	svg.push_str("</svg>");
+
+	let preamble = "data:image/svg+xml;base64,";
+	let mut data_url = String::with_capacity(preamble.len() + svg.len() * 4 / 3 + 4);
+	data_url.push_str(preamble);
+	base64::engine::general_purpose::STANDARD.encode_string(svg, &mut data_url);
+	data_url
+}
+
 impl TableRowLayout for Raster<GPU> {
 	fn type_name() -> &'static str {
 		"Raster"
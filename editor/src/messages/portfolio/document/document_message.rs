@@ -1,18 +1,21 @@
 use std::path::PathBuf;
 
 use super::utility_types::misc::{GroupFolderType, SnappingState};
+use crate::messages::frontend::utility_types::{ExportPreset, ExportTarget};
 use crate::messages::input_mapper::utility_types::input_keyboard::Key;
 use crate::messages::portfolio::document::data_panel::DataPanelMessage;
 use crate::messages::portfolio::document::overlays::utility_types::{OverlayContext, OverlaysType};
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
-use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis, GridSnapping};
+use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis, GridSnapping, SliceId};
 use crate::messages::portfolio::utility_types::PanelType;
 use crate::messages::prelude::*;
-use glam::DAffine2;
+use glam::{DAffine2, DVec2};
 use graph_craft::document::NodeId;
 use graphene_std::Color;
 use graphene_std::raster::BlendMode;
 use graphene_std::raster::Image;
+use graphene_std::raster::selection::SelectionStep;
+use graphene_std::raster_types::color_management::ColorSpace;
 use graphene_std::transform::Footprint;
 use graphene_std::vector::click_target::ClickTarget;
 use graphene_std::vector::style::RenderMode;
@@ -42,20 +45,46 @@ pub enum DocumentMessage {
 		aggregate: AlignAggregate,
 	},
 	RemoveArtboards,
+	AddExportSlice,
+	AddPixelSelectionStep {
+		step: SelectionStep,
+	},
+	ClearGuides,
+	ClearHistoryPanel,
 	ClearLayersPanel,
 	CreateEmptyFolder,
+	CreateHistorySnapshot,
+	ConfineToPixelSelection,
+	CreateSymbolFromSelectedLayers,
 	DeleteNode {
 		node_id: NodeId,
 	},
+	DeleteHistorySnapshot {
+		index: usize,
+	},
+	DeleteExportSlice {
+		id: SliceId,
+	},
 	DeleteSelectedLayers,
 	DeselectAllLayers,
+	DeselectPixels,
+	DetachSymbolInstance {
+		layer: LayerNodeIdentifier,
+	},
 	DocumentHistoryBackward,
 	DocumentHistoryForward,
 	DocumentStructureChanged,
+	DragGuide,
+	DragGuideFromRuler {
+		vertical: bool,
+	},
 	DrawArtboardOverlays {
 		context: OverlayContext,
 	},
 	DuplicateSelectedLayers,
+	EditSymbolDefinition {
+		layer: LayerNodeIdentifier,
+	},
 	EnterNestedNetwork {
 		node_id: NodeId,
 	},
@@ -63,6 +92,7 @@ pub enum DocumentMessage {
 	ExitNestedNetwork {
 		steps_back: usize,
 	},
+	ExportNodeLibrary,
 	FlipSelectedLayers {
 		flip_axis: FlipAxis,
 	},
@@ -82,6 +112,19 @@ pub enum DocumentMessage {
 	GridVisibility {
 		visible: bool,
 	},
+	GuideDragAbort,
+	GuideDragMove {
+		rotate: Key,
+		snap_angle: Key,
+	},
+	GuideDragStop,
+	GuideOverlays {
+		context: OverlayContext,
+	},
+	InvertPixelSelection,
+	JumpToHistoryStep {
+		step: usize,
+	},
 	GroupSelectedLayers {
 		group_folder_type: GroupFolderType,
 	},
@@ -110,20 +153,44 @@ pub enum DocumentMessage {
 		mouse: Option<(f64, f64)>,
 		parent_and_insert_index: Option<(LayerNodeIdentifier, usize)>,
 	},
+	PixelSelectionFromPath,
+	PixelSelectionOverlays {
+		context: OverlayContext,
+	},
+	PixelSelectionToPath,
 	Redo,
+	RenameHistorySnapshot {
+		index: usize,
+		name: String,
+	},
+	RenameExportSlice {
+		id: SliceId,
+		name: String,
+	},
 	RenameDocument {
 		new_name: String,
 	},
 	RenderRulers,
+	ResetSymbolOverrides {
+		layer: LayerNodeIdentifier,
+	},
+	RestoreHistorySnapshot {
+		index: usize,
+	},
 	RenderScrollbars,
 	SaveDocument,
 	SaveDocumentAs,
+	SavePixelSelectionAsMask,
 	SavedDocument {
 		path: Option<PathBuf>,
 	},
 	MarkAsSaved,
 	SelectParentLayer,
 	SelectAllLayers,
+	SelectLayersByQuery {
+		query: String,
+	},
+	SelectSimilarLayers,
 	SelectedLayersLower,
 	SelectedLayersLowerToBack,
 	SelectedLayersRaise,
@@ -156,6 +223,14 @@ pub enum DocumentMessage {
 	SetOpacityForSelectedLayers {
 		opacity: f64,
 	},
+	SetExportPresets {
+		target: ExportTarget,
+		presets: Vec<ExportPreset>,
+	},
+	SetExportSliceBounds {
+		id: SliceId,
+		bounds: [DVec2; 2],
+	},
 	SetFillForSelectedLayers {
 		fill: f64,
 	},
@@ -172,6 +247,9 @@ pub enum DocumentMessage {
 		closure: Option<for<'a> fn(&'a mut SnappingState) -> &'a mut bool>,
 		snapping_state: bool,
 	},
+	SliceOverlays {
+		context: OverlayContext,
+	},
 	SetToNodeOrLayer {
 		node_id: NodeId,
 		is_layer: bool,
@@ -179,8 +257,18 @@ pub enum DocumentMessage {
 	SetRenderMode {
 		render_mode: RenderMode,
 	},
-	AddTransaction,
-	StartTransaction,
+	SetWorkingColorSpace {
+		working_color_space: ColorSpace,
+	},
+	ConvertToWorkingColorSpace {
+		working_color_space: ColorSpace,
+	},
+	AddTransaction {
+		label: String,
+	},
+	StartTransaction {
+		label: String,
+	},
 	EndTransaction,
 	CommitTransaction,
 	CancelTransaction,
@@ -195,8 +283,11 @@ pub enum DocumentMessage {
 	ToggleSelectedVisibility,
 	ToggleSelectedLocked,
 	ToggleGridVisibility,
+	ToggleGuidesLocked,
+	ToggleGuidesVisibility,
 	ToggleOverlaysVisibility,
 	ToggleSnapping,
+	UpdateHistoryPanel,
 	UpdateUpstreamTransforms {
 		upstream_footprints: HashMap<NodeId, Footprint>,
 		local_transforms: HashMap<NodeId, DAffine2>,
@@ -213,6 +304,9 @@ pub enum DocumentMessage {
 	UngroupLayer {
 		layer: LayerNodeIdentifier,
 	},
+	UpgradeLibraryNode {
+		node_id: NodeId,
+	},
 	PTZUpdate,
 	SelectionStepBack,
 	SelectionStepForward,
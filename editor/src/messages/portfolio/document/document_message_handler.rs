@@ -1,11 +1,17 @@
 use super::node_graph::document_node_definitions;
 use super::node_graph::utility_types::Transform;
 use super::utility_types::error::EditorError;
-use super::utility_types::misc::{GroupFolderType, SNAP_FUNCTIONS_FOR_BOUNDING_BOXES, SNAP_FUNCTIONS_FOR_PATHS, SnappingOptions, SnappingState};
-use super::utility_types::network_interface::{self, NodeNetworkInterface, TransactionStatus};
+use super::utility_types::layer_query::LayerQuery;
+use super::utility_types::misc::{
+	ExportSlice, GroupFolderType, Guide, GuideId, Guides, SNAP_FUNCTIONS_FOR_BOUNDING_BOXES, SNAP_FUNCTIONS_FOR_GUIDES, SNAP_FUNCTIONS_FOR_PATHS, SliceId, SnappingOptions, SnappingState,
+};
+use super::utility_types::network_interface::{self, NetworkSnapshot, NodeNetworkInterface, TransactionStatus};
 use super::utility_types::nodes::{CollapsedLayers, SelectedNodes};
 use crate::application::{GRAPHITE_GIT_COMMIT_HASH, generate_uuid};
-use crate::consts::{ASYMPTOTIC_EFFECT, COLOR_OVERLAY_GRAY, DEFAULT_DOCUMENT_NAME, FILE_EXTENSION, SCALE_EFFECT, SCROLLBAR_SPACING, VIEWPORT_ROTATE_SNAP_INTERVAL};
+use crate::consts::{
+	ASYMPTOTIC_EFFECT, COLOR_OVERLAY_GRAY, DEFAULT_DOCUMENT_NAME, FILE_EXTENSION, LINE_ROTATE_SNAP_ANGLE, SCALE_EFFECT, SCROLLBAR_SPACING, SELECTION_TOLERANCE, VIEWPORT_ROTATE_SNAP_INTERVAL,
+};
+use crate::messages::frontend::utility_types::{ExportPreset, ExportTarget};
 use crate::messages::input_mapper::utility_types::macros::action_shortcut;
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::data_panel::{DataPanelMessageContext, DataPanelMessageHandler};
@@ -14,34 +20,43 @@ use crate::messages::portfolio::document::node_graph::NodeGraphMessageContext;
 use crate::messages::portfolio::document::node_graph::document_node_definitions::DefinitionIdentifier;
 use crate::messages::portfolio::document::node_graph::utility_types::FrontendGraphDataType;
 use crate::messages::portfolio::document::overlays::grid_overlays::{grid_overlay, overlay_options};
+use crate::messages::portfolio::document::overlays::guide_overlays::guide_overlay;
+use crate::messages::portfolio::document::overlays::pixel_selection_overlays::pixel_selection_overlay;
+use crate::messages::portfolio::document::overlays::slice_overlays::slice_overlay;
 use crate::messages::portfolio::document::overlays::utility_types::{OverlaysType, OverlaysVisibilitySettings, Pivot};
 use crate::messages::portfolio::document::properties_panel::properties_panel_message_handler::PropertiesPanelMessageContext;
 use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
 use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis, PTZ};
-use crate::messages::portfolio::document::utility_types::network_interface::{FlowType, InputConnector, NodeTemplate};
+use crate::messages::portfolio::document::utility_types::network_interface::{FlowType, InputConnector, NodeTemplate, OutputConnector};
 use crate::messages::portfolio::document::utility_types::nodes::RawBuffer;
 use crate::messages::portfolio::utility_types::{FontCatalog, PanelType, PersistentData};
 use crate::messages::prelude::*;
-use crate::messages::tool::common_functionality::graph_modification_utils::{self, get_blend_mode, get_fill, get_opacity};
+use crate::messages::tool::common_functionality::graph_modification_utils::{self, NodeGraphLayer, get_blend_mode, get_fill, get_opacity};
 use crate::messages::tool::tool_messages::select_tool::SelectToolPointerKeys;
 use crate::messages::tool::tool_messages::tool_prelude::Key;
 use crate::messages::tool::utility_types::ToolType;
 use crate::node_graph_executor::NodeGraphExecutor;
 use glam::{DAffine2, DVec2, IVec2};
 use graph_craft::document::value::TaggedValue;
-use graph_craft::document::{NodeId, NodeInput, NodeNetwork, OldNodeNetwork};
+use graph_craft::document::{DocumentNodeImplementation, NodeId, NodeInput, NodeNetwork, OldNodeNetwork};
+use graphene_std::Color;
+use graphene_std::choice_type::ChoiceTypeStatic;
 use graphene_std::math::quad::Quad;
 use graphene_std::path_bool::{boolean_intersect, path_bool_lib};
 use graphene_std::raster::BlendMode;
+use graphene_std::raster::selection::{SelectionCombine, SelectionShape, SelectionStep, flatten_bezpath};
 use graphene_std::raster_types::Raster;
+use graphene_std::raster_types::color_management::{self, ColorSpace};
 use graphene_std::subpath::Subpath;
 use graphene_std::table::Table;
 use graphene_std::text::Font;
 use graphene_std::vector::PointId;
 use graphene_std::vector::click_target::{ClickTarget, ClickTargetType};
 use graphene_std::vector::misc::{dvec2_to_point, point_to_dvec2};
-use graphene_std::vector::style::RenderMode;
+use graphene_std::vector::style::{Fill, RenderMode};
 use kurbo::{Affine, CubicBez, Line, ParamCurve, PathSeg, QuadBez};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -54,6 +69,7 @@ pub struct DocumentMessageContext<'a> {
 	pub current_tool: &'a ToolType,
 	pub preferences: &'a PreferencesMessageHandler,
 	pub data_panel_open: bool,
+	pub history_panel_open: bool,
 	pub layers_panel_open: bool,
 	pub properties_panel_open: bool,
 	pub viewport: &'a ViewportMessageHandler,
@@ -95,6 +111,9 @@ pub struct DocumentMessageHandler {
 	/// This is usually "Normal" but can be set to "Outline" or "Pixels" to see the canvas differently.
 	#[serde(alias = "view_mode")]
 	pub render_mode: RenderMode,
+	/// The color space whose primaries define the linear color values of the document's raster data.
+	/// Images brought into the document are converted into this space, and exports are converted from it into their chosen color profile.
+	pub working_color_space: ColorSpace,
 	/// Sets whether or not all the viewport overlays should be drawn on top of the artwork.
 	/// This includes tool interaction visualizations (like the transform cage and path anchors/handles), the grid, and more.
 	pub overlays_visibility_settings: OverlaysVisibilitySettings,
@@ -102,6 +121,15 @@ pub struct DocumentMessageHandler {
 	pub rulers_visible: bool,
 	/// The current user choices for snapping behavior, including whether snapping is enabled at all.
 	pub snapping_state: SnappingState,
+	/// The guides that the user has dragged out from the rulers, and whether they're shown and locked.
+	pub guides: Guides,
+	/// Regions of the document that are exported by Export All, independently of the layers within them.
+	pub export_slices: Vec<ExportSlice>,
+	/// The steps which build up the pixel selection made with the Pixel Selection tool, in document space.
+	/// Raster edits are confined to it by mapping it into the space of the edited layer.
+	pub pixel_selection: Vec<SelectionStep>,
+	/// The version of each node library most recently exported from the document, so every export of a library is given a newer version than the last.
+	pub node_library_versions: HashMap<String, u32>,
 	/// Sets whether or not the node graph is drawn (as an overlay) on top of the viewport area, or otherwise if it's hidden.
 	pub graph_view_overlay_open: bool,
 	/// The current opacity of the faded node graph background that covers up the artwork.
@@ -123,12 +151,15 @@ pub struct DocumentMessageHandler {
 	/// Path to network that is currently selected. Updated based on the most recently clicked panel.
 	#[serde(skip)]
 	selection_network_path: Vec<NodeId>,
-	/// Stack of document network snapshots for previous history states.
+	/// Stack of document network snapshots for previous history states. Unchanged nodes are shared between neighboring snapshots.
 	#[serde(skip)]
-	document_undo_history: VecDeque<NodeNetworkInterface>,
-	/// Stack of document network snapshots for future history states.
+	document_undo_history: VecDeque<HistoryStep>,
+	/// Stack of document network snapshots for future history states. Unchanged nodes are shared between neighboring snapshots.
 	#[serde(skip)]
-	document_redo_history: VecDeque<NodeNetworkInterface>,
+	document_redo_history: VecDeque<HistoryStep>,
+	/// Named snapshots of the document that the user captured in the History panel, which can be restored regardless of the undo history.
+	#[serde(skip)]
+	history_snapshots: Vec<HistorySnapshot>,
 	/// Hash of the document snapshot that was most recently saved to disk by the user.
 	#[serde(skip)]
 	saved_hash: Option<u64>,
@@ -142,6 +173,46 @@ pub struct DocumentMessageHandler {
 	/// Whether or not the editor has executed the network to render the document yet. If this is opened as an inactive tab, it won't be loaded initially because the active tab is prioritized.
 	#[serde(skip)]
 	pub is_loaded: bool,
+	/// The guide that is currently being dragged out from the rulers or moved around the canvas, if any.
+	#[serde(skip)]
+	guide_drag: Option<GuideDrag>,
+	/// The text in the search field of the Layers panel, kept so the field isn't cleared when the panel is redrawn.
+	#[serde(skip)]
+	layer_search_query: String,
+}
+
+/// An entry in the undo or redo history, labeled with the action that created it for display in the History panel.
+#[derive(Clone, Debug)]
+struct HistoryStep {
+	label: String,
+	snapshot: NetworkSnapshot,
+	state: DocumentState,
+}
+
+/// A snapshot of the document that the user named in the History panel.
+#[derive(Clone, Debug)]
+struct HistorySnapshot {
+	name: String,
+	snapshot: NetworkSnapshot,
+	state: DocumentState,
+}
+
+/// The parts of the document stored outside of its network, which the undo history restores along with the network.
+#[derive(Clone, Debug)]
+struct DocumentState {
+	/// The document's working color space, since changing it converts the colors stored in the network.
+	working_color_space: ColorSpace,
+	guides: Guides,
+	export_slices: Vec<ExportSlice>,
+	pixel_selection: Vec<SelectionStep>,
+}
+
+/// The state of a guide while it's being dragged, before it's placed in the document.
+#[derive(Clone, Debug)]
+struct GuideDrag {
+	guide: Guide,
+	/// The point in document space where the guide was grabbed, which it rotates around.
+	grab_point: DVec2,
 }
 
 impl Default for DocumentMessageHandler {
@@ -163,10 +234,15 @@ impl Default for DocumentMessageHandler {
 			commit_hash: GRAPHITE_GIT_COMMIT_HASH.to_string(),
 			document_ptz: PTZ::default(),
 			render_mode: RenderMode::default(),
+			working_color_space: ColorSpace::default(),
 			overlays_visibility_settings: OverlaysVisibilitySettings::default(),
 			rulers_visible: true,
 			graph_view_overlay_open: false,
 			snapping_state: SnappingState::default(),
+			guides: Guides::default(),
+			export_slices: Vec::new(),
+			pixel_selection: Vec::new(),
+			node_library_versions: HashMap::new(),
 			graph_fade_artwork_percentage: 80.,
 			// =============================================
 			// Fields omitted from the saved document format
@@ -177,10 +253,13 @@ impl Default for DocumentMessageHandler {
 			selection_network_path: Vec::new(),
 			document_undo_history: VecDeque::new(),
 			document_redo_history: VecDeque::new(),
+			history_snapshots: Vec::new(),
 			saved_hash: None,
 			auto_saved_hash: None,
 			layer_range_selection_reference: None,
 			is_loaded: false,
+			guide_drag: None,
+			layer_search_query: String::new(),
 		}
 	}
 }
@@ -197,6 +276,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			current_tool,
 			preferences,
 			data_panel_open,
+			history_panel_open,
 			layers_panel_open,
 			properties_panel_open,
 		} = context;
@@ -300,7 +380,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					};
 					let translation = (aggregated - center) * axis;
 					if !added_transaction {
-						responses.add(DocumentMessage::AddTransaction);
+						responses.add(DocumentMessage::AddTransaction { label: "Align".into() });
 						added_transaction = true;
 					}
 					responses.add(GraphOperationMessage::TransformChange {
@@ -314,6 +394,51 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			DocumentMessage::RemoveArtboards => {
 				responses.add(GraphOperationMessage::RemoveArtboards);
 			}
+			DocumentMessage::AddExportSlice => {
+				let Some(bounds) = self
+					.network_interface
+					.selected_bounds_document_space(true, &[])
+					.or_else(|| self.network_interface.document_bounds_document_space(true))
+				else {
+					return;
+				};
+
+				self.start_transaction("Add Slice".into(), preferences, responses);
+				self.export_slices.push(ExportSlice {
+					id: SliceId(generate_uuid()),
+					name: format!("Slice {}", self.export_slices.len() + 1),
+					bounds,
+					presets: vec![ExportPreset::default()],
+				});
+				responses.add(DocumentMessage::CommitTransaction);
+				responses.add(OverlaysMessage::Draw);
+			}
+			DocumentMessage::AddPixelSelectionStep { step } => {
+				self.start_transaction("Select Pixels".into(), preferences, responses);
+				if step.combine == SelectionCombine::Replace {
+					self.pixel_selection.clear();
+				}
+				self.pixel_selection.push(step);
+				responses.add(DocumentMessage::CommitTransaction);
+				responses.add(OverlaysMessage::Draw);
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			DocumentMessage::ClearGuides => {
+				if self.guides.guides.is_empty() {
+					return;
+				}
+				self.start_transaction("Clear Guides".into(), preferences, responses);
+				self.guides.guides.clear();
+				responses.add(DocumentMessage::CommitTransaction);
+				responses.add(OverlaysMessage::Draw);
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			DocumentMessage::ClearHistoryPanel => {
+				responses.add(LayoutMessage::SendLayout {
+					layout: Layout::default(),
+					layout_target: LayoutTarget::HistoryPanel,
+				});
+			}
 			DocumentMessage::ClearLayersPanel => {
 				// Send an empty layer list
 				let data_buffer: RawBuffer = Self::default().serialize_root();
@@ -345,7 +470,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					.unwrap_or(LayerNodeIdentifier::ROOT_PARENT);
 
 				let insert_index = DocumentMessageHandler::get_calculated_insert_index(self.metadata(), &self.network_interface.selected_nodes(), parent);
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "New Folder".into() });
 				responses.add(GraphOperationMessage::NewCustomLayer {
 					id,
 					nodes: Vec::new(),
@@ -354,8 +479,73 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				});
 				responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![id] });
 			}
+			DocumentMessage::CreateHistorySnapshot => {
+				let name = (1..)
+					.map(|number| format!("Snapshot {number}"))
+					.find(|name| self.history_snapshots.iter().all(|snapshot| snapshot.name != *name))
+					.unwrap_or_default();
+				let snapshot = self.network_interface.snapshot(self.document_undo_history.back().map(|step| &step.snapshot));
+				self.history_snapshots.push(HistorySnapshot {
+					name,
+					snapshot,
+					state: self.document_state(),
+				});
+				self.trim_history(preferences);
+
+				responses.add(DocumentMessage::UpdateHistoryPanel);
+			}
+			DocumentMessage::ConfineToPixelSelection => {
+				if self.pixel_selection.is_empty() {
+					return;
+				}
+
+				let mut confined = Vec::new();
+				for layer in self.selected_raster_layers() {
+					// The content feeding the layer is confined to the selection, compared to the content feeding that
+					let Some(OutputConnector::Node { node_id: content, .. }) = self.network_interface.upstream_output_connector(&InputConnector::node(layer.to_node(), 1), &[]) else {
+						continue;
+					};
+					let Some(original) = self.network_interface.upstream_output_connector(&InputConnector::node(content, 0), &[]) else {
+						continue;
+					};
+					let selection = self.pixel_selection_in_layer_space(layer);
+					confined.push(GraphOperationMessage::ConfineToSelection { layer, original, selection });
+				}
+				if confined.is_empty() {
+					return;
+				}
+
+				responses.add(DocumentMessage::AddTransaction { label: "Confine to Selection".into() });
+				for message in confined {
+					responses.add(message);
+				}
+			}
+			DocumentMessage::CreateSymbolFromSelectedLayers => {
+				let selected_layers = self.network_interface.selected_nodes().selected_layers(self.metadata()).collect::<Vec<_>>();
+				match selected_layers.as_slice() {
+					[] => {}
+					[layer] => {
+						responses.add(DocumentMessage::AddTransaction { label: "Create Symbol".into() });
+						responses.add(NodeGraphMessage::CreateSymbol { layer: Some(*layer) });
+					}
+					// A symbol is made from a single layer, so multiple layers are grouped into one first
+					_ => {
+						responses.add(DocumentMessage::GroupSelectedLayers {
+							group_folder_type: GroupFolderType::Layer,
+						});
+						responses.add(DocumentMessage::AddTransaction { label: "Create Symbol".into() });
+						responses.add(NodeGraphMessage::CreateSymbol { layer: None });
+					}
+				}
+			}
+			DocumentMessage::DeleteHistorySnapshot { index } => {
+				if index < self.history_snapshots.len() {
+					self.history_snapshots.remove(index);
+				}
+				responses.add(DocumentMessage::UpdateHistoryPanel);
+			}
 			DocumentMessage::DeleteNode { node_id } => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Delete Node".into() });
 
 				responses.add(NodeGraphMessage::DeleteNodes {
 					node_ids: vec![node_id],
@@ -366,6 +556,14 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(NodeGraphMessage::SendGraph);
 				responses.add(DocumentMessage::EndTransaction);
 			}
+			DocumentMessage::DeleteExportSlice { id } => {
+				let Some(index) = self.export_slices.iter().position(|slice| slice.id == id) else { return };
+
+				self.start_transaction("Delete Slice".into(), preferences, responses);
+				self.export_slices.remove(index);
+				responses.add(DocumentMessage::CommitTransaction);
+				responses.add(OverlaysMessage::Draw);
+			}
 			DocumentMessage::DeleteSelectedLayers => {
 				responses.add(NodeGraphMessage::DeleteSelectedNodes { delete_children: true });
 			}
@@ -373,8 +571,24 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![] });
 				self.layer_range_selection_reference = None;
 			}
-			DocumentMessage::DocumentHistoryBackward => self.undo_with_history(viewport, responses),
-			DocumentMessage::DocumentHistoryForward => self.redo_with_history(viewport, responses),
+			DocumentMessage::DeselectPixels => {
+				if self.pixel_selection.is_empty() {
+					return;
+				}
+				self.start_transaction("Deselect Pixels".into(), preferences, responses);
+				self.pixel_selection.clear();
+				responses.add(DocumentMessage::CommitTransaction);
+				responses.add(OverlaysMessage::Draw);
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			DocumentMessage::DetachSymbolInstance { layer } => {
+				let Some(instance) = self.network_interface.layer_symbol_instance(layer) else { return };
+
+				responses.add(DocumentMessage::AddTransaction { label: "Detach Symbol".into() });
+				responses.add(NodeGraphMessage::DetachSymbolInstance { node_id: instance });
+			}
+			DocumentMessage::DocumentHistoryBackward => self.undo_with_history(viewport, preferences, responses),
+			DocumentMessage::DocumentHistoryForward => self.redo_with_history(viewport, preferences, responses),
 			DocumentMessage::DocumentStructureChanged => {
 				if layers_panel_open {
 					self.network_interface.load_structure();
@@ -386,6 +600,40 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					responses.add(FrontendMessage::UpdateDocumentLayerStructure { data_buffer });
 				}
 			}
+			DocumentMessage::DragGuide => {
+				let Some(id) = self.guide_at(ipp.mouse.position).map(|guide| guide.id) else { return };
+				let Some(index) = self.guides.guides.iter().position(|guide| guide.id == id) else { return };
+
+				// Dropping the guide commits this transaction, or cancels it if the guide ends up where it started
+				self.start_transaction("Move Guide".into(), preferences, responses);
+
+				// The guide is taken out of the document while it's dragged so it isn't snapped to or drawn twice
+				let guide = self.guides.guides.remove(index);
+				let grab_point = guide.project(self.metadata().document_to_viewport.inverse().transform_point2(ipp.mouse.position));
+				self.guide_drag = Some(GuideDrag { guide, grab_point });
+				responses.add(OverlaysMessage::Draw);
+			}
+			DocumentMessage::DragGuideFromRuler { vertical } => {
+				if self.graph_view_overlay_open {
+					return;
+				}
+
+				self.start_transaction("Add Guide".into(), preferences, responses);
+
+				// Show the guides if they were hidden so the new one can be seen while it's placed
+				self.guides.visible = true;
+
+				let grab_point = self.metadata().document_to_viewport.inverse().transform_point2(ipp.mouse.position);
+				self.guide_drag = Some(GuideDrag {
+					guide: Guide {
+						id: GuideId(generate_uuid()),
+						origin: grab_point,
+						angle: if vertical { 90. } else { 0. },
+					},
+					grab_point,
+				});
+				responses.add(OverlaysMessage::Draw);
+			}
 			DocumentMessage::DrawArtboardOverlays { context: overlay_context } => {
 				if !overlay_context.visibility_settings.artboard_name() {
 					return;
@@ -424,7 +672,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				}
 			}
 			DocumentMessage::DuplicateSelectedLayers => {
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Duplicate".into() });
 
 				let mut new_dragging = Vec::new();
 				let mut layers = self.network_interface.shallowest_unique_layers(&[]).collect::<Vec<_>>();
@@ -465,7 +713,29 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(NodeGraphMessage::SelectedNodesSet { nodes });
 				responses.add(NodeGraphMessage::RunDocumentGraph);
 			}
+			DocumentMessage::EditSymbolDefinition { layer } => {
+				let Some(definition) = self
+					.network_interface
+					.layer_symbol_instance(layer)
+					.and_then(|instance| self.network_interface.symbol_definition(&instance))
+				else {
+					return;
+				};
+
+				responses.add(DocumentMessage::GraphViewOverlay { open: true });
+				if !self.breadcrumb_network_path.is_empty() {
+					responses.add(DocumentMessage::ExitNestedNetwork {
+						steps_back: self.breadcrumb_network_path.len(),
+					});
+				}
+				responses.add(DocumentMessage::EnterNestedNetwork { node_id: definition });
+			}
 			DocumentMessage::EnterNestedNetwork { node_id } => {
+				// Symbol instances refer to the network of their definition, so the definition is edited in their place
+				let node_id = match self.network_interface.symbol_definition(&node_id) {
+					Some(definition) if self.breadcrumb_network_path.is_empty() && self.network_interface.document_network().nodes.contains_key(&definition) => definition,
+					_ => node_id,
+				};
 				self.breadcrumb_network_path.push(node_id);
 				self.selection_network_path.clone_from(&self.breadcrumb_network_path);
 				responses.add(NodeGraphMessage::UnloadWires);
@@ -525,6 +795,29 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(DocumentMessage::PTZUpdate);
 				responses.add(NodeGraphMessage::UpdateNodeGraphWidth);
 			}
+			DocumentMessage::ExportNodeLibrary => {
+				let Some(selected_nodes) = self.network_interface.selected_nodes_in_nested_network(&self.selection_network_path) else {
+					log::error!("Could not get selected nodes in DocumentMessage::ExportNodeLibrary");
+					return;
+				};
+				let node_ids = selected_nodes.selected_nodes().cloned().collect::<Vec<_>>();
+
+				let previous_version = self.node_library_versions.get(&self.name).copied().unwrap_or_default();
+				match self.network_interface.create_node_library(self.name.clone(), previous_version, &node_ids, &self.selection_network_path) {
+					Ok(library) => {
+						self.node_library_versions.insert(library.name.clone(), library.version);
+						responses.add(PortfolioMessage::UpdateOpenDocumentsList);
+						responses.add(FrontendMessage::TriggerSaveFile {
+							name: library.file_name(),
+							content: library.to_file_contents().into_bytes(),
+						});
+					}
+					Err(description) => responses.add(DialogMessage::DisplayDialogError {
+						title: "Unable to export node library".to_string(),
+						description,
+					}),
+				}
+			}
 			DocumentMessage::FlipSelectedLayers { flip_axis } => {
 				let scale = match flip_axis {
 					FlipAxis::X => DVec2::new(-1., 1.),
@@ -536,7 +829,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					let mut added_transaction = false;
 					for layer in self.network_interface.selected_nodes().selected_unlocked_layers(&self.network_interface) {
 						if !added_transaction {
-							responses.add(DocumentMessage::AddTransaction);
+							responses.add(DocumentMessage::AddTransaction { label: "Flip".into() });
 							added_transaction = true;
 						}
 						responses.add(GraphOperationMessage::TransformChange {
@@ -559,7 +852,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					let mut added_transaction = false;
 					for layer in self.network_interface.selected_nodes().selected_unlocked_layers(&self.network_interface) {
 						if !added_transaction {
-							responses.add(DocumentMessage::AddTransaction);
+							responses.add(DocumentMessage::AddTransaction { label: "Rotate".into() });
 							added_transaction = true;
 						}
 
@@ -620,8 +913,60 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				self.snapping_state.grid_snapping = visible;
 				responses.add(OverlaysMessage::Draw);
 			}
+			DocumentMessage::GuideDragAbort => {
+				if self.guide_drag.take().is_none() {
+					return;
+				}
+
+				// Put the guides back the way they were when the drag started, including their visibility
+				if let Some(step) = self.document_undo_history.back() {
+					self.guides = step.state.guides.clone();
+				}
+				responses.add(DocumentMessage::CancelTransaction);
+				responses.add(OverlaysMessage::Draw);
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			DocumentMessage::GuideDragMove { rotate, snap_angle } => {
+				let document_to_viewport = self.metadata().document_to_viewport;
+				let Some(guide_drag) = &mut self.guide_drag else { return };
+
+				let mouse = document_to_viewport.inverse().transform_point2(ipp.mouse.position);
+				if ipp.keyboard.get(rotate as usize) {
+					// Rotate the guide around the point where it was grabbed so it points towards the mouse
+					let delta = mouse - guide_drag.grab_point;
+					if delta.length_squared() > 0. {
+						let mut angle = delta.y.atan2(delta.x).to_degrees();
+						if ipp.keyboard.get(snap_angle as usize) {
+							angle = (angle / LINE_ROTATE_SNAP_ANGLE).round() * LINE_ROTATE_SNAP_ANGLE;
+						}
+						guide_drag.guide.angle = angle.rem_euclid(180.);
+						guide_drag.guide.origin = guide_drag.grab_point;
+					}
+				} else {
+					guide_drag.guide.origin = mouse;
+				}
+				responses.add(OverlaysMessage::Draw);
+			}
+			DocumentMessage::GuideDragStop => {
+				let Some(guide_drag) = self.guide_drag.take() else { return };
+
+				// Dropping the guide outside the viewport, such as back onto the rulers, deletes it
+				let mouse = ipp.mouse.position;
+				if mouse.cmpge(DVec2::ZERO).all() && mouse.cmple(viewport.size().into_dvec2()).all() {
+					self.guides.guides.push(guide_drag.guide);
+				}
+				let unchanged = self.document_undo_history.back().is_some_and(|step| step.state.guides == self.guides);
+				responses.add(if unchanged { DocumentMessage::CancelTransaction } else { DocumentMessage::CommitTransaction });
+				responses.add(OverlaysMessage::Draw);
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			DocumentMessage::GuideOverlays { context: mut overlay_context } => {
+				if self.guides.visible && !self.graph_view_overlay_open {
+					guide_overlay(self, self.guide_drag.as_ref().map(|guide_drag| &guide_drag.guide), &mut overlay_context);
+				}
+			}
 			DocumentMessage::GroupSelectedLayers { group_folder_type } => {
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Group".into() });
 
 				let mut parent_per_selected_nodes: HashMap<LayerNodeIdentifier, Vec<NodeId>> = HashMap::new();
 				let artboards = LayerNodeIdentifier::ROOT_PARENT
@@ -668,6 +1013,35 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					responses.add(NodeGraphMessage::SelectedNodesSet { nodes: new_folders });
 				}
 			}
+			DocumentMessage::InvertPixelSelection => {
+				self.start_transaction("Invert Pixel Selection".into(), preferences, responses);
+				self.pixel_selection.push(SelectionStep {
+					shape: SelectionShape::All,
+					combine: SelectionCombine::Exclude,
+					anti_aliasing: false,
+				});
+				responses.add(DocumentMessage::CommitTransaction);
+				responses.add(OverlaysMessage::Draw);
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			DocumentMessage::JumpToHistoryStep { step } => {
+				if self.network_interface.transaction_status() != TransactionStatus::Finished {
+					return;
+				}
+
+				// The current step is the number of edits in the undo history, with the initial state being step zero
+				let current_step = self.document_undo_history.len();
+				let redo_steps = self.document_redo_history.len();
+				if step < current_step {
+					for _ in step..current_step {
+						responses.add(DocumentMessage::Undo);
+					}
+				} else {
+					for _ in current_step..step.min(current_step + redo_steps) {
+						responses.add(DocumentMessage::Redo);
+					}
+				}
+			}
 			DocumentMessage::MoveSelectedLayersTo { parent, insert_index } => {
 				if !self.selection_network_path.is_empty() {
 					log::error!("Moving selected layers is only supported for the Document Network");
@@ -733,7 +1107,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					})
 					.collect::<Vec<_>>();
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Move Layers".into() });
 
 				for (layer_index, (layer_to_move, insert_offset)) in layers_to_move_with_insert_offset.into_iter().enumerate() {
 					responses.add(NodeGraphMessage::MoveLayerToStack {
@@ -784,7 +1158,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				resize,
 				resize_opposite_corner,
 			} => {
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Nudge".into() });
 
 				let resize = ipp.keyboard.key(resize);
 				let resize_opposite_corner = ipp.keyboard.key(resize_opposite_corner);
@@ -864,11 +1238,15 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			}
 			DocumentMessage::PasteImage {
 				name,
-				image,
+				mut image,
 				mouse,
 				parent_and_insert_index,
 			} => {
 				// All the image's pixels have been converted to 0..=1, linear, and premultiplied by `Color::from_rgba8_srgb`
+				// so they are brought from linear sRGB into the document's working color space
+				if let Err(error) = color_management::convert_working_space(&mut image.data, ColorSpace::Srgb, self.working_color_space) {
+					log::error!("{error}");
+				}
 
 				let image_size = DVec2::new(image.width as f64, image.height as f64);
 
@@ -887,7 +1265,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				let layer_node_id = NodeId::new();
 				let layer_id = LayerNodeIdentifier::new_unchecked(layer_node_id);
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Paste Image".into() });
 
 				let layer = graph_modification_utils::new_image_layer(Table::new_from_element(Raster::new_cpu(image)), layer_node_id, self.new_layer_parent(true), responses);
 
@@ -932,7 +1310,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				let layer_node_id = NodeId::new();
 				let layer_id = LayerNodeIdentifier::new_unchecked(layer_node_id);
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Paste SVG".into() });
 
 				let layer = graph_modification_utils::new_svg_layer(svg, center_in_viewport, layer_node_id, self.new_layer_parent(true), responses);
 
@@ -954,6 +1332,60 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![layer.to_node()] });
 				responses.add(ToolMessage::ActivateTool { tool_type: ToolType::Select });
 			}
+			DocumentMessage::PixelSelectionFromPath => {
+				let selected_layers = self.network_interface.selected_nodes().selected_layers(self.metadata()).collect::<Vec<_>>();
+				let outlines = selected_layers
+					.into_iter()
+					.filter_map(|layer| {
+						let vector = self.network_interface.compute_modified_vector(layer)?;
+						let transform = self.metadata().transform_to_document(layer);
+						Some(vector.stroke_bezpath_iter().flat_map(|bezpath| flatten_bezpath(&bezpath, transform)).collect::<Vec<_>>())
+					})
+					.flatten()
+					.collect::<Vec<_>>();
+				if outlines.is_empty() {
+					return;
+				}
+
+				self.start_transaction("Pixel Selection from Path".into(), preferences, responses);
+				self.pixel_selection = vec![SelectionStep {
+					shape: SelectionShape::Polygons { outlines },
+					combine: SelectionCombine::Replace,
+					anti_aliasing: true,
+				}];
+				responses.add(DocumentMessage::CommitTransaction);
+				responses.add(OverlaysMessage::Draw);
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			DocumentMessage::PixelSelectionOverlays { context: mut overlay_context } => {
+				if !self.graph_view_overlay_open {
+					pixel_selection_overlay(self, &mut overlay_context);
+				}
+			}
+			DocumentMessage::PixelSelectionToPath | DocumentMessage::SavePixelSelectionAsMask => {
+				if self.pixel_selection.is_empty() {
+					return;
+				}
+				let Some(layer) = self.selected_raster_layers().into_iter().next() else { return };
+				let Some(source) = self.network_interface.upstream_output_connector(&InputConnector::node(layer.to_node(), 1), &[]) else {
+					return;
+				};
+
+				let parent = layer.parent(self.metadata()).unwrap_or(LayerNodeIdentifier::ROOT_PARENT);
+				let insert_index = parent.children(self.metadata()).position(|child| child == layer).unwrap_or(0);
+				let id = NodeId::new();
+
+				responses.add(DocumentMessage::AddTransaction { label: "Selection to Path".into() });
+				responses.add(GraphOperationMessage::NewPixelSelectionLayer {
+					id,
+					source,
+					selection: self.pixel_selection_in_layer_space(layer),
+					as_path: matches!(message, DocumentMessage::PixelSelectionToPath),
+					parent,
+					insert_index,
+				});
+				responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![id] });
+			}
 			DocumentMessage::Redo => {
 				if self.network_interface.transaction_status() != TransactionStatus::Finished {
 					return;
@@ -963,6 +1395,22 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(ToolMessage::Redo);
 				responses.add(OverlaysMessage::Draw);
 			}
+			DocumentMessage::RenameHistorySnapshot { index, name } => {
+				if let Some(snapshot) = self.history_snapshots.get_mut(index) {
+					snapshot.name = name;
+				}
+				responses.add(DocumentMessage::UpdateHistoryPanel);
+			}
+			DocumentMessage::RenameExportSlice { id, name } => {
+				let Some(index) = self.export_slices.iter().position(|slice| slice.id == id && slice.name != name) else {
+					return;
+				};
+
+				self.start_transaction("Rename Slice".into(), preferences, responses);
+				self.export_slices[index].name = name;
+				responses.add(DocumentMessage::CommitTransaction);
+				responses.add(OverlaysMessage::Draw);
+			}
 			DocumentMessage::RenameDocument { new_name } => {
 				self.name = new_name.clone();
 
@@ -1042,6 +1490,54 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					multiplier: scrollbar_multiplier.into(),
 				});
 			}
+			DocumentMessage::ResetSymbolOverrides { layer } => {
+				let Some(instance) = self.network_interface.layer_symbol_instance(layer) else { return };
+				let Some(definition) = self.network_interface.symbol_definition(&instance) else { return };
+				let Some(definition_node) = self.network_interface.document_network().nodes.get(&definition) else {
+					return;
+				};
+
+				responses.add(DocumentMessage::AddTransaction {
+					label: "Reset Symbol Overrides".into(),
+				});
+				for (input_index, input) in definition_node.inputs.iter().enumerate() {
+					responses.add(NodeGraphMessage::SetInput {
+						input_connector: InputConnector::node(instance, input_index),
+						input: input.clone(),
+					});
+				}
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
+			DocumentMessage::RestoreHistorySnapshot { index } => {
+				if self.network_interface.transaction_status() != TransactionStatus::Finished {
+					return;
+				}
+				let Some(HistorySnapshot { name, snapshot, state }) = self.history_snapshots.get(index).cloned() else {
+					return;
+				};
+
+				// Restoring is recorded as a new step so it can be undone, rather than discarding the history between here and the snapshot
+				let current = self.network_interface.snapshot(self.document_undo_history.back().map(|step| &step.snapshot));
+				self.document_undo_history.push_back(HistoryStep {
+					label: format!("Restore {name}"),
+					snapshot: current,
+					state: self.document_state(),
+				});
+				self.document_redo_history.clear();
+				self.trim_history(preferences);
+
+				self.replace_network_with_snapshot(&snapshot, viewport);
+				self.replace_document_state(state);
+
+				responses.add(SelectToolMessage::Abort);
+				responses.add(PortfolioMessage::UpdateOpenDocumentsList);
+				responses.add(NodeGraphMessage::SelectedNodesUpdated);
+				responses.add(NodeGraphMessage::ForceRunDocumentGraph);
+				responses.add(NodeGraphMessage::UnloadWires);
+				responses.add(NodeGraphMessage::SendWires);
+				responses.add(OverlaysMessage::Draw);
+				responses.add(DocumentMessage::UpdateHistoryPanel);
+			}
 			DocumentMessage::SaveDocument | DocumentMessage::SaveDocumentAs => {
 				if let DocumentMessage::SaveDocumentAs = message {
 					self.path = None;
@@ -1120,6 +1616,31 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				let nodes = all_layers_except_artboards_invisible_and_locked.map(|layer| layer.to_node()).collect();
 				responses.add(NodeGraphMessage::SelectedNodesSet { nodes });
 			}
+			DocumentMessage::SelectLayersByQuery { query } => {
+				self.layer_search_query = query;
+
+				let query = match LayerQuery::parse(&self.layer_search_query) {
+					Ok(query) => query,
+					Err(description) => {
+						responses.add(DialogMessage::DisplayDialogError {
+							title: "Invalid layer search".to_string(),
+							description,
+						});
+						return;
+					}
+				};
+				if !query.is_empty() {
+					self.select_layers_matching_query(&query, responses);
+				}
+			}
+			DocumentMessage::SelectSimilarLayers => {
+				let Some(layer) = self.network_interface.selected_nodes().selected_layers(self.metadata()).next() else {
+					return;
+				};
+
+				let query = LayerQuery::similar_to(layer, &mut self.network_interface);
+				self.select_layers_matching_query(&query, responses);
+			}
 			DocumentMessage::SelectedLayersLower => {
 				responses.add(DocumentMessage::SelectedLayersReorder { relative_index_offset: 1 });
 			}
@@ -1141,7 +1662,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			DocumentMessage::ClipLayer { id } => {
 				let layer = LayerNodeIdentifier::new(id, &self.network_interface);
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Clip Layer".into() });
 				responses.add(GraphOperationMessage::ClipModeToggle { layer });
 			}
 			DocumentMessage::SelectLayer { id, ctrl, shift } => {
@@ -1225,7 +1746,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(FrontendMessage::UpdateGraphFadeArtwork { percentage });
 			}
 			DocumentMessage::SetNodePinned { node_id, pinned } => {
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Pin Node".into() });
 				responses.add(NodeGraphMessage::SetPinned { node_id, pinned });
 				responses.add(NodeGraphMessage::RunDocumentGraph);
 				responses.add(NodeGraphMessage::SelectedNodesUpdated);
@@ -1237,6 +1758,36 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					responses.add(GraphOperationMessage::OpacitySet { layer, opacity });
 				}
 			}
+			DocumentMessage::SetExportPresets { target, presets } => match target {
+				ExportTarget::Layer(layer) => {
+					responses.add(DocumentMessage::StartTransaction { label: "Set Export Presets".into() });
+					responses.add(NodeGraphMessage::SetExportPresets { node_id: layer.to_node(), presets });
+					responses.add(DocumentMessage::EndTransaction);
+				}
+				ExportTarget::Slice(id) => {
+					let Some(index) = self.export_slices.iter().position(|slice| slice.id == id) else { return };
+
+					self.start_transaction("Set Export Presets".into(), preferences, responses);
+					self.export_slices[index].presets = presets;
+					responses.add(DocumentMessage::CommitTransaction);
+				}
+			},
+			// Sent repeatedly while a slice is drawn or resized, so the tool doing so is responsible for the transaction
+			DocumentMessage::SetExportSliceBounds { id, bounds } => {
+				let bounds = [bounds[0].min(bounds[1]), bounds[0].max(bounds[1])];
+				if let Some(slice) = self.export_slices.iter_mut().find(|slice| slice.id == id) {
+					slice.bounds = bounds;
+				} else {
+					self.export_slices.push(ExportSlice {
+						id,
+						name: format!("Slice {}", self.export_slices.len() + 1),
+						bounds,
+						presets: vec![ExportPreset::default()],
+					});
+				}
+				self.network_interface.transaction_modified();
+				responses.add(OverlaysMessage::Draw);
+			}
 			DocumentMessage::SetFillForSelectedLayers { fill } => {
 				let fill = fill.clamp(0., 1.);
 				for layer in self.network_interface.selected_nodes().selected_layers_except_artboards(&self.network_interface) {
@@ -1284,8 +1835,13 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					*closure(&mut self.snapping_state) = snapping_state;
 				}
 			}
+			DocumentMessage::SliceOverlays { context: mut overlay_context } => {
+				if !self.graph_view_overlay_open {
+					slice_overlay(self, &mut overlay_context);
+				}
+			}
 			DocumentMessage::SetToNodeOrLayer { node_id, is_layer } => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Set to Node or Layer".into() });
 				responses.add(NodeGraphMessage::SetToNodeOrLayer { node_id, is_layer });
 				responses.add(DocumentMessage::EndTransaction);
 			}
@@ -1293,22 +1849,44 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				self.render_mode = render_mode;
 				responses.add_front(NodeGraphMessage::RunDocumentGraph);
 			}
-			DocumentMessage::AddTransaction => {
+			DocumentMessage::SetWorkingColorSpace { working_color_space } => {
+				if working_color_space == self.working_color_space {
+					return;
+				}
+
+				// The conversion is recorded as a single history step, so undoing it restores both the colors and the setting
+				responses.add(DocumentMessage::StartTransaction {
+					label: "Set Working Color Space".into(),
+				});
+				responses.add(DocumentMessage::ConvertToWorkingColorSpace { working_color_space });
+				responses.add(DocumentMessage::CommitTransaction);
+			}
+			DocumentMessage::ConvertToWorkingColorSpace { working_color_space } => {
+				// Colors and images stored in the graph hold linear values relative to the working space's primaries, so they're converted to keep their appearance
+				let mut conversions = Vec::new();
+				collect_working_space_conversions(
+					self.network_interface.document_network(),
+					&mut Vec::new(),
+					self.working_color_space,
+					working_color_space,
+					&mut conversions,
+				);
+				for (network_path, input_connector, input) in conversions {
+					self.network_interface.set_input(&input_connector, input, &network_path);
+				}
+
+				// Images loaded from files are decoded into the new working space when the graph runs again
+				self.working_color_space = working_color_space;
+				responses.add(NodeGraphMessage::ForceRunDocumentGraph);
+				responses.add(PortfolioMessage::UpdateDocumentWidgets);
+			}
+			DocumentMessage::AddTransaction { label } => {
 				// Reverse order since they are added to the front
 				responses.add_front(DocumentMessage::CommitTransaction);
-				responses.add_front(DocumentMessage::StartTransaction);
+				responses.add_front(DocumentMessage::StartTransaction { label });
 			}
 			// Note: A transaction should never be started in a scope that mutates the network interface, since it will only be run after that scope ends.
-			DocumentMessage::StartTransaction => {
-				self.network_interface.start_transaction();
-				let network_interface_clone = self.network_interface.clone();
-				self.document_undo_history.push_back(network_interface_clone);
-				if self.document_undo_history.len() > crate::consts::MAX_UNDO_HISTORY_LEN {
-					self.document_undo_history.pop_front();
-				}
-				// Push the UpdateOpenDocumentsList message to the bus in order to update the save status of the open documents
-				responses.add(PortfolioMessage::UpdateOpenDocumentsList);
-			}
+			DocumentMessage::StartTransaction { label } => self.start_transaction(label, preferences, responses),
 			// Commits the transaction if the network was mutated since the transaction started, otherwise it cancels the transaction
 			DocumentMessage::EndTransaction => match self.network_interface.transaction_status() {
 				TransactionStatus::Started => {
@@ -1327,9 +1905,14 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				if self.network_interface.transaction_status() == TransactionStatus::Finished {
 					return;
 				}
+				// Symbol instances follow the edits made to their definitions within the same undo step
+				if self.network_interface.sync_symbol_instances() {
+					responses.add(NodeGraphMessage::SendGraph);
+				}
 				self.network_interface.finish_transaction();
 				self.document_redo_history.clear();
 				responses.add(PortfolioMessage::UpdateOpenDocumentsList);
+				responses.add(DocumentMessage::UpdateHistoryPanel);
 			}
 			DocumentMessage::AbortTransaction => match self.network_interface.transaction_status() {
 				TransactionStatus::Started => {
@@ -1352,6 +1935,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				self.network_interface.finish_transaction();
 				responses.add(OverlaysMessage::Draw);
 				responses.add(PortfolioMessage::UpdateOpenDocumentsList);
+				responses.add(DocumentMessage::UpdateHistoryPanel);
 			}
 			DocumentMessage::ToggleLayerExpansion { id, recursive } => {
 				let layer = LayerNodeIdentifier::new(id, &self.network_interface);
@@ -1385,6 +1969,19 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(OverlaysMessage::Draw);
 				responses.add(PortfolioMessage::UpdateDocumentWidgets);
 			}
+			DocumentMessage::ToggleGuidesLocked => {
+				self.start_transaction(if self.guides.locked { "Unlock Guides" } else { "Lock Guides" }.into(), preferences, responses);
+				self.guides.locked = !self.guides.locked;
+				responses.add(DocumentMessage::CommitTransaction);
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			DocumentMessage::ToggleGuidesVisibility => {
+				self.start_transaction(if self.guides.visible { "Hide Guides" } else { "Show Guides" }.into(), preferences, responses);
+				self.guides.visible = !self.guides.visible;
+				responses.add(DocumentMessage::CommitTransaction);
+				responses.add(OverlaysMessage::Draw);
+				responses.add(MenuBarMessage::SendLayout);
+			}
 			DocumentMessage::ToggleOverlaysVisibility => {
 				self.overlays_visibility_settings.all = !self.overlays_visibility_settings.all();
 				responses.add(OverlaysMessage::Draw);
@@ -1394,6 +1991,11 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				self.snapping_state.snapping_enabled = !self.snapping_state.snapping_enabled;
 				responses.add(PortfolioMessage::UpdateDocumentWidgets);
 			}
+			DocumentMessage::UpdateHistoryPanel => {
+				if history_panel_open {
+					self.update_history_panel(responses);
+				}
+			}
 			DocumentMessage::UpdateUpstreamTransforms {
 				upstream_footprints,
 				local_transforms,
@@ -1435,7 +2037,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					log::error!("Ungrouping selected layers is only supported for the Document Network");
 					return;
 				}
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Ungroup".into() });
 
 				let folder_paths = self.network_interface.folders_sorted_by_most_nested(&self.selection_network_path);
 				for folder in folder_paths {
@@ -1494,6 +2096,10 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(NodeGraphMessage::SelectedNodesUpdated);
 				responses.add(NodeGraphMessage::SendGraph);
 			}
+			DocumentMessage::UpgradeLibraryNode { node_id } => {
+				responses.add(DocumentMessage::AddTransaction { label: "Upgrade Library Node".into() });
+				responses.add(NodeGraphMessage::UpgradeLibraryNode { node_id });
+			}
 			DocumentMessage::PTZUpdate => {
 				if !self.graph_view_overlay_open {
 					let transform = self.navigation_handler.calculate_offset_transform(viewport.center_in_viewport_space().into(), &self.document_ptz);
@@ -1616,6 +2222,8 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			SelectAllLayers,
 			SetSnapping,
 			ToggleGridVisibility,
+			ToggleGuidesLocked,
+			ToggleGuidesVisibility,
 			ToggleOverlaysVisibility,
 			ToggleSnapping,
 			Undo,
@@ -1631,6 +2239,23 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 		#[cfg(not(target_family = "wasm"))]
 		common.extend(actions!(DocumentMessageDiscriminant::SaveDocumentAs));
 
+		// Additional actions if a guide is being dragged
+		if self.guide_drag.is_some() {
+			common.extend(actions!(DocumentMessageDiscriminant;
+				GuideDragAbort,
+				GuideDragMove,
+				GuideDragStop,
+			));
+		}
+
+		// Additional actions if there is a pixel selection
+		if !self.pixel_selection.is_empty() {
+			common.extend(actions!(DocumentMessageDiscriminant;
+				DeselectPixels,
+				InvertPixelSelection,
+			));
+		}
+
 		// Additional actions if there are any selected layers
 		if self.network_interface.selected_nodes().selected_layers(self.metadata()).next().is_some() {
 			let mut select = actions!(DocumentMessageDiscriminant;
@@ -1641,6 +2266,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				SelectedLayersLowerToBack,
 				SelectedLayersRaise,
 				SelectedLayersRaiseToFront,
+				SelectSimilarLayers,
 				UngroupSelectedLayers,
 				ToggleSelectedLocked
 			);
@@ -1668,6 +2294,32 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 }
 
 impl DocumentMessageHandler {
+	/// Actions which depend on what's under the mouse, so they're only available while it's in the right place.
+	pub fn actions_with_input(&self, ipp: &InputPreprocessorMessageHandler) -> ActionList {
+		// Grabbing a guide takes priority over the active tool, which would otherwise use the click
+		if self.guide_drag.is_none() && self.guide_at(ipp.mouse.position).is_some() {
+			return actions!(DocumentMessageDiscriminant; DragGuide);
+		}
+		Vec::new()
+	}
+
+	/// Finds the guide closest to the given viewport position that is near enough to be grabbed, if the guides are shown and unlocked.
+	pub fn guide_at(&self, viewport_position: DVec2) -> Option<&Guide> {
+		if !self.guides.visible || self.guides.locked || self.graph_view_overlay_open {
+			return None;
+		}
+
+		let document_to_viewport = self.metadata().document_to_viewport;
+		let document_position = document_to_viewport.inverse().transform_point2(viewport_position);
+		self.guides
+			.guides
+			.iter()
+			.map(|guide| (guide, viewport_position.distance(document_to_viewport.transform_point2(guide.project(document_position)))))
+			.filter(|&(_, distance)| distance < SELECTION_TOLERANCE)
+			.min_by(|(_, a), (_, b)| a.total_cmp(b))
+			.map(|(guide, _)| guide)
+	}
+
 	/// Runs an intersection test with all layers and a viewport space quad
 	pub fn intersect_quad<'a>(&'a self, viewport_quad: graphene_std::renderer::Quad, viewport: &ViewportMessageHandler) -> impl Iterator<Item = LayerNodeIdentifier> + use<'a> {
 		let document_to_viewport = self.navigation_handler.calculate_offset_transform(viewport.center_in_viewport_space().into(), &self.document_ptz);
@@ -1954,55 +2606,73 @@ impl DocumentMessageHandler {
 		structure_section.as_slice().into()
 	}
 
-	pub fn undo_with_history(&mut self, viewport: &ViewportMessageHandler, responses: &mut VecDeque<Message>) {
-		let Some(previous_network) = self.undo(viewport, responses) else { return };
+	pub fn undo_with_history(&mut self, viewport: &ViewportMessageHandler, preferences: &PreferencesMessageHandler, responses: &mut VecDeque<Message>) {
+		let Some(previous_step) = self.undo(viewport, responses) else { return };
 
-		self.document_redo_history.push_back(previous_network);
-		if self.document_redo_history.len() > crate::consts::MAX_UNDO_HISTORY_LEN {
-			self.document_redo_history.pop_front();
-		}
+		self.document_redo_history.push_back(previous_step);
+		self.trim_history(preferences);
+		responses.add(DocumentMessage::UpdateHistoryPanel);
 	}
 
-	pub fn undo(&mut self, viewport: &ViewportMessageHandler, responses: &mut VecDeque<Message>) -> Option<NodeNetworkInterface> {
+	/// Restores the most recent snapshot in the undo history, returning a snapshot of the state that was replaced under the same label.
+	fn undo(&mut self, viewport: &ViewportMessageHandler, responses: &mut VecDeque<Message>) -> Option<HistoryStep> {
 		// If there is no history return and don't broadcast SelectionChanged
-		let mut network_interface = self.document_undo_history.pop_back()?;
-
-		// Set the previous network navigation metadata to the current navigation metadata
-		network_interface.copy_all_navigation_metadata(&self.network_interface);
-		std::mem::swap(&mut network_interface.resolved_types, &mut self.network_interface.resolved_types);
-
-		//Update the metadata transform based on document PTZ
-		let transform = self.navigation_handler.calculate_offset_transform(viewport.center_in_viewport_space().into(), &self.document_ptz);
-		network_interface.set_document_to_viewport_transform(transform);
-
-		// Ensure document structure is loaded so that updating the selected nodes has the correct metadata
-		network_interface.load_structure();
-
-		let previous_network = std::mem::replace(&mut self.network_interface, network_interface);
+		let step = self.document_undo_history.pop_back()?;
+		let previous_network = self.replace_network_with_snapshot(&step.snapshot, viewport);
+		let previous_state = self.replace_document_state(step.state);
 
 		// Push the UpdateOpenDocumentsList message to the bus in order to update the save status of the open documents
 		responses.add(PortfolioMessage::UpdateOpenDocumentsList);
 		responses.add(NodeGraphMessage::SelectedNodesUpdated);
 		responses.add(NodeGraphMessage::ForceRunDocumentGraph);
+		responses.add(OverlaysMessage::Draw);
+		responses.add(MenuBarMessage::SendLayout);
 
 		// TODO: Remove once the footprint is used to load the imports/export distances from the edge
 		responses.add(NodeGraphMessage::UnloadWires);
 
-		Some(previous_network)
+		Some(HistoryStep {
+			snapshot: previous_network.snapshot(Some(&step.snapshot)),
+			label: step.label,
+			state: previous_state,
+		})
 	}
-	pub fn redo_with_history(&mut self, viewport: &ViewportMessageHandler, responses: &mut VecDeque<Message>) {
+
+	pub fn redo_with_history(&mut self, viewport: &ViewportMessageHandler, preferences: &PreferencesMessageHandler, responses: &mut VecDeque<Message>) {
 		// Push the UpdateOpenDocumentsList message to the queue in order to update the save status of the open documents
-		let Some(previous_network) = self.redo(viewport, responses) else { return };
+		let Some(previous_step) = self.redo(viewport, responses) else { return };
 
-		self.document_undo_history.push_back(previous_network);
-		if self.document_undo_history.len() > crate::consts::MAX_UNDO_HISTORY_LEN {
-			self.document_undo_history.pop_front();
-		}
+		self.document_undo_history.push_back(previous_step);
+		self.trim_history(preferences);
+		responses.add(DocumentMessage::UpdateHistoryPanel);
 	}
 
-	pub fn redo(&mut self, viewport: &ViewportMessageHandler, responses: &mut VecDeque<Message>) -> Option<NodeNetworkInterface> {
+	/// Restores the most recent snapshot in the redo history, returning a snapshot of the state that was replaced under the same label.
+	fn redo(&mut self, viewport: &ViewportMessageHandler, responses: &mut VecDeque<Message>) -> Option<HistoryStep> {
 		// If there is no history return and don't broadcast SelectionChanged
-		let mut network_interface = self.document_redo_history.pop_back()?;
+		let step = self.document_redo_history.pop_back()?;
+		let previous_network = self.replace_network_with_snapshot(&step.snapshot, viewport);
+		let previous_state = self.replace_document_state(step.state);
+
+		// Push the UpdateOpenDocumentsList message to the bus in order to update the save status of the open documents
+		responses.add(PortfolioMessage::UpdateOpenDocumentsList);
+		responses.add(NodeGraphMessage::SelectedNodesUpdated);
+		responses.add(NodeGraphMessage::ForceRunDocumentGraph);
+		responses.add(NodeGraphMessage::UnloadWires);
+		responses.add(NodeGraphMessage::SendWires);
+		responses.add(OverlaysMessage::Draw);
+		responses.add(MenuBarMessage::SendLayout);
+
+		Some(HistoryStep {
+			snapshot: previous_network.snapshot(Some(&step.snapshot)),
+			label: step.label,
+			state: previous_state,
+		})
+	}
+
+	/// Swaps in a network interface rebuilt from the snapshot, keeping the current navigation state, and returns the network interface it replaced.
+	fn replace_network_with_snapshot(&mut self, snapshot: &NetworkSnapshot, viewport: &ViewportMessageHandler) -> NodeNetworkInterface {
+		let mut network_interface = NodeNetworkInterface::from_snapshot(snapshot);
 
 		// Set the previous network navigation metadata to the current navigation metadata
 		network_interface.copy_all_navigation_metadata(&self.network_interface);
@@ -2012,18 +2682,181 @@ impl DocumentMessageHandler {
 		let transform = self.navigation_handler.calculate_offset_transform(viewport.center_in_viewport_space().into(), &self.document_ptz);
 		network_interface.set_document_to_viewport_transform(transform);
 
-		let previous_network = std::mem::replace(&mut self.network_interface, network_interface);
+		// Ensure document structure is loaded so that updating the selected nodes has the correct metadata
+		network_interface.load_structure();
+
+		std::mem::replace(&mut self.network_interface, network_interface)
+	}
+
+	/// Begins a transaction by recording the current state of the document in the undo history.
+	///
+	/// Handlers which change the document right away call this directly, since a queued [`DocumentMessage::StartTransaction`] would only run after their changes.
+	fn start_transaction(&mut self, label: String, preferences: &PreferencesMessageHandler, responses: &mut VecDeque<Message>) {
+		// Edits made after the previous transaction was committed reach the symbol instances as part of that undo step
+		if self.network_interface.sync_symbol_instances() {
+			responses.add(NodeGraphMessage::SendGraph);
+		}
+		self.network_interface.start_transaction();
+		let snapshot = self.network_interface.snapshot(self.document_undo_history.back().map(|step| &step.snapshot));
+		self.document_undo_history.push_back(HistoryStep {
+			label,
+			snapshot,
+			state: self.document_state(),
+		});
+		self.trim_history(preferences);
 		// Push the UpdateOpenDocumentsList message to the bus in order to update the save status of the open documents
 		responses.add(PortfolioMessage::UpdateOpenDocumentsList);
-		responses.add(NodeGraphMessage::SelectedNodesUpdated);
-		responses.add(NodeGraphMessage::ForceRunDocumentGraph);
-		responses.add(NodeGraphMessage::UnloadWires);
-		responses.add(NodeGraphMessage::SendWires);
-		Some(previous_network)
+	}
+
+	fn document_state(&self) -> DocumentState {
+		DocumentState {
+			working_color_space: self.working_color_space,
+			guides: self.guides.clone(),
+			export_slices: self.export_slices.clone(),
+			pixel_selection: self.pixel_selection.clone(),
+		}
+	}
+
+	/// Restores the parts of the document stored outside of its network, returning the state it replaced.
+	fn replace_document_state(&mut self, state: DocumentState) -> DocumentState {
+		let previous_state = self.document_state();
+		let DocumentState {
+			working_color_space,
+			guides,
+			export_slices,
+			pixel_selection,
+		} = state;
+		self.working_color_space = working_color_space;
+		self.guides = guides;
+		self.export_slices = export_slices;
+		self.pixel_selection = pixel_selection;
+		previous_state
+	}
+
+	/// Discards the oldest undo steps, and then the furthest redo steps, until the history fits within the length and memory budget set in the preferences.
+	/// The most recent undo step is always kept so the last edit can be undone even if it alone exceeds the memory budget.
+	/// Named snapshots count toward the memory budget but are only removed by the user, so keeping many of them leaves less room for undo steps.
+	pub fn trim_history(&mut self, preferences: &PreferencesMessageHandler) {
+		let max_length = preferences.undo_history_length.max(1) as usize;
+		while self.document_undo_history.len() > max_length {
+			self.document_undo_history.pop_front();
+		}
+		while self.document_redo_history.len() > max_length {
+			self.document_redo_history.pop_front();
+		}
+
+		let budget = preferences.undo_history_memory_budget_bytes();
+		let steps_used: usize = self.document_undo_history.iter().chain(&self.document_redo_history).map(|step| step.snapshot.unique_bytes()).sum();
+		let mut used = steps_used + self.history_snapshots.iter().map(|snapshot| snapshot.snapshot.unique_bytes()).sum::<usize>();
+		while used > budget && self.document_undo_history.len() > 1 {
+			let Some(step) = self.document_undo_history.pop_front() else { break };
+			used -= step.snapshot.unique_bytes();
+		}
+		while used > budget {
+			let Some(step) = self.document_redo_history.pop_front() else { break };
+			used -= step.snapshot.unique_bytes();
+		}
+	}
+
+	/// Sends the History panel's layout, listing the named snapshots followed by every step in the undo and redo history.
+	fn update_history_panel(&self, responses: &mut VecDeque<Message>) {
+		let mut layout = Vec::new();
+
+		let new_snapshot = TextButton::new("New Snapshot")
+			.tooltip_description("Capture the current state of the document so it can be restored later, even after those steps leave the undo history.")
+			.on_update(|_| DocumentMessage::CreateHistorySnapshot.into())
+			.widget_instance();
+		layout.push(LayoutGroup::Row { widgets: vec![new_snapshot] });
+
+		if !self.history_snapshots.is_empty() {
+			let rows = self
+				.history_snapshots
+				.iter()
+				.enumerate()
+				.map(|(index, snapshot)| {
+					vec![
+						TextInput::new(&snapshot.name)
+							.tooltip_description("Name of the snapshot.")
+							.on_update(move |text_input| {
+								DocumentMessage::RenameHistorySnapshot {
+									index,
+									name: text_input.value.clone(),
+								}
+								.into()
+							})
+							.max_width(200)
+							.widget_instance(),
+						TextButton::new("Restore")
+							.tooltip_description("Return the document to this snapshot. This is added as a new step in the undo history so it can be undone.")
+							.on_update(move |_| DocumentMessage::RestoreHistorySnapshot { index }.into())
+							.widget_instance(),
+						IconButton::new("Trash", 24)
+							.tooltip_description("Delete this snapshot.")
+							.on_update(move |_| DocumentMessage::DeleteHistorySnapshot { index }.into())
+							.widget_instance(),
+					]
+				})
+				.collect();
+			layout.push(LayoutGroup::Table { rows, unstyled: true });
+		}
+
+		// A transaction in progress has already pushed its step onto the undo history, but it isn't listed until it's committed
+		let pending_steps = usize::from(self.network_interface.transaction_status() != TransactionStatus::Finished);
+		let current_step = self.document_undo_history.len().saturating_sub(pending_steps);
+		let labels = std::iter::once("Initial State")
+			.chain(self.document_undo_history.iter().take(current_step).map(|step| step.label.as_str()))
+			.chain(self.document_redo_history.iter().rev().map(|step| step.label.as_str()));
+		let rows = labels
+			.enumerate()
+			.map(|(step, label)| {
+				let (icon, tooltip) = match step.cmp(&current_step) {
+					std::cmp::Ordering::Less => ("HistoryUndo", "Undo back to this step."),
+					std::cmp::Ordering::Equal => ("Dot", "The current state of the document."),
+					std::cmp::Ordering::Greater => ("HistoryRedo", "Redo forward to this step."),
+				};
+				vec![
+					IconLabel::new(icon).widget_instance(),
+					TextButton::new(label)
+						.emphasized(step == current_step)
+						.flush(true)
+						.tooltip_description(tooltip)
+						.on_update(move |_| DocumentMessage::JumpToHistoryStep { step }.into())
+						.widget_instance(),
+				]
+			})
+			.collect();
+		layout.push(LayoutGroup::Table { rows, unstyled: true });
+
+		responses.add(LayoutMessage::SendLayout {
+			layout: Layout(layout),
+			layout_target: LayoutTarget::HistoryPanel,
+		});
 	}
 
 	pub fn current_hash(&self) -> u64 {
-		self.network_interface.document_network().current_hash()
+		let mut hasher = DefaultHasher::new();
+		self.network_interface.document_network().current_hash().hash(&mut hasher);
+		self.guides.hash(&mut hasher);
+		self.export_slices.hash(&mut hasher);
+		self.pixel_selection.hash(&mut hasher);
+
+		// Export presets are kept in the layers' metadata, which isn't part of the network's hash
+		let mut layer_presets = self
+			.network_interface
+			.document_network()
+			.nodes
+			.keys()
+			.map(|node_id| (*node_id, self.network_interface.export_presets(node_id, &[])))
+			.filter(|(_, presets)| !presets.is_empty())
+			.collect::<Vec<_>>();
+		layer_presets.sort_by_key(|(node_id, _)| *node_id);
+		layer_presets.hash(&mut hasher);
+
+		let mut node_library_versions = self.node_library_versions.iter().collect::<Vec<_>>();
+		node_library_versions.sort();
+		node_library_versions.hash(&mut hasher);
+
+		hasher.finish()
 	}
 
 	pub fn is_auto_saved(&self) -> bool {
@@ -2082,6 +2915,21 @@ impl DocumentMessageHandler {
 			.unwrap_or_else(|| self.network_interface.all_artboards().iter().next().copied().unwrap_or(LayerNodeIdentifier::ROOT_PARENT))
 	}
 
+	/// The pixel selection mapped into the space of the content feeding a layer, which is the space of the selection steps stored by its raster nodes.
+	pub fn pixel_selection_in_layer_space(&self, layer: LayerNodeIdentifier) -> Vec<SelectionStep> {
+		let document_to_layer = self.metadata().downstream_transform_to_document(layer).inverse();
+		self.pixel_selection.iter().map(|step| step.transformed(document_to_layer)).collect()
+	}
+
+	/// The selected layers whose content is raster data.
+	fn selected_raster_layers(&mut self) -> Vec<LayerNodeIdentifier> {
+		let selected_layers = self.network_interface.selected_nodes().selected_layers(self.metadata()).collect::<Vec<_>>();
+		selected_layers
+			.into_iter()
+			.filter(|&layer| NodeGraphLayer::is_raster_layer(layer, &mut self.network_interface))
+			.collect()
+	}
+
 	pub fn get_calculated_insert_index(metadata: &DocumentMetadata, selected_nodes: &SelectedNodes, parent: LayerNodeIdentifier) -> usize {
 		parent
 			.children(metadata)
@@ -2196,6 +3044,7 @@ impl DocumentMessageHandler {
 	pub fn update_document_widgets(&self, responses: &mut VecDeque<Message>, animation_is_playing: bool, time: Duration) {
 		let mut snapping_state = self.snapping_state.clone();
 		let mut snapping_state2 = self.snapping_state.clone();
+		let mut snapping_state3 = self.snapping_state.clone();
 
 		let mut widgets = vec![
 			IconButton::new("PlaybackToStart", 24)
@@ -2543,6 +3392,29 @@ impl DocumentMessageHandler {
 							]
 						},
 					}))
+					.chain([LayoutGroup::Row {
+						widgets: vec![TextLabel::new(SnappingOptions::Guides.to_string()).widget_instance()],
+					}])
+					.chain(SNAP_FUNCTIONS_FOR_GUIDES.into_iter().map(|(name, closure, description)| LayoutGroup::Row {
+						widgets: {
+							let checkbox_id = CheckboxId::new();
+							vec![
+								CheckboxInput::new(*closure(&mut snapping_state3))
+									.on_update(move |input: &CheckboxInput| {
+										DocumentMessage::SetSnapping {
+											closure: Some(closure),
+											snapping_state: input.checked,
+										}
+										.into()
+									})
+									.tooltip_label(name)
+									.tooltip_description(description)
+									.for_label(checkbox_id)
+									.widget_instance(),
+								TextLabel::new(name).tooltip_label(name).tooltip_description(description).for_checkbox(checkbox_id).widget_instance(),
+							]
+						},
+					}))
 					.collect(),
 				))
 				.widget_instance(),
@@ -2581,6 +3453,24 @@ impl DocumentMessageHandler {
 			.selected_index(Some(self.render_mode as u32))
 			.narrow(true)
 			.widget_instance(),
+			Separator::new(SeparatorStyle::Related).widget_instance(),
+			DropdownInput::new(vec![
+				ColorSpace::list()
+					.iter()
+					.flat_map(|group| group.iter())
+					.map(|(working_color_space, metadata)| {
+						let working_color_space = *working_color_space;
+						MenuListEntry::new(metadata.name.as_ref())
+							.label(metadata.label.as_ref())
+							.on_commit(move |_| DocumentMessage::SetWorkingColorSpace { working_color_space }.into())
+					})
+					.collect(),
+			])
+			.selected_index(Some(self.working_color_space as u32))
+			.tooltip_label("Working Color Space")
+			.tooltip_description("The color space that the document's raster data is stored and blended in.")
+			.narrow(true)
+			.widget_instance(),
 			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
 		];
 
@@ -2697,7 +3587,7 @@ impl DocumentMessageHandler {
 						MenuListEntry::new(format!("{blend_mode:?}"))
 							.label(blend_mode.to_string())
 							.on_update(move |_| DocumentMessage::SetBlendModeForSelectedLayers { blend_mode }.into())
-							.on_commit(|_| DocumentMessage::AddTransaction.into())
+							.on_commit(|_| DocumentMessage::AddTransaction { label: "Blend Mode".into() }.into())
 					})
 					.collect()
 			})
@@ -2741,7 +3631,7 @@ impl DocumentMessageHandler {
 						Message::NoOp
 					}
 				})
-				.on_commit(|_| DocumentMessage::AddTransaction.into())
+				.on_commit(|_| DocumentMessage::AddTransaction { label: "Opacity".into() }.into())
 				.max_width(100)
 				.tooltip_label("Opacity")
 				.widget_instance(),
@@ -2763,7 +3653,7 @@ impl DocumentMessageHandler {
 						Message::NoOp
 					}
 				})
-				.on_commit(|_| DocumentMessage::AddTransaction.into())
+				.on_commit(|_| DocumentMessage::AddTransaction { label: "Fill".into() }.into())
 				.max_width(100)
 				.tooltip_label("Fill")
 				.widget_instance(),
@@ -2771,6 +3661,18 @@ impl DocumentMessageHandler {
 		let layers_panel_control_bar_left = Layout(vec![LayoutGroup::Row { widgets }]);
 
 		let widgets = vec![
+			TextInput::new(&self.layer_search_query)
+				.placeholder(Some("Search".to_string()))
+				.tooltip_label("Find Layers")
+				.tooltip_description(
+					"Select the layers matching a search. Words match layer names, where * and ? are wildcards.\n\
+					\n\
+					Layers can also be filtered with type:group|text|raster|vector|artboard, fill:#RRGGBB, stroke:#RRGGBB, blend:multiply, visible:yes|no, locked:yes|no and node:\"Node Name\".",
+				)
+				.on_update(|text_input: &TextInput| DocumentMessage::SelectLayersByQuery { query: text_input.value.clone() }.into())
+				.max_width(160)
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Related).widget_instance(),
 			IconButton::new(if selection_all_locked { "PadlockLocked" } else { "PadlockUnlocked" }, 24)
 				.hover_icon(Some((if selection_all_locked { "PadlockUnlocked" } else { "PadlockLocked" }).into()))
 				.tooltip_label(if selection_all_locked { "Unlock Selected" } else { "Lock Selected" })
@@ -2798,6 +3700,19 @@ impl DocumentMessageHandler {
 		});
 	}
 
+	/// Replaces the layer selection with every layer in the document that matches the query.
+	fn select_layers_matching_query(&mut self, query: &LayerQuery, responses: &mut VecDeque<Message>) {
+		let layers = self.metadata().all_layers().collect::<Vec<_>>();
+		let nodes = layers
+			.into_iter()
+			.filter(|&layer| query.matches(layer, &mut self.network_interface))
+			.map(|layer| layer.to_node())
+			.collect();
+
+		responses.add(NodeGraphMessage::SelectedNodesSet { nodes });
+		responses.add(EventMessage::SelectionChanged);
+	}
+
 	pub fn update_layers_panel_bottom_bar_widgets(&mut self, layers_panel_open: bool, responses: &mut VecDeque<Message>) {
 		if !layers_panel_open {
 			return;
@@ -2809,8 +3724,9 @@ impl DocumentMessageHandler {
 		let has_selection = selected_layer.is_some();
 		let has_multiple_selection = selected_layers.next().is_some();
 		for _ in selected_layers {}
+		let symbol_instance_layer = selected_layer.filter(|&layer| !has_multiple_selection && self.network_interface.layer_symbol_instance(layer).is_some());
 
-		let widgets = vec![
+		let mut widgets = vec![
 			PopoverButton::new()
 				.icon(Some("Node".to_string()))
 				.menu_direction(Some(MenuDirection::Top))
@@ -2844,6 +3760,34 @@ impl DocumentMessageHandler {
 				})
 				.widget_instance(),
 			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			IconButton::new("Link", 24)
+				.tooltip_label("Create Symbol")
+				.tooltip_description("Turn the selected layers into a symbol which can be reused throughout the document.")
+				.on_update(|_| DocumentMessage::CreateSymbolFromSelectedLayers.into())
+				.disabled(!has_selection)
+				.widget_instance(),
+		];
+		if let Some(layer) = symbol_instance_layer {
+			widgets.extend([
+				IconButton::new("Edit", 24)
+					.tooltip_label("Edit Symbol")
+					.tooltip_description("Open the symbol's definition in the node graph. Changes to it apply to every instance.")
+					.on_update(move |_| DocumentMessage::EditSymbolDefinition { layer }.into())
+					.widget_instance(),
+				IconButton::new("Reset", 24)
+					.tooltip_label("Reset Overrides")
+					.tooltip_description("Restore the inputs of this symbol instance to the values of its definition.")
+					.on_update(move |_| DocumentMessage::ResetSymbolOverrides { layer }.into())
+					.widget_instance(),
+				IconButton::new("Remove", 24)
+					.tooltip_label("Detach Instance")
+					.tooltip_description("Turn this symbol instance into an independent copy which is no longer updated by the symbol's definition.")
+					.on_update(move |_| DocumentMessage::DetachSymbolInstance { layer }.into())
+					.widget_instance(),
+			]);
+		}
+		widgets.extend([
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
 			IconButton::new("Folder", 24)
 				.tooltip_label("Group Selected")
 				.tooltip_shortcut(action_shortcut!(DocumentMessageDiscriminant::GroupSelectedLayers))
@@ -2864,13 +3808,29 @@ impl DocumentMessageHandler {
 				.on_update(|_| DocumentMessage::DeleteSelectedLayers.into())
 				.disabled(!has_selection)
 				.widget_instance(),
-		];
+		]);
 		responses.add(LayoutMessage::SendLayout {
 			layout: Layout(vec![LayoutGroup::Row { widgets }]),
 			layout_target: LayoutTarget::LayersPanelBottomBar,
 		});
 	}
 
+	/// Every item of the document that can have export presets, along with its name and presets.
+	/// This is each artboard, followed by any other layers with presets, followed by the slices.
+	pub fn export_preset_targets(&self) -> Vec<(ExportTarget, String, &[ExportPreset])> {
+		let (artboards, layers): (Vec<_>, Vec<_>) = self.metadata().all_layers().partition(|layer| self.network_interface.is_artboard(&layer.to_node(), &[]));
+		let layers = artboards
+			.into_iter()
+			.chain(layers.into_iter().filter(|layer| !self.network_interface.export_presets(&layer.to_node(), &[]).is_empty()))
+			.map(|layer| {
+				let name = self.network_interface.display_name(&layer.to_node(), &[]);
+				(ExportTarget::Layer(layer), name, self.network_interface.export_presets(&layer.to_node(), &[]))
+			});
+		let slices = self.export_slices.iter().map(|slice| (ExportTarget::Slice(slice.id), slice.name.clone(), slice.presets.as_slice()));
+
+		layers.chain(slices).collect()
+	}
+
 	pub fn selected_layers_reverse(&mut self, responses: &mut VecDeque<Message>) {
 		let selected_layers = self.network_interface.selected_nodes();
 		let metadata = self.metadata();
@@ -2943,7 +3903,7 @@ impl DocumentMessageHandler {
 			}
 
 			if !modified {
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Reverse Layer Order".into() });
 			}
 
 			for (index, layer) in final_layers.iter().enumerate() {
@@ -3003,6 +3963,93 @@ impl DocumentMessageHandler {
 	}
 }
 
+/// Finds the inputs in the network and its nested networks holding colors or images, paired with their values converted from one working color space to another.
+fn collect_working_space_conversions(network: &NodeNetwork, network_path: &mut Vec<NodeId>, from: ColorSpace, to: ColorSpace, conversions: &mut Vec<(Vec<NodeId>, InputConnector, NodeInput)>) {
+	let convert_color = |color: &mut Color| {
+		let mut pixels = [*color];
+		if color_management::convert_working_space(&mut pixels, from, to).is_ok() {
+			*color = pixels[0];
+		}
+	};
+
+	for (&node_id, node) in &network.nodes {
+		if let DocumentNodeImplementation::Network(nested_network) = &node.implementation {
+			network_path.push(node_id);
+			collect_working_space_conversions(nested_network, network_path, from, to, conversions);
+			network_path.pop();
+		}
+
+		for (index, input) in node.inputs.iter().enumerate() {
+			let NodeInput::Value { tagged_value, exposed } = input else { continue };
+
+			let converted = match &**tagged_value {
+				TaggedValue::Raster(table) => {
+					let mut table = table.clone();
+					for row in table.iter_mut() {
+						if let Err(error) = color_management::convert_working_space(&mut row.element.data_mut().data, from, to) {
+							log::error!("{error}");
+						}
+					}
+					TaggedValue::Raster(table)
+				}
+				TaggedValue::Color(table) => {
+					let mut table = table.clone();
+					table.iter_mut().for_each(|row| convert_color(row.element));
+					TaggedValue::Color(table)
+				}
+				TaggedValue::ColorNotInTable(color) => {
+					let mut color = *color;
+					convert_color(&mut color);
+					TaggedValue::ColorNotInTable(color)
+				}
+				TaggedValue::Fill(Fill::Solid(color)) => {
+					let mut color = *color;
+					convert_color(&mut color);
+					TaggedValue::Fill(Fill::Solid(color))
+				}
+				TaggedValue::Fill(Fill::Gradient(gradient)) => {
+					let mut gradient = gradient.clone();
+					gradient.stops.0.iter_mut().for_each(|(_, color)| convert_color(color));
+					TaggedValue::Fill(Fill::Gradient(gradient))
+				}
+				TaggedValue::Gradient(gradient) => {
+					let mut gradient = gradient.clone();
+					gradient.stops.0.iter_mut().for_each(|(_, color)| convert_color(color));
+					TaggedValue::Gradient(gradient)
+				}
+				TaggedValue::GradientStops(stops) => {
+					let mut stops = stops.clone();
+					stops.0.iter_mut().for_each(|(_, color)| convert_color(color));
+					TaggedValue::GradientStops(stops)
+				}
+				TaggedValue::GradientTable(table) => {
+					let mut table = table.clone();
+					table.iter_mut().for_each(|row| row.element.0.iter_mut().for_each(|(_, color)| convert_color(color)));
+					TaggedValue::GradientTable(table)
+				}
+				TaggedValue::Stroke(stroke) => {
+					let mut stroke = stroke.clone();
+					stroke.color.iter_mut().for_each(convert_color);
+					TaggedValue::Stroke(stroke)
+				}
+				TaggedValue::BrushStrokes(strokes) => {
+					let mut strokes = strokes.clone();
+					strokes.iter_mut().for_each(|stroke| convert_color(&mut stroke.style.color));
+					TaggedValue::BrushStrokes(strokes)
+				}
+				TaggedValue::SelectionFills(fills) => {
+					let mut fills = fills.clone();
+					fills.iter_mut().for_each(|fill| convert_color(&mut fill.color));
+					TaggedValue::SelectionFills(fills)
+				}
+				_ => continue,
+			};
+
+			conversions.push((network_path.clone(), InputConnector::node(node_id, index), NodeInput::value(converted, *exposed)));
+		}
+	}
+}
+
 /// Create a network interface with a single export
 fn default_document_network_interface() -> NodeNetworkInterface {
 	let mut network_interface = NodeNetworkInterface::default();
@@ -3223,7 +4270,10 @@ impl Iterator for ClickXRayIter<'_> {
 #[cfg(test)]
 mod document_message_handler_tests {
 	use super::*;
+	use crate::messages::frontend::utility_types::FileType;
+	use crate::messages::portfolio::document::utility_types::network_interface::SymbolRole;
 	use crate::test_utils::test_prelude::*;
+	use graphene_std::vector::style::Stroke;
 
 	#[tokio::test]
 	async fn test_layer_selection_with_shift_and_ctrl() {
@@ -3493,4 +4543,240 @@ mod document_message_handler_tests {
 			Dist:   {distance} (should be < 1)"
 		);
 	}
+
+	#[tokio::test]
+	async fn test_guide_dragged_from_ruler_and_back() {
+		use crate::messages::input_mapper::utility_types::input_mouse::{EditorMouseState, ScrollDelta};
+
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.set_viewport_size(DVec2::ZERO, DVec2::new(1000., 1000.)).await;
+
+		// Drag a horizontal guide out of the top ruler, which lies above the viewport
+		editor.move_mouse(50., -8., ModifierKeys::empty(), MouseKeys::empty()).await;
+		editor.handle_message(DocumentMessage::DragGuideFromRuler { vertical: false }).await;
+		editor.move_mouse(50., 100., ModifierKeys::empty(), MouseKeys::empty()).await;
+		editor.handle_message(DocumentMessage::GuideDragStop).await;
+
+		let document = editor.active_document();
+		assert_eq!(document.guides.guides.len(), 1);
+		let guide = document.guides.guides[0];
+		assert!(guide.is_horizontal());
+		assert!((document.metadata().document_to_viewport.transform_point2(guide.origin).y - 100.).abs() < 1e-6);
+		assert_eq!(document.guide_at(DVec2::new(300., 102.)).map(|guide| guide.id), Some(guide.id));
+
+		// Moving a guide makes the document differ from its saved state
+		let document = editor.active_document_mut();
+		document.set_save_state(true);
+		let saved_hash = document.current_hash();
+		document.guides.guides[0].origin.y += 1.;
+		assert_ne!(document.current_hash(), saved_hash);
+		document.guides.guides[0].origin.y -= 1.;
+
+		// Dragging the guide back onto the ruler deletes it, whichever tool is active
+		editor.select_tool(ToolType::Rectangle).await;
+		editor.move_mouse(300., 102., ModifierKeys::empty(), MouseKeys::empty()).await;
+		editor.left_mousedown(300., 102., ModifierKeys::empty()).await;
+		editor.move_mouse(300., -8., ModifierKeys::empty(), MouseKeys::LEFT).await;
+		editor
+			.mouseup(
+				EditorMouseState {
+					editor_position: DVec2::new(300., -8.),
+					mouse_keys: MouseKeys::empty(),
+					scroll_delta: ScrollDelta::default(),
+				},
+				ModifierKeys::empty(),
+			)
+			.await;
+
+		assert!(editor.active_document().guides.guides.is_empty());
+		assert!(!editor.active_document().is_saved());
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().guides.guides, vec![guide]);
+		assert!(editor.active_document().is_saved());
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert!(editor.active_document().guides.guides.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_undo_history_respects_length_preference() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.handle_message(PreferencesMessage::UndoHistoryLength { length: 2 }).await;
+
+		for offset in [0., 50., 100., 150.] {
+			editor.drag_tool(ToolType::Rectangle, offset, offset, offset + 100., offset + 100., ModifierKeys::empty()).await;
+		}
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 4);
+		assert_eq!(editor.active_document().document_undo_history.len(), 2);
+
+		// Only the two most recent history steps can be undone, so the older rectangles remain
+		for _ in 0..4 {
+			editor.handle_message(DocumentMessage::Undo).await;
+		}
+		assert!(editor.active_document().document_undo_history.is_empty());
+		assert!(editor.active_document().metadata().all_layers().count() >= 2);
+
+		// Redoing restores the rectangles from the snapshots
+		for _ in 0..4 {
+			editor.handle_message(DocumentMessage::Redo).await;
+		}
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 4);
+		assert!(editor.active_document().document_redo_history.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_history_panel_jump_and_snapshot() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		editor.drag_tool(ToolType::Rectangle, 0., 0., 100., 100., ModifierKeys::empty()).await;
+		editor.handle_message(DocumentMessage::CreateHistorySnapshot).await;
+		editor.drag_tool(ToolType::Rectangle, 50., 50., 150., 150., ModifierKeys::empty()).await;
+		editor.drag_tool(ToolType::Rectangle, 100., 100., 200., 200., ModifierKeys::empty()).await;
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 3);
+		let steps = editor.active_document().document_undo_history.len();
+		assert!(editor.active_document().document_undo_history.iter().all(|step| step.label == "Shape Tool"));
+
+		// Jumping back to the initial state undoes every step, and jumping forward again redoes them
+		editor.handle_message(DocumentMessage::JumpToHistoryStep { step: 0 }).await;
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 0);
+		assert_eq!(editor.active_document().document_redo_history.len(), steps);
+		editor.handle_message(DocumentMessage::JumpToHistoryStep { step: steps }).await;
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 3);
+
+		// Restoring the snapshot is added as a new step which can itself be undone
+		editor.handle_message(DocumentMessage::RestoreHistorySnapshot { index: 0 }).await;
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 1);
+		assert_eq!(editor.active_document().document_undo_history.back().unwrap().label, "Restore Snapshot 1");
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().metadata().all_layers().count(), 3);
+	}
+
+	#[tokio::test]
+	async fn test_export_presets_on_layers_and_slices() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		editor.drag_tool(ToolType::Rectangle, 0., 0., 100., 100., ModifierKeys::empty()).await;
+		let layer = editor.get_selected_layer().await.unwrap();
+
+		// A new slice covers the selected layer
+		editor.handle_message(DocumentMessage::AddExportSlice).await;
+		let document = editor.active_document();
+		assert_eq!(document.export_slices.len(), 1);
+		assert_eq!(Some(document.export_slices[0].bounds), document.network_interface.selected_bounds_document_space(true, &[]));
+
+		// Presets saved with a layer are part of the undo history
+		let preset = ExportPreset {
+			file_type: FileType::Jpg,
+			scales: vec![1., 2.],
+			..Default::default()
+		};
+		editor
+			.handle_message(DocumentMessage::SetExportPresets {
+				target: ExportTarget::Layer(layer),
+				presets: vec![preset.clone()],
+			})
+			.await;
+		assert_eq!(editor.active_document().network_interface.export_presets(&layer.to_node(), &[]), [preset]);
+		let document = editor.active_document();
+		let targets = document.export_preset_targets().into_iter().map(|(target, _, _)| target).collect::<Vec<_>>();
+		assert_eq!(targets, [ExportTarget::Layer(layer), ExportTarget::Slice(document.export_slices[0].id)]);
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert!(editor.active_document().network_interface.export_presets(&layer.to_node(), &[]).is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_symbol_instances_follow_definition() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		editor.drag_tool(ToolType::Rectangle, 0., 0., 100., 100., ModifierKeys::empty()).await;
+		editor.handle_message(DocumentMessage::CreateSymbolFromSelectedLayers).await;
+		let layer = editor.get_selected_layer().await.unwrap();
+		let network_interface = &editor.active_document().network_interface;
+		let instance = network_interface.layer_symbol_instance(layer).expect("The layer's content should be replaced by a symbol instance");
+		let definition = network_interface.symbol_definition(&instance).unwrap();
+		assert_eq!(network_interface.symbol_role(&definition), Some(&SymbolRole::Definition));
+
+		// A duplicated layer is another instance of the same symbol
+		editor.handle_message(DocumentMessage::DuplicateSelectedLayers).await;
+		let duplicate = editor.get_selected_layer().await.unwrap();
+		let duplicate_instance = editor.active_document().network_interface.layer_symbol_instance(duplicate).unwrap();
+		assert_ne!(duplicate_instance, instance);
+		assert_eq!(editor.active_document().network_interface.symbol_definition(&duplicate_instance), Some(definition));
+
+		// An input added to the definition is exposed by every instance, and undoing it removes it from them again
+		editor.handle_message(DocumentMessage::EditSymbolDefinition { layer }).await;
+		editor.handle_message(DocumentMessage::StartTransaction { label: "Add Import".into() }).await;
+		editor.handle_message(NodeGraphMessage::AddImport).await;
+		editor.handle_message(DocumentMessage::EndTransaction).await;
+		let network_interface = &editor.active_document().network_interface;
+		let definition_node = network_interface.document_node(&definition, &[]).unwrap();
+		assert_eq!(definition_node.inputs.len(), 1);
+		let compiled = network_interface.executable_network();
+		for node_id in [instance, duplicate_instance] {
+			// Instances refer to the definition's network instead of storing a copy of it
+			let instance_node = network_interface.document_node(&node_id, &[]).unwrap();
+			assert_eq!(instance_node.implementation, DocumentNodeImplementation::Network(NodeNetwork::default()));
+			assert_eq!(instance_node.inputs, definition_node.inputs);
+			assert_eq!(network_interface.implementation(&node_id, &[]), Some(&definition_node.implementation));
+			assert_eq!(compiled.nodes[&node_id].implementation, definition_node.implementation);
+		}
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		let network_interface = &editor.active_document().network_interface;
+		assert!(network_interface.document_node(&definition, &[]).unwrap().inputs.is_empty());
+		assert!(network_interface.document_node(&duplicate_instance, &[]).unwrap().inputs.is_empty());
+
+		// Detaching gives the instance its own copy of the definition's network, which no longer follows the definition
+		editor.handle_message(DocumentMessage::DetachSymbolInstance { layer: duplicate }).await;
+		let network_interface = &editor.active_document().network_interface;
+		assert_eq!(network_interface.layer_symbol_instance(duplicate), None);
+		let definition_implementation = network_interface.document_node(&definition, &[]).unwrap().implementation.clone();
+		assert_eq!(network_interface.document_node(&duplicate_instance, &[]).unwrap().implementation, definition_implementation);
+	}
+
+	#[tokio::test]
+	async fn test_working_color_space_change_converts_colors_and_undoes() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.drag_tool(ToolType::Rectangle, 0., 0., 100., 100., ModifierKeys::empty()).await;
+
+		let layer = editor.active_document().metadata().all_layers().next().unwrap();
+		editor.handle_message(GraphOperationMessage::FillSet { layer, fill: Fill::Solid(Color::RED) }).await;
+		editor
+			.handle_message(GraphOperationMessage::StrokeSet {
+				layer,
+				stroke: Stroke::new(Some(Color::BLUE), 2.),
+			})
+			.await;
+		let fill_color = |editor: &EditorTestUtils| graph_modification_utils::get_fill_color(layer, &editor.active_document().network_interface).unwrap();
+		let stroke_color = |editor: &EditorTestUtils| graph_modification_utils::get_stroke_color(layer, &editor.active_document().network_interface).unwrap();
+		let original_color = fill_color(&editor);
+		let original_stroke_color = stroke_color(&editor);
+		let history_length = editor.active_document().document_undo_history.len();
+
+		editor
+			.handle_message(DocumentMessage::SetWorkingColorSpace {
+				working_color_space: ColorSpace::DisplayP3,
+			})
+			.await;
+		assert_eq!(editor.active_document().working_color_space, ColorSpace::DisplayP3);
+		assert_eq!(editor.active_document().document_undo_history.len(), history_length + 1);
+		assert_ne!(fill_color(&editor), original_color);
+		assert_ne!(stroke_color(&editor), original_stroke_color);
+
+		// Undoing restores both the setting and the colors that were converted
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().working_color_space, ColorSpace::Srgb);
+		assert_eq!(fill_color(&editor), original_color);
+		assert_eq!(stroke_color(&editor), original_stroke_color);
+
+		editor.handle_message(DocumentMessage::Redo).await;
+		assert_eq!(editor.active_document().working_color_space, ColorSpace::DisplayP3);
+	}
 }
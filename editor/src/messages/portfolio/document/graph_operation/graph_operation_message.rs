@@ -1,12 +1,15 @@
 use super::utility_types::TransformIn;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
-use crate::messages::portfolio::document::utility_types::network_interface::NodeTemplate;
+use crate::messages::portfolio::document::utility_types::network_interface::{NodeTemplate, OutputConnector};
 use crate::messages::prelude::*;
 use glam::{DAffine2, IVec2};
 use graph_craft::document::NodeId;
 use graphene_std::Artboard;
 use graphene_std::brush::brush_stroke::BrushStroke;
+use graphene_std::brush::clone_stamp::CloneStroke;
 use graphene_std::raster::BlendMode;
+use graphene_std::raster::healing::{HealStroke, PatchRegion};
+use graphene_std::raster::selection::{SelectionFill, SelectionStep};
 use graphene_std::raster_types::{CPU, Raster};
 use graphene_std::subpath::Subpath;
 use graphene_std::table::Table;
@@ -61,6 +64,29 @@ pub enum GraphOperationMessage {
 		layer: LayerNodeIdentifier,
 		strokes: Vec<BrushStroke>,
 	},
+	CloneStamp {
+		layer: LayerNodeIdentifier,
+		strokes: Vec<CloneStroke>,
+		sample_all_layers: bool,
+		layers_beneath_transform: DAffine2,
+	},
+	Heal {
+		layer: LayerNodeIdentifier,
+		strokes: Vec<HealStroke>,
+	},
+	Patch {
+		layer: LayerNodeIdentifier,
+		patches: Vec<PatchRegion>,
+	},
+	SelectionFill {
+		layer: LayerNodeIdentifier,
+		fills: Vec<SelectionFill>,
+	},
+	ConfineToSelection {
+		layer: LayerNodeIdentifier,
+		original: OutputConnector,
+		selection: Vec<SelectionStep>,
+	},
 	SetUpstreamToChain {
 		layer: LayerNodeIdentifier,
 	},
@@ -74,6 +100,14 @@ pub enum GraphOperationMessage {
 		parent: LayerNodeIdentifier,
 		insert_index: usize,
 	},
+	NewPixelSelectionLayer {
+		id: NodeId,
+		source: OutputConnector,
+		selection: Vec<SelectionStep>,
+		as_path: bool,
+		parent: LayerNodeIdentifier,
+		insert_index: usize,
+	},
 	NewBooleanOperationLayer {
 		id: NodeId,
 		operation: graphene_std::path_bool::BooleanOperation,
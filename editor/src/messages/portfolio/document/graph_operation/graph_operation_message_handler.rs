@@ -100,6 +100,36 @@ impl MessageHandler<GraphOperationMessage, GraphOperationMessageContext<'_>> for
 					modify_inputs.brush_modify(strokes);
 				}
 			}
+			GraphOperationMessage::CloneStamp {
+				layer,
+				strokes,
+				sample_all_layers,
+				layers_beneath_transform,
+			} => {
+				if let Some(mut modify_inputs) = ModifyInputsContext::new_with_layer(layer, network_interface, responses) {
+					modify_inputs.clone_stamp_modify(strokes, sample_all_layers, layers_beneath_transform);
+				}
+			}
+			GraphOperationMessage::Heal { layer, strokes } => {
+				if let Some(mut modify_inputs) = ModifyInputsContext::new_with_layer(layer, network_interface, responses) {
+					modify_inputs.heal_modify(strokes);
+				}
+			}
+			GraphOperationMessage::Patch { layer, patches } => {
+				if let Some(mut modify_inputs) = ModifyInputsContext::new_with_layer(layer, network_interface, responses) {
+					modify_inputs.patch_modify(patches);
+				}
+			}
+			GraphOperationMessage::SelectionFill { layer, fills } => {
+				if let Some(mut modify_inputs) = ModifyInputsContext::new_with_layer(layer, network_interface, responses) {
+					modify_inputs.selection_fill_modify(fills);
+				}
+			}
+			GraphOperationMessage::ConfineToSelection { layer, original, selection } => {
+				let mut modify_inputs = ModifyInputsContext::new(network_interface, responses);
+				modify_inputs.insert_confine_to_selection(original, selection, layer);
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
 			GraphOperationMessage::SetUpstreamToChain { layer } => {
 				let Some(OutputConnector::Node { node_id: first_chain_node, .. }) = network_interface.upstream_output_connector(&InputConnector::node(layer.to_node(), 1), &[]) else {
 					return;
@@ -171,6 +201,24 @@ impl MessageHandler<GraphOperationMessage, GraphOperationMessageContext<'_>> for
 				network_interface.move_layer_to_stack(layer, parent, insert_index, &[]);
 				responses.add(NodeGraphMessage::RunDocumentGraph);
 			}
+			GraphOperationMessage::NewPixelSelectionLayer {
+				id,
+				source,
+				selection,
+				as_path,
+				parent,
+				insert_index,
+			} => {
+				let mut modify_inputs = ModifyInputsContext::new(network_interface, responses);
+				let layer = modify_inputs.create_layer(id);
+				modify_inputs.insert_pixel_selection(source, selection, as_path, layer);
+				network_interface.move_layer_to_stack(layer, parent, insert_index, &[]);
+				responses.add(NodeGraphMessage::SetDisplayNameImpl {
+					node_id: id,
+					alias: if as_path { "Selection Path" } else { "Selection Mask" }.to_string(),
+				});
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
 			GraphOperationMessage::NewBooleanOperationLayer { id, operation, parent, insert_index } => {
 				let mut modify_inputs = ModifyInputsContext::new(network_interface, responses);
 				let layer = modify_inputs.create_layer(id);
@@ -234,7 +282,7 @@ impl MessageHandler<GraphOperationMessage, GraphOperationMessageContext<'_>> for
 					return;
 				}
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Remove Artboards".into() });
 				responses.add(NodeGraphMessage::DeleteNodes {
 					node_ids: network_interface.all_artboards().iter().map(|layer_node| layer_node.to_node()).collect(),
 					delete_children: false,
@@ -9,7 +9,10 @@ use graph_craft::document::{NodeId, NodeInput};
 use graph_craft::{ProtoNodeIdentifier, concrete};
 use graphene_std::Artboard;
 use graphene_std::brush::brush_stroke::BrushStroke;
+use graphene_std::brush::clone_stamp::CloneStroke;
 use graphene_std::raster::BlendMode;
+use graphene_std::raster::healing::{HealStroke, PatchRegion};
+use graphene_std::raster::selection::{SelectionFill, SelectionStep};
 use graphene_std::raster_types::{CPU, Raster};
 use graphene_std::subpath::Subpath;
 use graphene_std::table::Table;
@@ -142,6 +145,42 @@ impl<'a> ModifyInputsContext<'a> {
 		LayerNodeIdentifier::new(new_id, self.network_interface)
 	}
 
+	/// Inserts a Pixel Selection node which selects from the source, optionally followed by a Selection to Path node, as the content of the layer.
+	pub fn insert_pixel_selection(&mut self, source: OutputConnector, selection: Vec<SelectionStep>, as_path: bool, layer: LayerNodeIdentifier) {
+		let OutputConnector::Node { node_id: source_id, output_index } = source else { return };
+
+		let pixel_selection = resolve_proto_node_type(graphene_std::raster::selection::pixel_selection::IDENTIFIER)
+			.expect("Pixel Selection node does not exist")
+			.node_template_input_override([None, Some(NodeInput::value(TaggedValue::SelectionSteps(selection), false))]);
+		let pixel_selection_id = NodeId::new();
+		self.network_interface.insert_node(pixel_selection_id, pixel_selection, &[]);
+		self.network_interface.move_node_to_chain_start(&pixel_selection_id, layer, &[]);
+		self.network_interface
+			.set_input(&InputConnector::node(pixel_selection_id, 0), NodeInput::node(source_id, output_index), &[]);
+
+		if as_path {
+			let selection_to_path = resolve_proto_node_type(graphene_std::raster::selection::selection_to_path::IDENTIFIER)
+				.expect("Selection to Path node does not exist")
+				.default_node_template();
+			let selection_to_path_id = NodeId::new();
+			self.network_interface.insert_node(selection_to_path_id, selection_to_path, &[]);
+			self.network_interface.move_node_to_chain_start(&selection_to_path_id, layer, &[]);
+		}
+	}
+
+	/// Inserts a Confine to Selection node at the start of the layer's chain, which restores the original content outside of the selection.
+	pub fn insert_confine_to_selection(&mut self, original: OutputConnector, selection: Vec<SelectionStep>, layer: LayerNodeIdentifier) {
+		let OutputConnector::Node { node_id: original_id, output_index } = original else { return };
+
+		let confine = resolve_proto_node_type(graphene_std::raster::selection::confine_to_selection::IDENTIFIER)
+			.expect("Confine to Selection node does not exist")
+			.node_template_input_override([None, None, Some(NodeInput::value(TaggedValue::SelectionSteps(selection), false))]);
+		let confine_id = NodeId::new();
+		self.network_interface.insert_node(confine_id, confine, &[]);
+		self.network_interface.move_node_to_chain_start(&confine_id, layer, &[]);
+		self.network_interface.set_input(&InputConnector::node(confine_id, 1), NodeInput::node(original_id, output_index), &[]);
+	}
+
 	pub fn insert_boolean_data(&mut self, operation: graphene_std::path_bool::BooleanOperation, layer: LayerNodeIdentifier) {
 		let boolean = resolve_network_node_type("Boolean Operation").expect("Boolean node does not exist").node_template_input_override([
 			Some(NodeInput::value(TaggedValue::Graphic(Default::default()), true)),
@@ -508,6 +547,58 @@ impl<'a> ModifyInputsContext<'a> {
 		self.set_input_with_refresh(InputConnector::node(brush_node_id, 1), NodeInput::value(TaggedValue::BrushStrokes(strokes), false), false);
 	}
 
+	pub fn clone_stamp_modify(&mut self, strokes: Vec<CloneStroke>, sample_all_layers: bool, layers_beneath_transform: DAffine2) {
+		let Some(output_layer) = self.get_output_layer() else {
+			return;
+		};
+		let Some(clone_stamp_node_id) = self.existing_proto_node_id(graphene_std::brush::clone_stamp::clone_stamp::IDENTIFIER, true) else {
+			return;
+		};
+
+		let input_connector = InputConnector::node(clone_stamp_node_id, graphene_std::brush::clone_stamp::clone_stamp::StrokesInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::CloneStrokes(strokes), false), true);
+		let input_connector = InputConnector::node(clone_stamp_node_id, graphene_std::brush::clone_stamp::clone_stamp::SampleAllLayersInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::Bool(sample_all_layers), false), true);
+		let input_connector = InputConnector::node(clone_stamp_node_id, graphene_std::brush::clone_stamp::clone_stamp::LayersBeneathTransformInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::DAffine2(layers_beneath_transform), false), true);
+
+		// Feed the layers stacked beneath this layer into the node so it can sample their composite
+		let layers_beneath_input = InputConnector::node(clone_stamp_node_id, graphene_std::brush::clone_stamp::clone_stamp::LayersBeneathInput::INDEX);
+		let layers_beneath = self.network_interface.upstream_output_connector(&InputConnector::node(output_layer.to_node(), 0), &[]);
+		match layers_beneath {
+			Some(layers_beneath) if sample_all_layers => self.network_interface.create_wire(&layers_beneath, &layers_beneath_input, &[]),
+			_ => self
+				.network_interface
+				.set_input(&layers_beneath_input, NodeInput::value(TaggedValue::Graphic(Default::default()), true), &[]),
+		}
+
+		self.responses.add(NodeGraphMessage::RunDocumentGraph);
+	}
+
+	pub fn heal_modify(&mut self, strokes: Vec<HealStroke>) {
+		let Some(spot_healing_node_id) = self.existing_proto_node_id(graphene_std::raster::healing::spot_healing::IDENTIFIER, true) else {
+			return;
+		};
+		let input_connector = InputConnector::node(spot_healing_node_id, graphene_std::raster::healing::spot_healing::StrokesInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::HealStrokes(strokes), false), false);
+	}
+
+	pub fn patch_modify(&mut self, patches: Vec<PatchRegion>) {
+		let Some(patch_node_id) = self.existing_proto_node_id(graphene_std::raster::healing::patch::IDENTIFIER, true) else {
+			return;
+		};
+		let input_connector = InputConnector::node(patch_node_id, graphene_std::raster::healing::patch::PatchesInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::PatchRegions(patches), false), false);
+	}
+
+	pub fn selection_fill_modify(&mut self, fills: Vec<SelectionFill>) {
+		let Some(selection_fill_node_id) = self.existing_proto_node_id(graphene_std::raster::selection::selection_fill::IDENTIFIER, true) else {
+			return;
+		};
+		let input_connector = InputConnector::node(selection_fill_node_id, graphene_std::raster::selection::selection_fill::FillsInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::SelectionFills(fills), false), false);
+	}
+
 	pub fn resize_artboard(&mut self, location: IVec2, dimensions: IVec2) {
 		let Some(artboard_node_id) = self.existing_network_node_id("Artboard", true) else {
 			return;
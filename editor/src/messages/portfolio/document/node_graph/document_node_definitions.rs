@@ -1,4 +1,5 @@
 mod document_node_derive;
+pub mod node_library;
 
 use super::node_properties::choice::enum_choice;
 use super::node_properties::{self, ParameterWidgetsInfo};
@@ -19,6 +20,7 @@ use graph_craft::document::*;
 use graphene_std::brush::brush_cache::BrushCache;
 use graphene_std::extract_xy::XY;
 use graphene_std::raster::{CellularDistanceFunction, CellularReturnType, Color, DomainWarpType, FractalType, NoiseType, RedGreenBlueAlpha};
+use graphene_std::raster_types::color_management::ColorSpace;
 use graphene_std::raster_types::{CPU, Raster};
 use graphene_std::table::Table;
 #[allow(unused_imports)]
@@ -123,7 +125,7 @@ pub struct DocumentNodeDefinition {
 }
 
 // We use the once_cell to use the document node definitions throughout the editor without passing a reference
-// TODO: If dynamic node library is required, use a Mutex as well
+// The definitions of imported node libraries are kept separately in `node_library`, since they change while the editor runs
 static DOCUMENT_NODE_TYPES: once_cell::sync::Lazy<HashMap<DefinitionIdentifier, DocumentNodeDefinition>> = once_cell::sync::Lazy::new(document_node_definitions);
 
 /// Defines the "signature" or "header file"-like metadata for the document nodes, but not the implementation (which is defined in the node registry).
@@ -459,6 +461,81 @@ fn document_node_definitions() -> HashMap<DefinitionIdentifier, DocumentNodeDefi
 			description: Cow::Borrowed("Creates a new Artboard which can be used as a working surface."),
 			properties: None,
 		},
+		DocumentNodeDefinition {
+			identifier: "Repeat Zone",
+			category: "Math: Logic",
+			node_template: NodeTemplate {
+				document_node: DocumentNode {
+					implementation: DocumentNodeImplementation::Network(NodeNetwork {
+						exports: vec![NodeInput::node(NodeId(1), 0)],
+						nodes: [
+							// 0: Previous Iteration, where the body of the loop starts
+							DocumentNode {
+								implementation: DocumentNodeImplementation::ProtoNode(vector_nodes::previous_iteration::IDENTIFIER),
+								inputs: vec![NodeInput::import(generic!(T), 0)],
+								..Default::default()
+							},
+							// 1: Iterate, where the body of the loop ends
+							DocumentNode {
+								implementation: DocumentNodeImplementation::ProtoNode(vector_nodes::iterate::IDENTIFIER),
+								inputs: vec![NodeInput::import(generic!(T), 0), NodeInput::node(NodeId(0), 0), NodeInput::import(concrete!(u64), 1)],
+								..Default::default()
+							},
+						]
+						.into_iter()
+						.enumerate()
+						.map(|(id, node)| (NodeId(id as u64), node))
+						.collect(),
+						..Default::default()
+					}),
+					inputs: vec![NodeInput::value(TaggedValue::Graphic(Default::default()), true), NodeInput::value(TaggedValue::U64(1), false)],
+					..Default::default()
+				},
+				persistent_node_metadata: DocumentNodePersistentMetadata {
+					input_metadata: vec![
+						("Initial", "The content handed to the first iteration, which is also the result if *Count* is 0.").into(),
+						("Count", "The number of times the body of the loop is evaluated.").into(),
+					],
+					output_names: vec!["Out".to_string()],
+					node_type_metadata: NodeTypePersistentMetadata::node(IVec2::new(0, 0)),
+					network_metadata: Some(NodeNetworkMetadata {
+						persistent_metadata: NodeNetworkPersistentMetadata {
+							node_metadata: [
+								// 0: Previous Iteration
+								DocumentNodeMetadata {
+									persistent_metadata: DocumentNodePersistentMetadata {
+										node_type_metadata: NodeTypePersistentMetadata::node(IVec2::new(-14, 0)),
+										..Default::default()
+									},
+									..Default::default()
+								},
+								// 1: Iterate
+								DocumentNodeMetadata {
+									persistent_metadata: DocumentNodePersistentMetadata {
+										node_type_metadata: NodeTypePersistentMetadata::node(IVec2::new(0, -1)),
+										..Default::default()
+									},
+									..Default::default()
+								},
+							]
+							.into_iter()
+							.enumerate()
+							.map(|(id, node)| (NodeId(id as u64), node))
+							.collect(),
+							..Default::default()
+						},
+						..Default::default()
+					}),
+					..Default::default()
+				},
+			},
+			description: Cow::Borrowed(
+				"Repeatedly evaluates the body of the loop inside this node's subgraph, each time handing it the result of the previous iteration (starting with *Initial*), and produces the result of the final iteration.\n\
+				\n\
+				Open the subgraph and insert nodes between *Previous Iteration* and *Iterate* to build up the loop body. *Instance Index* reads the iteration number. This allows for content which builds upon itself, such as recursive subdivision, L-systems, growth patterns, or repeated offsetting.",
+			),
+			properties: None,
+		},
 		DocumentNodeDefinition {
 			identifier: "Blend Shapes",
 			category: "Vector",
@@ -1006,7 +1083,7 @@ fn document_node_definitions() -> HashMap<DefinitionIdentifier, DocumentNodeDefi
 								..Default::default()
 							},
 							DocumentNode {
-								inputs: vec![NodeInput::node(NodeId(0), 0)],
+								inputs: vec![NodeInput::node(NodeId(0), 0), NodeInput::value(TaggedValue::ColorSpace(ColorSpace::Srgb), false)],
 								implementation: DocumentNodeImplementation::ProtoNode(wasm_application_io::decode_image::IDENTIFIER),
 								..Default::default()
 							},
@@ -2595,12 +2672,19 @@ pub fn resolve_proto_node_type(identifier: ProtoNodeIdentifier) -> Option<&'stat
 }
 
 pub fn resolve_document_node_type(identifier: &DefinitionIdentifier) -> Option<&'static DocumentNodeDefinition> {
-	DOCUMENT_NODE_TYPES.get(identifier)
+	DOCUMENT_NODE_TYPES.get(identifier).or_else(|| node_library::resolve_library_node_type(identifier))
 }
 
-pub fn collect_node_types() -> Vec<FrontendNodeType> {
+/// The built-in definitions followed by the definitions of the imported node libraries.
+fn all_document_node_types() -> impl Iterator<Item = (DefinitionIdentifier, &'static DocumentNodeDefinition)> {
 	DOCUMENT_NODE_TYPES
 		.iter()
+		.map(|(identifier, definition)| (identifier.clone(), definition))
+		.chain(node_library::library_node_types())
+}
+
+pub fn collect_node_types() -> Vec<FrontendNodeType> {
+	all_document_node_types()
 		.filter(|(_, definition)| !definition.category.is_empty())
 		.map(|(identifier, definition)| {
 			let input_types = definition
@@ -2625,8 +2709,7 @@ pub fn collect_node_types() -> Vec<FrontendNodeType> {
 }
 
 pub fn collect_node_descriptions() -> Vec<(String, String)> {
-	DOCUMENT_NODE_TYPES
-		.iter()
+	all_document_node_types()
 		.map(|(identifier, definition)| {
 			(
 				identifier.serialized(),
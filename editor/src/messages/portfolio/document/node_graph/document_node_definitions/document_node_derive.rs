@@ -36,6 +36,8 @@ pub(super) fn post_process_nodes(custom: Vec<DocumentNodeDefinition>) -> HashMap
 			description,
 			properties,
 			context_features,
+			disk_cache: _,
+			lru_cache: _,
 		} = metadata;
 
 		let Some(implementations) = &node_registry.get(id) else { continue };
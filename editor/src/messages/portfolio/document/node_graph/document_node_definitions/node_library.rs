@@ -0,0 +1,245 @@
+use super::{DOCUMENT_NODE_TYPES, DefinitionIdentifier, DocumentNodeDefinition};
+use crate::messages::portfolio::document::utility_types::network_interface::{NodeLibraryReference, NodeTemplate};
+use graph_craft::document::DocumentNodeImplementation;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// The file extension of node library files.
+pub const NODE_LIBRARY_FILE_EXTENSION: &str = "graphite-nodes";
+
+/// The version of the node library file format, which is increased whenever older versions of Graphite can no longer read the files.
+const NODE_LIBRARY_FILE_VERSION: u32 = 1;
+
+/// The node definitions of each imported library, which are listed in the node catalog beside the built-in definitions.
+static LIBRARY_NODE_TYPES: Mutex<Vec<RegisteredNodeLibrary>> = Mutex::new(Vec::new());
+
+/// A set of subgraph nodes packaged so they can be added to the node catalog and used in any document.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NodeLibrary {
+	/// The name of the library, which identifies it when a newer version of it is imported.
+	pub name: String,
+	/// Increased each time the library is exported, so documents using an older version of its nodes can be offered an upgrade.
+	pub version: u32,
+	pub nodes: Vec<NodeLibraryNode>,
+}
+
+/// A subgraph node in a [`NodeLibrary`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NodeLibraryNode {
+	/// The name of the node in the node catalog, which can't be the same as any other node's.
+	pub identifier: String,
+	/// The category of the node catalog that lists the node. The library's name is used when empty.
+	#[serde(default)]
+	pub category: String,
+	#[serde(default)]
+	pub description: String,
+	/// The network of the node along with its exposed inputs, their default values, names, and descriptions.
+	pub node_template: NodeTemplate,
+}
+
+/// The contents of a node library file.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct NodeLibraryFile {
+	format_version: u32,
+	#[serde(flatten)]
+	library: NodeLibrary,
+}
+
+struct RegisteredNodeLibrary {
+	/// The library as it was imported, to tell whether importing it again changed it.
+	library: NodeLibrary,
+	definitions: HashMap<DefinitionIdentifier, &'static DocumentNodeDefinition>,
+}
+
+impl NodeLibrary {
+	pub fn file_name(&self) -> String {
+		format!("{}.{NODE_LIBRARY_FILE_EXTENSION}", self.name)
+	}
+
+	/// Writes the library in the format of a node library file.
+	pub fn to_file_contents(&self) -> String {
+		let file = NodeLibraryFile {
+			format_version: NODE_LIBRARY_FILE_VERSION,
+			library: self.clone(),
+		};
+		serde_json::to_string_pretty(&file).unwrap_or_default()
+	}
+
+	/// Reads a library from the contents of a node library file.
+	pub fn from_file_contents(contents: &str) -> Result<Self, String> {
+		let file: NodeLibraryFile = serde_json::from_str(contents).map_err(|error| format!("The file is not a valid node library: {error}"))?;
+		if file.format_version > NODE_LIBRARY_FILE_VERSION {
+			return Err(format!(
+				"The node library was made with a newer version of Graphite (node library format version {}).",
+				file.format_version
+			));
+		}
+
+		Ok(file.library)
+	}
+
+	/// Checks that the library's nodes are subgraphs whose names aren't used by built-in nodes or the nodes of the other imported libraries.
+	/// Libraries with the same name as this one are ignored, since importing this library replaces them.
+	pub fn validate(&self, imported: &[NodeLibrary]) -> Result<(), String> {
+		if self.name.trim().is_empty() {
+			return Err("The node library has no name.".to_string());
+		}
+
+		let other_identifiers = imported
+			.iter()
+			.filter(|library| library.name != self.name)
+			.flat_map(|library| library.nodes.iter().map(|node| node.identifier.as_str()))
+			.collect::<HashSet<_>>();
+		let mut identifiers = HashSet::new();
+		for node in &self.nodes {
+			let identifier = node.identifier.as_str();
+			if identifier.trim().is_empty() {
+				return Err("The node library contains a node without a name.".to_string());
+			}
+			if !matches!(node.node_template.document_node.implementation, DocumentNodeImplementation::Network(_)) || node.node_template.persistent_node_metadata.network_metadata.is_none() {
+				return Err(format!("The \"{identifier}\" node in the node library is not a subgraph."));
+			}
+			if !identifiers.insert(identifier) {
+				return Err(format!("The node library contains more than one node named \"{identifier}\"."));
+			}
+			if DOCUMENT_NODE_TYPES.contains_key(&DefinitionIdentifier::Network(identifier.to_string())) {
+				return Err(format!("The node library's \"{identifier}\" node has the same name as a built-in node."));
+			}
+			if other_identifiers.contains(identifier) {
+				return Err(format!("The node library's \"{identifier}\" node has the same name as a node from another imported library."));
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Creates the definition which adds a node of the library to the node catalog.
+	/// Nodes created from it record the library and its version, so they can be upgraded when a newer version of the library is imported.
+	fn definition(&self, node: &NodeLibraryNode) -> DocumentNodeDefinition {
+		let mut node_template = node.node_template.clone();
+		node_template.persistent_node_metadata.library = Some(NodeLibraryReference {
+			library: self.name.clone(),
+			node: node.identifier.clone(),
+			version: self.version,
+		});
+		if let Some(network_metadata) = &mut node_template.persistent_node_metadata.network_metadata {
+			network_metadata.persistent_metadata.reference = Some(node.identifier.clone());
+		}
+
+		let category = if node.category.is_empty() { self.name.clone() } else { node.category.clone() };
+
+		// Definitions are borrowed for the lifetime of the editor like the built-in ones, so each imported version of a library is leaked once
+		DocumentNodeDefinition {
+			identifier: Box::leak(node.identifier.clone().into_boxed_str()),
+			node_template,
+			category: Box::leak(category.into_boxed_str()),
+			description: Cow::Owned(node.description.clone()),
+			properties: None,
+		}
+	}
+}
+
+/// Makes the nodes of the given libraries the ones available in the node catalog besides the built-in nodes.
+/// Libraries which are already registered unchanged keep their existing definitions, while those imported again with any changes, even at the same version, have them replaced.
+pub fn set_node_libraries(libraries: &[NodeLibrary]) {
+	let mut registered = LIBRARY_NODE_TYPES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+	registered.retain(|registered| libraries.contains(&registered.library));
+
+	for library in libraries {
+		if registered.iter().any(|registered| registered.library == *library) {
+			continue;
+		}
+
+		let mut definitions = HashMap::with_capacity(library.nodes.len());
+		for node in &library.nodes {
+			let identifier = DefinitionIdentifier::Network(node.identifier.clone());
+			if DOCUMENT_NODE_TYPES.contains_key(&identifier) {
+				log::warn!("The \"{}\" node from the \"{}\" node library has the same name as a built-in node", node.identifier, library.name);
+				continue;
+			}
+			definitions.insert(identifier, &*Box::leak(Box::new(library.definition(node))));
+		}
+
+		registered.push(RegisteredNodeLibrary {
+			library: library.clone(),
+			definitions,
+		});
+	}
+}
+
+pub(super) fn resolve_library_node_type(identifier: &DefinitionIdentifier) -> Option<&'static DocumentNodeDefinition> {
+	let registered = LIBRARY_NODE_TYPES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+	registered.iter().find_map(|library| library.definitions.get(identifier).copied())
+}
+
+pub(super) fn library_node_types() -> Vec<(DefinitionIdentifier, &'static DocumentNodeDefinition)> {
+	let registered = LIBRARY_NODE_TYPES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+	registered
+		.iter()
+		.flat_map(|library| library.definitions.iter().map(|(identifier, definition)| (identifier.clone(), *definition)))
+		.collect()
+}
+
+/// The version of the imported library that is newer than the one a node was created from, if the library still contains that node.
+pub fn newer_library_version(reference: &NodeLibraryReference) -> Option<u32> {
+	let registered = LIBRARY_NODE_TYPES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+	registered
+		.iter()
+		.find(|registered| registered.library.name == reference.library)
+		.filter(|registered| registered.library.version > reference.version && registered.definitions.contains_key(&DefinitionIdentifier::Network(reference.node.clone())))
+		.map(|registered| registered.library.version)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::messages::portfolio::document::utility_types::network_interface::{DocumentNodePersistentMetadata, NodeNetworkMetadata};
+	use graph_craft::document::{DocumentNode, NodeNetwork};
+
+	fn library(name: &str, version: u32, identifiers: &[&str]) -> NodeLibrary {
+		let nodes = identifiers
+			.iter()
+			.map(|identifier| NodeLibraryNode {
+				identifier: identifier.to_string(),
+				category: String::new(),
+				description: String::new(),
+				node_template: NodeTemplate {
+					document_node: DocumentNode {
+						implementation: DocumentNodeImplementation::Network(NodeNetwork::default()),
+						..Default::default()
+					},
+					persistent_node_metadata: DocumentNodePersistentMetadata {
+						network_metadata: Some(NodeNetworkMetadata::default()),
+						..Default::default()
+					},
+				},
+			})
+			.collect();
+		NodeLibrary {
+			name: name.to_string(),
+			version,
+			nodes,
+		}
+	}
+
+	#[test]
+	fn file_contents_round_trip() {
+		let library = library("Shapes", 3, &["Library Star"]);
+		assert_eq!(NodeLibrary::from_file_contents(&library.to_file_contents()), Ok(library));
+
+		let newer_format = r#"{ "format_version": 1000, "name": "Shapes", "version": 1, "nodes": [] }"#;
+		assert!(NodeLibrary::from_file_contents(newer_format).is_err());
+	}
+
+	#[test]
+	fn validate_rejects_conflicting_names() {
+		assert!(library("Shapes", 1, &["Library Star", "Library Gear"]).validate(&[]).is_ok());
+		assert!(library("Shapes", 1, &["Library Star", "Library Star"]).validate(&[]).is_err());
+		assert!(library("Shapes", 1, &["Merge"]).validate(&[]).is_err());
+
+		let imported = [library("Gears", 1, &["Library Gear"]), library("Shapes", 1, &["Library Star"])];
+		assert!(library("Shapes", 2, &["Library Star"]).validate(&imported).is_ok());
+		assert!(library("Shapes", 2, &["Library Gear"]).validate(&imported).is_err());
+	}
+}
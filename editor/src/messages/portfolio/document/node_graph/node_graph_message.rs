@@ -1,4 +1,5 @@
 use super::utility_types::Direction;
+use crate::messages::frontend::utility_types::ExportPreset;
 use crate::messages::input_mapper::utility_types::input_keyboard::Key;
 use crate::messages::portfolio::document::node_graph::document_node_definitions::DefinitionIdentifier;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
@@ -9,6 +10,7 @@ use graph_craft::document::value::TaggedValue;
 use graph_craft::document::{NodeId, NodeInput};
 use graph_craft::proto::GraphErrors;
 use interpreted_executor::dynamic_executor::ResolvedDocumentNodeTypesDelta;
+use interpreted_executor::profiling::NodeProfileReport;
 
 #[impl_message(Message, DocumentMessage, NodeGraph)]
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -45,6 +47,9 @@ pub enum NodeGraphMessage {
 		output_connector: OutputConnector,
 		input_connector: InputConnector,
 	},
+	CreateSymbol {
+		layer: Option<LayerNodeIdentifier>,
+	},
 	ConnectUpstreamOutputToInput {
 		downstream_input: InputConnector,
 		input_connector: InputConnector,
@@ -61,6 +66,9 @@ pub enum NodeGraphMessage {
 		input_connector: InputConnector,
 	},
 	DisconnectRootNode,
+	DetachSymbolInstance {
+		node_id: NodeId,
+	},
 	EnterNestedNetwork,
 	DuplicateSelectedNodes,
 	ExposeInput {
@@ -164,6 +172,10 @@ pub enum NodeGraphMessage {
 		node_id: NodeId,
 		alias: String,
 	},
+	SetExportPresets {
+		node_id: NodeId,
+		presets: Vec<ExportPreset>,
+	},
 	SetToNodeOrLayer {
 		node_id: NodeId,
 		is_layer: bool,
@@ -224,13 +236,24 @@ pub enum NodeGraphMessage {
 	UpdateBoxSelection,
 	UpdateImportsExports,
 	UpdateLayerPanel,
+	/// Adds, removes, and reorders the inputs of a Math node to match the variables in its expression.
+	UpdateMathVariables {
+		node_id: NodeId,
+	},
 	UpdateNewNodeGraph,
+	/// The execution statistics of the graph's nodes from the latest run, or `None` once profiling is disabled.
+	UpdateNodeProfile {
+		report: Option<NodeProfileReport>,
+	},
 	UpdateTypes {
 		#[serde(skip)]
 		resolved_types: ResolvedDocumentNodeTypesDelta,
 		#[serde(skip)]
 		node_graph_errors: GraphErrors,
 	},
+	UpgradeLibraryNode {
+		node_id: NodeId,
+	},
 	UpdateActionButtons,
 	UpdateGraphBarRight,
 	UpdateInSelectedNetwork,
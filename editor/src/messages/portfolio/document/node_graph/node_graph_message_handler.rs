@@ -1,5 +1,5 @@
 use super::node_properties;
-use super::utility_types::{BoxSelection, ContextMenuInformation, DragStart, FrontendNode};
+use super::utility_types::{BoxSelection, ContextMenuInformation, DragStart, FrontendNode, FrontendNodeProfile};
 use crate::consts::GRID_SIZE;
 use crate::messages::clipboard::utility_types::ClipboardContent;
 use crate::messages::input_mapper::utility_types::macros::{action_shortcut, action_shortcut_manual};
@@ -24,11 +24,13 @@ use crate::messages::tool::common_functionality::utility_functions::make_path_ed
 use crate::messages::tool::tool_messages::tool_prelude::{Key, MouseMotion};
 use crate::messages::tool::utility_types::{HintData, HintGroup, HintInfo};
 use crate::messages::viewport::Position;
+use crate::node_graph_executor::{EXPLICIT_WORKING_SPACE, is_working_space_input};
 use glam::{DAffine2, DVec2, IVec2};
 use graph_craft::document::{DocumentNodeImplementation, NodeId, NodeInput};
 use graphene_std::math::math_ext::QuadExt;
 use graphene_std::vector::algorithms::bezpath_algorithms::bezpath_is_inside_bezpath;
 use graphene_std::*;
+use interpreted_executor::profiling::NodeProfileReport;
 use kurbo::{DEFAULT_ACCURACY, Shape};
 use renderer::Quad;
 use std::cmp::Ordering;
@@ -99,6 +101,8 @@ pub struct NodeGraphMessageHandler {
 	frontend_nodes: Vec<NodeId>,
 	/// Used to keep track of what wires are sent to the front end so the old ones can be removed
 	frontend_wires: HashSet<(NodeId, usize)>,
+	/// The execution statistics of the graph's nodes, which are only collected while node profiling is enabled.
+	node_profile: Option<NodeProfileReport>,
 }
 
 /// NodeGraphMessageHandler always modifies the network which the selected nodes are in. No GraphOperationMessages should be added here, since those messages will always affect the document network.
@@ -258,7 +262,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				modify_inputs.create_node(&node_type);
 			}
 			NodeGraphMessage::CreateNodeInLayerWithTransaction { node_type, layer } => {
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Create Node".into() });
 				responses.add(NodeGraphMessage::CreateNodeInLayerNoTransaction { node_type, layer });
 				responses.add(PropertiesPanelMessage::Refresh);
 				responses.add(NodeGraphMessage::RunDocumentGraph);
@@ -291,7 +295,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				self.context_menu = None;
 
 				if add_transaction {
-					responses.add(DocumentMessage::AddTransaction);
+					responses.add(DocumentMessage::AddTransaction { label: "Create Node".into() });
 				}
 
 				responses.add(NodeGraphMessage::InsertNode {
@@ -349,6 +353,24 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				responses.add(NodeGraphMessage::Copy);
 				responses.add(NodeGraphMessage::DeleteSelectedNodes { delete_children: true });
 			}
+			NodeGraphMessage::CreateSymbol { layer } => {
+				let Some(layer) = layer.or_else(|| network_interface.selected_nodes().selected_layers(network_interface.document_metadata()).next()) else {
+					return;
+				};
+
+				match network_interface.create_symbol(layer) {
+					Ok(_) => {
+						responses.add(NodeGraphMessage::RunDocumentGraph);
+						responses.add(NodeGraphMessage::SendGraph);
+					}
+					Err(description) => {
+						responses.add(DialogMessage::DisplayDialogError {
+							title: "Unable to create symbol".to_string(),
+							description,
+						});
+					}
+				}
+			}
 			NodeGraphMessage::DeleteNodes { node_ids, delete_children } => {
 				network_interface.delete_nodes(node_ids, delete_children, selection_network_path);
 			}
@@ -359,7 +381,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 					log::error!("Could not get selected nodes in DeleteSelectedNodes");
 					return;
 				};
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Delete Nodes".into() });
 				responses.add(NodeGraphMessage::DeleteNodes {
 					node_ids: selected_nodes.selected_nodes().cloned().collect::<Vec<_>>(),
 					delete_children,
@@ -368,6 +390,10 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				responses.add(NodeGraphMessage::SelectedNodesUpdated);
 				responses.add(NodeGraphMessage::SendGraph);
 			}
+			NodeGraphMessage::DetachSymbolInstance { node_id } => {
+				network_interface.detach_symbol_instance(&node_id);
+				responses.add(NodeGraphMessage::SendGraph);
+			}
 			NodeGraphMessage::DisconnectInput { input_connector } => {
 				network_interface.disconnect_input(&input_connector, selection_network_path);
 			}
@@ -383,7 +409,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				let nodes = network_interface.copy_nodes(&copy_ids, selection_network_path).collect::<Vec<_>>();
 
 				let new_ids = nodes.iter().map(|(id, _)| (*id, NodeId::new())).collect::<HashMap<_, _>>();
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Duplicate Nodes".into() });
 				responses.add(NodeGraphMessage::AddNodes { nodes, new_ids: new_ids.clone() });
 				responses.add(NodeGraphMessage::SelectedNodesSet {
 					nodes: new_ids.values().cloned().collect(),
@@ -436,13 +462,13 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 						start_transaction: false,
 					});
 					responses.add_front(NodeGraphMessage::DisconnectInput { input_connector });
-					responses.add_front(DocumentMessage::StartTransaction);
+					responses.add_front(DocumentMessage::StartTransaction { label: "Expose Input".into() });
 					return;
 				}
 
 				// Add a history step, but only do so if we didn't already start a transaction in the first run-through of this message in the above code
 				if start_transaction {
-					responses.add_front(DocumentMessage::StartTransaction);
+					responses.add_front(DocumentMessage::StartTransaction { label: "Expose Input".into() });
 				}
 
 				// If this node's input is a value type, we set its chosen exposed state
@@ -651,7 +677,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				};
 				let center_of_selected_nodes_grid_space = IVec2::new((center_of_selected_nodes.x / 24. + 0.5).floor() as i32, (center_of_selected_nodes.y / 24. + 0.5).floor() as i32);
 				default_node_template.persistent_node_metadata.node_type_metadata = NodeTypePersistentMetadata::node(center_of_selected_nodes_grid_space - IVec2::new(3, 1));
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Merge Nodes".into() });
 				responses.add(NodeGraphMessage::InsertNode {
 					node_id: encapsulating_node_id,
 					node_template: Box::new(default_node_template),
@@ -733,7 +759,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 					return;
 				}
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Paste Nodes".into() });
 
 				let new_ids: HashMap<_, _> = data.iter().map(|(id, _)| (*id, NodeId::new())).collect();
 				let nodes: Vec<_> = new_ids.values().copied().collect();
@@ -845,7 +871,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				};
 
 				if let Some(remove_import_index) = modify_import_export.remove_imports_exports.clicked_output_port_from_point(node_graph_point) {
-					responses.add(DocumentMessage::AddTransaction);
+					responses.add(DocumentMessage::AddTransaction { label: "Remove Import".into() });
 					if remove_import_index == 0 {
 						responses.add(NodeGraphMessage::ExposeEncapsulatingPrimaryInput { exposed: false })
 					} else {
@@ -853,7 +879,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 					}
 					return;
 				} else if let Some(remove_export_index) = modify_import_export.remove_imports_exports.clicked_input_port_from_point(node_graph_point) {
-					responses.add(DocumentMessage::AddTransaction);
+					responses.add(DocumentMessage::AddTransaction { label: "Remove Export".into() });
 					if remove_export_index == 0 {
 						responses.add(NodeGraphMessage::ExposePrimaryExport { exposed: false })
 					} else {
@@ -861,11 +887,11 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 					}
 					return;
 				} else if let Some(move_import_index) = modify_import_export.reorder_imports_exports.clicked_output_port_from_point(node_graph_point) {
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Reorder Imports".into() });
 					self.reordering_import = Some(move_import_index);
 					return;
 				} else if let Some(move_export_index) = modify_import_export.reorder_imports_exports.clicked_input_port_from_point(node_graph_point) {
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Reorder Exports".into() });
 					self.reordering_export = Some(move_export_index);
 					return;
 				}
@@ -901,7 +927,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 
 				// Begin moving an existing wire
 				if let Some(clicked_input) = &clicked_input {
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Move Wire".into() });
 					self.initial_disconnecting = true;
 					self.disconnecting = Some(*clicked_input);
 
@@ -919,7 +945,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 
 				// Begin creating a new wire
 				if let Some(clicked_output) = clicked_output {
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Create Wire".into() });
 					self.initial_disconnecting = false;
 
 					self.wire_in_progress_from_connector = network_interface.output_position(&clicked_output, selection_network_path);
@@ -995,7 +1021,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 						responses.add(NodeGraphMessage::SelectedNodesSet { nodes: updated_selected })
 					}
 					// Start the transaction after setting the node, since when the transactions ends it aborts any changes after this
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Move Nodes".into() });
 
 					return;
 				}
@@ -1471,7 +1497,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				responses.add(DocumentMessage::AbortTransaction);
 
 				// Add a history step to abort to the state before shaking if right clicked
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Shake Node".into() });
 
 				let Some(selected_nodes) = network_interface.selected_nodes_in_nested_network(selection_network_path) else {
 					log::error!("Could not get selected nodes in ShakeNode");
@@ -1682,6 +1708,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 					self.frontend_nodes = nodes.iter().map(|node| node.id).collect();
 					responses.add(FrontendMessage::UpdateNodeGraphNodes { nodes });
 					responses.add(NodeGraphMessage::UpdateVisibleNodes);
+					self.send_node_profiles(breadcrumb_network_path, responses);
 
 					let error = self.node_graph_error(network_interface, breadcrumb_network_path);
 					responses.add(FrontendMessage::UpdateNodeGraphErrorDiagnostic { error });
@@ -1698,6 +1725,14 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				}
 			}
 			NodeGraphMessage::SetInputValue { node_id, input_index, value } => {
+				// A working space chosen in the properties no longer follows the document's working color space
+				if network_interface
+					.implementation(&node_id, selection_network_path)
+					.is_some_and(|implementation| is_working_space_input(implementation, input_index))
+				{
+					network_interface.set_input_data(&node_id, input_index, EXPLICIT_WORKING_SPACE, serde_json::Value::Bool(true), selection_network_path);
+				}
+
 				let input = NodeInput::value(value, false);
 				responses.add(NodeGraphMessage::SetInput {
 					input_connector: InputConnector::node(node_id, input_index),
@@ -1770,7 +1805,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 					log::error!("Could not get selected nodes in NodeGraphMessage::ToggleSelectedAsLayersOrNodes");
 					return;
 				};
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Toggle Layer or Node".into() });
 				for node_id in selected_nodes.selected_nodes() {
 					responses.add(NodeGraphMessage::SetToNodeOrLayer {
 						node_id: *node_id,
@@ -1806,7 +1841,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				skip_adding_history_step,
 			} => {
 				if !skip_adding_history_step {
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Rename Node".into() });
 				}
 				responses.add(NodeGraphMessage::SetDisplayNameImpl { node_id, alias });
 				if !skip_adding_history_step {
@@ -1821,15 +1856,20 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 			NodeGraphMessage::SetDisplayNameImpl { node_id, alias } => {
 				network_interface.set_display_name(&node_id, alias, selection_network_path);
 			}
+			NodeGraphMessage::SetExportPresets { node_id, presets } => {
+				network_interface.set_export_presets(&node_id, presets, &[]);
+			}
 			NodeGraphMessage::SetImportExportName { name, index } => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction {
+					label: "Rename Import or Export".into(),
+				});
 				responses.add(NodeGraphMessage::SetImportExportNameImpl { name, index });
 				responses.add(DocumentMessage::EndTransaction);
 				responses.add(NodeGraphMessage::UpdateImportsExports);
 			}
 			NodeGraphMessage::SetImportExportNameImpl { name, index } => network_interface.set_import_export_name(name, index, breadcrumb_network_path),
 			NodeGraphMessage::TogglePreview { node_id } => {
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Toggle Preview".into() });
 				responses.add(NodeGraphMessage::TogglePreviewImpl { node_id });
 				responses.add(NodeGraphMessage::UpdateActionButtons);
 				responses.add(NodeGraphMessage::RunDocumentGraph);
@@ -1847,7 +1887,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				// If any of the selected layers are locked, show them all. Otherwise, hide them all.
 				let locked = !node_ids.iter().all(|node_id| network_interface.is_locked(node_id, selection_network_path));
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Toggle Lock".into() });
 
 				for node_id in &node_ids {
 					responses.add(NodeGraphMessage::SetLocked { node_id: *node_id, locked });
@@ -1863,7 +1903,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 
 				let locked = !node_metadata.persistent_metadata.locked;
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Toggle Lock".into() });
 				responses.add(NodeGraphMessage::SetLocked { node_id, locked });
 				responses.add(NodeGraphMessage::SetLockedOrVisibilitySideEffects { node_ids: vec![node_id] })
 			}
@@ -1880,7 +1920,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				// If any of the selected nodes are pinned, unpin them all. Otherwise, pin them all.
 				let pinned = !node_ids.iter().all(|node_id| network_interface.is_pinned(node_id, breadcrumb_network_path));
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Toggle Pinned".into() });
 				for node_id in &node_ids {
 					responses.add(NodeGraphMessage::SetPinned { node_id: *node_id, pinned });
 				}
@@ -1896,7 +1936,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				// If any of the selected nodes are hidden, show them all. Otherwise, hide them all.
 				let visible = !node_ids.iter().all(|node_id| network_interface.is_visible(node_id, selection_network_path));
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Toggle Visibility".into() });
 				for node_id in &node_ids {
 					responses.add(NodeGraphMessage::SetVisibility { node_id: *node_id, visible });
 				}
@@ -1905,7 +1945,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 			NodeGraphMessage::ToggleVisibility { node_id } => {
 				let visible = !network_interface.is_visible(&node_id, selection_network_path);
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Toggle Visibility".into() });
 				responses.add(NodeGraphMessage::SetVisibility { node_id, visible });
 				responses.add(NodeGraphMessage::SetLockedOrVisibilitySideEffects { node_ids: vec![node_id] });
 			}
@@ -2019,6 +2059,28 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 			NodeGraphMessage::UpdateEdges => {
 				// Update the import/export UI edges whenever the PTZ changes or the bounding box of all nodes changes
 			}
+			NodeGraphMessage::UpdateMathVariables { node_id } => {
+				use graph_craft::document::value::TaggedValue;
+				use graphene_std::math_nodes::math::{ExpressionInput, VariableNamesInput};
+
+				let expression_connector = InputConnector::node(node_id, ExpressionInput::INDEX);
+				let Some(TaggedValue::String(expression)) = network_interface.input_from_connector(&expression_connector, selection_network_path).and_then(|input| input.as_value()) else {
+					return;
+				};
+				// An invalid expression keeps the existing variable inputs until it's corrected, and the parsing error is shown in the Properties panel
+				let Ok(variables) = graphene_std::math_nodes::math_expression_variables(expression) else {
+					return;
+				};
+
+				// The variable inputs follow their names, which the node uses to bind each input's value to its variable
+				let first_variable_index = VariableNamesInput::INDEX + 1;
+				network_interface.set_named_inputs(&node_id, first_variable_index, &variables, TaggedValue::F64(0.), selection_network_path);
+				let names_connector = InputConnector::node(node_id, VariableNamesInput::INDEX);
+				network_interface.set_input(&names_connector, NodeInput::value(TaggedValue::VecString(variables), false), selection_network_path);
+				responses.add(NodeGraphMessage::SendGraph);
+				responses.add(PropertiesPanelMessage::Refresh);
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
 			NodeGraphMessage::UpdateNewNodeGraph => {
 				let Some(selected_nodes) = network_interface.selected_nodes_mut(selection_network_path) else {
 					log::error!("Could not get selected nodes in NodeGraphMessage::UpdateNewNodeGraph");
@@ -2029,9 +2091,27 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 
 				responses.add(NodeGraphMessage::SendGraph);
 			}
+			NodeGraphMessage::UpdateNodeProfile { report } => {
+				let profiling_toggled = self.node_profile.is_some() != report.is_some();
+				self.node_profile = report;
+
+				if graph_view_overlay_open {
+					self.send_node_profiles(breadcrumb_network_path, responses);
+				}
+				if profiling_toggled {
+					responses.add(NodeGraphMessage::UpdateGraphBarRight);
+				}
+			}
 			NodeGraphMessage::UpdateTypes { resolved_types, node_graph_errors } => {
 				network_interface.resolved_types.update(resolved_types, node_graph_errors);
 			}
+			NodeGraphMessage::UpgradeLibraryNode { node_id } => {
+				if network_interface.upgrade_library_node(&node_id, selection_network_path) {
+					responses.add(NodeGraphMessage::RunDocumentGraph);
+					responses.add(NodeGraphMessage::SendGraph);
+					responses.add(PropertiesPanelMessage::Refresh);
+				}
+			}
 			NodeGraphMessage::UpdateActionButtons => {
 				if selection_network_path == breadcrumb_network_path {
 					self.update_graph_bar_left(network_interface, breadcrumb_network_path, responses);
@@ -2108,6 +2188,33 @@ impl NodeGraphMessageHandler {
 		});
 	}
 
+	/// Sends the profiled execution statistics of the nodes shown in the graph, which are cleared if profiling is disabled.
+	fn send_node_profiles(&self, breadcrumb_network_path: &[NodeId], responses: &mut VecDeque<Message>) {
+		let profiles = self
+			.node_profile
+			.iter()
+			.flat_map(|report| {
+				self.frontend_nodes
+					.iter()
+					.filter_map(|&node_id| report.document_node(&[breadcrumb_network_path, &[node_id]].concat()).map(|profile| (node_id, profile)))
+			})
+			.collect::<Vec<_>>();
+		let slowest = profiles.iter().map(|(_, profile)| profile.self_milliseconds).fold(0., f64::max);
+
+		let profiles = profiles
+			.into_iter()
+			.map(|(id, profile)| FrontendNodeProfile {
+				id,
+				calls: profile.calls,
+				cumulative_milliseconds: profile.cumulative_milliseconds,
+				self_milliseconds: profile.self_milliseconds,
+				cache_hit_rate: profile.cache_hit_rate(),
+				heat: if slowest > 0. { profile.self_milliseconds / slowest } else { 0. },
+			})
+			.collect();
+		responses.add(FrontendMessage::UpdateNodeProfiles { profiles });
+	}
+
 	/// Updates the buttons for visibility, locked, and preview
 	fn update_graph_bar_left(&mut self, network_interface: &mut NodeNetworkInterface, breadcrumb_network_path: &[NodeId], responses: &mut VecDeque<Message>) {
 		let Some(subgraph_path_names) = Self::collect_subgraph_names(network_interface, breadcrumb_network_path) else {
@@ -2295,7 +2402,15 @@ impl NodeGraphMessageHandler {
 			return;
 		};
 
+		let profiling = self.node_profile.is_some();
 		let mut widgets = vec![
+			TextButton::new("Profile")
+				.emphasized(profiling)
+				.tooltip_label(if profiling { "Stop Profiling Nodes" } else { "Profile Nodes" })
+				.tooltip_description("Measure how long each node takes to run and how often its cache is hit. The results are shown above the nodes, tinted red for the slowest ones.")
+				.on_update(move |_| PortfolioMessage::SetNodeProfiling { enabled: !profiling }.into())
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
 			NumberInput::new(Some(graph_fade_artwork_percentage))
 				.percentage()
 				.display_decimal_places(0)
@@ -2739,6 +2854,7 @@ impl NodeGraphMessageHandler {
 					descendant_of_selected: descendants_of_selected.contains(&node_id),
 					clipped: get_clip_mode(layer, network_interface).unwrap_or(false) && clippable,
 					clippable,
+					symbol_instance: network_interface.layer_symbol_instance(layer).is_some(),
 				};
 				responses.add(FrontendMessage::UpdateDocumentLayerDetails { data });
 			}
@@ -2817,6 +2933,7 @@ impl Default for NodeGraphMessageHandler {
 			end_index: None,
 			frontend_nodes: Vec::new(),
 			frontend_wires: HashSet::new(),
+			node_profile: None,
 		}
 	}
 }
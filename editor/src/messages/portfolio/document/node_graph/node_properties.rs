@@ -18,15 +18,19 @@ use graphene_std::animation::RealTimeMode;
 use graphene_std::extract_xy::XY;
 use graphene_std::path_bool::BooleanOperation;
 use graphene_std::raster::curve::Curve;
+use graphene_std::raster::filter::BokehShape;
+use graphene_std::raster::image_statistics::HistogramChannel;
+use graphene_std::raster::morphology::{MaskChannel, MorphologyOperation, MorphologyShape};
 use graphene_std::raster::{
 	BlendMode, CellularDistanceFunction, CellularReturnType, Color, DomainWarpType, FractalType, LuminanceCalculation, NoiseType, RedGreenBlue, RedGreenBlueAlpha, RelativeAbsolute,
 	SelectiveColorChoice,
 };
+use graphene_std::raster_types::color_management::{ColorSpace, RenderingIntent};
 use graphene_std::table::{Table, TableRow};
 use graphene_std::text::{Font, TextAlign};
 use graphene_std::transform::{Footprint, ReferencePoint, Transform};
 use graphene_std::vector::misc::{ArcType, CentroidType, ExtrudeJoiningAlgorithm, GridType, MergeByDistanceAlgorithm, PointSpacingType, SpiralType};
-use graphene_std::vector::style::{Fill, FillChoice, FillType, GradientStops, GradientType, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin};
+use graphene_std::vector::style::{ColorComponent, ColorModel, Fill, FillChoice, FillType, GradientStops, GradientType, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin};
 
 pub(crate) fn string_properties(text: &str) -> Vec<LayoutGroup> {
 	let widget = TextLabel::new(text).widget_instance();
@@ -45,7 +49,7 @@ pub fn update_value<T>(value: impl Fn(&T) -> TaggedValue + 'static + Send + Sync
 }
 
 pub fn commit_value<T>(_: &T) -> Message {
-	DocumentMessage::AddTransaction.into()
+	DocumentMessage::AddTransaction { label: "Change Property".into() }.into()
 }
 
 pub fn expose_widget(node_id: NodeId, index: usize, data_type: FrontendGraphDataType, exposed: bool) -> WidgetInstance {
@@ -203,6 +207,8 @@ pub(crate) fn property_from_type(
 						// =========================
 						Some(x) if x == TypeId::of::<FillType>() => enum_choice::<FillType>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<GradientType>() => enum_choice::<GradientType>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<ColorModel>() => enum_choice::<ColorModel>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<ColorComponent>() => enum_choice::<ColorComponent>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<RealTimeMode>() => enum_choice::<RealTimeMode>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<RedGreenBlue>() => enum_choice::<RedGreenBlue>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<RedGreenBlueAlpha>() => enum_choice::<RedGreenBlueAlpha>().for_socket(default_info).property_row(),
@@ -226,6 +232,13 @@ pub(crate) fn property_from_type(
 						Some(x) if x == TypeId::of::<BooleanOperation>() => enum_choice::<BooleanOperation>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<CentroidType>() => enum_choice::<CentroidType>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<LuminanceCalculation>() => enum_choice::<LuminanceCalculation>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<BokehShape>() => enum_choice::<BokehShape>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<MorphologyOperation>() => enum_choice::<MorphologyOperation>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<MorphologyShape>() => enum_choice::<MorphologyShape>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<MaskChannel>() => enum_choice::<MaskChannel>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<HistogramChannel>() => enum_choice::<HistogramChannel>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<ColorSpace>() => enum_choice::<ColorSpace>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<RenderingIntent>() => enum_choice::<RenderingIntent>().for_socket(default_info).property_row(),
 						// =====
 						// OTHER
 						// =====
@@ -1786,6 +1799,9 @@ pub(crate) fn generate_node_properties(node_id: NodeId, context: &mut NodeProper
 	if layout.is_empty() {
 		layout = node_no_properties(node_id, context);
 	}
+	if let Some(upgrade) = library_upgrade_row(node_id, context) {
+		layout.insert(0, upgrade);
+	}
 	let name = context.network_interface.implementation_name(&node_id, context.selection_network_path);
 
 	let description = context
@@ -1810,6 +1826,28 @@ pub(crate) fn generate_node_properties(node_id: NodeId, context: &mut NodeProper
 	}
 }
 
+/// Offers to upgrade a node created from a node library when a newer version of the library has been imported.
+fn library_upgrade_row(node_id: NodeId, context: &NodePropertiesContext) -> Option<LayoutGroup> {
+	let network_path = context.selection_network_path;
+	let version = context.network_interface.library_upgrade_version(&node_id, network_path)?;
+	let reference = context.network_interface.library_reference(&node_id, network_path)?;
+
+	let description = format!(
+		"Version {version} of the \"{}\" node library replaces the version {} network of this node. Inputs with the same names keep their values.",
+		reference.library, reference.version
+	);
+	Some(LayoutGroup::Row {
+		widgets: vec![
+			TextLabel::new(format!("Library version {version} is available")).italic(true).widget_instance(),
+			Separator::new(SeparatorStyle::Unrelated).widget_instance(),
+			TextButton::new("Upgrade")
+				.tooltip_description(description)
+				.on_update(move |_| DocumentMessage::UpgradeLibraryNode { node_id }.into())
+				.widget_instance(),
+		],
+	})
+}
+
 /// Fill Node Widgets LayoutGroup
 pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
 	use graphene_std::vector::fill::*;
@@ -2123,7 +2161,12 @@ pub fn math_properties(node_id: NodeId, context: &mut NodePropertiesContext) ->
 						node_id,
 						ExpressionInput::INDEX,
 					))
-					.on_commit(commit_value)
+					.on_commit(move |_| Message::Batched {
+						messages: Box::new([
+							NodeGraphMessage::UpdateMathVariables { node_id }.into(),
+							DocumentMessage::AddTransaction { label: "Change Expression".into() }.into(),
+						]),
+					})
 					.widget_instance(),
 			])
 		}
@@ -2132,11 +2175,90 @@ pub fn math_properties(node_id: NodeId, context: &mut NodePropertiesContext) ->
 	let operand_b = number_widget(ParameterWidgetsInfo::new(node_id, OperandBInput::<f64>::INDEX, true, context), NumberInput::default());
 	let operand_a_hint = vec![TextLabel::new("(Operand A is the primary input)").widget_instance()];
 
-	vec![
-		LayoutGroup::Row { widgets: expression }.with_tooltip_description(r#"A math expression that may incorporate "A" and/or "B", such as "sqrt(A + B) - B^2"."#),
-		LayoutGroup::Row { widgets: operand_b }.with_tooltip_description(r#"The value of "B" when calculating the expression."#),
-		LayoutGroup::Row { widgets: operand_a_hint }.with_tooltip_description(r#""A" is fed by the value from the previous node in the primary data flow, or it is 0 if disconnected."#),
-	]
+	let mut layout = vec![
+		LayoutGroup::Row { widgets: expression }
+			.with_tooltip_description(r#"A math expression that may incorporate "A", "B", and other variables, such as "sqrt(A + B) - B^2 * scale". Each other variable is given its own input."#),
+	];
+
+	// Show why the expression can't be calculated, since the node's output is then 0
+	if let Some(error) = math_calculation_error(node_id, context) {
+		let error_label = TextLabel::new(error.lines().next().unwrap_or_default()).widget_instance();
+		layout.push(LayoutGroup::Row { widgets: vec![error_label] }.with_tooltip_description(error));
+	}
+
+	layout.push(LayoutGroup::Row { widgets: operand_b }.with_tooltip_description(r#"The value of "B" when calculating the expression."#));
+
+	let first_variable_index = VariableNamesInput::INDEX + 1;
+	let variable_count = get_document_node(node_id, context).map_or(0, |document_node| document_node.inputs.len().saturating_sub(first_variable_index));
+	for index in first_variable_index..first_variable_index + variable_count {
+		let variable = number_widget(ParameterWidgetsInfo::new(node_id, index, true, context), NumberInput::default());
+		layout.push(LayoutGroup::Row { widgets: variable }.with_tooltip_description("The value of this variable when calculating the expression."));
+	}
+
+	layout.push(LayoutGroup::Row { widgets: operand_a_hint }.with_tooltip_description(r#""A" is fed by the value from the previous node in the primary data flow, or it is 0 if disconnected."#));
+	layout
+}
+
+/// Why the Math node can't calculate its expression with the values of its inputs, where each input connected to another node is given the zero value of its type.
+fn math_calculation_error(node_id: NodeId, context: &mut NodePropertiesContext) -> Option<String> {
+	use graphene_std::math_nodes::math::*;
+	use graphene_std::math_nodes::{MathOperand, MathVariable, calculate_math_expression};
+
+	fn input_value(node_id: NodeId, index: usize, context: &mut NodePropertiesContext) -> Option<TaggedValue> {
+		let document_node = get_document_node(node_id, context).ok()?;
+		if let Some(value) = document_node.inputs.get(index)?.as_value() {
+			return Some(value.clone());
+		}
+		let input_type = context.network_interface.input_type(&InputConnector::node(node_id, index), context.selection_network_path);
+		TaggedValue::from_type(input_type.compiled_nested_type()?)
+	}
+
+	fn variable(value: &TaggedValue) -> Option<MathVariable> {
+		match value {
+			TaggedValue::F64(number) => Some(number.into()),
+			TaggedValue::F32(number) => Some(number.into()),
+			TaggedValue::U32(number) => Some((&(*number as f64)).into()),
+			TaggedValue::DVec2(vector) => Some(vector.into()),
+			TaggedValue::Color(color) => Some(color.into()),
+			_ => None,
+		}
+	}
+
+	fn calculate<A: MathOperand>(expression: &str, a: &A, b: &TaggedValue, variables: Vec<(String, MathVariable)>) -> Option<String> {
+		let result = match b {
+			TaggedValue::F32(b) => calculate_math_expression(expression, a, b, variables).map(drop),
+			TaggedValue::DVec2(b) => calculate_math_expression(expression, a, b, variables).map(drop),
+			TaggedValue::Color(b) => calculate_math_expression(expression, a, b, variables).map(drop),
+			TaggedValue::F64(b) => calculate_math_expression(expression, a, b, variables).map(drop),
+			_ => calculate_math_expression(expression, a, &0_f64, variables).map(drop),
+		};
+		result.err()
+	}
+
+	let Some(TaggedValue::String(expression)) = input_value(node_id, ExpressionInput::INDEX, context) else {
+		return None;
+	};
+	let a = input_value(node_id, 0, context).unwrap_or(TaggedValue::F64(0.));
+	let b = input_value(node_id, OperandBInput::<f64>::INDEX, context).unwrap_or(TaggedValue::F64(0.));
+
+	let names = match input_value(node_id, VariableNamesInput::INDEX, context) {
+		Some(TaggedValue::VecString(names)) => names,
+		_ => Vec::new(),
+	};
+	let first_variable_index = VariableNamesInput::INDEX + 1;
+	let variables = names
+		.into_iter()
+		.enumerate()
+		.filter_map(|(index, name)| Some((name, variable(&input_value(node_id, first_variable_index + index, context)?)?)))
+		.collect();
+
+	match &a {
+		TaggedValue::F32(a) => calculate(&expression, a, &b, variables),
+		TaggedValue::DVec2(a) => calculate(&expression, a, &b, variables),
+		TaggedValue::Color(a) => calculate(&expression, a, &b, variables),
+		TaggedValue::F64(a) => calculate(&expression, a, &b, variables),
+		_ => calculate(&expression, &0_f64, &b, variables),
+	}
 }
 
 pub struct ParameterWidgetsInfo<'a> {
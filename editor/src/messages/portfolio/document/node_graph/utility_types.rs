@@ -110,6 +110,21 @@ pub struct FrontendNodeType {
 	pub input_types: Vec<String>,
 }
 
+/// The execution statistics of a node shown as a badge above it while node profiling is enabled.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct FrontendNodeProfile {
+	pub id: NodeId,
+	pub calls: u64,
+	#[serde(rename = "cumulativeMilliseconds")]
+	pub cumulative_milliseconds: f64,
+	#[serde(rename = "selfMilliseconds")]
+	pub self_milliseconds: f64,
+	#[serde(rename = "cacheHitRate")]
+	pub cache_hit_rate: Option<f64>,
+	/// The node's self time relative to the slowest node in the network, from 0 to 1.
+	pub heat: f64,
+}
+
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct DragStart {
 	pub start_x: f64,
@@ -0,0 +1,43 @@
+use crate::consts::{COLOR_OVERLAY_BLACK_75, COLOR_OVERLAY_BLUE, COLOR_OVERLAY_CYAN, COLOR_OVERLAY_WHITE};
+use crate::messages::portfolio::document::overlays::utility_types::{OverlayContext, Pivot};
+use crate::messages::portfolio::document::utility_types::misc::Guide;
+use crate::messages::prelude::*;
+use glam::{DAffine2, DVec2};
+
+/// Draws a guide as a line spanning the whole viewport.
+fn draw_guide(guide: &Guide, document_to_viewport: DAffine2, overlay_context: &mut OverlayContext, color: &str) {
+	let viewport_size: DVec2 = overlay_context.viewport.size().into();
+	let origin = document_to_viewport.transform_point2(guide.origin);
+	let direction = document_to_viewport.transform_vector2(guide.direction()).normalize_or_zero();
+	if direction == DVec2::ZERO || !origin.is_finite() {
+		return;
+	}
+
+	// Extend the line from the point closest to the center of the viewport far enough in both directions to cross all of it
+	let center = origin + (viewport_size / 2. - origin).project_onto(direction);
+	let extent = direction * viewport_size.length();
+	overlay_context.line(center - extent, center + extent, Some(color), None);
+}
+
+pub fn guide_overlay(document: &DocumentMessageHandler, dragged_guide: Option<&Guide>, overlay_context: &mut OverlayContext) {
+	let document_to_viewport = document.metadata().document_to_viewport;
+
+	for guide in &document.guides.guides {
+		draw_guide(guide, document_to_viewport, overlay_context, COLOR_OVERLAY_CYAN);
+	}
+
+	let Some(guide) = dragged_guide else { return };
+	draw_guide(guide, document_to_viewport, overlay_context, COLOR_OVERLAY_BLUE);
+
+	// Label the guide being dragged with its position, or its angle if it's neither horizontal nor vertical
+	let label = if guide.is_horizontal() {
+		format!("Y: {:.2}", guide.origin.y)
+	} else if guide.is_vertical() {
+		format!("X: {:.2}", guide.origin.x)
+	} else {
+		format!("{:.2}°", guide.angle)
+	};
+	let label = label.trim_end_matches('0').trim_end_matches('.').to_string();
+	let transform = DAffine2::from_translation(document_to_viewport.transform_point2(guide.origin) + DVec2::new(8., -8.));
+	overlay_context.text(&label, COLOR_OVERLAY_WHITE, Some(COLOR_OVERLAY_BLACK_75), transform, 4., [Pivot::Start, Pivot::End]);
+}
@@ -1,6 +1,9 @@
 pub mod grid_overlays;
+pub mod guide_overlays;
 mod overlays_message;
 mod overlays_message_handler;
+pub mod pixel_selection_overlays;
+pub mod slice_overlays;
 pub mod utility_functions;
 // Native (non‑wasm)
 #[cfg(not(target_family = "wasm"))]
@@ -0,0 +1,31 @@
+use crate::consts::{COLOR_OVERLAY_BLACK_75, COLOR_OVERLAY_WHITE};
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::prelude::*;
+use glam::DVec2;
+use graphene_std::raster::selection::SelectionShape;
+
+/// Half the length of the crosshair marking where the magic wand was clicked.
+const MAGIC_WAND_MARKER_SIZE: f64 = 5.;
+
+/// Outlines the shapes that make up the pixel selection with marching ants, and marks where the magic wand was clicked.
+pub fn pixel_selection_overlay(document: &DocumentMessageHandler, overlay_context: &mut OverlayContext) {
+	let document_to_viewport = document.metadata().document_to_viewport;
+
+	for step in &document.pixel_selection {
+		if let SelectionShape::MagicWand { seed, .. } = step.shape {
+			let seed = document_to_viewport.transform_point2(seed);
+			for direction in [DVec2::X, DVec2::Y] {
+				let offset = direction * MAGIC_WAND_MARKER_SIZE;
+				overlay_context.line(seed - offset, seed + offset, Some(COLOR_OVERLAY_BLACK_75), Some(3.));
+				overlay_context.line(seed - offset, seed + offset, Some(COLOR_OVERLAY_WHITE), None);
+			}
+			continue;
+		}
+
+		for outline in step.shape.outlines().unwrap_or_default() {
+			let outline = outline.into_iter().map(|point| document_to_viewport.transform_point2(point)).collect::<Vec<_>>();
+			overlay_context.polygon(&outline, Some(COLOR_OVERLAY_BLACK_75), None);
+			overlay_context.dashed_polygon(&outline, Some(COLOR_OVERLAY_WHITE), None, Some(4.), Some(4.), None);
+		}
+	}
+}
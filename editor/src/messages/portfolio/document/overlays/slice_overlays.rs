@@ -0,0 +1,18 @@
+use crate::consts::{COLOR_OVERLAY_GREEN, COLOR_OVERLAY_WHITE};
+use crate::messages::portfolio::document::overlays::utility_types::{OverlayContext, Pivot};
+use crate::messages::prelude::*;
+use glam::{DAffine2, DVec2};
+use graphene_std::math::quad::Quad;
+
+/// Outlines every export slice in the document and labels it with its name.
+pub fn slice_overlay(document: &DocumentMessageHandler, overlay_context: &mut OverlayContext) {
+	let document_to_viewport = document.metadata().document_to_viewport;
+
+	for slice in &document.export_slices {
+		let quad = document_to_viewport * Quad::from_box(slice.bounds);
+		overlay_context.dashed_quad(quad, Some(COLOR_OVERLAY_GREEN), None, Some(4.), Some(4.), None);
+
+		let transform = DAffine2::from_translation(quad.top_left() + DVec2::new(0., -4.));
+		overlay_context.text(&slice.name, COLOR_OVERLAY_WHITE, Some(COLOR_OVERLAY_GREEN), transform, 2., [Pivot::Start, Pivot::End]);
+	}
+}
@@ -0,0 +1,315 @@
+use super::document_metadata::LayerNodeIdentifier;
+use super::network_interface::{InputConnector, NodeNetworkInterface};
+use crate::messages::tool::common_functionality::graph_modification_utils::{self, NodeGraphLayer};
+use graph_craft::concrete;
+use graphene_std::raster::BlendMode;
+use graphene_std::table::Table;
+use graphene_std::vector::Vector;
+use graphene_std::{Color, Graphic};
+
+/// The broad category of content a layer holds, as searched for with `type:` in a [`LayerQuery`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LayerKind {
+	Artboard,
+	Group,
+	Text,
+	Raster,
+	Vector,
+}
+
+impl LayerKind {
+	/// Determines the kind of the layer, or `None` if its content doesn't fit any of the categories.
+	pub fn of_layer(layer: LayerNodeIdentifier, network_interface: &mut NodeNetworkInterface) -> Option<Self> {
+		if network_interface.is_artboard(&layer.to_node(), &[]) {
+			return Some(Self::Artboard);
+		}
+		if graph_modification_utils::get_text_id(layer, network_interface).is_some() {
+			return Some(Self::Text);
+		}
+		if NodeGraphLayer::is_raster_layer(layer, network_interface) {
+			return Some(Self::Raster);
+		}
+		if layer.has_children(network_interface.document_metadata()) {
+			return Some(Self::Group);
+		}
+
+		let input_type = network_interface.input_type(&InputConnector::node(layer.to_node(), 1), &[]);
+		match input_type.compiled_nested_type() {
+			Some(nested_type) if *nested_type == concrete!(Table<Graphic>) => Some(Self::Group),
+			Some(nested_type) if *nested_type == concrete!(Table<Vector>) => Some(Self::Vector),
+			_ => None,
+		}
+	}
+
+	fn parse(value: &str) -> Option<Self> {
+		match value.to_lowercase().as_str() {
+			"artboard" | "artboards" => Some(Self::Artboard),
+			"group" | "groups" | "folder" | "folders" => Some(Self::Group),
+			"text" => Some(Self::Text),
+			"raster" | "image" | "images" => Some(Self::Raster),
+			"vector" | "vectors" | "shape" | "shapes" => Some(Self::Vector),
+			_ => None,
+		}
+	}
+}
+
+/// A set of conditions which a layer must all meet to be found by a search in the Layers panel.
+///
+/// Queries are written as space-separated terms. A term of the form `key:value` filters by a property, and any other term is matched against the layer name.
+/// Values containing spaces can be wrapped in double quotes. The supported keys are:
+/// - `name:` a name pattern, where `*` matches any run of characters and `?` matches a single character (without wildcards, any part of the name may match)
+/// - `type:` one of `artboard`, `group`, `text`, `raster` or `vector`
+/// - `fill:` and `stroke:` a 6-character hex color, with or without the `#` prefix
+/// - `blend:` a blend mode name such as `multiply` or `color-burn`
+/// - `visible:` and `locked:` either `yes` or `no`
+/// - `node:` the name of a node which must be present in the layer's chain
+///
+/// For example, `type:vector fill:#ff0000 "Tree *"` finds red vector layers whose names start with "Tree ".
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LayerQuery {
+	pub name_patterns: Vec<String>,
+	pub kind: Option<LayerKind>,
+	pub fill: Option<Color>,
+	pub stroke: Option<Color>,
+	pub blend_mode: Option<BlendMode>,
+	pub visible: Option<bool>,
+	pub locked: Option<bool>,
+	pub nodes: Vec<String>,
+}
+
+impl LayerQuery {
+	/// Parses the text typed into the Layers panel search field, returning a description of the first invalid term upon failure.
+	pub fn parse(query: &str) -> Result<Self, String> {
+		let mut result = Self::default();
+
+		for term in split_terms(query) {
+			let Some((key, value)) = term.split_once(':').filter(|(key, _)| !key.is_empty() && key.chars().all(|character| character.is_ascii_alphabetic())) else {
+				result.name_patterns.push(term);
+				continue;
+			};
+
+			let invalid = || format!("\"{value}\" is not a valid value for \"{key}:\".");
+			match key.to_lowercase().as_str() {
+				"name" => result.name_patterns.push(value.to_string()),
+				"type" | "kind" => result.kind = Some(LayerKind::parse(value).ok_or_else(invalid)?),
+				"fill" => result.fill = Some(parse_color(value).ok_or_else(invalid)?),
+				"stroke" => result.stroke = Some(parse_color(value).ok_or_else(invalid)?),
+				"blend" => result.blend_mode = Some(parse_blend_mode(value).ok_or_else(invalid)?),
+				"visible" => result.visible = Some(parse_bool(value).ok_or_else(invalid)?),
+				"locked" => result.locked = Some(parse_bool(value).ok_or_else(invalid)?),
+				"node" => result.nodes.push(value.to_string()),
+				_ => return Err(format!("\"{key}:\" is not a recognized search key.")),
+			}
+		}
+
+		Ok(result)
+	}
+
+	/// A query for the layers which resemble the given one, having the same kind, fill and stroke colors, and blend mode.
+	pub fn similar_to(layer: LayerNodeIdentifier, network_interface: &mut NodeNetworkInterface) -> Self {
+		Self {
+			kind: LayerKind::of_layer(layer, network_interface),
+			fill: graph_modification_utils::get_fill_color(layer, network_interface),
+			stroke: graph_modification_utils::get_stroke_color(layer, network_interface),
+			blend_mode: Some(graph_modification_utils::get_blend_mode(layer, network_interface).unwrap_or_default()),
+			..Default::default()
+		}
+	}
+
+	/// Whether no conditions have been given, which would otherwise match every layer.
+	pub fn is_empty(&self) -> bool {
+		*self == Self::default()
+	}
+
+	/// Checks if the layer meets every condition of the query.
+	pub fn matches(&self, layer: LayerNodeIdentifier, network_interface: &mut NodeNetworkInterface) -> bool {
+		let node_id = layer.to_node();
+
+		if !self.name_patterns.is_empty() {
+			let name = network_interface.display_name(&node_id, &[]);
+			if !self.name_patterns.iter().all(|pattern| name_matches(pattern, &name)) {
+				return false;
+			}
+		}
+		if self.visible.is_some_and(|visible| network_interface.is_visible(&node_id, &[]) != visible) {
+			return false;
+		}
+		if self.locked.is_some_and(|locked| network_interface.is_locked(&node_id, &[]) != locked) {
+			return false;
+		}
+		if self
+			.blend_mode
+			.is_some_and(|blend_mode| graph_modification_utils::get_blend_mode(layer, network_interface).unwrap_or_default() != blend_mode)
+		{
+			return false;
+		}
+		if self
+			.fill
+			.is_some_and(|fill| !graph_modification_utils::get_fill_color(layer, network_interface).is_some_and(|color| same_color(color, fill)))
+		{
+			return false;
+		}
+		if self
+			.stroke
+			.is_some_and(|stroke| !graph_modification_utils::get_stroke_color(layer, network_interface).is_some_and(|color| same_color(color, stroke)))
+		{
+			return false;
+		}
+		if !self.nodes.is_empty() {
+			let node_names = chain_node_names(layer, network_interface);
+			if !self.nodes.iter().all(|node| node_names.iter().any(|name| name.eq_ignore_ascii_case(node.trim()))) {
+				return false;
+			}
+		}
+		if self.kind.is_some() && LayerKind::of_layer(layer, network_interface) != self.kind {
+			return false;
+		}
+
+		true
+	}
+}
+
+/// The display and implementation names of the nodes in the layer's own chain, up until the next layer is reached.
+fn chain_node_names(layer: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> Vec<String> {
+	NodeGraphLayer::new(layer, network_interface)
+		.horizontal_layer_flow()
+		.skip(1)
+		.take_while(|node_id| !network_interface.is_layer(node_id, &[]))
+		.flat_map(|node_id| [network_interface.display_name(&node_id, &[]), network_interface.implementation_name(&node_id, &[])])
+		.collect()
+}
+
+/// Colors are compared as they would be written in hex, so small floating point differences from color space conversions are ignored.
+fn same_color(a: Color, b: Color) -> bool {
+	a.to_rgb_hex_srgb() == b.to_rgb_hex_srgb()
+}
+
+/// Splits the query at whitespace, keeping the contents of double quotes together and removing the quotes.
+fn split_terms(query: &str) -> Vec<String> {
+	let mut terms = Vec::new();
+	let mut current = String::new();
+	let mut in_quotes = false;
+
+	for character in query.chars() {
+		match character {
+			'"' => in_quotes = !in_quotes,
+			character if character.is_whitespace() && !in_quotes => {
+				if !current.is_empty() {
+					terms.push(std::mem::take(&mut current));
+				}
+			}
+			character => current.push(character),
+		}
+	}
+	if !current.is_empty() {
+		terms.push(current);
+	}
+
+	terms
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+	Color::from_rgb_str(value.strip_prefix('#').unwrap_or(value)).map(|color| color.to_linear_srgb())
+}
+
+fn parse_blend_mode(value: &str) -> Option<BlendMode> {
+	let simplify = |name: &str| name.chars().filter(|character| character.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+	let value = simplify(value);
+	BlendMode::list().into_iter().flatten().copied().find(|blend_mode| simplify(&blend_mode.to_string()) == value)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+	match value.to_lowercase().as_str() {
+		"yes" | "true" | "on" => Some(true),
+		"no" | "false" | "off" => Some(false),
+		_ => None,
+	}
+}
+
+/// Case-insensitively matches a name against a pattern, where `*` matches any run of characters and `?` matches any single character.
+/// A pattern without wildcards matches if it appears anywhere in the name.
+fn name_matches(pattern: &str, name: &str) -> bool {
+	let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+	let name = name.to_lowercase().chars().collect::<Vec<_>>();
+
+	if !pattern.iter().any(|&character| character == '*' || character == '?') {
+		return name.windows(pattern.len().max(1)).any(|window| window == pattern.as_slice()) || pattern.is_empty();
+	}
+
+	// Standard wildcard matching which backtracks to the most recent `*` upon a mismatch
+	let (mut pattern_index, mut name_index) = (0, 0);
+	let mut last_star = None;
+	while name_index < name.len() {
+		match pattern.get(pattern_index) {
+			Some('*') => {
+				last_star = Some((pattern_index, name_index));
+				pattern_index += 1;
+			}
+			Some(&character) if character == '?' || character == name[name_index] => {
+				pattern_index += 1;
+				name_index += 1;
+			}
+			_ => {
+				let Some((star_pattern_index, star_name_index)) = last_star else { return false };
+				pattern_index = star_pattern_index + 1;
+				name_index = star_name_index + 1;
+				last_star = Some((star_pattern_index, star_name_index + 1));
+			}
+		}
+	}
+
+	pattern[pattern_index..].iter().all(|&character| character == '*')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_utils::test_prelude::*;
+
+	#[test]
+	fn parse_query() {
+		let query = LayerQuery::parse(r#"type:vector fill:#FF0000 blend:color-burn visible:no "Big Tree*" node:"Gaussian Blur""#).unwrap();
+		assert_eq!(query.kind, Some(LayerKind::Vector));
+		assert_eq!(query.fill.map(|color| color.to_rgb_hex_srgb()), Some("ff0000".to_string()));
+		assert_eq!(query.blend_mode, Some(BlendMode::ColorBurn));
+		assert_eq!(query.visible, Some(false));
+		assert_eq!(query.name_patterns, vec!["Big Tree*".to_string()]);
+		assert_eq!(query.nodes, vec!["Gaussian Blur".to_string()]);
+
+		assert!(LayerQuery::parse("type:circle").is_err());
+		assert!(LayerQuery::parse("color:red").is_err());
+		assert!(LayerQuery::parse("").unwrap().is_empty());
+	}
+
+	#[test]
+	fn name_patterns() {
+		assert!(name_matches("tree", "Big Tree 2"));
+		assert!(name_matches("big*2", "Big Tree 2"));
+		assert!(name_matches("Big?Tree*", "Big Tree 2"));
+		assert!(!name_matches("tree*", "Big Tree 2"));
+		assert!(!name_matches("bush", "Big Tree 2"));
+		assert!(name_matches("*", ""));
+	}
+
+	#[tokio::test]
+	async fn select_by_type_and_similar() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		editor.draw_ellipse(150., 0., 250., 100.).await;
+		editor.create_raster_image(Image::new(10, 10, Color::WHITE), Some((300., 300.))).await;
+
+		editor.handle_message(DocumentMessage::SelectLayersByQuery { query: "type:vector".to_string() }).await;
+		let document = editor.active_document();
+		assert_eq!(document.network_interface.selected_nodes().selected_layers(document.metadata()).count(), 2);
+
+		editor.handle_message(DocumentMessage::SelectLayersByQuery { query: "type:raster".to_string() }).await;
+		let document = editor.active_document();
+		assert_eq!(document.network_interface.selected_nodes().selected_layers(document.metadata()).count(), 1);
+
+		editor.draw_rect(300., 0., 400., 100.).await;
+		editor.handle_message(DocumentMessage::SelectSimilarLayers).await;
+		let document = editor.active_document();
+		assert_eq!(document.network_interface.selected_nodes().selected_layers(document.metadata()).count(), 3);
+	}
+}
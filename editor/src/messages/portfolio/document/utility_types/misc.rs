@@ -1,7 +1,9 @@
 use crate::consts::COLOR_OVERLAY_GRAY;
+use crate::messages::frontend::utility_types::ExportPreset;
 use glam::DVec2;
 use graphene_std::raster::Color;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize, specta::Type)]
@@ -64,6 +66,7 @@ pub struct SnappingState {
 	pub tolerance: f64,
 	pub bounding_box: BoundingBoxSnapping,
 	pub path: PathSnapping,
+	pub guide: GuideSnapping,
 	pub grid: GridSnapping,
 }
 
@@ -76,6 +79,7 @@ impl Default for SnappingState {
 			tolerance: 8.,
 			bounding_box: BoundingBoxSnapping::default(),
 			path: PathSnapping::default(),
+			guide: GuideSnapping::default(),
 			grid: GridSnapping::default(),
 		}
 	}
@@ -103,6 +107,10 @@ impl SnappingState {
 			},
 			SnapTarget::Artboard(_) => self.artboards,
 			SnapTarget::Grid(_) => self.grid_snapping,
+			SnapTarget::Guide(target) => match target {
+				GuideSnapTarget::Line => self.guide.along_guide,
+				GuideSnapTarget::Intersection => self.guide.guide_intersection_point,
+			},
 			SnapTarget::Alignment(AlignmentSnapTarget::AlignWithAnchorPoint) => self.path.align_with_anchor_point,
 			SnapTarget::Alignment(_) => self.bounding_box.align_with_edges,
 			SnapTarget::DistributeEvenly(_) => self.bounding_box.distribute_evenly,
@@ -161,6 +169,22 @@ impl Default for PathSnapping {
 	}
 }
 
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct GuideSnapping {
+	pub along_guide: bool,
+	pub guide_intersection_point: bool,
+}
+
+impl Default for GuideSnapping {
+	fn default() -> Self {
+		Self {
+			along_guide: true,
+			guide_intersection_point: true,
+		}
+	}
+}
+
 #[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
 pub enum GridType {
 	#[serde(alias = "Rectangle")]
@@ -266,6 +290,92 @@ impl GridSnapping {
 	}
 }
 
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct GuideId(pub u64);
+
+/// A straight line of infinite length that the user drags out of the rulers to line up and snap artwork against.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct Guide {
+	pub id: GuideId,
+	/// A point in document space that the guide passes through.
+	pub origin: DVec2,
+	/// The angle in degrees between the guide and the document's X axis, so horizontal guides are at 0° and vertical guides are at 90°.
+	pub angle: f64,
+}
+
+impl Guide {
+	pub fn direction(&self) -> DVec2 {
+		DVec2::from_angle(self.angle.to_radians())
+	}
+
+	/// Finds the closest point on the guide to the given document space point.
+	pub fn project(&self, point: DVec2) -> DVec2 {
+		(point - self.origin).project_onto(self.direction()) + self.origin
+	}
+
+	pub fn is_horizontal(&self) -> bool {
+		self.angle.rem_euclid(180.) == 0.
+	}
+
+	pub fn is_vertical(&self) -> bool {
+		self.angle.rem_euclid(180.) == 90.
+	}
+}
+
+impl Hash for Guide {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.id.hash(state);
+		self.origin.x.to_bits().hash(state);
+		self.origin.y.to_bits().hash(state);
+		self.angle.to_bits().hash(state);
+	}
+}
+
+/// The guides placed in a document along with the user's choices for how they're displayed and edited.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq, Hash)]
+#[serde(default)]
+pub struct Guides {
+	pub guides: Vec<Guide>,
+	/// Whether or not the guides are drawn in the viewport and snapped to.
+	pub visible: bool,
+	/// Whether or not the guides are protected from being moved or deleted.
+	pub locked: bool,
+}
+
+impl Default for Guides {
+	fn default() -> Self {
+		Self {
+			guides: Vec::new(),
+			visible: true,
+			locked: false,
+		}
+	}
+}
+
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct SliceId(pub u64);
+
+/// A named rectangular region of the document which is exported by Export All independently of the layers within it.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct ExportSlice {
+	pub id: SliceId,
+	pub name: String,
+	/// The top left and bottom right corners of the region in document space.
+	pub bounds: [DVec2; 2],
+	pub presets: Vec<ExportPreset>,
+}
+
+impl Hash for ExportSlice {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.id.hash(state);
+		self.name.hash(state);
+		self.bounds.iter().for_each(|corner| [corner.x, corner.y].map(f64::to_bits).hash(state));
+		self.presets.hash(state);
+	}
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BoundingBoxSnapSource {
 	CornerPoint,
@@ -409,6 +519,18 @@ pub const SNAP_FUNCTIONS_FOR_BOUNDING_BOXES: [(&str, GetSnapState, &str); 5] = [
 		"Snaps to a consistent distance offset established by the bounding boxes of nearby layers.",
 	),
 ];
+pub const SNAP_FUNCTIONS_FOR_GUIDES: [(&str, GetSnapState, &str); 2] = [
+	(
+		"Along Guides",
+		(|snapping_state: &mut SnappingState| &mut snapping_state.guide.along_guide) as GetSnapState,
+		"Snaps along the length of any guide dragged out from the rulers.",
+	),
+	(
+		"Guide Intersection Points",
+		(|snapping_state: &mut SnappingState| &mut snapping_state.guide.guide_intersection_point) as GetSnapState,
+		"Snaps to any points where guides intersect.",
+	),
+];
 pub const SNAP_FUNCTIONS_FOR_PATHS: [(&str, GetSnapState, &str); 7] = [
 	(
 		"Align with Anchor Points",
@@ -531,6 +653,21 @@ impl fmt::Display for GridSnapTarget {
 	}
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuideSnapTarget {
+	Line,
+	Intersection,
+}
+
+impl fmt::Display for GuideSnapTarget {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			GuideSnapTarget::Line => write!(f, "Guide: Along Guide"),
+			GuideSnapTarget::Intersection => write!(f, "Guide: Intersection Point"),
+		}
+	}
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AlignmentSnapTarget {
 	BoundingBoxCornerPoint,
@@ -598,6 +735,7 @@ pub enum SnapTarget {
 	Path(PathSnapTarget),
 	Artboard(ArtboardSnapTarget),
 	Grid(GridSnapTarget),
+	Guide(GuideSnapTarget),
 	Alignment(AlignmentSnapTarget),
 	DistributeEvenly(DistributionSnapTarget),
 }
@@ -619,6 +757,7 @@ impl fmt::Display for SnapTarget {
 			SnapTarget::Path(path_snap_target) => write!(f, "{path_snap_target}"),
 			SnapTarget::Artboard(artboard_snap_target) => write!(f, "{artboard_snap_target}"),
 			SnapTarget::Grid(grid_snap_target) => write!(f, "{grid_snap_target}"),
+			SnapTarget::Guide(guide_snap_target) => write!(f, "{guide_snap_target}"),
 			SnapTarget::Alignment(alignment_snap_target) => write!(f, "{alignment_snap_target}"),
 			SnapTarget::DistributeEvenly(distribution_snap_target) => write!(f, "{distribution_snap_target}"),
 		}
@@ -629,6 +768,7 @@ impl fmt::Display for SnapTarget {
 pub enum SnappingOptions {
 	BoundingBoxes,
 	Paths,
+	Guides,
 }
 
 impl fmt::Display for SnappingOptions {
@@ -636,6 +776,7 @@ impl fmt::Display for SnappingOptions {
 		match self {
 			SnappingOptions::BoundingBoxes => write!(f, "Bounding Boxes"),
 			SnappingOptions::Paths => write!(f, "Paths"),
+			SnappingOptions::Guides => write!(f, "Guides"),
 		}
 	}
 }
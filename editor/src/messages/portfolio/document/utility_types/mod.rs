@@ -1,6 +1,7 @@
 pub mod clipboards;
 pub mod document_metadata;
 pub mod error;
+pub mod layer_query;
 pub mod misc;
 pub mod network_interface;
 pub mod nodes;
@@ -1,11 +1,15 @@
 mod deserialization;
+mod history;
 mod memo_network;
+mod node_libraries;
 mod resolved_types;
+mod symbols;
 
 use super::document_metadata::{DocumentMetadata, LayerNodeIdentifier, NodeRelations};
 use super::misc::PTZ;
 use super::nodes::SelectedNodes;
 use crate::consts::{EXPORTS_TO_RIGHT_EDGE_PIXEL_GAP, EXPORTS_TO_TOP_EDGE_PIXEL_GAP, GRID_SIZE, IMPORTS_TO_LEFT_EDGE_PIXEL_GAP, IMPORTS_TO_TOP_EDGE_PIXEL_GAP};
+use crate::messages::frontend::utility_types::ExportPreset;
 use crate::messages::portfolio::document::graph_operation::utility_types::ModifyInputsContext;
 use crate::messages::portfolio::document::node_graph::document_node_definitions::{DefinitionIdentifier, resolve_document_node_type};
 use crate::messages::portfolio::document::node_graph::utility_types::{Direction, FrontendClickTargets, FrontendGraphDataType, FrontendGraphInput, FrontendGraphOutput};
@@ -32,6 +36,10 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
 use std::ops::Deref;
 
+pub use history::NetworkSnapshot;
+pub use node_libraries::NodeLibraryReference;
+pub use symbols::SymbolRole;
+
 /// All network modifications should be done through this API, so the fields cannot be public. However, all fields within this struct can be public since it it not possible to have a public mutable reference.
 #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct NodeNetworkInterface {
@@ -101,7 +109,7 @@ impl NodeNetworkInterface {
 
 	/// Gets the nested network based on network_path
 	pub fn nested_network(&self, network_path: &[NodeId]) -> Option<&NodeNetwork> {
-		let Some(network) = self.document_network().nested_network(network_path) else {
+		let Some(network) = self.document_network().nested_network(&self.symbol_network_path(network_path)) else {
 			log::error!("Could not get nested network with path {network_path:?} in NodeNetworkInterface::network");
 			return None;
 		};
@@ -137,7 +145,7 @@ impl NodeNetworkInterface {
 
 	/// The network metadata should always exist for the current network
 	pub fn network_metadata(&self, network_path: &[NodeId]) -> Option<&NodeNetworkMetadata> {
-		let Some(network_metadata) = self.network_metadata.nested_metadata(network_path) else {
+		let Some(network_metadata) = self.network_metadata.nested_metadata(&self.symbol_network_path(network_path)) else {
 			log::error!("Could not get nested network_metadata with path {network_path:?}");
 			return None;
 		};
@@ -442,10 +450,18 @@ impl NodeNetworkInterface {
 			return None;
 		};
 
-		Some(NodeTemplate {
+		let mut node_template = NodeTemplate {
 			persistent_node_metadata: node_metadata.persistent_metadata,
 			document_node: node.clone(),
-		})
+		};
+		// Copies of a symbol instance carry the content of its definition, so they keep it where the definition doesn't exist
+		if network_path.is_empty()
+			&& let Some((implementation, network_metadata)) = self.symbol_instance_content(node_id)
+		{
+			node_template.document_node.implementation = implementation;
+			node_template.persistent_node_metadata.network_metadata = network_metadata;
+		}
+		Some(node_template)
 	}
 
 	/// Converts all node id inputs to a new id based on a HashMap.
@@ -937,6 +953,11 @@ impl NodeNetworkInterface {
 	}
 
 	pub fn implementation(&self, node_id: &NodeId, network_path: &[NodeId]) -> Option<&DocumentNodeImplementation> {
+		if network_path.is_empty()
+			&& let Some(implementation) = self.symbol_implementation(node_id)
+		{
+			return Some(implementation);
+		}
 		let Some(node) = self.document_node(node_id, network_path) else {
 			log::error!("Could not get implementation");
 			return None;
@@ -975,6 +996,18 @@ impl NodeNetworkInterface {
 		metadata.persistent_metadata.widget_override = widget_override;
 	}
 
+	pub fn set_input_data(&mut self, node_id: &NodeId, index: usize, key: &str, value: Value, network_path: &[NodeId]) {
+		let Some(metadata) = self
+			.node_metadata_mut(node_id, network_path)
+			.and_then(|node_metadata| node_metadata.persistent_metadata.input_metadata.get_mut(index))
+		else {
+			log::error!("Could not get input metadata for {node_id} index {index} in set_input_data");
+			return;
+		};
+		metadata.persistent_metadata.input_data.insert(key.to_string(), value);
+		self.transaction_modified();
+	}
+
 	/// Returns the input name to display in the properties panel. If the name is empty then the type is used.
 	pub fn displayed_input_name_and_description(&mut self, node_id: &NodeId, input_index: usize, network_path: &[NodeId]) -> (String, String) {
 		let Some(input_metadata) = self.persistent_input_metadata(node_id, input_index, network_path) else {
@@ -1036,6 +1069,14 @@ impl NodeNetworkInterface {
 		node_metadata.persistent_metadata.pinned
 	}
 
+	pub fn export_presets(&self, node_id: &NodeId, network_path: &[NodeId]) -> &[ExportPreset] {
+		let Some(node_metadata) = self.node_metadata(node_id, network_path) else {
+			log::error!("Could not get persistent node metadata in export_presets for node {node_id}");
+			return &[];
+		};
+		&node_metadata.persistent_metadata.export_presets
+	}
+
 	pub fn is_visible(&self, node_id: &NodeId, network_path: &[NodeId]) -> bool {
 		let Some(node) = self.document_node(node_id, network_path) else {
 			log::error!("Could not get node in is_visible");
@@ -1399,11 +1440,13 @@ impl NodeNetworkInterface {
 // Private mutable getters for use within the network interface
 impl NodeNetworkInterface {
 	fn network_mut(&mut self, network_path: &[NodeId]) -> Option<&mut NodeNetwork> {
-		self.document_network_mut().nested_network_mut(network_path)
+		let network_path = self.symbol_network_path(network_path);
+		self.document_network_mut().nested_network_mut(&network_path)
 	}
 
 	fn network_metadata_mut(&mut self, network_path: &[NodeId]) -> Option<&mut NodeNetworkMetadata> {
-		self.network_metadata.nested_metadata_mut(network_path)
+		let network_path = self.symbol_network_path(network_path);
+		self.network_metadata.nested_metadata_mut(&network_path)
 	}
 
 	fn node_metadata_mut(&mut self, node_id: &NodeId, network_path: &[NodeId]) -> Option<&mut DocumentNodeMetadata> {
@@ -1504,7 +1547,7 @@ impl NodeNetworkInterface {
 			network_metadata.persistent_metadata.selection_undo_history.push_back(last_selection_state);
 			network_metadata.persistent_metadata.selection_redo_history.clear();
 
-			if network_metadata.persistent_metadata.selection_undo_history.len() > crate::consts::MAX_UNDO_HISTORY_LEN {
+			if network_metadata.persistent_metadata.selection_undo_history.len() > crate::consts::MAX_SELECTION_HISTORY_LEN {
 				network_metadata.persistent_metadata.selection_undo_history.pop_front();
 			}
 		}
@@ -3366,6 +3409,65 @@ impl NodeNetworkInterface {
 		self.unload_modify_import_export(network_path);
 	}
 
+	/// Replaces the inputs of a node from `first_input_index` onward with inputs of the given names, such as the inputs for the variables of a Math node's expression.
+	/// Inputs with a name that remains keep their value or connection, and new inputs are exposed with the default value. Inputs that are removed are disconnected first.
+	pub fn set_named_inputs(&mut self, node_id: &NodeId, first_input_index: usize, input_names: &[String], default_value: TaggedValue, network_path: &[NodeId]) {
+		let Some(node_metadata) = self.node_metadata(node_id, network_path) else {
+			log::error!("Could not get node_metadata in set_named_inputs");
+			return;
+		};
+		let previous_names: Vec<String> = node_metadata
+			.persistent_metadata
+			.input_metadata
+			.iter()
+			.skip(first_input_index)
+			.map(|input_metadata| input_metadata.persistent_metadata.input_name.clone())
+			.collect();
+		if previous_names == input_names {
+			return;
+		}
+
+		for (index, previous_name) in previous_names.iter().enumerate() {
+			if !input_names.contains(previous_name) {
+				self.disconnect_input(&InputConnector::node(*node_id, first_input_index + index), network_path);
+			}
+		}
+		let previous_index = |name: &String| previous_names.iter().position(|previous_name| previous_name == name);
+
+		let Some(network) = self.network_mut(network_path) else {
+			log::error!("Could not get nested network in set_named_inputs");
+			return;
+		};
+		let Some(node) = network.nodes.get_mut(node_id) else {
+			log::error!("Could not get node in set_named_inputs");
+			return;
+		};
+		let first_input_index = first_input_index.min(node.inputs.len());
+		let mut previous_inputs: Vec<_> = node.inputs.drain(first_input_index..).map(Some).collect();
+		for name in input_names {
+			let input = previous_index(name).and_then(|index| previous_inputs.get_mut(index)?.take());
+			node.inputs.push(input.unwrap_or_else(|| NodeInput::value(default_value.clone(), true)));
+		}
+
+		let Some(node_metadata) = self.node_metadata_mut(node_id, network_path) else {
+			log::error!("Could not get node_metadata in set_named_inputs");
+			return;
+		};
+		let input_metadata = &mut node_metadata.persistent_metadata.input_metadata;
+		let first_input_index = first_input_index.min(input_metadata.len());
+		let mut previous_metadata: Vec<_> = input_metadata.drain(first_input_index..).map(Some).collect();
+		for name in input_names {
+			let metadata = previous_index(name).and_then(|index| previous_metadata.get_mut(index)?.take());
+			input_metadata.push(metadata.unwrap_or_else(|| (name.as_str(), "").into()));
+		}
+
+		self.transaction_modified();
+
+		self.unload_outward_wires(network_path);
+		self.unload_node_click_targets(node_id, network_path);
+		self.unload_all_nodes_bounding_box(network_path);
+	}
+
 	// First disconnects the export, then removes it
 	pub fn remove_export(&mut self, export_index: usize, network_path: &[NodeId]) {
 		let mut encapsulating_network_path = network_path.to_vec();
@@ -4229,6 +4331,10 @@ impl NodeNetworkInterface {
 		}
 
 		for delete_node_id in &delete_nodes {
+			if network_path.is_empty() && matches!(self.symbol_role(delete_node_id), Some(SymbolRole::Definition)) {
+				self.detach_symbol_instances_of(delete_node_id);
+			}
+
 			let upstream_chain_nodes = self
 				.upstream_flow_back_from_nodes(vec![*delete_node_id], network_path, FlowType::PrimaryFlow)
 				.skip(1)
@@ -4431,6 +4537,18 @@ impl NodeNetworkInterface {
 		self.unload_node_click_targets(node_id, network_path);
 	}
 
+	pub fn set_export_presets(&mut self, node_id: &NodeId, export_presets: Vec<ExportPreset>, network_path: &[NodeId]) {
+		let Some(node_metadata) = self.node_metadata_mut(node_id, network_path) else {
+			log::error!("Could not get node {node_id} in set_export_presets");
+			return;
+		};
+
+		if node_metadata.persistent_metadata.export_presets != export_presets {
+			node_metadata.persistent_metadata.export_presets = export_presets;
+			self.transaction_modified();
+		}
+	}
+
 	pub fn set_import_export_name(&mut self, mut name: String, index: ImportOrExport, network_path: &[NodeId]) {
 		let Some(encapsulating_node) = self.encapsulating_node_metadata_mut(network_path) else {
 			log::error!("Could not get encapsulating network in set_import_export_name");
@@ -6254,6 +6372,15 @@ pub struct DocumentNodePersistentMetadata {
 	pub node_type_metadata: NodeTypePersistentMetadata,
 	/// This should always be Some for nodes with a [`DocumentNodeImplementation::Network`], and none for [`DocumentNodeImplementation::ProtoNode`]
 	pub network_metadata: Option<NodeNetworkMetadata>,
+	/// Whether the node defines a symbol or is an instance of one.
+	#[serde(default)]
+	pub symbol: Option<SymbolRole>,
+	/// The files that this layer is exported to by Export All.
+	#[serde(default)]
+	pub export_presets: Vec<ExportPreset>,
+	/// The node library node which this node was created from, along with the library's version.
+	#[serde(default)]
+	pub library: Option<NodeLibraryReference>,
 }
 
 impl DocumentNodePersistentMetadata {
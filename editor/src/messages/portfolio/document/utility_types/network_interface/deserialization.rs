@@ -149,6 +149,9 @@ impl From<DocumentNodePersistentMetadataStringReference> for DocumentNodePersist
 			pinned: old.pinned,
 			node_type_metadata: old.node_type_metadata,
 			network_metadata: old.network_metadata,
+			symbol: None,
+			export_presets: Vec::new(),
+			library: None,
 		}
 	}
 }
@@ -0,0 +1,195 @@
+use super::{DocumentNodeMetadata, MemoNetwork, NodeNetworkInterface, NodeNetworkMetadata, NodeNetworkPersistentMetadata, TransactionStatus};
+use glam::DVec2;
+use graph_craft::document::value::TaggedValue;
+use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeId, NodeInput, NodeNetwork};
+use graphene_std::Color;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+/// A snapshot of the persistent state of a [`NodeNetworkInterface`], stored in the document's undo and redo history.
+///
+/// Each node of the root network is stored behind an [`Arc`] that is shared with the snapshot it was captured against whenever the node is unchanged,
+/// so a snapshot only allocates for the nodes that were modified since then. Node input values are already reference counted, so even a modified
+/// node only duplicates the values which themselves changed.
+#[derive(Debug, Clone)]
+pub struct NetworkSnapshot {
+	shell: Arc<SnapshotShell>,
+	nodes: HashMap<NodeId, Arc<SnapshotNode>>,
+	/// Estimated number of bytes allocated by this snapshot that aren't shared with the snapshot it was captured against.
+	unique_bytes: usize,
+}
+
+/// Everything in the root network and its metadata other than the nodes.
+#[derive(Debug)]
+struct SnapshotShell {
+	/// The root network, with empty `nodes` since they are stored separately.
+	network: NodeNetwork,
+	/// The root network's persistent metadata, with an empty `node_metadata` since that is stored alongside each node.
+	persistent_metadata: NodeNetworkPersistentMetadata,
+}
+
+impl PartialEq for SnapshotShell {
+	fn eq(&self, other: &Self) -> bool {
+		self.network.exports == other.network.exports && self.network.scope_injections == other.network.scope_injections && self.persistent_metadata == other.persistent_metadata
+	}
+}
+
+#[derive(Debug)]
+struct SnapshotNode {
+	/// Hash of the document node, used to cheaply detect whether it changed since the previous snapshot.
+	hash: u64,
+	node: DocumentNode,
+	metadata: DocumentNodeMetadata,
+}
+
+impl NetworkSnapshot {
+	/// Estimated number of bytes retained by this snapshot beyond what it shares with the snapshot it was captured against.
+	pub fn unique_bytes(&self) -> usize {
+		self.unique_bytes
+	}
+}
+
+impl NodeNetworkInterface {
+	/// Captures the persistent state of the network for the undo history, sharing every unchanged node with `previous` when provided.
+	pub fn snapshot(&self, previous: Option<&NetworkSnapshot>) -> NetworkSnapshot {
+		let network = self.document_network();
+		let persistent_metadata = &self.network_metadata.persistent_metadata;
+		let mut unique_bytes = size_of::<NetworkSnapshot>();
+
+		let shell = SnapshotShell {
+			network: NodeNetwork {
+				exports: network.exports.clone(),
+				nodes: Default::default(),
+				scope_injections: network.scope_injections.clone(),
+				generated: network.generated,
+			},
+			persistent_metadata: NodeNetworkPersistentMetadata {
+				reference: persistent_metadata.reference.clone(),
+				node_metadata: HashMap::new(),
+				previewing: persistent_metadata.previewing.clone(),
+				navigation_metadata: persistent_metadata.navigation_metadata.clone(),
+				selection_undo_history: persistent_metadata.selection_undo_history.clone(),
+				selection_redo_history: persistent_metadata.selection_redo_history.clone(),
+			},
+		};
+		let shell = match previous {
+			Some(previous) if *previous.shell == shell => previous.shell.clone(),
+			_ => {
+				unique_bytes += size_of::<SnapshotShell>() + size_of_val(shell.network.exports.as_slice());
+				Arc::new(shell)
+			}
+		};
+
+		let mut nodes = HashMap::with_capacity(network.nodes.len());
+		for (node_id, node) in &network.nodes {
+			let Some(metadata) = persistent_metadata.node_metadata.get(node_id) else {
+				log::error!("Could not get node metadata for node {node_id} in NodeNetworkInterface::snapshot");
+				continue;
+			};
+
+			let mut hasher = DefaultHasher::new();
+			node.hash(&mut hasher);
+			let hash = hasher.finish();
+
+			let previous_node = previous.and_then(|previous| previous.nodes.get(node_id));
+			if let Some(previous_node) = previous_node.filter(|previous_node| previous_node.hash == hash && previous_node.metadata == *metadata) {
+				nodes.insert(*node_id, previous_node.clone());
+				continue;
+			}
+
+			// Values that the previous version of this node already held are shared with it, so they don't count towards this snapshot's size
+			let mut shared_values = HashSet::new();
+			if let Some(previous_node) = previous_node {
+				collect_value_hashes(&previous_node.node, &mut shared_values);
+			}
+			unique_bytes += size_of::<SnapshotNode>() + estimated_node_bytes(node, &shared_values);
+
+			let snapshot_node = SnapshotNode {
+				hash,
+				node: node.clone(),
+				metadata: metadata.clone(),
+			};
+			nodes.insert(*node_id, Arc::new(snapshot_node));
+		}
+		unique_bytes += nodes.capacity() * size_of::<(NodeId, Arc<SnapshotNode>)>();
+
+		NetworkSnapshot { shell, nodes, unique_bytes }
+	}
+
+	/// Rebuilds a network interface from a snapshot in the undo history. Transient metadata starts out unloaded.
+	pub fn from_snapshot(snapshot: &NetworkSnapshot) -> Self {
+		let network = NodeNetwork {
+			exports: snapshot.shell.network.exports.clone(),
+			nodes: snapshot.nodes.iter().map(|(node_id, snapshot_node)| (*node_id, snapshot_node.node.clone())).collect(),
+			scope_injections: snapshot.shell.network.scope_injections.clone(),
+			generated: snapshot.shell.network.generated,
+		};
+
+		let shell_metadata = &snapshot.shell.persistent_metadata;
+		let persistent_metadata = NodeNetworkPersistentMetadata {
+			reference: shell_metadata.reference.clone(),
+			node_metadata: snapshot.nodes.iter().map(|(node_id, snapshot_node)| (*node_id, snapshot_node.metadata.clone())).collect(),
+			previewing: shell_metadata.previewing.clone(),
+			navigation_metadata: shell_metadata.navigation_metadata.clone(),
+			selection_undo_history: shell_metadata.selection_undo_history.clone(),
+			selection_redo_history: shell_metadata.selection_redo_history.clone(),
+		};
+
+		Self {
+			network: MemoNetwork::new(network),
+			network_metadata: NodeNetworkMetadata {
+				persistent_metadata,
+				transient_metadata: Default::default(),
+			},
+			document_metadata: Default::default(),
+			resolved_types: Default::default(),
+			transaction_status: TransactionStatus::Finished,
+		}
+	}
+}
+
+/// Collects the hashes of every value input in the node, including those within its nested network.
+fn collect_value_hashes(node: &DocumentNode, hashes: &mut HashSet<u64>) {
+	for input in &node.inputs {
+		if let NodeInput::Value { tagged_value, .. } = input {
+			hashes.insert(tagged_value.hash_code());
+		}
+	}
+	if let DocumentNodeImplementation::Network(network) = &node.implementation {
+		for nested_node in network.nodes.values() {
+			collect_value_hashes(nested_node, hashes);
+		}
+	}
+}
+
+/// Approximates the memory used by a node, excluding any values whose hashes are in `shared_values` because another snapshot already holds them.
+fn estimated_node_bytes(node: &DocumentNode, shared_values: &HashSet<u64>) -> usize {
+	let mut bytes = size_of::<DocumentNode>() + size_of_val(node.inputs.as_slice());
+
+	for input in &node.inputs {
+		if let NodeInput::Value { tagged_value, .. } = input
+			&& !shared_values.contains(&tagged_value.hash_code())
+		{
+			bytes += estimated_value_bytes(tagged_value);
+		}
+	}
+	if let DocumentNodeImplementation::Network(network) = &node.implementation {
+		bytes += network.nodes.values().map(|nested_node| estimated_node_bytes(nested_node, shared_values)).sum::<usize>();
+	}
+
+	bytes
+}
+
+/// Approximates the heap memory used by a value. Only the kinds of data which can grow large are measured.
+fn estimated_value_bytes(value: &TaggedValue) -> usize {
+	let heap_bytes = match value {
+		TaggedValue::Raster(table) => table.iter().map(|row| row.element.data().data.len() * size_of::<Color>()).sum(),
+		TaggedValue::Vector(table) => table.iter().map(|row| row.element.point_domain.positions().len() * size_of::<DVec2>()).sum(),
+		TaggedValue::BrushStrokes(strokes) => strokes.iter().map(|stroke| size_of_val(stroke.trace.as_slice())).sum(),
+		TaggedValue::String(string) => string.len(),
+		_ => 0,
+	};
+	size_of::<TaggedValue>() + heap_bytes
+}
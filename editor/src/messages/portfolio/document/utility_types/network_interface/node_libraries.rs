@@ -0,0 +1,265 @@
+use super::{InputMetadata, NodeNetworkInterface, NodeTypePersistentMetadata};
+use crate::messages::portfolio::document::node_graph::document_node_definitions::node_library::{NodeLibrary, NodeLibraryNode, newer_library_version};
+use crate::messages::portfolio::document::node_graph::document_node_definitions::{DefinitionIdentifier, resolve_document_node_type};
+use glam::IVec2;
+use graph_craft::document::{DocumentNodeImplementation, NodeId};
+use std::collections::{HashMap, HashSet};
+
+/// Identifies the node of a [`NodeLibrary`] which a document node was created from.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NodeLibraryReference {
+	/// The name of the library.
+	pub library: String,
+	/// The identifier of the node in the library.
+	pub node: String,
+	/// The version of the library that the node was created from or last upgraded to.
+	pub version: u32,
+}
+
+impl NodeNetworkInterface {
+	/// The library node which the node was created from, if any.
+	pub fn library_reference(&self, node_id: &NodeId, network_path: &[NodeId]) -> Option<&NodeLibraryReference> {
+		self.node_metadata(node_id, network_path)?.persistent_metadata.library.as_ref()
+	}
+
+	/// The version of the node's library which the node can be upgraded to, if one newer than the node's was imported.
+	pub fn library_upgrade_version(&self, node_id: &NodeId, network_path: &[NodeId]) -> Option<u32> {
+		newer_library_version(self.library_reference(node_id, network_path)?)
+	}
+
+	/// Packages subgraph nodes into a node library, where each node's current input values become the defaults of the library node.
+	/// The library's version follows both the previously exported version and the newest version of it that any of the nodes were created from.
+	/// Returns a description of why the library can't be created if any of the nodes isn't a subgraph.
+	pub fn create_node_library(&mut self, name: String, previous_version: u32, node_ids: &[NodeId], network_path: &[NodeId]) -> Result<NodeLibrary, String> {
+		if node_ids.is_empty() {
+			return Err("Select the subgraph nodes which should be included in the node library.".to_string());
+		}
+
+		let mut version = previous_version;
+		let mut identifiers = HashSet::new();
+		let mut nodes = Vec::with_capacity(node_ids.len());
+		for node_id in node_ids {
+			let Some(node_template) = self.create_node_template(node_id, network_path) else {
+				return Err("Could not copy the selected nodes.".to_string());
+			};
+			if !matches!(node_template.document_node.implementation, DocumentNodeImplementation::Network(_)) {
+				return Err(format!(
+					"The \"{}\" node is not a subgraph, so it can't be included in a node library.",
+					self.display_name(node_id, network_path)
+				));
+			}
+
+			// Inputs connected to other nodes are given their current values, since the connections can't be part of the library
+			let mut node_template = self.map_ids(node_template, node_id, &HashMap::new(), network_path);
+
+			let metadata = &mut node_template.persistent_node_metadata;
+			let library_reference = metadata.library.take();
+			let display_name = std::mem::take(&mut metadata.display_name);
+			let identifier = if !display_name.is_empty() {
+				display_name
+			} else if let Some(reference) = &library_reference {
+				reference.node.clone()
+			} else {
+				self.implementation_name(node_id, network_path)
+			};
+			if !identifiers.insert(identifier.clone()) {
+				return Err(format!(
+					"More than one of the selected nodes is named \"{identifier}\". Rename them so each node in the library has its own name."
+				));
+			}
+			if let Some(reference) = library_reference.as_ref().filter(|reference| reference.library == name) {
+				version = version.max(reference.version);
+			}
+
+			let definition = self.reference(node_id, network_path).as_ref().and_then(resolve_document_node_type);
+			let category = match (&library_reference, definition) {
+				(Some(_), Some(definition)) => definition.category.to_string(),
+				_ => String::new(),
+			};
+			let description = definition
+				.map(|definition| definition.description.to_string())
+				.filter(|description| description != "TODO")
+				.unwrap_or_default();
+
+			// The library node starts out unplaced and without any of the state of this particular instance
+			metadata.locked = false;
+			metadata.pinned = false;
+			metadata.symbol = None;
+			metadata.export_presets.clear();
+			metadata.node_type_metadata = if metadata.is_layer() {
+				NodeTypePersistentMetadata::layer(IVec2::ZERO)
+			} else {
+				NodeTypePersistentMetadata::node(IVec2::ZERO)
+			};
+			if let Some(network_metadata) = &mut metadata.network_metadata {
+				network_metadata.persistent_metadata.reference = None;
+			}
+
+			nodes.push(NodeLibraryNode {
+				identifier,
+				category,
+				description,
+				node_template,
+			});
+		}
+
+		Ok(NodeLibrary { name, version: version + 1, nodes })
+	}
+
+	/// Replaces the network of a node created from a library with the one from the newer version of the library that was imported.
+	/// Inputs which are still found in the new version, by name or otherwise by position, keep their values and connections. Returns whether the node was upgraded.
+	pub fn upgrade_library_node(&mut self, node_id: &NodeId, network_path: &[NodeId]) -> bool {
+		let Some(reference) = self.library_reference(node_id, network_path).cloned() else { return false };
+		if newer_library_version(&reference).is_none() {
+			return false;
+		}
+		let Some(definition) = resolve_document_node_type(&DefinitionIdentifier::Network(reference.node)) else {
+			return false;
+		};
+		let mut new_template = definition.default_node_template();
+
+		let Some(old_inputs) = self.document_node(node_id, network_path).map(|node| node.inputs.clone()) else {
+			return false;
+		};
+		let old_input_names = self
+			.node_metadata(node_id, network_path)
+			.map(|metadata| metadata.persistent_metadata.input_metadata.iter().map(input_name).collect::<Vec<_>>())
+			.unwrap_or_default();
+
+		let new_input_names = new_template.persistent_node_metadata.input_metadata.iter().map(input_name).collect::<Vec<_>>();
+		for (index, input) in new_template.document_node.inputs.iter_mut().enumerate() {
+			let old_index = match new_input_names.get(index).filter(|name| !name.is_empty()) {
+				Some(name) => old_input_names.iter().position(|old_name| old_name == name),
+				None => Some(index).filter(|&index| old_input_names.get(index).is_none_or(|old_name| old_name.is_empty())),
+			};
+			if let Some(old_input) = old_index.and_then(|old_index| old_inputs.get(old_index)) {
+				*input = old_input.clone();
+			}
+		}
+
+		let library = new_template.persistent_node_metadata.library.take();
+		let output_names = std::mem::take(&mut new_template.persistent_node_metadata.output_names);
+		self.replace_implementation(node_id, network_path, &mut new_template);
+		self.replace_inputs(node_id, network_path, &mut new_template);
+		let Some(node_metadata) = self.node_metadata_mut(node_id, network_path) else { return false };
+		node_metadata.persistent_metadata.output_names = output_names;
+		node_metadata.persistent_metadata.library = library;
+
+		// Inputs connected to outputs which the new version no longer has are disconnected
+		let output_count = self.number_of_outputs(node_id, network_path);
+		let downstream = self
+			.outward_wires(network_path)
+			.into_iter()
+			.flat_map(|wires| wires.iter())
+			.flat_map(|(output, inputs)| inputs.iter().map(move |input| (*output, *input)))
+			.filter(|(output, _)| output.node_id() == Some(*node_id) && output.index() >= output_count)
+			.map(|(_, input)| input)
+			.collect::<Vec<_>>();
+		for input in downstream {
+			self.disconnect_input(&input, network_path);
+		}
+
+		self.unload_wires_for_node(node_id, network_path);
+		self.unload_node_click_targets(node_id, network_path);
+		self.unload_outward_wires(network_path);
+		self.unload_all_nodes_bounding_box(network_path);
+		true
+	}
+}
+
+fn input_name(input_metadata: &InputMetadata) -> String {
+	input_metadata.persistent_metadata.input_name.clone()
+}
+
+#[cfg(test)]
+mod node_library_tests {
+	use crate::messages::portfolio::document::node_graph::document_node_definitions::node_library::NodeLibrary;
+	use crate::messages::portfolio::document::node_graph::document_node_definitions::resolve_document_node_type;
+	use crate::messages::portfolio::document::utility_types::network_interface::InputConnector;
+	use crate::test_utils::test_prelude::*;
+	use graph_craft::document::NodeId;
+	use graph_craft::document::value::TaggedValue;
+
+	async fn export_library(editor: &mut EditorTestUtils, node_id: NodeId) -> NodeLibrary {
+		editor.handle_message(NodeGraphMessage::SelectedNodesSet { nodes: vec![node_id] }).await;
+		let content = editor
+			.handle_message(DocumentMessage::ExportNodeLibrary)
+			.await
+			.into_iter()
+			.find_map(|message| match message {
+				FrontendMessage::TriggerSaveFile { content, .. } => Some(content),
+				_ => None,
+			})
+			.expect("the node library should be saved");
+		NodeLibrary::from_file_contents(&String::from_utf8(content).unwrap()).unwrap()
+	}
+
+	async fn import_library(editor: &mut EditorTestUtils, library: &NodeLibrary) {
+		let contents = library.to_file_contents();
+		editor.handle_message(PreferencesMessage::LoadNodeLibraryFile { contents }).await;
+	}
+
+	fn library_version(editor: &EditorTestUtils, node_id: NodeId) -> Option<u32> {
+		editor.active_document().network_interface.library_reference(&node_id, &[]).map(|reference| reference.version)
+	}
+
+	#[tokio::test]
+	async fn library_nodes_are_exported_and_upgraded() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		// Only subgraph nodes can be packaged into a library
+		let rectangle = editor
+			.create_node_by_name(DefinitionIdentifier::ProtoNode(graphene_std::vector::generator_nodes::rectangle::IDENTIFIER))
+			.await;
+		let network_interface = &mut editor.active_document_mut().network_interface;
+		assert!(network_interface.create_node_library("Shapes".to_string(), 0, &[rectangle], &[]).is_err());
+
+		let transform = editor.create_node_by_name(DefinitionIdentifier::Network("Transform".into())).await;
+		editor
+			.handle_message(NodeGraphMessage::SetDisplayName {
+				node_id: transform,
+				alias: "Library Transform".to_string(),
+				skip_adding_history_step: true,
+			})
+			.await;
+
+		// Each export is given a newer version, even when none of the nodes were created from the previous one
+		let library = export_library(&mut editor, transform).await;
+		assert_eq!(library.version, 1);
+		assert_eq!(library.nodes.len(), 1);
+		assert_eq!(library.nodes[0].identifier, "Library Transform");
+		let library = export_library(&mut editor, transform).await;
+		assert_eq!(library.version, 2);
+		import_library(&mut editor, &library).await;
+
+		let library_node = editor.create_node_by_name(DefinitionIdentifier::Network("Library Transform".into())).await;
+		editor
+			.handle_message(NodeGraphMessage::SetInputValue {
+				node_id: library_node,
+				input_index: 2,
+				value: TaggedValue::F64(45.),
+			})
+			.await;
+		assert_eq!(library_version(&editor, library_node), Some(2));
+		assert_eq!(editor.active_document().network_interface.library_upgrade_version(&library_node, &[]), None);
+
+		// Upgrading to a newer version keeps the values of the inputs it still has
+		let newer_library = export_library(&mut editor, transform).await;
+		assert_eq!(newer_library.version, 3);
+		import_library(&mut editor, &newer_library).await;
+		let network_interface = &mut editor.active_document_mut().network_interface;
+		assert_eq!(network_interface.library_upgrade_version(&library_node, &[]), Some(3));
+		assert!(network_interface.upgrade_library_node(&library_node, &[]));
+		assert_eq!(library_version(&editor, library_node), Some(3));
+		let rotation = editor.active_document().network_interface.input_from_connector(&InputConnector::node(library_node, 2), &[]);
+		assert_eq!(rotation.and_then(|input| input.as_value()), Some(&TaggedValue::F64(45.)));
+
+		// Importing a changed library replaces its nodes, even at the same version
+		let mut changed_library = newer_library.clone();
+		changed_library.nodes[0].description = "Rotates and scales".to_string();
+		import_library(&mut editor, &changed_library).await;
+		let definition = resolve_document_node_type(&DefinitionIdentifier::Network("Library Transform".into())).unwrap();
+		assert_eq!(definition.description, "Rotates and scales");
+	}
+}
@@ -0,0 +1,325 @@
+use super::{
+	DocumentNodeMetadata, DocumentNodePersistentMetadata, FlowType, InputConnector, NodeNetworkInterface, NodeNetworkMetadata, NodeNetworkPersistentMetadata, NodeTemplate, NodeTypePersistentMetadata,
+	OutputConnector,
+};
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use glam::IVec2;
+use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeId, NodeInput, NodeNetwork};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+/// Grid distance between a newly created symbol definition and the layer it was created from.
+const DEFINITION_OFFSET: IVec2 = IVec2::new(-16, -4);
+
+/// The part a node plays in a reusable symbol, which is artwork defined once in the document and placed any number of times as instances.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SymbolRole {
+	/// The node stores the symbol's network in the document network. Its input values are the defaults for the inputs exposed by every instance.
+	Definition,
+	/// The node refers to the network of the symbol defined by the `definition` node in the document network instead of storing a copy of it, and exposes the definition's inputs.
+	Instance {
+		definition: NodeId,
+		/// The definition's inputs when the instance was last synced. Instance inputs which differ from these are overrides, so they are kept when the definition changes.
+		synced_inputs: Vec<NodeInput>,
+	},
+}
+
+impl NodeNetworkInterface {
+	/// The role of a node in the document network within a symbol, if any.
+	pub fn symbol_role(&self, node_id: &NodeId) -> Option<&SymbolRole> {
+		self.network_metadata.persistent_metadata.node_metadata.get(node_id)?.persistent_metadata.symbol.as_ref()
+	}
+
+	/// Finds the symbol instance node in the chain of nodes feeding the content of the layer.
+	pub fn layer_symbol_instance(&self, layer: LayerNodeIdentifier) -> Option<NodeId> {
+		if layer == LayerNodeIdentifier::ROOT_PARENT {
+			return None;
+		}
+		self.upstream_flow_back_from_nodes(vec![layer.to_node()], &[], FlowType::HorizontalFlow)
+			.skip(1)
+			.find(|node_id| matches!(self.symbol_role(node_id), Some(SymbolRole::Instance { .. })))
+	}
+
+	/// The definition node of the symbol which the node is an instance of.
+	pub fn symbol_definition(&self, instance: &NodeId) -> Option<NodeId> {
+		match self.symbol_role(instance) {
+			Some(SymbolRole::Instance { definition, .. }) => Some(*definition),
+			_ => None,
+		}
+	}
+
+	/// The definition node of the symbol which the node in the document network is an instance of, if it still exists.
+	fn instance_definition_node(&self, node_id: &NodeId) -> Option<&DocumentNode> {
+		let definition = self.symbol_definition(node_id)?;
+		self.document_network().nodes.get(&definition)
+	}
+
+	/// The implementation of a node in the document network, which for a symbol instance is the network of its definition.
+	pub(super) fn symbol_implementation(&self, node_id: &NodeId) -> Option<&DocumentNodeImplementation> {
+		self.instance_definition_node(node_id).map(|definition| &definition.implementation)
+	}
+
+	/// Replaces a symbol instance at the start of the network path with its definition, since the network inside an instance is the one of its definition.
+	pub(super) fn symbol_network_path<'a>(&self, network_path: &'a [NodeId]) -> Cow<'a, [NodeId]> {
+		let Some((node_id, rest)) = network_path.split_first() else { return Cow::Borrowed(network_path) };
+		match self.symbol_definition(node_id).filter(|definition| self.document_network().nodes.contains_key(definition)) {
+			Some(definition) => Cow::Owned(std::iter::once(definition).chain(rest.iter().copied()).collect()),
+			None => Cow::Borrowed(network_path),
+		}
+	}
+
+	/// The network and its metadata of the symbol which the node is an instance of, for a copy of the instance which no longer refers to its definition.
+	pub(super) fn symbol_instance_content(&self, node_id: &NodeId) -> Option<(DocumentNodeImplementation, Option<NodeNetworkMetadata>)> {
+		let definition = self.symbol_definition(node_id)?;
+		let implementation = self.document_network().nodes.get(&definition)?.implementation.clone();
+		let network_metadata = self.network_metadata.persistent_metadata.node_metadata.get(&definition)?.persistent_metadata.network_metadata.clone();
+		Some((implementation, network_metadata))
+	}
+
+	/// A copy of the document network in which every symbol instance has the network of its definition, ready to be compiled.
+	/// Inputs which the instance hasn't been synced with yet are given the values of the definition.
+	pub fn executable_network(&self) -> NodeNetwork {
+		let mut network = self.document_network().clone();
+		for (node_id, metadata) in &self.network_metadata.persistent_metadata.node_metadata {
+			let Some(SymbolRole::Instance { definition, .. }) = &metadata.persistent_metadata.symbol else {
+				continue;
+			};
+			let Some(definition_node) = self.document_network().nodes.get(definition) else { continue };
+			let Some(instance_node) = network.nodes.get_mut(node_id) else { continue };
+
+			instance_node.implementation = definition_node.implementation.clone();
+			instance_node.inputs.truncate(definition_node.inputs.len());
+			let synced_len = instance_node.inputs.len();
+			instance_node.inputs.extend(definition_node.inputs[synced_len..].iter().cloned());
+		}
+		network
+	}
+
+	/// Turns the content of a layer into a new symbol. The content is moved into a definition node beside the layer in the document network, and replaced by an instance of it.
+	/// Returns the ids of the definition and instance nodes, or a description of why the layer can't become a symbol.
+	pub fn create_symbol(&mut self, layer: LayerNodeIdentifier) -> Result<(NodeId, NodeId), String> {
+		let layer_node = layer.to_node();
+		let Some(content_input @ NodeInput::Node { node_id: content_node, .. }) = self.document_node(&layer_node, &[]).and_then(|node| node.inputs.get(1)).cloned() else {
+			return Err("The layer has no content to turn into a symbol.".to_string());
+		};
+
+		let content_ids = self.upstream_flow_back_from_nodes(vec![layer_node], &[], FlowType::LayerChildrenUpstreamFlow).collect::<HashSet<_>>();
+
+		// The content is moved into the definition, so it can't also be used by anything outside the layer
+		let Some(outward_wires) = self.outward_wires(&[]).cloned() else {
+			return Err("Could not read the connections of the layer's content.".to_string());
+		};
+		let used_elsewhere = outward_wires.iter().any(|(output_connector, downstream)| {
+			let OutputConnector::Node { node_id, .. } = output_connector else { return false };
+			content_ids.contains(node_id)
+				&& downstream.iter().any(|input_connector| match input_connector {
+					InputConnector::Node { node_id, input_index } => !content_ids.contains(node_id) && !(*node_id == layer_node && *input_index == 1),
+					InputConnector::Export(_) => true,
+				})
+		});
+		if used_elsewhere {
+			return Err("Part of the layer's content is also used elsewhere in the document, so it can't be moved into a symbol.".to_string());
+		}
+
+		// Nodes positioned relative to the layer are given absolute positions, since the layer stays outside the definition
+		let relative_to_layer = self
+			.upstream_flow_back_from_nodes(vec![content_node], &[], FlowType::PrimaryFlow)
+			.take_while(|node_id| *node_id == content_node || self.is_chain(node_id, &[]))
+			.collect::<Vec<_>>();
+		let mut absolute_positions = HashMap::new();
+		for node_id in relative_to_layer {
+			if let Some(position) = self.position(&node_id, &[]) {
+				absolute_positions.insert(node_id, position);
+			}
+		}
+		let layer_position = self.position(&layer_node, &[]).unwrap_or_default();
+
+		let mut nodes = HashMap::with_capacity(content_ids.len());
+		let mut node_metadata = HashMap::with_capacity(content_ids.len());
+		for node_id in &content_ids {
+			let Some(mut node_template) = self.create_node_template(node_id, &[]) else {
+				return Err("Could not copy the layer's content.".to_string());
+			};
+			if let Some(&position) = absolute_positions.get(node_id) {
+				node_template.persistent_node_metadata.node_type_metadata = if node_template.persistent_node_metadata.is_layer() {
+					NodeTypePersistentMetadata::layer(position)
+				} else {
+					NodeTypePersistentMetadata::node(position)
+				};
+			}
+			nodes.insert(*node_id, node_template.document_node);
+			node_metadata.insert(
+				*node_id,
+				DocumentNodeMetadata {
+					persistent_metadata: node_template.persistent_node_metadata,
+					transient_metadata: Default::default(),
+				},
+			);
+		}
+
+		let definition_template = NodeTemplate {
+			document_node: DocumentNode {
+				implementation: DocumentNodeImplementation::Network(NodeNetwork {
+					exports: vec![content_input],
+					nodes: nodes.into_iter().collect(),
+					..Default::default()
+				}),
+				..Default::default()
+			},
+			persistent_node_metadata: DocumentNodePersistentMetadata {
+				display_name: self.display_name(&layer_node, &[]),
+				output_names: vec!["Content".to_string()],
+				node_type_metadata: NodeTypePersistentMetadata::node(layer_position + DEFINITION_OFFSET),
+				network_metadata: Some(NodeNetworkMetadata {
+					persistent_metadata: NodeNetworkPersistentMetadata { node_metadata, ..Default::default() },
+					transient_metadata: Default::default(),
+				}),
+				symbol: Some(SymbolRole::Definition),
+				..Default::default()
+			},
+		};
+
+		let definition = NodeId::new();
+		let instance = NodeId::new();
+		let mut instance_template = definition_template.clone();
+		instance_template.document_node.implementation = DocumentNodeImplementation::Network(NodeNetwork::default());
+		instance_template.persistent_node_metadata.network_metadata = Some(NodeNetworkMetadata::default());
+		instance_template.persistent_node_metadata.symbol = Some(SymbolRole::Instance {
+			definition,
+			synced_inputs: Vec::new(),
+		});
+
+		// Replace the content with the instance
+		self.disconnect_input(&InputConnector::node(layer_node, 1), &[]);
+		for node_id in &content_ids {
+			self.document_network_mut().nodes.remove(node_id);
+			self.network_metadata.persistent_metadata.node_metadata.remove(node_id);
+		}
+		if let Some(selected_nodes) = self.selected_nodes_mut(&[]) {
+			selected_nodes.retain_selected_nodes(|node_id| !content_ids.contains(node_id));
+		}
+		self.transaction_modified();
+		self.unload_outward_wires(&[]);
+		self.unload_all_nodes_click_targets(&[]);
+
+		self.insert_node(definition, definition_template, &[]);
+		self.insert_node(instance, instance_template, &[]);
+		self.set_input(&InputConnector::node(layer_node, 1), NodeInput::node(instance, 0), &[]);
+		self.set_chain_position(&instance, &[]);
+		self.load_structure();
+
+		Ok((definition, instance))
+	}
+
+	/// Turns a symbol instance into an independent copy of the symbol's network, which is no longer updated when the definition changes.
+	pub fn detach_symbol_instance(&mut self, instance: &NodeId) {
+		if !matches!(self.symbol_role(instance), Some(SymbolRole::Instance { .. })) {
+			return;
+		}
+
+		// Copies of instances made where the definition doesn't exist already carry its content
+		if let Some((implementation, network_metadata)) = self.symbol_instance_content(instance) {
+			let Some(instance_node) = self.document_network_mut().nodes.get_mut(instance) else { return };
+			instance_node.implementation = implementation;
+			let Some(instance_metadata) = self.node_metadata_mut(instance, &[]) else { return };
+			instance_metadata.persistent_metadata.network_metadata = network_metadata;
+		}
+		let Some(instance_metadata) = self.node_metadata_mut(instance, &[]) else { return };
+		instance_metadata.persistent_metadata.symbol = None;
+		self.transaction_modified();
+		self.unload_node_click_targets(instance, &[]);
+	}
+
+	/// Detaches the instances of a symbol definition which is about to be deleted, so they keep its content.
+	pub(super) fn detach_symbol_instances_of(&mut self, definition: &NodeId) {
+		let instances = self
+			.network_metadata
+			.persistent_metadata
+			.node_metadata
+			.iter()
+			.filter(|(_, metadata)| matches!(&metadata.persistent_metadata.symbol, Some(SymbolRole::Instance { definition: instance_definition, .. }) if instance_definition == definition))
+			.map(|(node_id, _)| *node_id)
+			.collect::<Vec<_>>();
+		for instance in instances {
+			self.detach_symbol_instance(&instance);
+		}
+	}
+
+	/// Updates the inputs of every symbol instance in the document network to match its definition, keeping the inputs overridden by each instance.
+	/// Copies of instances which carry the content of their definition are made to refer to it again, and those whose definition doesn't exist are detached.
+	/// Returns whether any instance changed.
+	pub fn sync_symbol_instances(&mut self) -> bool {
+		let instances = self
+			.network_metadata
+			.persistent_metadata
+			.node_metadata
+			.iter()
+			.filter_map(|(node_id, metadata)| match &metadata.persistent_metadata.symbol {
+				Some(SymbolRole::Instance { definition, synced_inputs }) => Some((*node_id, *definition, synced_inputs.clone())),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+
+		let reference = DocumentNodeImplementation::Network(NodeNetwork::default());
+		let mut changed = false;
+		for (instance, definition, synced_inputs) in instances {
+			let network = self.document_network();
+			let (Some(definition_node), Some(instance_node)) = (network.nodes.get(&definition), network.nodes.get(&instance)) else {
+				self.detach_symbol_instance(&instance);
+				changed = true;
+				continue;
+			};
+
+			let carries_content = instance_node.implementation != reference;
+			let inputs = definition_node
+				.inputs
+				.iter()
+				.enumerate()
+				.map(|(index, definition_input)| match (instance_node.inputs.get(index), synced_inputs.get(index)) {
+					(Some(instance_input), Some(synced_input)) if instance_input != synced_input => instance_input.clone(),
+					_ => definition_input.clone(),
+				})
+				.collect::<Vec<_>>();
+			let inputs_changed = inputs != instance_node.inputs;
+			let definition_inputs = definition_node.inputs.clone();
+
+			let Some(definition_metadata) = self.node_metadata(&definition, &[]).map(|metadata| &metadata.persistent_metadata) else {
+				continue;
+			};
+			let Some(instance_metadata) = self.node_metadata(&instance, &[]).map(|metadata| &metadata.persistent_metadata) else {
+				continue;
+			};
+			let metadata_changed = instance_metadata.input_metadata != definition_metadata.input_metadata || instance_metadata.output_names != definition_metadata.output_names;
+			if !carries_content && !inputs_changed && !metadata_changed && synced_inputs == definition_inputs {
+				continue;
+			}
+			let input_metadata = definition_metadata.input_metadata.clone();
+			let output_names = definition_metadata.output_names.clone();
+
+			let Some(instance_node) = self.document_network_mut().nodes.get_mut(&instance) else { continue };
+			instance_node.implementation = reference.clone();
+			instance_node.inputs = inputs;
+
+			let Some(instance_metadata) = self.node_metadata_mut(&instance, &[]) else { continue };
+			let instance_metadata = &mut instance_metadata.persistent_metadata;
+			instance_metadata.network_metadata = Some(NodeNetworkMetadata::default());
+			instance_metadata.input_metadata = input_metadata;
+			instance_metadata.output_names = output_names;
+			instance_metadata.symbol = Some(SymbolRole::Instance {
+				definition,
+				synced_inputs: definition_inputs,
+			});
+
+			self.transaction_modified();
+			self.unload_wires_for_node(&instance, &[]);
+			self.unload_node_click_targets(&instance, &[]);
+			changed = true;
+		}
+
+		if changed {
+			self.unload_outward_wires(&[]);
+			self.unload_all_nodes_bounding_box(&[]);
+		}
+		changed
+	}
+}
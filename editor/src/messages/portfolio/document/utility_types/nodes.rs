@@ -62,6 +62,8 @@ pub struct LayerPanelEntry {
 	pub descendant_of_selected: bool,
 	pub clipped: bool,
 	pub clippable: bool,
+	#[serde(rename = "symbolInstance")]
+	pub symbol_instance: bool,
 }
 
 /// IMPORTANT: the same node may appear multiple times.
@@ -1070,6 +1070,14 @@ fn migrate_node(node_id: &NodeId, node: &DocumentNode, network_path: &[NodeId],
 		document.network_interface.set_call_argument(node_id, network_path, graph_craft::concrete!(graphene_std::Context));
 	}
 
+	// Upgrade the Decode Image node to add the "Working Space" input, including where it's used inside the "Load Image" node that has no definition reference
+	if node.implementation == DocumentNodeImplementation::ProtoNode(graphene_std::wasm_application_io::decode_image::IDENTIFIER) && node.inputs.len() == 1 {
+		let mut node_template = resolve_proto_node_type(graphene_std::wasm_application_io::decode_image::IDENTIFIER)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		document.network_interface.set_input(&InputConnector::node(*node_id, 0), old_inputs[0].clone(), network_path);
+	}
+
 	// Only nodes that have not been modified and still refer to a definition can be updated
 	let reference = document.network_interface.reference(node_id, network_path)?;
 
@@ -1682,6 +1690,40 @@ fn migrate_node(node_id: &NodeId, node: &DocumentNode, network_path: &[NodeId],
 		document.network_interface.set_input(&InputConnector::node(*node_id, 1), old_inputs[2].clone(), network_path);
 	}
 
+	// Upgrade the Sample Gradient node to add the "Interpolation" input
+	if reference == DefinitionIdentifier::ProtoNode(graphene_std::math_nodes::sample_gradient::IDENTIFIER) && inputs_count == 3 {
+		let mut node_template = resolve_document_node_type(&reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (i, input) in old_inputs.iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, i), input.clone(), network_path);
+		}
+	}
+
+	// Upgrade the Math node to add the "Variable Names" input, which binds its variable inputs by name instead of by the order the variables appear in the expression
+	if reference == DefinitionIdentifier::ProtoNode(graphene_std::math_nodes::math::IDENTIFIER) && !matches!(node.inputs.get(3).and_then(|input| input.as_value()), Some(TaggedValue::VecString(_))) {
+		let mut node_template = resolve_document_node_type(&reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (i, input) in old_inputs.iter().enumerate().take(3) {
+			document.network_interface.set_input(&InputConnector::node(*node_id, i), input.clone(), network_path);
+		}
+
+		let variable_names = match old_inputs.get(1).and_then(|input| input.as_value()) {
+			Some(TaggedValue::String(expression)) => graphene_std::math_nodes::math_expression_variables(expression).unwrap_or_default(),
+			_ => Vec::new(),
+		};
+		document.network_interface.set_named_inputs(node_id, 4, &variable_names, TaggedValue::F64(0.), network_path);
+		document.network_interface.set_input(
+			&InputConnector::node(*node_id, 3),
+			NodeInput::value(TaggedValue::VecString(variable_names.clone()), false),
+			network_path,
+		);
+		for (i, input) in old_inputs.iter().enumerate().skip(3).take(variable_names.len()) {
+			document.network_interface.set_input(&InputConnector::node(*node_id, i + 1), input.clone(), network_path);
+		}
+	}
+
 	// Add context features to nodes that don't have them (fine-grained context caching migration)
 	if node.context_features == graphene_std::ContextDependencies::default()
 		&& let Some(reference) = document.network_interface.reference(node_id, network_path).clone()
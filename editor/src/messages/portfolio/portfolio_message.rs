@@ -6,6 +6,7 @@ use crate::messages::portfolio::utility_types::FontCatalog;
 use crate::messages::prelude::*;
 use graphene_std::Color;
 use graphene_std::raster::Image;
+use graphene_std::raster_types::color_management::ColorSpace;
 use graphene_std::text::Font;
 use std::path::PathBuf;
 
@@ -115,6 +116,9 @@ pub enum PortfolioMessage {
 	SetActivePanel {
 		panel: PanelType,
 	},
+	SetNodeProfiling {
+		enabled: bool,
+	},
 	SelectDocument {
 		document_id: DocumentId,
 	},
@@ -126,17 +130,23 @@ pub enum PortfolioMessage {
 		transparent_background: bool,
 		artboard_name: Option<String>,
 		artboard_count: usize,
+		color_profile: ColorSpace,
+		embed_color_profile: bool,
 	},
 	SubmitActiveGraphRender,
+	SubmitBatchExport,
 	SubmitGraphRender {
 		document_id: DocumentId,
 		ignore_hash: bool,
 	},
 	ToggleDataPanelOpen,
+	ToggleHistoryPanelOpen,
 	TogglePropertiesPanelOpen,
 	ToggleLayersPanelOpen,
 	ToggleRulers,
+	TrimDocumentHistories,
 	UpdateDocumentWidgets,
+	UpdateNodeCacheMemoryBudget,
 	UpdateOpenDocumentsList,
 	UpdateVelloPreference,
 }
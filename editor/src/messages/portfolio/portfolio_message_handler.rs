@@ -6,7 +6,7 @@ use crate::consts::{DEFAULT_DOCUMENT_NAME, DEFAULT_STROKE_WIDTH, FILE_EXTENSION}
 use crate::messages::animation::TimingInformation;
 use crate::messages::clipboard::utility_types::ClipboardContent;
 use crate::messages::dialog::simple_dialogs;
-use crate::messages::frontend::utility_types::{DocumentDetails, OpenDocument};
+use crate::messages::frontend::utility_types::{DocumentDetails, ExportBounds, ExportTarget, FileType, OpenDocument};
 use crate::messages::input_mapper::utility_types::input_keyboard::Key;
 use crate::messages::input_mapper::utility_types::macros::{action_shortcut, action_shortcut_manual};
 use crate::messages::layout::utility_types::widget_prelude::*;
@@ -58,6 +58,7 @@ pub struct PortfolioMessageHandler {
 	pub selection_mode: SelectionMode,
 	pub reset_node_definitions_on_open: bool,
 	pub data_panel_open: bool,
+	pub history_panel_open: bool,
 	#[derivative(Default(value = "true"))]
 	pub layers_panel_open: bool,
 	#[derivative(Default(value = "true"))]
@@ -92,6 +93,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 						preferences,
 						viewport,
 						data_panel_open: self.data_panel_open,
+						history_panel_open: self.history_panel_open,
 						layers_panel_open: self.layers_panel_open,
 						properties_panel_open: self.properties_panel_open,
 					};
@@ -149,6 +151,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 						preferences,
 						viewport,
 						data_panel_open: self.data_panel_open,
+						history_panel_open: self.history_panel_open,
 						layers_panel_open: self.layers_panel_open,
 						properties_panel_open: self.properties_panel_open,
 					};
@@ -198,6 +201,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					responses.add(PropertiesPanelMessage::Clear);
 					responses.add(DocumentMessage::ClearLayersPanel);
 					responses.add(DataPanelMessage::ClearLayout);
+					responses.add(DocumentMessage::ClearHistoryPanel);
 					HintData::clear_layout(responses);
 				}
 
@@ -222,6 +226,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					responses.add(PropertiesPanelMessage::Clear);
 					responses.add(DocumentMessage::ClearLayersPanel);
 					responses.add(DataPanelMessage::ClearLayout);
+					responses.add(DocumentMessage::ClearHistoryPanel);
 					HintData::clear_layout(responses);
 				}
 
@@ -623,7 +628,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 						for entry in data.into_iter().rev() {
 							if !added_nodes {
 								responses.add(DocumentMessage::DeselectAllLayers);
-								responses.add(DocumentMessage::AddTransaction);
+								responses.add(DocumentMessage::AddTransaction { label: "Paste".into() });
 								added_nodes = true;
 							}
 
@@ -806,7 +811,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 						return;
 					}
 
-					responses.add(DocumentMessage::AddTransaction);
+					responses.add(DocumentMessage::AddTransaction { label: "Paste".into() });
 
 					for &item_id in &top_level_items_to_center {
 						if document.network_interface.is_artboard(&item_id.to_node(), &document.node_graph_handler.network) {
@@ -979,6 +984,13 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				self.active_panel = panel;
 				responses.add(DocumentMessage::SetActivePanel { active_panel: self.active_panel });
 			}
+			PortfolioMessage::SetNodeProfiling { enabled } => {
+				self.executor.set_node_profiling(enabled);
+				responses.add(NodeGraphMessage::UpdateNodeProfile {
+					report: enabled.then(Default::default),
+				});
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
 			PortfolioMessage::SelectDocument { document_id } => {
 				// Auto-save the document we are leaving
 				let mut node_graph_open = false;
@@ -1007,6 +1019,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				responses.add(NavigationMessage::CanvasPan { delta: (0., 0.).into() });
 				responses.add(NodeGraphMessage::RunDocumentGraph);
 				responses.add(DocumentMessage::GraphViewOverlay { open: node_graph_open });
+				responses.add(DocumentMessage::UpdateHistoryPanel);
 				if node_graph_open {
 					responses.add(NodeGraphMessage::UpdateGraphBarRight);
 					responses.add(NodeGraphMessage::UnloadWires);
@@ -1036,6 +1049,8 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				transparent_background,
 				artboard_name,
 				artboard_count,
+				color_profile,
+				embed_color_profile,
 			} => {
 				let document = self.active_document_id.and_then(|id| self.documents.get_mut(&id)).expect("Tried to render non-existent document");
 				let export_config = ExportConfig {
@@ -1046,6 +1061,8 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					transparent_background,
 					artboard_name,
 					artboard_count,
+					color_profile,
+					embed_color_profile,
 					..Default::default()
 				};
 				let result = self.executor.submit_document_export(document, self.active_document_id.unwrap(), export_config);
@@ -1057,6 +1074,51 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					});
 				}
 			}
+			PortfolioMessage::SubmitBatchExport => {
+				let Some(document_id) = self.active_document_id else { return };
+				let Some(document) = self.documents.get_mut(&document_id) else { return };
+
+				// Files are written in the document's working color space, so its colors are exported without conversion and tagged with its profile
+				let color_profile = document.working_color_space;
+
+				let mut export_configs = Vec::new();
+				for (target, name, presets) in document.export_preset_targets() {
+					let bounds = match target {
+						ExportTarget::Layer(layer) if document.network_interface.is_artboard(&layer.to_node(), &[]) => ExportBounds::Artboard(layer),
+						ExportTarget::Layer(layer) => ExportBounds::Layer(layer),
+						ExportTarget::Slice(id) => ExportBounds::Slice(id),
+					};
+					for preset in presets {
+						for scale_factor in preset.export_scales() {
+							export_configs.push(ExportConfig {
+								name: preset.file_stem(&name, scale_factor),
+								file_type: preset.file_type,
+								scale_factor,
+								bounds,
+								transparent_background: preset.file_type != FileType::Jpg && preset.transparent_background,
+								color_profile,
+								embed_color_profile: true,
+								..Default::default()
+							});
+						}
+					}
+				}
+
+				if export_configs.is_empty() {
+					responses.add(DialogMessage::DisplayDialogError {
+						title: "Nothing to export".to_string(),
+						description: "No layers, artboards, or slices in this document have export presets.".to_string(),
+					});
+					return;
+				}
+
+				if let Err(description) = self.executor.submit_batch_export(document, document_id, export_configs) {
+					responses.add(DialogMessage::DisplayDialogError {
+						title: "Unable to export everything".to_string(),
+						description,
+					});
+				}
+			}
 			PortfolioMessage::SubmitActiveGraphRender => {
 				if let Some(document_id) = self.active_document_id {
 					responses.add(PortfolioMessage::SubmitGraphRender { document_id, ignore_hash: false });
@@ -1111,6 +1173,23 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					responses.add(FrontendMessage::UpdateDataPanelState { open: self.data_panel_open });
 				}
 			}
+			PortfolioMessage::ToggleHistoryPanelOpen => {
+				self.history_panel_open = !self.history_panel_open;
+				responses.add(MenuBarMessage::SendLayout);
+
+				if self.history_panel_open {
+					// When opening, we make the frontend show the panel first so it can start receiving its message subscriptions for the data it will display
+					responses.add(FrontendMessage::UpdateHistoryPanelState { open: self.history_panel_open });
+
+					responses.add(DocumentMessage::UpdateHistoryPanel);
+				} else {
+					// If we don't clear the panel, the layout diffing system will assume widgets still exist when it attempts to update the history panel next time it is opened
+					responses.add(DocumentMessage::ClearHistoryPanel);
+
+					// When closing, we make the frontend hide the panel last so it can finish receiving its message subscriptions before it is destroyed
+					responses.add(FrontendMessage::UpdateHistoryPanelState { open: self.history_panel_open });
+				}
+			}
 			PortfolioMessage::TogglePropertiesPanelOpen => {
 				self.properties_panel_open = !self.properties_panel_open;
 				responses.add(MenuBarMessage::SendLayout);
@@ -1153,11 +1232,17 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					responses.add(MenuBarMessage::SendLayout);
 				}
 			}
+			PortfolioMessage::TrimDocumentHistories => {
+				for document in self.documents.values_mut() {
+					document.trim_history(preferences);
+				}
+			}
 			PortfolioMessage::UpdateDocumentWidgets => {
 				if let Some(document) = self.active_document() {
 					document.update_document_widgets(responses, animation.is_playing(), timing_information.animation_time);
 				}
 			}
+			PortfolioMessage::UpdateNodeCacheMemoryBudget => self.executor.update_memo_cache_budget(preferences.node_cache_memory_budget_bytes()),
 			PortfolioMessage::UpdateOpenDocumentsList => {
 				// Send the list of document tab names
 				let open_documents = self
@@ -1205,6 +1290,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 			PrevDocument,
 			ToggleRulers,
 			ToggleDataPanelOpen,
+			ToggleHistoryPanelOpen,
 		);
 
 		// Extend with actions that require an active document
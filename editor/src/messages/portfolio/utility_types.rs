@@ -121,6 +121,7 @@ pub enum PanelType {
 	Layers,
 	Properties,
 	DataPanel,
+	HistoryPanel,
 }
 
 impl From<String> for PanelType {
@@ -131,6 +132,7 @@ impl From<String> for PanelType {
 			"Layers" => PanelType::Layers,
 			"Properties" => PanelType::Properties,
 			"Data" => PanelType::DataPanel,
+			"History" => PanelType::HistoryPanel,
 			_ => panic!("Unknown panel type: {value}"),
 		}
 	}
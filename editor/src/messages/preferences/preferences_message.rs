@@ -1,3 +1,4 @@
+use crate::messages::input_mapper::utility_types::user_keymap::UserKeyBinding;
 use crate::messages::portfolio::document::utility_types::wires::GraphWireStyle;
 use crate::messages::preferences::SelectionMode;
 use crate::messages::prelude::*;
@@ -17,4 +18,15 @@ pub enum PreferencesMessage {
 	GraphWireStyle { style: GraphWireStyle },
 	ViewportZoomWheelRate { rate: f64 },
 	UIScale { scale: f64 },
+	UndoHistoryLength { length: u32 },
+	UndoHistoryMemoryBudget { megabytes: u32 },
+	NodeCacheMemoryBudget { megabytes: u32 },
+	SetKeyBinding { binding: Box<UserKeyBinding> },
+	ResetKeyBindings { action: Option<Box<Message>> },
+	ExportKeymap,
+	ImportKeymap,
+	LoadKeymapFile { contents: String },
+	ImportNodeLibrary,
+	LoadNodeLibraryFile { contents: String },
+	RemoveNodeLibrary { name: String },
 }
@@ -1,5 +1,8 @@
-use crate::consts::{UI_SCALE_DEFAULT, VIEWPORT_ZOOM_WHEEL_RATE};
+use crate::consts::{NODE_CACHE_MEMORY_BUDGET_DEFAULT_MB, UI_SCALE_DEFAULT, UNDO_HISTORY_LENGTH_DEFAULT, UNDO_HISTORY_MEMORY_BUDGET_DEFAULT_MB, VIEWPORT_ZOOM_WHEEL_RATE};
 use crate::messages::input_mapper::key_mapping::MappingVariant;
+use crate::messages::input_mapper::utility_types::misc::Mapping;
+use crate::messages::input_mapper::utility_types::user_keymap::{KEYMAP_FILE_NAME, UserKeymap};
+use crate::messages::portfolio::document::node_graph::document_node_definitions::{self, node_library, node_library::NodeLibrary};
 use crate::messages::portfolio::document::utility_types::wires::GraphWireStyle;
 use crate::messages::preferences::SelectionMode;
 use crate::messages::prelude::*;
@@ -21,6 +24,18 @@ pub struct PreferencesMessageHandler {
 	pub graph_wire_style: GraphWireStyle,
 	pub viewport_zoom_wheel_rate: f64,
 	pub ui_scale: f64,
+	/// The maximum number of steps kept in each document's undo history.
+	pub undo_history_length: u32,
+	/// The approximate amount of memory, in megabytes, that each document's undo history may use before its oldest steps are discarded.
+	pub undo_history_memory_budget: u32,
+	/// The approximate amount of memory, in megabytes, shared by the caches of the node graph's nodes before their least recently used outputs are evicted.
+	pub node_cache_memory_budget: u32,
+	/// The user's changes to the built-in keyboard shortcuts.
+	#[specta(skip)]
+	pub keymap: UserKeymap,
+	/// The imported node libraries whose nodes are added to the node catalog.
+	#[specta(skip)]
+	pub node_libraries: Vec<NodeLibrary>,
 }
 
 impl PreferencesMessageHandler {
@@ -34,9 +49,46 @@ impl PreferencesMessageHandler {
 		}
 	}
 
+	pub fn mapping_variant(&self) -> MappingVariant {
+		if self.zoom_with_scroll { MappingVariant::ZoomWithScroll } else { MappingVariant::Default }
+	}
+
+	/// The input mapping in effect, with the user's keymap applied on top of the built-in key bindings.
+	pub fn key_mapping(&self) -> Mapping {
+		let mut mapping = self.mapping_variant().into();
+		self.keymap.apply(&mut mapping);
+		mapping
+	}
+
+	pub fn undo_history_memory_budget_bytes(&self) -> usize {
+		self.undo_history_memory_budget as usize * 1024 * 1024
+	}
+
+	pub fn node_cache_memory_budget_bytes(&self) -> usize {
+		self.node_cache_memory_budget as usize * 1024 * 1024
+	}
+
 	pub fn supports_wgpu(&self) -> bool {
 		graph_craft::wasm_application_io::wgpu_available().unwrap_or_default()
 	}
+
+	/// Rebuilds the input mapping with the current keymap and updates the places where shortcuts are shown.
+	fn refresh_key_mapping(&self, responses: &mut VecDeque<Message>) {
+		responses.add(KeyMappingMessage::ModifyMapping { mapping: self.mapping_variant() });
+		responses.add(MenuBarMessage::SendLayout);
+		responses.add(PreferencesDialogMessage::Update);
+	}
+
+	/// Adds the nodes of the imported libraries to the node catalog, and updates the places where they're listed or can be upgraded.
+	fn refresh_node_libraries(&self, responses: &mut VecDeque<Message>) {
+		node_library::set_node_libraries(&self.node_libraries);
+
+		responses.add(FrontendMessage::SendUIMetadata {
+			node_descriptions: document_node_definitions::collect_node_descriptions(),
+			node_types: document_node_definitions::collect_node_types(),
+		});
+		responses.add(PropertiesPanelMessage::Refresh);
+	}
 }
 
 impl Default for PreferencesMessageHandler {
@@ -49,6 +101,11 @@ impl Default for PreferencesMessageHandler {
 			graph_wire_style: GraphWireStyle::default(),
 			viewport_zoom_wheel_rate: VIEWPORT_ZOOM_WHEEL_RATE,
 			ui_scale: UI_SCALE_DEFAULT,
+			undo_history_length: UNDO_HISTORY_LENGTH_DEFAULT,
+			undo_history_memory_budget: UNDO_HISTORY_MEMORY_BUDGET_DEFAULT_MB,
+			node_cache_memory_budget: NODE_CACHE_MEMORY_BUDGET_DEFAULT_MB,
+			keymap: UserKeymap::default(),
+			node_libraries: Vec::new(),
 		}
 	}
 }
@@ -67,16 +124,23 @@ impl MessageHandler<PreferencesMessage, PreferencesMessageContext<'_>> for Prefe
 
 				responses.add(PortfolioMessage::EditorPreferences);
 				responses.add(PortfolioMessage::UpdateVelloPreference);
+				responses.add(PortfolioMessage::UpdateNodeCacheMemoryBudget);
 				responses.add(PreferencesMessage::ModifyLayout {
 					zoom_with_scroll: self.zoom_with_scroll,
 				});
 				responses.add(FrontendMessage::UpdateUIScale { scale: self.ui_scale });
+				self.refresh_node_libraries(responses);
 			}
 			PreferencesMessage::ResetToDefaults => {
 				refresh_dialog(responses);
 				responses.add(KeyMappingMessage::ModifyMapping { mapping: MappingVariant::Default });
 
-				*self = Self::default()
+				// Imported node libraries aren't settings, so they're kept since documents may use their nodes
+				let node_libraries = std::mem::take(&mut self.node_libraries);
+				*self = Self::default();
+				self.node_libraries = node_libraries;
+
+				responses.add(PortfolioMessage::UpdateNodeCacheMemoryBudget);
 			}
 
 			// Per-preference messages
@@ -88,7 +152,11 @@ impl MessageHandler<PreferencesMessage, PreferencesMessageContext<'_>> for Prefe
 			PreferencesMessage::BrushTool { enabled } => {
 				self.brush_tool = enabled;
 
-				if !enabled && tool_message_handler.tool_state.tool_data.active_tool_type == ToolType::Brush {
+				if !enabled
+					&& matches!(
+						tool_message_handler.tool_state.tool_data.active_tool_type,
+						ToolType::Brush | ToolType::Heal | ToolType::Clone | ToolType::Patch
+					) {
 					responses.add(ToolMessage::ActivateToolSelect);
 				}
 
@@ -97,8 +165,7 @@ impl MessageHandler<PreferencesMessage, PreferencesMessageContext<'_>> for Prefe
 			PreferencesMessage::ModifyLayout { zoom_with_scroll } => {
 				self.zoom_with_scroll = zoom_with_scroll;
 
-				let variant = if zoom_with_scroll { MappingVariant::ZoomWithScroll } else { MappingVariant::Default };
-				responses.add(KeyMappingMessage::ModifyMapping { mapping: variant });
+				responses.add(KeyMappingMessage::ModifyMapping { mapping: self.mapping_variant() });
 			}
 			PreferencesMessage::SelectionMode { selection_mode } => {
 				self.selection_mode = selection_mode;
@@ -115,6 +182,76 @@ impl MessageHandler<PreferencesMessage, PreferencesMessageContext<'_>> for Prefe
 				self.ui_scale = scale;
 				responses.add(FrontendMessage::UpdateUIScale { scale: self.ui_scale });
 			}
+			PreferencesMessage::UndoHistoryLength { length } => {
+				self.undo_history_length = length.max(1);
+				responses.add(PortfolioMessage::TrimDocumentHistories);
+			}
+			PreferencesMessage::UndoHistoryMemoryBudget { megabytes } => {
+				self.undo_history_memory_budget = megabytes;
+				responses.add(PortfolioMessage::TrimDocumentHistories);
+			}
+			PreferencesMessage::NodeCacheMemoryBudget { megabytes } => {
+				self.node_cache_memory_budget = megabytes;
+				responses.add(PortfolioMessage::UpdateNodeCacheMemoryBudget);
+			}
+			PreferencesMessage::SetKeyBinding { binding } => {
+				self.keymap.set_binding(*binding);
+				self.refresh_key_mapping(responses);
+			}
+			PreferencesMessage::ResetKeyBindings { action } => {
+				match action {
+					Some(action) => self.keymap.reset_action(&action),
+					None => self.keymap = UserKeymap::default(),
+				}
+				self.refresh_key_mapping(responses);
+			}
+			PreferencesMessage::ExportKeymap => {
+				responses.add(FrontendMessage::TriggerSaveFile {
+					name: KEYMAP_FILE_NAME.to_string(),
+					content: self.keymap.to_file_contents().into_bytes(),
+				});
+			}
+			PreferencesMessage::ImportKeymap => {
+				responses.add(FrontendMessage::TriggerImportKeymap);
+			}
+			PreferencesMessage::LoadKeymapFile { contents } => match UserKeymap::from_file_contents(&contents) {
+				Ok(keymap) => {
+					self.keymap = keymap;
+					self.refresh_key_mapping(responses);
+				}
+				Err(description) => {
+					responses.add(DialogMessage::DisplayDialogError {
+						title: "Failed to import keymap".to_string(),
+						description,
+					});
+				}
+			},
+			PreferencesMessage::ImportNodeLibrary => {
+				responses.add(FrontendMessage::TriggerImportNodeLibrary);
+			}
+			PreferencesMessage::LoadNodeLibraryFile { contents } => {
+				let library = NodeLibrary::from_file_contents(&contents).and_then(|library| library.validate(&self.node_libraries).map(|_| library));
+				match library {
+					Ok(library) => {
+						// A library replaces the previously imported version of itself
+						self.node_libraries.retain(|imported| imported.name != library.name);
+						self.node_libraries.push(library);
+						self.refresh_node_libraries(responses);
+						responses.add(PreferencesDialogMessage::Update);
+					}
+					Err(description) => {
+						responses.add(DialogMessage::DisplayDialogError {
+							title: "Failed to import node library".to_string(),
+							description,
+						});
+					}
+				}
+			}
+			PreferencesMessage::RemoveNodeLibrary { name } => {
+				self.node_libraries.retain(|library| library.name != name);
+				self.refresh_node_libraries(responses);
+				responses.add(PreferencesDialogMessage::Update);
+			}
 		}
 
 		responses.add(FrontendMessage::TriggerSavePreferences { preferences: self.clone() });
@@ -1463,7 +1463,7 @@ impl ShapeState {
 
 			for point in std::mem::take(&mut state.selected_points) {
 				if !transaction_started && start_transaction {
-					responses.add(DocumentMessage::AddTransaction);
+					responses.add(DocumentMessage::AddTransaction { label: "Delete Points".into() });
 					transaction_started = true;
 				}
 
@@ -1573,7 +1573,7 @@ impl ShapeState {
 			for (segment, _, start, end) in vector.segment_bezier_iter() {
 				if state.selected_segments.contains(&segment) {
 					if start_transaction && !transaction_started {
-						responses.add(DocumentMessage::AddTransaction);
+						responses.add(DocumentMessage::AddTransaction { label: "Delete Segments".into() });
 						transaction_started = true;
 					}
 					self.dissolve_segment(responses, layer, &vector, segment, [start, end]);
@@ -1595,7 +1595,7 @@ impl ShapeState {
 					&& vector.all_connected(*anchor).all(|segment| state.is_segment_selected(segment.segment))
 				{
 					if !transaction_started && start_transaction {
-						responses.add(DocumentMessage::AddTransaction);
+						responses.add(DocumentMessage::AddTransaction { label: "Delete Anchors".into() });
 						transaction_started = true
 					}
 					let modification_type = VectorModificationType::RemovePoint { id: *anchor };
@@ -1621,7 +1621,7 @@ impl ShapeState {
 					// Disable the g1 continuous
 					for &handles in &vector.colinear_manipulators {
 						if !transaction_started {
-							responses.add(DocumentMessage::AddTransaction);
+							responses.add(DocumentMessage::AddTransaction { label: "Break Path".into() });
 							transaction_started = true;
 						}
 
@@ -1638,7 +1638,7 @@ impl ShapeState {
 					}
 
 					if !transaction_started {
-						responses.add(DocumentMessage::AddTransaction);
+						responses.add(DocumentMessage::AddTransaction { label: "Break Path".into() });
 						transaction_started = true;
 					}
 
@@ -1673,7 +1673,7 @@ impl ShapeState {
 				let Some(point) = delete.get_anchor(&vector) else { continue };
 
 				if !transaction_started {
-					responses.add(DocumentMessage::AddTransaction);
+					responses.add(DocumentMessage::AddTransaction { label: "Break Path".into() });
 					transaction_started = true;
 				}
 				// Delete point
@@ -0,0 +1,75 @@
+use super::*;
+use crate::messages::portfolio::document::utility_types::misc::{GuideSnapTarget, SnapTarget};
+use glam::DVec2;
+use graphene_std::renderer::Quad;
+
+#[derive(Clone, Debug, Default)]
+pub struct GuideSnapper;
+
+impl GuideSnapper {
+	fn enabled(snap_data: &SnapData) -> bool {
+		let document = snap_data.document;
+		document.guides.visible
+			&& (document.snapping_state.target_enabled(SnapTarget::Guide(GuideSnapTarget::Line)) || document.snapping_state.target_enabled(SnapTarget::Guide(GuideSnapTarget::Intersection)))
+	}
+
+	pub fn free_snap(&mut self, snap_data: &mut SnapData, point: &SnapCandidatePoint, snap_results: &mut SnapResults) {
+		if !Self::enabled(snap_data) {
+			return;
+		}
+		let tolerance = snap_tolerance(snap_data.document);
+
+		for guide in &snap_data.document.guides.guides {
+			let projected = guide.project(point.document_point);
+			let distance = point.document_point.distance(projected);
+			if !distance.is_finite() || distance > tolerance {
+				continue;
+			}
+
+			snap_results.guide_lines.push(SnappedLine {
+				direction: guide.direction(),
+				point: SnappedPoint {
+					snapped_point_document: projected,
+					source: point.source,
+					target: SnapTarget::Guide(GuideSnapTarget::Line),
+					source_bounds: point.quad,
+					distance,
+					tolerance,
+					..Default::default()
+				},
+			});
+		}
+	}
+
+	pub fn constrained_snap(&mut self, snap_data: &mut SnapData, point: &SnapCandidatePoint, snap_results: &mut SnapResults, constraint: SnapConstraint) {
+		if !Self::enabled(snap_data) || !snap_data.document.snapping_state.target_enabled(SnapTarget::Guide(GuideSnapTarget::Line)) {
+			return;
+		}
+		let tolerance = snap_tolerance(snap_data.document);
+		let projected = constraint.projection(point.document_point);
+		let (constraint_start, constraint_direction) = match constraint {
+			SnapConstraint::Line { origin, direction } => (origin, direction.normalize_or_zero()),
+			SnapConstraint::Direction(direction) => (projected, direction.normalize_or_zero()),
+			_ => return,
+		};
+
+		for guide in &snap_data.document.guides.guides {
+			let Some(intersection) = Quad::intersect_rays(guide.origin, guide.direction(), constraint_start, constraint_direction) else {
+				continue;
+			};
+			let distance = intersection.distance(point.document_point);
+			if distance < tolerance {
+				snap_results.points.push(SnappedPoint {
+					snapped_point_document: intersection,
+					source: point.source,
+					target: SnapTarget::Guide(GuideSnapTarget::Line),
+					constrained: true,
+					source_bounds: point.quad,
+					distance,
+					tolerance,
+					..Default::default()
+				})
+			}
+		}
+	}
+}
@@ -393,7 +393,7 @@ pub fn transforming_transform_cage(
 	let is_flat_layer = bounding_box_manager.as_ref().map(|bounding_box_manager| bounding_box_manager.transform_tampered).unwrap_or(true);
 
 	if dragging_bounds.is_some() && !is_flat_layer {
-		responses.add(DocumentMessage::StartTransaction);
+		responses.add(DocumentMessage::StartTransaction { label: "Transform".into() });
 
 		*layers_dragging = selected;
 
@@ -432,7 +432,7 @@ pub fn transforming_transform_cage(
 	}
 
 	if rotating_bounds {
-		responses.add(DocumentMessage::StartTransaction);
+		responses.add(DocumentMessage::StartTransaction { label: "Transform".into() });
 
 		if let Some(bounds) = &mut bounding_box_manager {
 			layers_dragging.retain(|layer| {
@@ -590,3 +590,17 @@ pub fn make_path_editable_is_allowed(network_interface: &mut NodeNetworkInterfac
 
 	Some(first_layer)
 }
+
+/// Steps a brush diameter by `change`, such as with the `[` and `]` keys, first snapping a diameter that's off the step's grid onto it in the direction of the change.
+pub fn step_brush_diameter(diameter: f64, change: f64) -> f64 {
+	let step = change.abs();
+	let needs_rounding = ((diameter + step / 2.) % step - step / 2.).abs() > 0.5;
+	let diameter = if needs_rounding && change > 0. {
+		(diameter / step).ceil() * step
+	} else if needs_rounding && change < 0. {
+		(diameter / step).floor() * step
+	} else {
+		(diameter / step).round() * step + change
+	};
+	diameter.max(1.)
+}
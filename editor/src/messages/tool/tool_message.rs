@@ -15,6 +15,8 @@ pub enum ToolMessage {
 	#[child]
 	Artboard(ArtboardToolMessage),
 	#[child]
+	Slice(SliceToolMessage),
+	#[child]
 	Navigate(NavigateToolMessage),
 	#[child]
 	Eyedropper(EyedropperToolMessage),
@@ -38,12 +40,14 @@ pub enum ToolMessage {
 
 	#[child]
 	Brush(BrushToolMessage),
-	// 	// #[child]
-	// Heal(HealToolMessage),
-	// 	// #[child]
-	// Clone(CloneToolMessage),
-	// 	// #[child]
-	// Patch(PatchToolMessage),
+	#[child]
+	Heal(HealToolMessage),
+	#[child]
+	Clone(CloneToolMessage),
+	#[child]
+	Patch(PatchToolMessage),
+	#[child]
+	PixelSelect(PixelSelectToolMessage),
 	// 	// #[child]
 	// Relight(RelightToolMessage),
 	// 	// #[child]
@@ -54,6 +58,7 @@ pub enum ToolMessage {
 	// General tools
 	ActivateToolSelect,
 	ActivateToolArtboard,
+	ActivateToolSlice,
 	ActivateToolNavigate,
 	ActivateToolEyedropper,
 	ActivateToolFill,
@@ -70,6 +75,10 @@ pub enum ToolMessage {
 	ActivateToolText,
 	// Raster tools
 	ActivateToolBrush,
+	ActivateToolHeal,
+	ActivateToolClone,
+	ActivateToolPatch,
+	ActivateToolPixelSelect,
 
 	ActivateTool {
 		tool_type: ToolType,
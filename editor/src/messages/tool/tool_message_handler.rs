@@ -61,6 +61,7 @@ impl MessageHandler<ToolMessage, ToolMessageContext<'_>> for ToolMessageHandler
 
 			ToolMessage::ActivateToolSelect => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Select }),
 			ToolMessage::ActivateToolArtboard => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Artboard }),
+			ToolMessage::ActivateToolSlice => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Slice }),
 			ToolMessage::ActivateToolNavigate => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Navigate }),
 			ToolMessage::ActivateToolEyedropper => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Eyedropper }),
 			ToolMessage::ActivateToolText => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Text }),
@@ -81,6 +82,10 @@ impl MessageHandler<ToolMessage, ToolMessageContext<'_>> for ToolMessageHandler
 				responses.add(ShapeToolMessage::HideShapeTypeWidget { hide: false })
 			}
 			ToolMessage::ActivateToolBrush => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Brush }),
+			ToolMessage::ActivateToolHeal => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Heal }),
+			ToolMessage::ActivateToolClone => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Clone }),
+			ToolMessage::ActivateToolPatch => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::Patch }),
+			ToolMessage::ActivateToolPixelSelect => responses.add_front(ToolMessage::ActivateTool { tool_type: ToolType::PixelSelect }),
 			ToolMessage::ActivateToolShapeLine | ToolMessage::ActivateToolShapeRectangle | ToolMessage::ActivateToolShapeEllipse => {
 				let shape = match message {
 					ToolMessage::ActivateToolShapeLine => Line,
@@ -369,6 +374,7 @@ impl MessageHandler<ToolMessage, ToolMessageContext<'_>> for ToolMessageHandler
 		let mut list = actions!(ToolMessageDiscriminant;
 			ActivateToolSelect,
 			ActivateToolArtboard,
+			ActivateToolSlice,
 			ActivateToolNavigate,
 			ActivateToolEyedropper,
 			ActivateToolFill,
@@ -406,6 +412,10 @@ impl ToolMessageHandler {
 		if preferences.brush_tool {
 			list.extend(actions!(ToolMessageDiscriminant;
 				ActivateToolBrush,
+				ActivateToolHeal,
+				ActivateToolClone,
+				ActivateToolPatch,
+				ActivateToolPixelSelect,
 			));
 		}
 
@@ -311,7 +311,7 @@ impl Fsm for ArtboardToolFsmState {
 
 					ArtboardToolFsmState::Drawing
 				};
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Artboard Tool".into() });
 				state
 			}
 			(ArtboardToolFsmState::ResizingBounds, ArtboardToolMessage::PointerMove { constrain_axis_or_aspect, center }) => {
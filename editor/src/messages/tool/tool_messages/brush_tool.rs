@@ -5,6 +5,7 @@ use crate::messages::portfolio::document::node_graph::document_node_definitions:
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::network_interface::FlowType;
 use crate::messages::tool::common_functionality::color_selector::{ToolColorOptions, ToolColorType};
+use crate::messages::tool::common_functionality::utility_functions::step_brush_diameter;
 use graph_craft::document::NodeId;
 use graph_craft::document::value::TaggedValue;
 use graphene_std::Color;
@@ -234,15 +235,7 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Brus
 		match options {
 			BrushToolMessageOptionsUpdate::BlendMode(blend_mode) => self.options.blend_mode = blend_mode,
 			BrushToolMessageOptionsUpdate::ChangeDiameter(change) => {
-				let needs_rounding = ((self.options.diameter + change.abs() / 2.) % change.abs() - change.abs() / 2.).abs() > 0.5;
-				if needs_rounding && change > 0. {
-					self.options.diameter = (self.options.diameter / change.abs()).ceil() * change.abs();
-				} else if needs_rounding && change < 0. {
-					self.options.diameter = (self.options.diameter / change.abs()).floor() * change.abs();
-				} else {
-					self.options.diameter = (self.options.diameter / change.abs()).round() * change.abs() + change;
-				}
-				self.options.diameter = self.options.diameter.max(1.);
+				self.options.diameter = step_brush_diameter(self.options.diameter, change);
 				self.send_layout(responses, LayoutTarget::ToolOptions);
 			}
 			BrushToolMessageOptionsUpdate::Diameter(diameter) => self.options.diameter = diameter,
@@ -359,7 +352,7 @@ impl Fsm for BrushToolFsmState {
 		let ToolMessage::Brush(event) = event else { return self };
 		match (self, event) {
 			(BrushToolFsmState::Ready, BrushToolMessage::DragStart) => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Brush Tool".into() });
 				let loaded_layer = tool_data.load_existing_strokes(document);
 
 				if let Some(layer) = loaded_layer {
@@ -378,6 +371,9 @@ impl Fsm for BrushToolFsmState {
 						.max((layer_document_scale.matrix2 * glam::DVec2::X).length())
 						.max((layer_document_scale.matrix2 * glam::DVec2::Y).length());
 
+					// Confine the stroke to the pixel selection, mapped from document space into the space the stroke is drawn in
+					let document_to_layer = (document.metadata().downstream_transform_to_document(layer) * tool_data.transform).inverse();
+
 					// Start a new stroke with a single sample
 					let blend_mode = match tool_options.draw_mode {
 						DrawMode::Draw => tool_options.blend_mode,
@@ -394,6 +390,7 @@ impl Fsm for BrushToolFsmState {
 							spacing: tool_options.spacing,
 							blend_mode,
 						},
+						selection: document.pixel_selection.iter().map(|step| step.transformed(document_to_layer)).collect(),
 					});
 
 					tool_data.update_strokes(responses);
@@ -0,0 +1,460 @@
+use super::tool_prelude::*;
+use crate::consts::{COLOR_OVERLAY_BLUE, COLOR_OVERLAY_WHITE, DEFAULT_BRUSH_SIZE};
+use crate::messages::portfolio::document::graph_operation::transform_utils::get_current_transform;
+use crate::messages::portfolio::document::node_graph::document_node_definitions::DefinitionIdentifier;
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::network_interface::FlowType;
+use crate::messages::tool::common_functionality::utility_functions::step_brush_diameter;
+use graph_craft::document::value::TaggedValue;
+use graphene_std::brush::brush_stroke::{BrushInputSample, BrushStroke, BrushStyle};
+use graphene_std::brush::clone_stamp::CloneStroke;
+
+const BRUSH_MAX_SIZE: f64 = 5000.;
+
+#[derive(PartialEq, Copy, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum CloneSampleMode {
+	CurrentLayer = 0,
+	AllLayers,
+}
+
+#[derive(Default, ExtractField)]
+pub struct CloneTool {
+	fsm_state: CloneToolFsmState,
+	data: CloneToolData,
+	options: CloneOptions,
+}
+
+pub struct CloneOptions {
+	diameter: f64,
+	hardness: f64,
+	flow: f64,
+	spacing: f64,
+	aligned: bool,
+	sample_mode: CloneSampleMode,
+}
+
+impl Default for CloneOptions {
+	fn default() -> Self {
+		Self {
+			diameter: DEFAULT_BRUSH_SIZE,
+			hardness: 0.,
+			flow: 100.,
+			spacing: 20.,
+			aligned: true,
+			sample_mode: CloneSampleMode::CurrentLayer,
+		}
+	}
+}
+
+#[impl_message(Message, ToolMessage, Clone)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum CloneToolMessage {
+	// Standard messages
+	Abort,
+	Overlays { context: OverlayContext },
+
+	// Tool-specific messages
+	DragStart,
+	DragStop,
+	PointerMove,
+	SetSource,
+	UpdateOptions { options: CloneToolMessageOptionsUpdate },
+}
+
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum CloneToolMessageOptionsUpdate {
+	Aligned(bool),
+	ChangeDiameter(f64),
+	Diameter(f64),
+	Flow(f64),
+	Hardness(f64),
+	SampleMode(CloneSampleMode),
+	Spacing(f64),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum CloneToolFsmState {
+	#[default]
+	Ready,
+	Drawing,
+}
+
+impl ToolMetadata for CloneTool {
+	fn icon_name(&self) -> String {
+		"RasterCloneTool".into()
+	}
+	fn tooltip_label(&self) -> String {
+		"Clone Tool".into()
+	}
+	fn tool_type(&self) -> crate::messages::tool::utility_types::ToolType {
+		ToolType::Clone
+	}
+}
+
+impl LayoutHolder for CloneTool {
+	fn layout(&self) -> Layout {
+		let mut widgets = vec![
+			NumberInput::new(Some(self.options.diameter))
+				.label("Diameter")
+				.min(1.)
+				.max(BRUSH_MAX_SIZE) /* Anything bigger would cause the application to be unresponsive and eventually die */
+				.unit(" px")
+				.on_update(|number_input: &NumberInput| CloneToolMessage::UpdateOptions { options: CloneToolMessageOptionsUpdate::Diameter(number_input.value.unwrap()) }.into())
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Related).widget_instance(),
+			NumberInput::new(Some(self.options.hardness))
+				.label("Hardness")
+				.min(0.)
+				.max(100.)
+				.mode_range()
+				.unit("%")
+				.on_update(|number_input: &NumberInput| {
+					CloneToolMessage::UpdateOptions {
+						options: CloneToolMessageOptionsUpdate::Hardness(number_input.value.unwrap()),
+					}
+					.into()
+				})
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Related).widget_instance(),
+			NumberInput::new(Some(self.options.flow))
+				.label("Flow")
+				.min(1.)
+				.max(100.)
+				.mode_range()
+				.unit("%")
+				.on_update(|number_input: &NumberInput| {
+					CloneToolMessage::UpdateOptions {
+						options: CloneToolMessageOptionsUpdate::Flow(number_input.value.unwrap()),
+					}
+					.into()
+				})
+				.widget_instance(),
+			Separator::new(SeparatorStyle::Related).widget_instance(),
+			NumberInput::new(Some(self.options.spacing))
+				.label("Spacing")
+				.min(1.)
+				.max(100.)
+				.mode_range()
+				.unit("%")
+				.on_update(|number_input: &NumberInput| {
+					CloneToolMessage::UpdateOptions {
+						options: CloneToolMessageOptionsUpdate::Spacing(number_input.value.unwrap()),
+					}
+					.into()
+				})
+				.widget_instance(),
+		];
+
+		widgets.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
+
+		let aligned_description = "Keep the source point at a fixed offset from the brush across strokes. When disabled, each stroke begins sampling from the source point again.";
+		let checkbox_id = CheckboxId::new();
+		widgets.push(
+			CheckboxInput::new(self.options.aligned)
+				.on_update(|&CheckboxInput { checked, .. }| {
+					CloneToolMessage::UpdateOptions {
+						options: CloneToolMessageOptionsUpdate::Aligned(checked),
+					}
+					.into()
+				})
+				.tooltip_label("Aligned")
+				.tooltip_description(aligned_description)
+				.for_label(checkbox_id)
+				.widget_instance(),
+		);
+		widgets.push(
+			TextLabel::new("Aligned")
+				.tooltip_label("Aligned")
+				.tooltip_description(aligned_description)
+				.for_checkbox(checkbox_id)
+				.widget_instance(),
+		);
+
+		widgets.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
+
+		let sample_mode_entries: Vec<_> = [(CloneSampleMode::CurrentLayer, "Current Layer"), (CloneSampleMode::AllLayers, "All Layers")]
+			.into_iter()
+			.map(|(sample_mode, label)| {
+				RadioEntryData::new(format!("{sample_mode:?}")).label(label).on_update(move |_| {
+					CloneToolMessage::UpdateOptions {
+						options: CloneToolMessageOptionsUpdate::SampleMode(sample_mode),
+					}
+					.into()
+				})
+			})
+			.collect();
+		widgets.push(RadioInput::new(sample_mode_entries).selected_index(Some(self.options.sample_mode as u32)).widget_instance());
+
+		Layout(vec![LayoutGroup::Row { widgets }])
+	}
+}
+
+#[message_handler_data]
+impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for CloneTool {
+	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
+		let ToolMessage::Clone(CloneToolMessage::UpdateOptions { options }) = message else {
+			self.fsm_state.process_event(message, &mut self.data, context, &self.options, responses, true);
+			return;
+		};
+		match options {
+			CloneToolMessageOptionsUpdate::Aligned(aligned) => {
+				self.options.aligned = aligned;
+				self.data.aligned_offset = None;
+			}
+			CloneToolMessageOptionsUpdate::ChangeDiameter(change) => self.options.diameter = step_brush_diameter(self.options.diameter, change),
+			CloneToolMessageOptionsUpdate::Diameter(diameter) => self.options.diameter = diameter,
+			CloneToolMessageOptionsUpdate::Flow(flow) => self.options.flow = flow,
+			CloneToolMessageOptionsUpdate::Hardness(hardness) => self.options.hardness = hardness,
+			CloneToolMessageOptionsUpdate::SampleMode(sample_mode) => self.options.sample_mode = sample_mode,
+			CloneToolMessageOptionsUpdate::Spacing(spacing) => self.options.spacing = spacing,
+		}
+
+		self.send_layout(responses, LayoutTarget::ToolOptions);
+	}
+
+	fn actions(&self) -> ActionList {
+		match self.fsm_state {
+			CloneToolFsmState::Ready => actions!(CloneToolMessageDiscriminant;
+				DragStart,
+				DragStop,
+				PointerMove,
+				SetSource,
+				UpdateOptions,
+			),
+			CloneToolFsmState::Drawing => actions!(CloneToolMessageDiscriminant;
+				DragStop,
+				PointerMove,
+				Abort,
+				UpdateOptions,
+			),
+		}
+	}
+}
+
+impl ToolTransition for CloneTool {
+	fn event_to_message_map(&self) -> EventToMessageMap {
+		EventToMessageMap {
+			tool_abort: Some(CloneToolMessage::Abort.into()),
+			overlay_provider: Some(|context| CloneToolMessage::Overlays { context }.into()),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+struct CloneToolData {
+	strokes: Vec<CloneStroke>,
+	layer: Option<LayerNodeIdentifier>,
+	transform: DAffine2,
+	/// The point in document space that the first stroke after setting it samples from.
+	source: Option<DVec2>,
+	/// The offset in document space from the brush to the source, kept across strokes when painting in aligned mode.
+	aligned_offset: Option<DVec2>,
+	/// The offset in document space from the brush to the source during the current stroke.
+	stroke_offset: Option<DVec2>,
+}
+
+impl CloneToolData {
+	fn load_existing_strokes(&mut self, document: &DocumentMessageHandler) -> Option<LayerNodeIdentifier> {
+		self.transform = DAffine2::IDENTITY;
+		self.strokes.clear();
+
+		if document.network_interface.selected_nodes().selected_layers(document.metadata()).count() != 1 {
+			return None;
+		}
+		let layer = document.network_interface.selected_nodes().selected_layers(document.metadata()).next()?;
+
+		self.layer = Some(layer);
+		for node_id in document.network_interface.upstream_flow_back_from_nodes(vec![layer.to_node()], &[], FlowType::HorizontalFlow) {
+			let Some(node) = document.network_interface.document_network().nodes.get(&node_id) else {
+				continue;
+			};
+			let Some(reference) = document.network_interface.reference(&node_id, &[]) else {
+				continue;
+			};
+
+			if reference == DefinitionIdentifier::ProtoNode(graphene_std::brush::clone_stamp::clone_stamp::IDENTIFIER) {
+				let strokes_input = node.inputs.get(1)?;
+				let Some(TaggedValue::CloneStrokes(strokes)) = strokes_input.as_value() else { continue };
+				self.strokes.clone_from(strokes);
+
+				return Some(layer);
+			}
+
+			if reference == DefinitionIdentifier::Network("Transform".into()) {
+				self.transform = get_current_transform(&node.inputs) * self.transform;
+			}
+		}
+
+		// The layer has no Clone Stamp node yet, so one will be created at the start of its chain where no transforms apply
+		self.transform = DAffine2::IDENTITY;
+		Some(layer)
+	}
+
+	/// The transform from the space that the strokes are painted in to the document.
+	fn stroke_to_document(&self, document: &DocumentMessageHandler, layer: LayerNodeIdentifier) -> DAffine2 {
+		document.metadata().downstream_transform_to_document(layer) * self.transform
+	}
+
+	fn update_strokes(&self, tool_options: &CloneOptions, responses: &mut VecDeque<Message>) {
+		let Some(layer) = self.layer else { return };
+		responses.add(GraphOperationMessage::CloneStamp {
+			layer,
+			strokes: self.strokes.clone(),
+			sample_all_layers: tool_options.sample_mode == CloneSampleMode::AllLayers,
+			layers_beneath_transform: self.transform,
+		});
+	}
+
+	/// The offset that a new stroke starting at the given document space point should sample from.
+	fn offset_for_new_stroke(&mut self, start: DVec2, aligned: bool) -> Option<DVec2> {
+		if aligned && let Some(offset) = self.aligned_offset {
+			return Some(offset);
+		}
+
+		let offset = self.source? - start;
+		if aligned {
+			self.aligned_offset = Some(offset);
+		}
+		Some(offset)
+	}
+
+	/// The point in viewport space that is sampled when the brush is at the given viewport space point.
+	fn sample_point_viewport(&self, document: &DocumentMessageHandler, brush: DVec2, drawing: bool, aligned: bool) -> Option<DVec2> {
+		let document_to_viewport = document.metadata().document_to_viewport;
+		let brush = document_to_viewport.inverse().transform_point2(brush);
+
+		let sample_point = match (drawing, aligned) {
+			(true, _) => brush + self.stroke_offset?,
+			(false, true) => self.aligned_offset.map(|offset| brush + offset).or(self.source)?,
+			(false, false) => self.source?,
+		};
+		Some(document_to_viewport.transform_point2(sample_point))
+	}
+}
+
+impl Fsm for CloneToolFsmState {
+	type ToolData = CloneToolData;
+	type ToolOptions = CloneOptions;
+
+	fn transition(
+		self,
+		event: ToolMessage,
+		tool_data: &mut Self::ToolData,
+		tool_action_data: &mut ToolActionMessageContext,
+		tool_options: &Self::ToolOptions,
+		responses: &mut VecDeque<Message>,
+	) -> Self {
+		let ToolActionMessageContext { document, input, .. } = tool_action_data;
+
+		let ToolMessage::Clone(event) = event else { return self };
+		match (self, event) {
+			(_, CloneToolMessage::Overlays { context: mut overlay_context }) => {
+				let drawing = self == CloneToolFsmState::Drawing;
+				if let Some(sample_point) = tool_data.sample_point_viewport(document, input.mouse.position, drawing, tool_options.aligned) {
+					let radius = tool_options.diameter * document.metadata().document_to_viewport.matrix2.x_axis.length() / 2.;
+					overlay_context.circle(sample_point, radius.max(1.), None, Some(COLOR_OVERLAY_WHITE));
+					overlay_context.circle(sample_point, 3., Some(COLOR_OVERLAY_WHITE), Some(COLOR_OVERLAY_BLUE));
+				}
+
+				self
+			}
+			(CloneToolFsmState::Ready, CloneToolMessage::SetSource) => {
+				tool_data.source = Some(document.metadata().document_to_viewport.inverse().transform_point2(input.mouse.position));
+				tool_data.aligned_offset = None;
+				responses.add(OverlaysMessage::Draw);
+
+				CloneToolFsmState::Ready
+			}
+			(CloneToolFsmState::Ready, CloneToolMessage::DragStart) => {
+				let Some(layer) = tool_data.load_existing_strokes(document) else {
+					return CloneToolFsmState::Ready;
+				};
+
+				let start = document.metadata().document_to_viewport.inverse().transform_point2(input.mouse.position);
+				let Some(offset) = tool_data.offset_for_new_stroke(start, tool_options.aligned) else {
+					return CloneToolFsmState::Ready;
+				};
+				tool_data.stroke_offset = Some(offset);
+
+				responses.add(DocumentMessage::StartTransaction { label: "Clone Tool".into() });
+
+				let document_to_stroke = tool_data.stroke_to_document(document, layer).inverse();
+				let layer_position = document_to_stroke.transform_point2(start);
+				let source_offset = document_to_stroke.transform_vector2(offset);
+				let layer_scale = 0.0001_f64 // Safety against division by zero
+					.max((document.metadata().downstream_transform_to_viewport(layer) * tool_data.transform).matrix2.x_axis.length());
+
+				// Start a new stroke with a single sample
+				tool_data.strokes.push(CloneStroke {
+					stroke: BrushStroke {
+						trace: vec![BrushInputSample { position: layer_position }],
+						style: BrushStyle {
+							diameter: tool_options.diameter / layer_scale,
+							hardness: tool_options.hardness,
+							flow: tool_options.flow,
+							spacing: tool_options.spacing,
+							..Default::default()
+						},
+						selection: document.pixel_selection.iter().map(|step| step.transformed(document_to_stroke)).collect(),
+					},
+					source_offset,
+				});
+
+				tool_data.update_strokes(tool_options, responses);
+				CloneToolFsmState::Drawing
+			}
+			(CloneToolFsmState::Drawing, CloneToolMessage::PointerMove) => {
+				if let Some(layer) = tool_data.layer
+					&& let Some(clone_stroke) = tool_data.strokes.last_mut()
+				{
+					let position = document.metadata().document_to_viewport.inverse().transform_point2(input.mouse.position);
+					let layer_position = tool_data.stroke_to_document(document, layer).inverse().transform_point2(position);
+
+					clone_stroke.stroke.trace.push(BrushInputSample { position: layer_position });
+				}
+				tool_data.update_strokes(tool_options, responses);
+				responses.add(OverlaysMessage::Draw);
+
+				CloneToolFsmState::Drawing
+			}
+			(_, CloneToolMessage::PointerMove) => {
+				responses.add(OverlaysMessage::Draw);
+				self
+			}
+			(CloneToolFsmState::Drawing, CloneToolMessage::DragStop) => {
+				responses.add(DocumentMessage::EndTransaction);
+				tool_data.strokes.clear();
+				tool_data.stroke_offset = None;
+
+				CloneToolFsmState::Ready
+			}
+			(CloneToolFsmState::Drawing, CloneToolMessage::Abort) => {
+				responses.add(DocumentMessage::AbortTransaction);
+				tool_data.strokes.clear();
+				tool_data.stroke_offset = None;
+
+				CloneToolFsmState::Ready
+			}
+			_ => self,
+		}
+	}
+
+	fn update_hints(&self, responses: &mut VecDeque<Message>) {
+		let hint_data = match self {
+			CloneToolFsmState::Ready => HintData(vec![
+				HintGroup(vec![HintInfo::keys_and_mouse([Key::Alt], MouseMotion::Lmb, "Set Source")]),
+				HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDrag, "Paint from Source")]),
+				HintGroup(vec![HintInfo::multi_keys([[Key::BracketLeft], [Key::BracketRight]], "Shrink/Grow Brush")]),
+			]),
+			CloneToolFsmState::Drawing => HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()])]),
+		};
+
+		hint_data.send_layout(responses);
+	}
+
+	fn update_cursor(&self, responses: &mut VecDeque<Message>) {
+		responses.add(FrontendMessage::UpdateMouseCursor { cursor: MouseCursorIcon::Default });
+	}
+}
@@ -1,6 +1,12 @@
 use super::tool_prelude::*;
+use crate::messages::portfolio::document::graph_operation::transform_utils::get_current_transform;
+use crate::messages::portfolio::document::node_graph::document_node_definitions::DefinitionIdentifier;
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::network_interface::FlowType;
 use crate::messages::tool::common_functionality::graph_modification_utils::NodeGraphLayer;
+use graph_craft::document::value::TaggedValue;
+use graphene_std::raster::selection::SelectionFill;
 use graphene_std::vector::style::Fill;
 
 #[derive(Default, ExtractField)]
@@ -124,17 +130,33 @@ impl Fsm for FillToolFsmState {
 				let Some(layer_identifier) = document.click(input, viewport) else {
 					return self;
 				};
-				// If the layer is a raster layer, don't fill it, wait till the flood fill tool is implemented
-				if NodeGraphLayer::is_raster_layer(layer_identifier, &mut document.network_interface) {
-					return self;
-				}
-				let fill = match color_event {
-					FillToolMessage::FillPrimaryColor => Fill::Solid(global_tool_data.primary_color.to_gamma_srgb()),
-					FillToolMessage::FillSecondaryColor => Fill::Solid(global_tool_data.secondary_color.to_gamma_srgb()),
+				let color = match color_event {
+					FillToolMessage::FillPrimaryColor => global_tool_data.primary_color,
+					FillToolMessage::FillSecondaryColor => global_tool_data.secondary_color,
 					_ => return self,
 				};
 
-				responses.add(DocumentMessage::AddTransaction);
+				// Raster layers are only filled within the pixel selection, until the flood fill tool is implemented
+				if NodeGraphLayer::is_raster_layer(layer_identifier, &mut document.network_interface) {
+					if document.pixel_selection.is_empty() {
+						return self;
+					}
+
+					let (mut fills, transform) = load_existing_selection_fills(document, layer_identifier);
+					let document_to_layer = (document.metadata().downstream_transform_to_document(layer_identifier) * transform).inverse();
+					fills.push(SelectionFill {
+						color,
+						selection: document.pixel_selection.iter().map(|step| step.transformed(document_to_layer)).collect(),
+					});
+
+					responses.add(DocumentMessage::AddTransaction { label: "Fill Tool".into() });
+					responses.add(GraphOperationMessage::SelectionFill { layer: layer_identifier, fills });
+
+					return FillToolFsmState::Filling;
+				}
+				let fill = Fill::Solid(color.to_gamma_srgb());
+
+				responses.add(DocumentMessage::AddTransaction { label: "Fill Tool".into() });
 				responses.add(GraphOperationMessage::FillSet { layer: layer_identifier, fill });
 
 				FillToolFsmState::Filling
@@ -166,9 +188,37 @@ impl Fsm for FillToolFsmState {
 	}
 }
 
+/// Finds the fills already made within pixel selections on the raster layer, and the transform from the space they're stored in to the layer's space.
+fn load_existing_selection_fills(document: &DocumentMessageHandler, layer: LayerNodeIdentifier) -> (Vec<SelectionFill>, DAffine2) {
+	let mut transform = DAffine2::IDENTITY;
+	for node_id in document.network_interface.upstream_flow_back_from_nodes(vec![layer.to_node()], &[], FlowType::HorizontalFlow) {
+		let Some(node) = document.network_interface.document_network().nodes.get(&node_id) else {
+			continue;
+		};
+		let Some(reference) = document.network_interface.reference(&node_id, &[]) else {
+			continue;
+		};
+
+		if reference == DefinitionIdentifier::ProtoNode(graphene_std::raster::selection::selection_fill::IDENTIFIER) {
+			let Some(TaggedValue::SelectionFills(fills)) = node.inputs.get(1).and_then(|input| input.as_value()) else {
+				continue;
+			};
+			return (fills.clone(), transform);
+		}
+
+		if reference == DefinitionIdentifier::Network("Transform".into()) {
+			transform = get_current_transform(&node.inputs) * transform;
+		}
+	}
+
+	// The layer has no Selection Fill node yet, so one will be created at the start of its chain where no transforms apply
+	(Vec::new(), DAffine2::IDENTITY)
+}
+
 #[cfg(test)]
 mod test_fill {
 	pub use crate::test_utils::test_prelude::*;
+	use graphene_std::raster::selection::selection_fill;
 	use graphene_std::vector::fill;
 	use graphene_std::vector::style::Fill;
 
@@ -199,6 +249,23 @@ mod test_fill {
 		assert!(get_fills(&mut editor,).await.is_empty());
 	}
 
+	#[tokio::test]
+	async fn raster_within_pixel_selection() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.create_raster_image(Image::new(100, 100, Color::WHITE), Some((0., 0.))).await;
+		editor.drag_tool(ToolType::PixelSelect, 10., 10., 50., 50., ModifierKeys::empty()).await;
+		editor.select_primary_color(Color::GREEN).await;
+		editor.click_tool(ToolType::Fill, MouseKeys::LEFT, DVec2::new(20., 20.), ModifierKeys::empty()).await;
+
+		let instrumented = editor.eval_graph().await.expect("Failed to evaluate graph");
+		let selection_fills = instrumented.grab_all_input::<selection_fill::FillsInput>(&editor.runtime).collect::<Vec<_>>();
+		assert_eq!(selection_fills.len(), 1);
+		assert_eq!(selection_fills[0].len(), 1);
+		assert_eq!(selection_fills[0][0].color, Color::GREEN);
+		assert!(get_fills(&mut editor).await.is_empty());
+	}
+
 	#[tokio::test]
 	async fn primary() {
 		let mut editor = EditorTestUtils::create();
@@ -248,7 +248,7 @@ impl Fsm for FreehandToolFsmState {
 				self
 			}
 			(FreehandToolFsmState::Ready, FreehandToolMessage::DragStart { append_to_selected }) => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Freehand Tool".into() });
 
 				tool_data.dragged = false;
 				tool_data.end_point = None;
@@ -302,7 +302,7 @@ impl Fsm for GradientToolFsmState {
 					return self;
 				}
 
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Gradient Tool".into() });
 
 				// Remove the selected point
 				match selected_gradient.dragging {
@@ -364,7 +364,7 @@ impl Fsm for GradientToolFsmState {
 					if distance < (SELECTION_THRESHOLD * 2.) {
 						// Try and insert the new stop
 						if let Some(index) = gradient.insert_stop(mouse, transform) {
-							responses.add(DocumentMessage::AddTransaction);
+							responses.add(DocumentMessage::AddTransaction { label: "Gradient Tool".into() });
 
 							let mut selected_gradient = SelectedGradient::new(gradient, layer, document);
 
@@ -454,7 +454,7 @@ impl Fsm for GradientToolFsmState {
 						GradientToolFsmState::Ready
 					}
 				};
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Gradient Tool".into() });
 				gradient_state
 			}
 			(GradientToolFsmState::Drawing, GradientToolMessage::PointerMove { constrain_axis }) => {
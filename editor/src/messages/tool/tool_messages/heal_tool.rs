@@ -0,0 +1,274 @@
+use super::tool_prelude::*;
+use crate::consts::{COLOR_OVERLAY_WHITE, DEFAULT_BRUSH_SIZE};
+use crate::messages::portfolio::document::graph_operation::transform_utils::get_current_transform;
+use crate::messages::portfolio::document::node_graph::document_node_definitions::DefinitionIdentifier;
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::network_interface::FlowType;
+use crate::messages::tool::common_functionality::utility_functions::step_brush_diameter;
+use graph_craft::document::value::TaggedValue;
+use graphene_std::raster::healing::HealStroke;
+
+const BRUSH_MAX_SIZE: f64 = 5000.;
+
+#[derive(Default, ExtractField)]
+pub struct HealTool {
+	fsm_state: HealToolFsmState,
+	data: HealToolData,
+	options: HealOptions,
+}
+
+pub struct HealOptions {
+	diameter: f64,
+}
+
+impl Default for HealOptions {
+	fn default() -> Self {
+		Self { diameter: DEFAULT_BRUSH_SIZE }
+	}
+}
+
+#[impl_message(Message, ToolMessage, Heal)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum HealToolMessage {
+	// Standard messages
+	Abort,
+	Overlays { context: OverlayContext },
+
+	// Tool-specific messages
+	DragStart,
+	DragStop,
+	PointerMove,
+	UpdateOptions { options: HealToolMessageOptionsUpdate },
+}
+
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum HealToolMessageOptionsUpdate {
+	ChangeDiameter(f64),
+	Diameter(f64),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum HealToolFsmState {
+	#[default]
+	Ready,
+	Drawing,
+}
+
+impl ToolMetadata for HealTool {
+	fn icon_name(&self) -> String {
+		"RasterHealTool".into()
+	}
+	fn tooltip_label(&self) -> String {
+		"Heal Tool".into()
+	}
+	fn tool_type(&self) -> crate::messages::tool::utility_types::ToolType {
+		ToolType::Heal
+	}
+}
+
+impl LayoutHolder for HealTool {
+	fn layout(&self) -> Layout {
+		let widgets = vec![
+			NumberInput::new(Some(self.options.diameter))
+				.label("Diameter")
+				.min(1.)
+				.max(BRUSH_MAX_SIZE) /* Anything bigger would cause the application to be unresponsive and eventually die */
+				.unit(" px")
+				.on_update(|number_input: &NumberInput| HealToolMessage::UpdateOptions { options: HealToolMessageOptionsUpdate::Diameter(number_input.value.unwrap()) }.into())
+				.widget_instance(),
+		];
+
+		Layout(vec![LayoutGroup::Row { widgets }])
+	}
+}
+
+#[message_handler_data]
+impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for HealTool {
+	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
+		let ToolMessage::Heal(HealToolMessage::UpdateOptions { options }) = message else {
+			self.fsm_state.process_event(message, &mut self.data, context, &self.options, responses, true);
+			return;
+		};
+		match options {
+			HealToolMessageOptionsUpdate::ChangeDiameter(change) => self.options.diameter = step_brush_diameter(self.options.diameter, change),
+			HealToolMessageOptionsUpdate::Diameter(diameter) => self.options.diameter = diameter,
+		}
+
+		self.send_layout(responses, LayoutTarget::ToolOptions);
+		responses.add(OverlaysMessage::Draw);
+	}
+
+	fn actions(&self) -> ActionList {
+		match self.fsm_state {
+			HealToolFsmState::Ready => actions!(HealToolMessageDiscriminant;
+				DragStart,
+				DragStop,
+				PointerMove,
+				UpdateOptions,
+			),
+			HealToolFsmState::Drawing => actions!(HealToolMessageDiscriminant;
+				DragStop,
+				PointerMove,
+				Abort,
+				UpdateOptions,
+			),
+		}
+	}
+}
+
+impl ToolTransition for HealTool {
+	fn event_to_message_map(&self) -> EventToMessageMap {
+		EventToMessageMap {
+			tool_abort: Some(HealToolMessage::Abort.into()),
+			overlay_provider: Some(|context| HealToolMessage::Overlays { context }.into()),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+struct HealToolData {
+	strokes: Vec<HealStroke>,
+	layer: Option<LayerNodeIdentifier>,
+	transform: DAffine2,
+}
+
+impl HealToolData {
+	fn load_existing_strokes(&mut self, document: &DocumentMessageHandler) -> Option<LayerNodeIdentifier> {
+		self.transform = DAffine2::IDENTITY;
+		self.strokes.clear();
+
+		if document.network_interface.selected_nodes().selected_layers(document.metadata()).count() != 1 {
+			return None;
+		}
+		let layer = document.network_interface.selected_nodes().selected_layers(document.metadata()).next()?;
+
+		self.layer = Some(layer);
+		for node_id in document.network_interface.upstream_flow_back_from_nodes(vec![layer.to_node()], &[], FlowType::HorizontalFlow) {
+			let Some(node) = document.network_interface.document_network().nodes.get(&node_id) else {
+				continue;
+			};
+			let Some(reference) = document.network_interface.reference(&node_id, &[]) else {
+				continue;
+			};
+
+			if reference == DefinitionIdentifier::ProtoNode(graphene_std::raster::healing::spot_healing::IDENTIFIER) {
+				let strokes_input = node.inputs.get(1)?;
+				let Some(TaggedValue::HealStrokes(strokes)) = strokes_input.as_value() else { continue };
+				self.strokes.clone_from(strokes);
+
+				return Some(layer);
+			}
+
+			if reference == DefinitionIdentifier::Network("Transform".into()) {
+				self.transform = get_current_transform(&node.inputs) * self.transform;
+			}
+		}
+
+		// The layer has no Spot Healing node yet, so one will be created at the start of its chain where no transforms apply
+		self.transform = DAffine2::IDENTITY;
+		Some(layer)
+	}
+
+	fn layer_position(&self, document: &DocumentMessageHandler, layer: LayerNodeIdentifier, viewport_position: DVec2) -> DVec2 {
+		let layer_to_viewport = document.metadata().downstream_transform_to_viewport(layer) * self.transform;
+		layer_to_viewport.inverse().transform_point2(viewport_position)
+	}
+
+	fn update_strokes(&self, responses: &mut VecDeque<Message>) {
+		let Some(layer) = self.layer else { return };
+		let strokes = self.strokes.clone();
+		responses.add(GraphOperationMessage::Heal { layer, strokes });
+	}
+}
+
+impl Fsm for HealToolFsmState {
+	type ToolData = HealToolData;
+	type ToolOptions = HealOptions;
+
+	fn transition(
+		self,
+		event: ToolMessage,
+		tool_data: &mut Self::ToolData,
+		tool_action_data: &mut ToolActionMessageContext,
+		tool_options: &Self::ToolOptions,
+		responses: &mut VecDeque<Message>,
+	) -> Self {
+		let ToolActionMessageContext { document, input, .. } = tool_action_data;
+
+		let ToolMessage::Heal(event) = event else { return self };
+		match (self, event) {
+			(_, HealToolMessage::Overlays { context: mut overlay_context }) => {
+				// The brush diameter is in viewport pixels, so the outline of the brush around the cursor shows the area that's painted over
+				overlay_context.circle(input.mouse.position, (tool_options.diameter / 2.).max(1.), None, Some(COLOR_OVERLAY_WHITE));
+
+				self
+			}
+			(HealToolFsmState::Ready, HealToolMessage::DragStart) => {
+				let Some(layer) = tool_data.load_existing_strokes(document) else {
+					return HealToolFsmState::Ready;
+				};
+				responses.add(DocumentMessage::StartTransaction { label: "Heal Tool".into() });
+
+				let layer_position = tool_data.layer_position(document, layer, input.mouse.position);
+				let layer_scale = 0.0001_f64 // Safety against division by zero
+					.max((document.metadata().downstream_transform_to_viewport(layer) * tool_data.transform).matrix2.x_axis.length());
+
+				// Start a new stroke with a single sample
+				tool_data.strokes.push(HealStroke {
+					trace: vec![layer_position],
+					diameter: tool_options.diameter / layer_scale,
+				});
+
+				tool_data.update_strokes(responses);
+				HealToolFsmState::Drawing
+			}
+			(HealToolFsmState::Drawing, HealToolMessage::PointerMove) => {
+				if let Some(layer) = tool_data.layer {
+					let layer_position = tool_data.layer_position(document, layer, input.mouse.position);
+					if let Some(stroke) = tool_data.strokes.last_mut() {
+						stroke.trace.push(layer_position);
+					}
+				}
+				tool_data.update_strokes(responses);
+				responses.add(OverlaysMessage::Draw);
+
+				HealToolFsmState::Drawing
+			}
+			(_, HealToolMessage::PointerMove) => {
+				responses.add(OverlaysMessage::Draw);
+				self
+			}
+			(HealToolFsmState::Drawing, HealToolMessage::DragStop) => {
+				responses.add(DocumentMessage::EndTransaction);
+				tool_data.strokes.clear();
+
+				HealToolFsmState::Ready
+			}
+			(HealToolFsmState::Drawing, HealToolMessage::Abort) => {
+				responses.add(DocumentMessage::AbortTransaction);
+				tool_data.strokes.clear();
+
+				HealToolFsmState::Ready
+			}
+			_ => self,
+		}
+	}
+
+	fn update_hints(&self, responses: &mut VecDeque<Message>) {
+		let hint_data = match self {
+			HealToolFsmState::Ready => HintData(vec![
+				HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDrag, "Paint Over Blemish")]),
+				HintGroup(vec![HintInfo::multi_keys([[Key::BracketLeft], [Key::BracketRight]], "Shrink/Grow Brush")]),
+			]),
+			HealToolFsmState::Drawing => HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()])]),
+		};
+
+		hint_data.send_layout(responses);
+	}
+
+	fn update_cursor(&self, responses: &mut VecDeque<Message>) {
+		responses.add(FrontendMessage::UpdateMouseCursor { cursor: MouseCursorIcon::Default });
+	}
+}
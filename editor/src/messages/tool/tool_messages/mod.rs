@@ -1,14 +1,19 @@
 pub mod artboard_tool;
 pub mod brush_tool;
+pub mod clone_tool;
 pub mod eyedropper_tool;
 pub mod fill_tool;
 pub mod freehand_tool;
 pub mod gradient_tool;
+pub mod heal_tool;
 pub mod navigate_tool;
+pub mod patch_tool;
 pub mod path_tool;
 pub mod pen_tool;
+pub mod pixel_select_tool;
 pub mod select_tool;
 pub mod shape_tool;
+pub mod slice_tool;
 pub mod spline_tool;
 pub mod text_tool;
 
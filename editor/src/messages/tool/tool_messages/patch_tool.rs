@@ -0,0 +1,270 @@
+use super::tool_prelude::*;
+use crate::consts::{COLOR_OVERLAY_BLUE, COLOR_OVERLAY_BLUE_50};
+use crate::messages::portfolio::document::graph_operation::transform_utils::get_current_transform;
+use crate::messages::portfolio::document::node_graph::document_node_definitions::DefinitionIdentifier;
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::network_interface::FlowType;
+use graph_craft::document::value::TaggedValue;
+use graphene_std::raster::healing::{PatchRegion, point_in_polygon};
+
+#[derive(Default, ExtractField)]
+pub struct PatchTool {
+	fsm_state: PatchToolFsmState,
+	data: PatchToolData,
+}
+
+#[impl_message(Message, ToolMessage, Patch)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum PatchToolMessage {
+	// Standard messages
+	Abort,
+	Overlays { context: OverlayContext },
+
+	// Tool-specific messages
+	DragStart,
+	DragStop,
+	PointerMove,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum PatchToolFsmState {
+	#[default]
+	Ready,
+	/// Drawing the outline of the region to be repaired.
+	Selecting,
+	/// The region to be repaired is outlined and can be dragged onto a source area.
+	Selected,
+	/// Dragging the outlined region onto a source area.
+	Dragging,
+}
+
+impl ToolMetadata for PatchTool {
+	fn icon_name(&self) -> String {
+		"RasterPatchTool".into()
+	}
+	fn tooltip_label(&self) -> String {
+		"Patch Tool".into()
+	}
+	fn tool_type(&self) -> crate::messages::tool::utility_types::ToolType {
+		ToolType::Patch
+	}
+}
+
+impl LayoutHolder for PatchTool {
+	fn layout(&self) -> Layout {
+		Layout::default()
+	}
+}
+
+#[message_handler_data]
+impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for PatchTool {
+	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
+		self.fsm_state.process_event(message, &mut self.data, context, &(), responses, true);
+	}
+
+	fn actions(&self) -> ActionList {
+		match self.fsm_state {
+			PatchToolFsmState::Ready => actions!(PatchToolMessageDiscriminant;
+				DragStart,
+			),
+			PatchToolFsmState::Selected => actions!(PatchToolMessageDiscriminant;
+				DragStart,
+				Abort,
+			),
+			PatchToolFsmState::Selecting | PatchToolFsmState::Dragging => actions!(PatchToolMessageDiscriminant;
+				DragStop,
+				PointerMove,
+				Abort,
+			),
+		}
+	}
+}
+
+impl ToolTransition for PatchTool {
+	fn event_to_message_map(&self) -> EventToMessageMap {
+		EventToMessageMap {
+			tool_abort: Some(PatchToolMessage::Abort.into()),
+			overlay_provider: Some(|context| PatchToolMessage::Overlays { context }.into()),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+struct PatchToolData {
+	/// The outline of the region to be repaired, in document space.
+	outline: Vec<DVec2>,
+	/// Where the drag of the outlined region began, in document space.
+	drag_start: DVec2,
+	/// The current displacement of the dragged region, in document space.
+	drag_offset: DVec2,
+}
+
+impl PatchToolData {
+	fn load_existing_patches(document: &DocumentMessageHandler) -> Option<(LayerNodeIdentifier, Vec<PatchRegion>, DAffine2)> {
+		if document.network_interface.selected_nodes().selected_layers(document.metadata()).count() != 1 {
+			return None;
+		}
+		let layer = document.network_interface.selected_nodes().selected_layers(document.metadata()).next()?;
+
+		let mut transform = DAffine2::IDENTITY;
+		for node_id in document.network_interface.upstream_flow_back_from_nodes(vec![layer.to_node()], &[], FlowType::HorizontalFlow) {
+			let Some(node) = document.network_interface.document_network().nodes.get(&node_id) else {
+				continue;
+			};
+			let Some(reference) = document.network_interface.reference(&node_id, &[]) else {
+				continue;
+			};
+
+			if reference == DefinitionIdentifier::ProtoNode(graphene_std::raster::healing::patch::IDENTIFIER) {
+				let patches_input = node.inputs.get(1)?;
+				let Some(TaggedValue::PatchRegions(patches)) = patches_input.as_value() else { continue };
+
+				return Some((layer, patches.clone(), transform));
+			}
+
+			if reference == DefinitionIdentifier::Network("Transform".into()) {
+				transform = get_current_transform(&node.inputs) * transform;
+			}
+		}
+
+		// The layer has no Patch node yet, so one will be created at the start of its chain where no transforms apply
+		Some((layer, Vec::new(), DAffine2::IDENTITY))
+	}
+
+	fn contains(&self, point: DVec2) -> bool {
+		point_in_polygon(point, &self.outline)
+	}
+
+	/// Adds the region as a patch on the selected layer, sampling from where it was dragged to.
+	fn apply_patch(&self, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+		let Some((layer, mut patches, transform)) = Self::load_existing_patches(document) else { return };
+
+		let document_to_layer = (document.metadata().downstream_transform_to_document(layer) * transform).inverse();
+		patches.push(PatchRegion {
+			outline: self.outline.iter().map(|&point| document_to_layer.transform_point2(point)).collect(),
+			source_offset: document_to_layer.transform_vector2(self.drag_offset),
+		});
+
+		responses.add(DocumentMessage::StartTransaction { label: "Patch Tool".into() });
+		responses.add(GraphOperationMessage::Patch { layer, patches });
+		responses.add(DocumentMessage::EndTransaction);
+	}
+}
+
+impl Fsm for PatchToolFsmState {
+	type ToolData = PatchToolData;
+	type ToolOptions = ();
+
+	fn transition(
+		self,
+		event: ToolMessage,
+		tool_data: &mut Self::ToolData,
+		tool_action_data: &mut ToolActionMessageContext,
+		_tool_options: &Self::ToolOptions,
+		responses: &mut VecDeque<Message>,
+	) -> Self {
+		let ToolActionMessageContext { document, input, .. } = tool_action_data;
+
+		let ToolMessage::Patch(event) = event else { return self };
+		let mouse_position = document.metadata().document_to_viewport.inverse().transform_point2(input.mouse.position);
+		match (self, event) {
+			(_, PatchToolMessage::Overlays { context: mut overlay_context }) => {
+				if tool_data.outline.len() > 1 {
+					let document_to_viewport = document.metadata().document_to_viewport;
+					let closed = self != PatchToolFsmState::Selecting;
+
+					let mut draw_outline = |offset: DVec2, color: &str| {
+						let points = tool_data.outline.iter().map(|&point| document_to_viewport.transform_point2(point + offset)).collect::<Vec<_>>();
+						for segment in points.windows(2) {
+							overlay_context.line(segment[0], segment[1], Some(color), None);
+						}
+						if closed && let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+							overlay_context.line(last, first, Some(color), None);
+						}
+					};
+
+					draw_outline(DVec2::ZERO, COLOR_OVERLAY_BLUE);
+					if self == PatchToolFsmState::Dragging {
+						draw_outline(tool_data.drag_offset, COLOR_OVERLAY_BLUE_50);
+					}
+				}
+
+				self
+			}
+			(PatchToolFsmState::Selected, PatchToolMessage::DragStart) if tool_data.contains(mouse_position) => {
+				tool_data.drag_start = mouse_position;
+				tool_data.drag_offset = DVec2::ZERO;
+
+				PatchToolFsmState::Dragging
+			}
+			(PatchToolFsmState::Ready | PatchToolFsmState::Selected, PatchToolMessage::DragStart) => {
+				tool_data.outline = vec![mouse_position];
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Selecting
+			}
+			(PatchToolFsmState::Selecting, PatchToolMessage::PointerMove) => {
+				tool_data.outline.push(mouse_position);
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Selecting
+			}
+			(PatchToolFsmState::Selecting, PatchToolMessage::DragStop) => {
+				responses.add(OverlaysMessage::Draw);
+				if tool_data.outline.len() < 3 {
+					tool_data.outline.clear();
+					return PatchToolFsmState::Ready;
+				}
+
+				PatchToolFsmState::Selected
+			}
+			(PatchToolFsmState::Dragging, PatchToolMessage::PointerMove) => {
+				tool_data.drag_offset = mouse_position - tool_data.drag_start;
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Dragging
+			}
+			(PatchToolFsmState::Dragging, PatchToolMessage::DragStop) => {
+				if tool_data.drag_offset != DVec2::ZERO {
+					tool_data.apply_patch(document, responses);
+				}
+				tool_data.outline.clear();
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Ready
+			}
+			(_, PatchToolMessage::Abort) => {
+				tool_data.outline.clear();
+				responses.add(OverlaysMessage::Draw);
+
+				PatchToolFsmState::Ready
+			}
+			_ => self,
+		}
+	}
+
+	fn update_hints(&self, responses: &mut VecDeque<Message>) {
+		let hint_data = match self {
+			PatchToolFsmState::Ready => HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDrag, "Outline Region to Repair")])]),
+			PatchToolFsmState::Selected => HintData(vec![
+				HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDrag, "Drag Region onto Source")]),
+				HintGroup(vec![HintInfo::keys([Key::Escape], "Deselect")]),
+			]),
+			PatchToolFsmState::Selecting | PatchToolFsmState::Dragging => {
+				HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()])])
+			}
+		};
+
+		hint_data.send_layout(responses);
+	}
+
+	fn update_cursor(&self, responses: &mut VecDeque<Message>) {
+		let cursor = match self {
+			PatchToolFsmState::Dragging => MouseCursorIcon::Grabbing,
+			_ => MouseCursorIcon::Default,
+		};
+		responses.add(FrontendMessage::UpdateMouseCursor { cursor });
+	}
+}
@@ -761,7 +761,7 @@ impl PathToolData {
 			self.frontier_handles_info.as_ref(),
 			point_editing_mode,
 		) {
-			responses.add(DocumentMessage::StartTransaction);
+			responses.add(DocumentMessage::StartTransaction { label: "Path Tool".into() });
 
 			self.set_ghost_outline(shape_editor, document);
 
@@ -844,7 +844,7 @@ impl PathToolData {
 		}
 		// We didn't find a point nearby, so we will see if there is a segment to select or insert a point on
 		else if let Some(segment) = shape_editor.upper_closest_segment(&document.network_interface, input.mouse.position, SELECTION_THRESHOLD) {
-			responses.add(DocumentMessage::StartTransaction);
+			responses.add(DocumentMessage::StartTransaction { label: "Path Tool".into() });
 
 			self.set_ghost_outline(shape_editor, document);
 
@@ -2666,7 +2666,7 @@ impl Fsm for PathToolFsmState {
 				PathToolFsmState::Ready
 			}
 			(_, PathToolMessage::ClosePath) => {
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Path Tool".into() });
 				shape_editor.close_selected_path(document, responses);
 				responses.add(DocumentMessage::EndTransaction);
 
@@ -2712,7 +2712,7 @@ impl Fsm for PathToolFsmState {
 
 				if let (Some(start_id), Some(end_id)) = (start_point_id, end_point_id) {
 					// Create segment directly
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Path Tool".into() });
 
 					let segment_id = SegmentId::generate();
 					let modification_type = VectorModificationType::InsertSegment {
@@ -2729,7 +2729,7 @@ impl Fsm for PathToolFsmState {
 				self
 			}
 			(_, PathToolMessage::StartSlidingPoint) => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Path Tool".into() });
 				if tool_data.start_sliding_point(shape_editor, document) {
 					PathToolFsmState::SlidingPoint
 				} else {
@@ -2819,7 +2819,7 @@ impl Fsm for PathToolFsmState {
 				// Deserialize the data
 				if let Ok(data) = serde_json::from_str::<Vec<(LayerNodeIdentifier, Vector, DAffine2)>>(&data) {
 					shape_editor.deselect_all_points();
-					responses.add(DocumentMessage::AddTransaction);
+					responses.add(DocumentMessage::AddTransaction { label: "Path Tool".into() });
 					let mut new_layers = Vec::new();
 					for (layer, new_vector, transform) in data {
 						// If layer is not selected then create a new selected layer
@@ -2934,7 +2934,7 @@ impl Fsm for PathToolFsmState {
 				PathToolFsmState::Ready
 			}
 			(_, PathToolMessage::Duplicate) => {
-				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::AddTransaction { label: "Path Tool".into() });
 
 				// Copy the existing selected geometry and paste it in the existing layers
 				for (layer, layer_selection_state) in shape_editor.selected_shape_state.clone() {
@@ -3045,7 +3045,7 @@ impl Fsm for PathToolFsmState {
 				if nearest_point.is_some() {
 					// Flip the selected point between smooth and sharp
 					if !tool_data.double_click_handled && tool_data.drag_start_pos.distance(input.mouse.position) <= DRAG_THRESHOLD {
-						responses.add(DocumentMessage::StartTransaction);
+						responses.add(DocumentMessage::StartTransaction { label: "Path Tool".into() });
 
 						shape_editor.select_points_by_layer_and_id(&tool_data.saved_points_before_anchor_convert_smooth_sharp);
 						shape_editor.flip_smooth_sharp(&document.network_interface, responses);
@@ -3199,20 +3199,20 @@ impl Fsm for PathToolFsmState {
 				self
 			}
 			(_, PathToolMessage::ManipulatorMakeHandlesColinear) => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Path Tool".into() });
 				shape_editor.convert_selected_manipulators_to_colinear_handles(responses, document);
 				responses.add(DocumentMessage::EndTransaction);
 				responses.add(PathToolMessage::SelectionChanged);
 				PathToolFsmState::Ready
 			}
 			(_, PathToolMessage::ManipulatorMakeHandlesFree) => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Path Tool".into() });
 				shape_editor.disable_colinear_handles_state_on_selected(&document.network_interface, responses);
 				responses.add(DocumentMessage::EndTransaction);
 				PathToolFsmState::Ready
 			}
 			(_, PathToolMessage::SetPivot { position }) => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Path Tool".into() });
 
 				tool_data.pivot_gizmo.pivot.last_non_none_reference_point = position;
 				let position: Option<DVec2> = position.into();
@@ -1805,7 +1805,7 @@ impl Fsm for PenToolFsmState {
 				self
 			}
 			(PenToolFsmState::Ready, PenToolMessage::DragStart { append_to_selected }) => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Pen Tool".into() });
 				tool_data.handle_mode = HandleMode::Free;
 
 				// Get the closest point and the segment it is on
@@ -1843,7 +1843,7 @@ impl Fsm for PenToolFsmState {
 					PenToolFsmState::DraggingHandle(tool_data.handle_mode)
 				} else {
 					if tool_data.handle_end.is_some() {
-						responses.add(DocumentMessage::StartTransaction);
+						responses.add(DocumentMessage::StartTransaction { label: "Pen Tool".into() });
 					}
 					// Merge two layers if the point is connected to the end point of another path
 
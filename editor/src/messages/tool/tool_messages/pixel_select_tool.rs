@@ -0,0 +1,414 @@
+use super::tool_prelude::*;
+use crate::consts::{COLOR_OVERLAY_BLACK_75, COLOR_OVERLAY_WHITE, DRAG_THRESHOLD};
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use graphene_std::raster::selection::{SelectionCombine, SelectionShape, SelectionStep};
+
+#[derive(Default, ExtractField)]
+pub struct PixelSelectTool {
+	fsm_state: PixelSelectToolFsmState,
+	data: PixelSelectToolData,
+	options: PixelSelectOptions,
+}
+
+pub struct PixelSelectOptions {
+	mode: PixelSelectMode,
+	combine: SelectionCombine,
+	tolerance: f64,
+	contiguous: bool,
+	anti_aliasing: bool,
+}
+
+impl Default for PixelSelectOptions {
+	fn default() -> Self {
+		Self {
+			mode: PixelSelectMode::Rectangle,
+			combine: SelectionCombine::Replace,
+			tolerance: 32.,
+			contiguous: true,
+			anti_aliasing: true,
+		}
+	}
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Hash, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum PixelSelectMode {
+	#[default]
+	Rectangle = 0,
+	Ellipse,
+	Lasso,
+	MagicWand,
+}
+
+#[impl_message(Message, ToolMessage, PixelSelect)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum PixelSelectToolMessage {
+	// Standard messages
+	Abort,
+	Overlays { context: OverlayContext },
+
+	// Tool-specific messages
+	DragStart { add: Key, subtract: Key },
+	DragStop,
+	PointerMove,
+	UpdateOptions { options: PixelSelectToolMessageOptionsUpdate },
+}
+
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum PixelSelectToolMessageOptionsUpdate {
+	Mode(PixelSelectMode),
+	Combine(SelectionCombine),
+	Tolerance(f64),
+	Contiguous(bool),
+	AntiAliasing(bool),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum PixelSelectToolFsmState {
+	#[default]
+	Ready,
+	/// Dragging out a marquee or drawing a lasso.
+	Drawing,
+}
+
+impl ToolMetadata for PixelSelectTool {
+	fn icon_name(&self) -> String {
+		"GeneralSelectTool".into()
+	}
+	fn tooltip_label(&self) -> String {
+		"Pixel Selection Tool".into()
+	}
+	fn tool_type(&self) -> crate::messages::tool::utility_types::ToolType {
+		ToolType::PixelSelect
+	}
+}
+
+impl LayoutHolder for PixelSelectTool {
+	fn layout(&self) -> Layout {
+		let mode_entries: Vec<_> = [
+			(PixelSelectMode::Rectangle, "Rectangle"),
+			(PixelSelectMode::Ellipse, "Ellipse"),
+			(PixelSelectMode::Lasso, "Lasso"),
+			(PixelSelectMode::MagicWand, "Magic Wand"),
+		]
+		.into_iter()
+		.map(|(mode, label)| {
+			RadioEntryData::new(format!("{mode:?}")).label(label).on_update(move |_| {
+				PixelSelectToolMessage::UpdateOptions {
+					options: PixelSelectToolMessageOptionsUpdate::Mode(mode),
+				}
+				.into()
+			})
+		})
+		.collect();
+		let mut widgets = vec![RadioInput::new(mode_entries).selected_index(Some(self.options.mode as u32)).widget_instance()];
+
+		if self.options.mode == PixelSelectMode::MagicWand {
+			widgets.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
+			widgets.push(
+				NumberInput::new(Some(self.options.tolerance))
+					.label("Tolerance")
+					.min(0.)
+					.max(100.)
+					.mode_range()
+					.unit("%")
+					.tooltip_description("How different the colors of the selected pixels can be from the color of the clicked pixel.")
+					.on_update(|number_input: &NumberInput| {
+						PixelSelectToolMessage::UpdateOptions {
+							options: PixelSelectToolMessageOptionsUpdate::Tolerance(number_input.value.unwrap_or_default()),
+						}
+						.into()
+					})
+					.widget_instance(),
+			);
+
+			widgets.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
+			let contiguous_description = "Only select pixels connected to the clicked pixel through other selected pixels. When disabled, similar colors are selected across the whole image.";
+			let checkbox_id = CheckboxId::new();
+			widgets.push(
+				CheckboxInput::new(self.options.contiguous)
+					.on_update(|&CheckboxInput { checked, .. }| {
+						PixelSelectToolMessage::UpdateOptions {
+							options: PixelSelectToolMessageOptionsUpdate::Contiguous(checked),
+						}
+						.into()
+					})
+					.tooltip_label("Contiguous")
+					.tooltip_description(contiguous_description)
+					.for_label(checkbox_id)
+					.widget_instance(),
+			);
+			widgets.push(
+				TextLabel::new("Contiguous")
+					.tooltip_label("Contiguous")
+					.tooltip_description(contiguous_description)
+					.for_checkbox(checkbox_id)
+					.widget_instance(),
+			);
+		}
+
+		widgets.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
+		let anti_aliasing_description = "Smooth the edges of the selection with partially selected pixels.";
+		let checkbox_id = CheckboxId::new();
+		widgets.push(
+			CheckboxInput::new(self.options.anti_aliasing)
+				.on_update(|&CheckboxInput { checked, .. }| {
+					PixelSelectToolMessage::UpdateOptions {
+						options: PixelSelectToolMessageOptionsUpdate::AntiAliasing(checked),
+					}
+					.into()
+				})
+				.tooltip_label("Anti-aliasing")
+				.tooltip_description(anti_aliasing_description)
+				.for_label(checkbox_id)
+				.widget_instance(),
+		);
+		widgets.push(
+			TextLabel::new("Anti-aliasing")
+				.tooltip_label("Anti-aliasing")
+				.tooltip_description(anti_aliasing_description)
+				.for_checkbox(checkbox_id)
+				.widget_instance(),
+		);
+
+		widgets.push(Separator::new(SeparatorStyle::Unrelated).widget_instance());
+		let combine_entries: Vec<_> = [
+			(SelectionCombine::Replace, "New", "Replace the selection"),
+			(SelectionCombine::Add, "Add", "Add to the selection (Shift)"),
+			(SelectionCombine::Subtract, "Subtract", "Subtract from the selection (Alt)"),
+			(SelectionCombine::Intersect, "Intersect", "Intersect with the selection (Shift+Alt)"),
+		]
+		.into_iter()
+		.map(|(combine, label, description)| {
+			RadioEntryData::new(format!("{combine:?}")).label(label).tooltip_description(description).on_update(move |_| {
+				PixelSelectToolMessage::UpdateOptions {
+					options: PixelSelectToolMessageOptionsUpdate::Combine(combine),
+				}
+				.into()
+			})
+		})
+		.collect();
+		let combine_index = [SelectionCombine::Replace, SelectionCombine::Add, SelectionCombine::Subtract, SelectionCombine::Intersect]
+			.iter()
+			.position(|&combine| combine == self.options.combine)
+			.map(|index| index as u32);
+		widgets.push(RadioInput::new(combine_entries).selected_index(combine_index).widget_instance());
+
+		Layout(vec![LayoutGroup::Row { widgets }])
+	}
+}
+
+#[message_handler_data]
+impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for PixelSelectTool {
+	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
+		let ToolMessage::PixelSelect(PixelSelectToolMessage::UpdateOptions { options }) = message else {
+			self.fsm_state.process_event(message, &mut self.data, context, &self.options, responses, true);
+			return;
+		};
+		match options {
+			PixelSelectToolMessageOptionsUpdate::Mode(mode) => self.options.mode = mode,
+			PixelSelectToolMessageOptionsUpdate::Combine(combine) => self.options.combine = combine,
+			PixelSelectToolMessageOptionsUpdate::Tolerance(tolerance) => self.options.tolerance = tolerance,
+			PixelSelectToolMessageOptionsUpdate::Contiguous(contiguous) => self.options.contiguous = contiguous,
+			PixelSelectToolMessageOptionsUpdate::AntiAliasing(anti_aliasing) => self.options.anti_aliasing = anti_aliasing,
+		}
+
+		self.send_layout(responses, LayoutTarget::ToolOptions);
+	}
+
+	fn actions(&self) -> ActionList {
+		match self.fsm_state {
+			PixelSelectToolFsmState::Ready => actions!(PixelSelectToolMessageDiscriminant;
+				DragStart,
+				UpdateOptions,
+			),
+			PixelSelectToolFsmState::Drawing => actions!(PixelSelectToolMessageDiscriminant;
+				DragStop,
+				PointerMove,
+				Abort,
+				UpdateOptions,
+			),
+		}
+	}
+}
+
+impl ToolTransition for PixelSelectTool {
+	fn event_to_message_map(&self) -> EventToMessageMap {
+		EventToMessageMap {
+			tool_abort: Some(PixelSelectToolMessage::Abort.into()),
+			overlay_provider: Some(|context| PixelSelectToolMessage::Overlays { context }.into()),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+struct PixelSelectToolData {
+	/// Where the drag began, in document space.
+	drag_start: DVec2,
+	/// The current position of the drag, in document space.
+	drag_current: DVec2,
+	/// The points drawn by the lasso, in document space.
+	lasso: Vec<DVec2>,
+	/// How the shape being drawn is combined with the existing selection.
+	combine: SelectionCombine,
+}
+
+impl PixelSelectToolData {
+	/// The shape drawn by the drag, in document space.
+	fn shape(&self, mode: PixelSelectMode) -> Option<SelectionShape> {
+		let min = self.drag_start.min(self.drag_current);
+		let transform = DAffine2::from_scale_angle_translation(self.drag_start.max(self.drag_current) - min, 0., min);
+
+		match mode {
+			PixelSelectMode::Rectangle => Some(SelectionShape::Rectangle { transform }),
+			PixelSelectMode::Ellipse => Some(SelectionShape::Ellipse { transform }),
+			PixelSelectMode::Lasso if self.lasso.len() >= 3 => Some(SelectionShape::Polygons { outlines: vec![self.lasso.clone()] }),
+			PixelSelectMode::Lasso | PixelSelectMode::MagicWand => None,
+		}
+	}
+}
+
+impl Fsm for PixelSelectToolFsmState {
+	type ToolData = PixelSelectToolData;
+	type ToolOptions = PixelSelectOptions;
+
+	fn transition(
+		self,
+		event: ToolMessage,
+		tool_data: &mut Self::ToolData,
+		tool_action_data: &mut ToolActionMessageContext,
+		tool_options: &Self::ToolOptions,
+		responses: &mut VecDeque<Message>,
+	) -> Self {
+		let ToolActionMessageContext { document, input, .. } = tool_action_data;
+
+		let ToolMessage::PixelSelect(event) = event else { return self };
+		let document_to_viewport = document.metadata().document_to_viewport;
+		let mouse_position = document_to_viewport.inverse().transform_point2(input.mouse.position);
+		match (self, event) {
+			(PixelSelectToolFsmState::Drawing, PixelSelectToolMessage::Overlays { context: mut overlay_context }) => {
+				if let Some(shape) = tool_data.shape(tool_options.mode) {
+					for outline in shape.outlines().unwrap_or_default() {
+						let outline = outline.into_iter().map(|point| document_to_viewport.transform_point2(point)).collect::<Vec<_>>();
+						overlay_context.polygon(&outline, Some(COLOR_OVERLAY_BLACK_75), None);
+						overlay_context.dashed_polygon(&outline, Some(COLOR_OVERLAY_WHITE), None, Some(4.), Some(4.), None);
+					}
+				}
+
+				self
+			}
+			(PixelSelectToolFsmState::Ready, PixelSelectToolMessage::DragStart { add, subtract }) => {
+				tool_data.combine = match (input.keyboard.key(add), input.keyboard.key(subtract)) {
+					(true, true) => SelectionCombine::Intersect,
+					(true, false) => SelectionCombine::Add,
+					(false, true) => SelectionCombine::Subtract,
+					(false, false) => tool_options.combine,
+				};
+
+				// The magic wand selects from a single click
+				if tool_options.mode == PixelSelectMode::MagicWand {
+					let step = SelectionStep {
+						shape: SelectionShape::MagicWand {
+							seed: mouse_position,
+							tolerance: tool_options.tolerance,
+							contiguous: tool_options.contiguous,
+						},
+						combine: tool_data.combine,
+						anti_aliasing: tool_options.anti_aliasing,
+					};
+					responses.add(DocumentMessage::AddPixelSelectionStep { step });
+					return PixelSelectToolFsmState::Ready;
+				}
+
+				tool_data.drag_start = mouse_position;
+				tool_data.drag_current = mouse_position;
+				tool_data.lasso = vec![mouse_position];
+
+				PixelSelectToolFsmState::Drawing
+			}
+			(PixelSelectToolFsmState::Drawing, PixelSelectToolMessage::PointerMove) => {
+				tool_data.drag_current = mouse_position;
+				tool_data.lasso.push(mouse_position);
+				responses.add(OverlaysMessage::Draw);
+
+				PixelSelectToolFsmState::Drawing
+			}
+			(PixelSelectToolFsmState::Drawing, PixelSelectToolMessage::DragStop) => {
+				let drag_distance = document_to_viewport.transform_vector2(tool_data.drag_current - tool_data.drag_start).length();
+				let dragged = if tool_options.mode == PixelSelectMode::Lasso {
+					tool_data.lasso.len() >= 3
+				} else {
+					drag_distance >= DRAG_THRESHOLD
+				};
+
+				match tool_data.shape(tool_options.mode) {
+					Some(shape) if dragged => {
+						let step = SelectionStep {
+							shape,
+							combine: tool_data.combine,
+							anti_aliasing: tool_options.anti_aliasing,
+						};
+						responses.add(DocumentMessage::AddPixelSelectionStep { step });
+					}
+					// Clicking without dragging a new selection deselects
+					_ if tool_data.combine == SelectionCombine::Replace => responses.add(DocumentMessage::DeselectPixels),
+					_ => {}
+				}
+				tool_data.lasso.clear();
+				responses.add(OverlaysMessage::Draw);
+
+				PixelSelectToolFsmState::Ready
+			}
+			(_, PixelSelectToolMessage::Abort) => {
+				tool_data.lasso.clear();
+				responses.add(OverlaysMessage::Draw);
+
+				PixelSelectToolFsmState::Ready
+			}
+			_ => self,
+		}
+	}
+
+	fn update_hints(&self, responses: &mut VecDeque<Message>) {
+		let hint_data = match self {
+			PixelSelectToolFsmState::Ready => HintData(vec![
+				HintGroup(vec![
+					HintInfo::mouse(MouseMotion::LmbDrag, "Select Pixels"),
+					HintInfo::keys([Key::Shift], "Add").prepend_plus(),
+					HintInfo::keys([Key::Alt], "Subtract").prepend_plus(),
+					HintInfo::keys([Key::Shift, Key::Alt], "Intersect").prepend_plus(),
+				]),
+				HintGroup(vec![HintInfo::mouse(MouseMotion::Lmb, "Deselect")]),
+			]),
+			PixelSelectToolFsmState::Drawing => HintData(vec![HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()])]),
+		};
+
+		hint_data.send_layout(responses);
+	}
+
+	fn update_cursor(&self, responses: &mut VecDeque<Message>) {
+		responses.add(FrontendMessage::UpdateMouseCursor { cursor: MouseCursorIcon::Crosshair });
+	}
+}
+
+#[cfg(test)]
+mod test_pixel_select {
+	pub use crate::test_utils::test_prelude::*;
+
+	#[tokio::test]
+	async fn selection_edits_are_undoable() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.create_raster_image(Image::new(100, 100, Color::WHITE), Some((0., 0.))).await;
+
+		editor.drag_tool(ToolType::PixelSelect, 10., 10., 50., 50., ModifierKeys::empty()).await;
+		assert_eq!(editor.active_document().pixel_selection.len(), 1);
+		editor.handle_message(DocumentMessage::DeselectPixels).await;
+		assert!(editor.active_document().pixel_selection.is_empty());
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().pixel_selection.len(), 1);
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert!(editor.active_document().pixel_selection.is_empty());
+	}
+}
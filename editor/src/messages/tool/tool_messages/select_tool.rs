@@ -1056,7 +1056,7 @@ impl Fsm for SelectToolFsmState {
 				let can_grab_compass_rose = compass_rose_state.can_grab() && (show_compass || bounds.is_none());
 
 				let state = if let Some(state) = tool_data.state_from_pivot_gizmo(input.mouse.position) {
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Select Tool".into() });
 
 					// tool_data.snap_manager.start_snap(document, input, document.bounding_boxes(), true, true);
 					// tool_data.snap_manager.add_all_document_handles(document, input, &[], &[], &[]);
@@ -1073,7 +1073,7 @@ impl Fsm for SelectToolFsmState {
 				}
 				// Dragging the selected layers around to transform them
 				else if can_grab_compass_rose || intersection.is_some_and(|intersection| selected.iter().any(|selected_layer| intersection.starts_with(*selected_layer, document.metadata()))) {
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Select Tool".into() });
 
 					if input.keyboard.key(select_deepest) || tool_data.nested_selection_behavior == NestedSelectionBehavior::Deepest {
 						tool_data.select_single_layer = intersection;
@@ -1127,7 +1127,7 @@ impl Fsm for SelectToolFsmState {
 						}
 						tool_data.get_snap_candidates(document, input, viewport);
 
-						responses.add(DocumentMessage::StartTransaction);
+						responses.add(DocumentMessage::StartTransaction { label: "Select Tool".into() });
 
 						tool_data.pivot_gizmo_start = Some(tool_data.drag_current);
 
@@ -1327,6 +1327,17 @@ impl Fsm for SelectToolFsmState {
 					cursor = MouseCursorIcon::Move;
 				}
 
+				// Hovering a guide shows that it can be dragged across its length
+				if let Some(guide) = document.guide_at(input.mouse.position) {
+					cursor = if guide.is_horizontal() {
+						MouseCursorIcon::NSResize
+					} else if guide.is_vertical() {
+						MouseCursorIcon::EWResize
+					} else {
+						MouseCursorIcon::Move
+					};
+				}
+
 				// Generate the hover outline
 				responses.add(OverlaysMessage::Draw);
 
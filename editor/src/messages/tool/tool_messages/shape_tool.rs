@@ -764,7 +764,7 @@ impl Fsm for ShapeToolFsmState {
 
 				if tool_data.gizmo_manager.handle_click() && !input.keyboard.key(Key::Accel) {
 					tool_data.data.drag_start = document.metadata().document_to_viewport.inverse().transform_point2(mouse_pos);
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Shape Tool".into() });
 
 					let cursor = tool_data.gizmo_manager.mouse_cursor_icon().unwrap_or(MouseCursorIcon::Crosshair);
 					tool_data.cursor = cursor;
@@ -774,7 +774,7 @@ impl Fsm for ShapeToolFsmState {
 						modifier: ShapeToolData::shape_tool_modifier_keys(),
 					});
 
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Shape Tool".into() });
 
 					return ShapeToolFsmState::ModifyingGizmo;
 				}
@@ -842,7 +842,7 @@ impl Fsm for ShapeToolFsmState {
 					}
 				}
 
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Shape Tool".into() });
 
 				let node = match tool_data.current_shape {
 					ShapeType::Polygon => Polygon::create_node(tool_options.vertices),
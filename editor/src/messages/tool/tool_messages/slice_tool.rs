@@ -0,0 +1,323 @@
+use super::tool_prelude::*;
+use crate::application::generate_uuid;
+use crate::consts::DRAG_THRESHOLD;
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::misc::SliceId;
+use crate::messages::tool::common_functionality::transformation_cage::*;
+
+#[derive(Default, ExtractField)]
+pub struct SliceTool {
+	fsm_state: SliceToolFsmState,
+	data: SliceToolData,
+}
+
+#[impl_message(Message, ToolMessage, Slice)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum SliceToolMessage {
+	// Standard messages
+	Abort,
+	Overlays { context: OverlayContext },
+
+	// Tool-specific messages
+	DeleteSelected,
+	PointerDown,
+	PointerMove { constrain_axis_or_aspect: Key, center: Key },
+	PointerUp,
+}
+
+impl ToolMetadata for SliceTool {
+	fn icon_name(&self) -> String {
+		"GeneralArtboardTool".into()
+	}
+	fn tooltip_label(&self) -> String {
+		"Slice Tool".into()
+	}
+	fn tool_type(&self) -> crate::messages::tool::utility_types::ToolType {
+		ToolType::Slice
+	}
+}
+
+#[message_handler_data]
+impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for SliceTool {
+	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
+		self.fsm_state.process_event(message, &mut self.data, context, &(), responses, false);
+	}
+
+	fn actions(&self) -> ActionList {
+		let mut common = actions!(SliceToolMessageDiscriminant;
+			DeleteSelected,
+			PointerMove,
+		);
+
+		let additional = match self.fsm_state {
+			SliceToolFsmState::Ready => actions!(SliceToolMessageDiscriminant; PointerDown),
+			_ => actions!(SliceToolMessageDiscriminant; PointerUp, Abort),
+		};
+		common.extend(additional);
+
+		common
+	}
+}
+
+impl LayoutHolder for SliceTool {
+	fn layout(&self) -> Layout {
+		Layout::default()
+	}
+}
+
+impl ToolTransition for SliceTool {
+	fn event_to_message_map(&self) -> EventToMessageMap {
+		EventToMessageMap {
+			tool_abort: Some(SliceToolMessage::Abort.into()),
+			overlay_provider: Some(|context| SliceToolMessage::Overlays { context }.into()),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SliceToolFsmState {
+	#[default]
+	Ready,
+	Drawing,
+	ResizingBounds,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SliceToolData {
+	bounding_box_manager: Option<BoundingBoxManager>,
+	selected_slice: Option<SliceId>,
+	cursor: MouseCursorIcon,
+	/// Where the drag began, in document space.
+	drag_start: DVec2,
+}
+
+impl SliceToolData {
+	/// The topmost slice under the mouse, which is the one drawn last.
+	fn hovered_slice(document: &DocumentMessageHandler, input: &InputPreprocessorMessageHandler) -> Option<SliceId> {
+		let mouse = document.metadata().document_to_viewport.inverse().transform_point2(input.mouse.position);
+		document
+			.export_slices
+			.iter()
+			.rev()
+			.find(|slice| mouse.cmpge(slice.bounds[0]).all() && mouse.cmple(slice.bounds[1]).all())
+			.map(|slice| slice.id)
+	}
+
+	fn selected_bounds(&self, document: &DocumentMessageHandler) -> Option<[DVec2; 2]> {
+		let id = self.selected_slice?;
+		document.export_slices.iter().find(|slice| slice.id == id).map(|slice| slice.bounds)
+	}
+
+	/// Fits the bounding box to the selected slice, or discards it if no slice is selected.
+	fn update_bounding_box(&mut self, document: &DocumentMessageHandler) {
+		let Some(bounds) = self.selected_bounds(document) else {
+			self.bounding_box_manager.take();
+			return;
+		};
+
+		let bounding_box_manager = self.bounding_box_manager.get_or_insert(BoundingBoxManager::default());
+		bounding_box_manager.bounds = bounds;
+		bounding_box_manager.transform = document.metadata().document_to_viewport;
+	}
+
+	fn check_dragging_bounds(&mut self, cursor: DVec2) -> bool {
+		let Some(bounding_box) = self.bounding_box_manager.as_mut() else { return false };
+		let Some((top, bottom, left, right)) = bounding_box.check_selected_edges(cursor) else {
+			return false;
+		};
+
+		let selected_edges = SelectedEdges::new(top, bottom, left, right, bounding_box.bounds);
+		bounding_box.opposite_pivot = selected_edges.calculate_pivot();
+		bounding_box.selected_edges = Some(selected_edges);
+		bounding_box.center_of_transformation = bounding_box.transform.transform_point2((bounding_box.bounds[0] + bounding_box.bounds[1]) / 2.);
+
+		true
+	}
+}
+
+impl Fsm for SliceToolFsmState {
+	type ToolData = SliceToolData;
+	type ToolOptions = ();
+
+	fn transition(self, event: ToolMessage, tool_data: &mut Self::ToolData, tool_action_data: &mut ToolActionMessageContext, _tool_options: &(), responses: &mut VecDeque<Message>) -> Self {
+		let ToolActionMessageContext { document, input, .. } = tool_action_data;
+
+		let ToolMessage::Slice(event) = event else { return self };
+		let document_to_viewport = document.metadata().document_to_viewport;
+		match (self, event) {
+			(state, SliceToolMessage::Overlays { context: mut overlay_context }) => {
+				tool_data.update_bounding_box(document);
+				if let Some(bounding_box_manager) = &mut tool_data.bounding_box_manager
+					&& state != SliceToolFsmState::Drawing
+				{
+					bounding_box_manager.render_overlays(&mut overlay_context, true);
+				}
+
+				self
+			}
+			(SliceToolFsmState::Ready, SliceToolMessage::PointerDown) => {
+				tool_data.drag_start = document_to_viewport.inverse().transform_point2(input.mouse.position);
+				tool_data.update_bounding_box(document);
+
+				if tool_data.check_dragging_bounds(input.mouse.position) {
+					responses.add(DocumentMessage::StartTransaction { label: "Resize Slice".into() });
+					return SliceToolFsmState::ResizingBounds;
+				}
+
+				if let Some(id) = SliceToolData::hovered_slice(document, input) {
+					tool_data.selected_slice = Some(id);
+					responses.add(OverlaysMessage::Draw);
+					return SliceToolFsmState::Ready;
+				}
+
+				// The slice is added once the drag is large enough to give it a size
+				tool_data.selected_slice = Some(SliceId(generate_uuid()));
+				responses.add(DocumentMessage::StartTransaction { label: "Draw Slice".into() });
+				SliceToolFsmState::Drawing
+			}
+			(SliceToolFsmState::Drawing, SliceToolMessage::PointerMove { constrain_axis_or_aspect, center }) => {
+				let Some(id) = tool_data.selected_slice else { return SliceToolFsmState::Drawing };
+
+				let start = tool_data.drag_start;
+				let mut end = document_to_viewport.inverse().transform_point2(input.mouse.position);
+				if input.keyboard.get(constrain_axis_or_aspect as usize) {
+					let size = end - start;
+					end = start + size.signum() * size.abs().max_element();
+				}
+				let start = if input.keyboard.get(center as usize) { start * 2. - end } else { start };
+
+				if document_to_viewport.transform_vector2(end - start).length() >= DRAG_THRESHOLD {
+					responses.add(DocumentMessage::SetExportSliceBounds {
+						id,
+						bounds: [start.round(), end.round()],
+					});
+				}
+
+				SliceToolFsmState::Drawing
+			}
+			(SliceToolFsmState::ResizingBounds, SliceToolMessage::PointerMove { constrain_axis_or_aspect, center }) => {
+				let (Some(id), Some(bounding_box)) = (tool_data.selected_slice, &tool_data.bounding_box_manager) else {
+					return SliceToolFsmState::ResizingBounds;
+				};
+				let Some(movement) = &bounding_box.selected_edges else { return SliceToolFsmState::ResizingBounds };
+
+				let center = input.keyboard.get(center as usize).then_some(bounding_box.center_of_transformation);
+				let constrain = input.keyboard.get(constrain_axis_or_aspect as usize);
+				let (min, size) = movement.new_size(input.mouse.position, bounding_box.transform, center, constrain, None);
+				responses.add(DocumentMessage::SetExportSliceBounds {
+					id,
+					bounds: [min.round(), (min + size).round()],
+				});
+
+				SliceToolFsmState::ResizingBounds
+			}
+			(SliceToolFsmState::Ready, SliceToolMessage::PointerMove { .. }) => {
+				tool_data.update_bounding_box(document);
+				let mut cursor = tool_data
+					.bounding_box_manager
+					.as_ref()
+					.map_or(MouseCursorIcon::Default, |bounds| bounds.get_cursor(input, false, false, None));
+				if cursor == MouseCursorIcon::Default && SliceToolData::hovered_slice(document, input).is_none() {
+					cursor = MouseCursorIcon::Crosshair;
+				}
+
+				if tool_data.cursor != cursor {
+					tool_data.cursor = cursor;
+					responses.add(FrontendMessage::UpdateMouseCursor { cursor });
+				}
+
+				SliceToolFsmState::Ready
+			}
+			(SliceToolFsmState::Drawing | SliceToolFsmState::ResizingBounds, SliceToolMessage::PointerUp) => {
+				// A click that didn't draw a slice deselects the previous one
+				if tool_data.selected_bounds(document).is_none() {
+					tool_data.selected_slice = None;
+				}
+				if let Some(bounds) = &mut tool_data.bounding_box_manager {
+					bounds.selected_edges = None;
+				}
+
+				responses.add(DocumentMessage::EndTransaction);
+				responses.add(OverlaysMessage::Draw);
+
+				SliceToolFsmState::Ready
+			}
+			(SliceToolFsmState::Drawing | SliceToolFsmState::ResizingBounds, SliceToolMessage::Abort) => {
+				if self == SliceToolFsmState::Drawing {
+					tool_data.selected_slice = None;
+				}
+
+				responses.add(DocumentMessage::AbortTransaction);
+				responses.add(OverlaysMessage::Draw);
+
+				SliceToolFsmState::Ready
+			}
+			(_, SliceToolMessage::Abort) => {
+				tool_data.selected_slice = None;
+				responses.add(OverlaysMessage::Draw);
+
+				SliceToolFsmState::Ready
+			}
+			(_, SliceToolMessage::DeleteSelected) => {
+				if let Some(id) = tool_data.selected_slice.take() {
+					responses.add(DocumentMessage::DeleteExportSlice { id });
+				}
+
+				SliceToolFsmState::Ready
+			}
+			_ => self,
+		}
+	}
+
+	fn update_hints(&self, responses: &mut VecDeque<Message>) {
+		let hint_data = match self {
+			SliceToolFsmState::Ready => HintData(vec![
+				HintGroup(vec![HintInfo::mouse(MouseMotion::LmbDrag, "Draw Slice")]),
+				HintGroup(vec![HintInfo::mouse(MouseMotion::Lmb, "Select Slice")]),
+				HintGroup(vec![HintInfo::keys([Key::Backspace], "Delete Slice")]),
+			]),
+			SliceToolFsmState::Drawing => HintData(vec![
+				HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()]),
+				HintGroup(vec![HintInfo::keys([Key::Shift], "Constrain Square"), HintInfo::keys([Key::Alt], "From Center")]),
+			]),
+			SliceToolFsmState::ResizingBounds => HintData(vec![
+				HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()]),
+				HintGroup(vec![HintInfo::keys([Key::Shift], "Preserve Aspect Ratio"), HintInfo::keys([Key::Alt], "From Center")]),
+			]),
+		};
+
+		hint_data.send_layout(responses);
+	}
+
+	fn update_cursor(&self, responses: &mut VecDeque<Message>) {
+		responses.add(FrontendMessage::UpdateMouseCursor { cursor: MouseCursorIcon::Crosshair });
+	}
+}
+
+#[cfg(test)]
+mod test_slice {
+	pub use crate::test_utils::test_prelude::*;
+
+	#[tokio::test]
+	async fn slice_draw_resize_and_undo() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		editor.drag_tool(ToolType::Slice, 10., 10., 50., 30., ModifierKeys::empty()).await;
+		let slices = &editor.active_document().export_slices;
+		assert_eq!(slices.len(), 1);
+		assert_eq!(slices[0].bounds, [DVec2::new(10., 10.), DVec2::new(50., 30.)]);
+
+		// Dragging the bottom right corner of the selected slice resizes it
+		editor.drag_tool(ToolType::Slice, 50., 30., 70., 60., ModifierKeys::empty()).await;
+		let slices = &editor.active_document().export_slices;
+		assert_eq!(slices.len(), 1);
+		assert_eq!(slices[0].bounds, [DVec2::new(10., 10.), DVec2::new(70., 60.)]);
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().export_slices[0].bounds, [DVec2::new(10., 10.), DVec2::new(50., 30.)]);
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert!(editor.active_document().export_slices.is_empty());
+	}
+}
@@ -335,7 +335,7 @@ impl Fsm for SplineToolFsmState {
 				SplineToolFsmState::Ready
 			}
 			(SplineToolFsmState::Ready, SplineToolMessage::DragStart { append_to_selected }) => {
-				responses.add(DocumentMessage::StartTransaction);
+				responses.add(DocumentMessage::StartTransaction { label: "Spline Tool".into() });
 
 				tool_data.snap_manager.cleanup(responses);
 				tool_data.cleanup();
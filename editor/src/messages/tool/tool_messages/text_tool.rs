@@ -456,7 +456,7 @@ impl TextToolData {
 
 		self.layer = layer;
 		if self.load_layer_text_node(document).is_some() {
-			responses.add(DocumentMessage::AddTransaction);
+			responses.add(DocumentMessage::AddTransaction { label: "Text Tool".into() });
 
 			self.set_editing(true, font_cache, responses);
 
@@ -473,7 +473,7 @@ impl TextToolData {
 	fn new_text(&mut self, document: &DocumentMessageHandler, editing_text: EditingText, font_cache: &FontCache, responses: &mut VecDeque<Message>) {
 		// Create new text
 		self.new_text = String::new();
-		responses.add(DocumentMessage::AddTransaction);
+		responses.add(DocumentMessage::AddTransaction { label: "Text Tool".into() });
 
 		self.layer = LayerNodeIdentifier::new_unchecked(NodeId::new());
 
@@ -675,7 +675,7 @@ impl Fsm for TextToolFsmState {
 				let selected = all_selected.find(|layer| is_layer_fed_by_node_of_name(*layer, &document.network_interface, &DefinitionIdentifier::ProtoNode(graphene_std::text::text::IDENTIFIER)));
 
 				if dragging_bounds.is_some() {
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Text Tool".into() });
 
 					// Set the original transform
 					if let Some(id) = selected {
@@ -691,7 +691,7 @@ impl Fsm for TextToolFsmState {
 
 					return TextToolFsmState::ResizingBounds;
 				} else if let Some(clicked_layer) = TextToolData::check_click(document, input, font_cache) {
-					responses.add(DocumentMessage::StartTransaction);
+					responses.add(DocumentMessage::StartTransaction { label: "Text Tool".into() });
 
 					if selected != Some(clicked_layer) {
 						responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![clicked_layer.to_node()] });
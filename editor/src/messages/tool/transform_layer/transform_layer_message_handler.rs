@@ -196,7 +196,7 @@ impl MessageHandler<TransformLayerMessage, TransformLayerMessageContext<'_>> for
 
 			selected.original_transforms.clear();
 
-			selected.responses.add(DocumentMessage::StartTransaction);
+			selected.responses.add(DocumentMessage::StartTransaction { label: "Transform".into() });
 		};
 
 		match message {
@@ -258,7 +258,7 @@ impl ToolData {
 				tool_group
 					.iter()
 					.filter_map(|tool_availability| {
-						if !brush_tool && let ToolRole::Normal(tool) = tool_availability && tool.tool_type() == ToolType::Brush {
+						if !brush_tool && let ToolRole::Normal(tool) = tool_availability && matches!(tool.tool_type(), ToolType::Brush | ToolType::Heal | ToolType::Clone | ToolType::Patch | ToolType::PixelSelect) {
 							return None;
 						}
 
@@ -366,6 +366,7 @@ pub enum ToolType {
 	#[default]
 	Select,
 	Artboard,
+	Slice,
 	Navigate,
 	Eyedropper,
 	Fill,
@@ -387,6 +388,7 @@ pub enum ToolType {
 	Heal,
 	Clone,
 	Patch,
+	PixelSelect,
 	Detail,
 	Relight,
 }
@@ -416,6 +418,7 @@ fn list_tools_in_groups() -> Vec<Vec<ToolRole>> {
 			// General tool group
 			ToolRole::Normal(Box::<select_tool::SelectTool>::default()),
 			ToolRole::Normal(Box::<artboard_tool::ArtboardTool>::default()),
+			ToolRole::Normal(Box::<slice_tool::SliceTool>::default()),
 			ToolRole::Normal(Box::<navigate_tool::NavigateTool>::default()),
 			ToolRole::Normal(Box::<eyedropper_tool::EyedropperTool>::default()),
 			ToolRole::Normal(Box::<fill_tool::FillTool>::default()),
@@ -436,19 +439,10 @@ fn list_tools_in_groups() -> Vec<Vec<ToolRole>> {
 		vec![
 			// Raster tool group
 			ToolRole::Normal(Box::<brush_tool::BrushTool>::default()),
-			// ToolRole::Normal(
-			// 	ToolEntry::new(ToolType::Heal, "RasterHealTool")
-			// 		.tooltip_label("Heal Tool")
-			// 		.tooltip_shortcut(action_shortcut_manual!(Key::KeyJ)),
-			// ),
-			// ToolRole::Normal(
-			// 	ToolEntry::new(ToolType::Clone, "RasterCloneTool")
-			// 		.tooltip_label("Clone Tool")
-			// 		.tooltip_shortcut(action_shortcut_manual!(Key::KeyC)),
-			// ),
-			// ToolRole::Normal(ToolEntry::new(ToolType::Patch, "RasterPatchTool")
-			// 		.tooltip_label("Patch Tool"),
-			// ),
+			ToolRole::Normal(Box::<heal_tool::HealTool>::default()),
+			ToolRole::Normal(Box::<clone_tool::CloneTool>::default()),
+			ToolRole::Normal(Box::<patch_tool::PatchTool>::default()),
+			ToolRole::Normal(Box::<pixel_select_tool::PixelSelectTool>::default()),
 			// ToolRole::Normal(
 			// 	ToolEntry::new(ToolType::Detail, "RasterDetailTool")
 			// 		.tooltip_label("Detail Tool")
@@ -468,6 +462,7 @@ pub fn tool_message_to_tool_type(tool_message: &ToolMessage) -> ToolType {
 		// General tool group
 		ToolMessage::Select(_) => ToolType::Select,
 		ToolMessage::Artboard(_) => ToolType::Artboard,
+		ToolMessage::Slice(_) => ToolType::Slice,
 		ToolMessage::Navigate(_) => ToolType::Navigate,
 		ToolMessage::Eyedropper(_) => ToolType::Eyedropper,
 		ToolMessage::Fill(_) => ToolType::Fill,
@@ -483,9 +478,10 @@ pub fn tool_message_to_tool_type(tool_message: &ToolMessage) -> ToolType {
 
 		// Raster tool group
 		ToolMessage::Brush(_) => ToolType::Brush,
-		// ToolMessage::Heal(_) => ToolType::Heal,
-		// ToolMessage::Clone(_) => ToolType::Clone,
-		// ToolMessage::Patch(_) => ToolType::Patch,
+		ToolMessage::Heal(_) => ToolType::Heal,
+		ToolMessage::Clone(_) => ToolType::Clone,
+		ToolMessage::Patch(_) => ToolType::Patch,
+		ToolMessage::PixelSelect(_) => ToolType::PixelSelect,
 		// ToolMessage::Detail(_) => ToolType::Detail,
 		// ToolMessage::Relight(_) => ToolType::Relight,
 		_ => panic!("Conversion from ToolMessage to ToolType impossible because the given ToolMessage does not have a matching ToolType. Got: {tool_message:?}"),
@@ -497,6 +493,7 @@ pub fn tool_type_to_activate_tool_message(tool_type: ToolType) -> ToolMessageDis
 		// General tool group
 		ToolType::Select => ToolMessageDiscriminant::ActivateToolSelect,
 		ToolType::Artboard => ToolMessageDiscriminant::ActivateToolArtboard,
+		ToolType::Slice => ToolMessageDiscriminant::ActivateToolSlice,
 		ToolType::Navigate => ToolMessageDiscriminant::ActivateToolNavigate,
 		ToolType::Eyedropper => ToolMessageDiscriminant::ActivateToolEyedropper,
 		ToolType::Fill => ToolMessageDiscriminant::ActivateToolFill,
@@ -515,9 +512,10 @@ pub fn tool_type_to_activate_tool_message(tool_type: ToolType) -> ToolMessageDis
 
 		// Raster tool group
 		ToolType::Brush => ToolMessageDiscriminant::ActivateToolBrush,
-		// ToolType::Heal => ToolMessageDiscriminant::ActivateToolHeal,
-		// ToolType::Clone => ToolMessageDiscriminant::ActivateToolClone,
-		// ToolType::Patch => ToolMessageDiscriminant::ActivateToolPatch,
+		ToolType::Heal => ToolMessageDiscriminant::ActivateToolHeal,
+		ToolType::Clone => ToolMessageDiscriminant::ActivateToolClone,
+		ToolType::Patch => ToolMessageDiscriminant::ActivateToolPatch,
+		ToolType::PixelSelect => ToolMessageDiscriminant::ActivateToolPixelSelect,
 		// ToolType::Detail => ToolMessageDiscriminant::ActivateToolDetail,
 		// ToolType::Relight => ToolMessageDiscriminant::ActivateToolRelight,
 		_ => panic!("Conversion from ToolType to ToolMessage impossible because the given ToolType does not have a matching ToolMessage. Got: {tool_type:?}"),
@@ -1,18 +1,25 @@
 use crate::messages::frontend::utility_types::{ExportBounds, FileType};
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::network_interface::NodeNetworkInterface;
 use crate::messages::prelude::*;
 use glam::{DAffine2, DVec2, UVec2};
 use graph_craft::document::value::{RenderOutput, TaggedValue};
-use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeId, NodeInput};
+use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeId, NodeInput, NodeNetwork};
 use graph_craft::proto::GraphErrors;
 use graph_craft::wasm_application_io::EditorPreferences;
 use graphene_std::application_io::{NodeGraphUpdateMessage, RenderConfig};
 use graphene_std::application_io::{SurfaceFrame, TimingInformation};
+use graphene_std::math::quad::Quad;
+use graphene_std::memo::MemoCacheStats;
+use graphene_std::raster::soft_proof::soft_proof;
+use graphene_std::raster_types::color_management::ColorSpace;
 use graphene_std::renderer::{RenderMetadata, format_transform_matrix};
 use graphene_std::text::FontCache;
 use graphene_std::transform::Footprint;
 use graphene_std::vector::Vector;
 use graphene_std::wasm_application_io::RenderOutputType;
 use interpreted_executor::dynamic_executor::ResolvedDocumentNodeTypesDelta;
+use interpreted_executor::profiling::NodeProfileReport;
 
 mod runtime_io;
 pub use runtime_io::NodeRuntimeIO;
@@ -33,6 +40,10 @@ pub struct ExecutionResponse {
 	vector_modify: HashMap<NodeId, Vector>,
 	/// The resulting value from the temporary inspected during execution
 	inspect_result: Option<InspectResult>,
+	/// How the memory budget of the graph's caches was being used once the execution finished
+	memo_cache_stats: MemoCacheStats,
+	/// The execution statistics of the graph's nodes, if node profiling is enabled
+	node_profile: Option<NodeProfileReport>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -55,6 +66,7 @@ pub struct NodeGraphExecutor {
 	node_graph_hash: u64,
 	previous_node_to_inspect: Option<NodeId>,
 	last_svg_canvas: Option<SurfaceFrame>,
+	memo_cache_stats: MemoCacheStats,
 }
 
 #[derive(Debug, Clone)]
@@ -78,6 +90,7 @@ impl NodeGraphExecutor {
 			current_execution_id: 0,
 			previous_node_to_inspect: None,
 			last_svg_canvas: None,
+			memo_cache_stats: MemoCacheStats::default(),
 		};
 		(node_runtime, node_executor)
 	}
@@ -102,12 +115,26 @@ impl NodeGraphExecutor {
 			.expect("Failed to send editor preferences");
 	}
 
+	pub fn update_memo_cache_budget(&self, bytes: usize) {
+		self.runtime_io.send(GraphRuntimeRequest::MemoCacheBudgetUpdate(bytes)).expect("Failed to send memo cache budget");
+	}
+
+	/// Enables or disables recording how long each node takes to run and how often its cache is hit, which is sent to the node graph after each execution.
+	pub fn set_node_profiling(&self, enabled: bool) {
+		self.runtime_io.send(GraphRuntimeRequest::NodeProfilingUpdate(enabled)).expect("Failed to send node profiling update");
+	}
+
+	/// The memory used by the caches of the graph's nodes, and how often they were hit or had to evict outputs, as of the latest execution.
+	pub fn memo_cache_stats(&self) -> MemoCacheStats {
+		self.memo_cache_stats
+	}
+
 	/// Updates the network to monitor all inputs. Useful for the testing.
 	#[cfg(test)]
 	pub(crate) fn update_node_graph_instrumented(&mut self, document: &mut DocumentMessageHandler) -> Result<Instrumented, String> {
 		// We should always invalidate the cache.
 		self.node_graph_hash = crate::application::generate_uuid();
-		let mut network = document.network_interface.document_network().clone();
+		let mut network = document.network_interface.executable_network();
 		let instrumented = Instrumented::new(&mut network);
 
 		self.runtime_io
@@ -121,7 +148,7 @@ impl NodeGraphExecutor {
 		let network_hash = document.network_interface.network_hash();
 		// Refresh the graph when it changes or the inspect node changes
 		if network_hash != self.node_graph_hash || self.previous_node_to_inspect != node_to_inspect || ignore_hash {
-			let network = document.network_interface.document_network().clone();
+			let network = executable_network(document);
 			self.previous_node_to_inspect = node_to_inspect;
 			self.node_graph_hash = network_hash;
 
@@ -185,9 +212,35 @@ impl NodeGraphExecutor {
 	}
 
 	/// Evaluates a node graph for export
-	pub fn submit_document_export(&mut self, document: &mut DocumentMessageHandler, document_id: DocumentId, mut export_config: ExportConfig) -> Result<(), String> {
-		let network = document.network_interface.document_network().clone();
+	pub fn submit_document_export(&mut self, document: &mut DocumentMessageHandler, document_id: DocumentId, export_config: ExportConfig) -> Result<(), String> {
+		let network = executable_network(document);
+		self.runtime_io
+			.send(GraphRuntimeRequest::GraphUpdate(GraphUpdate { network, node_to_inspect: None }))
+			.map_err(|e| e.to_string())?;
 
+		self.queue_export(document, document_id, export_config)
+	}
+
+	/// Evaluates a node graph once for each export, sending the graph to the runtime only a single time for all of them
+	pub fn submit_batch_export(&mut self, document: &mut DocumentMessageHandler, document_id: DocumentId, export_configs: Vec<ExportConfig>) -> Result<(), String> {
+		let network = executable_network(document);
+		self.runtime_io
+			.send(GraphRuntimeRequest::GraphUpdate(GraphUpdate { network, node_to_inspect: None }))
+			.map_err(|e| e.to_string())?;
+
+		// An export which can't be rendered doesn't prevent the others from being exported
+		let mut errors = Vec::new();
+		for export_config in export_configs {
+			let name = export_config.name.clone();
+			if let Err(error) = self.queue_export(document, document_id, export_config) {
+				errors.push(format!("{name}: {error}"));
+			}
+		}
+
+		if errors.is_empty() { Ok(()) } else { Err(errors.join("\n")) }
+	}
+
+	fn queue_export(&mut self, document: &DocumentMessageHandler, document_id: DocumentId, mut export_config: ExportConfig) -> Result<(), String> {
 		let export_format = if export_config.file_type == FileType::Svg {
 			graphene_std::application_io::ExportFormat::Svg
 		} else {
@@ -199,6 +252,16 @@ impl NodeGraphExecutor {
 			ExportBounds::AllArtwork => document.network_interface.document_bounds_document_space(!export_config.transparent_background),
 			ExportBounds::Selection => document.network_interface.selected_bounds_document_space(!export_config.transparent_background, &[]),
 			ExportBounds::Artboard(id) => document.metadata().bounding_box_document(id),
+			// Parts of the layer outside of any artboard that clips it aren't visible, so they're left out
+			ExportBounds::Layer(id) => id
+				.ancestors(document.metadata())
+				.skip(1)
+				.filter(|&ancestor| ancestor != LayerNodeIdentifier::ROOT_PARENT && document.metadata().is_clip(ancestor.to_node()))
+				.filter_map(|artboard| document.metadata().bounding_box_document(artboard))
+				.fold(document.metadata().bounding_box_document(id), |bounds, artboard_bounds| {
+					bounds.map(|bounds| Quad::clip(bounds, artboard_bounds))
+				}),
+			ExportBounds::Slice(id) => document.export_slices.iter().find(|slice| slice.id == id).map(|slice| slice.bounds),
 		}
 		.ok_or_else(|| "No bounding box".to_string())?;
 		let resolution = (bounds[1] - bounds[0]).round().as_uvec2();
@@ -219,11 +282,9 @@ impl NodeGraphExecutor {
 			for_export: true,
 		};
 		export_config.size = resolution.as_dvec2();
+		export_config.working_color_space = document.working_color_space;
 
 		// Execute the node graph
-		self.runtime_io
-			.send(GraphRuntimeRequest::GraphUpdate(GraphUpdate { network, node_to_inspect: None }))
-			.map_err(|e| e.to_string())?;
 		let execution_id = self.queue_execution(render_config);
 		let execution_context = ExecutionContext {
 			export_config: Some(export_config),
@@ -244,14 +305,16 @@ impl NodeGraphExecutor {
 			transparent_background,
 			artboard_name,
 			artboard_count,
+			#[cfg(feature = "gpu")]
+			working_color_space,
+			#[cfg(feature = "gpu")]
+			color_profile,
+			#[cfg(feature = "gpu")]
+			embed_color_profile,
 			..
 		} = export_config;
 
-		let file_extension = match file_type {
-			FileType::Svg => "svg",
-			FileType::Png => "png",
-			FileType::Jpg => "jpg",
-		};
+		let file_extension = file_type.extension();
 		let base_name = match (artboard_name, artboard_count) {
 			(Some(artboard_name), count) if count > 1 => format!("{name} - {artboard_name}"),
 			_ => name,
@@ -273,26 +336,42 @@ impl NodeGraphExecutor {
 			}
 			#[cfg(feature = "gpu")]
 			TaggedValue::RenderOutput(RenderOutput {
-				data: RenderOutputType::Buffer { data, width, height },
+				data: RenderOutputType::Buffer { mut data, width, height },
 				..
 			}) if file_type != FileType::Svg => {
+				use graphene_std::raster_types::color_management::{self, RenderingIntent};
 				use image::buffer::ConvertBuffer;
-				use image::{ImageFormat, RgbImage, RgbaImage};
+				use image::codecs::jpeg::JpegEncoder;
+				use image::codecs::png::PngEncoder;
+				use image::{ExtendedColorType, ImageEncoder, RgbImage, RgbaImage};
+
+				color_management::convert_rendered_rgba8(&mut data, working_color_space, color_profile, RenderingIntent::Perceptual)?;
 
 				let Some(image) = RgbaImage::from_raw(width, height, data) else {
 					return Err("Failed to create image buffer for export".to_string());
 				};
+				let icc_profile = if embed_color_profile { Some(color_profile.icc_data()?) } else { None };
 
 				let mut encoded = Vec::new();
 				let mut cursor = std::io::Cursor::new(&mut encoded);
 
+				fn write_image(mut encoder: impl ImageEncoder, icc_profile: Option<Vec<u8>>, data: &[u8], width: u32, height: u32, color_type: ExtendedColorType) -> image::ImageResult<()> {
+					if let Some(icc_profile) = icc_profile
+						&& let Err(err) = encoder.set_icc_profile(icc_profile)
+					{
+						warn!("The color profile could not be embedded: {err}");
+					}
+					encoder.write_image(data, width, height, color_type)
+				}
+
 				match file_type {
 					FileType::Png => {
+						let encoder = PngEncoder::new(&mut cursor);
 						let result = if transparent_background {
-							image.write_to(&mut cursor, ImageFormat::Png)
+							write_image(encoder, icc_profile, &image, width, height, ExtendedColorType::Rgba8)
 						} else {
 							let image: RgbImage = image.convert();
-							image.write_to(&mut cursor, ImageFormat::Png)
+							write_image(encoder, icc_profile, &image, width, height, ExtendedColorType::Rgb8)
 						};
 						if let Err(err) = result {
 							return Err(format!("Failed to encode PNG: {err}"));
@@ -300,7 +379,7 @@ impl NodeGraphExecutor {
 					}
 					FileType::Jpg => {
 						let image: RgbImage = image.convert();
-						let result = image.write_to(&mut cursor, ImageFormat::Jpeg);
+						let result = write_image(JpegEncoder::new(&mut cursor), icc_profile, &image, width, height, ExtendedColorType::Rgb8);
 						if let Err(err) = result {
 							return Err(format!("Failed to encode JPG: {err}"));
 						}
@@ -331,8 +410,15 @@ impl NodeGraphExecutor {
 						responses: existing_responses,
 						vector_modify,
 						inspect_result,
+						memo_cache_stats,
+						node_profile,
 					} = execution_response;
 
+					self.memo_cache_stats = memo_cache_stats;
+					if let Some(report) = node_profile {
+						responses.add(NodeGraphMessage::UpdateNodeProfile { report: Some(report) });
+					}
+
 					responses.add(OverlaysMessage::Draw);
 
 					let node_graph_output = match result {
@@ -466,6 +552,44 @@ impl NodeGraphExecutor {
 	}
 }
 
+/// Input data key which marks the working space input of a node that decodes or soft proofs images as explicitly chosen, so it no longer follows the document's working color space.
+pub const EXPLICIT_WORKING_SPACE: &str = "explicit_working_space";
+
+/// Whether the input is the working space of a node that decodes or soft proofs images, which follows the document's working color space unless it's explicitly chosen.
+pub fn is_working_space_input(implementation: &DocumentNodeImplementation, input_index: usize) -> bool {
+	let identifiers = [graphene_std::wasm_application_io::decode_image::IDENTIFIER, soft_proof::IDENTIFIER];
+	input_index == 1 && matches!(implementation, DocumentNodeImplementation::ProtoNode(identifier) if identifiers.contains(identifier))
+}
+
+/// The document network ready to be compiled, with symbol instances resolved and the document's working color space applied.
+fn executable_network(document: &DocumentMessageHandler) -> NodeNetwork {
+	let mut network = document.network_interface.executable_network();
+	apply_working_color_space(&mut network, &mut Vec::new(), &document.network_interface, document.working_color_space);
+	network
+}
+
+/// Sets the working space input of the nodes that decode and soft proof images to the document's working color space, which is a setting of the document rather than of each node.
+/// Inputs explicitly chosen in the node's properties are left as they are.
+fn apply_working_color_space(network: &mut NodeNetwork, network_path: &mut Vec<NodeId>, network_interface: &NodeNetworkInterface, working_color_space: ColorSpace) {
+	for (node_id, node) in network.nodes.iter_mut() {
+		if let DocumentNodeImplementation::Network(nested_network) = &mut node.implementation {
+			network_path.push(*node_id);
+			apply_working_color_space(nested_network, network_path, network_interface, working_color_space);
+			network_path.pop();
+			continue;
+		}
+		if !is_working_space_input(&node.implementation, 1) || network_interface.input_data(node_id, 1, EXPLICIT_WORKING_SPACE, network_path) == Some(&serde_json::Value::Bool(true)) {
+			continue;
+		}
+
+		if let Some(NodeInput::Value { tagged_value, .. }) = node.inputs.get_mut(1)
+			&& matches!(**tagged_value, TaggedValue::ColorSpace(_))
+		{
+			*tagged_value = TaggedValue::ColorSpace(working_color_space).into();
+		}
+	}
+}
+
 // Re-export for usage by tests in other modules
 #[cfg(test)]
 pub use test::Instrumented;
@@ -475,7 +599,6 @@ mod test {
 	use std::sync::Arc;
 
 	use super::*;
-	use crate::messages::portfolio::document::utility_types::network_interface::NodeNetworkInterface;
 	use crate::test_utils::test_prelude::{self, NodeGraphLayer};
 	use graph_craft::ProtoNodeIdentifier;
 	use graph_craft::document::NodeNetwork;
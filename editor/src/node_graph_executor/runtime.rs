@@ -12,6 +12,7 @@ use graphene_std::bounds::RenderBoundingBox;
 use graphene_std::memo::IORecord;
 use graphene_std::ops::Convert;
 use graphene_std::raster_types::Raster;
+use graphene_std::raster_types::color_management::ColorSpace;
 use graphene_std::renderer::{Render, RenderParams, SvgRender};
 use graphene_std::renderer::{RenderSvgSegmentList, SvgSegment};
 use graphene_std::table::{Table, TableRow};
@@ -20,6 +21,8 @@ use graphene_std::transform::RenderQuality;
 use graphene_std::vector::Vector;
 use graphene_std::wasm_application_io::{RenderOutputType, WasmApplicationIo, WasmEditorApi};
 use graphene_std::{Artboard, Context, Graphic};
+#[cfg(not(target_family = "wasm"))]
+pub use interpreted_executor::disk_cache::{DISK_CACHE_LIMIT_DEFAULT, DiskCache};
 use interpreted_executor::dynamic_executor::{DynamicExecutor, IntrospectError, ResolvedDocumentNodeTypesDelta};
 use interpreted_executor::util::wrap_network_in_scope;
 use once_cell::sync::Lazy;
@@ -68,6 +71,10 @@ pub enum GraphRuntimeRequest {
 	ExecutionRequest(ExecutionRequest),
 	FontCacheUpdate(FontCache),
 	EditorPreferencesUpdate(EditorPreferences),
+	/// The approximate number of bytes that the caches of the graph's LRU memo nodes may use together.
+	MemoCacheBudgetUpdate(usize),
+	/// Whether to record the execution statistics of every node, which are then returned with each execution.
+	NodeProfilingUpdate(bool),
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -87,6 +94,12 @@ pub struct ExportConfig {
 	pub size: DVec2,
 	pub artboard_name: Option<String>,
 	pub artboard_count: usize,
+	/// The working color space of the document, which the rendered pixels are in.
+	pub working_color_space: ColorSpace,
+	/// The color space that the exported pixels are converted into.
+	pub color_profile: ColorSpace,
+	/// Whether to embed the ICC profile of the color space in the exported file.
+	pub embed_color_profile: bool,
 }
 
 #[derive(Clone)]
@@ -158,6 +171,8 @@ impl NodeRuntime {
 
 		let mut font = None;
 		let mut preferences = None;
+		let mut memo_cache_budget = None;
+		let mut profiling = None;
 		let mut graph = None;
 		let mut execution = None;
 		for request in self.receiver.try_iter() {
@@ -173,9 +188,11 @@ impl NodeRuntime {
 				}
 				GraphRuntimeRequest::FontCacheUpdate(_) => font = Some(request),
 				GraphRuntimeRequest::EditorPreferencesUpdate(_) => preferences = Some(request),
+				GraphRuntimeRequest::MemoCacheBudgetUpdate(_) => memo_cache_budget = Some(request),
+				GraphRuntimeRequest::NodeProfilingUpdate(_) => profiling = Some(request),
 			}
 		}
-		let requests = [font, preferences, graph, execution].into_iter().flatten();
+		let requests = [font, preferences, memo_cache_budget, profiling, graph, execution].into_iter().flatten();
 
 		for request in requests {
 			match request {
@@ -206,6 +223,15 @@ impl NodeRuntime {
 						let _ = self.update_network(graph).await;
 					}
 				}
+				GraphRuntimeRequest::MemoCacheBudgetUpdate(bytes) => graphene_std::memo::set_memo_cache_budget(bytes),
+				GraphRuntimeRequest::NodeProfilingUpdate(enabled) => {
+					// This clears the executor when profiling is toggled, so the nodes are rebuilt with or without being profiled
+					self.executor.set_profiling(enabled);
+					if let Some(graph) = self.old_graph.clone() {
+						// We ignore this result as compilation errors should have been reported in an earlier iteration
+						let _ = self.update_network(graph).await;
+					}
+				}
 				GraphRuntimeRequest::GraphUpdate(GraphUpdate { mut network, node_to_inspect }) => {
 					// Insert the monitor node to manage the inspection
 					self.inspect_state = node_to_inspect.map(|inspect| InspectState::monitor_inspect_node(&mut network, inspect));
@@ -233,6 +259,7 @@ impl NodeRuntime {
 						}
 					}
 
+					self.executor.reset_profile();
 					let result = self.execute_network(render_config).await;
 					let mut responses = VecDeque::new();
 					// TODO: Only process monitor nodes if the graph has changed, not when only the Footprint changes
@@ -362,6 +389,8 @@ impl NodeRuntime {
 						responses,
 						vector_modify: self.vector_modify.clone(),
 						inspect_result,
+						memo_cache_stats: graphene_std::memo::memo_cache_stats(),
+						node_profile: self.executor.profile_report(),
 					});
 					return texture;
 				}
@@ -545,6 +574,20 @@ pub async fn replace_application_io(application_io: WasmApplicationIo) {
 	}
 }
 
+/// Sets the on-disk cache which keeps the outputs of expensive nodes between sessions, which is only available to native builds.
+#[cfg(not(target_family = "wasm"))]
+pub async fn replace_disk_cache(disk_cache: Option<Arc<DiskCache>>) {
+	let mut node_runtime = NODE_RUNTIME.lock();
+	if let Some(node_runtime) = &mut *node_runtime {
+		// This clears the executor, so the nodes are rebuilt with or without the disk cache
+		node_runtime.executor.set_disk_cache(disk_cache);
+		if let Some(graph) = node_runtime.old_graph.clone() {
+			// We ignore this result as compilation errors should have been reported in an earlier iteration
+			let _ = node_runtime.update_network(graph).await;
+		}
+	}
+}
+
 /// Which node is inspected and which monitor node is used (if any) for the current execution
 #[derive(Debug, Clone, Copy)]
 struct InspectState {
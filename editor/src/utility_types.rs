@@ -93,3 +93,17 @@ impl DebugMessageTree {
 		self.message_handler.as_ref()
 	}
 }
+
+/// Inserts a space before each uppercase letter that follows a lowercase letter, turning `DeleteSelectedLayers` into `Delete Selected Layers`.
+pub fn split_camel_case(name: &str) -> String {
+	let mut words = String::with_capacity(name.len() + 4);
+	let mut previous_lowercase = false;
+	for character in name.chars() {
+		if character.is_uppercase() && previous_lowercase {
+			words.push(' ');
+		}
+		previous_lowercase = character.is_lowercase() || character.is_ascii_digit();
+		words.push(character);
+	}
+	words
+}
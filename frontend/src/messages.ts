@@ -128,6 +128,11 @@ export class UpdateNodeThumbnail extends JsMessage {
 	readonly value!: string;
 }
 
+export class UpdateNodeProfiles extends JsMessage {
+	@Type(() => FrontendNodeProfile)
+	readonly profiles!: FrontendNodeProfile[];
+}
+
 export class UpdateNodeGraphSelection extends JsMessage {
 	@Type(() => BigInt)
 	readonly selected!: bigint[];
@@ -260,6 +265,20 @@ export class FrontendNodeType {
 	readonly inputTypes!: string[];
 }
 
+export class FrontendNodeProfile {
+	readonly id!: bigint;
+
+	readonly calls!: number;
+
+	readonly cumulativeMilliseconds!: number;
+
+	readonly selfMilliseconds!: number;
+
+	readonly cacheHitRate!: number | undefined;
+
+	readonly heat!: number;
+}
+
 export type DefinitionIdentifier = { type: "Network" | "ProtoNode"; data: string };
 
 export class NodeGraphTransform {
@@ -715,6 +734,10 @@ export class UpdateDataPanelState extends JsMessage {
 	readonly open!: boolean;
 }
 
+export class UpdateHistoryPanelState extends JsMessage {
+	readonly open!: boolean;
+}
+
 export class UpdatePropertiesPanelState extends JsMessage {
 	readonly open!: boolean;
 }
@@ -745,6 +768,10 @@ export class TriggerOpenDocument extends JsMessage {}
 
 export class TriggerImport extends JsMessage {}
 
+export class TriggerImportKeymap extends JsMessage {}
+
+export class TriggerImportNodeLibrary extends JsMessage {}
+
 export class TriggerClipboardRead extends JsMessage {}
 
 export class TriggerSaveDocument extends JsMessage {
@@ -870,6 +897,8 @@ export class LayerPanelEntry {
 	clipped!: boolean;
 
 	clippable!: boolean;
+
+	symbolInstance!: boolean;
 }
 
 export class DisplayDialogDismiss extends JsMessage {}
@@ -1476,6 +1505,7 @@ export type LayoutTarget =
 	| "DialogColumn1"
 	| "DialogColumn2"
 	| "DocumentBar"
+	| "HistoryPanel"
 	| "LayersPanelBottomBar"
 	| "LayersPanelControlLeftBar"
 	| "LayersPanelControlRightBar"
@@ -1642,6 +1672,8 @@ export class UpdatePropertiesPanelLayout extends WidgetDiffUpdate {}
 
 export class UpdateDataPanelLayout extends WidgetDiffUpdate {}
 
+export class UpdateHistoryPanelLayout extends WidgetDiffUpdate {}
+
 export class UpdateStatusBarHintsLayout extends WidgetDiffUpdate {}
 
 export class UpdateStatusBarInfoLayout extends WidgetDiffUpdate {}
@@ -1676,6 +1708,8 @@ export const messageMakers: Record<string, MessageMaker> = {
 	TriggerFontCatalogLoad,
 	TriggerFontDataLoad,
 	TriggerImport,
+	TriggerImportKeymap,
+	TriggerImportNodeLibrary,
 	TriggerLoadFirstAutoSaveDocument,
 	TriggerLoadPreferences,
 	TriggerLoadRestAutoSaveDocuments,
@@ -1712,6 +1746,8 @@ export const messageMakers: Record<string, MessageMaker> = {
 	UpdateEyedropperSamplingState,
 	UpdateGraphFadeArtwork,
 	UpdateGraphViewOverlay,
+	UpdateHistoryPanelLayout,
+	UpdateHistoryPanelState,
 	UpdateImportReorderIndex,
 	UpdateImportsExports,
 	UpdateInSelectedNetwork,
@@ -1728,6 +1764,7 @@ export const messageMakers: Record<string, MessageMaker> = {
 	UpdateNodeGraphSelection,
 	UpdateNodeGraphTransform,
 	UpdateNodeGraphWires,
+	UpdateNodeProfiles,
 	UpdateNodeThumbnail,
 	UpdateOpenDocumentsList,
 	UpdatePlatform,
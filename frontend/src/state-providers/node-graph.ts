@@ -7,6 +7,7 @@ import {
 	type FrontendClickTargets,
 	type ContextMenuInformation,
 	type FrontendNode,
+	type FrontendNodeProfile,
 	type FrontendNodeType,
 	type WirePath,
 	ClearAllNodeGraphWires,
@@ -24,6 +25,7 @@ import {
 	UpdateNodeGraphWires,
 	UpdateNodeGraphSelection,
 	UpdateNodeGraphTransform,
+	UpdateNodeProfiles,
 	UpdateNodeThumbnail,
 	UpdateWirePathInProgress,
 	UpdateNodeGraphErrorDiagnostic,
@@ -47,6 +49,7 @@ export function createNodeGraphState(editor: Editor) {
 		nodeDescriptions: new Map<string, string>(),
 		nodeTypes: [] as FrontendNodeType[],
 		thumbnails: new Map<bigint, string>(),
+		profiles: new Map<bigint, FrontendNodeProfile>(),
 		selected: [] as bigint[],
 		transform: { scale: 1, x: 0, y: 0 },
 		inSelectedNetwork: true,
@@ -176,6 +179,12 @@ export function createNodeGraphState(editor: Editor) {
 			return state;
 		});
 	});
+	editor.subscriptions.subscribeJsMessage(UpdateNodeProfiles, (data) => {
+		update((state) => {
+			state.profiles = new Map(data.profiles.map((profile) => [profile.id, profile]));
+			return state;
+		});
+	});
 	editor.subscriptions.subscribeJsMessage(UpdateNodeThumbnail, (data) => {
 		update((state) => {
 			state.thumbnails.set(data.id, data.value);
@@ -8,10 +8,13 @@ import {
 	TriggerExportImage,
 	TriggerSaveFile,
 	TriggerImport,
+	TriggerImportKeymap,
+	TriggerImportNodeLibrary,
 	TriggerOpenDocument,
 	UpdateActiveDocument,
 	UpdateOpenDocumentsList,
 	UpdateDataPanelState,
+	UpdateHistoryPanelState,
 	UpdatePropertiesPanelState,
 	UpdateLayersPanelState,
 } from "@graphite/messages";
@@ -24,6 +27,7 @@ export function createPortfolioState(editor: Editor) {
 		documents: [] as OpenDocument[],
 		activeDocumentIndex: 0,
 		dataPanelOpen: false,
+		historyPanelOpen: false,
 		propertiesPanelOpen: true,
 		layersPanelOpen: true,
 	});
@@ -90,6 +94,14 @@ export function createPortfolioState(editor: Editor) {
 		const imageData = await extractPixelData(new Blob([new Uint8Array(data.content.data)], { type: data.type }));
 		editor.handle.pasteImage(data.filename, new Uint8Array(imageData.data), imageData.width, imageData.height);
 	});
+	editor.subscriptions.subscribeJsMessage(TriggerImportKeymap, async () => {
+		const data = await upload(".json", "text");
+		editor.handle.loadKeymapFile(data.content);
+	});
+	editor.subscriptions.subscribeJsMessage(TriggerImportNodeLibrary, async () => {
+		const data = await upload(".graphite-nodes", "text");
+		editor.handle.loadNodeLibraryFile(data.content);
+	});
 	editor.subscriptions.subscribeJsMessage(TriggerSaveDocument, (data) => {
 		downloadFile(data.name, data.content);
 	});
@@ -118,6 +130,12 @@ export function createPortfolioState(editor: Editor) {
 			return state;
 		});
 	});
+	editor.subscriptions.subscribeJsMessage(UpdateHistoryPanelState, async (data) => {
+		update((state) => {
+			state.historyPanelOpen = data.open;
+			return state;
+		});
+	});
 	editor.subscriptions.subscribeJsMessage(UpdatePropertiesPanelState, async (data) => {
 		update((state) => {
 			state.propertiesPanelOpen = data.open;
@@ -252,25 +252,25 @@ impl EditorHandle {
 
 	#[wasm_bindgen(js_name = addPrimaryImport)]
 	pub fn add_primary_import(&self) {
-		self.dispatch(DocumentMessage::AddTransaction);
+		self.dispatch(DocumentMessage::AddTransaction { label: "Add Import".into() });
 		self.dispatch(NodeGraphMessage::AddPrimaryImport);
 	}
 
 	#[wasm_bindgen(js_name = addSecondaryImport)]
 	pub fn add_secondary_import(&self) {
-		self.dispatch(DocumentMessage::AddTransaction);
+		self.dispatch(DocumentMessage::AddTransaction { label: "Add Import".into() });
 		self.dispatch(NodeGraphMessage::AddSecondaryImport);
 	}
 
 	#[wasm_bindgen(js_name = addPrimaryExport)]
 	pub fn add_primary_export(&self) {
-		self.dispatch(DocumentMessage::AddTransaction);
+		self.dispatch(DocumentMessage::AddTransaction { label: "Add Export".into() });
 		self.dispatch(NodeGraphMessage::AddPrimaryExport);
 	}
 
 	#[wasm_bindgen(js_name = addSecondaryExport)]
 	pub fn add_secondary_export(&self) {
-		self.dispatch(DocumentMessage::AddTransaction);
+		self.dispatch(DocumentMessage::AddTransaction { label: "Add Export".into() });
 		self.dispatch(NodeGraphMessage::AddSecondaryExport);
 	}
 
@@ -400,6 +400,18 @@ impl EditorHandle {
 		self.dispatch(message);
 	}
 
+	#[wasm_bindgen(js_name = loadKeymapFile)]
+	pub fn load_keymap_file(&self, contents: String) {
+		let message = PreferencesMessage::LoadKeymapFile { contents };
+		self.dispatch(message);
+	}
+
+	#[wasm_bindgen(js_name = loadNodeLibraryFile)]
+	pub fn load_node_library_file(&self, contents: String) {
+		let message = PreferencesMessage::LoadNodeLibraryFile { contents };
+		self.dispatch(message);
+	}
+
 	#[wasm_bindgen(js_name = selectDocument)]
 	pub fn select_document(&self, document_id: u64) {
 		let document_id = DocumentId(document_id);
@@ -838,6 +850,18 @@ impl EditorHandle {
 		self.dispatch(message);
 	}
 
+	/// Begin dragging out a new guide from one of the rulers, which follows the pointer until `stopGuideDrag` is called
+	#[wasm_bindgen(js_name = dragGuideFromRuler)]
+	pub fn drag_guide_from_ruler(&self, vertical: bool) {
+		self.dispatch(DocumentMessage::DragGuideFromRuler { vertical });
+	}
+
+	/// Place the guide being dragged, or delete it if it was dropped outside the viewport
+	#[wasm_bindgen(js_name = stopGuideDrag)]
+	pub fn stop_guide_drag(&self) {
+		self.dispatch(DocumentMessage::GuideDragStop);
+	}
+
 	/// Toggle expansions state of a layer from the layer list
 	#[wasm_bindgen(js_name = toggleLayerExpansion)]
 	pub fn toggle_layer_expansion(&self, id: u64, recursive: bool) {
@@ -73,3 +73,29 @@ pub enum Node {
 	BinOp { lhs: Box<Node>, op: BinaryOp, rhs: Box<Node> },
 	UnaryOp { expr: Box<Node>, op: UnaryOp },
 }
+
+impl Node {
+	/// The names of the variables read by the expression, in the order they first appear.
+	pub fn variables(&self) -> Vec<&str> {
+		let mut variables = Vec::new();
+		self.collect_variables(&mut variables);
+		variables
+	}
+
+	fn collect_variables<'a>(&'a self, variables: &mut Vec<&'a str>) {
+		match self {
+			Node::Lit(_) => {}
+			Node::Var(name) => {
+				if !variables.contains(&name.as_str()) {
+					variables.push(name);
+				}
+			}
+			Node::FnCall { expr, .. } => expr.iter().for_each(|expr| expr.collect_variables(variables)),
+			Node::BinOp { lhs, rhs, .. } => {
+				lhs.collect_variables(variables);
+				rhs.collect_variables(variables);
+			}
+			Node::UnaryOp { expr, .. } => expr.collect_variables(variables),
+		}
+	}
+}
@@ -11,6 +11,7 @@ pub trait FunctionProvider {
 	fn run_function(&self, name: &str, args: &[Value]) -> Option<Value>;
 }
 
+#[derive(Default)]
 pub struct ValueMap(HashMap<String, Value>);
 
 pub struct NothingMap;
@@ -13,6 +13,8 @@ pub enum EvalError {
 	MissingFunction(String),
 	#[error("Wrong type for function call")]
 	TypeError,
+	#[error("Unsupported operand types for {0}")]
+	OperandTypes(String),
 }
 
 impl Node {
@@ -23,12 +25,8 @@ impl Node {
 				Literal::Complex(num) => Ok(Value::Number(Number::Complex(*num))),
 			},
 
-			Node::BinOp { lhs, op, rhs } => match (lhs.eval(context)?, rhs.eval(context)?) {
-				(Value::Number(lhs), Value::Number(rhs)) => Ok(Value::Number(lhs.binary_op(*op, rhs))),
-			},
-			Node::UnaryOp { expr, op } => match expr.eval(context)? {
-				Value::Number(num) => Ok(Value::Number(num.unary_op(*op))),
-			},
+			Node::BinOp { lhs, op, rhs } => lhs.eval(context)?.binary_op(*op, rhs.eval(context)?).ok_or_else(|| EvalError::OperandTypes(format!("{op:?}"))),
+			Node::UnaryOp { expr, op } => expr.eval(context)?.unary_op(*op).ok_or_else(|| EvalError::OperandTypes(format!("{op:?}"))),
 			Node::Var(name) => context.get_value(name).ok_or_else(|| EvalError::MissingValue(name.clone())),
 			Node::FnCall { name, expr } => {
 				let values = expr.iter().map(|expr| expr.eval(context)).collect::<Result<Vec<Value>, EvalError>>()?;
@@ -97,4 +95,19 @@ mod tests {
 			 rhs: Box::new(Node::Lit(Literal::Float(3.0))),
 		 },
 	}
+
+	#[test]
+	fn vector_operands() {
+		let mut values = ValueMap::default();
+		values.insert("v".to_string(), Value::Vec2(glam::DVec2::new(1., 2.)));
+		values.insert("w".to_string(), Value::Vec2(glam::DVec2::new(3., 4.)));
+		let context = EvalContext::new(values, crate::context::NothingMap);
+
+		let (node, _) = Node::try_parse_from_str("(v + w) * 2 - -v").unwrap();
+		assert_eq!(node.eval(&context).unwrap(), Value::Vec2(glam::DVec2::new(9., 14.)));
+		assert_eq!(node.variables(), ["v", "w"]);
+
+		let (node, _) = Node::try_parse_from_str("v + i").unwrap();
+		assert!(node.eval(&context).is_err());
+	}
 }
@@ -1,4 +1,5 @@
 use crate::ast::{BinaryOp, UnaryOp};
+use glam::{DVec2, DVec4};
 use num_complex::ComplexFloat;
 use std::f64::consts::PI;
 
@@ -7,6 +8,9 @@ pub type Complex = num_complex::Complex<f64>;
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Value {
 	Number(Number),
+	Vec2(DVec2),
+	/// A four component vector, such as the RGBA channels of a color.
+	Vec4(DVec4),
 }
 
 impl Value {
@@ -20,6 +24,51 @@ impl Value {
 			_ => None,
 		}
 	}
+
+	/// Applies the operation to the two values. Vectors are operated on component-wise, and a real number operand is applied to every component of a vector.
+	///
+	/// Returns `None` if the operation isn't defined for the types of the operands, such as a vector with a complex number.
+	pub fn binary_op(self, op: BinaryOp, other: Value) -> Option<Value> {
+		let value = match (self, other) {
+			(Value::Number(lhs), Value::Number(rhs)) => Value::Number(lhs.binary_op(op, rhs)),
+
+			(Value::Vec2(lhs), Value::Vec2(rhs)) => Value::Vec2(DVec2::from_array(component_wise(lhs.to_array(), op, rhs.to_array()))),
+			(Value::Vec2(lhs), Value::Number(Number::Real(rhs))) => Value::Vec2(DVec2::from_array(component_wise(lhs.to_array(), op, [rhs; 2]))),
+			(Value::Number(Number::Real(lhs)), Value::Vec2(rhs)) => Value::Vec2(DVec2::from_array(component_wise([lhs; 2], op, rhs.to_array()))),
+
+			(Value::Vec4(lhs), Value::Vec4(rhs)) => Value::Vec4(DVec4::from_array(component_wise(lhs.to_array(), op, rhs.to_array()))),
+			(Value::Vec4(lhs), Value::Number(Number::Real(rhs))) => Value::Vec4(DVec4::from_array(component_wise(lhs.to_array(), op, [rhs; 4]))),
+			(Value::Number(Number::Real(lhs)), Value::Vec4(rhs)) => Value::Vec4(DVec4::from_array(component_wise([lhs; 4], op, rhs.to_array()))),
+
+			_ => return None,
+		};
+		Some(value)
+	}
+
+	/// Applies the operation to the value, or to every component of a vector.
+	///
+	/// Returns `None` if the operation isn't defined for vectors.
+	pub fn unary_op(self, op: UnaryOp) -> Option<Value> {
+		let real_op = |real: f64| match op {
+			UnaryOp::Neg => Some(-real),
+			UnaryOp::Sqrt => Some(real.sqrt()),
+			UnaryOp::Fac => None,
+		};
+
+		let value = match self {
+			Value::Number(number) => Value::Number(number.unary_op(op)),
+			Value::Vec2(vector) => Value::Vec2(DVec2::new(real_op(vector.x)?, real_op(vector.y)?)),
+			Value::Vec4(vector) => Value::Vec4(DVec4::new(real_op(vector.x)?, real_op(vector.y)?, real_op(vector.z)?, real_op(vector.w)?)),
+		};
+		Some(value)
+	}
+}
+
+fn component_wise<const N: usize>(lhs: [f64; N], op: BinaryOp, rhs: [f64; N]) -> [f64; N] {
+	std::array::from_fn(|i| match Number::Real(lhs[i]).binary_op(op, Number::Real(rhs[i])) {
+		Number::Real(real) => real,
+		Number::Complex(complex) => complex.re,
+	})
 }
 
 impl From<f64> for Value {
@@ -32,6 +81,8 @@ impl core::fmt::Display for Value {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Value::Number(num) => num.fmt(f),
+			Value::Vec2(vector) => write!(f, "({}, {})", vector.x, vector.y),
+			Value::Vec4(vector) => write!(f, "({}, {}, {}, {})", vector.x, vector.y, vector.z, vector.w),
 		}
 	}
 }
@@ -1,3 +1,4 @@
+pub mod diff;
 pub mod value;
 
 use crate::document::value::TaggedValue;
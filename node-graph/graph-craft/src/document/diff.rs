@@ -0,0 +1,821 @@
+use super::value::TaggedValue;
+use super::{DocumentNode, DocumentNodeImplementation, NodeId, NodeInput, NodeNetwork};
+use rustc_hash::FxHashMap;
+use std::collections::{BTreeSet, HashMap};
+
+/// Values whose debug representation is longer than this are described by their type instead.
+const MAX_VALUE_DESCRIPTION_LENGTH: usize = 60;
+
+/// The names shown for nodes and their inputs when describing changes and conflicts.
+/// The editor stores these alongside a network rather than in it, so they're provided separately.
+#[derive(Clone, Debug, Default)]
+pub struct NodeNames {
+	nodes: HashMap<Vec<NodeId>, NodeName>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeName {
+	pub name: String,
+	pub input_names: Vec<String>,
+}
+
+impl NodeNames {
+	/// Names the node at the given path, which ends with the ID of the node itself.
+	pub fn insert(&mut self, node_path: Vec<NodeId>, name: NodeName) {
+		self.nodes.insert(node_path, name);
+	}
+
+	fn get(&self, network_path: &[NodeId], node_id: NodeId) -> Option<&NodeName> {
+		self.nodes.get(&node_path(network_path, node_id))
+	}
+}
+
+/// A difference between two versions of a network, or of a network nested in one of its nodes.
+/// Nodes are matched up between the versions by their [`NodeId`], and `network_path` leads to the network containing the node.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NetworkChange {
+	NodeAdded {
+		network_path: Vec<NodeId>,
+		node_id: NodeId,
+	},
+	NodeRemoved {
+		network_path: Vec<NodeId>,
+		node_id: NodeId,
+	},
+	/// The node now runs a different proto node, or switched between being a proto node and a subgraph.
+	ImplementationChanged {
+		network_path: Vec<NodeId>,
+		node_id: NodeId,
+		old: String,
+		new: String,
+	},
+	/// The input was connected to a different node output or import, or switched between being connected and holding a value.
+	InputRewired {
+		network_path: Vec<NodeId>,
+		node_id: NodeId,
+		input_index: usize,
+		old: Option<NodeInput>,
+		new: Option<NodeInput>,
+	},
+	/// The value held by an input which isn't connected to anything was changed.
+	ValueChanged {
+		network_path: Vec<NodeId>,
+		node_id: NodeId,
+		input_index: usize,
+		old: TaggedValue,
+		new: TaggedValue,
+	},
+	VisibilityChanged {
+		network_path: Vec<NodeId>,
+		node_id: NodeId,
+		visible: bool,
+	},
+	ExportRewired {
+		network_path: Vec<NodeId>,
+		export_index: usize,
+		old: Option<NodeInput>,
+		new: Option<NodeInput>,
+	},
+}
+
+/// The changes made between two versions of a network, found by [`diff_networks`].
+#[derive(Clone, Debug, Default)]
+pub struct NetworkDiff {
+	pub changes: Vec<NetworkChange>,
+	/// The implementation of each node in either version, used to name nodes which aren't given a name.
+	implementation_names: HashMap<Vec<NodeId>, String>,
+}
+
+impl NetworkDiff {
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+
+	/// Describes each change in a sentence, using the given names for nodes and inputs where they're available.
+	pub fn describe(&self, names: &NodeNames) -> Vec<String> {
+		let labels = Labels {
+			names,
+			implementation_names: &self.implementation_names,
+		};
+		self.changes.iter().map(|change| labels.change(change)).collect()
+	}
+
+	fn add_network_changes(&mut self, old: &NodeNetwork, new: &NodeNetwork, network_path: &mut Vec<NodeId>) {
+		for (&node_id, node) in old.nodes.iter().chain(new.nodes.iter()) {
+			self.implementation_names.insert(node_path(network_path, node_id), implementation_name(&node.implementation));
+		}
+
+		for node_id in sorted_node_ids(&[old, new]) {
+			match (old.nodes.get(&node_id), new.nodes.get(&node_id)) {
+				(Some(_), None) => self.changes.push(NetworkChange::NodeRemoved {
+					network_path: network_path.clone(),
+					node_id,
+				}),
+				(None, Some(_)) => self.changes.push(NetworkChange::NodeAdded {
+					network_path: network_path.clone(),
+					node_id,
+				}),
+				(Some(old_node), Some(new_node)) => self.add_node_changes(old_node, new_node, node_id, network_path),
+				(None, None) => {}
+			}
+		}
+
+		for export_index in 0..old.exports.len().max(new.exports.len()) {
+			let (old_export, new_export) = (old.exports.get(export_index), new.exports.get(export_index));
+			if old_export != new_export {
+				self.changes.push(NetworkChange::ExportRewired {
+					network_path: network_path.clone(),
+					export_index,
+					old: old_export.cloned(),
+					new: new_export.cloned(),
+				});
+			}
+		}
+	}
+
+	fn add_node_changes(&mut self, old_node: &DocumentNode, new_node: &DocumentNode, node_id: NodeId, network_path: &mut Vec<NodeId>) {
+		let nested_networks = match (&old_node.implementation, &new_node.implementation) {
+			(DocumentNodeImplementation::Network(old_network), DocumentNodeImplementation::Network(new_network)) => Some((old_network, new_network)),
+			(old_implementation, new_implementation) => {
+				if old_implementation != new_implementation {
+					self.changes.push(NetworkChange::ImplementationChanged {
+						network_path: network_path.clone(),
+						node_id,
+						old: implementation_name(old_implementation),
+						new: implementation_name(new_implementation),
+					});
+				}
+				None
+			}
+		};
+
+		for input_index in 0..old_node.inputs.len().max(new_node.inputs.len()) {
+			let (old_input, new_input) = (old_node.inputs.get(input_index), new_node.inputs.get(input_index));
+			let change = match (old_input, new_input) {
+				(
+					Some(NodeInput::Value {
+						tagged_value: old_value,
+						exposed: old_exposed,
+					}),
+					Some(NodeInput::Value {
+						tagged_value: new_value,
+						exposed: new_exposed,
+					}),
+				) if old_exposed == new_exposed => (old_value != new_value).then(|| NetworkChange::ValueChanged {
+					network_path: network_path.clone(),
+					node_id,
+					input_index,
+					old: TaggedValue::clone(old_value),
+					new: TaggedValue::clone(new_value),
+				}),
+				_ => (old_input != new_input).then(|| NetworkChange::InputRewired {
+					network_path: network_path.clone(),
+					node_id,
+					input_index,
+					old: old_input.cloned(),
+					new: new_input.cloned(),
+				}),
+			};
+			self.changes.extend(change);
+		}
+
+		if old_node.visible != new_node.visible {
+			self.changes.push(NetworkChange::VisibilityChanged {
+				network_path: network_path.clone(),
+				node_id,
+				visible: new_node.visible,
+			});
+		}
+
+		if let Some((old_network, new_network)) = nested_networks {
+			network_path.push(node_id);
+			self.add_network_changes(old_network, new_network, network_path);
+			network_path.pop();
+		}
+	}
+}
+
+/// Finds the nodes which were added, removed, or rewired, and the input values which were changed, between two versions of a network and the networks nested in it.
+pub fn diff_networks(old: &NodeNetwork, new: &NodeNetwork) -> NetworkDiff {
+	let mut diff = NetworkDiff::default();
+	diff.add_network_changes(old, new, &mut Vec::new());
+	diff
+}
+
+/// Part of a network which was changed in different ways by both sides of a [`merge_networks`].
+/// Each side is `None` where the node, input, or export doesn't exist in that version.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum MergeConflict {
+	/// The node was removed on one side but changed on the other, or its implementation was changed differently by both.
+	Node {
+		network_path: Vec<NodeId>,
+		node_id: NodeId,
+		base: Option<DocumentNode>,
+		ours: Option<DocumentNode>,
+		theirs: Option<DocumentNode>,
+	},
+	Input {
+		network_path: Vec<NodeId>,
+		node_id: NodeId,
+		input_index: usize,
+		base: Option<NodeInput>,
+		ours: Option<NodeInput>,
+		theirs: Option<NodeInput>,
+	},
+	Export {
+		network_path: Vec<NodeId>,
+		export_index: usize,
+		base: Option<NodeInput>,
+		ours: Option<NodeInput>,
+		theirs: Option<NodeInput>,
+	},
+}
+
+/// The result of a [`merge_networks`].
+#[derive(Clone, Debug)]
+pub struct MergedNetwork {
+	pub network: NodeNetwork,
+	/// The parts of the network that both sides changed differently, which use our side in the merged network until they're resolved.
+	pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergedNetwork {
+	/// Describes each conflict with conflict markers around each side, like those git writes around conflicting lines of text.
+	pub fn describe_conflicts(&self, names: &NodeNames) -> Vec<String> {
+		let mut implementation_names = HashMap::new();
+		add_implementation_names(&self.network, &mut Vec::new(), &mut implementation_names);
+		let labels = Labels {
+			names,
+			implementation_names: &implementation_names,
+		};
+		self.conflicts.iter().map(|conflict| labels.conflict(conflict)).collect()
+	}
+}
+
+/// Combines the changes made to a common base network by two sides, node by node and input by input.
+/// Where only one side changed something, its change is taken. Where both changed the same node input or export differently, a conflict is recorded and our side is kept.
+/// A node which one side removed but the other changed is kept, so the changes made to it aren't lost.
+pub fn merge_networks(base: &NodeNetwork, ours: &NodeNetwork, theirs: &NodeNetwork) -> MergedNetwork {
+	let mut conflicts = Vec::new();
+	let network = merge_network(base, ours, theirs, &mut Vec::new(), &mut conflicts);
+	MergedNetwork { network, conflicts }
+}
+
+fn merge_network(base: &NodeNetwork, ours: &NodeNetwork, theirs: &NodeNetwork, network_path: &mut Vec<NodeId>, conflicts: &mut Vec<MergeConflict>) -> NodeNetwork {
+	let mut nodes = FxHashMap::default();
+
+	for node_id in sorted_node_ids(&[base, ours, theirs]) {
+		let (base_node, our_node, their_node) = (base.nodes.get(&node_id), ours.nodes.get(&node_id), theirs.nodes.get(&node_id));
+		let merged = match three_way(base_node, our_node, their_node, nodes_equal) {
+			Some(merged) => merged.cloned(),
+			None => match (our_node, their_node) {
+				(Some(our_node), Some(their_node)) => Some(merge_node(base_node, our_node, their_node, node_id, network_path, conflicts)),
+				(our_node, their_node) => {
+					conflicts.push(MergeConflict::Node {
+						network_path: network_path.clone(),
+						node_id,
+						base: base_node.cloned(),
+						ours: our_node.cloned(),
+						theirs: their_node.cloned(),
+					});
+					our_node.or(their_node).cloned()
+				}
+			},
+		};
+		if let Some(node) = merged {
+			nodes.insert(node_id, node);
+		}
+	}
+
+	let exports = merge_inputs(&base.exports, &ours.exports, &theirs.exports, |export_index, base, ours, theirs| {
+		conflicts.push(MergeConflict::Export {
+			network_path: network_path.clone(),
+			export_index,
+			base,
+			ours,
+			theirs,
+		})
+	});
+
+	let mut scope_injections = ours.scope_injections.clone();
+	for key in base.scope_injections.keys().chain(theirs.scope_injections.keys()) {
+		let (base_injection, our_injection, their_injection) = (base.scope_injections.get(key), ours.scope_injections.get(key), theirs.scope_injections.get(key));
+		// Scope injections belong to the nodes providing them, so conflicts between them are already reported for those nodes
+		if let Some(merged) = three_way(base_injection, our_injection, their_injection, |a, b| a == b) {
+			match merged {
+				Some(injection) => scope_injections.insert(key.clone(), injection.clone()),
+				None => scope_injections.remove(key),
+			};
+		}
+	}
+
+	NodeNetwork {
+		exports,
+		nodes,
+		scope_injections,
+		generated: false,
+	}
+}
+
+/// Merges a node which both sides changed in different ways.
+fn merge_node(base: Option<&DocumentNode>, ours: &DocumentNode, theirs: &DocumentNode, node_id: NodeId, network_path: &mut Vec<NodeId>, conflicts: &mut Vec<MergeConflict>) -> DocumentNode {
+	let mut merged = ours.clone();
+
+	match (&ours.implementation, &theirs.implementation) {
+		(DocumentNodeImplementation::Network(our_network), DocumentNodeImplementation::Network(their_network)) => {
+			let empty_network = NodeNetwork::default();
+			let base_network = base.and_then(|base| base.implementation.get_network()).unwrap_or(&empty_network);
+			network_path.push(node_id);
+			merged.implementation = DocumentNodeImplementation::Network(merge_network(base_network, our_network, their_network, network_path, conflicts));
+			network_path.pop();
+		}
+		(our_implementation, their_implementation) => match three_way(base.map(|base| &base.implementation), Some(our_implementation), Some(their_implementation), |a, b| a == b) {
+			Some(implementation) => merged.implementation = implementation.unwrap_or(our_implementation).clone(),
+			None => {
+				// The inputs of different implementations can't be compared, so the whole node is kept from our side
+				conflicts.push(MergeConflict::Node {
+					network_path: network_path.clone(),
+					node_id,
+					base: base.cloned(),
+					ours: Some(ours.clone()),
+					theirs: Some(theirs.clone()),
+				});
+				return merged;
+			}
+		},
+	}
+
+	let empty_inputs = Vec::new();
+	let base_inputs = base.map_or(&empty_inputs, |base| &base.inputs);
+	merged.inputs = merge_inputs(base_inputs, &ours.inputs, &theirs.inputs, |input_index, base, ours, theirs| {
+		conflicts.push(MergeConflict::Input {
+			network_path: network_path.clone(),
+			node_id,
+			input_index,
+			base,
+			ours,
+			theirs,
+		})
+	});
+
+	// These follow from the implementation or can only be set one way, so there's nothing to report when they can't be merged
+	let three_way_field = |field: fn(&DocumentNode) -> bool| three_way(base.map(field).as_ref(), Some(&field(ours)), Some(&field(theirs)), |a, b| a == b).flatten().copied();
+	merged.visible = three_way_field(|node| node.visible).unwrap_or(ours.visible);
+	merged.skip_deduplication = three_way_field(|node| node.skip_deduplication).unwrap_or(ours.skip_deduplication);
+	if let Some(Some(call_argument)) = three_way(base.map(|base| &base.call_argument), Some(&ours.call_argument), Some(&theirs.call_argument), |a, b| a == b) {
+		merged.call_argument = call_argument.clone();
+	}
+	if let Some(Some(context_features)) = three_way(base.map(|base| &base.context_features), Some(&ours.context_features), Some(&theirs.context_features), |a, b| a == b) {
+		merged.context_features = context_features.clone();
+	}
+
+	merged
+}
+
+/// Merges a list of inputs or exports index by index, reporting the indices both sides changed differently to `conflict` and keeping our side for them.
+/// If only one side changed the number of inputs, that side's number is kept. If both sides changed it differently, every index past the shorter side is reported as a conflict and our number is kept.
+fn merge_inputs(base: &[NodeInput], ours: &[NodeInput], theirs: &[NodeInput], mut conflict: impl FnMut(usize, Option<NodeInput>, Option<NodeInput>, Option<NodeInput>)) -> Vec<NodeInput> {
+	let both_resized = ours.len() != base.len() && theirs.len() != base.len() && ours.len() != theirs.len();
+	let length = if ours.len() == base.len() { theirs.len() } else { ours.len() };
+	let checked_length = if both_resized { ours.len().max(theirs.len()) } else { length };
+	let shorter_length = ours.len().min(theirs.len());
+
+	(0..checked_length)
+		.filter_map(|index| {
+			let (base_input, our_input, their_input) = (base.get(index), ours.get(index), theirs.get(index));
+			let resized = both_resized && index >= shorter_length;
+			match three_way(base_input, our_input, their_input, |a, b| a == b) {
+				Some(merged) if !resized => merged.or(our_input).or(their_input).cloned(),
+				_ => {
+					conflict(index, base_input.cloned(), our_input.cloned(), their_input.cloned());
+					if resized { our_input.cloned() } else { our_input.or(their_input).cloned() }
+				}
+			}
+		})
+		.collect()
+}
+
+/// Picks the side which changed something from the base, or returns `None` if both sides changed it differently.
+fn three_way<'a, T>(base: Option<&'a T>, ours: Option<&'a T>, theirs: Option<&'a T>, equal: impl Fn(&T, &T) -> bool) -> Option<Option<&'a T>> {
+	let same = |a: Option<&T>, b: Option<&T>| match (a, b) {
+		(Some(a), Some(b)) => equal(a, b),
+		(a, b) => a.is_none() && b.is_none(),
+	};
+
+	if same(ours, theirs) || same(base, theirs) {
+		Some(ours)
+	} else if same(base, ours) {
+		Some(theirs)
+	} else {
+		None
+	}
+}
+
+/// Compares nodes including the contents of their nested networks, which the [`PartialEq`] implementation of [`NodeNetwork`] leaves out.
+fn nodes_equal(a: &DocumentNode, b: &DocumentNode) -> bool {
+	let implementations_equal = match (&a.implementation, &b.implementation) {
+		(DocumentNodeImplementation::Network(a), DocumentNodeImplementation::Network(b)) => networks_equal(a, b),
+		(a, b) => a == b,
+	};
+
+	implementations_equal
+		&& a.inputs == b.inputs
+		&& a.call_argument == b.call_argument
+		&& a.visible == b.visible
+		&& a.skip_deduplication == b.skip_deduplication
+		&& a.context_features == b.context_features
+}
+
+fn networks_equal(a: &NodeNetwork, b: &NodeNetwork) -> bool {
+	a.exports == b.exports && a.scope_injections == b.scope_injections && a.nodes.len() == b.nodes.len() && a.nodes.iter().all(|(node_id, a)| b.nodes.get(node_id).is_some_and(|b| nodes_equal(a, b)))
+}
+
+fn add_implementation_names(network: &NodeNetwork, network_path: &mut Vec<NodeId>, implementation_names: &mut HashMap<Vec<NodeId>, String>) {
+	for (&node_id, node) in &network.nodes {
+		implementation_names.insert(node_path(network_path, node_id), implementation_name(&node.implementation));
+		if let DocumentNodeImplementation::Network(nested_network) = &node.implementation {
+			network_path.push(node_id);
+			add_implementation_names(nested_network, network_path, implementation_names);
+			network_path.pop();
+		}
+	}
+}
+
+fn sorted_node_ids(networks: &[&NodeNetwork]) -> BTreeSet<NodeId> {
+	networks.iter().flat_map(|network| network.nodes.keys().copied()).collect()
+}
+
+fn node_path(network_path: &[NodeId], node_id: NodeId) -> Vec<NodeId> {
+	let mut node_path = network_path.to_vec();
+	node_path.push(node_id);
+	node_path
+}
+
+/// A short name for what a node does, such as the name of its proto node without the module path.
+fn implementation_name(implementation: &DocumentNodeImplementation) -> String {
+	match implementation {
+		DocumentNodeImplementation::Network(_) => "Subgraph".to_string(),
+		DocumentNodeImplementation::ProtoNode(identifier) => {
+			let path = identifier.as_str().split('<').next().unwrap_or_default();
+			path.rsplit("::").next().unwrap_or(path).to_string()
+		}
+		DocumentNodeImplementation::Extract => "Extract".to_string(),
+	}
+}
+
+fn describe_value(value: &TaggedValue) -> String {
+	match value {
+		TaggedValue::None => "nothing".to_string(),
+		TaggedValue::String(string) => format!("\"{string}\""),
+		TaggedValue::U32(_) | TaggedValue::U64(_) | TaggedValue::F32(_) | TaggedValue::F64(_) | TaggedValue::Bool(_) => value.to_string(),
+		_ => {
+			let debug = format!("{value:?}");
+			if debug.len() <= MAX_VALUE_DESCRIPTION_LENGTH { debug } else { format!("a {} value", value.ty()) }
+		}
+	}
+}
+
+struct Labels<'a> {
+	names: &'a NodeNames,
+	implementation_names: &'a HashMap<Vec<NodeId>, String>,
+}
+
+impl Labels<'_> {
+	fn node(&self, network_path: &[NodeId], node_id: NodeId) -> String {
+		let name = self
+			.names
+			.get(network_path, node_id)
+			.map(|name| name.name.clone())
+			.filter(|name| !name.is_empty())
+			.or_else(|| self.implementation_names.get(&node_path(network_path, node_id)).cloned())
+			.unwrap_or_else(|| "Node".to_string());
+		format!("\"{name}\" ({node_id})")
+	}
+
+	fn input(&self, network_path: &[NodeId], node_id: NodeId, input_index: usize) -> String {
+		let name = self.names.get(network_path, node_id).and_then(|name| name.input_names.get(input_index)).filter(|name| !name.is_empty());
+		match name {
+			Some(name) => format!("input \"{name}\""),
+			None => format!("input {input_index}"),
+		}
+	}
+
+	fn location(&self, network_path: &[NodeId]) -> String {
+		match network_path.split_last() {
+			Some((&node_id, parent_path)) => format!(" inside {}", self.node(parent_path, node_id)),
+			None => String::new(),
+		}
+	}
+
+	fn connection(&self, network_path: &[NodeId], input: Option<&NodeInput>) -> String {
+		match input {
+			None => "nothing".to_string(),
+			Some(NodeInput::Node { node_id, output_index }) => format!("output {output_index} of {}", self.node(network_path, *node_id)),
+			Some(NodeInput::Value { tagged_value, .. }) => format!("the value {}", describe_value(tagged_value)),
+			Some(NodeInput::Import { import_index, .. }) => format!("import {import_index}"),
+			Some(NodeInput::Scope(key)) => format!("the \"{key}\" scope"),
+			Some(NodeInput::Reflection(metadata)) => format!("the {metadata:?} reflection"),
+			Some(NodeInput::Inline(inline)) => format!("the inline Rust `{}`", inline.expr),
+		}
+	}
+
+	fn change(&self, change: &NetworkChange) -> String {
+		match change {
+			NetworkChange::NodeAdded { network_path, node_id } => format!("Added {}{}", self.node(network_path, *node_id), self.location(network_path)),
+			NetworkChange::NodeRemoved { network_path, node_id } => format!("Removed {}{}", self.node(network_path, *node_id), self.location(network_path)),
+			NetworkChange::ImplementationChanged { network_path, node_id, old, new } => {
+				format!("Changed {}{} from {old} to {new}", self.node(network_path, *node_id), self.location(network_path))
+			}
+			NetworkChange::InputRewired {
+				network_path,
+				node_id,
+				input_index,
+				old,
+				new,
+			} => format!(
+				"Connected {} of {}{} to {} instead of {}",
+				self.input(network_path, *node_id, *input_index),
+				self.node(network_path, *node_id),
+				self.location(network_path),
+				self.connection(network_path, new.as_ref()),
+				self.connection(network_path, old.as_ref()),
+			),
+			NetworkChange::ValueChanged {
+				network_path,
+				node_id,
+				input_index,
+				old,
+				new,
+			} => format!(
+				"Changed {} of {}{} from {} to {}",
+				self.input(network_path, *node_id, *input_index),
+				self.node(network_path, *node_id),
+				self.location(network_path),
+				describe_value(old),
+				describe_value(new),
+			),
+			NetworkChange::VisibilityChanged { network_path, node_id, visible } => {
+				let action = if *visible { "Showed" } else { "Hid" };
+				format!("{action} {}{}", self.node(network_path, *node_id), self.location(network_path))
+			}
+			NetworkChange::ExportRewired { network_path, export_index, old, new } => format!(
+				"Connected export {export_index}{} to {} instead of {}",
+				self.location(network_path),
+				self.connection(network_path, new.as_ref()),
+				self.connection(network_path, old.as_ref()),
+			),
+		}
+	}
+
+	fn conflict(&self, conflict: &MergeConflict) -> String {
+		let (subject, [base, ours, theirs]) = match conflict {
+			MergeConflict::Node {
+				network_path,
+				node_id,
+				base,
+				ours,
+				theirs,
+			} => {
+				let label = |node: &Option<DocumentNode>| {
+					node.as_ref().map_or_else(
+						|| "removed".to_string(),
+						|node| format!("{} with {} inputs", implementation_name(&node.implementation), node.inputs.len()),
+					)
+				};
+				(
+					format!("{}{}", self.node(network_path, *node_id), self.location(network_path)),
+					[label(base), label(ours), label(theirs)],
+				)
+			}
+			MergeConflict::Input {
+				network_path,
+				node_id,
+				input_index,
+				base,
+				ours,
+				theirs,
+			} => (
+				format!(
+					"{} of {}{}",
+					self.input(network_path, *node_id, *input_index),
+					self.node(network_path, *node_id),
+					self.location(network_path)
+				),
+				[base, ours, theirs].map(|input| self.connection(network_path, input.as_ref())),
+			),
+			MergeConflict::Export {
+				network_path,
+				export_index,
+				base,
+				ours,
+				theirs,
+			} => (
+				format!("export {export_index}{}", self.location(network_path)),
+				[base, ours, theirs].map(|export| self.connection(network_path, export.as_ref())),
+			),
+		};
+
+		format!("<<<<<<< ours\n{subject}: {ours}\n||||||| base\n{subject}: {base}\n=======\n{subject}: {theirs}\n>>>>>>> theirs")
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::ProtoNodeIdentifier;
+
+	fn node(identifier: &'static str, inputs: Vec<NodeInput>) -> DocumentNode {
+		DocumentNode {
+			inputs,
+			implementation: DocumentNodeImplementation::ProtoNode(ProtoNodeIdentifier::new(identifier)),
+			..Default::default()
+		}
+	}
+
+	fn base_network() -> NodeNetwork {
+		NodeNetwork {
+			exports: vec![NodeInput::node(NodeId(1), 0)],
+			nodes: [
+				(NodeId(0), node("graphene_core::ops::ValueNode", vec![NodeInput::value(TaggedValue::F64(1.), false)])),
+				(
+					NodeId(1),
+					node("graphene_core::ops::AddNode", vec![NodeInput::node(NodeId(0), 0), NodeInput::value(TaggedValue::F64(2.), false)]),
+				),
+			]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn diff_reports_added_removed_rewired_and_changed_values() {
+		let old = base_network();
+		let mut new = base_network();
+		new.nodes.remove(&NodeId(0));
+		new.nodes.insert(NodeId(2), node("graphene_core::ops::ValueNode", vec![NodeInput::value(TaggedValue::F64(3.), false)]));
+		new.nodes.get_mut(&NodeId(1)).unwrap().inputs = vec![NodeInput::node(NodeId(2), 0), NodeInput::value(TaggedValue::F64(5.), false)];
+
+		let diff = diff_networks(&old, &new);
+		assert_eq!(
+			diff.changes,
+			vec![
+				NetworkChange::NodeRemoved {
+					network_path: vec![],
+					node_id: NodeId(0)
+				},
+				NetworkChange::InputRewired {
+					network_path: vec![],
+					node_id: NodeId(1),
+					input_index: 0,
+					old: Some(NodeInput::node(NodeId(0), 0)),
+					new: Some(NodeInput::node(NodeId(2), 0)),
+				},
+				NetworkChange::ValueChanged {
+					network_path: vec![],
+					node_id: NodeId(1),
+					input_index: 1,
+					old: TaggedValue::F64(2.),
+					new: TaggedValue::F64(5.),
+				},
+				NetworkChange::NodeAdded {
+					network_path: vec![],
+					node_id: NodeId(2)
+				},
+			]
+		);
+
+		let mut names = NodeNames::default();
+		names.insert(
+			vec![NodeId(1)],
+			NodeName {
+				name: "Sum".to_string(),
+				input_names: vec!["Primary".to_string(), "Addend".to_string()],
+			},
+		);
+		assert_eq!(diff.describe(&names)[2], "Changed input \"Addend\" of \"Sum\" (1) from 2 to 5");
+		assert_eq!(diff.describe(&NodeNames::default())[0], "Removed \"ValueNode\" (0)");
+
+		assert!(diff_networks(&old, &base_network()).is_empty());
+	}
+
+	#[test]
+	fn diff_reports_changes_in_nested_networks() {
+		let old = NodeNetwork {
+			nodes: [(
+				NodeId(5),
+				DocumentNode {
+					implementation: DocumentNodeImplementation::Network(base_network()),
+					..Default::default()
+				},
+			)]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		};
+		let mut new = old.clone();
+		let nested = new.nodes.get_mut(&NodeId(5)).unwrap().implementation.get_network_mut().unwrap();
+		nested.nodes.get_mut(&NodeId(0)).unwrap().inputs = vec![NodeInput::value(TaggedValue::F64(4.), false)];
+
+		let diff = diff_networks(&old, &new);
+		assert_eq!(
+			diff.changes,
+			vec![NetworkChange::ValueChanged {
+				network_path: vec![NodeId(5)],
+				node_id: NodeId(0),
+				input_index: 0,
+				old: TaggedValue::F64(1.),
+				new: TaggedValue::F64(4.),
+			}]
+		);
+		assert_eq!(diff.describe(&NodeNames::default())[0], "Changed input 0 of \"ValueNode\" (0) inside \"Subgraph\" (5) from 1 to 4");
+	}
+
+	#[test]
+	fn merge_combines_changes_to_different_inputs() {
+		let base = base_network();
+		let mut ours = base_network();
+		ours.nodes.get_mut(&NodeId(0)).unwrap().inputs[0] = NodeInput::value(TaggedValue::F64(10.), false);
+		let mut theirs = base_network();
+		theirs.nodes.get_mut(&NodeId(1)).unwrap().inputs[1] = NodeInput::value(TaggedValue::F64(20.), false);
+		theirs.nodes.insert(NodeId(2), node("graphene_core::ops::ValueNode", vec![]));
+
+		let merged = merge_networks(&base, &ours, &theirs);
+		assert!(merged.conflicts.is_empty());
+		assert_eq!(merged.network.nodes[&NodeId(0)].inputs[0], NodeInput::value(TaggedValue::F64(10.), false));
+		assert_eq!(merged.network.nodes[&NodeId(1)].inputs[1], NodeInput::value(TaggedValue::F64(20.), false));
+		assert!(merged.network.nodes.contains_key(&NodeId(2)));
+	}
+
+	#[test]
+	fn merge_reports_conflicting_inputs_and_keeps_ours() {
+		let base = base_network();
+		let mut ours = base_network();
+		ours.nodes.get_mut(&NodeId(1)).unwrap().inputs[1] = NodeInput::value(TaggedValue::F64(10.), false);
+		let mut theirs = base_network();
+		theirs.nodes.get_mut(&NodeId(1)).unwrap().inputs[1] = NodeInput::value(TaggedValue::F64(20.), false);
+		theirs.nodes.get_mut(&NodeId(1)).unwrap().visible = false;
+
+		let merged = merge_networks(&base, &ours, &theirs);
+		assert_eq!(
+			merged.conflicts,
+			vec![MergeConflict::Input {
+				network_path: vec![],
+				node_id: NodeId(1),
+				input_index: 1,
+				base: Some(NodeInput::value(TaggedValue::F64(2.), false)),
+				ours: Some(NodeInput::value(TaggedValue::F64(10.), false)),
+				theirs: Some(NodeInput::value(TaggedValue::F64(20.), false)),
+			}]
+		);
+		assert_eq!(merged.network.nodes[&NodeId(1)].inputs[1], NodeInput::value(TaggedValue::F64(10.), false));
+		assert!(!merged.network.nodes[&NodeId(1)].visible);
+		assert!(merged.describe_conflicts(&NodeNames::default())[0].starts_with("<<<<<<< ours\ninput 1 of \"AddNode\" (1): the value 10\n"));
+	}
+
+	#[test]
+	fn merge_reports_conflicting_input_counts_and_keeps_ours() {
+		let base = base_network();
+		let mut ours = base_network();
+		ours.nodes.get_mut(&NodeId(1)).unwrap().inputs.pop();
+		let mut theirs = base_network();
+		theirs.nodes.get_mut(&NodeId(1)).unwrap().inputs.push(NodeInput::value(TaggedValue::F64(3.), false));
+
+		let merged = merge_networks(&base, &ours, &theirs);
+		assert_eq!(
+			merged.conflicts,
+			vec![
+				MergeConflict::Input {
+					network_path: vec![],
+					node_id: NodeId(1),
+					input_index: 1,
+					base: Some(NodeInput::value(TaggedValue::F64(2.), false)),
+					ours: None,
+					theirs: Some(NodeInput::value(TaggedValue::F64(2.), false)),
+				},
+				MergeConflict::Input {
+					network_path: vec![],
+					node_id: NodeId(1),
+					input_index: 2,
+					base: None,
+					ours: None,
+					theirs: Some(NodeInput::value(TaggedValue::F64(3.), false)),
+				},
+			]
+		);
+		assert_eq!(merged.network.nodes[&NodeId(1)].inputs, ours.nodes[&NodeId(1)].inputs);
+	}
+
+	#[test]
+	fn merge_keeps_nodes_removed_on_one_side_and_changed_on_the_other() {
+		let base = base_network();
+		let mut ours = base_network();
+		ours.nodes.remove(&NodeId(0));
+		let mut theirs = base_network();
+		theirs.nodes.get_mut(&NodeId(0)).unwrap().inputs[0] = NodeInput::value(TaggedValue::F64(30.), false);
+
+		let merged = merge_networks(&base, &ours, &theirs);
+		assert!(matches!(merged.conflicts.as_slice(), [MergeConflict::Node { node_id: NodeId(0), ours: None, .. }]));
+		assert_eq!(merged.network.nodes[&NodeId(0)], theirs.nodes[&NodeId(0)]);
+	}
+}
@@ -3,6 +3,7 @@ use crate::proto::{Any as DAny, FutureAny};
 use crate::wasm_application_io::WasmEditorApi;
 use brush_nodes::brush_cache::BrushCache;
 use brush_nodes::brush_stroke::BrushStroke;
+use brush_nodes::clone_stamp::CloneStroke;
 use core_types::table::Table;
 use core_types::uuid::NodeId;
 use core_types::{Color, ContextFeatures, MemoHash, Node, Type};
@@ -217,6 +218,11 @@ tagged_value! {
 	GradientStops(GradientStops),
 	Font(text_nodes::Font),
 	BrushStrokes(Vec<BrushStroke>),
+	CloneStrokes(Vec<CloneStroke>),
+	HealStrokes(Vec<raster_nodes::healing::HealStroke>),
+	PatchRegions(Vec<raster_nodes::healing::PatchRegion>),
+	SelectionSteps(Vec<raster_nodes::selection::SelectionStep>),
+	SelectionFills(Vec<raster_nodes::selection::SelectionFill>),
 	BrushCache(BrushCache),
 	DocumentNode(DocumentNode),
 	ContextFeatures(ContextFeatures),
@@ -240,6 +246,13 @@ tagged_value! {
 	DomainWarpType(raster_nodes::adjustments::DomainWarpType),
 	RelativeAbsolute(raster_nodes::adjustments::RelativeAbsolute),
 	SelectiveColorChoice(raster_nodes::adjustments::SelectiveColorChoice),
+	BokehShape(raster_nodes::filter::BokehShape),
+	MorphologyOperation(raster_nodes::morphology::MorphologyOperation),
+	MorphologyShape(raster_nodes::morphology::MorphologyShape),
+	MaskChannel(raster_nodes::morphology::MaskChannel),
+	HistogramChannel(raster_nodes::image_statistics::HistogramChannel),
+	ColorSpace(graphic_types::raster_types::color_management::ColorSpace),
+	RenderingIntent(graphic_types::raster_types::color_management::RenderingIntent),
 	GridType(vector::misc::GridType),
 	ArcType(vector::misc::ArcType),
 	MergeByDistanceAlgorithm(vector::misc::MergeByDistanceAlgorithm),
@@ -254,6 +267,8 @@ tagged_value! {
 	PaintOrder(vector::style::PaintOrder),
 	FillType(vector::style::FillType),
 	GradientType(vector::style::GradientType),
+	ColorModel(vector::style::ColorModel),
+	ColorComponent(vector::style::ColorComponent),
 	ReferencePoint(vector::ReferencePoint),
 	CentroidType(vector::misc::CentroidType),
 	BooleanOperation(path_bool_nodes::BooleanOperation),
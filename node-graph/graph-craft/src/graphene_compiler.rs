@@ -5,7 +5,16 @@ use std::error::Error;
 pub struct Compiler {}
 
 impl Compiler {
-	pub fn compile(&self, mut network: NodeNetwork) -> impl Iterator<Item = Result<ProtoNetwork, String>> {
+	pub fn compile(&self, network: NodeNetwork) -> impl Iterator<Item = Result<ProtoNetwork, String>> {
+		self.compile_with_indexed_ids(network).map(|proto_network| {
+			let mut proto_network = proto_network?;
+			proto_network.generate_stable_node_ids();
+			Ok(proto_network)
+		})
+	}
+	/// Compiles the network like [`Self::compile`], but leaves the ID of each proto node as its index.
+	/// The proto nodes can then still be changed before [`ProtoNetwork::generate_stable_node_ids`] is called, without compiling the network again.
+	pub fn compile_with_indexed_ids(&self, mut network: NodeNetwork) -> impl Iterator<Item = Result<ProtoNetwork, String>> {
 		let node_ids = network.nodes.keys().copied().collect::<Vec<_>>();
 		network.populate_dependants();
 		for id in node_ids {
@@ -18,13 +27,17 @@ impl Compiler {
 
 		proto_networks.map(move |mut proto_network| {
 			proto_network.insert_context_nullification_nodes()?;
-			proto_network.generate_stable_node_ids();
 			Ok(proto_network)
 		})
 	}
 	pub fn compile_single(&self, network: NodeNetwork) -> Result<ProtoNetwork, String> {
+		let mut proto_network = self.compile_single_with_indexed_ids(network)?;
+		proto_network.generate_stable_node_ids();
+		Ok(proto_network)
+	}
+	pub fn compile_single_with_indexed_ids(&self, network: NodeNetwork) -> Result<ProtoNetwork, String> {
 		assert_eq!(network.exports.len(), 1, "Graph with multiple outputs not yet handled");
-		let Some(proto_network) = self.compile(network).next() else {
+		let Some(proto_network) = self.compile_with_indexed_ids(network).next() else {
 			return Err("Failed to convert graph into proto graph".to_string());
 		};
 		proto_network
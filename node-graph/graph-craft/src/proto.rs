@@ -317,6 +317,14 @@ impl ProtoNetwork {
 			p.push(NodeId(10))
 		}
 
+		// Nodes opt into keeping several outputs with the `lru_cache` attribute, since the shared memory budget relies on size estimates which don't account for GPU textures or render outputs
+		let lru_cache = NODE_METADATA.lock().unwrap().get(&node.identifier).is_some_and(|metadata| metadata.lru_cache);
+		let memo_identifier = if lru_cache {
+			graphene_core::memo::lru_memo::IDENTIFIER
+		} else {
+			graphene_core::memo::memo::IDENTIFIER
+		};
+
 		let memo_node_id = NodeId(self.nodes.len() as u64);
 
 		self.nodes.push((
@@ -324,7 +332,7 @@ impl ProtoNetwork {
 			ProtoNode {
 				construction_args: ConstructionArgs::Nodes(vec![node_id]),
 				call_argument: concrete!(Context),
-				identifier: graphene_core::memo::memo::IDENTIFIER,
+				identifier: memo_identifier,
 				original_location: OriginalLocation {
 					path: path.clone(),
 					..Default::default()
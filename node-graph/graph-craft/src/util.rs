@@ -1,11 +1,36 @@
-use crate::document::NodeNetwork;
+use crate::document::{NodeId, NodeNetwork};
 use crate::graphene_compiler::Compiler;
 use crate::proto::ProtoNetwork;
 
 pub fn load_network(document_string: &str) -> NodeNetwork {
 	let document: serde_json::Value = serde_json::from_str(document_string).expect("Failed to parse document");
-	let document = (document["network_interface"]["network"].clone()).to_string();
-	serde_json::from_str::<NodeNetwork>(&document).expect("Failed to parse document")
+	let network = (document["network_interface"]["network"].clone()).to_string();
+	let mut network = serde_json::from_str::<NodeNetwork>(&network).expect("Failed to parse document");
+	resolve_symbol_instances(&mut network, &document);
+	network
+}
+
+/// Gives each symbol instance in the document network the network of its definition, since instances only refer to their definition instead of storing a copy of it.
+fn resolve_symbol_instances(network: &mut NodeNetwork, document: &serde_json::Value) {
+	let node_metadata = document["network_interface"]["network_metadata"]["persistent_metadata"]["node_metadata"]
+		.as_array()
+		.cloned()
+		.unwrap_or_default();
+	let instances = node_metadata.iter().filter_map(|entry| {
+		let instance = serde_json::from_value::<NodeId>(entry[0].clone()).ok()?;
+		let definition = serde_json::from_value::<NodeId>(entry[1]["persistent_metadata"]["symbol"]["Instance"]["definition"].clone()).ok()?;
+		Some((instance, definition))
+	});
+
+	for (instance, definition) in instances {
+		let Some(definition_node) = network.nodes.get(&definition).cloned() else { continue };
+		let Some(instance_node) = network.nodes.get_mut(&instance) else { continue };
+
+		instance_node.implementation = definition_node.implementation;
+		instance_node.inputs.truncate(definition_node.inputs.len());
+		let synced_len = instance_node.inputs.len();
+		instance_node.inputs.extend(definition_node.inputs.into_iter().skip(synced_len));
+	}
 }
 
 pub fn compile(network: NodeNetwork) -> ProtoNetwork {
@@ -0,0 +1,163 @@
+use graph_craft::document::diff::{NodeName, NodeNames, diff_networks, merge_networks};
+use graph_craft::document::{DocumentNodeImplementation, NodeId, NodeNetwork};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+
+/// The field of a merged document which lists the conflicts of the merge that produced it.
+const MERGE_CONFLICTS_FIELD: &str = "merge_conflicts";
+
+/// Prints the changes between two versions of a document, one per line.
+pub fn print_diff(old_path: &Path, new_path: &Path) -> Result<(), Box<dyn Error>> {
+	let old = read_document(old_path)?;
+	let new = read_document(new_path)?;
+
+	let mut names = NodeNames::default();
+	for document in [&old, &new] {
+		add_node_names(node_metadata(document), &mut Vec::new(), &mut names);
+	}
+
+	let diff = diff_networks(&document_network(&old)?, &document_network(&new)?);
+	for change in diff.describe(&names) {
+		println!("{change}");
+	}
+
+	Ok(())
+}
+
+/// Writes the three-way merge of two versions of a document with their common base version, and returns the number of conflicts.
+/// The parts of the document other than the node graph, such as the view of the canvas, are kept from our version.
+/// Conflicts keep our version in the merged node graph, and are recorded in the document's `merge_conflicts` list so they survive beyond the printed output.
+pub fn merge_documents(base_path: &Path, ours_path: &Path, theirs_path: &Path, output_path: &Path) -> Result<usize, Box<dyn Error>> {
+	let base = read_document(base_path)?;
+	let ours = read_document(ours_path)?;
+	let theirs = read_document(theirs_path)?;
+
+	let merged = merge_networks(&document_network(&base)?, &document_network(&ours)?, &document_network(&theirs)?);
+
+	let mut names = NodeNames::default();
+	for document in [&base, &theirs, &ours] {
+		add_node_names(node_metadata(document), &mut Vec::new(), &mut names);
+	}
+	let descriptions = merged.describe_conflicts(&names);
+	for description in &descriptions {
+		eprintln!("{description}");
+	}
+
+	// The editor keeps the metadata of each node, like its position, beside the network, so it has to follow the nodes chosen by the merge
+	let merged_metadata = merge_node_metadata(node_metadata(&base), node_metadata(&ours), node_metadata(&theirs), &merged.network);
+
+	let mut document = ours;
+	document["network_interface"]["network"] = serde_json::to_value(&merged.network)?;
+	document["network_interface"]["network_metadata"]["persistent_metadata"]["node_metadata"] = merged_metadata;
+
+	// The editor ignores fields it doesn't know, so the conflicts can be listed beside the merged document without breaking it, each with its conflict markers and the versions of each side
+	let document_object = document.as_object_mut().ok_or("The document isn't a JSON object")?;
+	if merged.conflicts.is_empty() {
+		document_object.remove(MERGE_CONFLICTS_FIELD);
+	} else {
+		let conflicts = descriptions
+			.into_iter()
+			.zip(&merged.conflicts)
+			.map(|(markers, conflict)| Ok(serde_json::json!({ "markers": markers, "conflict": serde_json::to_value(conflict)? })))
+			.collect::<Result<Vec<_>, serde_json::Error>>()?;
+		document_object.insert(MERGE_CONFLICTS_FIELD.to_string(), Value::Array(conflicts));
+	}
+	std::fs::write(output_path, serde_json::to_string(&document)?)?;
+
+	Ok(merged.conflicts.len())
+}
+
+fn read_document(path: &Path) -> Result<Value, Box<dyn Error>> {
+	let contents = std::fs::read_to_string(path).map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+	Ok(serde_json::from_str(&contents).map_err(|error| format!("Failed to parse {}: {error}", path.display()))?)
+}
+
+fn document_network(document: &Value) -> Result<NodeNetwork, Box<dyn Error>> {
+	Ok(serde_json::from_value(document["network_interface"]["network"].clone())?)
+}
+
+pub fn node_metadata(document: &Value) -> &Value {
+	&document["network_interface"]["network_metadata"]["persistent_metadata"]["node_metadata"]
+}
+
+pub fn nested_node_metadata(metadata: &Value) -> &Value {
+	&metadata["persistent_metadata"]["network_metadata"]["persistent_metadata"]["node_metadata"]
+}
+
+/// The metadata of each node in a network, which is stored as a list of pairs of node IDs and metadata.
+pub fn metadata_entries(node_metadata: &Value) -> HashMap<NodeId, &Value> {
+	let entries = node_metadata.as_array().map(Vec::as_slice).unwrap_or_default();
+	entries.iter().filter_map(|entry| Some((NodeId(entry.get(0)?.as_u64()?), entry.get(1)?))).collect()
+}
+
+/// Names each node by its display name, or otherwise by the name of the node definition it was created from.
+fn add_node_names(node_metadata: &Value, network_path: &mut Vec<NodeId>, names: &mut NodeNames) {
+	for (node_id, metadata) in metadata_entries(node_metadata) {
+		network_path.push(node_id);
+		names.insert(network_path.clone(), node_name(metadata));
+		add_node_names(nested_node_metadata(metadata), network_path, names);
+		network_path.pop();
+	}
+}
+
+/// The name shown for a node and its inputs in the editor, read from the node's metadata.
+pub fn node_name(metadata: &Value) -> NodeName {
+	let persistent_metadata = &metadata["persistent_metadata"];
+	let name = persistent_metadata["display_name"]
+		.as_str()
+		.filter(|name| !name.is_empty())
+		.or_else(|| persistent_metadata["reference"].as_str())
+		.unwrap_or_default()
+		.to_string();
+	let input_metadata = persistent_metadata["input_metadata"].as_array().map(Vec::as_slice).unwrap_or_default();
+	let input_names = input_metadata
+		.iter()
+		.map(|input| input["persistent_metadata"]["input_name"].as_str().unwrap_or_default().to_string())
+		.collect();
+
+	NodeName { name, input_names }
+}
+
+/// Picks the metadata of each node in the merged network from the side whose version of the node was taken, preferring our side where both changed it.
+/// The metadata of the inputs is then taken from whichever side has as many of them as the merged node has inputs.
+fn merge_node_metadata(base: &Value, ours: &Value, theirs: &Value, network: &NodeNetwork) -> Value {
+	let (base_entries, our_entries, their_entries) = (metadata_entries(base), metadata_entries(ours), metadata_entries(theirs));
+
+	let mut node_ids = network.nodes.keys().copied().collect::<Vec<_>>();
+	node_ids.sort();
+
+	let entries = node_ids
+		.into_iter()
+		.filter_map(|node_id| {
+			let (base_metadata, our_metadata, their_metadata) = (base_entries.get(&node_id), our_entries.get(&node_id), their_entries.get(&node_id));
+			let mut metadata = match (our_metadata, their_metadata) {
+				(Some(ours), Some(theirs)) if ours == theirs || base_metadata == Some(theirs) => (*ours).clone(),
+				(Some(ours), Some(theirs)) if base_metadata == Some(ours) => (*theirs).clone(),
+				(Some(ours), Some(theirs)) => {
+					let mut metadata = (*ours).clone();
+					if let Some(DocumentNodeImplementation::Network(nested_network)) = network.nodes.get(&node_id).map(|node| &node.implementation) {
+						let base_nested = base_metadata.map_or(&Value::Null, |base| nested_node_metadata(base));
+						let nested = merge_node_metadata(base_nested, nested_node_metadata(ours), nested_node_metadata(theirs), nested_network);
+						metadata["persistent_metadata"]["network_metadata"]["persistent_metadata"]["node_metadata"] = nested;
+					}
+					metadata
+				}
+				(ours, theirs) => (*ours.or(theirs)?).clone(),
+			};
+
+			let input_metadata_count = |metadata: &Value| metadata["persistent_metadata"]["input_metadata"].as_array().map(Vec::len);
+			let input_count = network.nodes.get(&node_id).map(|node| node.inputs.len());
+			if input_metadata_count(&metadata) != input_count
+				&& let Some(matching) = [our_metadata, their_metadata].into_iter().flatten().find(|side| input_metadata_count(side) == input_count)
+			{
+				metadata["persistent_metadata"]["input_metadata"] = matching["persistent_metadata"]["input_metadata"].clone();
+			}
+
+			Some(Value::Array(vec![node_id.0.into(), metadata]))
+		})
+		.collect();
+
+	Value::Array(entries)
+}
@@ -1,5 +1,6 @@
 use graph_craft::document::value::{RenderOutputType, TaggedValue, UVec2};
 use graph_craft::graphene_compiler::Executor;
+pub use graphene_std::application_io::export_preset::FileType;
 use graphene_std::application_io::{ExportFormat, RenderConfig};
 use graphene_std::core_types::ops::Convert;
 use graphene_std::core_types::transform::Footprint;
@@ -9,19 +10,10 @@ use std::error::Error;
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum FileType {
-	Svg,
-	Png,
-	Jpg,
-}
-
 pub fn detect_file_type(path: &Path) -> Result<FileType, String> {
-	match path.extension().and_then(|s| s.to_str()) {
-		Some("svg") => Ok(FileType::Svg),
-		Some("png") => Ok(FileType::Png),
-		Some("jpg" | "jpeg") => Ok(FileType::Jpg),
-		_ => Err(format!("Unsupported file extension. Supported formats: .svg, .png, .jpg")),
+	match path.extension().and_then(|s| s.to_str()).and_then(FileType::from_extension) {
+		Some(file_type) => Ok(file_type),
+		None => Err(format!("Unsupported file extension. Supported formats: .svg, .png, .jpg")),
 	}
 }
 
@@ -35,6 +27,16 @@ pub async fn export_document(
 	height: Option<u32>,
 	transparent: bool,
 ) -> Result<(), Box<dyn Error>> {
+	let render_config = render_config(file_type, scale, width, height);
+
+	// Execute the graph
+	let result = executor.execute(render_config).await?;
+
+	write_render_output(result, wgpu_executor, output_path, file_type, transparent).await
+}
+
+/// The settings for rendering the document as a file of the given type, optionally at a specific size in pixels.
+pub fn render_config(file_type: FileType, scale: f64, width: Option<u32>, height: Option<u32>) -> RenderConfig {
 	// Determine export format based on file type
 	let export_format = match file_type {
 		FileType::Svg => ExportFormat::Svg,
@@ -52,41 +54,44 @@ pub async fn export_document(
 		render_config.viewport.resolution = UVec2::new(w, h);
 	}
 
-	// Execute the graph
-	let result = executor.execute(render_config).await?;
+	render_config
+}
+
+/// Writes the result of rendering the graph to a file of the given type.
+pub async fn write_render_output(result: TaggedValue, wgpu_executor: &wgpu_executor::WgpuExecutor, output_path: PathBuf, file_type: FileType, transparent: bool) -> Result<(), Box<dyn Error>> {
+	let contents = encode_render_output(result, wgpu_executor, file_type, transparent).await?;
+	std::fs::write(&output_path, contents)?;
+	log::info!("Exported {} to: {}", file_type.extension().to_uppercase(), output_path.display());
+
+	Ok(())
+}
 
-	// Handle the result based on output type
+/// Encodes the result of rendering the graph as the contents of a file of the given type.
+pub async fn encode_render_output(result: TaggedValue, wgpu_executor: &wgpu_executor::WgpuExecutor, file_type: FileType, transparent: bool) -> Result<Vec<u8>, Box<dyn Error>> {
 	match result {
 		TaggedValue::RenderOutput(output) => match output.data {
-			RenderOutputType::Svg { svg, .. } => {
-				// Write SVG directly to file
-				std::fs::write(&output_path, svg)?;
-				log::info!("Exported SVG to: {}", output_path.display());
-			}
+			// SVG is already the contents of the file
+			RenderOutputType::Svg { svg, .. } => Ok(svg.into_bytes()),
 			RenderOutputType::Texture(image_texture) => {
 				// Convert GPU texture to CPU buffer
 				let gpu_raster = Raster::<GPU>::new_gpu(image_texture.texture);
 				let cpu_raster: Raster<CPU> = gpu_raster.convert(Footprint::BOUNDLESS, wgpu_executor).await;
 				let (data, width, height) = cpu_raster.to_flat_u8();
 
-				// Encode and write raster image
-				write_raster_image(output_path, file_type, data, width, height, transparent)?;
+				// Encode raster image
+				encode_raster_image(file_type, data, width, height, transparent)
 			}
 			RenderOutputType::Buffer { data, width, height } => {
-				// Encode and write raster image when buffer is already provided
-				write_raster_image(output_path, file_type, data, width, height, transparent)?;
-			}
-			other => {
-				return Err(format!("Unexpected render output type: {:?}. Expected Texture, Buffer for raster export or Svg for SVG export.", other).into());
+				// Encode raster image when buffer is already provided
+				encode_raster_image(file_type, data, width, height, transparent)
 			}
+			other => Err(format!("Unexpected render output type: {:?}. Expected Texture, Buffer for raster export or Svg for SVG export.", other).into()),
 		},
-		other => return Err(format!("Expected RenderOutput, got: {:?}", other).into()),
+		other => Err(format!("Expected RenderOutput, got: {:?}", other).into()),
 	}
-
-	Ok(())
 }
 
-fn write_raster_image(output_path: PathBuf, file_type: FileType, data: Vec<u8>, width: u32, height: u32, transparent: bool) -> Result<(), Box<dyn Error>> {
+fn encode_raster_image(file_type: FileType, data: Vec<u8>, width: u32, height: u32, transparent: bool) -> Result<Vec<u8>, Box<dyn Error>> {
 	use image::{ImageFormat, RgbaImage};
 
 	let image = RgbaImage::from_raw(width, height, data).ok_or("Failed to create image from buffer")?;
@@ -101,16 +106,13 @@ fn write_raster_image(output_path: PathBuf, file_type: FileType, data: Vec<u8>,
 				let image: image::RgbImage = image::DynamicImage::ImageRgba8(image).to_rgb8();
 				image.write_to(&mut cursor, ImageFormat::Png)?;
 			}
-			log::info!("Exported PNG to: {}", output_path.display());
 		}
 		FileType::Jpg => {
 			let image: image::RgbImage = image::DynamicImage::ImageRgba8(image).to_rgb8();
 			image.write_to(&mut cursor, ImageFormat::Jpeg)?;
-			log::info!("Exported JPG to: {}", output_path.display());
 		}
-		FileType::Svg => unreachable!("SVG should have been handled in export_document"),
+		FileType::Svg => unreachable!("SVG should have been handled in encode_render_output"),
 	}
 
-	std::fs::write(&output_path, cursor.into_inner())?;
-	Ok(())
+	Ok(cursor.into_inner())
 }
@@ -1,8 +1,13 @@
+mod document_diff;
 mod export;
+mod presets;
+mod profile;
+mod serve;
 
 use clap::{Args, Parser, Subcommand};
 use fern::colors::{Color, ColoredLevelConfig};
 use futures::executor::block_on;
+use graph_craft::ProtoNodeIdentifier;
 use graph_craft::document::*;
 use graph_craft::graphene_compiler::Compiler;
 use graph_craft::proto::ProtoNetwork;
@@ -11,8 +16,10 @@ use graph_craft::wasm_application_io::EditorPreferences;
 use graphene_std::application_io::{ApplicationIo, NodeGraphUpdateMessage, NodeGraphUpdateSender};
 use graphene_std::text::FontCache;
 use graphene_std::wasm_application_io::{WasmApplicationIo, WasmEditorApi};
+use interpreted_executor::disk_cache::DiskCache;
 use interpreted_executor::dynamic_executor::DynamicExecutor;
 use interpreted_executor::util::wrap_network_in_scope;
+use std::collections::HashMap;
 use std::error::Error;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -75,6 +82,79 @@ enum Command {
 		#[clap(long)]
 		transparent: bool,
 	},
+	/// Export every preset saved with the layers, artboards, and slices of a .graphite document, like Export All in the editor.
+	ExportAll {
+		/// Path to the .graphite document
+		document: PathBuf,
+
+		/// Directory that the exported files are written into
+		#[clap(long, short = 'o')]
+		output_directory: PathBuf,
+
+		/// Optional input image resource
+		#[clap(long)]
+		image: Option<PathBuf>,
+	},
+	/// Render a .graphite document and report how long each of its nodes took to run, as JSON.
+	Profile {
+		/// Path to the .graphite document
+		document: PathBuf,
+
+		/// JSON file to write the report into, instead of printing it
+		#[clap(long, short = 'o')]
+		output: Option<PathBuf>,
+
+		/// Number of times to render the document, which shows how often the nodes' caches are hit after the first render
+		#[clap(long, default_value = "1")]
+		runs: u32,
+
+		/// Optional input image resource
+		#[clap(long)]
+		image: Option<PathBuf>,
+	},
+	/// Describe the changes between two versions of a .graphite document, node by node.
+	Diff {
+		/// Path to the older version of the .graphite document
+		old: PathBuf,
+
+		/// Path to the newer version of the .graphite document
+		new: PathBuf,
+	},
+	/// Merge the changes that two versions of a .graphite document made to their common base version, like a git merge driver.
+	/// Conflicting changes keep our version in the merged node graph. They're printed with conflict markers, and also listed with their markers and each side's version under `merge_conflicts` in the merged document.
+	/// Exits with an error status if there were any conflicts, so git leaves the merged document marked as conflicted until they're resolved.
+	Merge {
+		/// Path to the common base version of the .graphite document
+		base: PathBuf,
+
+		/// Path to our version of the .graphite document
+		ours: PathBuf,
+
+		/// Path to their version of the .graphite document
+		theirs: PathBuf,
+
+		/// Path to write the merged .graphite document to, which may be the same as one of the versions
+		#[clap(long, short = 'o')]
+		output: PathBuf,
+	},
+	/// Keep .graphite documents compiled and render them for local HTTP requests, which can override the values of the documents' inputs.
+	///
+	/// Each document is rendered at /render/<document file name without its extension>.
+	/// GET requests take the format, width, height, scale, and transparent options from the query string.
+	/// POST requests take a JSON body with the same options along with a list of overrides, each with a node, an input, and a value.
+	Serve {
+		/// Paths to the .graphite documents
+		#[clap(required = true)]
+		documents: Vec<PathBuf>,
+
+		/// Local address to listen for requests at
+		#[clap(long, default_value = "127.0.0.1:8080")]
+		address: String,
+
+		/// Optional input image resource
+		#[clap(long)]
+		image: Option<PathBuf>,
+	},
 	ListNodeIdentifiers,
 }
 
@@ -83,6 +163,14 @@ struct GlobalOpts {
 	/// Verbosity level (can be specified multiple times)
 	#[clap(long, short, global = true, action = clap::ArgAction::Count)]
 	verbose: u8,
+
+	/// Directory to keep the outputs of expensive nodes in, so later runs of the same document can reuse them
+	#[clap(long, global = true)]
+	disk_cache: Option<PathBuf>,
+
+	/// Maximum size of the disk cache in megabytes, beyond which the least recently used outputs are deleted
+	#[clap(long, global = true, default_value = "2048")]
+	disk_cache_limit: u64,
 }
 
 #[tokio::main]
@@ -94,8 +182,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
 	init_logging(log_level);
 
 	let document_path = match app.command {
-		Command::Compile { ref document, .. } => document,
-		Command::Export { ref document, .. } => document,
+		Command::Compile { ref document, .. } => Some(document),
+		Command::Export { ref document, .. } => Some(document),
+		Command::ExportAll { ref document, .. } => Some(document),
+		Command::Profile { ref document, .. } => Some(document),
+		// Serving loads each of its documents once the GPU context is ready
+		Command::Serve { .. } => None,
 		Command::ListNodeIdentifiers => {
 			let mut ids: Vec<_> = graphene_std::registry::NODE_METADATA.lock().unwrap().keys().cloned().collect();
 			ids.sort_by_key(|x| x.as_str().to_string());
@@ -104,14 +196,29 @@ async fn main() -> Result<(), Box<dyn Error>> {
 			}
 			return Ok(());
 		}
+		Command::Diff { ref old, ref new } => return document_diff::print_diff(old, new),
+		Command::Merge {
+			ref base,
+			ref ours,
+			ref theirs,
+			ref output,
+		} => {
+			let conflicts = document_diff::merge_documents(base, ours, theirs, output)?;
+			if conflicts > 0 {
+				return Err(format!("The merge has {conflicts} conflicts, which use our version in the merged document and are listed in its `merge_conflicts` field").into());
+			}
+			return Ok(());
+		}
 	};
 
-	let document_string = std::fs::read_to_string(document_path).expect("Failed to read document");
-
 	log::info!("creating gpu context",);
 	let mut application_io = block_on(WasmApplicationIo::new_offscreen());
 
-	if let Command::Export { image: Some(ref image_path), .. } = app.command {
+	if let Command::Export { image: Some(ref image_path), .. }
+	| Command::ExportAll { image: Some(ref image_path), .. }
+	| Command::Profile { image: Some(ref image_path), .. }
+	| Command::Serve { image: Some(ref image_path), .. } = app.command
+	{
 		application_io.resources.insert("null".to_string(), Arc::from(std::fs::read(image_path).expect("Failed to read image")));
 	}
 
@@ -133,7 +240,27 @@ async fn main() -> Result<(), Box<dyn Error>> {
 		editor_preferences: Box::new(preferences),
 	});
 
-	let proto_graph = compile_graph(document_string, editor_api)?;
+	let disk_cache = match &app.global_opts.disk_cache {
+		Some(directory) => Some(Arc::new(DiskCache::open(directory, app.global_opts.disk_cache_limit * 1024 * 1024)?)),
+		None => None,
+	};
+
+	if let Command::Serve { ref documents, ref address, .. } = app.command {
+		// Spawn thread to poll GPU device
+		std::thread::spawn(move || {
+			loop {
+				std::thread::sleep(std::time::Duration::from_nanos(10));
+				device.poll(wgpu::PollType::Poll).unwrap();
+			}
+		});
+
+		return serve::serve_documents(documents, address, editor_api, wgpu_executor_ref, disk_cache).await;
+	}
+
+	let document_path = document_path.expect("Every command other than serve should have a document");
+	let document_string = std::fs::read_to_string(document_path).expect("Failed to read document");
+
+	let proto_graph = compile_graph(document_string.clone(), editor_api)?;
 
 	match app.command {
 		Command::Compile { print_proto, .. } => {
@@ -161,10 +288,44 @@ async fn main() -> Result<(), Box<dyn Error>> {
 			let file_type = export::detect_file_type(&output)?;
 
 			// Create executor
-			let executor = create_executor(proto_graph)?;
+			let executor = create_executor(proto_graph, disk_cache)?;
 
 			// Perform export
 			export::export_document(&executor, wgpu_executor_ref, output, file_type, scale, width, height, transparent).await?;
+			log_disk_cache_stats(&executor);
+		}
+		Command::ExportAll { output_directory, .. } => {
+			// Spawn thread to poll GPU device
+			std::thread::spawn(move || {
+				loop {
+					std::thread::sleep(std::time::Duration::from_nanos(10));
+					device.poll(wgpu::PollType::Poll).unwrap();
+				}
+			});
+
+			let document: serde_json::Value = serde_json::from_str(&document_string)?;
+			let executor = create_executor(proto_graph, disk_cache)?;
+
+			presets::export_presets(&executor, wgpu_executor_ref, &document, &output_directory).await?;
+			log_disk_cache_stats(&executor);
+		}
+		Command::Profile { output, runs, .. } => {
+			// Spawn thread to poll GPU device
+			std::thread::spawn(move || {
+				loop {
+					std::thread::sleep(std::time::Duration::from_nanos(10));
+					device.poll(wgpu::PollType::Poll).unwrap();
+				}
+			});
+
+			let mut executor = DynamicExecutor::default();
+			executor.set_profiling(true);
+			executor.set_disk_cache(disk_cache);
+			block_on(executor.update(proto_graph)).map_err(|(_, errors)| errors.iter().map(|e| format!("{e:?}")).reduce(|acc, e| format!("{acc}\n{e}")).unwrap_or_default())?;
+
+			let report = profile::profile_document(&executor, runs).await?;
+			profile::write_profile(&report, output)?;
+			log_disk_cache_stats(&executor);
 		}
 		_ => unreachable!("All other commands should be handled before this match statement is run"),
 	}
@@ -226,15 +387,46 @@ fn compile_graph(document_string: String, editor_api: Arc<WasmEditorApi>) -> Res
 	fix_nodes(&mut network);
 
 	let substitutions = preprocessor::generate_node_substitutions();
-	preprocessor::expand_network(&mut network, &substitutions);
+	compile_network(network, &substitutions, editor_api)
+}
+
+/// Compiles the network of a document whose nodes were already fixed by [`fix_nodes`].
+fn compile_network(network: NodeNetwork, substitutions: &HashMap<ProtoNodeIdentifier, DocumentNode>, editor_api: Arc<WasmEditorApi>) -> Result<ProtoNetwork, Box<dyn Error>> {
+	let mut proto_network = compile_network_with_indexed_ids(network, substitutions, editor_api)?;
+	proto_network.generate_stable_node_ids();
+	Ok(proto_network)
+}
 
-	let wrapped_network = wrap_network_in_scope(network.clone(), editor_api);
+/// Compiles the network like [`compile_network`], but leaves the ID of each proto node as its index, so the proto nodes can still be changed before their stable IDs are generated.
+fn compile_network_with_indexed_ids(mut network: NodeNetwork, substitutions: &HashMap<ProtoNodeIdentifier, DocumentNode>, editor_api: Arc<WasmEditorApi>) -> Result<ProtoNetwork, Box<dyn Error>> {
+	preprocessor::expand_network(&mut network, substitutions);
+
+	let wrapped_network = wrap_network_in_scope(network, editor_api);
 
 	let compiler = Compiler {};
-	compiler.compile_single(wrapped_network).map_err(|x| x.into())
+	compiler.compile_single_with_indexed_ids(wrapped_network).map_err(|x| x.into())
 }
 
-fn create_executor(proto_network: ProtoNetwork) -> Result<DynamicExecutor, Box<dyn Error>> {
-	let executor = block_on(DynamicExecutor::new(proto_network)).map_err(|errors| errors.iter().map(|e| format!("{e:?}")).reduce(|acc, e| format!("{acc}\n{e}")).unwrap_or_default())?;
+fn create_executor(proto_network: ProtoNetwork, disk_cache: Option<Arc<DiskCache>>) -> Result<DynamicExecutor, Box<dyn Error>> {
+	let mut executor = DynamicExecutor::default();
+	executor.set_disk_cache(disk_cache);
+	block_on(executor.update(proto_network)).map_err(|(_, errors)| errors.iter().map(|e| format!("{e:?}")).reduce(|acc, e| format!("{acc}\n{e}")).unwrap_or_default())?;
 	Ok(executor)
 }
+
+fn log_disk_cache_stats(executor: &DynamicExecutor) {
+	if let Some(disk_cache) = executor.disk_cache() {
+		// Outputs are written in the background, so they're waited for before the process exits
+		disk_cache.flush();
+
+		let stats = disk_cache.stats();
+		log::info!(
+			"Disk cache: {} hits, {} misses, {} of {} MB used by {} outputs",
+			stats.hits,
+			stats.misses,
+			stats.used / (1024 * 1024),
+			stats.limit / (1024 * 1024),
+			stats.entries
+		);
+	}
+}
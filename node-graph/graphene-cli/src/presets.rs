@@ -0,0 +1,192 @@
+use crate::export::{self, FileType};
+use graph_craft::document::value::{DAffine2, DVec2, TaggedValue};
+use graph_craft::document::{NodeId, NodeNetwork};
+use graph_craft::graphene_compiler::Executor;
+use graphene_std::application_io::export_preset::ExportPreset;
+use graphene_std::application_io::{ExportFormat, RenderConfig};
+use graphene_std::core_types::transform::Footprint;
+use graphene_std::math::quad::Quad;
+use interpreted_executor::dynamic_executor::DynamicExecutor;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+struct NodeMetadata {
+	persistent_metadata: NodePersistentMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+struct NodePersistentMetadata {
+	#[serde(default)]
+	display_name: String,
+	#[serde(default)]
+	export_presets: Vec<ExportPreset>,
+	#[serde(default)]
+	node_type_metadata: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportSlice {
+	name: String,
+	bounds: [DVec2; 2],
+	presets: Vec<ExportPreset>,
+}
+
+/// Where the region exported for a preset comes from.
+enum TargetBounds {
+	/// The bounds of a layer, which are only known once the graph has been rendered.
+	/// The layer is clipped by any of the layers containing it, from innermost to outermost, which are artboards that clip their content.
+	Layer {
+		node_id: NodeId,
+		ancestors: Vec<NodeId>,
+	},
+	Slice([DVec2; 2]),
+}
+
+struct PresetTarget {
+	name: String,
+	bounds: TargetBounds,
+	presets: Vec<ExportPreset>,
+}
+
+/// Finds the layers and slices of the document which have export presets.
+fn preset_targets(document: &serde_json::Value) -> Vec<PresetTarget> {
+	let node_metadata = document["network_interface"]["network_metadata"]["persistent_metadata"]["node_metadata"]
+		.as_array()
+		.cloned()
+		.unwrap_or_default()
+		.into_iter()
+		.filter_map(|entry| serde_json::from_value::<(NodeId, NodeMetadata)>(entry).ok())
+		.collect::<Vec<_>>();
+
+	let network = serde_json::from_value::<NodeNetwork>(document["network_interface"]["network"].clone()).unwrap_or_default();
+	let layer_ids = node_metadata
+		.iter()
+		.filter(|(_, metadata)| metadata.persistent_metadata.node_type_metadata.get("Layer").is_some())
+		.map(|(node_id, _)| *node_id)
+		.collect();
+	let parents = parent_layers(&network, &layer_ids);
+
+	let layers = node_metadata
+		.into_iter()
+		.filter(|(_, metadata)| !metadata.persistent_metadata.export_presets.is_empty())
+		.map(|(node_id, metadata)| {
+			let NodePersistentMetadata { display_name, export_presets, .. } = metadata.persistent_metadata;
+			let ancestors = std::iter::successors(parents.get(&node_id).copied(), |parent| parents.get(parent).copied())
+				.take(parents.len())
+				.collect();
+			PresetTarget {
+				name: if display_name.is_empty() { "Untitled Layer".to_string() } else { display_name },
+				bounds: TargetBounds::Layer { node_id, ancestors },
+				presets: export_presets,
+			}
+		});
+
+	let slices = serde_json::from_value::<Vec<ExportSlice>>(document["export_slices"].clone()).unwrap_or_default();
+	let slices = slices.into_iter().map(|slice| PresetTarget {
+		name: slice.name,
+		bounds: TargetBounds::Slice(slice.bounds),
+		presets: slice.presets,
+	});
+
+	layers.chain(slices).collect()
+}
+
+/// Finds the layer containing each layer in the network.
+/// Each layer in a stack feeds into the primary input of the layer above it, and the top of the stack feeds into the secondary input of the layer containing it.
+fn parent_layers(network: &NodeNetwork, layers: &HashSet<NodeId>) -> HashMap<NodeId, NodeId> {
+	let mut downstream = HashMap::new();
+	for (&node_id, node) in &network.nodes {
+		for (input_index, input) in node.inputs.iter().enumerate() {
+			if let Some(upstream) = input.as_node() {
+				downstream.entry(upstream).or_insert((node_id, input_index));
+			}
+		}
+	}
+
+	layers
+		.iter()
+		.filter_map(|&layer| {
+			let mut current = layer;
+			// Bounded by the number of nodes in case the network contains a cycle
+			for _ in 0..network.nodes.len() {
+				let &(node_id, input_index) = downstream.get(&current)?;
+				if input_index == 1 && layers.contains(&node_id) {
+					return Some((layer, node_id));
+				}
+				current = node_id;
+			}
+			None
+		})
+		.collect()
+}
+
+/// Renders every export preset saved in the document into `output_directory`, like Export All in the editor.
+pub async fn export_presets(executor: &DynamicExecutor, wgpu_executor: &wgpu_executor::WgpuExecutor, document: &serde_json::Value, output_directory: &Path) -> Result<(), Box<dyn Error>> {
+	let targets = preset_targets(document);
+	if targets.is_empty() {
+		return Err("No layers, artboards, or slices in this document have export presets".into());
+	}
+
+	// Render the whole document once to find where each layer is
+	let mut metadata_config = RenderConfig::default();
+	metadata_config.export_format = ExportFormat::Svg;
+	metadata_config.for_export = true;
+	let TaggedValue::RenderOutput(output) = executor.execute(metadata_config).await? else {
+		return Err("Expected the document to render".into());
+	};
+	let metadata = output.metadata;
+
+	std::fs::create_dir_all(output_directory)?;
+
+	let layer_bounds = |node_id: NodeId| {
+		let footprint = metadata.upstream_footprints.get(&node_id).map(|footprint| footprint.transform).unwrap_or_default();
+		let transform = footprint * metadata.local_transforms.get(&node_id).copied().unwrap_or_default();
+		metadata.click_targets.get(&node_id).and_then(|click_targets| {
+			click_targets
+				.iter()
+				.filter_map(|click_target| click_target.bounding_box_with_transform(transform))
+				.reduce(Quad::combine_bounds)
+		})
+	};
+
+	for target in targets {
+		let bounds = match target.bounds {
+			TargetBounds::Slice(bounds) => Some(bounds),
+			// Parts of the layer outside of any artboard that clips it aren't visible, so they're left out like in the editor
+			TargetBounds::Layer { node_id, ancestors } => ancestors
+				.into_iter()
+				.filter(|ancestor| metadata.clip_targets.contains(ancestor))
+				.filter_map(layer_bounds)
+				.fold(layer_bounds(node_id), |bounds, artboard_bounds| bounds.map(|bounds| Quad::clip(bounds, artboard_bounds))),
+		};
+		let Some(bounds) = bounds.filter(|bounds| bounds[0].cmplt(bounds[1]).all()) else {
+			log::error!("Skipping \"{}\" since it has no bounds", target.name);
+			continue;
+		};
+
+		for preset in &target.presets {
+			for scale in preset.export_scales() {
+				let mut render_config = RenderConfig::default();
+				render_config.viewport = Footprint {
+					resolution: (bounds[1] - bounds[0]).round().as_uvec2(),
+					transform: DAffine2::from_translation(-bounds[0]),
+					..Default::default()
+				};
+				render_config.scale = scale;
+				render_config.export_format = if preset.file_type == FileType::Svg { ExportFormat::Svg } else { ExportFormat::Raster };
+				render_config.hide_artboards = preset.transparent_background;
+				render_config.for_export = true;
+
+				let transparent = preset.file_type != FileType::Jpg && preset.transparent_background;
+				let file_name = format!("{}.{}", preset.file_stem(&target.name, scale), preset.file_type.extension());
+				let result = executor.execute(render_config).await?;
+				export::write_render_output(result, wgpu_executor, output_directory.join(file_name), preset.file_type, transparent).await?;
+			}
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,34 @@
+use graph_craft::graphene_compiler::Executor;
+use graphene_std::application_io::{ExportFormat, RenderConfig};
+use interpreted_executor::dynamic_executor::DynamicExecutor;
+use interpreted_executor::profiling::NodeProfileReport;
+use std::error::Error;
+use std::path::PathBuf;
+
+/// Renders the document the given number of times and returns the combined execution statistics of its nodes.
+///
+/// The first render computes every node, while later ones show how well the nodes' caches are hit.
+pub async fn profile_document(executor: &DynamicExecutor, runs: u32) -> Result<NodeProfileReport, Box<dyn Error>> {
+	let mut render_config = RenderConfig::default();
+	render_config.export_format = ExportFormat::Svg;
+	render_config.for_export = true;
+
+	executor.reset_profile();
+	for _ in 0..runs.max(1) {
+		executor.execute(render_config).await?;
+	}
+
+	executor.profile_report().ok_or_else(|| "Profiling was not enabled for the executor".into())
+}
+
+/// Writes the report as JSON to the output file, or prints it if no file is given.
+pub fn write_profile(report: &NodeProfileReport, output: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+	let json = serde_json::to_string_pretty(report)?;
+
+	match output {
+		Some(path) => std::fs::write(path, json)?,
+		None => println!("{json}"),
+	}
+
+	Ok(())
+}
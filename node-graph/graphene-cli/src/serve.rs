@@ -0,0 +1,666 @@
+use crate::document_diff::{metadata_entries, nested_node_metadata, node_metadata, node_name};
+use crate::export::{self, FileType};
+use crate::{compile_network_with_indexed_ids, create_executor, fix_nodes};
+use graph_craft::ProtoNodeIdentifier;
+use graph_craft::document::diff::NodeName;
+use graph_craft::document::value::TaggedValue;
+use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeId, NodeInput, NodeNetwork};
+use graph_craft::graphene_compiler::Executor;
+use graph_craft::proto::{ConstructionArgs, ProtoNetwork};
+use graph_craft::util::load_network;
+use graphene_std::wasm_application_io::WasmEditorApi;
+use interpreted_executor::disk_cache::DiskCache;
+use interpreted_executor::dynamic_executor::DynamicExecutor;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc, oneshot};
+
+/// Requests larger than this are rejected, which leaves plenty of room for overriding the inputs of a document.
+const MAX_REQUEST_SIZE: usize = 16 * 1024 * 1024;
+
+/// How long a client has to send its whole request before the connection is closed, so stalled clients don't hold on to their connections.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many connections are handled at once, which also bounds the number of requests waiting to be rendered. Further connections wait to be accepted until one of them is closed.
+const MAX_CONNECTIONS: usize = 64;
+
+/// A document which is compiled once, so each request only changes the values it overrides and recomputes the nodes affected by them.
+struct ServedDocument {
+	/// The network of the document without any overrides, which the overrides of each request are checked against.
+	network: NodeNetwork,
+	/// The names of the nodes and their inputs in the editor, by the path to each node.
+	node_names: HashMap<Vec<NodeId>, NodeName>,
+	/// The compiled document without any overrides, whose proto nodes are still identified by their indices so the overridden values can be changed before the stable IDs are generated.
+	proto_network: ProtoNetwork,
+	/// The path of the proto node holding the value of each value input, by the path to the input's node and the index of the input.
+	value_nodes: HashMap<(Vec<NodeId>, usize), Vec<NodeId>>,
+	executor: DynamicExecutor,
+}
+
+/// The options for rendering a document, from either the JSON body of a POST request or the query string of a GET request.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RenderRequest {
+	/// The type of file to render the document as, which is "svg" (the default), "png", or "jpg".
+	format: Option<String>,
+	width: Option<u32>,
+	height: Option<u32>,
+	scale: Option<f64>,
+	/// Whether PNGs keep the transparency of the document's background.
+	transparent: bool,
+	overrides: Vec<InputOverride>,
+}
+
+/// Replaces the value of a node's input for a single request.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InputOverride {
+	/// The name of the node in the editor, which has to be unique, or the path of node IDs leading to it.
+	node: NodeSelector,
+	/// The name of the input in the editor, or its index. The input has to hold a value rather than being connected to another node.
+	input: InputSelector,
+	/// Either a value in the format documents store it in, like `{ "F64": 2.5 }`, or a number, boolean, or string which is converted to the type of the input's current value.
+	value: Value,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum NodeSelector {
+	Path(Vec<NodeId>),
+	Name(String),
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum InputSelector {
+	Index(usize),
+	Name(String),
+}
+
+struct HttpRequest {
+	method: String,
+	path: String,
+	query: String,
+	body: Vec<u8>,
+}
+
+struct HttpResponse {
+	status: u16,
+	content_type: &'static str,
+	body: Vec<u8>,
+}
+
+/// A request read by the task of its connection, which waits for the response to be rendered.
+struct RenderJob {
+	request: HttpRequest,
+	respond: oneshot::Sender<HttpResponse>,
+}
+
+/// Compiles the documents, then renders them for each request until the process is stopped.
+/// Each document is rendered at `/render/<name>`, where the name is the document's file name without its extension.
+pub async fn serve_documents(
+	document_paths: &[PathBuf],
+	address: &str,
+	editor_api: Arc<WasmEditorApi>,
+	wgpu_executor: &wgpu_executor::WgpuExecutor,
+	disk_cache: Option<Arc<DiskCache>>,
+) -> Result<(), Box<dyn Error>> {
+	let substitutions = preprocessor::generate_node_substitutions();
+
+	let mut documents: HashMap<String, ServedDocument> = HashMap::new();
+	for path in document_paths {
+		let name = path.file_stem().and_then(|name| name.to_str()).ok_or_else(|| format!("Invalid document path: {}", path.display()))?;
+		if documents.contains_key(name) {
+			return Err(format!("More than one of the documents is named \"{name}\"").into());
+		}
+
+		let document_string = std::fs::read_to_string(path).map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+		let document: Value = serde_json::from_str(&document_string)?;
+		let mut network = load_network(&document_string);
+		fix_nodes(&mut network);
+
+		let mut node_names = HashMap::new();
+		add_node_names(node_metadata(&document), &mut Vec::new(), &mut node_names);
+
+		let mut value_nodes = HashMap::new();
+		let mut separated_network = network.clone();
+		separate_value_inputs(&mut separated_network, &mut Vec::new(), &mut value_nodes);
+		let proto_network = compile_network_with_indexed_ids(separated_network, &substitutions, editor_api.clone())?;
+
+		let mut stable_proto_network = proto_network.clone();
+		stable_proto_network.generate_stable_node_ids();
+		let executor = create_executor(stable_proto_network, disk_cache.clone())?;
+		log::info!("Loaded \"{name}\" from {}", path.display());
+
+		documents.insert(
+			name.to_string(),
+			ServedDocument {
+				network,
+				node_names,
+				proto_network,
+				value_nodes,
+				executor,
+			},
+		);
+	}
+
+	let listener = TcpListener::bind(address).await?;
+	println!("Serving {} documents at http://{}", documents.len(), listener.local_addr()?);
+
+	let (render_sender, mut render_receiver) = mpsc::channel(MAX_CONNECTIONS);
+	tokio::spawn(accept_connections(listener, render_sender));
+
+	// Connections are read and answered by tasks of their own, but requests are rendered one at a time, since rendering a document with different overrides changes its executor
+	while let Some(RenderJob { request, respond }) = render_receiver.recv().await {
+		let response = handle_request(request, &mut documents, wgpu_executor).await;
+		// The client may have closed the connection while waiting, in which case nobody is left to send the response to
+		let _ = respond.send(response);
+	}
+
+	Ok(())
+}
+
+async fn accept_connections(listener: TcpListener, render_sender: mpsc::Sender<RenderJob>) {
+	let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+	loop {
+		// The semaphore is never closed
+		let Ok(permit) = connections.clone().acquire_owned().await else { return };
+		match listener.accept().await {
+			Ok((stream, _)) => {
+				tokio::spawn(handle_connection(stream, render_sender.clone(), permit));
+			}
+			Err(error) => log::warn!("Failed to accept a connection: {error}"),
+		}
+	}
+}
+
+/// Reads the connection's request, waits for it to be rendered, and sends back the response.
+/// The permit is held until the connection is closed, so it counts towards the connections handled at once.
+async fn handle_connection(mut stream: TcpStream, render_sender: mpsc::Sender<RenderJob>, _permit: OwnedSemaphorePermit) {
+	let response = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+		Ok(Ok(request)) => {
+			let (respond, response) = oneshot::channel();
+			if render_sender.send(RenderJob { request, respond }).await.is_err() {
+				return;
+			}
+			let Ok(response) = response.await else { return };
+			response
+		}
+		Ok(Err(error)) => HttpResponse::error(400, error),
+		Err(_) => HttpResponse::error(408, format!("The whole request wasn't received within {} seconds", REQUEST_TIMEOUT.as_secs())),
+	};
+
+	if let Err(error) = response.write(&mut stream).await {
+		log::warn!("Failed to send a response: {error}");
+	}
+}
+
+async fn handle_request(request: HttpRequest, documents: &mut HashMap<String, ServedDocument>, wgpu_executor: &wgpu_executor::WgpuExecutor) -> HttpResponse {
+	let Some(name) = request.path.strip_prefix("/render/") else {
+		return HttpResponse::error(404, "Documents are rendered at /render/<document name>");
+	};
+	let name = percent_decode(name);
+
+	let render_request = match request.method.as_str() {
+		"GET" => request_from_query(&request.query),
+		"POST" if request.body.is_empty() => Ok(RenderRequest::default()),
+		"POST" => serde_json::from_slice(&request.body).map_err(|error| format!("Invalid render request: {error}")),
+		_ => return HttpResponse::error(405, "Documents are rendered with GET or POST requests"),
+	};
+	let render_request = match render_request {
+		Ok(render_request) => render_request,
+		Err(error) => return HttpResponse::error(400, error),
+	};
+
+	let Some(document) = documents.get_mut(&name) else {
+		return HttpResponse::error(404, format!("There is no document named \"{name}\""));
+	};
+	match document.render(render_request, wgpu_executor).await {
+		Ok(response) | Err(response) => response,
+	}
+}
+
+impl ServedDocument {
+	/// Renders the document with the request's overrides, which replace those of the previous request.
+	async fn render(&mut self, request: RenderRequest, wgpu_executor: &wgpu_executor::WgpuExecutor) -> Result<HttpResponse, HttpResponse> {
+		let file_type = match request.format.as_deref() {
+			Some(format) => FileType::from_extension(format).ok_or_else(|| HttpResponse::error(400, format!("Unsupported format \"{format}\". Supported formats: svg, png, jpg")))?,
+			None => FileType::Svg,
+		};
+
+		let mut proto_network = self.proto_network.clone();
+		for input_override in &request.overrides {
+			self.apply_override(&mut proto_network, input_override).map_err(|error| HttpResponse::error(400, error))?;
+		}
+
+		// Only the overridden values differ from the compiled document, so their value nodes and the nodes depending on them are the only ones whose stable IDs change,
+		// and the update keeps every other node along with its cached outputs
+		proto_network.generate_stable_node_ids();
+		self.executor.update(proto_network).await.map_err(|(_, errors)| {
+			let errors = errors.iter().map(|e| format!("{e:?}")).reduce(|acc, e| format!("{acc}\n{e}")).unwrap_or_default();
+			HttpResponse::error(400, format!("The overrides don't fit the graph:\n{errors}"))
+		})?;
+
+		let render_config = export::render_config(file_type, request.scale.unwrap_or(1.), request.width, request.height);
+		let result = self.executor.execute(render_config).await.map_err(|error| HttpResponse::error(500, error.to_string()))?;
+		let body = export::encode_render_output(result, wgpu_executor, file_type, request.transparent)
+			.await
+			.map_err(|error| HttpResponse::error(500, error.to_string()))?;
+
+		Ok(HttpResponse {
+			status: 200,
+			content_type: file_type.to_mime(),
+			body,
+		})
+	}
+
+	fn apply_override(&self, proto_network: &mut ProtoNetwork, input_override: &InputOverride) -> Result<(), String> {
+		let node_path = match &input_override.node {
+			NodeSelector::Path(node_path) => node_path.clone(),
+			NodeSelector::Name(name) => {
+				let mut node_paths = self.node_names.iter().filter(|(_, node_name)| &node_name.name == name).map(|(node_path, _)| node_path);
+				match (node_paths.next(), node_paths.next()) {
+					(Some(node_path), None) => node_path.clone(),
+					(None, _) => return Err(format!("There is no node named \"{name}\"")),
+					(Some(_), Some(_)) => return Err(format!("More than one node is named \"{name}\", so it has to be chosen by its path of node IDs")),
+				}
+			}
+		};
+		let Some((&node_id, network_path)) = node_path.split_last() else {
+			return Err("The path of node IDs leading to a node can't be empty".to_string());
+		};
+		let node = self
+			.network
+			.nested_network(network_path)
+			.and_then(|network| network.nodes.get(&node_id))
+			.ok_or_else(|| format!("There is no node at the path {node_path:?}"))?;
+
+		let input_index = match &input_override.input {
+			InputSelector::Index(input_index) => *input_index,
+			InputSelector::Name(name) => self
+				.node_names
+				.get(&node_path)
+				.and_then(|node_name| node_name.input_names.iter().position(|input_name| input_name == name))
+				.ok_or_else(|| format!("The node at {node_path:?} has no input named \"{name}\""))?,
+		};
+		let input = node.inputs.get(input_index).ok_or_else(|| format!("The node at {node_path:?} has no input {input_index}"))?;
+		let value_node_path = self
+			.value_nodes
+			.get(&(node_path.clone(), input_index))
+			.ok_or_else(|| format!("Input {input_index} of the node at {node_path:?} doesn't hold a value which can be overridden"))?;
+
+		let value =
+			override_value(&input_override.value, input.as_value()).ok_or_else(|| format!("{} isn't a valid value for input {input_index} of the node at {node_path:?}", input_override.value))?;
+		for (_, proto_node) in &mut proto_network.nodes {
+			if proto_node.original_location.path.as_ref() == Some(value_node_path) {
+				proto_node.construction_args = ConstructionArgs::Value(value.clone().into());
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Converts the value of an override into a value of the same type as the input's current value, unless it's given in the format documents store values in.
+/// Values in that format are only accepted if they have the same type as the current value, since the compiled nodes depend on the type.
+fn override_value(value: &Value, current: Option<&TaggedValue>) -> Option<TaggedValue> {
+	if value.is_object() {
+		let value: TaggedValue = serde_json::from_value(value.clone()).ok()?;
+		return current.is_none_or(|current| value.ty() == current.ty()).then_some(value);
+	}
+
+	let ty = current?.ty();
+	match value {
+		// Colors are only read from strings in quotes
+		Value::String(string) => TaggedValue::from_primitive_string(string, &ty).or_else(|| TaggedValue::from_primitive_string(&format!("\"{string}\""), &ty)),
+		Value::Number(_) | Value::Bool(_) => TaggedValue::from_primitive_string(&value.to_string(), &ty),
+		_ => None,
+	}
+}
+
+/// Moves each value input of the network's nodes into a value node of its own, which is what compiling the network does anyway, but with a known path.
+/// That path leads to the proto node holding the input's value once the network is compiled, so its value can be overridden without compiling the network again.
+fn separate_value_inputs(network: &mut NodeNetwork, network_path: &mut Vec<NodeId>, value_nodes: &mut HashMap<(Vec<NodeId>, usize), Vec<NodeId>>) {
+	// The nodes of generated networks don't get paths of their own
+	if network.generated {
+		return;
+	}
+
+	let value_node_implementation = DocumentNodeImplementation::ProtoNode(ProtoNodeIdentifier::new("core_types::value::ClonedNode"));
+	let mut separated_value_nodes = Vec::new();
+	for (&node_id, node) in &mut network.nodes {
+		let node_path = [network_path.as_slice(), &[node_id]].concat();
+
+		if node.implementation == value_node_implementation {
+			// Value nodes are compiled into proto nodes holding their value as they are
+			value_nodes.insert((node_path.clone(), 0), node_path);
+			continue;
+		}
+		// Hidden nodes are replaced by the compiler, which only keeps their first input
+		if !node.visible {
+			continue;
+		}
+
+		for (input_index, input) in node.inputs.iter_mut().enumerate() {
+			if !matches!(input, NodeInput::Value { .. }) {
+				continue;
+			}
+
+			let value_node_id = NodeId::new();
+			let value_input = std::mem::replace(input, NodeInput::node(value_node_id, 0));
+			let value_node = DocumentNode {
+				inputs: vec![value_input],
+				implementation: value_node_implementation.clone(),
+				..Default::default()
+			};
+			separated_value_nodes.push((value_node_id, value_node));
+			value_nodes.insert((node_path.clone(), input_index), [network_path.as_slice(), &[value_node_id]].concat());
+		}
+
+		if let DocumentNodeImplementation::Network(nested_network) = &mut node.implementation {
+			network_path.push(node_id);
+			separate_value_inputs(nested_network, network_path, value_nodes);
+			network_path.pop();
+		}
+	}
+
+	network.nodes.extend(separated_value_nodes);
+}
+
+fn add_node_names(node_metadata: &Value, network_path: &mut Vec<NodeId>, node_names: &mut HashMap<Vec<NodeId>, NodeName>) {
+	for (node_id, metadata) in metadata_entries(node_metadata) {
+		network_path.push(node_id);
+		node_names.insert(network_path.clone(), node_name(metadata));
+		add_node_names(nested_node_metadata(metadata), network_path, node_names);
+		network_path.pop();
+	}
+}
+
+fn request_from_query(query: &str) -> Result<RenderRequest, String> {
+	fn parse<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+		value.parse().map_err(|_| format!("Invalid value \"{value}\" for \"{key}\""))
+	}
+
+	let mut request = RenderRequest::default();
+	for parameter in query.split('&').filter(|parameter| !parameter.is_empty()) {
+		let (key, value) = parameter.split_once('=').unwrap_or((parameter, "true"));
+		let value = percent_decode(value);
+		match key {
+			"format" => request.format = Some(value),
+			"width" => request.width = Some(parse(key, &value)?),
+			"height" => request.height = Some(parse(key, &value)?),
+			"scale" => request.scale = Some(parse(key, &value)?),
+			"transparent" => request.transparent = parse(key, &value)?,
+			_ => return Err(format!("Unknown query parameter \"{key}\". Overrides can only be given in the body of a POST request")),
+		}
+	}
+
+	Ok(request)
+}
+
+/// Decodes the `%XX` escapes and `+` spaces of a part of a URL.
+fn percent_decode(text: &str) -> String {
+	let bytes = text.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut index = 0;
+	while index < bytes.len() {
+		let escaped = if bytes[index] == b'%' {
+			text.get(index + 1..index + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok())
+		} else {
+			None
+		};
+		match (bytes[index], escaped) {
+			(_, Some(byte)) => {
+				decoded.push(byte);
+				index += 3;
+			}
+			(b'+', _) => {
+				decoded.push(b' ');
+				index += 1;
+			}
+			(byte, _) => {
+				decoded.push(byte);
+				index += 1;
+			}
+		}
+	}
+
+	String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Reads a request up to the end of the body given by its `Content-Length` header.
+async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest, String> {
+	let mut buffer = Vec::new();
+	let header_end = loop {
+		if let Some(header_end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+			break header_end;
+		}
+		read_more(stream, &mut buffer).await?;
+	};
+
+	let head = std::str::from_utf8(&buffer[..header_end]).map_err(|_| "The request's headers aren't valid UTF-8".to_string())?;
+	let mut lines = head.split("\r\n");
+	let mut request_line = lines.next().unwrap_or_default().split(' ');
+	let (method, target) = (request_line.next().unwrap_or_default().to_string(), request_line.next().unwrap_or_default().to_string());
+	let content_length = lines
+		.filter_map(|line| line.split_once(':'))
+		.find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+		.map(|(_, value)| value.trim().parse::<usize>().map_err(|_| "Invalid Content-Length header".to_string()))
+		.transpose()?
+		.unwrap_or_default();
+	if content_length > MAX_REQUEST_SIZE {
+		return Err("The request is too large".to_string());
+	}
+
+	let mut body = buffer.split_off(header_end + 4);
+	while body.len() < content_length {
+		read_more(stream, &mut body).await?;
+	}
+	body.truncate(content_length);
+
+	let (path, query) = target.split_once('?').unwrap_or((target.as_str(), ""));
+	Ok(HttpRequest {
+		method,
+		path: path.to_string(),
+		query: query.to_string(),
+		body,
+	})
+}
+
+async fn read_more(stream: &mut TcpStream, buffer: &mut Vec<u8>) -> Result<(), String> {
+	if buffer.len() > MAX_REQUEST_SIZE {
+		return Err("The request is too large".to_string());
+	}
+
+	let mut chunk = [0; 8192];
+	let read = stream.read(&mut chunk).await.map_err(|error| error.to_string())?;
+	if read == 0 {
+		return Err("The connection was closed before the whole request was received".to_string());
+	}
+	buffer.extend_from_slice(&chunk[..read]);
+
+	Ok(())
+}
+
+impl HttpResponse {
+	fn error(status: u16, message: impl Into<String>) -> Self {
+		Self {
+			status,
+			content_type: "text/plain; charset=utf-8",
+			body: message.into().into_bytes(),
+		}
+	}
+
+	async fn write(self, stream: &mut TcpStream) -> std::io::Result<()> {
+		let reason = match self.status {
+			200 => "OK",
+			400 => "Bad Request",
+			404 => "Not Found",
+			405 => "Method Not Allowed",
+			408 => "Request Timeout",
+			_ => "Internal Server Error",
+		};
+		let head = format!(
+			"HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+			self.status,
+			self.content_type,
+			self.body.len()
+		);
+
+		stream.write_all(head.as_bytes()).await?;
+		stream.write_all(&self.body).await?;
+		stream.shutdown().await
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use graphene_std::Color;
+	use serde_json::json;
+
+	#[test]
+	fn percent_decode_escapes_and_spaces() {
+		assert_eq!(percent_decode("Blur%20Radius"), "Blur Radius");
+		assert_eq!(percent_decode("a+b"), "a b");
+		assert_eq!(percent_decode("%E2%9C%93"), "✓");
+		assert_eq!(percent_decode("plain"), "plain");
+	}
+
+	#[test]
+	fn percent_decode_keeps_invalid_escapes() {
+		assert_eq!(percent_decode("100%"), "100%");
+		assert_eq!(percent_decode("%4"), "%4");
+		assert_eq!(percent_decode("%zz"), "%zz");
+		assert_eq!(percent_decode("%é"), "%é");
+	}
+
+	#[test]
+	fn request_from_query_options() {
+		let request = request_from_query("format=png&width=800&height=600&scale=1.5&transparent").unwrap();
+		assert_eq!(request.format.as_deref(), Some("png"));
+		assert_eq!(request.width, Some(800));
+		assert_eq!(request.height, Some(600));
+		assert_eq!(request.scale, Some(1.5));
+		assert!(request.transparent);
+		assert!(request.overrides.is_empty());
+
+		let request = request_from_query("").unwrap();
+		assert_eq!(request.format, None);
+		assert!(!request.transparent);
+
+		assert_eq!(request_from_query("format=svg%2Bxml").unwrap().format.as_deref(), Some("svg+xml"));
+		assert!(!request_from_query("transparent=false").unwrap().transparent);
+	}
+
+	#[test]
+	fn request_from_query_rejects_invalid_parameters() {
+		assert!(request_from_query("width=wide").is_err());
+		assert!(request_from_query("width=-1").is_err());
+		assert!(request_from_query("transparent=maybe").is_err());
+		assert!(request_from_query("overrides=[]").is_err());
+	}
+
+	#[test]
+	fn override_value_converts_to_current_type() {
+		assert_eq!(override_value(&json!(2.5), Some(&TaggedValue::F64(1.))), Some(TaggedValue::F64(2.5)));
+		assert_eq!(override_value(&json!(3), Some(&TaggedValue::U32(1))), Some(TaggedValue::U32(3)));
+		assert_eq!(override_value(&json!(true), Some(&TaggedValue::Bool(false))), Some(TaggedValue::Bool(true)));
+		assert_eq!(
+			override_value(&json!("Hello"), Some(&TaggedValue::String(String::new()))),
+			Some(TaggedValue::String("Hello".to_string()))
+		);
+		assert_eq!(
+			override_value(&json!("ff0000"), Some(&TaggedValue::ColorNotInTable(Color::BLACK))),
+			Color::from_rgb_str("ff0000").map(TaggedValue::ColorNotInTable)
+		);
+	}
+
+	#[test]
+	fn override_value_in_document_format() {
+		assert_eq!(override_value(&json!({ "F64": 2.5 }), None), Some(TaggedValue::F64(2.5)));
+		assert_eq!(override_value(&json!({ "F64": 2.5 }), Some(&TaggedValue::F64(1.))), Some(TaggedValue::F64(2.5)));
+		assert_eq!(override_value(&json!({ "NotAType": 1 }), None), None);
+	}
+
+	#[test]
+	fn override_value_rejects_mismatches() {
+		assert_eq!(override_value(&json!("wide"), Some(&TaggedValue::F64(1.))), None);
+		assert_eq!(override_value(&json!(-1), Some(&TaggedValue::U32(1))), None);
+		assert_eq!(override_value(&json!(2.5), None), None);
+		assert_eq!(override_value(&json!(null), Some(&TaggedValue::F64(1.))), None);
+		assert_eq!(override_value(&json!([1, 2]), Some(&TaggedValue::F64(1.))), None);
+		assert_eq!(override_value(&json!({ "U32": 7 }), Some(&TaggedValue::F64(1.))), None);
+	}
+
+	#[test]
+	fn override_changes_the_separated_value_node() {
+		let network = NodeNetwork {
+			exports: vec![NodeInput::node(NodeId(0), 0)],
+			nodes: [(
+				NodeId(0),
+				DocumentNode {
+					inputs: vec![NodeInput::value(TaggedValue::F64(1.), false), NodeInput::value(TaggedValue::F64(2.), false)],
+					implementation: DocumentNodeImplementation::ProtoNode(graphene_std::math_nodes::add::IDENTIFIER),
+					..Default::default()
+				},
+			)]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		};
+
+		let mut value_nodes = HashMap::new();
+		let mut separated_network = network.clone();
+		separate_value_inputs(&mut separated_network, &mut Vec::new(), &mut value_nodes);
+		assert_eq!(value_nodes.len(), 2);
+		// The paths are generated like they are when the network is wrapped in its scope before compiling it
+		separated_network.generate_node_paths(&[]);
+		let proto_network = graph_craft::graphene_compiler::Compiler {}.compile_single_with_indexed_ids(separated_network).unwrap();
+
+		let mut stable_proto_network = proto_network.clone();
+		stable_proto_network.generate_stable_node_ids();
+		let mut document = ServedDocument {
+			network,
+			node_names: HashMap::from([(
+				vec![NodeId(0)],
+				NodeName {
+					name: "Add".to_string(),
+					input_names: vec!["Augend".to_string(), "Addend".to_string()],
+				},
+			)]),
+			proto_network,
+			value_nodes,
+			executor: create_executor(stable_proto_network, None).unwrap(),
+		};
+		let evaluate = |document: &ServedDocument| {
+			let context = graphene_std::OwnedContextImpl::default().into_context();
+			futures::executor::block_on(document.executor.tree().eval_tagged_value(document.executor.output(), context))
+		};
+		assert_eq!(evaluate(&document), Ok(TaggedValue::F64(3.)));
+
+		let mut proto_network = document.proto_network.clone();
+		let input_override = InputOverride {
+			node: NodeSelector::Name("Add".to_string()),
+			input: InputSelector::Name("Addend".to_string()),
+			value: json!(5),
+		};
+		document.apply_override(&mut proto_network, &input_override).unwrap();
+		proto_network.generate_stable_node_ids();
+		futures::executor::block_on(document.executor.update(proto_network)).unwrap();
+		assert_eq!(evaluate(&document), Ok(TaggedValue::F64(6.)));
+
+		let mismatched_override = InputOverride {
+			value: json!({ "String": "5" }),
+			..input_override
+		};
+		assert!(document.apply_override(&mut document.proto_network.clone(), &mismatched_override).is_err());
+	}
+}
@@ -0,0 +1,437 @@
+use core_types::memo::MemoHash;
+use core_types::{Context, Node};
+use dyn_any::DynAny;
+use graph_craft::document::value::TaggedValue;
+use graph_craft::proto::{Any, FutureAny, TypeErasedBox};
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::time::SystemTime;
+
+/// The number of bytes the disk cache may occupy when no other limit has been set with [`DiskCache::set_limit`].
+pub const DISK_CACHE_LIMIT_DEFAULT: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Changed whenever the layout of the cache files or the hashing of their keys changes, so files written by other versions are never read back.
+const DISK_CACHE_FORMAT_VERSION: u32 = 3;
+
+/// How many outputs may wait to be written at once. Outputs produced while the queue is full aren't cached, so evaluations never wait for the disk.
+const WRITE_QUEUE_SIZE: usize = 32;
+
+const ENTRY_EXTENSION: &str = "json";
+const PARTIAL_ENTRY_EXTENSION: &str = "partial";
+/// The extension of the entries written by an earlier version of the cache, which are deleted when it's opened.
+const LEGACY_ENTRY_EXTENSION: &str = "msgpack";
+
+/// A content-addressed cache on disk for the outputs of the nodes marked `disk_cache`, so their results survive closing and reopening a document.
+///
+/// Each output is stored as a serialized [`TaggedValue`] in a file named after the [`MemoHash`] of the node's identifier, the hashes of its inputs, and the context it was evaluated with.
+/// Those hashes only stay the same between builds made with the same Rust version, but a changed hash merely leaves the old files unused until they're evicted.
+/// Outputs are serialized and written by a background thread, so evaluations don't wait for the disk. Once the files exceed the size limit, the least recently used ones are deleted.
+#[derive(Debug)]
+pub struct DiskCache {
+	directory: PathBuf,
+	limit: AtomicU64,
+	index: Mutex<DiskCacheIndex>,
+	/// The keys of the outputs waiting to be written by the background thread, so each output is only queued once.
+	pending_writes: Mutex<HashSet<u64>>,
+	writes_finished: Condvar,
+	hits: AtomicU64,
+	misses: AtomicU64,
+	writes: AtomicU64,
+	evictions: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct DiskCacheIndex {
+	entries: HashMap<u64, DiskCacheEntry>,
+	used: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DiskCacheEntry {
+	size: u64,
+	last_used: SystemTime,
+}
+
+/// A snapshot of the contents of the disk cache and how it has been used since it was opened.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DiskCacheStats {
+	/// The number of bytes the cache files may occupy.
+	pub limit: u64,
+	/// The number of bytes the cache files currently occupy.
+	pub used: u64,
+	/// The number of outputs currently stored.
+	pub entries: usize,
+	/// The number of evaluations which read their output from the disk.
+	pub hits: u64,
+	/// The number of evaluations which had to evaluate the cached node.
+	pub misses: u64,
+	/// The number of outputs written to the disk.
+	pub writes: u64,
+	/// The number of outputs deleted to stay within the limit.
+	pub evictions: u64,
+}
+
+impl DiskCache {
+	/// Opens the cache stored in the given directory (creating it if needed), picking up the files left by previous sessions.
+	pub fn open(directory: impl Into<PathBuf>, limit: u64) -> io::Result<Self> {
+		let directory = directory.into();
+		std::fs::create_dir_all(&directory)?;
+
+		let mut index = DiskCacheIndex::default();
+		for file in std::fs::read_dir(&directory)? {
+			let file = file?;
+			let path = file.path();
+
+			// Files which weren't completely written before the application exited are unusable, as are those in the previous format
+			if path.extension().is_some_and(|extension| extension == PARTIAL_ENTRY_EXTENSION || extension == LEGACY_ENTRY_EXTENSION) {
+				let _ = std::fs::remove_file(&path);
+				continue;
+			}
+			let Some(key) = entry_key_from_path(&path) else { continue };
+
+			let metadata = file.metadata()?;
+			let entry = DiskCacheEntry {
+				size: metadata.len(),
+				last_used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+			};
+			index.used += entry.size;
+			index.entries.insert(key, entry);
+		}
+
+		let cache = Self {
+			directory,
+			limit: AtomicU64::new(limit),
+			index: Mutex::new(index),
+			pending_writes: Mutex::new(HashSet::new()),
+			writes_finished: Condvar::new(),
+			hits: AtomicU64::new(0),
+			misses: AtomicU64::new(0),
+			writes: AtomicU64::new(0),
+			evictions: AtomicU64::new(0),
+		};
+		cache.enforce_limit(&mut cache.index.lock().unwrap());
+
+		Ok(cache)
+	}
+
+	pub fn directory(&self) -> &Path {
+		&self.directory
+	}
+
+	/// Sets the number of bytes the cache files may occupy, deleting the least recently used ones if it's already exceeded.
+	pub fn set_limit(&self, bytes: u64) {
+		self.limit.store(bytes, Ordering::Relaxed);
+		self.enforce_limit(&mut self.index.lock().unwrap());
+	}
+
+	pub fn stats(&self) -> DiskCacheStats {
+		let index = self.index.lock().unwrap();
+		DiskCacheStats {
+			limit: self.limit.load(Ordering::Relaxed),
+			used: index.used,
+			entries: index.entries.len(),
+			hits: self.hits.load(Ordering::Relaxed),
+			misses: self.misses.load(Ordering::Relaxed),
+			writes: self.writes.load(Ordering::Relaxed),
+			evictions: self.evictions.load(Ordering::Relaxed),
+		}
+	}
+
+	/// Waits until the outputs queued so far have been written to the disk, which should happen before the process exits.
+	pub fn flush(&self) {
+		let pending_writes = self.pending_writes.lock().unwrap();
+		let _pending_writes = self.writes_finished.wait_while(pending_writes, |pending_writes| !pending_writes.is_empty()).unwrap();
+	}
+
+	/// Deletes every stored output.
+	pub fn clear(&self) -> io::Result<()> {
+		self.flush();
+		let mut index = self.index.lock().unwrap();
+		for key in index.entries.keys() {
+			match std::fs::remove_file(self.entry_path(*key)) {
+				Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+				_ => {}
+			}
+		}
+		*index = DiskCacheIndex::default();
+		Ok(())
+	}
+
+	/// Wraps a node so its outputs are read from and written to the cache.
+	///
+	/// The `node_key` identifies the node and its inputs (see [`BorrowTree`](crate::dynamic_executor::BorrowTree)), and `output_type` is the [`TypeId`] of its output, which must be representable as a [`TaggedValue`].
+	pub(crate) fn wrap(self: &Arc<Self>, node_key: u64, output_type: TypeId, node: TypeErasedBox<'static>) -> TypeErasedBox<'static> {
+		Box::new(DiskCachedNode {
+			node,
+			cache: self.clone(),
+			node_key,
+			output_type,
+		})
+	}
+
+	fn entry_path(&self, key: u64) -> PathBuf {
+		self.directory.join(format!("{key:016x}.{ENTRY_EXTENSION}"))
+	}
+
+	fn load(&self, key: u64) -> Option<TaggedValue> {
+		let now = SystemTime::now();
+		{
+			let mut index = self.index.lock().unwrap();
+			index.entries.get_mut(&key)?.last_used = now;
+		}
+
+		let path = self.entry_path(key);
+		let value = std::fs::read(&path).ok().and_then(|data| serde_json::from_slice(&data).ok());
+		match value {
+			// The modification time of the file records its last use for the next sessions
+			Some(_) => {
+				let _ = std::fs::File::options().append(true).open(&path).and_then(|file| file.set_modified(now));
+			}
+			None => {
+				log::warn!("Discarding unreadable disk cache entry {}", path.display());
+				self.remove(key);
+			}
+		}
+		value
+	}
+
+	/// Queues the output to be written by the background thread, unless the same output is already waiting to be written or the queue is full.
+	fn store(self: &Arc<Self>, key: u64, value: TaggedValue) {
+		if !self.pending_writes.lock().unwrap().insert(key) {
+			return;
+		}
+
+		let job = WriteJob { cache: self.clone(), key, value };
+		match WRITER.try_send(job) {
+			Ok(()) => {}
+			Err(TrySendError::Full(job)) => {
+				log::debug!("Skipped caching an output since the disk cache is still writing {WRITE_QUEUE_SIZE} others");
+				job.cache.finish_write(key);
+			}
+			// The writer thread only stops if it panicked, in which case the output is simply not cached
+			Err(TrySendError::Disconnected(job)) => job.cache.finish_write(key),
+		}
+	}
+
+	fn finish_write(&self, key: u64) {
+		let mut pending_writes = self.pending_writes.lock().unwrap();
+		pending_writes.remove(&key);
+		if pending_writes.is_empty() {
+			self.writes_finished.notify_all();
+		}
+	}
+
+	fn write(&self, key: u64, value: &TaggedValue) {
+		let data = match serde_json::to_vec(value) {
+			Ok(data) => data,
+			Err(error) => {
+				log::warn!("Failed to serialize a value for the disk cache: {error}");
+				return;
+			}
+		};
+		let size = data.len() as u64;
+		if size > self.limit.load(Ordering::Relaxed) {
+			return;
+		}
+
+		// Writing to a temporary file first ensures that a crash never leaves a truncated entry behind
+		let path = self.entry_path(key);
+		let partial_path = path.with_extension(PARTIAL_ENTRY_EXTENSION);
+		if let Err(error) = std::fs::write(&partial_path, data).and_then(|_| std::fs::rename(&partial_path, &path)) {
+			log::warn!("Failed to write the disk cache entry {}: {error}", path.display());
+			let _ = std::fs::remove_file(&partial_path);
+			return;
+		}
+		self.writes.fetch_add(1, Ordering::Relaxed);
+
+		let mut index = self.index.lock().unwrap();
+		let entry = DiskCacheEntry { size, last_used: SystemTime::now() };
+		if let Some(previous) = index.entries.insert(key, entry) {
+			index.used -= previous.size;
+		}
+		index.used += size;
+		self.enforce_limit(&mut index);
+	}
+
+	fn remove(&self, key: u64) {
+		let mut index = self.index.lock().unwrap();
+		if let Some(entry) = index.entries.remove(&key) {
+			index.used -= entry.size;
+		}
+		let _ = std::fs::remove_file(self.entry_path(key));
+	}
+
+	/// Deletes the least recently used entries until the files fit within the limit.
+	fn enforce_limit(&self, index: &mut DiskCacheIndex) {
+		let limit = self.limit.load(Ordering::Relaxed);
+		while index.used > limit {
+			let Some((&key, _)) = index.entries.iter().min_by_key(|(_, entry)| entry.last_used) else { break };
+			let entry = index.entries.remove(&key).unwrap();
+			index.used -= entry.size;
+			let _ = std::fs::remove_file(self.entry_path(key));
+			self.evictions.fetch_add(1, Ordering::Relaxed);
+		}
+	}
+}
+
+fn entry_key_from_path(path: &Path) -> Option<u64> {
+	if path.extension()? != ENTRY_EXTENSION {
+		return None;
+	}
+	u64::from_str_radix(path.file_stem()?.to_str()?, 16).ok()
+}
+
+/// An output which the background thread serializes and writes into its cache.
+struct WriteJob {
+	cache: Arc<DiskCache>,
+	key: u64,
+	value: TaggedValue,
+}
+
+/// Sends outputs to the thread shared by all caches which writes them one after another.
+static WRITER: LazyLock<SyncSender<WriteJob>> = LazyLock::new(|| {
+	let (sender, receiver) = mpsc::sync_channel::<WriteJob>(WRITE_QUEUE_SIZE);
+	std::thread::Builder::new()
+		.name("disk-cache-writer".into())
+		.spawn(move || {
+			for WriteJob { cache, key, value } in receiver {
+				cache.write(key, &value);
+				cache.finish_write(key);
+			}
+		})
+		.expect("Failed to spawn the disk cache writer thread");
+	sender
+});
+
+/// Combines the key of a node and its inputs with the context it's evaluated with, which together determine its output.
+fn entry_key(node_key: u64, context: &Context) -> u64 {
+	MemoHash::new((DISK_CACHE_FORMAT_VERSION, env!("CARGO_PKG_VERSION"), node_key, context)).hash_code()
+}
+
+/// A wrapper around a node which returns its output from the [`DiskCache`] when it's been stored before.
+struct DiskCachedNode {
+	node: TypeErasedBox<'static>,
+	cache: Arc<DiskCache>,
+	node_key: u64,
+	output_type: TypeId,
+}
+
+impl<'i> Node<'i, Any<'i>> for DiskCachedNode {
+	type Output = FutureAny<'i>;
+
+	fn eval(&'i self, input: Any<'i>) -> Self::Output {
+		if DynAny::type_id(input.as_ref()) != TypeId::of::<Context>() {
+			return self.node.eval(input);
+		}
+		let context = *dyn_any::downcast::<Context>(input).unwrap();
+
+		Box::pin(async move {
+			let key = entry_key(self.node_key, &context);
+
+			if let Some(output) = self.cache.load(key).map(TaggedValue::to_dynany)
+				&& DynAny::type_id(output.as_ref()) == self.output_type
+			{
+				self.cache.hits.fetch_add(1, Ordering::Relaxed);
+				return output;
+			}
+			self.cache.misses.fetch_add(1, Ordering::Relaxed);
+
+			let output = self.node.eval(Box::new(context)).await;
+			if DynAny::type_id(output.as_ref()) != self.output_type {
+				return output;
+			}
+			let Ok(value) = TaggedValue::try_from_any(output) else {
+				unreachable!("The output type was checked to be representable as a tagged value")
+			};
+			// Copying the output is much cheaper than serializing it, which is left to the writer thread
+			self.cache.store(key, value.clone());
+			value.to_dynany()
+		})
+	}
+
+	fn reset(&self) {
+		self.node.reset();
+	}
+
+	fn node_name(&self) -> &'static str {
+		self.node.node_name()
+	}
+
+	fn serialize(&self) -> Option<Arc<dyn std::any::Any + Send + Sync>> {
+		self.node.serialize()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn temporary_directory(name: &str) -> PathBuf {
+		let directory = std::env::temp_dir().join(format!("graphene-disk-cache-test-{name}-{}", std::process::id()));
+		let _ = std::fs::remove_dir_all(&directory);
+		directory
+	}
+
+	#[test]
+	fn stores_and_evicts_entries() {
+		let directory = temporary_directory("evict");
+		let cache = DiskCache::open(&directory, DISK_CACHE_LIMIT_DEFAULT).unwrap();
+
+		cache.write(1, &TaggedValue::String("first".into()));
+		cache.write(2, &TaggedValue::String("second".into()));
+		assert_eq!(cache.load(1), Some(TaggedValue::String("first".into())));
+		assert_eq!(cache.load(3), None);
+		assert_eq!(cache.stats().entries, 2);
+
+		// Entries from previous sessions are picked up when reopening the cache
+		let cache = DiskCache::open(&directory, DISK_CACHE_LIMIT_DEFAULT).unwrap();
+		assert_eq!(cache.load(2), Some(TaggedValue::String("second".into())));
+
+		// Shrinking the limit deletes the least recently used entry
+		cache.set_limit(cache.stats().used - 1);
+		let stats = cache.stats();
+		assert_eq!(stats.entries, 1);
+		assert_eq!(stats.evictions, 1);
+		assert_eq!(cache.load(1), None);
+		assert!(cache.load(2).is_some());
+
+		cache.clear().unwrap();
+		assert_eq!(cache.stats().used, 0);
+		std::fs::remove_dir_all(directory).unwrap();
+	}
+
+	#[test]
+	fn writes_queued_outputs_in_background() {
+		let directory = temporary_directory("queue");
+		let cache = Arc::new(DiskCache::open(&directory, DISK_CACHE_LIMIT_DEFAULT).unwrap());
+
+		let value = TaggedValue::VecF64(vec![1., 2., 3.]);
+		cache.store(1, value.clone());
+		cache.flush();
+		assert_eq!(cache.stats().writes, 1);
+		assert_eq!(cache.load(1), Some(value));
+
+		std::fs::remove_dir_all(directory).unwrap();
+	}
+
+	#[test]
+	fn queues_each_output_once() {
+		let directory = temporary_directory("dedupe");
+		let cache = Arc::new(DiskCache::open(&directory, DISK_CACHE_LIMIT_DEFAULT).unwrap());
+
+		// An output which is already waiting to be written isn't queued a second time
+		cache.pending_writes.lock().unwrap().insert(1);
+		cache.store(1, TaggedValue::F64(1.));
+		cache.finish_write(1);
+		cache.flush();
+		assert_eq!(cache.stats().writes, 0);
+		assert_eq!(cache.load(1), None);
+
+		std::fs::remove_dir_all(directory).unwrap();
+	}
+}
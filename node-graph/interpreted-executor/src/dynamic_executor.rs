@@ -1,4 +1,7 @@
+#[cfg(not(target_family = "wasm"))]
+use crate::disk_cache::DiskCache;
 use crate::node_registry;
+use crate::profiling::{NodeProfileReport, NodeProfiler};
 use dyn_any::StaticType;
 use graph_craft::Type;
 use graph_craft::document::NodeId;
@@ -65,7 +68,8 @@ impl DynamicExecutor {
 	/// Updates the existing [`BorrowTree`] to reflect the new [`ProtoNetwork`], reusing nodes where possible.
 	#[cfg_attr(debug_assertions, inline(never))]
 	pub async fn update(&mut self, proto_network: ProtoNetwork) -> Result<ResolvedDocumentNodeTypesDelta, (ResolvedDocumentNodeTypesDelta, GraphErrors)> {
-		self.output = proto_network.output;
+		// The output is only replaced once the new network is in the tree, so a network failing to update leaves the previous one evaluable
+		let output = proto_network.output;
 		self.typing_context.update(&proto_network).map_err(|e| {
 			// If there is an error then get types that have been resolved before the error
 			let add = proto_network
@@ -92,6 +96,7 @@ impl DynamicExecutor {
 			.update(proto_network, &self.typing_context)
 			.await
 			.map_err(|e| (ResolvedDocumentNodeTypesDelta::default(), e))?;
+		self.output = output;
 		let old_to_remove = core::mem::replace(&mut self.orphaned_nodes, orphaned);
 		let mut remove = Vec::with_capacity(old_to_remove.len() - self.orphaned_nodes.len().min(old_to_remove.len()));
 		for node_id in old_to_remove {
@@ -112,6 +117,57 @@ impl DynamicExecutor {
 		self.tree.introspect(node_path)
 	}
 
+	/// Enables or disables recording the call counts, times, and cache hits of every node.
+	///
+	/// The nodes need to be wrapped to be profiled, so changing this clears the tree and the next [`update`](DynamicExecutor::update) rebuilds all of its nodes.
+	pub fn set_profiling(&mut self, enabled: bool) {
+		if enabled == self.tree.profiler.is_some() {
+			return;
+		}
+
+		*self = Self {
+			tree: BorrowTree {
+				profiler: enabled.then(Default::default),
+				#[cfg(not(target_family = "wasm"))]
+				disk_cache: self.tree.disk_cache.take(),
+				..Default::default()
+			},
+			..Default::default()
+		};
+	}
+
+	/// Sets the on-disk cache which stores the outputs of the nodes marked `disk_cache`, or disables it with `None`.
+	///
+	/// Like with [`set_profiling`](DynamicExecutor::set_profiling), this clears the tree so the next [`update`](DynamicExecutor::update) rebuilds all of its nodes.
+	#[cfg(not(target_family = "wasm"))]
+	pub fn set_disk_cache(&mut self, disk_cache: Option<Arc<DiskCache>>) {
+		*self = Self {
+			tree: BorrowTree {
+				profiler: self.tree.profiler.take(),
+				disk_cache,
+				..Default::default()
+			},
+			..Default::default()
+		};
+	}
+
+	#[cfg(not(target_family = "wasm"))]
+	pub fn disk_cache(&self) -> Option<&Arc<DiskCache>> {
+		self.tree.disk_cache.as_ref()
+	}
+
+	/// The statistics of each node's executions since the profile was last reset, if profiling is enabled.
+	pub fn profile_report(&self) -> Option<NodeProfileReport> {
+		self.tree.profiler.as_ref().map(|profiler| profiler.report())
+	}
+
+	/// Starts a new profile, so the next report only covers the executions after this point.
+	pub fn reset_profile(&self) {
+		if let Some(profiler) = &self.tree.profiler {
+			profiler.reset();
+		}
+	}
+
 	pub fn input_type(&self) -> Option<Type> {
 		self.typing_context.type_of(self.output).map(|node_io| node_io.call_argument.clone())
 	}
@@ -201,6 +257,14 @@ pub struct BorrowTree {
 	nodes: HashMap<NodeId, (SharedNodeContainer, Path)>,
 	/// A hashmap from the document path to the proto node ID.
 	source_map: HashMap<Path, (NodeId, NodeTypes)>,
+	/// Records the executions of every node when profiling is enabled.
+	profiler: Option<Arc<NodeProfiler>>,
+	/// Persists the outputs of the nodes marked `disk_cache` when enabled.
+	#[cfg(not(target_family = "wasm"))]
+	disk_cache: Option<Arc<DiskCache>>,
+	/// The hash of each node's identifier and inputs, which (unlike the node IDs) stays the same across sessions and so addresses the outputs in the [`DiskCache`].
+	#[cfg(not(target_family = "wasm"))]
+	disk_cache_keys: HashMap<NodeId, u64>,
 }
 
 impl BorrowTree {
@@ -233,8 +297,40 @@ impl BorrowTree {
 		nodes.iter().map(|node| self.nodes.get(node).unwrap().0.clone()).collect()
 	}
 
-	fn store_node(&mut self, node: SharedNodeContainer, id: NodeId, path: Path) {
-		self.nodes.insert(id, (node, path));
+	// The typing context is only needed to find the nodes whose outputs can be stored in the disk cache
+	#[cfg_attr(target_family = "wasm", expect(unused_variables))]
+	fn store_node(&mut self, node: TypeErasedBox<'static>, id: NodeId, path: Path, proto_node: &ProtoNode, typing_context: &TypingContext) {
+		#[cfg(not(target_family = "wasm"))]
+		let node = match &self.disk_cache {
+			Some(disk_cache) => {
+				let key = self.disk_cache_key(proto_node);
+				self.disk_cache_keys.insert(id, key);
+				match disk_cached_output_type(proto_node, typing_context, id) {
+					Some(output_type) => disk_cache.wrap(key, output_type, node),
+					None => node,
+				}
+			}
+			None => node,
+		};
+		let node = match &self.profiler {
+			Some(profiler) => profiler.instrument(id, &path, &proto_node.identifier, node),
+			None => node,
+		};
+		self.nodes.insert(id, (NodeContainer::new(node), path));
+	}
+
+	/// The [`MemoHash`](core_types::memo::MemoHash) of the node's identifier and call argument along with the memoized hash of its input value, or the keys of its input nodes.
+	#[cfg(not(target_family = "wasm"))]
+	fn disk_cache_key(&self, proto_node: &ProtoNode) -> u64 {
+		let inputs = match &proto_node.construction_args {
+			// The editor API is hashed by its address in memory, which differs between sessions
+			ConstructionArgs::Value(value) if matches!(**value, TaggedValue::EditorApi(_)) => Vec::new(),
+			ConstructionArgs::Value(value) => vec![Some(value.hash_code())],
+			ConstructionArgs::Nodes(ids) => ids.iter().map(|id| self.disk_cache_keys.get(id).copied()).collect(),
+			ConstructionArgs::Inline(_) => Vec::new(),
+		};
+
+		core_types::memo::MemoHash::new((proto_node.identifier.as_str(), &proto_node.call_argument, inputs)).hash_code()
 	}
 
 	/// Calls the `Node::serialize` for that specific node, returning for example the cached value for a monitor node. The node path must match the document node path.
@@ -326,6 +422,11 @@ impl BorrowTree {
 	/// - Returns `None` if the node is not found in the `nodes` HashMap.
 	pub fn free_node(&mut self, id: NodeId) -> Option<Path> {
 		let (_, path) = self.nodes.remove(&id)?;
+		if let Some(profiler) = &self.profiler {
+			profiler.remove(id);
+		}
+		#[cfg(not(target_family = "wasm"))]
+		self.disk_cache_keys.remove(&id);
 		if self.source_map.get(&path)?.0 == id {
 			self.source_map.remove(&path);
 			return Some(path);
@@ -401,14 +502,12 @@ impl BorrowTree {
 			ConstructionArgs::Value(value) => {
 				let node = if let TaggedValue::EditorApi(api) = &**value {
 					let editor_api = UpcastAsRefNode::new(api.clone());
-					let node = Box::new(editor_api) as TypeErasedBox<'_>;
-					NodeContainer::new(node)
+					Box::new(editor_api) as TypeErasedBox<'_>
 				} else {
 					let upcasted = UpcastNode::new(value.to_owned());
-					let node = Box::new(upcasted) as TypeErasedBox<'_>;
-					NodeContainer::new(node)
+					Box::new(upcasted) as TypeErasedBox<'_>
 				};
-				self.store_node(node, id, path.into());
+				self.store_node(node, id, path.into(), &proto_node, typing_context);
 			}
 			ConstructionArgs::Inline(_) => unimplemented!("Inline nodes are not supported yet"),
 			ConstructionArgs::Nodes(ids) => {
@@ -416,8 +515,7 @@ impl BorrowTree {
 				let construction_nodes = self.node_deps(&ids);
 				let constructor = typing_context.constructor(id).ok_or_else(|| vec![GraphError::new(&proto_node, GraphErrorType::NoConstructor)])?;
 				let node = constructor(construction_nodes).await;
-				let node = NodeContainer::new(node);
-				self.store_node(node, id, path.into());
+				self.store_node(node, id, path.into(), &proto_node, typing_context);
 			}
 		};
 		Ok(())
@@ -429,6 +527,19 @@ impl BorrowTree {
 	}
 }
 
+/// The type of the node's output if its outputs should be stored in the [`DiskCache`], which requires it to be marked `disk_cache`, to be called with a [`Context`](graphene_std::Context), and to return a type that can be serialized as a [`TaggedValue`].
+#[cfg(not(target_family = "wasm"))]
+fn disk_cached_output_type(proto_node: &ProtoNode, typing_context: &TypingContext, id: NodeId) -> Option<std::any::TypeId> {
+	let node_metadata = graphene_std::registry::NODE_METADATA.lock().unwrap();
+	if !node_metadata.get(&proto_node.identifier).is_some_and(|metadata| metadata.disk_cache) || proto_node.call_argument != graph_craft::concrete!(graphene_std::Context) {
+		return None;
+	}
+
+	let node_io = typing_context.type_of(id)?;
+	let output = TaggedValue::from_type(&node_io.return_value)?.to_dynany();
+	Some(dyn_any::DynAny::type_id(output.as_ref()))
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -445,4 +556,29 @@ mod test {
 		let result = futures::executor::block_on(tree.eval(NodeId(0), ()));
 		assert_eq!(result, Some(2u32));
 	}
+
+	#[test]
+	fn profile_node_calls() {
+		let mut tree = BorrowTree {
+			profiler: Some(Default::default()),
+			..Default::default()
+		};
+		let val_1_protonode = ProtoNode::value(ConstructionArgs::Value(TaggedValue::U32(2u32).into()), vec![NodeId(5)]);
+		let context = TypingContext::default();
+		futures::executor::block_on(tree.push_node(NodeId(0), val_1_protonode, &context)).unwrap();
+		for _ in 0..3 {
+			let result = futures::executor::block_on(tree.eval(NodeId(0), ()));
+			assert_eq!(result, Some(2u32));
+		}
+
+		let profiler = tree.profiler.as_ref().unwrap();
+		let report = profiler.report();
+		assert_eq!(report.nodes.len(), 1);
+		assert_eq!(report.nodes[0].path, vec![NodeId(5)]);
+		assert_eq!(report.nodes[0].calls, 3);
+		assert_eq!(report.nodes[0].cache_hit_rate(), None);
+
+		profiler.reset();
+		assert!(profiler.report().nodes.is_empty());
+	}
 }
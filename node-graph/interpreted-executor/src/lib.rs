@@ -1,5 +1,8 @@
+#[cfg(not(target_family = "wasm"))]
+pub mod disk_cache;
 pub mod dynamic_executor;
 pub mod node_registry;
+pub mod profiling;
 pub mod util;
 
 #[cfg(test)]
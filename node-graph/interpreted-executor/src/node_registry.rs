@@ -7,6 +7,7 @@ use graphene_std::any::DynAnyNode;
 use graphene_std::application_io::{ImageTexture, SurfaceFrame};
 use graphene_std::brush::brush_cache::BrushCache;
 use graphene_std::brush::brush_stroke::BrushStroke;
+use graphene_std::brush::clone_stamp::CloneStroke;
 use graphene_std::gradient::GradientStops;
 #[cfg(feature = "gpu")]
 use graphene_std::raster::GPU;
@@ -108,6 +109,11 @@ fn node_registry() -> HashMap<ProtoNodeIdentifier, HashMap<NodeIOTypes, NodeCons
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => Graphic]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::text::Font]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => Vec<BrushStroke>]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => Vec<CloneStroke>]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::healing::HealStroke>]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::healing::PatchRegion>]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::selection::SelectionStep>]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::selection::SelectionFill>]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => BrushCache]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => DocumentNode]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster::curve::Curve]),
@@ -125,6 +131,13 @@ fn node_registry() -> HashMap<ProtoNodeIdentifier, HashMap<NodeIOTypes, NodeCons
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster::adjustments::DomainWarpType]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster::adjustments::RelativeAbsolute]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster::adjustments::SelectiveColorChoice]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster::filter::BokehShape]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster::morphology::MorphologyOperation]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster::morphology::MorphologyShape]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster::morphology::MaskChannel]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster::image_statistics::HistogramChannel]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster_types::color_management::ColorSpace]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::raster_types::color_management::RenderingIntent]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::GridType]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::ArcType]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::MergeByDistanceAlgorithm]),
@@ -132,6 +145,8 @@ fn node_registry() -> HashMap<ProtoNodeIdentifier, HashMap<NodeIOTypes, NodeCons
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::PointSpacingType]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::vector::style::FillType]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::vector::style::GradientType]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::vector::style::ColorModel]),
+		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::vector::style::ColorComponent]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::transform::ReferencePoint]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::CentroidType]),
 		async_node!(graphene_core::memo::MonitorNode<_, _, _>, input: Context, fn_params: [Context => graphene_std::text::TextAlign]),
@@ -192,6 +207,11 @@ fn node_registry() -> HashMap<ProtoNodeIdentifier, HashMap<NodeIOTypes, NodeCons
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::Gradient]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::text::Font]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => Vec<BrushStroke>]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => Vec<CloneStroke>]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::healing::HealStroke>]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::healing::PatchRegion>]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::selection::SelectionStep>]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::selection::SelectionFill>]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => BrushCache]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => DocumentNode]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::ContextFeatures]),
@@ -212,6 +232,13 @@ fn node_registry() -> HashMap<ProtoNodeIdentifier, HashMap<NodeIOTypes, NodeCons
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::DomainWarpType]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::RelativeAbsolute]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::SelectiveColorChoice]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::filter::BokehShape]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::morphology::MorphologyOperation]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::morphology::MorphologyShape]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::morphology::MaskChannel]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::image_statistics::HistogramChannel]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster_types::color_management::ColorSpace]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster_types::color_management::RenderingIntent]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::GridType]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::ArcType]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::MergeByDistanceAlgorithm]),
@@ -223,11 +250,111 @@ fn node_registry() -> HashMap<ProtoNodeIdentifier, HashMap<NodeIOTypes, NodeCons
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::PaintOrder]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::FillType]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::GradientType]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::ColorModel]),
+		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::ColorComponent]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::transform::ReferencePoint]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::CentroidType]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => path_bool_nodes::BooleanOperation]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::text::TextAlign]),
 		async_node!(graphene_core::memo::MemoNode<_, _>, input: Context, fn_params: [Context => RenderIntermediate]),
+		// ==============
+		// LRU MEMO NODES
+		// ==============
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => ()]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => bool]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Table<Artboard>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Table<Graphic>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Table<Vector>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Table<Raster<CPU>>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Table<Color>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Image<Color>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Table<GradientStops>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => GradientStops]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<DVec2>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<NodeId>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<f64>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<f32>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<String>]),
+		#[cfg(feature = "gpu")]
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Arc<WasmSurfaceHandle>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => WindowHandle]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Option<WgpuSurface>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => SurfaceFrame]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => f64]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => f32]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => u32]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => u64]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => DVec2]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => String]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => DAffine2]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Footprint]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => RenderOutput]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => &WasmEditorApi]),
+		#[cfg(feature = "gpu")]
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => WgpuSurface]),
+		#[cfg(feature = "gpu")]
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Table<Raster<GPU>>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Option<f64>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Color]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Option<Color>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => [f64; 4]]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Graphic]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => glam::f32::Vec2]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => glam::f32::Affine2]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::Stroke]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::Gradient]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::text::Font]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<BrushStroke>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<CloneStroke>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::healing::HealStroke>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::healing::PatchRegion>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::selection::SelectionStep>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Vec<graphene_std::raster::selection::SelectionFill>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => BrushCache]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => DocumentNode]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::ContextFeatures]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::curve::Curve]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::transform::Footprint]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => Box<graphene_std::vector::VectorModification>]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::Fill]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::blending::BlendMode]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::LuminanceCalculation]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::extract_xy::XY]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::RedGreenBlue]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::RedGreenBlueAlpha]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::animation::RealTimeMode]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::NoiseType]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::FractalType]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::CellularDistanceFunction]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::CellularReturnType]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::DomainWarpType]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::RelativeAbsolute]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::SelectiveColorChoice]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::filter::BokehShape]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::morphology::MorphologyOperation]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::morphology::MorphologyShape]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::morphology::MaskChannel]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster::image_statistics::HistogramChannel]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster_types::color_management::ColorSpace]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::raster_types::color_management::RenderingIntent]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::GridType]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::ArcType]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::MergeByDistanceAlgorithm]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::ExtrudeJoiningAlgorithm]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::PointSpacingType]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::StrokeCap]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::StrokeJoin]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::StrokeAlign]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::PaintOrder]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::FillType]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::GradientType]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::ColorModel]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::style::ColorComponent]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::transform::ReferencePoint]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::vector::misc::CentroidType]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => path_bool_nodes::BooleanOperation]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => graphene_std::text::TextAlign]),
+		async_node!(graphene_core::memo::LruMemoNode<_, _>, input: Context, fn_params: [Context => RenderIntermediate]),
 	];
 	// =============
 	// CONVERT NODES
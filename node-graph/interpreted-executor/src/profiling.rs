@@ -0,0 +1,306 @@
+use core_types::Node;
+use graph_craft::ProtoNodeIdentifier;
+use graph_craft::document::NodeId;
+use graph_craft::proto::{Any, FutureAny, TypeErasedBox};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// Collects the execution statistics of the nodes in a [`BorrowTree`](crate::dynamic_executor::BorrowTree) while profiling is enabled.
+#[derive(Debug, Default)]
+pub struct NodeProfiler {
+	nodes: Mutex<HashMap<NodeId, ProfiledNodeInfo>>,
+}
+
+#[derive(Debug)]
+struct ProfiledNodeInfo {
+	path: Box<[NodeId]>,
+	identifier: String,
+	counters: Arc<NodeCounters>,
+}
+
+/// The counters of a single proto node, which are updated from the evaluations of its [`ProfiledNode`] wrapper.
+#[derive(Debug, Default)]
+struct NodeCounters {
+	calls: AtomicU64,
+	cumulative_nanoseconds: AtomicU64,
+	self_nanoseconds: AtomicU64,
+	cache_lookups: AtomicU64,
+	cache_hits: AtomicU64,
+}
+
+impl NodeCounters {
+	fn reset(&self) {
+		for counter in [&self.calls, &self.cumulative_nanoseconds, &self.self_nanoseconds, &self.cache_lookups, &self.cache_hits] {
+			counter.store(0, Ordering::Relaxed);
+		}
+	}
+}
+
+impl NodeProfiler {
+	/// Wraps the node so its evaluations are recorded under the given ID and document path.
+	pub(crate) fn instrument(&self, id: NodeId, path: &[NodeId], identifier: &ProtoNodeIdentifier, node: TypeErasedBox<'static>) -> TypeErasedBox<'static> {
+		let counters = Arc::new(NodeCounters::default());
+		let caching = *identifier == graphene_core::memo::memo::IDENTIFIER || *identifier == graphene_core::memo::lru_memo::IDENTIFIER;
+		let info = ProfiledNodeInfo {
+			path: path.into(),
+			identifier: identifier.as_str().to_string(),
+			counters: counters.clone(),
+		};
+		self.nodes.lock().unwrap().insert(id, info);
+
+		Box::new(ProfiledNode { node, counters, caching })
+	}
+
+	/// Stops reporting the statistics of a node which was removed from the tree.
+	pub(crate) fn remove(&self, id: NodeId) {
+		self.nodes.lock().unwrap().remove(&id);
+	}
+
+	/// Sets the statistics of every node back to zero, so the next report only covers the executions after this point.
+	pub fn reset(&self) {
+		for info in self.nodes.lock().unwrap().values() {
+			info.counters.reset();
+		}
+	}
+
+	/// The statistics of every node which was called since the profiler was last reset.
+	pub fn report(&self) -> NodeProfileReport {
+		let nodes = self.nodes.lock().unwrap();
+
+		let mut nodes = nodes
+			.values()
+			.filter(|info| info.counters.calls.load(Ordering::Relaxed) > 0)
+			.map(|info| {
+				let counters = &info.counters;
+				NodeProfile {
+					path: info.path.to_vec(),
+					identifier: info.identifier.clone(),
+					calls: counters.calls.load(Ordering::Relaxed),
+					cumulative_milliseconds: counters.cumulative_nanoseconds.load(Ordering::Relaxed) as f64 / 1_000_000.,
+					self_milliseconds: counters.self_nanoseconds.load(Ordering::Relaxed) as f64 / 1_000_000.,
+					cache_lookups: counters.cache_lookups.load(Ordering::Relaxed),
+					cache_hits: counters.cache_hits.load(Ordering::Relaxed),
+				}
+			})
+			.collect::<Vec<_>>();
+		nodes.sort_by(|a, b| b.self_milliseconds.total_cmp(&a.self_milliseconds));
+
+		NodeProfileReport { nodes }
+	}
+}
+
+/// The execution statistics of a proto node, keyed by the path of the document node it was compiled from.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NodeProfile {
+	pub path: Vec<NodeId>,
+	pub identifier: String,
+	/// How many times the node was evaluated.
+	pub calls: u64,
+	/// The time spent evaluating the node, including the time spent evaluating its inputs.
+	pub cumulative_milliseconds: f64,
+	/// The time spent evaluating the node, excluding the time spent evaluating its inputs.
+	pub self_milliseconds: f64,
+	/// How many evaluations of a caching node looked up its cache.
+	pub cache_lookups: u64,
+	/// How many of those lookups returned the cached value without evaluating the node's input.
+	pub cache_hits: u64,
+}
+
+impl NodeProfile {
+	/// The fraction of cache lookups that were hits, if this is a caching node that was looked up.
+	pub fn cache_hit_rate(&self) -> Option<f64> {
+		(self.cache_lookups > 0).then(|| self.cache_hits as f64 / self.cache_lookups as f64)
+	}
+}
+
+/// The execution statistics of every profiled node, sorted from the most to the least self time.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NodeProfileReport {
+	pub nodes: Vec<NodeProfile>,
+}
+
+impl NodeProfileReport {
+	/// Combines the statistics of all proto nodes compiled from the document node at the given path, including those of its nested nodes.
+	///
+	/// The self times are summed, while the call count and cumulative time come from the outermost proto node, which is the one with the most cumulative time.
+	pub fn document_node(&self, path: &[NodeId]) -> Option<NodeProfile> {
+		let mut nodes = self.nodes.iter().filter(|node| node.path.starts_with(path)).peekable();
+		nodes.peek()?;
+
+		let mut combined = NodeProfile {
+			path: path.to_vec(),
+			..Default::default()
+		};
+		for node in nodes {
+			if node.cumulative_milliseconds >= combined.cumulative_milliseconds {
+				combined.identifier.clone_from(&node.identifier);
+				combined.calls = node.calls;
+				combined.cumulative_milliseconds = node.cumulative_milliseconds;
+			}
+			combined.self_milliseconds += node.self_milliseconds;
+			combined.cache_lookups += node.cache_lookups;
+			combined.cache_hits += node.cache_hits;
+		}
+
+		Some(combined)
+	}
+
+	/// The total time spent evaluating the profiled nodes.
+	pub fn total_milliseconds(&self) -> f64 {
+		self.nodes.iter().map(|node| node.self_milliseconds).sum()
+	}
+}
+
+/// A wrapper around a node which records its evaluations into its [`NodeCounters`].
+struct ProfiledNode {
+	node: TypeErasedBox<'static>,
+	counters: Arc<NodeCounters>,
+	/// Whether this is a memo node, whose evaluations are cache hits when they don't evaluate the input.
+	caching: bool,
+}
+
+impl<'i> Node<'i, Any<'i>> for ProfiledNode {
+	type Output = FutureAny<'i>;
+
+	fn eval(&'i self, input: Any<'i>) -> Self::Output {
+		self.counters.calls.fetch_add(1, Ordering::Relaxed);
+
+		// Nodes evaluate their inputs while their own future is being polled, so the innermost poll in progress on this thread belongs to the node using this one as an input
+		let parent = POLL_STACK.with_borrow(|stack| stack.last().cloned());
+
+		Box::pin(ProfiledFuture {
+			future: self.node.eval(input),
+			counters: &self.counters,
+			caching: self.caching,
+			frame: Arc::default(),
+			parent,
+		})
+	}
+
+	fn reset(&self) {
+		self.node.reset();
+	}
+
+	fn node_name(&self) -> &'static str {
+		self.node.node_name()
+	}
+
+	fn serialize(&self) -> Option<Arc<dyn std::any::Any + Send + Sync>> {
+		self.node.serialize()
+	}
+}
+
+/// Measures the time spent polling the future of a node's evaluation.
+///
+/// Each input's future reports its poll times to the evaluation of the node using it, even when it's polled on another thread, which tells apart the node's own time from the time of its inputs.
+/// When inputs are evaluated in parallel, their combined time can exceed the time the node spent waiting for them, in which case the node's self time is clamped to zero.
+struct ProfiledFuture<'i> {
+	future: FutureAny<'i>,
+	counters: &'i NodeCounters,
+	caching: bool,
+	/// The time spent on the inputs of this evaluation, reported by their futures.
+	frame: Arc<EvaluationFrame>,
+	/// The evaluation of the node which this one is an input of, which was being polled on the thread that created this future.
+	parent: Option<Arc<EvaluationFrame>>,
+}
+
+/// The time which the inputs of a profiled node's evaluation have reported, shared with their futures across threads.
+#[derive(Default)]
+struct EvaluationFrame {
+	/// The time spent polling the futures of the node's inputs.
+	input_nanoseconds: AtomicU64,
+	/// Whether the future of any input was polled.
+	evaluated_inputs: AtomicBool,
+}
+
+thread_local! {
+	/// The evaluations whose futures are being polled on this thread, from the outermost to the innermost.
+	static POLL_STACK: RefCell<Vec<Arc<EvaluationFrame>>> = const { RefCell::new(Vec::new()) };
+}
+
+impl<'i> Future for ProfiledFuture<'i> {
+	type Output = Any<'i>;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		// Polls nested in another profiled poll on this thread belong to that node, while a future polled on its own, such as on another thread, reports to the node it was created for
+		let parent = POLL_STACK.with_borrow_mut(|stack| {
+			let parent = stack.last().cloned().or_else(|| self.parent.clone());
+			stack.push(self.frame.clone());
+			parent
+		});
+		if let Some(parent) = &parent {
+			parent.evaluated_inputs.store(true, Ordering::Relaxed);
+		}
+
+		let input_nanoseconds_before = self.frame.input_nanoseconds.load(Ordering::Relaxed);
+		let start = now_nanoseconds();
+		let result = self.future.as_mut().poll(cx);
+		let elapsed = now_nanoseconds().saturating_sub(start);
+		let input_nanoseconds = self.frame.input_nanoseconds.load(Ordering::Relaxed).saturating_sub(input_nanoseconds_before);
+
+		POLL_STACK.with_borrow_mut(|stack| stack.pop());
+		if let Some(parent) = &parent {
+			parent.input_nanoseconds.fetch_add(elapsed, Ordering::Relaxed);
+		}
+
+		self.counters.cumulative_nanoseconds.fetch_add(elapsed, Ordering::Relaxed);
+		self.counters.self_nanoseconds.fetch_add(elapsed.saturating_sub(input_nanoseconds), Ordering::Relaxed);
+
+		if result.is_ready() && self.caching {
+			self.counters.cache_lookups.fetch_add(1, Ordering::Relaxed);
+			if !self.frame.evaluated_inputs.load(Ordering::Relaxed) {
+				self.counters.cache_hits.fetch_add(1, Ordering::Relaxed);
+			}
+		}
+
+		result
+	}
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn now_nanoseconds() -> u64 {
+	static START: std::sync::LazyLock<std::time::Instant> = std::sync::LazyLock::new(std::time::Instant::now);
+	START.elapsed().as_nanos() as u64
+}
+
+#[cfg(target_family = "wasm")]
+fn now_nanoseconds() -> u64 {
+	// `std::time::Instant` isn't available in the browser, so the high resolution timer of the page is used instead
+	let milliseconds = web_sys::window().and_then(|window| window.performance()).map_or(0., |performance| performance.now());
+	(milliseconds * 1_000_000.) as u64
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn profile(path: &[u64], calls: u64, cumulative_milliseconds: f64, self_milliseconds: f64) -> NodeProfile {
+		NodeProfile {
+			path: path.iter().copied().map(NodeId).collect(),
+			identifier: String::new(),
+			calls,
+			cumulative_milliseconds,
+			self_milliseconds,
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn combines_nested_proto_nodes() {
+		let report = NodeProfileReport {
+			nodes: vec![profile(&[1, 2], 3, 5., 4.), profile(&[1, 3], 1, 8., 3.), profile(&[4], 2, 1., 1.)],
+		};
+
+		let combined = report.document_node(&[NodeId(1)]).unwrap();
+		assert_eq!(combined.calls, 1);
+		assert_eq!(combined.cumulative_milliseconds, 8.);
+		assert_eq!(combined.self_milliseconds, 7.);
+
+		assert!(report.document_node(&[NodeId(5)]).is_none());
+		assert_eq!(report.total_milliseconds(), 8.);
+	}
+}
@@ -0,0 +1,91 @@
+use std::hash::{Hash, Hasher};
+
+/// The image formats that documents can be exported to.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum FileType {
+	#[default]
+	Png,
+	Jpg,
+	Svg,
+}
+
+impl FileType {
+	pub fn from_extension(extension: &str) -> Option<Self> {
+		match extension {
+			"png" => Some(FileType::Png),
+			"jpg" | "jpeg" => Some(FileType::Jpg),
+			"svg" => Some(FileType::Svg),
+			_ => None,
+		}
+	}
+
+	pub fn to_mime(self) -> &'static str {
+		match self {
+			FileType::Png => "image/png",
+			FileType::Jpg => "image/jpeg",
+			FileType::Svg => "image/svg+xml",
+		}
+	}
+
+	pub fn extension(self) -> &'static str {
+		match self {
+			FileType::Png => "png",
+			FileType::Jpg => "jpg",
+			FileType::Svg => "svg",
+		}
+	}
+}
+
+/// Settings saved with a layer, artboard, or slice describing the files it's exported to by Export All in the editor and by the command line exporter.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ExportPreset {
+	pub file_type: FileType,
+	/// A separate file is exported at each of these scale factors, such as 1x, 2x, and 3x.
+	pub scales: Vec<f64>,
+	/// Appended to the name of the exported item to form the file name.
+	pub suffix: String,
+	pub transparent_background: bool,
+}
+
+impl Default for ExportPreset {
+	fn default() -> Self {
+		Self {
+			file_type: FileType::Png,
+			scales: vec![1.],
+			suffix: String::new(),
+			transparent_background: false,
+		}
+	}
+}
+
+impl Hash for ExportPreset {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.file_type.hash(state);
+		self.scales.iter().map(|scale| scale.to_bits()).collect::<Vec<_>>().hash(state);
+		self.suffix.hash(state);
+		self.transparent_background.hash(state);
+	}
+}
+
+impl ExportPreset {
+	/// The scale factors offered as toggles when editing a preset.
+	pub const SCALE_CHOICES: [f64; 3] = [1., 2., 3.];
+
+	/// The name, without an extension, of the file exported for the item named `item_name` at the given scale. Scales other than 1x are marked like `@2x`.
+	pub fn file_stem(&self, item_name: &str, scale: f64) -> String {
+		let suffix = &self.suffix;
+		if scale == 1. { format!("{item_name}{suffix}") } else { format!("{item_name}{suffix}@{scale}x") }
+	}
+
+	/// The scales which actually produce a file. SVG is resolution independent, so it's only exported once.
+	pub fn export_scales(&self) -> Vec<f64> {
+		if self.file_type == FileType::Svg {
+			return vec![1.];
+		}
+		let mut scales = self.scales.iter().copied().filter(|scale| *scale > 0.).collect::<Vec<_>>();
+		scales.sort_by(f64::total_cmp);
+		scales.dedup();
+		scales
+	}
+}
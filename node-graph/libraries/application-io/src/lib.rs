@@ -1,3 +1,5 @@
+pub mod export_preset;
+
 use core_types::transform::Footprint;
 use dyn_any::{DynAny, StaticType, StaticTypeSized};
 use glam::{DAffine2, DVec2, UVec2};
@@ -8,6 +8,7 @@ pub mod math;
 pub mod memo;
 pub mod misc;
 pub mod ops;
+pub mod parallel;
 pub mod registry;
 pub mod render_complexity;
 pub mod table;
@@ -20,7 +21,7 @@ pub use blending::*;
 pub use color::Color;
 pub use context::*;
 pub use ctor;
-pub use dyn_any::{StaticTypeSized, WasmNotSend, WasmNotSync};
+pub use dyn_any::{StaticType, StaticTypeSized, WasmNotSend, WasmNotSync};
 pub use memo::MemoHash;
 pub use no_std_types::AsU32;
 pub use no_std_types::blending;
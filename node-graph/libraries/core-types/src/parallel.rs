@@ -0,0 +1,152 @@
+//! Concurrent evaluation of independent node inputs.
+//!
+//! On native targets, the futures are first polled in parallel on the threads of a shared pool, which completes the evaluations that only compute without waiting on anything.
+//! Futures which are still pending after that, for example because they wait for a file or a GPU readback, are awaited one after another by the calling task, so its executor drives them instead of a blocked pool thread.
+//! The WASM build always evaluates them one after another on the calling thread. Either way, the outputs are returned in the order of their futures.
+
+use crate::WasmNotSend;
+use std::future::Future;
+
+/// Evaluates the futures, potentially concurrently, and returns their outputs in the same order as the futures.
+///
+/// The futures are collected right away, so the returned future doesn't borrow the iterator.
+pub fn join_all<F>(futures: impl IntoIterator<Item = F>) -> impl Future<Output = Vec<F::Output>> + WasmNotSend
+where
+	F: Future + WasmNotSend,
+	F::Output: WasmNotSend,
+{
+	let futures: Vec<F> = futures.into_iter().collect();
+
+	async move {
+		#[cfg(not(target_family = "wasm"))]
+		if native::worthwhile(futures.len()) {
+			let mut futures: Vec<_> = futures.into_iter().map(Box::pin).collect();
+			let polled = native::poll_all(&mut futures);
+
+			let mut outputs = Vec::with_capacity(futures.len());
+			for (future, output) in futures.into_iter().zip(polled) {
+				outputs.push(match output {
+					Some(output) => output,
+					None => future.await,
+				});
+			}
+			return outputs;
+		}
+
+		let mut outputs = Vec::with_capacity(futures.len());
+		for future in futures {
+			outputs.push(future.await);
+		}
+		outputs
+	}
+}
+
+/// Evaluates both futures, potentially concurrently, and returns their outputs.
+pub async fn join<A, B>(a: A, b: B) -> (A::Output, B::Output)
+where
+	A: Future + WasmNotSend,
+	B: Future + WasmNotSend,
+	A::Output: WasmNotSend,
+	B::Output: WasmNotSend,
+{
+	#[cfg(not(target_family = "wasm"))]
+	if native::worthwhile(2) {
+		let (mut a, mut b) = (Box::pin(a), Box::pin(b));
+		let (a_output, b_output) = native::poll_both(&mut a, &mut b);
+
+		let a = match a_output {
+			Some(output) => output,
+			None => a.await,
+		};
+		let b = match b_output {
+			Some(output) => output,
+			None => b.await,
+		};
+		return (a, b);
+	}
+
+	let a = a.await;
+	let b = b.await;
+	(a, b)
+}
+
+#[cfg(not(target_family = "wasm"))]
+mod native {
+	use rayon::prelude::*;
+	use std::future::Future;
+	use std::pin::Pin;
+	use std::task::{Context, Poll, Waker};
+	use tokio::runtime::Handle;
+
+	/// Whether there are several futures and several threads in the pool to poll them on. Joins nested in a pool thread share the same pool, so they don't oversubscribe the cores.
+	pub(super) fn worthwhile(futures: usize) -> bool {
+		futures > 1 && rayon::current_num_threads() > 1
+	}
+
+	/// Polls every future once on the thread pool, returning the outputs of those which are ready.
+	pub(super) fn poll_all<F: Future + Send>(futures: &mut [Pin<Box<F>>]) -> Vec<Option<F::Output>>
+	where
+		F::Output: Send,
+	{
+		let runtime = Handle::try_current().ok();
+		futures.par_iter_mut().map(|future| poll_once(future, runtime.as_ref())).collect()
+	}
+
+	/// Polls both futures once on the thread pool, returning the outputs of those which are ready.
+	pub(super) fn poll_both<A: Future + Send, B: Future + Send>(a: &mut Pin<Box<A>>, b: &mut Pin<Box<B>>) -> (Option<A::Output>, Option<B::Output>)
+	where
+		A::Output: Send,
+		B::Output: Send,
+	{
+		let runtime = Handle::try_current().ok();
+		rayon::join(|| poll_once(a, runtime.as_ref()), || poll_once(b, runtime.as_ref()))
+	}
+
+	/// Polls the future within the Tokio runtime of the calling task, if there is one, so futures which rely on it can be created and polled on a pool thread.
+	fn poll_once<F: Future>(future: &mut Pin<Box<F>>, runtime: Option<&Handle>) -> Option<F::Output> {
+		let _runtime = runtime.map(Handle::enter);
+
+		// A pending future is polled again by the calling task with its own waker, so there is nothing to wake here
+		match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+			Poll::Ready(output) => Some(output),
+			Poll::Pending => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn join_all_keeps_order() {
+		let futures = (0..64_u64).map(|i| async move {
+			// Make the earlier futures slower, so they would finish last if the outputs were collected by completion
+			std::thread::sleep(std::time::Duration::from_micros(64 - i));
+			i * 2
+		});
+		let outputs = futures::executor::block_on(join_all(futures));
+
+		assert_eq!(outputs, (0..64).map(|i| i * 2).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn nested_joins() {
+		let outputs = futures::executor::block_on(join(join_all((0..8).map(|i| async move { join(async move { i }, async move { i + 1 }).await })), async { "done" }));
+
+		assert_eq!(outputs.0, (0..8).map(|i| (i, i + 1)).collect::<Vec<_>>());
+		assert_eq!(outputs.1, "done");
+	}
+
+	#[tokio::test]
+	async fn pending_futures_finish_on_calling_task() {
+		let futures = (0..16_u64).map(|i| async move {
+			// Yielding leaves the future pending after its first poll, so the calling task has to finish it
+			tokio::task::yield_now().await;
+			i * 2
+		});
+		let outputs = join_all(futures).await;
+
+		assert_eq!(outputs, (0..16).map(|i| i * 2).collect::<Vec<_>>());
+	}
+}
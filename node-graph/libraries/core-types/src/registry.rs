@@ -16,6 +16,10 @@ pub struct NodeMetadata {
 	pub description: &'static str,
 	pub properties: Option<&'static str>,
 	pub context_features: Vec<ContextFeature>,
+	/// Whether the node's outputs are expensive enough to compute that they should be kept in the on-disk render cache of native builds.
+	pub disk_cache: bool,
+	/// Whether the node is evaluated under alternating contexts often enough that the compiler should cache several of its outputs, in the LRU memo node sharing the node cache memory budget, rather than only the latest one.
+	pub lru_cache: bool,
 }
 
 // Translation struct between macro and definition
@@ -182,6 +186,28 @@ impl<I, O> DowncastBothNode<I, O> {
 		}
 	}
 }
+
+/// Converts the type-erased output of one of the inputs collected by a `#[variadic]` node field.
+///
+/// The inputs of a variadic field are added by the editor, so their types aren't known when the node is compiled, and each output is converted at runtime instead.
+pub trait FromVariadicInput {
+	fn from_variadic_input(output: Any<'_>) -> Self;
+}
+
+/// Evaluates the nodes of a `#[variadic]` node field with the given call argument, converting each of their outputs.
+pub async fn eval_variadic<'n, I, T>(nodes: &'n [SharedNodeContainer], input: I) -> Vec<T>
+where
+	I: 'n + StaticType + Clone + WasmNotSend,
+	T: FromVariadicInput + WasmNotSend,
+{
+	let mut values = Vec::with_capacity(nodes.len());
+	for node in nodes {
+		let output = node.eval(Box::new(input.clone())).await;
+		values.push(T::from_variadic_input(output));
+	}
+	values
+}
+
 pub struct FutureWrapperNode<Node> {
 	node: Node,
 }
@@ -910,6 +910,204 @@ impl Color {
 		[hue, saturation, lightness, self.alpha]
 	}
 
+	/// Create a [Color] from a hue, saturation, value and alpha (all between 0 and 1)
+	///
+	/// # Examples
+	/// ```
+	/// use core_types::color::Color;
+	/// let color = Color::from_hsva(0.5, 0.2, 0.3, 1.);
+	/// ```
+	pub fn from_hsva(hue: f32, saturation: f32, value: f32, alpha: f32) -> Color {
+		let sector = wrap_unit(hue) * 6.;
+		let fraction = sector - sector.floor();
+
+		let p = value * (1. - saturation);
+		let q = value * (1. - saturation * fraction);
+		let t = value * (1. - saturation * (1. - fraction));
+
+		let (red, green, blue) = match sector as u32 {
+			0 => (value, t, p),
+			1 => (q, value, p),
+			2 => (p, value, t),
+			3 => (p, q, value),
+			4 => (t, p, value),
+			_ => (value, p, q),
+		};
+
+		Color { red, green, blue, alpha }
+	}
+
+	/// Convert a [Color] to a hue, saturation, value and alpha (all between 0 and 1)
+	///
+	/// # Examples
+	/// ```
+	/// use core_types::color::Color;
+	/// let color = Color::from_hsva(0.5, 0.2, 0.3, 1.).to_hsva();
+	/// ```
+	pub fn to_hsva(&self) -> [f32; 4] {
+		let min_channel = self.red.min(self.green).min(self.blue);
+		let max_channel = self.red.max(self.green).max(self.blue);
+		let delta = max_channel - min_channel;
+
+		let saturation = if max_channel > 0. { delta / max_channel } else { 0. };
+		let hue = if delta == 0. {
+			0.
+		} else if max_channel == self.red {
+			(self.green - self.blue) / delta
+		} else if max_channel == self.green {
+			2. + (self.blue - self.red) / delta
+		} else {
+			4. + (self.red - self.green) / delta
+		} / 6.;
+
+		[wrap_unit(hue), saturation, max_channel, self.alpha]
+	}
+
+	// https://bottosson.github.io/posts/oklab/
+	/// Create a [Color] from OKLab lightness (between 0 and 1), a and b (roughly between -0.4 and 0.4) and alpha.
+	///
+	/// OKLab is a perceptual color space, so equal steps in it appear as roughly equal changes in color. The result is in linear sRGB and may be out of gamut.
+	///
+	/// # Examples
+	/// ```
+	/// use core_types::color::Color;
+	/// let color = Color::from_oklaba(0.7, 0.1, -0.05, 1.);
+	/// ```
+	pub fn from_oklaba(lightness: f32, a: f32, b: f32, alpha: f32) -> Color {
+		let l = (lightness + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+		let m = (lightness - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+		let s = (lightness - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+
+		Color {
+			red: 4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+			green: -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+			blue: -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+			alpha,
+		}
+	}
+
+	/// Convert a linear sRGB [Color] to OKLab lightness (between 0 and 1), a and b (roughly between -0.4 and 0.4) and alpha.
+	///
+	/// # Examples
+	/// ```
+	/// use core_types::color::Color;
+	/// let [lightness, a, b, alpha] = Color::from_oklaba(0.7, 0.1, -0.05, 1.).to_oklaba();
+	/// ```
+	pub fn to_oklaba(&self) -> [f32; 4] {
+		let l = (0.412_221_46 * self.red + 0.536_332_55 * self.green + 0.051_445_995 * self.blue).cbrt();
+		let m = (0.211_903_5 * self.red + 0.680_699_5 * self.green + 0.107_396_96 * self.blue).cbrt();
+		let s = (0.088_302_46 * self.red + 0.281_718_85 * self.green + 0.629_978_7 * self.blue).cbrt();
+
+		[
+			0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+			1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+			0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+			self.alpha,
+		]
+	}
+
+	/// Create a [Color] from OKLCH lightness (between 0 and 1), chroma (roughly between 0 and 0.4), hue (between 0 and 1) and alpha.
+	///
+	/// OKLCH is the cylindrical form of OKLab, which makes it suited to picking hues and generating palettes. The result is in linear sRGB and may be out of gamut.
+	///
+	/// # Examples
+	/// ```
+	/// use core_types::color::Color;
+	/// let color = Color::from_oklcha(0.7, 0.15, 0.4, 1.);
+	/// ```
+	pub fn from_oklcha(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Color {
+		let [a, b] = polar_to_cartesian(chroma, hue);
+		Color::from_oklaba(lightness, a, b, alpha)
+	}
+
+	/// Convert a linear sRGB [Color] to OKLCH lightness (between 0 and 1), chroma (roughly between 0 and 0.4), hue (between 0 and 1) and alpha.
+	///
+	/// # Examples
+	/// ```
+	/// use core_types::color::Color;
+	/// let [lightness, chroma, hue, alpha] = Color::from_oklcha(0.7, 0.15, 0.4, 1.).to_oklcha();
+	/// ```
+	pub fn to_oklcha(&self) -> [f32; 4] {
+		let [lightness, a, b, alpha] = self.to_oklaba();
+		let [chroma, hue] = cartesian_to_polar(a, b);
+		[lightness, chroma, hue, alpha]
+	}
+
+	/// Create a [Color] from CIE L*a*b* lightness (between 0 and 100), a* and b* (roughly between -128 and 128) and alpha, relative to the D65 white point.
+	///
+	/// The result is in linear sRGB and may be out of gamut.
+	///
+	/// # Examples
+	/// ```
+	/// use core_types::color::Color;
+	/// let color = Color::from_laba(60., 20., -30., 1.);
+	/// ```
+	pub fn from_laba(lightness: f32, a: f32, b: f32, alpha: f32) -> Color {
+		let inverse = |t: f32| if t > LAB_EPSILON_ROOT { t.powi(3) } else { 3. * LAB_EPSILON_ROOT.powi(2) * (t - 4. / 29.) };
+
+		let fy = (lightness + 16.) / 116.;
+		let x = D65_WHITE[0] * inverse(fy + a / 500.);
+		let y = D65_WHITE[1] * inverse(fy);
+		let z = D65_WHITE[2] * inverse(fy - b / 200.);
+
+		Color {
+			red: 3.240_454_2 * x - 1.537_138_5 * y - 0.498_531_4 * z,
+			green: -0.969_266 * x + 1.876_010_8 * y + 0.041_556 * z,
+			blue: 0.055_643_4 * x - 0.204_025_9 * y + 1.057_225_2 * z,
+			alpha,
+		}
+	}
+
+	/// Convert a linear sRGB [Color] to CIE L*a*b* lightness (between 0 and 100), a* and b* (roughly between -128 and 128) and alpha, relative to the D65 white point.
+	///
+	/// # Examples
+	/// ```
+	/// use core_types::color::Color;
+	/// let [lightness, a, b, alpha] = Color::from_laba(60., 20., -30., 1.).to_laba();
+	/// ```
+	pub fn to_laba(&self) -> [f32; 4] {
+		let forward = |t: f32| {
+			if t > LAB_EPSILON_ROOT.powi(3) { t.cbrt() } else { t / (3. * LAB_EPSILON_ROOT.powi(2)) + 4. / 29. }
+		};
+
+		let x = 0.412_456_4 * self.red + 0.357_576_1 * self.green + 0.180_437_5 * self.blue;
+		let y = 0.212_672_9 * self.red + 0.715_152_2 * self.green + 0.072_175 * self.blue;
+		let z = 0.019_333_9 * self.red + 0.119_192 * self.green + 0.950_304_1 * self.blue;
+
+		let fx = forward(x / D65_WHITE[0]);
+		let fy = forward(y / D65_WHITE[1]);
+		let fz = forward(z / D65_WHITE[2]);
+
+		[116. * fy - 16., 500. * (fx - fy), 200. * (fy - fz), self.alpha]
+	}
+
+	/// Create a [Color] from CIE LCh lightness (between 0 and 100), chroma (roughly between 0 and 150), hue (between 0 and 1) and alpha, relative to the D65 white point.
+	///
+	/// The result is in linear sRGB and may be out of gamut.
+	///
+	/// # Examples
+	/// ```
+	/// use core_types::color::Color;
+	/// let color = Color::from_lcha(60., 40., 0.7, 1.);
+	/// ```
+	pub fn from_lcha(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Color {
+		let [a, b] = polar_to_cartesian(chroma, hue);
+		Color::from_laba(lightness, a, b, alpha)
+	}
+
+	/// Convert a linear sRGB [Color] to CIE LCh lightness (between 0 and 100), chroma (roughly between 0 and 150), hue (between 0 and 1) and alpha, relative to the D65 white point.
+	///
+	/// # Examples
+	/// ```
+	/// use core_types::color::Color;
+	/// let [lightness, chroma, hue, alpha] = Color::from_lcha(60., 40., 0.7, 1.).to_lcha();
+	/// ```
+	pub fn to_lcha(&self) -> [f32; 4] {
+		let [lightness, a, b, alpha] = self.to_laba();
+		let [chroma, hue] = cartesian_to_polar(a, b);
+		[lightness, chroma, hue, alpha]
+	}
+
 	// TODO: Readd formatting
 
 	/// Creates a color from a 8-character RGBA hex string (without a # prefix).
@@ -1102,6 +1300,32 @@ impl Color {
 	}
 }
 
+/// The XYZ coordinates of the D65 white point, which is the reference white of sRGB.
+const D65_WHITE: [f32; 3] = [0.950_47, 1., 1.088_83];
+
+/// The cube root of the threshold below which the CIE L*a*b* transfer function is linear.
+const LAB_EPSILON_ROOT: f32 = 6. / 29.;
+
+/// Wraps a value (such as a hue) into the range 0 to 1.
+fn wrap_unit(value: f32) -> f32 {
+	#[cfg(feature = "std")]
+	let value = value.rem_euclid(1.);
+	#[cfg(not(feature = "std"))]
+	let value = value.rem_euclid(&1.);
+	value
+}
+
+/// Converts the chroma and hue (between 0 and 1) of a cylindrical color space to the two opponent axes of its rectangular form.
+fn polar_to_cartesian(chroma: f32, hue: f32) -> [f32; 2] {
+	let (sin, cos) = (hue * core::f32::consts::TAU).sin_cos();
+	[chroma * cos, chroma * sin]
+}
+
+/// Converts the two opponent axes of a rectangular color space to the chroma and hue (between 0 and 1) of its cylindrical form.
+fn cartesian_to_polar(a: f32, b: f32) -> [f32; 2] {
+	[(a * a + b * b).sqrt(), wrap_unit(b.atan2(a) / core::f32::consts::TAU)]
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1139,4 +1363,61 @@ mod tests {
 			assert!((col.a() - result.a()) < f32::EPSILON * 100.);
 		}
 	}
+
+	#[test]
+	fn hsv_roundtrip() {
+		for (red, green, blue) in [(24, 98, 118), (69, 11, 89), (255, 255, 178), (82, 82, 82), (0, 0, 0)] {
+			let col = Color::from_rgb8_srgb(red, green, blue);
+			let [hue, saturation, value, alpha] = col.to_hsva();
+			let result = Color::from_hsva(hue, saturation, value, alpha);
+			for (a, b) in [(col.r(), result.r()), (col.g(), result.g()), (col.b(), result.b()), (col.a(), result.a())] {
+				assert!((a - b).abs() < 1e-5, "{col:?} != {result:?}");
+			}
+		}
+	}
+
+	#[test]
+	fn perceptual_roundtrip() {
+		for (red, green, blue) in [(24, 98, 118), (69, 11, 89), (255, 255, 178), (82, 82, 82), (255, 0, 0)] {
+			let col = Color::from_rgb8_srgb(red, green, blue);
+			let results = [
+				{
+					let [l, a, b, alpha] = col.to_oklaba();
+					Color::from_oklaba(l, a, b, alpha)
+				},
+				{
+					let [l, c, h, alpha] = col.to_oklcha();
+					Color::from_oklcha(l, c, h, alpha)
+				},
+				{
+					let [l, a, b, alpha] = col.to_laba();
+					Color::from_laba(l, a, b, alpha)
+				},
+				{
+					let [l, c, h, alpha] = col.to_lcha();
+					Color::from_lcha(l, c, h, alpha)
+				},
+			];
+			for result in results {
+				for (a, b) in [(col.r(), result.r()), (col.g(), result.g()), (col.b(), result.b()), (col.a(), result.a())] {
+					assert!((a - b).abs() < 1e-4, "{col:?} != {result:?}");
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn perceptual_reference_values() {
+		// White has full lightness and no chroma in both perceptual color spaces
+		let [l, a, b, _] = Color::WHITE.to_oklaba();
+		assert!((l - 1.).abs() < 1e-3 && a.abs() < 1e-3 && b.abs() < 1e-3);
+		let [l, a, b, _] = Color::WHITE.to_laba();
+		assert!((l - 100.).abs() < 1e-2 && a.abs() < 1e-2 && b.abs() < 1e-2);
+
+		// Reference values for pure sRGB red
+		let [l, a, b, _] = Color::RED.to_oklaba();
+		assert!((l - 0.628).abs() < 1e-3 && (a - 0.225).abs() < 1e-3 && (b - 0.126).abs() < 1e-3);
+		let [l, a, b, _] = Color::RED.to_laba();
+		assert!((l - 53.24).abs() < 0.1 && (a - 80.09).abs() < 0.1 && (b - 67.20).abs() < 0.1);
+	}
 }
@@ -0,0 +1,259 @@
+//! Color management backed by ICC profiles.
+//!
+//! Raster data is stored as linear, premultiplied color in the document's *working color space*, which defines the RGB primaries that the
+//! linear values are relative to. The default working space is sRGB, which keeps the long-standing meaning of [`Color`] as linear sRGB.
+//! Rendered output is gamma encoded with the sRGB transfer function in the same primaries.
+//!
+//! All conversions between profiles go through the [`moxcms`] color management system rather than hardcoded transfer functions.
+
+use crate::Image;
+use core_types::Color;
+use dyn_any::DynAny;
+use moxcms::{ColorProfile, Layout, TransformOptions, curve_from_gamma};
+
+/// A standard RGB color space, used as the working space of a document or as the target profile when soft-proofing and exporting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, DynAny, specta::Type, serde::Serialize, serde::Deserialize, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum ColorSpace {
+	/// The standard color space of the web and most displays.
+	#[default]
+	#[label("sRGB")]
+	Srgb,
+	/// The wide gamut color space of modern Apple and HDR-capable displays.
+	#[label("Display P3")]
+	DisplayP3,
+	/// The wide gamut color space commonly used in photography and print workflows.
+	#[label("Adobe RGB (1998)")]
+	AdobeRgb,
+	/// The very wide gamut color space of UHD video.
+	#[label("Rec. 2020")]
+	Rec2020,
+}
+
+impl ColorSpace {
+	/// The standard ICC profile of this color space, with its own gamma encoding.
+	pub fn profile(self) -> ColorProfile {
+		match self {
+			ColorSpace::Srgb => ColorProfile::new_srgb(),
+			ColorSpace::DisplayP3 => ColorProfile::new_display_p3(),
+			ColorSpace::AdobeRgb => ColorProfile::new_adobe_rgb(),
+			ColorSpace::Rec2020 => ColorProfile::new_bt2020(),
+		}
+	}
+
+	/// The profile of linear color with this color space's primaries, which is how raster data is stored in a working space.
+	pub fn linear_profile(self) -> ColorProfile {
+		self.profile_with_curve(curve_from_gamma(1.))
+	}
+
+	/// The profile of rendered output in this working space, which uses this color space's primaries with the sRGB transfer function.
+	pub fn rendered_profile(self) -> ColorProfile {
+		let srgb = ColorProfile::new_srgb();
+		self.profile_with_curve(srgb.red_trc.expect("the sRGB profile has a tone curve"))
+	}
+
+	/// The binary ICC profile data of this color space, for embedding in exported files.
+	pub fn icc_data(self) -> Result<Vec<u8>, String> {
+		self.profile().encode().map_err(|err| format!("Failed to encode the {self:?} ICC profile: {err}"))
+	}
+
+	fn profile_with_curve(self, curve: moxcms::ToneReprCurve) -> ColorProfile {
+		let mut profile = self.profile();
+		profile.red_trc = Some(curve.clone());
+		profile.green_trc = Some(curve.clone());
+		profile.blue_trc = Some(curve);
+		// The coding-independent code points would otherwise override the replaced tone curves
+		profile.cicp = None;
+		profile
+	}
+}
+
+/// How colors that are outside the gamut of the destination profile are brought into it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, DynAny, specta::Type, serde::Serialize, serde::Deserialize, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum RenderingIntent {
+	/// Compresses the whole gamut to preserve the visual relationship between colors.
+	#[default]
+	Perceptual,
+	/// Keeps in-gamut colors exact relative to the white point and clips the rest.
+	#[label("Relative Colorimetric")]
+	RelativeColorimetric,
+	/// Favors vivid colors over accuracy, for graphics like charts.
+	Saturation,
+	/// Keeps in-gamut colors exact including the white point, simulating the paper color of print.
+	#[label("Absolute Colorimetric")]
+	AbsoluteColorimetric,
+}
+
+impl From<RenderingIntent> for moxcms::RenderingIntent {
+	fn from(intent: RenderingIntent) -> Self {
+		match intent {
+			RenderingIntent::Perceptual => moxcms::RenderingIntent::Perceptual,
+			RenderingIntent::RelativeColorimetric => moxcms::RenderingIntent::RelativeColorimetric,
+			RenderingIntent::Saturation => moxcms::RenderingIntent::Saturation,
+			RenderingIntent::AbsoluteColorimetric => moxcms::RenderingIntent::AbsoluteColorimetric,
+		}
+	}
+}
+
+/// Converts unassociated RGBA channels in place from the source profile to the destination profile.
+///
+/// When `extended_range` is set, colors outside the destination gamut are kept as out-of-range values instead of being clipped, which only matters for linear destinations.
+fn transform_rgba_f32(pixels: &mut [f32], source: &ColorProfile, destination: &ColorProfile, intent: RenderingIntent, extended_range: bool) -> Result<(), String> {
+	let options = TransformOptions {
+		rendering_intent: intent.into(),
+		prefer_fixed_point: false,
+		allow_extended_range_rgb_xyz: extended_range,
+		..Default::default()
+	};
+	let transform = source
+		.create_transform_f32(Layout::Rgba, destination, Layout::Rgba, options)
+		.map_err(|err| format!("Failed to create a color transform: {err}"))?;
+
+	let input = pixels.to_vec();
+	transform.transform(&input, pixels).map_err(|err| format!("Failed to transform colors: {err}"))
+}
+
+fn unassociated_channels(pixels: &[Color]) -> Vec<f32> {
+	pixels
+		.iter()
+		.flat_map(|color| {
+			let color = color.to_unassociated_alpha();
+			[color.r(), color.g(), color.b(), color.a()]
+		})
+		.collect()
+}
+
+fn associated_colors(channels: &[f32]) -> impl Iterator<Item = Color> + '_ {
+	channels.chunks_exact(4).map(|pixel| Color::from_unassociated_alpha(pixel[0], pixel[1], pixel[2], pixel[3]))
+}
+
+/// Decodes an encoded image file (PNG, JPEG, WebP, etc.) into the working space, honoring its embedded ICC profile.
+///
+/// Files without an embedded profile, or with one that can't be parsed, are assumed to be sRGB.
+pub fn decode_image(data: &[u8], working_space: ColorSpace) -> Option<Image<Color>> {
+	use image::ImageDecoder;
+
+	let mut decoder = image::ImageReader::new(std::io::Cursor::new(data)).with_guessed_format().ok()?.into_decoder().ok()?;
+	let embedded_profile = decoder.icc_profile().ok().flatten();
+	let image = image::DynamicImage::from_decoder(decoder).ok()?.to_rgba32f();
+	let (width, height) = image.dimensions();
+	let mut channels = image.into_raw();
+
+	// Every file goes through the same transform, so its colors don't depend on the working space beyond the change of primaries
+	let source = embedded_profile.and_then(|icc| ColorProfile::new_from_slice(&icc).ok()).unwrap_or_else(ColorProfile::new_srgb);
+	transform_rgba_f32(&mut channels, &source, &working_space.linear_profile(), RenderingIntent::Perceptual, true).ok()?;
+
+	Some(Image {
+		width,
+		height,
+		data: associated_colors(&channels).collect(),
+		base64_string: None,
+	})
+}
+
+/// Converts linear colors in place between two working spaces. Colors outside the destination gamut are kept as out-of-range values.
+pub fn convert_working_space(pixels: &mut [Color], from: ColorSpace, to: ColorSpace) -> Result<(), String> {
+	if from == to {
+		return Ok(());
+	}
+
+	let mut channels = unassociated_channels(pixels);
+	transform_rgba_f32(&mut channels, &from.linear_profile(), &to.linear_profile(), RenderingIntent::RelativeColorimetric, true)?;
+	for (pixel, color) in pixels.iter_mut().zip(associated_colors(&channels)) {
+		*pixel = color;
+	}
+	Ok(())
+}
+
+/// Simulates in place how linear colors in the working space will appear once reproduced in the proof color space, by converting them there and back.
+///
+/// Out-of-gamut colors are brought into the proof gamut according to the rendering intent, and values are quantized to the 8 bits per channel of typical output files.
+pub fn soft_proof(pixels: &mut [Color], working_space: ColorSpace, proof_space: ColorSpace, intent: RenderingIntent) -> Result<(), String> {
+	let mut channels = unassociated_channels(pixels);
+
+	transform_rgba_f32(&mut channels, &working_space.linear_profile(), &proof_space.profile(), intent, false)?;
+	for channel in channels.iter_mut() {
+		*channel = (channel.clamp(0., 1.) * 255.).round() / 255.;
+	}
+	transform_rgba_f32(&mut channels, &proof_space.profile(), &working_space.linear_profile(), RenderingIntent::RelativeColorimetric, true)?;
+
+	for (pixel, color) in pixels.iter_mut().zip(associated_colors(&channels)) {
+		*pixel = color;
+	}
+	Ok(())
+}
+
+/// Converts rendered RGBA8 pixels in place from the working space to the output color space, ready to be encoded with that color space's ICC profile.
+pub fn convert_rendered_rgba8(data: &mut [u8], working_space: ColorSpace, output_space: ColorSpace, intent: RenderingIntent) -> Result<(), String> {
+	if working_space == output_space && matches!(output_space, ColorSpace::Srgb | ColorSpace::DisplayP3) {
+		// These color spaces already use the sRGB transfer function of rendered output
+		return Ok(());
+	}
+
+	let options = TransformOptions {
+		rendering_intent: intent.into(),
+		..Default::default()
+	};
+	let transform = working_space
+		.rendered_profile()
+		.create_transform_8bit(Layout::Rgba, &output_space.profile(), Layout::Rgba, options)
+		.map_err(|err| format!("Failed to create a color transform: {err}"))?;
+
+	let input = data.to_vec();
+	transform.transform(&input, data).map_err(|err| format!("Failed to transform colors: {err}"))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn working_space_round_trip() {
+		let original = [Color::from_rgbaf32_unchecked(0.8, 0.2, 0.1, 1.), Color::from_rgbaf32_unchecked(0.05, 0.3, 0.2, 0.5)];
+
+		let mut pixels = original;
+		convert_working_space(&mut pixels, ColorSpace::Srgb, ColorSpace::DisplayP3).unwrap();
+		assert_ne!(pixels, original);
+		convert_working_space(&mut pixels, ColorSpace::DisplayP3, ColorSpace::Srgb).unwrap();
+
+		for (converted, original) in pixels.iter().zip(original) {
+			for (a, b) in [
+				(converted.r(), original.r()),
+				(converted.g(), original.g()),
+				(converted.b(), original.b()),
+				(converted.a(), original.a()),
+			] {
+				assert!((a - b).abs() < 1e-3, "{converted:?} != {original:?}");
+			}
+		}
+	}
+
+	#[test]
+	fn decoded_image_matches_across_working_spaces() {
+		let mut png = Vec::new();
+		image::RgbaImage::from_pixel(1, 1, image::Rgba([200, 100, 50, 255]))
+			.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+			.unwrap();
+
+		let srgb = decode_image(&png, ColorSpace::Srgb).unwrap().data[0];
+		let mut display_p3 = decode_image(&png, ColorSpace::DisplayP3).unwrap().data;
+		convert_working_space(&mut display_p3, ColorSpace::DisplayP3, ColorSpace::Srgb).unwrap();
+
+		// An untagged file is read as sRGB in every working space
+		assert!((srgb.r() - Color::srgb_to_linear(200. / 255.)).abs() < 1e-3, "{srgb:?}");
+		for (a, b) in [(srgb.r(), display_p3[0].r()), (srgb.g(), display_p3[0].g()), (srgb.b(), display_p3[0].b())] {
+			assert!((a - b).abs() < 1e-3, "{srgb:?} != {:?}", display_p3[0]);
+		}
+	}
+
+	#[test]
+	fn soft_proof_clips_to_proof_gamut() {
+		// A saturated Rec. 2020 green is far outside the sRGB gamut
+		let mut pixels = [Color::from_rgbaf32_unchecked(0., 1., 0., 1.)];
+		soft_proof(&mut pixels, ColorSpace::Rec2020, ColorSpace::Srgb, RenderingIntent::RelativeColorimetric).unwrap();
+
+		// The closest sRGB green contains some red when expressed in Rec. 2020 primaries
+		assert!(pixels[0].r() > 0.2, "{:?}", pixels[0]);
+	}
+}
@@ -1,3 +1,4 @@
+pub mod color_management;
 pub mod image;
 pub mod raster_types;
 
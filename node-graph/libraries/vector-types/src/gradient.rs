@@ -10,6 +10,145 @@ pub enum GradientType {
 	Radial,
 }
 
+/// A way of describing colors by a set of components, used when constructing, splitting, and interpolating colors.
+///
+/// Hues are measured in degrees. Perceptual models (the CIE and OK ones) space their components so equal steps appear as roughly equal changes in color.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum ColorModel {
+	/// Red, green, and blue (0-1) encoded with the sRGB gamma, as seen in hex codes.
+	#[default]
+	#[label("sRGB")]
+	Srgb,
+	/// Red, green, and blue (0-1) in linear light, as used when compositing.
+	#[label("Linear RGB")]
+	LinearRgb,
+	/// Hue, saturation (0-1), and lightness (0-1) of the sRGB gamma encoded color.
+	#[label("HSL")]
+	Hsl,
+	/// Hue, saturation (0-1), and value (0-1) of the sRGB gamma encoded color.
+	#[label("HSV")]
+	Hsv,
+	/// CIE L*a*b* lightness (0-100) and the green-red and blue-yellow axes (roughly -128 to 128).
+	#[label("CIE Lab")]
+	Lab,
+	/// CIE LCh lightness (0-100), chroma (roughly 0-150), and hue.
+	#[label("CIE LCh")]
+	Lch,
+	/// OKLab lightness (0-1) and the green-red and blue-yellow axes (roughly -0.4 to 0.4).
+	#[label("OKLab")]
+	Oklab,
+	/// OKLCH lightness (0-1), chroma (roughly 0-0.4), and hue.
+	#[label("OKLCH")]
+	Oklch,
+}
+
+/// One of the components of a color in a [`ColorModel`], such as the hue of HSL or the lightness of OKLab.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum ColorComponent {
+	/// The first component, such as the red of RGB, the hue of HSL, or the lightness of OKLab.
+	#[default]
+	First,
+	/// The second component, such as the green of RGB, the saturation of HSL, or the a axis of OKLab.
+	Second,
+	/// The third component, such as the blue of RGB, the lightness of HSL, or the b axis of OKLab.
+	Third,
+	Alpha,
+}
+
+impl ColorModel {
+	/// The index of the hue among the components of this model, if it has one. The saturation or chroma is always the second component.
+	fn hue_index(self) -> Option<usize> {
+		match self {
+			ColorModel::Hsl | ColorModel::Hsv => Some(0),
+			ColorModel::Lch | ColorModel::Oklch => Some(2),
+			_ => None,
+		}
+	}
+
+	/// Splits a linear, premultiplied color into its three components in this model, followed by its alpha.
+	pub fn components(self, color: Color) -> [f32; 4] {
+		let color = color.to_unassociated_alpha();
+		let degrees = |[first, second, hue, alpha]: [f32; 4]| [first, second, hue * 360., alpha];
+
+		match self {
+			ColorModel::Srgb => {
+				let color = color.to_gamma_srgb();
+				[color.r(), color.g(), color.b(), color.a()]
+			}
+			ColorModel::LinearRgb => [color.r(), color.g(), color.b(), color.a()],
+			ColorModel::Hsl => {
+				let [hue, saturation, lightness, alpha] = color.to_gamma_srgb().to_hsla();
+				[hue * 360., saturation, lightness, alpha]
+			}
+			ColorModel::Hsv => {
+				let [hue, saturation, value, alpha] = color.to_gamma_srgb().to_hsva();
+				[hue * 360., saturation, value, alpha]
+			}
+			ColorModel::Lab => color.to_laba(),
+			ColorModel::Lch => degrees(color.to_lcha()),
+			ColorModel::Oklab => color.to_oklaba(),
+			ColorModel::Oklch => degrees(color.to_oklcha()),
+		}
+	}
+
+	/// Builds a linear, premultiplied color from its three components in this model, followed by its alpha.
+	///
+	/// Colors that fall outside the sRGB gamut have their channels clamped.
+	pub fn color(self, [first, second, third, alpha]: [f32; 4]) -> Color {
+		let alpha = alpha.clamp(0., 1.);
+		let color = match self {
+			ColorModel::Srgb => Color::from_rgbaf32_unchecked(first, second, third, alpha).to_linear_srgb(),
+			ColorModel::LinearRgb => Color::from_rgbaf32_unchecked(first, second, third, alpha),
+			ColorModel::Hsl => Color::from_hsla(first / 360., second.clamp(0., 1.), third.clamp(0., 1.), alpha).to_linear_srgb(),
+			ColorModel::Hsv => Color::from_hsva(first / 360., second.clamp(0., 1.), third.clamp(0., 1.), alpha).to_linear_srgb(),
+			ColorModel::Lab => Color::from_laba(first, second, third, alpha),
+			ColorModel::Lch => Color::from_lcha(first, second.max(0.), third / 360., alpha),
+			ColorModel::Oklab => Color::from_oklaba(first, second, third, alpha),
+			ColorModel::Oklch => Color::from_oklcha(first, second.max(0.), third / 360., alpha),
+		};
+
+		let color = color.map_rgb(|channel| if channel.is_finite() { channel.clamp(0., 1.) } else { 0. });
+		Color::from_unassociated_alpha(color.r(), color.g(), color.b(), color.a())
+	}
+
+	/// Interpolates between two linear, premultiplied colors by the factor `t` (0-1) through this model.
+	///
+	/// Hues travel the shorter way around the color wheel, and the hue of a gray is ignored in favor of the other color's hue.
+	pub fn interpolate(self, start: Color, end: Color, t: f32) -> Color {
+		if self == ColorModel::LinearRgb {
+			return start.lerp(&end, t);
+		}
+
+		let mut start_components = self.components(start);
+		let mut end_components = self.components(end);
+
+		if let Some(hue) = self.hue_index() {
+			const GRAY: f32 = 1e-4;
+			let chroma = 1;
+			if start_components[chroma] < GRAY {
+				start_components[hue] = end_components[hue];
+			} else if end_components[chroma] < GRAY {
+				end_components[hue] = start_components[hue];
+			}
+
+			let difference = end_components[hue] - start_components[hue];
+			if difference > 180. {
+				start_components[hue] += 360.;
+			} else if difference < -180. {
+				end_components[hue] += 360.;
+			}
+		}
+
+		let mut components = [0.; 4];
+		for (index, component) in components.iter_mut().enumerate() {
+			*component = start_components[index] + (end_components[index] - start_components[index]) * t;
+		}
+		self.color(components)
+	}
+}
+
 // TODO: Someday we could switch this to a Box[T] to avoid over-allocation
 // TODO: Use linear not gamma colors
 /// A list of colors associated with positions (in the range 0 to 1) along a gradient.
@@ -86,6 +225,11 @@ impl GradientStops {
 	}
 
 	pub fn evaluate(&self, t: f64) -> Color {
+		self.evaluate_in(t, ColorModel::Srgb)
+	}
+
+	/// Evaluates the color at position `t` along the gradient, interpolating between the surrounding stops through the given color model.
+	pub fn evaluate_in(&self, t: f64, model: ColorModel) -> Color {
 		if self.0.is_empty() {
 			return Color::BLACK;
 		}
@@ -101,14 +245,33 @@ impl GradientStops {
 			let (t1, c1) = self.0[i];
 			let (t2, c2) = self.0[i + 1];
 			if t >= t1 && t <= t2 {
-				let normalized_t = (t - t1) / (t2 - t1);
-				return c1.lerp(&c2, normalized_t as f32);
+				let normalized_t = ((t - t1) / (t2 - t1)) as f32;
+				return match model {
+					// The stops are already gamma encoded, so this is the sRGB interpolation
+					ColorModel::Srgb => c1.lerp(&c2, normalized_t),
+					_ => model.interpolate(c1.to_linear_srgb(), c2.to_linear_srgb(), normalized_t).to_gamma_srgb(),
+				};
 			}
 		}
 
 		Color::BLACK
 	}
 
+	/// Replaces the stops with evenly spaced ones sampled through the given color model, so renderers that only interpolate sRGB colors show the chosen interpolation.
+	///
+	/// The original stops are kept, with `steps` additional stops spread across the whole gradient.
+	pub fn resampled(&self, model: ColorModel, steps: u32) -> Self {
+		if self.0.len() < 2 || model == ColorModel::Srgb {
+			return self.clone();
+		}
+
+		let (first, last) = (self.0[0].0, self.0[self.0.len() - 1].0);
+		let sampled = (1..=steps).map(|step| first + (last - first) * step as f64 / (steps + 1) as f64);
+		let positions = self.0.iter().map(|(position, _)| *position).chain(sampled);
+
+		Self::new(positions.map(|position| (position, self.evaluate_in(position, model))).collect())
+	}
+
 	pub fn sort(&mut self) {
 		self.0.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 	}
@@ -38,7 +38,20 @@ pub(crate) fn generate_node_code(crate_ident: &CrateIdent, parsed: &ParsedNodeFn
 	let struct_name = format_ident!("{}Node", struct_name);
 
 	// Separate data fields from regular fields
-	let (data_fields, regular_fields): (Vec<_>, Vec<_>) = fields.iter().partition(|f| f.is_data_field);
+	let (data_fields, regular_fields): (Vec<_>, Vec<_>) = fields.iter().filter(|f| !f.is_variadic_field).partition(|f| f.is_data_field);
+
+	// The variadic field, if any, holds the nodes of all inputs after the regular fields
+	let variadic_field = fields.iter().find(|f| f.is_variadic_field);
+	let variadic_field_name = variadic_field.map(|f| &f.pat_ident.ident);
+	let variadic_param = variadic_field.map(|field| {
+		let pat_ident = &field.pat_ident;
+		let ty = match &field.ty {
+			ParsedFieldType::Regular(RegularParsedField { ty, .. }) => ty,
+			_ => unreachable!("Variadic fields must be Regular types, not Node types"),
+		};
+		quote!(, #pat_ident: #ty)
+	});
+	let variadic_arg = variadic_field_name.map(|name| quote!(, #name));
 
 	// Extract function generics used by data fields
 	let data_field_generics: Vec<_> = fn_generics
@@ -114,7 +127,9 @@ pub(crate) fn generate_node_code(crate_ident: &CrateIdent, parsed: &ParsedNodeFn
 		quote! { pub(super) #name: #r#gen }
 	});
 
-	let struct_fields = data_field_defs.chain(regular_field_defs);
+	let variadic_field_def = variadic_field_name.map(|name| quote! { pub(super) #name: Vec<#core_types::registry::SharedNodeContainer> });
+
+	let struct_fields = data_field_defs.chain(regular_field_defs).chain(variadic_field_def);
 
 	let mut future_idents = Vec::new();
 
@@ -136,9 +151,11 @@ pub(crate) fn generate_node_code(crate_ident: &CrateIdent, parsed: &ParsedNodeFn
 		.iter()
 		.map(|field| match &field.ty {
 			ParsedFieldType::Regular(RegularParsedField { ty, .. }) => ty.clone(),
-			ParsedFieldType::Node(NodeParsedField { output_type, input_type, .. }) => match parsed.is_async {
-				true => parse_quote!(&'n impl #core_types::Node<'n, #input_type, Output = impl core::future::Future<Output=#output_type>>),
-				false => parse_quote!(&'n impl #core_types::Node<'n, #input_type, Output = #output_type>),
+			// Parallel nodes may evaluate their node inputs from several threads at once, so only they require the nodes and their futures to be thread safe
+			ParsedFieldType::Node(NodeParsedField { output_type, input_type, .. }) => match (parsed.is_async, parsed.attributes.parallel) {
+				(true, true) => parse_quote!(&'n (impl #core_types::Node<'n, #input_type, Output = impl core::future::Future<Output=#output_type> + #core_types::WasmNotSend> + #core_types::WasmNotSync)),
+				(true, false) => parse_quote!(&'n impl #core_types::Node<'n, #input_type, Output = impl core::future::Future<Output=#output_type>>),
+				(false, _) => parse_quote!(&'n impl #core_types::Node<'n, #input_type, Output = #output_type>),
 			},
 		})
 		.collect();
@@ -233,18 +250,42 @@ pub(crate) fn generate_node_code(crate_ident: &CrateIdent, parsed: &ParsedNodeFn
 		})
 		.collect();
 
+	if parsed.attributes.parallel && !is_async {
+		return Err(Error::new_spanned(&parsed.fn_name, "Node needs to be async if you want to evaluate its inputs in parallel"));
+	}
+
 	// Only eval regular fields (data fields are accessed directly as self.field_name)
-	let eval_args = regular_fields.iter().map(|field| {
-		let name = &field.pat_ident.ident;
-		match &field.ty {
-			ParsedFieldType::Regular { .. } => {
-				quote! { let #name = self.#name.eval(__input.clone()).await; }
-			}
-			ParsedFieldType::Node { .. } => {
-				quote! { let #name = &self.#name; }
+	let mut eval_args: Vec<_> = regular_fields
+		.iter()
+		.filter_map(|field| {
+			let name = &field.pat_ident.ident;
+			match &field.ty {
+				ParsedFieldType::Regular { .. } if parsed.attributes.parallel => None,
+				ParsedFieldType::Regular { .. } => Some(quote! { let #name = self.#name.eval(__input.clone()).await; }),
+				ParsedFieldType::Node { .. } => Some(quote! { let #name = &self.#name; }),
 			}
+		})
+		.collect();
+
+	// Parallel nodes join the evaluations of their regular fields pairwise instead, as in `let (a, (b, c)) = join(a, join(b, c)).await`
+	let evaluated_names: Vec<_> = regular_fields
+		.iter()
+		.filter(|field| matches!(field.ty, ParsedFieldType::Regular { .. }))
+		.map(|field| &field.pat_ident.ident)
+		.collect();
+	if let (true, Some((last, rest))) = (parsed.attributes.parallel, evaluated_names.split_last()) {
+		let mut pattern = quote!(#last);
+		let mut future = quote!(self.#last.eval(__input.clone()));
+		for name in rest.iter().rev() {
+			pattern = quote!((#name, #pattern));
+			future = quote!(#core_types::parallel::join(self.#name.eval(__input.clone()), #future));
 		}
-	});
+
+		eval_args.push(quote! { let #pattern = #future.await; });
+	}
+	if let Some(name) = variadic_field_name {
+		eval_args.push(quote! { let #name = #core_types::registry::eval_variadic(&self.#name, __input.clone()).await; });
+	}
 
 	// Only regular fields can have min/max constraints
 	let min_max_args = regular_fields.iter().map(|field| match &field.ty {
@@ -331,10 +372,19 @@ pub(crate) fn generate_node_code(crate_ident: &CrateIdent, parsed: &ParsedNodeFn
 	);
 	struct_where_clause.predicates.extend(extra_where);
 
+	// The call argument is passed on to the nodes of the variadic inputs, whose types are erased
+	if variadic_field.is_some() {
+		struct_where_clause
+			.predicates
+			.push(parse_quote!(#input_type: Clone + #core_types::StaticType + #core_types::WasmNotSend));
+	}
+
 	// Only regular fields are parameters to new()
-	let new_args = node_generics.iter().zip(regular_field_names.iter()).map(|(r#gen, name)| {
-		quote! { #name: #r#gen }
-	});
+	let new_args = node_generics
+		.iter()
+		.zip(regular_field_names.iter())
+		.map(|(r#gen, name)| quote! { #name: #r#gen })
+		.chain(variadic_field_name.map(|name| quote! { #name: Vec<#core_types::registry::SharedNodeContainer> }));
 
 	// Initialize data fields with Default, regular fields with parameters
 	let data_inits = data_field_names.iter().map(|name| {
@@ -343,13 +393,13 @@ pub(crate) fn generate_node_code(crate_ident: &CrateIdent, parsed: &ParsedNodeFn
 	let regular_inits = regular_field_names.iter().map(|name| {
 		quote! { #name }
 	});
-	let all_field_inits = data_inits.chain(regular_inits);
+	let all_field_inits = data_inits.chain(regular_inits).chain(variadic_field_name.map(|name| quote! { #name }));
 
 	let async_keyword = is_async.then(|| quote!(async));
 	let await_keyword = is_async.then(|| quote!(.await));
 
 	// Data fields may not implement Copy, PartialEq, etc., so only derive Debug and Clone
-	let struct_derives = if data_fields.is_empty() {
+	let struct_derives = if data_fields.is_empty() && variadic_field.is_none() {
 		quote!(#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)])
 	} else {
 		quote!(#[derive(Debug, Clone)])
@@ -376,7 +426,7 @@ pub(crate) fn generate_node_code(crate_ident: &CrateIdent, parsed: &ParsedNodeFn
 
 				#(#eval_args)*
 				#(#min_max_args)*
-				self::#fn_name(__input #(, &self.#data_field_names)* #(, #regular_field_names)*) #await_keyword
+				self::#fn_name(__input #(, &self.#data_field_names)* #(, #regular_field_names)* #variadic_arg) #await_keyword
 			})
 		}
 
@@ -396,6 +446,8 @@ pub(crate) fn generate_node_code(crate_ident: &CrateIdent, parsed: &ParsedNodeFn
 	let import_name = format_ident!("_IMPORT_STUB_{}", mod_name.to_string().to_case(Case::UpperSnake));
 
 	let properties = &attributes.properties_string.as_ref().map(|value| quote!(Some(#value))).unwrap_or(quote!(None));
+	let disk_cache = attributes.disk_cache;
+	let lru_cache = attributes.lru_cache;
 
 	let cfg = crate::shader_nodes::modify_cfg(attributes);
 	let node_input_accessor = generate_node_input_references(parsed, fn_generics, &field_idents, core_types, &identifier, &cfg);
@@ -405,7 +457,7 @@ pub(crate) fn generate_node_code(crate_ident: &CrateIdent, parsed: &ParsedNodeFn
 		/// Underlying implementation for [#struct_name]
 		#[inline]
 		#[allow(clippy::too_many_arguments)]
-		#vis #async_keyword fn #fn_name <'n, #(#fn_generics,)*> (#input_ident: #input_type #(, #data_field_idents: #data_field_types)* #(, #field_idents: #field_types)*) -> #output_type #where_clause #body
+		#vis #async_keyword fn #fn_name <'n, #(#fn_generics,)*> (#input_ident: #input_type #(, #data_field_idents: #data_field_types)* #(, #field_idents: #field_types)* #variadic_param) -> #output_type #where_clause #body
 
 		#cfg
 		#[automatically_derived]
@@ -469,6 +521,8 @@ pub(crate) fn generate_node_code(crate_ident: &CrateIdent, parsed: &ParsedNodeFn
 					description: #description,
 					properties: #properties,
 					context_features: vec![#(ContextFeature::#context_features,)*],
+					disk_cache: #disk_cache,
+					lru_cache: #lru_cache,
 					fields: vec![
 						#(
 							FieldMetadata {
@@ -597,7 +651,15 @@ fn generate_register_node_impl(parsed: &ParsedNodeFn, field_names: &[&Ident], st
 	let mut constructors = Vec::new();
 	let unit = parse_quote!(gcore::Context);
 
-	let regular_fields: Vec<_> = parsed.fields.iter().filter(|f| !f.is_data_field).collect();
+	let regular_fields: Vec<_> = parsed.fields.iter().filter(|f| !f.is_data_field && !f.is_variadic_field).collect();
+
+	// Any inputs after the regular fields belong to the variadic field, and they aren't part of the node's type signature
+	let has_variadic_field = parsed.fields.iter().any(|f| f.is_variadic_field);
+	let variadic_args = has_variadic_field.then(|| {
+		let first_variadic_index = regular_fields.len();
+		quote!(args.get(#first_variadic_index..).unwrap_or_default().to_vec())
+	});
+	let variadic_panic_args = has_variadic_field.then(|| quote!(Vec::new()));
 
 	let parameter_types: Vec<_> = regular_fields
 		.iter()
@@ -661,13 +723,13 @@ fn generate_register_node_impl(parsed: &ParsedNodeFn, field_names: &[&Ident], st
 				|args| {
 					Box::pin(async move {
 						#(#temp_constructors;)*
-						let node = #struct_name::new(#(#field_names,)*);
+						let node = #struct_name::new(#(#field_names,)* #variadic_args);
 						// try polling futures
 						let any: DynAnyNode<#input_type, _, _> = DynAnyNode::new(node);
 						Box::new(any) as TypeErasedBox<'_>
 					})
 				}, {
-					let node = #struct_name::new(#(PanicNode::<#panic_node_types>::new(),)*);
+					let node = #struct_name::new(#(PanicNode::<#panic_node_types>::new(),)* #variadic_panic_args);
 					let params = vec![#(#temp_node_io,)*];
 					let mut node_io = NodeIO::<'_, #input_type>::to_async_node_io(&node, params);
 					node_io
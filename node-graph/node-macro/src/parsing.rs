@@ -45,6 +45,12 @@ pub(crate) struct NodeFnAttributes {
 	pub(crate) display_name: Option<LitStr>,
 	pub(crate) path: Option<Path>,
 	pub(crate) skip_impl: bool,
+	/// whether the regular inputs are evaluated concurrently instead of one after another, and the node inputs may be evaluated from several threads at once, defaults to false
+	pub(crate) parallel: bool,
+	/// whether the outputs of this node may be persisted in the on-disk render cache of native builds, defaults to false
+	pub(crate) disk_cache: bool,
+	/// whether the compiler caches several outputs of this node in an LRU memo node instead of only the latest one, defaults to false
+	pub(crate) lru_cache: bool,
 	pub(crate) properties_string: Option<LitStr>,
 	/// whether to `#[cfg]` gate the node implementation, defaults to None
 	pub(crate) cfg: Option<TokenStream2>,
@@ -115,6 +121,8 @@ pub struct ParsedField {
 	pub number_step: Option<LitFloat>,
 	pub unit: Option<LitStr>,
 	pub is_data_field: bool,
+	/// Whether this field (which must be the last one) collects the values of any number of inputs after the other fields
+	pub is_variadic_field: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -201,6 +209,9 @@ impl Parse for NodeFnAttributes {
 		let mut display_name = None;
 		let mut path = None;
 		let mut skip_impl = false;
+		let mut parallel = false;
+		let mut disk_cache = false;
+		let mut lru_cache = false;
 		let mut properties_string = None;
 		let mut cfg = None;
 		let mut shader_node = None;
@@ -249,6 +260,27 @@ impl Parse for NodeFnAttributes {
 					}
 					skip_impl = true;
 				}
+				"parallel" => {
+					let path = meta.require_path_only()?;
+					if parallel {
+						return Err(Error::new_spanned(path, "Multiple 'parallel' attributes are not allowed"));
+					}
+					parallel = true;
+				}
+				"disk_cache" => {
+					let path = meta.require_path_only()?;
+					if disk_cache {
+						return Err(Error::new_spanned(path, "Multiple 'disk_cache' attributes are not allowed"));
+					}
+					disk_cache = true;
+				}
+				"lru_cache" => {
+					let path = meta.require_path_only()?;
+					if lru_cache {
+						return Err(Error::new_spanned(path, "Multiple 'lru_cache' attributes are not allowed"));
+					}
+					lru_cache = true;
+				}
 				"properties" => {
 					let meta = meta.require_list()?;
 					if properties_string.is_some() {
@@ -290,7 +322,7 @@ impl Parse for NodeFnAttributes {
 						indoc!(
 							r#"
 							Unsupported attribute in `node`.
-							Supported attributes are 'category', 'path', 'name', 'skip_impl', 'cfg', 'properties', 'serialize', and 'shader_node'.
+							Supported attributes are 'category', 'path', 'name', 'skip_impl', 'parallel', 'disk_cache', 'lru_cache', 'cfg', 'properties', 'serialize', and 'shader_node'.
 
 							Example usage:
 							#[node_macro::node(category("Value"), name("Test Node"))]
@@ -306,6 +338,9 @@ impl Parse for NodeFnAttributes {
 			display_name,
 			path,
 			skip_impl,
+			parallel,
+			disk_cache,
+			lru_cache,
 			properties_string,
 			cfg,
 			shader_node,
@@ -402,6 +437,9 @@ fn parse_inputs(inputs: &Punctuated<FnArg, Comma>) -> syn::Result<(Input, Vec<Pa
 				});
 			} else if let Pat::Ident(pat_ident) = &**pat {
 				let field = parse_field(pat_ident.clone(), (**ty).clone(), attrs).map_err(|e| Error::new_spanned(pat_ident, format!("Failed to parse argument '{}': {}", pat_ident.ident, e)))?;
+				if field.is_variadic_field && index != inputs.len() - 1 {
+					return Err(Error::new_spanned(pat_ident, "A variadic field (#[variadic]) must be the last argument"));
+				}
 				fields.push(field);
 			} else {
 				return Err(Error::new_spanned(pat, "Expected a simple identifier for the field name"));
@@ -485,6 +523,9 @@ fn parse_field(pat_ident: PatIdent, ty: Type, attrs: &[Attribute]) -> syn::Resul
 	// Check if this is a data field (struct field, not a parameter)
 	let is_data_field = extract_attribute(attrs, "data").is_some();
 
+	// Check if this field collects the values of all remaining inputs
+	let is_variadic_field = extract_attribute(attrs, "variadic").is_some();
+
 	let default_value = extract_attribute(attrs, "default")
 		.map(|attr| attr.parse_args().map_err(|e| Error::new_spanned(attr, format!("Invalid `default` value for argument '{ident}': {e}"))))
 		.transpose()?;
@@ -526,6 +567,19 @@ fn parse_field(pat_ident: PatIdent, ty: Type, attrs: &[Attribute]) -> syn::Resul
 		}
 	}
 
+	// Validate variadic field attributes
+	if is_variadic_field {
+		if is_data_field {
+			return Err(Error::new_spanned(&pat_ident, "Variadic fields (#[variadic]) cannot also be data fields (#[data])"));
+		}
+		if default_value.is_some() || scope.is_some() || exposed {
+			return Err(Error::new_spanned(
+				&pat_ident,
+				"Variadic fields (#[variadic]) cannot have #[default], #[scope], or #[expose] attributes. Their inputs are added to the node by the editor",
+			));
+		}
+	}
+
 	let value_source = match (default_value, scope) {
 		(Some(_), Some(_)) => return Err(Error::new_spanned(&pat_ident, "Cannot have both `default` and `scope` attributes")),
 		(Some(default_value), _) => ParsedValueSource::Default(default_value),
@@ -623,6 +677,13 @@ fn parse_field(pat_ident: PatIdent, ty: Type, attrs: &[Attribute]) -> syn::Resul
 		.fold(String::new(), |acc, b| acc + &b + "\n");
 
 	if is_node {
+		if is_variadic_field {
+			return Err(Error::new_spanned(
+				&ty,
+				"Variadic fields (#[variadic]) cannot be of type `impl Node`. They must be a `Vec` of a type implementing `FromVariadicInput`",
+			));
+		}
+
 		// Data fields cannot be impl Node types
 		if is_data_field {
 			return Err(Error::new_spanned(
@@ -656,6 +717,7 @@ fn parse_field(pat_ident: PatIdent, ty: Type, attrs: &[Attribute]) -> syn::Resul
 			number_step,
 			unit,
 			is_data_field,
+			is_variadic_field,
 		})
 	} else {
 		let implementations = extract_attribute(attrs, "implementations")
@@ -683,6 +745,7 @@ fn parse_field(pat_ident: PatIdent, ty: Type, attrs: &[Attribute]) -> syn::Resul
 			number_step,
 			unit,
 			is_data_field,
+			is_variadic_field,
 		})
 	}
 }
@@ -781,6 +844,9 @@ mod tests {
 		assert_eq!(parsed.attributes.display_name, expected.attributes.display_name);
 		assert_eq!(parsed.attributes.path, expected.attributes.path);
 		assert_eq!(parsed.attributes.skip_impl, expected.attributes.skip_impl);
+		assert_eq!(parsed.attributes.parallel, expected.attributes.parallel);
+		assert_eq!(parsed.attributes.disk_cache, expected.attributes.disk_cache);
+		assert_eq!(parsed.attributes.lru_cache, expected.attributes.lru_cache);
 		assert_eq!(parsed.fields.len(), expected.fields.len());
 		assert_eq!(parsed.description, expected.description);
 
@@ -870,6 +936,9 @@ mod tests {
 				display_name: None,
 				path: Some(parse_quote!(core_types::TestNode)),
 				skip_impl: true,
+				parallel: false,
+				disk_cache: false,
+				lru_cache: false,
 				properties_string: None,
 				cfg: None,
 				shader_node: None,
@@ -909,6 +978,7 @@ mod tests {
 				number_step: None,
 				unit: None,
 				is_data_field: false,
+				is_variadic_field: false,
 			}],
 			body: TokenStream2::new(),
 			description: String::from("Multi\nLine\n"),
@@ -938,6 +1008,9 @@ mod tests {
 				display_name: None,
 				path: None,
 				skip_impl: false,
+				parallel: false,
+				disk_cache: false,
+				lru_cache: false,
 				properties_string: None,
 				cfg: None,
 				shader_node: None,
@@ -971,6 +1044,7 @@ mod tests {
 					number_step: None,
 					unit: None,
 					is_data_field: false,
+					is_variadic_field: false,
 				},
 				ParsedField {
 					pat_ident: pat_ident("translate"),
@@ -993,6 +1067,7 @@ mod tests {
 					number_step: None,
 					unit: None,
 					is_data_field: false,
+					is_variadic_field: false,
 				},
 			],
 			body: TokenStream2::new(),
@@ -1020,6 +1095,9 @@ mod tests {
 				display_name: None,
 				path: None,
 				skip_impl: false,
+				parallel: false,
+				disk_cache: false,
+				lru_cache: false,
 				properties_string: None,
 				cfg: None,
 				shader_node: None,
@@ -1059,6 +1137,7 @@ mod tests {
 				number_step: None,
 				unit: None,
 				is_data_field: false,
+				is_variadic_field: false,
 			}],
 			body: TokenStream2::new(),
 			description: "Test\n".into(),
@@ -1084,6 +1163,9 @@ mod tests {
 				display_name: None,
 				path: None,
 				skip_impl: false,
+				parallel: false,
+				disk_cache: false,
+				lru_cache: false,
 				properties_string: None,
 				cfg: None,
 				shader_node: None,
@@ -1128,6 +1210,7 @@ mod tests {
 				number_step: None,
 				unit: None,
 				is_data_field: false,
+				is_variadic_field: false,
 			}],
 			body: TokenStream2::new(),
 			description: String::new(),
@@ -1160,6 +1243,9 @@ mod tests {
 				display_name: None,
 				path: Some(parse_quote!(core_types::TestNode)),
 				skip_impl: false,
+				parallel: false,
+				disk_cache: false,
+				lru_cache: false,
 				properties_string: None,
 				cfg: None,
 				shader_node: None,
@@ -1199,6 +1285,7 @@ mod tests {
 				number_step: None,
 				unit: None,
 				is_data_field: false,
+				is_variadic_field: false,
 			}],
 			body: TokenStream2::new(),
 			description: String::new(),
@@ -1224,6 +1311,9 @@ mod tests {
 				display_name: None,
 				path: None,
 				skip_impl: false,
+				parallel: false,
+				disk_cache: false,
+				lru_cache: false,
 				properties_string: None,
 				cfg: None,
 				shader_node: None,
@@ -1263,6 +1353,7 @@ mod tests {
 				number_step: None,
 				unit: None,
 				is_data_field: false,
+				is_variadic_field: false,
 			}],
 			body: TokenStream2::new(),
 			description: String::new(),
@@ -1288,6 +1379,9 @@ mod tests {
 				display_name: Some(parse_quote!("CustomNode2")),
 				path: None,
 				skip_impl: false,
+				parallel: false,
+				disk_cache: false,
+				lru_cache: false,
 				properties_string: None,
 				cfg: None,
 				shader_node: None,
@@ -1326,6 +1420,70 @@ mod tests {
 		parse_node_fn(attr, input).unwrap();
 	}
 
+	#[test]
+	fn test_parallel_attribute() {
+		let attr = quote!(category("General"), parallel);
+		let input = quote!(
+			async fn add(_: impl Ctx, a: i32, b: i32) -> i32 {
+				a + b
+			}
+		);
+		let parsed = parse_node_fn(attr, input).unwrap();
+		assert!(parsed.attributes.parallel);
+		assert!(!parsed.attributes.skip_impl);
+	}
+
+	#[test]
+	fn test_disk_cache_attribute() {
+		let attr = quote!(category("Raster"), disk_cache);
+		let input = quote!(
+			fn blur(_: impl Ctx, image: Table<Raster<CPU>>, radius: f64) -> Table<Raster<CPU>> {
+				image
+			}
+		);
+		let parsed = parse_node_fn(attr, input).unwrap();
+		assert!(parsed.attributes.disk_cache);
+		assert!(!parsed.attributes.parallel);
+	}
+
+	#[test]
+	fn test_lru_cache_attribute() {
+		let attr = quote!(category("Vector"), lru_cache);
+		let input = quote!(
+			fn boolean_operation(_: impl Ctx, content: Table<Graphic>) -> Table<Vector> {
+				Table::new()
+			}
+		);
+		let parsed = parse_node_fn(attr, input).unwrap();
+		assert!(parsed.attributes.lru_cache);
+		assert!(!parsed.attributes.disk_cache);
+	}
+
+	#[test]
+	fn test_variadic_field() {
+		let attr = quote!(category("Math"));
+		let input = quote!(
+			fn sum(_: impl Ctx, first: f64, #[variadic] rest: Vec<f64>) -> f64 {
+				first + rest.iter().sum::<f64>()
+			}
+		);
+		let parsed = parse_node_fn(attr, input).unwrap();
+		assert!(!parsed.fields[0].is_variadic_field);
+		assert!(parsed.fields[1].is_variadic_field);
+	}
+
+	#[test]
+	#[should_panic(expected = "A variadic field (#[variadic]) must be the last argument")]
+	fn test_variadic_field_not_last() {
+		let attr = quote!(category("Math"));
+		let input = quote!(
+			fn sum(_: impl Ctx, #[variadic] rest: Vec<f64>, last: f64) -> f64 {
+				last + rest.iter().sum::<f64>()
+			}
+		);
+		parse_node_fn(attr, input).unwrap();
+	}
+
 	#[test]
 	#[should_panic(expected = "Call argument cannot be given a default value")]
 	fn test_default_value_for_first_arg() {
@@ -246,6 +246,7 @@ impl PerPixelAdjustCodegen<'_> {
 			number_step: None,
 			unit: None,
 			is_data_field: false,
+			is_variadic_field: false,
 		});
 
 		// find exactly one gpu_image field, runtime doesn't support more than 1 atm
@@ -12,6 +12,7 @@ use core_types::value::ClonedNode;
 use core_types::{Ctx, Node};
 use glam::{DAffine2, DVec2};
 use raster_nodes::blending_nodes::blend_colors;
+use raster_nodes::selection::{SelectionMask, SelectionStep};
 use raster_nodes::std_nodes::{empty_image, extend_image_to_bounds};
 use raster_types::BitmapMut;
 use raster_types::Image;
@@ -209,6 +210,15 @@ async fn brush(
 
 	let mut draw_strokes: Vec<_> = strokes.iter().filter(|&s| !matches!(s.style.blend_mode, BlendMode::Erase | BlendMode::Restore)).cloned().collect();
 
+	// The magic wand selects from the colors of the image before any strokes are drawn
+	let selection_source = strokes
+		.iter()
+		.any(|stroke| !stroke.selection.is_empty())
+		.then(|| extend_image_to_bounds((), Table::new_from_row(table_row.clone()), background_bounds).into_iter().next())
+		.flatten()
+		.unwrap_or_default();
+	let mut selection_masks = Vec::new();
+
 	let mut brush_plan = cache.compute_brush_plan(table_row, &draw_strokes);
 
 	// TODO: Find a way to handle more than one row
@@ -259,7 +269,13 @@ async fn brush(
 
 			let table = blit_node.eval(blit_target).await;
 			assert_eq!(table.len(), 1);
-			table.into_iter().next().unwrap_or_default()
+			let stroke_texture = table.into_iter().next().unwrap_or_default();
+
+			if stroke.selection.is_empty() {
+				stroke_texture
+			} else {
+				confine_to_selection(stroke_texture, selection_mask(&mut selection_masks, &selection_source, &stroke.selection))
+			}
 		};
 
 		// Cache image before doing final blend, and store final stroke texture.
@@ -303,7 +319,14 @@ async fn brush(
 				FutureWrapperNode::new(ClonedNode::new(positions)),
 				FutureWrapperNode::new(ClonedNode::new(blend_params)),
 			);
+			let previous_mask = (!stroke.selection.is_empty()).then(|| erase_restore_mask.clone());
 			erase_restore_mask = blit_node.eval(Table::new_from_row(erase_restore_mask)).await.into_iter().next().unwrap_or_default();
+
+			// Keep the previous mask outside of the selection
+			if let Some(previous_mask) = previous_mask {
+				let mask = selection_mask(&mut selection_masks, &selection_source, &stroke.selection);
+				erase_restore_mask = blend_within_selection(previous_mask, erase_restore_mask, mask);
+			}
 		}
 
 		let blend_params = FnNode::new(|(a, b)| blend_colors(a, b, BlendMode::MultiplyAlpha, 1.));
@@ -319,6 +342,53 @@ async fn brush(
 	image
 }
 
+/// Finds the mask of a selection computed for an earlier stroke, or computes it from the source image.
+fn selection_mask<'a>(masks: &'a mut Vec<(Vec<SelectionStep>, SelectionMask)>, source: &TableRow<Raster<CPU>>, selection: &[SelectionStep]) -> &'a SelectionMask {
+	let index = match masks.iter().position(|(steps, _)| steps == selection) {
+		Some(index) => index,
+		None => {
+			let pixel_to_layer = source.transform * DAffine2::from_scale(DVec2::new(1. / source.element.width as f64, 1. / source.element.height as f64));
+			masks.push((selection.to_vec(), SelectionMask::new(source.element.data(), pixel_to_layer, selection)));
+			masks.len() - 1
+		}
+	};
+	&masks[index].1
+}
+
+/// Fades out each pixel of a stroke texture by how unselected it is.
+fn confine_to_selection(mut texture: TableRow<Raster<CPU>>, mask: &SelectionMask) -> TableRow<Raster<CPU>> {
+	let (width, height) = (texture.element.width, texture.element.height);
+	let pixel_to_layer = texture.transform * DAffine2::from_scale(DVec2::new(1. / width as f64, 1. / height as f64));
+
+	let image = texture.element.data_mut();
+	for y in 0..height {
+		for x in 0..width {
+			let coverage = mask.coverage_at(pixel_to_layer.transform_point2(DVec2::new(x as f64 + 0.5, y as f64 + 0.5)));
+			let index = (y * width + x) as usize;
+			image.data[index] = image.data[index].apply_opacity(coverage);
+		}
+	}
+
+	texture
+}
+
+/// Blends between two versions of the same image by how selected each pixel is.
+fn blend_within_selection(unselected: TableRow<Raster<CPU>>, mut selected: TableRow<Raster<CPU>>, mask: &SelectionMask) -> TableRow<Raster<CPU>> {
+	let (width, height) = (selected.element.width, selected.element.height);
+	let pixel_to_layer = selected.transform * DAffine2::from_scale(DVec2::new(1. / width as f64, 1. / height as f64));
+
+	let image = selected.element.data_mut();
+	for y in 0..height {
+		for x in 0..width {
+			let coverage = mask.coverage_at(pixel_to_layer.transform_point2(DVec2::new(x as f64 + 0.5, y as f64 + 0.5)));
+			let index = (y * width + x) as usize;
+			image.data[index] = unselected.element.data[index].lerp(&image.data[index], coverage);
+		}
+	}
+
+	selected
+}
+
 pub fn blend_image_closure(foreground: TableRow<Raster<CPU>>, mut background: TableRow<Raster<CPU>>, map_fn: impl Fn(Color, Color) -> Color) -> TableRow<Raster<CPU>> {
 	let foreground_size = DVec2::new(foreground.element.width as f64, foreground.element.height as f64);
 	let background_size = DVec2::new(background.element.width as f64, background.element.height as f64);
@@ -407,10 +477,49 @@ mod test {
 					spacing: 20.,
 					blend_mode: BlendMode::Normal,
 				},
+				selection: Vec::new(),
 			}],
 			BrushCache::default(),
 		)
 		.await;
 		assert_eq!(image.iter().next().unwrap().element.width, 20);
 	}
+
+	#[tokio::test]
+	async fn test_brush_confined_to_selection() {
+		use raster_nodes::selection::{SelectionCombine, SelectionShape};
+		use raster_types::Bitmap;
+
+		// Select the left half of a white image and paint a black dab across its center
+		let mut background = Table::new_from_element(Raster::new_cpu(Image::new(20, 20, Color::WHITE)));
+		*background.get_mut(0).unwrap().transform = DAffine2::from_scale(DVec2::splat(20.));
+		let selection = vec![SelectionStep {
+			shape: SelectionShape::Rectangle {
+				transform: DAffine2::from_scale(DVec2::new(10., 20.)),
+			},
+			combine: SelectionCombine::Replace,
+			anti_aliasing: false,
+		}];
+
+		let image = brush(
+			(),
+			background,
+			vec![BrushStroke {
+				trace: vec![crate::brush_stroke::BrushInputSample { position: DVec2::splat(10.) }],
+				style: BrushStyle {
+					color: Color::BLACK,
+					diameter: 8.,
+					hardness: 100.,
+					..Default::default()
+				},
+				selection,
+			}],
+			BrushCache::default(),
+		)
+		.await;
+
+		let image = image.iter().next().unwrap().element.data();
+		assert_eq!(image.get_pixel(8, 10), Some(Color::BLACK));
+		assert_eq!(image.get_pixel(11, 10), Some(Color::WHITE));
+	}
 }
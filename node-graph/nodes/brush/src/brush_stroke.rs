@@ -3,6 +3,7 @@ use core_types::color::Color;
 use core_types::math::bbox::AxisAlignedBbox;
 use dyn_any::DynAny;
 use glam::DVec2;
+use raster_nodes::selection::SelectionStep;
 use std::hash::{Hash, Hasher};
 
 /// The style of a brush.
@@ -74,6 +75,9 @@ impl Hash for BrushInputSample {
 pub struct BrushStroke {
 	pub style: BrushStyle,
 	pub trace: Vec<BrushInputSample>,
+	/// The pixel selection the stroke is confined to, in layer space. The stroke isn't confined if this is empty.
+	#[serde(default)]
+	pub selection: Vec<SelectionStep>,
 }
 
 impl BrushStroke {
@@ -0,0 +1,337 @@
+use crate::brush::create_brush_texture;
+use crate::brush_stroke::{BrushStroke, BrushStyle};
+use core_types::Ctx;
+use core_types::blending::BlendMode;
+use core_types::bounds::{BoundingBox, RenderBoundingBox};
+use core_types::color::Color;
+use core_types::math::bbox::AxisAlignedBbox;
+use core_types::table::{Table, TableRow};
+use dyn_any::DynAny;
+use glam::{DAffine2, DVec2, IVec2};
+use graphic_types::Graphic;
+use raster_nodes::std_nodes::extend_image_to_bounds;
+use raster_types::Image;
+use raster_types::{CPU, Raster};
+use std::hash::{Hash, Hasher};
+
+/// A stroke painted by the Clone tool, which copies the pixels found at an offset from each point along its brush stroke.
+#[derive(Clone, Debug, PartialEq, Default, DynAny, serde::Serialize, serde::Deserialize)]
+pub struct CloneStroke {
+	/// The brush shape and path of the stroke. The brush color is unused, since the color comes from the sampled pixels.
+	pub stroke: BrushStroke,
+	/// The displacement in layer space from each painted point to the point that its pixels are sampled from.
+	pub source_offset: DVec2,
+}
+
+impl Hash for CloneStroke {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.stroke.hash(state);
+		self.source_offset.x.to_bits().hash(state);
+		self.source_offset.y.to_bits().hash(state);
+	}
+}
+
+/// The coverage of a brush stroke, accumulated from its stamps onto a grid of layer space pixels.
+struct StrokeMask {
+	/// The layer space pixel at the top left corner of the mask.
+	origin: IVec2,
+	width: i32,
+	height: i32,
+	coverage: Vec<f32>,
+}
+
+impl StrokeMask {
+	async fn new(stroke: &BrushStroke) -> Option<Self> {
+		let positions = stroke.compute_blit_points();
+		if positions.is_empty() {
+			return None;
+		}
+
+		// The color is white so the texture's alpha holds the brush coverage, including its flow
+		let style = BrushStyle {
+			color: Color::WHITE,
+			blend_mode: BlendMode::Normal,
+			..stroke.style.clone()
+		};
+		let texture = create_brush_texture(&style).await;
+		let texture_size = IVec2::new(texture.width as i32, texture.height as i32);
+
+		let starts: Vec<IVec2> = positions.iter().map(|position| (*position - texture_size.as_dvec2() / 2.).round().as_ivec2()).collect();
+		let origin = starts.iter().copied().reduce(IVec2::min)?;
+		let end = starts.iter().copied().reduce(IVec2::max)? + texture_size;
+		let (width, height) = ((end - origin).x, (end - origin).y);
+
+		let mut coverage = vec![0.; (width * height) as usize];
+		for start in starts {
+			let offset = start - origin;
+			for y in 0..texture_size.y {
+				for x in 0..texture_size.x {
+					let stamp = texture.data[(y * texture_size.x + x) as usize].a();
+					let covered = &mut coverage[((offset.y + y) * width + offset.x + x) as usize];
+					*covered += stamp * (1. - *covered);
+				}
+			}
+		}
+
+		Some(Self { origin, width, height, coverage })
+	}
+
+	fn bounds(&self) -> AxisAlignedBbox {
+		AxisAlignedBbox {
+			start: self.origin.as_dvec2(),
+			end: (self.origin + IVec2::new(self.width, self.height)).as_dvec2(),
+		}
+	}
+
+	fn coverage_at(&self, point: DVec2) -> f32 {
+		let pixel = point.floor().as_ivec2() - self.origin;
+		if pixel.x < 0 || pixel.y < 0 || pixel.x >= self.width || pixel.y >= self.height {
+			return 0.;
+		}
+		self.coverage[(pixel.y * self.width + pixel.x) as usize]
+	}
+}
+
+/// A raster image that pixels are sampled from, positioned in layer space.
+struct SampleSource {
+	layer_to_pixel: DAffine2,
+	image: Image<Color>,
+	opacity: f32,
+}
+
+impl SampleSource {
+	fn new(image: Image<Color>, transform: DAffine2, opacity: f32) -> Self {
+		let layer_to_pixel = DAffine2::from_scale(DVec2::new(image.width as f64, image.height as f64)) * transform.inverse();
+		Self { layer_to_pixel, image, opacity }
+	}
+
+	fn sample(&self, point: DVec2) -> Color {
+		let pixel = self.layer_to_pixel.transform_point2(point).floor();
+		if pixel.x < 0. || pixel.y < 0. || pixel.x >= self.image.width as f64 || pixel.y >= self.image.height as f64 {
+			return Color::TRANSPARENT;
+		}
+		self.image.data[pixel.y as usize * self.image.width as usize + pixel.x as usize].apply_opacity(self.opacity)
+	}
+}
+
+/// Collects the raster images, at any depth of nesting, from the graphic table in the order that they're drawn.
+fn collect_sample_sources(graphic: &Table<Graphic>, parent_transform: DAffine2, parent_opacity: f32, sources: &mut Vec<SampleSource>) {
+	for row in graphic.iter() {
+		let transform = parent_transform * *row.transform;
+		let opacity = parent_opacity * row.alpha_blending.opacity(false);
+
+		match row.element {
+			Graphic::Graphic(graphic) => collect_sample_sources(graphic, transform, opacity, sources),
+			Graphic::RasterCPU(raster) => {
+				for raster_row in raster.iter() {
+					let opacity = opacity * raster_row.alpha_blending.opacity(false);
+					sources.push(SampleSource::new(raster_row.element.data().clone(), transform * *raster_row.transform, opacity));
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+/// Paints the strokes of the Clone tool, which copy pixels from another part of the image to retouch it non-destructively.
+///
+/// Pixels are sampled from the image as it is before each stroke, or from the composite of it and the layers beneath when "Sample All Layers" is enabled.
+#[node_macro::node(category("Raster"))]
+async fn clone_stamp(
+	_: impl Ctx,
+	/// The raster content that is painted onto and sampled from.
+	mut image: Table<Raster<CPU>>,
+	/// The strokes painted by the Clone tool, each with its brush path and style and the offset to where it samples from.
+	strokes: Vec<CloneStroke>,
+	/// Whether the pixels are sampled from the composite of the image and the layers beneath it, instead of the image alone.
+	sample_all_layers: bool,
+	/// The layers beneath this one, sampled when "Sample All Layers" is enabled. Only their raster content is included.
+	layers_beneath: Table<Graphic>,
+	/// The transform from the space of the image into that of the layers beneath, which accounts for any transforms applied later in the layer's chain.
+	layers_beneath_transform: DAffine2,
+) -> Table<Raster<CPU>> {
+	let mut masks = Vec::with_capacity(strokes.len());
+	for clone_stroke in &strokes {
+		if let Some(mask) = StrokeMask::new(&clone_stroke.stroke).await {
+			masks.push((mask, clone_stroke.source_offset));
+		}
+	}
+	if masks.is_empty() {
+		return image;
+	}
+
+	if image.is_empty() {
+		image.push(TableRow::default());
+	}
+	let stroke_bbox = masks.iter().map(|(mask, _)| mask.bounds()).reduce(|a, b| a.union(&b)).unwrap_or(AxisAlignedBbox::ZERO);
+
+	// The first row is extended to hold the whole of the strokes, as the Brush node does, while the other rows are painted where the strokes overlap them
+	let mut targets = Vec::with_capacity(image.len());
+	for (index, table_row) in image.iter().enumerate() {
+		let table_row = table_row.into_cloned();
+		if index > 0 {
+			targets.push(table_row);
+			continue;
+		}
+
+		let image_bbox = match Table::new_from_row(table_row.clone()).bounding_box(DAffine2::IDENTITY, false) {
+			RenderBoundingBox::Rectangle([start, end]) => AxisAlignedBbox { start, end },
+			_ => AxisAlignedBbox::ZERO,
+		};
+		let bbox = if image_bbox.size().length() < 0.1 { stroke_bbox.clone() } else { stroke_bbox.union(&image_bbox) };
+
+		let Some(target) = extend_image_to_bounds((), Table::new_from_row(table_row), bbox.to_transform()).into_iter().next() else {
+			return Table::new();
+		};
+		targets.push(target);
+	}
+
+	let mut sources_beneath = Vec::new();
+	if sample_all_layers {
+		collect_sample_sources(&layers_beneath, layers_beneath_transform.inverse(), 1., &mut sources_beneath);
+	}
+
+	for (mask, source_offset) in masks {
+		// Sampling from a snapshot of every row keeps the stroke from copying pixels it has already painted, while letting it copy across rows
+		let snapshots: Vec<_> = targets.iter().map(|target| SampleSource::new(target.element.data().clone(), target.transform, 1.)).collect();
+		let sources: Vec<_> = sources_beneath.iter().chain(&snapshots).collect();
+
+		for target in &mut targets {
+			paint_clone_stroke(target, &mask, source_offset, &sources);
+		}
+	}
+
+	for (row, target) in image.iter_mut().zip(targets) {
+		*row.element = target.element;
+		*row.transform = target.transform;
+	}
+
+	image
+}
+
+/// Paints the pixels of the row covered by the stroke with the composite of the sources at the stroke's offset.
+fn paint_clone_stroke(target: &mut TableRow<Raster<CPU>>, mask: &StrokeMask, source_offset: DVec2, sources: &[&SampleSource]) {
+	let (width, height) = (target.element.width, target.element.height);
+	if width == 0 || height == 0 {
+		return;
+	}
+	let pixel_to_layer = target.transform * DAffine2::from_scale(DVec2::new(1. / width as f64, 1. / height as f64));
+
+	let mask_bounds = mask.bounds();
+	let pixel_bounds = [
+		mask_bounds.start,
+		mask_bounds.end,
+		DVec2::new(mask_bounds.start.x, mask_bounds.end.y),
+		DVec2::new(mask_bounds.end.x, mask_bounds.start.y),
+	]
+	.map(|corner| pixel_to_layer.inverse().transform_point2(corner));
+	let start = pixel_bounds.iter().copied().reduce(DVec2::min).unwrap_or_default().floor().max(DVec2::ZERO).as_uvec2();
+	let end = pixel_bounds
+		.iter()
+		.copied()
+		.reduce(DVec2::max)
+		.unwrap_or_default()
+		.ceil()
+		.min(DVec2::new(width as f64, height as f64))
+		.as_uvec2();
+
+	let data = &mut target.element.data_mut().data;
+	for y in start.y..end.y {
+		for x in start.x..end.x {
+			let point = pixel_to_layer.transform_point2(DVec2::new(x as f64 + 0.5, y as f64 + 0.5));
+			let coverage = mask.coverage_at(point);
+			if coverage <= 0. {
+				continue;
+			}
+
+			let source_point = point + source_offset;
+			let sampled = sources.iter().fold(Color::TRANSPARENT, |below, source| below.alpha_blend(source.sample(source_point)));
+
+			let pixel = &mut data[(y * width + x) as usize];
+			*pixel = pixel.alpha_blend(sampled.apply_opacity(coverage));
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::brush_stroke::BrushInputSample;
+
+	#[tokio::test]
+	async fn clone_stamp_copies_offset_pixels() {
+		// A 20x10 image whose left half is red and right half is blue
+		let data = (0..200).map(|i| if i % 20 < 10 { Color::RED } else { Color::BLUE }).collect();
+		let mut image = Table::new_from_element(Raster::new_cpu(Image {
+			width: 20,
+			height: 10,
+			data,
+			base64_string: None,
+		}));
+		*image.get_mut(0).unwrap().transform = DAffine2::from_scale(DVec2::new(20., 10.));
+
+		// Paint a hard dab on the left half, sampling from the right half
+		let stroke = CloneStroke {
+			stroke: BrushStroke {
+				trace: vec![BrushInputSample { position: DVec2::new(5., 5.) }],
+				style: BrushStyle {
+					diameter: 4.,
+					hardness: 100.,
+					..Default::default()
+				},
+				selection: Vec::new(),
+			},
+			source_offset: DVec2::new(10., 0.),
+		};
+
+		let result = clone_stamp((), image, vec![stroke], false, Table::new(), DAffine2::IDENTITY).await;
+		let result = result.iter().next().unwrap().element;
+
+		assert_eq!(result.width, 20);
+		// The center of the dab takes on the sampled blue, while pixels outside the dab stay red
+		let center = result.data[5 * 20 + 5];
+		assert!(center.b() > 0.9 && center.r() < 0.1, "Expected the dab to be blue, got {center:?}");
+		assert_eq!(result.data[5 * 20 + 1], Color::RED);
+	}
+	#[tokio::test]
+	async fn clone_stamp_paints_every_row_under_the_stroke() {
+		let solid = |color: Color| {
+			Raster::new_cpu(Image {
+				width: 10,
+				height: 10,
+				data: vec![color; 100],
+				base64_string: None,
+			})
+		};
+		// A red row, and a blue row beside it
+		let mut image = Table::new_from_element(solid(Color::RED));
+		*image.get_mut(0).unwrap().transform = DAffine2::from_scale(DVec2::splat(10.));
+		image.push(TableRow {
+			element: solid(Color::BLUE),
+			transform: DAffine2::from_translation(DVec2::new(20., 0.)) * DAffine2::from_scale(DVec2::splat(10.)),
+			..Default::default()
+		});
+
+		// Paint a hard dab on the blue row, sampling from the red row
+		let stroke = CloneStroke {
+			stroke: BrushStroke {
+				trace: vec![BrushInputSample { position: DVec2::new(25., 5.) }],
+				style: BrushStyle {
+					diameter: 4.,
+					hardness: 100.,
+					..Default::default()
+				},
+				selection: Vec::new(),
+			},
+			source_offset: DVec2::new(-20., 0.),
+		};
+
+		let result = clone_stamp((), image, vec![stroke], false, Table::new(), DAffine2::IDENTITY).await;
+		let blue_row = result.iter().nth(1).unwrap().element;
+
+		assert_eq!(blue_row.width, 10);
+		let center = blue_row.data[5 * 10 + 5];
+		assert!(center.r() > 0.9 && center.b() < 0.1, "Expected the dab to be red, got {center:?}");
+		assert_eq!(blue_row.data[5 * 10 + 1], Color::BLUE);
+	}
+}
@@ -1,3 +1,4 @@
 pub mod brush;
 pub mod brush_cache;
 pub mod brush_stroke;
+pub mod clone_stamp;
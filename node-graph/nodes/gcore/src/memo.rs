@@ -1,9 +1,13 @@
-use core_types::WasmNotSend;
 use core_types::memo::*;
+use core_types::table::Table;
+use core_types::{Color, WasmNotSend, WasmNotSync};
+use graphic_types::raster_types::{CPU, GPU, Raster};
+use graphic_types::{Artboard, Graphic, Vector};
+use std::any::Any;
 use std::hash::DefaultHasher;
 use std::hash::{Hash, Hasher};
-use std::sync::Arc;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, Weak};
 
 /// Caches the output of a given node called with a specific input.
 ///
@@ -27,6 +31,346 @@ async fn memo<I: Hash + Send + 'n, T: Clone + WasmNotSend>(input: I, #[data] cac
 	value
 }
 
+/// Caches the outputs of a given node for several different inputs, so a node evaluated under multiple footprints, indices or times doesn't recompute each time the input alternates.
+///
+/// The least recently used outputs are discarded once the node holds [`LRU_MEMO_ENTRIES_MAX`] of them, or once all LRU memo nodes together exceed the memory budget set by [`set_memo_cache_budget`].
+/// The compiler inserts it instead of [`memo`] after nodes marked with the `lru_cache` attribute whose output only depends on part of the context.
+#[node_macro::node(category(""), path(graphene_core::memo), skip_impl)]
+async fn lru_memo<I: Hash + Send + 'n, T: Clone + WasmNotSend + 'static>(input: I, #[data] cache: LruMemoCache<T>, node: impl Node<I, Output = T>) -> T {
+	let mut hasher = DefaultHasher::new();
+	input.hash(&mut hasher);
+	let hash = hasher.finish();
+
+	if let Some(value) = cache.get(hash) {
+		return value;
+	}
+
+	let value = node.eval(input).await;
+	cache.insert(hash, value.clone());
+	value
+}
+
+/// The most outputs that a single LRU memo node keeps, regardless of the remaining memory budget.
+pub const LRU_MEMO_ENTRIES_MAX: usize = 64;
+
+/// The memory budget shared by all LRU memo nodes when none has been set with [`set_memo_cache_budget`].
+pub const MEMO_CACHE_BUDGET_DEFAULT: usize = 512 * 1024 * 1024;
+
+static GLOBAL_MEMO_BUDGET: LazyLock<Arc<MemoBudget>> = LazyLock::new(|| Arc::new(MemoBudget::new(MEMO_CACHE_BUDGET_DEFAULT)));
+
+/// Sets the approximate number of bytes that all LRU memo nodes together may use, evicting the least recently used outputs if it's already exceeded.
+pub fn set_memo_cache_budget(bytes: usize) {
+	GLOBAL_MEMO_BUDGET.set_limit(bytes);
+}
+
+/// The current usage and eviction statistics of the memory budget shared by all LRU memo nodes.
+pub fn memo_cache_stats() -> MemoCacheStats {
+	GLOBAL_MEMO_BUDGET.stats()
+}
+
+/// A snapshot of how the memory budget shared by the LRU memo nodes is being used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoCacheStats {
+	/// The approximate number of bytes the caches may use.
+	pub budget: usize,
+	/// The approximate number of bytes the cached outputs currently occupy.
+	pub used: usize,
+	/// The number of outputs currently cached.
+	pub entries: usize,
+	/// The number of evaluations which returned a cached output.
+	pub hits: u64,
+	/// The number of evaluations which had to evaluate the cached node.
+	pub misses: u64,
+	/// The number of outputs discarded to make room for others.
+	pub evictions: u64,
+}
+
+/// Tracks the memory used by a set of LRU memo caches, evicting their least recently used entries across all of them once the limit is exceeded.
+pub struct MemoBudget {
+	limit: AtomicUsize,
+	used: AtomicUsize,
+	entries: AtomicUsize,
+	/// Incremented upon every use of an entry to order them from least to most recently used.
+	clock: AtomicU64,
+	hits: AtomicU64,
+	misses: AtomicU64,
+	evictions: AtomicU64,
+	caches: Mutex<CacheList>,
+}
+
+/// The caches sharing a [`MemoBudget`], which are weakly referenced so they are freed along with their nodes.
+struct CacheList(Vec<Weak<dyn EvictableCache>>);
+
+// WebAssembly is single threaded, so the caches can't be accessed from another thread even though their values aren't required to be `Send` or `Sync` there
+#[cfg(target_family = "wasm")]
+unsafe impl Send for CacheList {}
+#[cfg(target_family = "wasm")]
+unsafe impl Sync for CacheList {}
+
+impl MemoBudget {
+	pub fn new(limit: usize) -> Self {
+		Self {
+			limit: AtomicUsize::new(limit),
+			used: AtomicUsize::new(0),
+			entries: AtomicUsize::new(0),
+			clock: AtomicU64::new(0),
+			hits: AtomicU64::new(0),
+			misses: AtomicU64::new(0),
+			evictions: AtomicU64::new(0),
+			caches: Mutex::new(CacheList(Vec::new())),
+		}
+	}
+
+	pub fn set_limit(&self, limit: usize) {
+		self.limit.store(limit, Ordering::Relaxed);
+		self.enforce_limit();
+	}
+
+	pub fn stats(&self) -> MemoCacheStats {
+		MemoCacheStats {
+			budget: self.limit.load(Ordering::Relaxed),
+			used: self.used.load(Ordering::Relaxed),
+			entries: self.entries.load(Ordering::Relaxed),
+			hits: self.hits.load(Ordering::Relaxed),
+			misses: self.misses.load(Ordering::Relaxed),
+			evictions: self.evictions.load(Ordering::Relaxed),
+		}
+	}
+
+	fn tick(&self) -> u64 {
+		self.clock.fetch_add(1, Ordering::Relaxed)
+	}
+
+	fn register(&self, cache: Weak<dyn EvictableCache>) {
+		let mut caches = self.caches.lock().unwrap();
+		caches.0.retain(|cache| cache.strong_count() > 0);
+		caches.0.push(cache);
+	}
+
+	/// Evicts the least recently used entry among all the caches until the memory in use fits within the limit.
+	fn enforce_limit(&self) {
+		if self.used.load(Ordering::Relaxed) <= self.limit.load(Ordering::Relaxed) {
+			return;
+		}
+
+		let caches = self.caches.lock().unwrap();
+		while self.used.load(Ordering::Relaxed) > self.limit.load(Ordering::Relaxed) {
+			let oldest = caches
+				.0
+				.iter()
+				.filter_map(Weak::upgrade)
+				.filter_map(|cache| Some((cache.oldest_use()?, cache)))
+				.min_by_key(|(last_used, _)| *last_used);
+			let Some((_, cache)) = oldest else { break };
+			cache.evict_oldest();
+		}
+	}
+}
+
+/// A cache whose entries can be evicted by the [`MemoBudget`] it shares with other caches.
+trait EvictableCache: WasmNotSend + WasmNotSync {
+	/// The tick at which the least recently used entry was last used, or `None` if the cache is empty.
+	fn oldest_use(&self) -> Option<u64>;
+	/// Discards the least recently used entry.
+	fn evict_oldest(&self);
+}
+
+struct LruMemoEntry<T> {
+	hash: u64,
+	value: T,
+	size: usize,
+	last_used: u64,
+}
+
+struct LruMemoCacheInner<T> {
+	budget: Arc<MemoBudget>,
+	/// Ordered from least to most recently used.
+	entries: Mutex<Vec<LruMemoEntry<T>>>,
+}
+
+impl<T> LruMemoCacheInner<T> {
+	fn remove_oldest(&self, entries: &mut Vec<LruMemoEntry<T>>) {
+		if entries.is_empty() {
+			return;
+		}
+		let entry = entries.remove(0);
+		self.budget.used.fetch_sub(entry.size, Ordering::Relaxed);
+		self.budget.entries.fetch_sub(1, Ordering::Relaxed);
+		self.budget.evictions.fetch_add(1, Ordering::Relaxed);
+	}
+}
+
+impl<T: WasmNotSend> EvictableCache for LruMemoCacheInner<T> {
+	fn oldest_use(&self) -> Option<u64> {
+		self.entries.lock().unwrap().first().map(|entry| entry.last_used)
+	}
+
+	fn evict_oldest(&self) {
+		let mut entries = self.entries.lock().unwrap();
+		self.remove_oldest(&mut entries);
+	}
+}
+
+impl<T> Drop for LruMemoCacheInner<T> {
+	fn drop(&mut self) {
+		let entries = self.entries.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner());
+		let size = entries.iter().map(|entry| entry.size).sum();
+		self.budget.used.fetch_sub(size, Ordering::Relaxed);
+		self.budget.entries.fetch_sub(entries.len(), Ordering::Relaxed);
+	}
+}
+
+/// The storage of an LRU memo node, holding several input hashes with their outputs and accounting for their memory in a [`MemoBudget`].
+pub struct LruMemoCache<T>(Arc<LruMemoCacheInner<T>>);
+
+impl<T> Clone for LruMemoCache<T> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+}
+
+impl<T> std::fmt::Debug for LruMemoCache<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let entries = self.0.entries.lock().unwrap();
+		f.debug_struct("LruMemoCache").field("entries", &entries.len()).finish()
+	}
+}
+
+impl<T: WasmNotSend + 'static> Default for LruMemoCache<T> {
+	fn default() -> Self {
+		Self::with_budget(GLOBAL_MEMO_BUDGET.clone())
+	}
+}
+
+impl<T: WasmNotSend + 'static> LruMemoCache<T> {
+	/// Creates a cache which shares the given budget rather than the global one used by the LRU memo nodes.
+	pub fn with_budget(budget: Arc<MemoBudget>) -> Self {
+		let inner = Arc::new(LruMemoCacheInner {
+			budget,
+			entries: Mutex::new(Vec::new()),
+		});
+		let weak = Arc::downgrade(&inner) as Weak<dyn EvictableCache>;
+		inner.budget.register(weak);
+		Self(inner)
+	}
+}
+
+impl<T: Clone + 'static> LruMemoCache<T> {
+	/// Returns a copy of the output cached for the input hash, marking it as the most recently used.
+	pub fn get(&self, hash: u64) -> Option<T> {
+		let budget = &self.0.budget;
+		let mut entries = self.0.entries.lock().unwrap();
+
+		let Some(index) = entries.iter().position(|entry| entry.hash == hash) else {
+			budget.misses.fetch_add(1, Ordering::Relaxed);
+			return None;
+		};
+		budget.hits.fetch_add(1, Ordering::Relaxed);
+
+		let mut entry = entries.remove(index);
+		entry.last_used = budget.tick();
+		let value = entry.value.clone();
+		entries.push(entry);
+		Some(value)
+	}
+
+	/// Stores the output for the input hash, then evicts the least recently used outputs of this or other caches if the budget is exceeded.
+	pub fn insert(&self, hash: u64, value: T) {
+		let budget = &self.0.budget;
+		let size = estimated_size(&value);
+
+		{
+			let mut entries = self.0.entries.lock().unwrap();
+			if let Some(index) = entries.iter().position(|entry| entry.hash == hash) {
+				let entry = entries.remove(index);
+				budget.used.fetch_sub(entry.size, Ordering::Relaxed);
+				budget.entries.fetch_sub(1, Ordering::Relaxed);
+			}
+			if entries.len() >= LRU_MEMO_ENTRIES_MAX {
+				self.0.remove_oldest(&mut entries);
+			}
+
+			entries.push(LruMemoEntry {
+				hash,
+				value,
+				size,
+				last_used: budget.tick(),
+			});
+			budget.used.fetch_add(size, Ordering::Relaxed);
+			budget.entries.fetch_add(1, Ordering::Relaxed);
+		}
+
+		// The lock on this cache's entries is released first since evicting may lock every cache sharing the budget
+		budget.enforce_limit();
+	}
+
+	/// The number of outputs currently cached.
+	pub fn len(&self) -> usize {
+		self.0.entries.lock().unwrap().len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+/// Approximates the number of bytes occupied by a cached value, including the heap allocations of the data types which typically dominate memory use.
+pub fn estimated_size<T: 'static>(value: &T) -> usize {
+	std::mem::size_of::<T>() + heap_size(value)
+}
+
+fn heap_size(value: &dyn Any) -> usize {
+	if let Some(table) = value.downcast_ref::<Table<Graphic>>() {
+		table_heap_size(table, graphic_heap_size)
+	} else if let Some(table) = value.downcast_ref::<Table<Vector>>() {
+		table_heap_size(table, vector_heap_size)
+	} else if let Some(table) = value.downcast_ref::<Table<Raster<CPU>>>() {
+		table_heap_size(table, raster_heap_size)
+	} else if let Some(table) = value.downcast_ref::<Table<Raster<GPU>>>() {
+		table_heap_size(table, |_| 0)
+	} else if let Some(table) = value.downcast_ref::<Table<Artboard>>() {
+		table_heap_size(table, |artboard| artboard.label.len() + table_heap_size(&artboard.content, graphic_heap_size))
+	} else if let Some(table) = value.downcast_ref::<Table<Color>>() {
+		table_heap_size(table, |_| 0)
+	} else if let Some(string) = value.downcast_ref::<String>() {
+		string.len()
+	} else if let Some(vec) = value.downcast_ref::<Vec<f64>>() {
+		std::mem::size_of_val(vec.as_slice())
+	} else {
+		0
+	}
+}
+
+fn table_heap_size<T>(table: &Table<T>, element_heap_size: impl Fn(&T) -> usize) -> usize {
+	let row_size = std::mem::size_of::<T>() + std::mem::size_of::<core_types::table::TableRow<()>>();
+	table.iter().map(|row| row_size + element_heap_size(row.element)).sum()
+}
+
+fn graphic_heap_size(graphic: &Graphic) -> usize {
+	match graphic {
+		Graphic::Graphic(table) => table_heap_size(table, graphic_heap_size),
+		Graphic::Vector(table) => table_heap_size(table, vector_heap_size),
+		Graphic::RasterCPU(table) => table_heap_size(table, raster_heap_size),
+		Graphic::RasterGPU(table) => table_heap_size(table, |_| 0),
+		Graphic::Color(table) => table_heap_size(table, |_| 0),
+		Graphic::Gradient(table) => table_heap_size(table, |gradient| std::mem::size_of_val(gradient.0.as_slice())),
+	}
+}
+
+fn vector_heap_size(vector: &Vector) -> usize {
+	// Rough per-element sizes of the attributes stored for each point, segment and region
+	const POINT_SIZE: usize = 24;
+	const SEGMENT_SIZE: usize = 72;
+	const REGION_SIZE: usize = 32;
+
+	let upstream = vector.upstream_data.as_ref().map_or(0, |table| table_heap_size(table, graphic_heap_size));
+	vector.point_domain.ids().len() * POINT_SIZE + vector.segment_domain.ids().len() * SEGMENT_SIZE + vector.region_domain.ids().len() * REGION_SIZE + upstream
+}
+
+fn raster_heap_size(raster: &Raster<CPU>) -> usize {
+	std::mem::size_of_val(raster.data().data.as_slice())
+}
+
 type MonitorValue<I, T> = Arc<Mutex<Option<Arc<IORecord<I, T>>>>>;
 
 /// Caches the output of the last graph evaluation for introspection.
@@ -47,3 +391,72 @@ fn serialize_monitor<I: Clone + 'static + Send + Sync, T: Clone + 'static + Send
 	let io = io.lock().unwrap();
 	io.as_ref().map(|output| output.clone() as Arc<dyn std::any::Any + Send + Sync>)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn keeps_several_entries() {
+		let cache = LruMemoCache::<u64>::with_budget(Arc::new(MemoBudget::new(usize::MAX)));
+		cache.insert(1, 10);
+		cache.insert(2, 20);
+		cache.insert(3, 30);
+
+		assert_eq!(cache.get(1), Some(10));
+		assert_eq!(cache.get(2), Some(20));
+		assert_eq!(cache.get(3), Some(30));
+		assert_eq!(cache.get(4), None);
+		assert_eq!(cache.0.budget.stats().hits, 3);
+		assert_eq!(cache.0.budget.stats().misses, 1);
+	}
+
+	#[test]
+	fn limits_entries_per_cache() {
+		let cache = LruMemoCache::<u64>::with_budget(Arc::new(MemoBudget::new(usize::MAX)));
+		for hash in 0..LRU_MEMO_ENTRIES_MAX as u64 + 1 {
+			cache.insert(hash, hash);
+		}
+
+		assert_eq!(cache.len(), LRU_MEMO_ENTRIES_MAX);
+		assert_eq!(cache.get(0), None);
+		assert_eq!(cache.get(1), Some(1));
+	}
+
+	#[test]
+	fn evicts_least_recently_used_across_caches() {
+		let entry_size = estimated_size(&0_u64);
+		let budget = Arc::new(MemoBudget::new(entry_size * 3));
+		let first = LruMemoCache::<u64>::with_budget(budget.clone());
+		let second = LruMemoCache::<u64>::with_budget(budget.clone());
+
+		first.insert(1, 1);
+		second.insert(2, 2);
+		first.insert(3, 3);
+		// Using the first entry makes the second cache's entry the least recently used
+		assert_eq!(first.get(1), Some(1));
+		second.insert(4, 4);
+
+		assert_eq!(second.get(2), None);
+		assert_eq!(first.get(1), Some(1));
+		assert_eq!(first.get(3), Some(3));
+		assert_eq!(second.get(4), Some(4));
+
+		let stats = budget.stats();
+		assert_eq!(stats.entries, 3);
+		assert_eq!(stats.used, entry_size * 3);
+		assert_eq!(stats.evictions, 1);
+
+		drop(first);
+		assert_eq!(budget.stats().entries, 1);
+		assert_eq!(budget.stats().used, entry_size);
+	}
+
+	#[test]
+	fn estimates_image_size() {
+		let image = Raster::new_cpu(graphic_types::raster_types::Image::new(10, 10, Color::BLACK));
+		let table = Table::new_from_element(image);
+
+		assert!(estimated_size(&table) >= 10 * 10 * std::mem::size_of::<Color>());
+	}
+}
@@ -40,7 +40,7 @@ pub async fn source_node_id<I: 'n + Send + Clone>(
 }
 
 /// Joins two tables of the same type, extending the base table with the rows of the new table.
-#[node_macro::node(category("General"))]
+#[node_macro::node(category("General"), parallel)]
 pub async fn extend<I: 'n + Send + Clone>(
 	_: impl Ctx,
 	/// The table whose rows will appear at the start of the extended table.
@@ -13,6 +13,7 @@ pub use graphic_nodes;
 pub use math_nodes;
 pub use path_bool_nodes as path_bool;
 pub use raster_nodes;
+pub use script_nodes;
 pub use text_nodes;
 pub use transform_nodes;
 pub use vector_nodes;
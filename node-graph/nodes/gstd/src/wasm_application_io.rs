@@ -1,13 +1,15 @@
 #[cfg(target_family = "wasm")]
 use base64::Engine;
 #[cfg(target_family = "wasm")]
+use core_types::Color;
+use core_types::Ctx;
+#[cfg(target_family = "wasm")]
 use core_types::WasmNotSend;
 #[cfg(target_family = "wasm")]
 use core_types::math::bbox::Bbox;
 use core_types::table::Table;
 #[cfg(target_family = "wasm")]
 use core_types::transform::Footprint;
-use core_types::{Color, Ctx};
 pub use graph_craft::document::value::RenderOutputType;
 pub use graph_craft::wasm_application_io::*;
 use graphene_application_io::ApplicationIo;
@@ -15,7 +17,9 @@ use graphene_application_io::ApplicationIo;
 use graphic_types::Graphic;
 #[cfg(target_family = "wasm")]
 use graphic_types::Vector;
+#[cfg(target_family = "wasm")]
 use graphic_types::raster_types::Image;
+use graphic_types::raster_types::color_management::{self, ColorSpace};
 use graphic_types::raster_types::{CPU, Raster};
 #[cfg(target_family = "wasm")]
 use graphic_types::vector_types::gradient::GradientStops;
@@ -149,22 +153,17 @@ async fn load_resource<'a: 'n>(_: impl Ctx, _primary: (), #[scope("editor-api")]
 
 /// Converts raw binary data to a raster image.
 ///
-/// Works with standard image format (PNG, JPEG, WebP, etc.). Automatically converts the color space to linear sRGB for accurate compositing.
-#[node_macro::node(category("Web Request"))]
-fn decode_image(_: impl Ctx, data: Arc<[u8]>) -> Table<Raster<CPU>> {
-	let Some(image) = image::load_from_memory(data.as_ref()).ok() else {
+/// Works with standard image format (PNG, JPEG, WebP, etc.). Automatically converts the colors from the image's embedded ICC profile (or sRGB if it has none) into the linear working space for accurate compositing.
+#[node_macro::node(category("Web Request"), disk_cache)]
+fn decode_image(
+	_: impl Ctx,
+	data: Arc<[u8]>,
+	/// The color space that the document's raster data is stored in. The editor sets this to the document's working color space unless it's chosen here.
+	working_space: ColorSpace,
+) -> Table<Raster<CPU>> {
+	let Some(image) = color_management::decode_image(data.as_ref(), working_space) else {
 		return Table::new();
 	};
-	let image = image.to_rgba32f();
-	let image = Image {
-		data: image
-			.chunks(4)
-			.map(|pixel| Color::from_unassociated_alpha(pixel[0], pixel[1], pixel[2], pixel[3]).to_linear_srgb())
-			.collect(),
-		width: image.width(),
-		height: image.height(),
-		..Default::default()
-	};
 
 	Table::new_from_element(Raster::new_cpu(image))
 }
@@ -1,79 +1,235 @@
 use core_types::registry::types::{Fraction, Percentage, PixelSize, TextArea};
+use core_types::registry::{Any, FromVariadicInput};
 use core_types::table::Table;
 use core_types::transform::Footprint;
 use core_types::{Color, Ctx, num_traits};
-use glam::{DAffine2, DVec2};
-use log::warn;
-use math_parser::ast;
-use math_parser::context::{EvalContext, NothingMap, ValueProvider};
+use dyn_any::DynAny;
+use glam::{DAffine2, DVec2, DVec4, Vec4};
+use math_parser::ast::{self, BinaryOp};
+use math_parser::context::{EvalContext, FunctionProvider, ValueProvider};
 use math_parser::value::{Number, Value};
 use num_traits::Pow;
 use rand::{Rng, SeedableRng};
+use std::any::TypeId;
 use std::ops::{Add, Div, Mul, Rem, Sub};
-use vector_types::GradientStops;
+use vector_types::{ColorComponent, ColorModel, GradientStops};
 
-/// The struct that stores the context for the maths parser.
-/// This is currently just limited to supplying `a` and `b` until we add better node graph support and UI for variadic inputs.
+/// The values of the variables in the Math node's expression, which are looked up by their names.
 struct MathNodeContext {
-	a: f64,
-	b: f64,
+	a: Value,
+	b: Value,
+	variables: Vec<(String, Value)>,
 }
 
 impl ValueProvider for MathNodeContext {
 	fn get_value(&self, name: &str) -> Option<Value> {
 		if name.eq_ignore_ascii_case("a") {
-			Some(Value::from_f64(self.a))
+			Some(self.a)
 		} else if name.eq_ignore_ascii_case("b") {
-			Some(Value::from_f64(self.b))
+			Some(self.b)
 		} else {
-			None
+			self.variables.iter().find(|(variable, _)| variable == name).map(|(_, value)| *value)
 		}
 	}
 }
 
-/// Calculates a mathematical expression with input values "A" and "B".
-#[node_macro::node(category("Math: Arithmetic"), properties("math_properties"))]
-fn math<T: num_traits::float::Float>(
-	_: impl Ctx,
-	/// The value of "A" when calculating the expression.
-	#[implementations(f64, f32)]
-	operand_a: T,
-	/// A math expression that may incorporate "A" and/or "B", such as `sqrt(A + B) - B^2`.
-	#[default(A + B)]
-	expression: String,
-	/// The value of "B" when calculating the expression.
-	#[implementations(f64, f32)]
-	#[default(1.)]
-	operand_b: T,
-) -> T {
-	let (node, _unit) = match ast::Node::try_parse_from_str(&expression) {
-		Ok(expr) => expr,
-		Err(e) => {
-			warn!("Invalid expression: `{expression}`\n{e:?}");
-			return T::from(0.).unwrap();
+/// The functions available to the Math node's expressions, in addition to the trigonometric functions built into the math parser.
+struct MathNodeFunctions;
+
+impl FunctionProvider for MathNodeFunctions {
+	fn run_function(&self, name: &str, args: &[Value]) -> Option<Value> {
+		let value = match (name, args) {
+			("vec2", [x, y]) => Value::Vec2(DVec2::new(x.as_real()?, y.as_real()?)),
+			("rgb", [r, g, b]) => Value::Vec4(DVec4::new(r.as_real()?, g.as_real()?, b.as_real()?, 1.)),
+			("rgba", [r, g, b, a]) => Value::Vec4(DVec4::new(r.as_real()?, g.as_real()?, b.as_real()?, a.as_real()?)),
+			("length", [Value::Vec2(vector)]) => Value::from_f64(vector.length()),
+			("length", [Value::Vec4(vector)]) => Value::from_f64(vector.length()),
+			("normalize", [Value::Vec2(vector)]) => Value::Vec2(vector.normalize_or_zero()),
+			("normalize", [Value::Vec4(vector)]) => Value::Vec4(vector.normalize_or_zero()),
+			("dot", [Value::Vec2(a), Value::Vec2(b)]) => Value::from_f64(a.dot(*b)),
+			("dot", [Value::Vec4(a), Value::Vec4(b)]) => Value::from_f64(a.dot(*b)),
+			("abs", [value]) => map_components(*value, f64::abs)?,
+			("floor", [value]) => map_components(*value, f64::floor)?,
+			("ceil", [value]) => map_components(*value, f64::ceil)?,
+			("round", [value]) => map_components(*value, f64::round)?,
+			("min", [a, b]) => zip_components(*a, *b, f64::min)?,
+			("max", [a, b]) => zip_components(*a, *b, f64::max)?,
+			("clamp", [value, min, max]) => zip_components(zip_components(*value, *min, f64::max)?, *max, f64::min)?,
+			("lerp", [a, b, t]) => b.binary_op(BinaryOp::Sub, *a)?.binary_op(BinaryOp::Mul, *t)?.binary_op(BinaryOp::Add, *a)?,
+			_ => return None,
+		};
+		Some(value)
+	}
+}
+
+/// Applies the function to a real number, or to every component of a vector.
+fn map_components(value: Value, function: impl Fn(f64) -> f64) -> Option<Value> {
+	match value {
+		Value::Number(Number::Real(real)) => Some(Value::from_f64(function(real))),
+		Value::Number(Number::Complex(_)) => None,
+		Value::Vec2(vector) => Some(Value::Vec2(DVec2::new(function(vector.x), function(vector.y)))),
+		Value::Vec4(vector) => Some(Value::Vec4(DVec4::new(function(vector.x), function(vector.y), function(vector.z), function(vector.w)))),
+	}
+}
+
+/// Applies the function to the pairs of components of two values of the same kind, or to every component of a vector paired with a real number.
+fn zip_components(a: Value, b: Value, function: impl Fn(f64, f64) -> f64) -> Option<Value> {
+	match (a, b) {
+		(Value::Vec2(a), Value::Vec2(b)) => Some(Value::Vec2(DVec2::new(function(a.x, b.x), function(a.y, b.y)))),
+		(Value::Vec4(a), Value::Vec4(b)) => Some(Value::Vec4(DVec4::new(function(a.x, b.x), function(a.y, b.y), function(a.z, b.z), function(a.w, b.w)))),
+		(vector, Value::Number(Number::Real(b))) => map_components(vector, |a| function(a, b)),
+		(Value::Number(Number::Real(a)), vector) => map_components(vector, |b| function(a, b)),
+		_ => None,
+	}
+}
+
+/// A type which can be given to the "A" and "B" operands of the Math node, and which its result is converted back to.
+pub trait MathOperand: Default {
+	fn to_math_value(&self) -> Value;
+
+	/// Converts the result of an expression, or returns `None` if it's a kind of value this type can't represent.
+	fn from_math_value(value: Value) -> Option<Self>;
+}
+
+impl MathOperand for f64 {
+	fn to_math_value(&self) -> Value {
+		Value::from_f64(*self)
+	}
+
+	fn from_math_value(value: Value) -> Option<Self> {
+		match value {
+			Value::Number(Number::Real(real)) => Some(real),
+			Value::Number(Number::Complex(complex)) => Some(complex.re),
+			_ => None,
 		}
-	};
+	}
+}
+
+impl MathOperand for f32 {
+	fn to_math_value(&self) -> Value {
+		Value::from_f64(*self as f64)
+	}
+
+	fn from_math_value(value: Value) -> Option<Self> {
+		f64::from_math_value(value).map(|real| real as f32)
+	}
+}
+
+impl MathOperand for DVec2 {
+	fn to_math_value(&self) -> Value {
+		Value::Vec2(*self)
+	}
+
+	fn from_math_value(value: Value) -> Option<Self> {
+		match value {
+			Value::Vec2(vector) => Some(vector),
+			Value::Number(Number::Real(real)) => Some(DVec2::splat(real)),
+			_ => None,
+		}
+	}
+}
+
+impl MathOperand for Table<Color> {
+	fn to_math_value(&self) -> Value {
+		let color = self.iter().next().map_or(Color::TRANSPARENT, |row| *row.element);
+		Value::Vec4(DVec4::new(color.r() as f64, color.g() as f64, color.b() as f64, color.a() as f64))
+	}
+
+	fn from_math_value(value: Value) -> Option<Self> {
+		let Value::Vec4(rgba) = value else { return None };
+		let rgba = rgba.as_vec4().max(Vec4::ZERO).min(Vec4::new(f32::MAX, f32::MAX, f32::MAX, 1.));
+		Some(Table::new_from_element(Color::from_rgbaf32_unchecked(rgba.x, rgba.y, rgba.z, rgba.w)))
+	}
+}
+
+/// The value of one of the variables in the Math node's expression, given by one of its variadic inputs.
+///
+/// Inputs of a type that can't be used in an expression have no value, so evaluating an expression using them fails.
+pub struct MathVariable(Option<Value>);
+
+impl FromVariadicInput for MathVariable {
+	fn from_variadic_input(output: Any<'_>) -> Self {
+		let type_id = DynAny::type_id(output.as_ref());
+
+		let value = if type_id == TypeId::of::<f64>() {
+			dyn_any::downcast::<f64>(output).ok().map(|number| number.to_math_value())
+		} else if type_id == TypeId::of::<f32>() {
+			dyn_any::downcast::<f32>(output).ok().map(|number| number.to_math_value())
+		} else if type_id == TypeId::of::<u32>() {
+			dyn_any::downcast::<u32>(output).ok().map(|number| Value::from_f64(*number as f64))
+		} else if type_id == TypeId::of::<DVec2>() {
+			dyn_any::downcast::<DVec2>(output).ok().map(|vector| vector.to_math_value())
+		} else if type_id == TypeId::of::<Color>() {
+			dyn_any::downcast::<Color>(output).ok().map(|color| Table::new_from_element(*color).to_math_value())
+		} else if type_id == TypeId::of::<Table<Color>>() {
+			dyn_any::downcast::<Table<Color>>(output).ok().map(|table| table.to_math_value())
+		} else {
+			None
+		};
+
+		Self(value)
+	}
+}
+
+impl<T: MathOperand> From<&T> for MathVariable {
+	fn from(value: &T) -> Self {
+		Self(Some(value.to_math_value()))
+	}
+}
+
+/// The names of the variables in the expression which need their own inputs on the Math node, in the order they first appear.
+///
+/// This excludes "A" and "B", which are always given by the node's operands. Returns the parsing error if the expression is invalid.
+pub fn math_expression_variables(expression: &str) -> Result<Vec<String>, String> {
+	let (node, _unit) = ast::Node::try_parse_from_str(expression).map_err(|error| error.to_string())?;
+	Ok(variadic_variables(&node).into_iter().map(String::from).collect())
+}
+
+fn variadic_variables(node: &ast::Node) -> Vec<&str> {
+	node.variables().into_iter().filter(|name| !name.eq_ignore_ascii_case("a") && !name.eq_ignore_ascii_case("b")).collect()
+}
+
+/// Calculates the expression as the Math node does, with each other variable given the value paired with its name, or returns why it can't be calculated.
+///
+/// The Properties panel shows this error, since the Math node then outputs the default value of operand A's type.
+pub fn calculate_math_expression<A: MathOperand, B: MathOperand>(expression: &str, a: &A, b: &B, variables: impl IntoIterator<Item = (String, MathVariable)>) -> Result<A, String> {
+	let (node, _unit) = ast::Node::try_parse_from_str(expression).map_err(|error| format!("Invalid expression: {error}"))?;
+
+	let variables = variables.into_iter().filter_map(|(name, MathVariable(value))| Some((name, value?))).collect();
 	let context = EvalContext::new(
 		MathNodeContext {
-			a: operand_a.to_f64().unwrap(),
-			b: operand_b.to_f64().unwrap(),
+			a: a.to_math_value(),
+			b: b.to_math_value(),
+			variables,
 		},
-		NothingMap,
+		MathNodeFunctions,
 	);
 
-	let value = match node.eval(&context) {
-		Ok(value) => value,
-		Err(e) => {
-			warn!("Expression evaluation error: {e:?}");
-			return T::from(0.).unwrap();
-		}
-	};
+	let value = node.eval(&context).map_err(|error| format!("Can't calculate the expression: {error}"))?;
+	A::from_math_value(value).ok_or_else(|| format!("The result ({value}) can't be converted to the type of operand A"))
+}
 
-	let Value::Number(num) = value;
-	match num {
-		Number::Real(val) => T::from(val).unwrap(),
-		Number::Complex(c) => T::from(c.re).unwrap(),
-	}
+/// Calculates a mathematical expression with input values "A" and "B", along with any other variables named in the expression, which are each given an input of their own.
+#[node_macro::node(category("Math: Arithmetic"), properties("math_properties"))]
+fn math<A: MathOperand, B: MathOperand>(
+	_: impl Ctx,
+	/// The value of "A" when calculating the expression.
+	#[implementations(f64, f32, DVec2, DVec2, Table<Color>, Table<Color>)]
+	operand_a: A,
+	/// A math expression that may incorporate "A", "B", and other variables, such as `sqrt(A + B) - B^2 * scale`.
+	#[default(A + B)]
+	expression: String,
+	/// The value of "B" when calculating the expression.
+	#[implementations(f64, f32, DVec2, f64, Table<Color>, f64)]
+	#[default(1.)]
+	operand_b: B,
+	/// The names of the other variables in the expression, each naming the variable input at the same position. The editor keeps these in sync with the expression.
+	variable_names: Vec<String>,
+	/// The values of the other variables in the expression, in the order of their names.
+	#[variadic]
+	variables: Vec<MathVariable>,
+) -> A {
+	calculate_math_expression(&expression, &operand_a, &operand_b, variable_names.into_iter().zip(variables)).unwrap_or_default()
 }
 
 /// The addition operation (`+`) calculates the sum of two scalar numbers or vectors.
@@ -776,9 +932,108 @@ fn gradient_table_value(_: impl Ctx, _primary: (), gradient: GradientStops) -> T
 
 /// Gets the color at the specified position along the gradient, given a position from 0 (left) to 1 (right).
 #[node_macro::node(category("Color"))]
-fn sample_gradient(_: impl Ctx, _primary: (), gradient: GradientStops, position: Fraction) -> Table<Color> {
+fn sample_gradient(
+	_: impl Ctx,
+	_primary: (),
+	gradient: GradientStops,
+	position: Fraction,
+	/// The color model that the colors between stops are interpolated through.
+	interpolation: ColorModel,
+) -> Table<Color> {
 	let position = position.clamp(0., 1.);
-	let color = gradient.evaluate(position);
+	let color = gradient.evaluate_in(position, interpolation);
+	Table::new_from_element(color)
+}
+
+/// Adds evenly spaced stops to the gradient sampled through the chosen color model, so it's drawn with that interpolation instead of the sRGB blending used by renderers.
+///
+/// Interpolating through OKLab or OKLCH avoids the muddy, desaturated midpoints of blending between distant hues.
+#[node_macro::node(category("Color"))]
+fn resample_gradient(
+	_: impl Ctx,
+	gradient: GradientStops,
+	/// The color model that the colors between stops are interpolated through.
+	#[default(ColorModel::Oklab)]
+	interpolation: ColorModel,
+	/// The number of stops added across the gradient. More stops follow the interpolation more closely.
+	#[default(16)]
+	#[hard_min(1.)]
+	#[soft_max(64.)]
+	steps: u32,
+) -> GradientStops {
+	gradient.resampled(interpolation, steps)
+}
+
+/// Constructs a color from its components in the chosen color model, such as the lightness, chroma, and hue of OKLCH.
+///
+/// Hues are measured in degrees. Colors that fall outside the sRGB gamut are clamped.
+#[node_macro::node(category("Color"))]
+fn compose_color(
+	_: impl Ctx,
+	_primary: (),
+	/// The color model that describes the meaning of the components.
+	model: ColorModel,
+	/// The red of RGB, the hue of HSL and HSV, or the lightness of the CIE and OK models.
+	first: f64,
+	/// The green of RGB, the saturation of HSL and HSV, the green-red axis of Lab, or the chroma of LCh.
+	second: f64,
+	/// The blue of RGB, the lightness of HSL, the value of HSV, the blue-yellow axis of Lab, or the hue of LCh.
+	third: f64,
+	#[default(100.)]
+	#[range((0., 100.))]
+	alpha: Percentage,
+) -> Table<Color> {
+	let color = model.color([first as f32, second as f32, third as f32, (alpha / 100.) as f32]);
+	Table::new_from_element(color)
+}
+
+/// Obtains one component of the color in the chosen color model, such as the lightness of OKLab or the hue of HSV.
+///
+/// The inverse of this node is "Compose Color". Hues are measured in degrees.
+#[node_macro::node(category("Color"))]
+fn extract_color_component(
+	_: impl Ctx,
+	color: Table<Color>,
+	/// The color model that describes the meaning of the components.
+	model: ColorModel,
+	/// The component to obtain, from the order they're listed in the name of the color model.
+	component: ColorComponent,
+) -> f64 {
+	let Some(color) = color.iter().next().map(|row| *row.element) else { return 0. };
+	let [first, second, third, alpha] = model.components(color);
+
+	let value = match component {
+		ColorComponent::First => first,
+		ColorComponent::Second => second,
+		ColorComponent::Third => third,
+		ColorComponent::Alpha => alpha,
+	};
+	value as f64
+}
+
+/// Blends between two colors by the given factor, interpolating through the chosen color model.
+///
+/// Perceptual models like OKLab give even-looking blends, while hue-based models like OKLCH travel around the color wheel.
+#[node_macro::node(category("Color"))]
+fn mix_colors(
+	_: impl Ctx,
+	_primary: (),
+	#[default(Color::BLACK)] first: Table<Color>,
+	#[default(Color::WHITE)] second: Table<Color>,
+	/// How far the blend is from the first color (0) to the second color (1).
+	#[default(0.5)]
+	factor: Fraction,
+	/// The color model that the colors are interpolated through.
+	#[default(ColorModel::Oklab)]
+	model: ColorModel,
+) -> Table<Color> {
+	let first_color = first.iter().next().map(|row| *row.element);
+	let second_color = second.iter().next().map(|row| *row.element);
+
+	let color = match (first_color, second_color) {
+		(Some(first_color), Some(second_color)) => model.interpolate(first_color, second_color, factor.clamp(0., 1.) as f32),
+		_ => return if first_color.is_some() { first } else { second },
+	};
 	Table::new_from_element(color)
 }
 
@@ -902,28 +1157,79 @@ mod test {
 
 	#[test]
 	fn test_basic_expression() {
-		let result = math((), 0., "2 + 2".to_string(), 0.);
+		let result = math((), 0., "2 + 2".to_string(), 0., Vec::new(), Vec::new());
 		assert_eq!(result, 4.);
 	}
 
 	#[test]
 	fn test_complex_expression() {
-		let result = math((), 0., "(5 * 3) + (10 / 2)".to_string(), 0.);
+		let result = math((), 0., "(5 * 3) + (10 / 2)".to_string(), 0., Vec::new(), Vec::new());
 		assert_eq!(result, 20.);
 	}
 
 	#[test]
 	fn test_default_expression() {
-		let result = math((), 0., "0".to_string(), 0.);
+		let result = math((), 0., "0".to_string(), 0., Vec::new(), Vec::new());
 		assert_eq!(result, 0.);
 	}
 
 	#[test]
 	fn test_invalid_expression() {
-		let result = math((), 0., "invalid".to_string(), 0.);
+		let result = math((), 0., "invalid".to_string(), 0., Vec::new(), Vec::new());
+		assert_eq!(result, 0.);
+
+		let result = math((), 1., "A +".to_string(), 0., Vec::new(), Vec::new());
+		assert_eq!(result, 0.);
+		assert!(math_expression_variables("A +").is_err());
+	}
+
+	#[test]
+	fn test_expression_variables() {
+		assert_eq!(math_expression_variables("a * scale + B - offset * scale").unwrap(), ["scale", "offset"]);
+
+		let names = vec!["scale".to_string(), "offset".to_string()];
+		let variables = vec![MathVariable::from(&3.), MathVariable::from(&0.5)];
+		let result = math((), 2., "a * scale + B - offset * scale".to_string(), 1., names.clone(), variables);
+		assert_eq!(result, 5.5);
+
+		// Variables are bound by name, so reordering the expression's variables doesn't swap their values
+		let variables = vec![MathVariable::from(&3.), MathVariable::from(&0.5)];
+		let result = math((), 2., "B - offset * scale + a * scale".to_string(), 1., names, variables);
+		assert_eq!(result, 5.5);
+
+		// A variable whose input has a type that can't be used in an expression fails the evaluation
+		let result = math((), 2., "a * scale".to_string(), 1., vec!["scale".to_string()], vec![MathVariable(None)]);
 		assert_eq!(result, 0.);
 	}
 
+	#[test]
+	fn test_calculation_errors() {
+		assert_eq!(calculate_math_expression("A * scale", &2., &1., [("scale".to_string(), MathVariable::from(&3.))]), Ok(6.));
+
+		let error = calculate_math_expression::<f64, f64>("A +", &2., &1., []).unwrap_err();
+		assert!(error.starts_with("Invalid expression"));
+
+		let error = calculate_math_expression::<f64, f64>("A * scale", &2., &1., []).unwrap_err();
+		assert!(error.starts_with("Can't calculate the expression"), "{error}");
+
+		let error = calculate_math_expression::<f64, f64>("vec2(A, B)", &2., &1., []).unwrap_err();
+		assert!(error.contains("can't be converted"), "{error}");
+	}
+
+	#[test]
+	fn test_vector_and_color_expressions() {
+		let result = math((), DVec2::new(1., 2.), "normalize(A * B) + vec2(1, 0)".to_string(), 2., Vec::new(), Vec::new());
+		assert!(result.abs_diff_eq(DVec2::new(1. + 1. / 5_f64.sqrt(), 2. / 5_f64.sqrt()), 1e-10));
+
+		let result = math((), DVec2::new(3., 4.), "length(A)".to_string(), 0., Vec::new(), Vec::new());
+		assert_eq!(result, DVec2::splat(5.));
+
+		let color = Table::new_from_element(Color::from_rgbaf32_unchecked(0.2, 0.4, 0.6, 1.));
+		let result = math((), color, "lerp(A, rgb(1, 1, 1), B)".to_string(), 0.5, Vec::new(), Vec::new());
+		let result = *result.iter().next().unwrap().element;
+		assert!((result.r() - 0.6).abs() < 1e-6 && (result.b() - 0.8).abs() < 1e-6 && result.a() == 1.);
+	}
+
 	#[test]
 	pub fn foo() {
 		let fnn = FnNode::new(|(a, b)| (b, a));
@@ -954,4 +1260,22 @@ mod test {
 	pub fn modulo_negative() {
 		assert_eq!(super::modulo((), -5_f64, 2_f64, false), -1_f64);
 	}
+
+	#[test]
+	fn mix_colors_through_oklab() {
+		let mix = |model| {
+			*mix_colors((), (), Table::new_from_element(Color::BLACK), Table::new_from_element(Color::WHITE), 0.5, model)
+				.iter()
+				.next()
+				.unwrap()
+				.element
+		};
+
+		// The linear light midpoint between black and white looks too bright, so the perceptual midpoint has less light
+		let linear = mix(ColorModel::LinearRgb);
+		let oklab = mix(ColorModel::Oklab);
+		assert!((linear.r() - 0.5).abs() < 1e-4);
+		assert!(oklab.r() < linear.r());
+		assert!((extract_color_component((), Table::new_from_element(oklab), ColorModel::Oklab, ColorComponent::First) - 0.5).abs() < 1e-3);
+	}
 }
@@ -1,5 +1,5 @@
 use core_types::table::{Table, TableRow, TableRowRef};
-use core_types::{Color, Ctx};
+use core_types::{Color, Ctx, parallel};
 use dyn_any::DynAny;
 use glam::{DAffine2, DVec2};
 use graphic_types::vector_types::subpath::{ManipulatorGroup, PathSegPoints, Subpath, pathseg_points};
@@ -35,7 +35,7 @@ pub enum BooleanOperation {
 }
 
 /// Combines the geometric forms of one or more closed paths into a new vector path that results from cutting or joining the paths by the chosen method.
-#[node_macro::node(category(""))]
+#[node_macro::node(category(""), disk_cache, lru_cache)]
 async fn boolean_operation<I: graphic_types::IntoGraphicTable + 'n + Send + Clone>(
 	_: impl Ctx,
 	/// The table of vector paths to perform the boolean operation on. Nested tables are automatically flattened.
@@ -52,7 +52,7 @@ async fn boolean_operation<I: graphic_types::IntoGraphicTable + 'n + Send + Clon
 	let content = content.into_graphic_table();
 
 	// The first index is the bottom of the stack
-	let mut result_vector_table = boolean_operation_on_vector_table(flatten_vector(&content).iter(), operation);
+	let mut result_vector_table = boolean_operation_on_vector_table(flatten_vector(&content).iter(), operation).await;
 
 	// Replace the transformation matrix with a mutation of the vector points themselves
 	if let Some(result_vector) = result_vector_table.iter_mut().next() {
@@ -70,13 +70,14 @@ async fn boolean_operation<I: graphic_types::IntoGraphicTable + 'n + Send + Clon
 	result_vector_table
 }
 
-fn boolean_operation_on_vector_table<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>> + Clone, boolean_operation: BooleanOperation) -> Table<Vector> {
+// Union, subtraction and intersection fold each row into the result of the rows before it, so only the difference, whose per-row overlaps are independent, is evaluated in parallel
+async fn boolean_operation_on_vector_table<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>> + Clone + Send, boolean_operation: BooleanOperation) -> Table<Vector> {
 	match boolean_operation {
 		BooleanOperation::Union => union(vector),
 		BooleanOperation::SubtractFront => subtract(vector),
 		BooleanOperation::SubtractBack => subtract(vector.rev()),
 		BooleanOperation::Intersect => intersect(vector),
-		BooleanOperation::Difference => difference(vector),
+		BooleanOperation::Difference => difference(vector).await,
 	}
 }
 
@@ -181,34 +182,23 @@ fn intersect<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>
 	result_vector_table
 }
 
-fn difference<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>> + Clone) -> Table<Vector> {
-	let mut vector_iter = vector.clone().rev();
-	let mut any_intersection = TableRow::default();
+async fn difference<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>> + Clone + Send) -> Table<Vector> {
 	let default = TableRow::default();
-	let mut second_vector = Some(vector_iter.next().unwrap_or(default.as_ref()));
-
-	// Find where all vector table row paths intersect at least once
-	while let Some(lower_vector) = second_vector {
-		let filtered_vector = vector.clone().filter(|v| *v != lower_vector).collect::<Vec<_>>().into_iter();
-		let unioned = boolean_operation_on_vector_table(filtered_vector, BooleanOperation::Union);
-		let first_row = unioned.iter().next().expect("Expected at least one row after the boolean union");
-
-		let transform_of_lower_into_space_of_upper = first_row.transform.inverse() * *lower_vector.transform;
+	let mut lower_vectors = vector.clone().rev().collect::<Vec<_>>();
+	if lower_vectors.is_empty() {
+		lower_vectors.push(default.as_ref());
+	}
 
-		let upper_path_string = to_path(first_row.element, DAffine2::IDENTITY);
-		let lower_path_string = to_path(lower_vector.element, transform_of_lower_into_space_of_upper);
-
-		#[allow(unused_unsafe)]
-		let boolean_intersection_string = unsafe { boolean_intersect(upper_path_string, lower_path_string) };
-		let mut element = from_path(&boolean_intersection_string);
-		element.style = first_row.element.style.clone();
-		let boolean_intersection_result = TableRow {
-			element,
-			transform: *first_row.transform,
-			alpha_blending: *first_row.alpha_blending,
-			source_node_id: *first_row.source_node_id,
-		};
+	// Find where each vector table row path intersects the others, which doesn't depend on the other rows' intersections
+	let intersections = parallel::join_all(lower_vectors.into_iter().map(|lower_vector| {
+		let vector = vector.clone();
+		async move { intersection_with_others(vector, lower_vector) }
+	}))
+	.await;
 
+	// Find where all vector table row paths intersect at least once
+	let mut any_intersection = TableRow::default();
+	for boolean_intersection_result in intersections {
 		let transform_of_lower_into_space_of_upper = boolean_intersection_result.transform.inverse() * any_intersection.transform;
 
 		let upper_path_string = to_path(&boolean_intersection_result.element, DAffine2::IDENTITY);
@@ -221,13 +211,33 @@ fn difference<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector
 		any_intersection.transform = boolean_intersection_result.transform;
 		any_intersection.element.style = boolean_intersection_result.element.style.clone();
 		any_intersection.alpha_blending = boolean_intersection_result.alpha_blending;
-
-		second_vector = vector_iter.next();
 	}
 
 	// Subtract the area where they intersect at least once from the union of all vector paths
-	let union = boolean_operation_on_vector_table(vector, BooleanOperation::Union);
-	boolean_operation_on_vector_table(union.iter().chain(std::iter::once(any_intersection.as_ref())), BooleanOperation::SubtractFront)
+	let union = union(vector);
+	subtract(union.iter().chain(std::iter::once(any_intersection.as_ref())))
+}
+
+fn intersection_with_others<'a>(vector: impl DoubleEndedIterator<Item = TableRowRef<'a, Vector>>, lower_vector: TableRowRef<'_, Vector>) -> TableRow<Vector> {
+	let filtered_vector = vector.filter(|v| *v != lower_vector).collect::<Vec<_>>().into_iter();
+	let unioned = union(filtered_vector);
+	let first_row = unioned.iter().next().expect("Expected at least one row after the boolean union");
+
+	let transform_of_lower_into_space_of_upper = first_row.transform.inverse() * *lower_vector.transform;
+
+	let upper_path_string = to_path(first_row.element, DAffine2::IDENTITY);
+	let lower_path_string = to_path(lower_vector.element, transform_of_lower_into_space_of_upper);
+
+	#[allow(unused_unsafe)]
+	let boolean_intersection_string = unsafe { boolean_intersect(upper_path_string, lower_path_string) };
+	let mut element = from_path(&boolean_intersection_string);
+	element.style = first_row.element.style.clone();
+	TableRow {
+		element,
+		transform: *first_row.transform,
+		alpha_blending: *first_row.alpha_blending,
+		source_node_id: *first_row.source_node_id,
+	}
 }
 
 fn flatten_vector(graphic_table: &Table<Graphic>) -> Table<Vector> {
@@ -285,7 +295,7 @@ fn flatten_vector(graphic_table: &Table<Graphic>) -> Table<Vector> {
 					}
 
 					// Recursively flatten the inner table into the output vector table
-					let unioned = boolean_operation_on_vector_table(flatten_vector(&graphic).iter(), BooleanOperation::Union);
+					let unioned = union(flatten_vector(&graphic).iter());
 
 					unioned.into_iter().collect::<Vec<_>>()
 				}
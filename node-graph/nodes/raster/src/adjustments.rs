@@ -262,7 +262,7 @@ fn brightness_contrast<T: Adjust<Color>>(
 // Some further analysis available at:
 // https://geraldbakker.nl/psnumbers/levels.html
 #[node_macro::node(category("Raster: Adjustment"), shader_node(PerPixelAdjust))]
-fn levels<T: Adjust<Color>>(
+pub(crate) fn levels<T: Adjust<Color>>(
 	_: impl Ctx,
 	#[implementations(
 		Table<Raster<CPU>>,
@@ -1,13 +1,15 @@
 use core_types::color::Color;
 use core_types::context::Ctx;
-use core_types::registry::types::PixelLength;
+use core_types::registry::types::{Angle, Percentage, PixelLength};
 use core_types::table::Table;
+use dyn_any::DynAny;
+use glam::{DAffine2, DVec2};
 use raster_types::Image;
 use raster_types::{Bitmap, BitmapMut};
 use raster_types::{CPU, Raster};
 
 /// Blurs the image with a Gaussian or box blur kernel filter.
-#[node_macro::node(category("Raster: Filter"))]
+#[node_macro::node(category("Raster: Filter"), disk_cache, lru_cache)]
 async fn blur(
 	_: impl Ctx,
 	/// The image to be blurred.
@@ -72,8 +74,205 @@ async fn median_filter(
 		.collect()
 }
 
+/// Blurs the image along a straight line, simulating a camera or subject moving during the exposure.
+#[node_macro::node(category("Raster: Filter"), lru_cache)]
+async fn motion_blur(
+	_: impl Ctx,
+	/// The image to be blurred.
+	image_frame: Table<Raster<CPU>>,
+	/// The direction of the motion, measured in document space.
+	angle: Angle,
+	/// The length of the motion streak, measured in document space.
+	#[default(20.)]
+	#[range((0., 200.))]
+	#[hard_min(0.)]
+	distance: PixelLength,
+	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
+	gamma: bool,
+) -> Table<Raster<CPU>> {
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			let document_to_pixel = document_to_pixel_transform(row.transform, &row.element);
+			let streak = document_to_pixel.transform_vector2(DVec2::from_angle(angle.to_radians()) * distance);
+
+			// Minimum streak length
+			if streak.length() < 0.5 {
+				return row;
+			}
+
+			let image = row.element.clone().into_data();
+			row.element = Raster::new_cpu(motion_blur_algorithm(image, streak, gamma));
+			row
+		})
+		.collect()
+}
+
+/// Blurs the image outward from a center point, like zooming the lens during the exposure.
+#[node_macro::node(category("Raster: Filter"), lru_cache)]
+async fn zoom_blur(
+	_: impl Ctx,
+	/// The image to be blurred.
+	image_frame: Table<Raster<CPU>>,
+	/// The point, in document space, which the streaks radiate from.
+	center: DVec2,
+	/// How far each pixel is smeared toward the center, as a percentage of its distance from the center.
+	#[default(25.)]
+	#[range((0., 100.))]
+	strength: Percentage,
+	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
+	gamma: bool,
+) -> Table<Raster<CPU>> {
+	let strength = (strength / 100.).clamp(0., 1.);
+
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			if strength <= 0. {
+				return row;
+			}
+
+			let document_to_pixel = document_to_pixel_transform(row.transform, &row.element);
+			let scale_about_center = |t: f64| DAffine2::from_translation(center) * DAffine2::from_scale(DVec2::splat(1. - strength * t)) * DAffine2::from_translation(-center);
+
+			let image = row.element.clone().into_data();
+			row.element = Raster::new_cpu(path_blur_algorithm(image, document_to_pixel, gamma, scale_about_center));
+			row
+		})
+		.collect()
+}
+
+/// Blurs the image in concentric arcs around a center point, like spinning the camera during the exposure.
+#[node_macro::node(category("Raster: Filter"), lru_cache)]
+async fn spin_blur(
+	_: impl Ctx,
+	/// The image to be blurred.
+	image_frame: Table<Raster<CPU>>,
+	/// The point, in document space, which the arcs revolve around.
+	center: DVec2,
+	/// The total angle swept by each arc.
+	#[default(10.)]
+	#[range((0., 180.))]
+	#[hard_min(0.)]
+	angle: Angle,
+	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
+	gamma: bool,
+) -> Table<Raster<CPU>> {
+	let angle = angle.to_radians();
+
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			if angle <= 0. {
+				return row;
+			}
+
+			let document_to_pixel = document_to_pixel_transform(row.transform, &row.element);
+			let rotate_about_center = |t: f64| DAffine2::from_translation(center) * DAffine2::from_angle(angle * (t - 0.5)) * DAffine2::from_translation(-center);
+
+			let image = row.element.clone().into_data();
+			row.element = Raster::new_cpu(path_blur_algorithm(image, document_to_pixel, gamma, rotate_about_center));
+			row
+		})
+		.collect()
+}
+
+/// The outline of the camera aperture, which determines the shape of out-of-focus highlights.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, DynAny, specta::Type, serde::Serialize, serde::Deserialize, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum BokehShape {
+	#[default]
+	Circle,
+	Triangle,
+	Square,
+	Pentagon,
+	Hexagon,
+	Heptagon,
+	Octagon,
+}
+
+impl BokehShape {
+	/// The number of aperture blades, or `None` for a perfectly round aperture.
+	pub fn sides(&self) -> Option<u32> {
+		match self {
+			BokehShape::Circle => None,
+			BokehShape::Triangle => Some(3),
+			BokehShape::Square => Some(4),
+			BokehShape::Pentagon => Some(5),
+			BokehShape::Hexagon => Some(6),
+			BokehShape::Heptagon => Some(7),
+			BokehShape::Octagon => Some(8),
+		}
+	}
+}
+
+/// Simulates the shallow depth of field of a camera lens, optionally varying the amount of blur per pixel using a depth map.
+#[node_macro::node(category("Raster: Filter"), lru_cache)]
+#[allow(clippy::too_many_arguments)]
+async fn lens_blur(
+	_: impl Ctx,
+	/// The image to be blurred.
+	image_frame: Table<Raster<CPU>>,
+	/// A grayscale image where black is near and white is far. When left empty, the whole image is blurred by the full radius.
+	#[expose]
+	depth_map: Table<Raster<CPU>>,
+	/// The radius of the largest out-of-focus disc.
+	#[default(10.)]
+	#[range((0., 100.))]
+	#[hard_min(0.)]
+	radius: PixelLength,
+	/// The shape of the aperture, visible in the out-of-focus highlights.
+	bokeh_shape: BokehShape,
+	/// The rotation of the aperture shape.
+	bokeh_rotation: Angle,
+	/// The depth, from near to far, that remains perfectly sharp.
+	focal_distance: Percentage,
+	/// Treat white as near and black as far in the depth map.
+	invert_depth: bool,
+	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
+	gamma: bool,
+) -> Table<Raster<CPU>> {
+	// TODO: Figure out what it means to support multiple depth map rows?
+	let depth_map = depth_map.into_iter().next().filter(|row| row.element.width > 0 && row.element.height > 0);
+	let focal_distance = (focal_distance / 100.).clamp(0., 1.) as f32;
+
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			let document_to_pixel = document_to_pixel_transform(row.transform, &row.element);
+			let pixel_to_document = document_to_pixel.inverse();
+			let pixel_radius = document_to_pixel.transform_vector2(DVec2::new(radius, 0.)).length();
+
+			// Minimum blur radius
+			if pixel_radius < 0.5 {
+				return row;
+			}
+
+			// Blur amount for each pixel in the range 0-1, sampled from the depth map's location in document space
+			let (width, height) = (row.element.width, row.element.height);
+			let blur_amounts = depth_map.as_ref().map(|depth_map| {
+				let document_to_depth_pixel = document_to_pixel_transform(depth_map.transform, &depth_map.element);
+				let mut amounts = Vec::with_capacity((width * height) as usize);
+				for y in 0..height {
+					for x in 0..width {
+						let document_point = pixel_to_document.transform_point2(DVec2::new(x as f64 + 0.5, y as f64 + 0.5));
+						let depth = depth_map.element.sample(document_to_depth_pixel.transform_point2(document_point)).luminance_srgb();
+						let depth = if invert_depth { 1. - depth } else { depth };
+						amounts.push((depth - focal_distance).abs());
+					}
+				}
+				amounts
+			});
+
+			let image = row.element.clone().into_data();
+			row.element = Raster::new_cpu(lens_blur_algorithm(image, pixel_radius, bokeh_shape, bokeh_rotation.to_radians(), blur_amounts.as_deref(), gamma));
+			row
+		})
+		.collect()
+}
+
 // 1D gaussian kernel
-fn gaussian_kernel(radius: f64) -> Vec<f64> {
+pub(crate) fn gaussian_kernel(radius: f64) -> Vec<f64> {
 	// Given radius, compute the size of the kernel that's approximately three times the radius
 	let kernel_radius = (3. * radius).ceil() as usize;
 	let kernel_size = 2 * kernel_radius + 1;
@@ -210,6 +409,198 @@ fn box_blur_algorithm(mut original_buffer: Image<Color>, radius: f64, gamma: boo
 	y_axis
 }
 
+/// The transform from document space into the pixel coordinates of the given image, where each pixel spans one unit.
+fn document_to_pixel_transform(transform: DAffine2, image: &Raster<CPU>) -> DAffine2 {
+	DAffine2::from_scale(DVec2::new(image.width as f64, image.height as f64)) * transform.inverse()
+}
+
+/// Samples the image at the given pixel coordinate with bilinear interpolation, clamping to the nearest edge pixel outside the bounds.
+fn sample_bilinear(image: &Image<Color>, position: DVec2) -> Color {
+	let max = DVec2::new(image.width as f64 - 1., image.height as f64 - 1.);
+	let position = (position - DVec2::splat(0.5)).clamp(DVec2::ZERO, max);
+
+	let (x0, y0) = (position.x.floor() as u32, position.y.floor() as u32);
+	let (x1, y1) = ((x0 + 1).min(image.width - 1), (y0 + 1).min(image.height - 1));
+	let (tx, ty) = ((position.x - x0 as f64) as f32, (position.y - y0 as f64) as f32);
+
+	let [top_left, top_right, bottom_left, bottom_right] = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)].map(|(x, y)| image.get_pixel(x, y).unwrap_or_default());
+	top_left.lerp(&top_right, tx).lerp(&bottom_left.lerp(&bottom_right, tx), ty)
+}
+
+// The upper limit on samples taken per pixel by the path-based blurs, to keep very long streaks from stalling the render
+const MAX_PATH_SAMPLES: u32 = 256;
+
+fn motion_blur_algorithm(mut original_buffer: Image<Color>, streak: DVec2, gamma: bool) -> Image<Color> {
+	if gamma {
+		original_buffer.map_pixels(|px| px.to_gamma_srgb().to_associated_alpha(px.a()));
+	} else {
+		original_buffer.map_pixels(|px| px.to_associated_alpha(px.a()));
+	}
+
+	let (width, height) = original_buffer.dimensions();
+	let mut output = Image::new(width, height, Color::TRANSPARENT);
+
+	// Sample at roughly one pixel intervals along the streak, centered on each pixel
+	let samples = (streak.length().ceil() as u32 + 1).clamp(2, MAX_PATH_SAMPLES);
+	let weight = 1. / samples as f32;
+
+	for y in 0..height {
+		for x in 0..width {
+			let pixel_center = DVec2::new(x as f64 + 0.5, y as f64 + 0.5);
+
+			let (mut r_sum, mut g_sum, mut b_sum, mut a_sum) = (0., 0., 0., 0.);
+			for i in 0..samples {
+				let t = i as f64 / (samples - 1) as f64 - 0.5;
+				let px = sample_bilinear(&original_buffer, pixel_center + streak * t);
+				r_sum += px.r() * weight;
+				g_sum += px.g() * weight;
+				b_sum += px.b() * weight;
+				a_sum += px.a() * weight;
+			}
+
+			output.set_pixel(x, y, Color::from_rgbaf32_unchecked(r_sum, g_sum, b_sum, a_sum));
+		}
+	}
+
+	if gamma {
+		output.map_pixels(|px| px.to_linear_srgb().to_unassociated_alpha());
+	} else {
+		output.map_pixels(|px| px.to_unassociated_alpha());
+	}
+
+	output
+}
+
+/// Averages samples taken along a path through document space, where `path_at` returns the transform moving a pixel's document space position to its location at path progress `t` in the range 0-1.
+fn path_blur_algorithm(mut original_buffer: Image<Color>, document_to_pixel: DAffine2, gamma: bool, path_at: impl Fn(f64) -> DAffine2) -> Image<Color> {
+	if gamma {
+		original_buffer.map_pixels(|px| px.to_gamma_srgb().to_associated_alpha(px.a()));
+	} else {
+		original_buffer.map_pixels(|px| px.to_associated_alpha(px.a()));
+	}
+
+	let (width, height) = original_buffer.dimensions();
+	let mut output = Image::new(width, height, Color::TRANSPARENT);
+
+	// Compose each step of the path into a single transform in pixel space
+	let pixel_to_document = document_to_pixel.inverse();
+	let path_start = document_to_pixel * path_at(0.) * pixel_to_document;
+	let path_end = document_to_pixel * path_at(1.) * pixel_to_document;
+	let path_steps = (0..MAX_PATH_SAMPLES)
+		.map(|i| document_to_pixel * path_at(i as f64 / (MAX_PATH_SAMPLES - 1) as f64) * pixel_to_document)
+		.collect::<Vec<_>>();
+
+	for y in 0..height {
+		for x in 0..width {
+			let pixel_center = DVec2::new(x as f64 + 0.5, y as f64 + 0.5);
+
+			// Take roughly one sample per pixel traveled, approximating the path length by the distance between its endpoints and midpoint
+			let (start, end) = (path_start.transform_point2(pixel_center), path_end.transform_point2(pixel_center));
+			let middle = path_steps[path_steps.len() / 2].transform_point2(pixel_center);
+			let path_length = start.distance(middle) + middle.distance(end);
+			let samples = (path_length.ceil() as u32 + 1).clamp(2, MAX_PATH_SAMPLES);
+			let weight = 1. / samples as f32;
+
+			let (mut r_sum, mut g_sum, mut b_sum, mut a_sum) = (0., 0., 0., 0.);
+			for i in 0..samples {
+				let step = i as usize * (MAX_PATH_SAMPLES - 1) as usize / (samples - 1) as usize;
+				let px = sample_bilinear(&original_buffer, path_steps[step].transform_point2(pixel_center));
+				r_sum += px.r() * weight;
+				g_sum += px.g() * weight;
+				b_sum += px.b() * weight;
+				a_sum += px.a() * weight;
+			}
+
+			output.set_pixel(x, y, Color::from_rgbaf32_unchecked(r_sum, g_sum, b_sum, a_sum));
+		}
+	}
+
+	if gamma {
+		output.map_pixels(|px| px.to_linear_srgb().to_unassociated_alpha());
+	} else {
+		output.map_pixels(|px| px.to_unassociated_alpha());
+	}
+
+	output
+}
+
+/// Distance of the offset from the aperture center, normalized so the aperture's outline lies at 1.
+fn bokeh_distance(offset: DVec2, shape: BokehShape, rotation: f64) -> f64 {
+	let distance = offset.length();
+	let Some(sides) = shape.sides() else { return distance };
+
+	// The radius of a regular polygon with a circumradius of 1, in the direction of the offset
+	let sector = std::f64::consts::TAU / sides as f64;
+	let angle = (offset.y.atan2(offset.x) - rotation - std::f64::consts::FRAC_PI_2).rem_euclid(sector);
+	let polygon_radius = (sector / 2.).cos() / (angle - sector / 2.).cos();
+
+	distance / polygon_radius
+}
+
+// The upper limit on samples taken per pixel by the lens blur, which would otherwise grow with the square of the radius
+const MAX_LENS_SAMPLES: u32 = 4096;
+
+fn lens_blur_algorithm(mut original_buffer: Image<Color>, radius: f64, shape: BokehShape, rotation: f64, blur_amounts: Option<&[f32]>, gamma: bool) -> Image<Color> {
+	if gamma {
+		original_buffer.map_pixels(|px| px.to_gamma_srgb().to_associated_alpha(px.a()));
+	} else {
+		original_buffer.map_pixels(|px| px.to_associated_alpha(px.a()));
+	}
+
+	let (width, height) = original_buffer.dimensions();
+	let mut output = Image::new(width, height, Color::TRANSPARENT);
+
+	// Every offset within the aperture at its full radius, paired with its distance in pixels, sorted so smaller apertures use a prefix.
+	// Large apertures sample a sparser grid of offsets so the kernel never exceeds the sample limit.
+	let kernel_radius = radius.ceil() as i32;
+	let stride = ((2 * kernel_radius + 1) as f64 / (MAX_LENS_SAMPLES as f64).sqrt()).ceil().max(1.) as i32;
+	let grid_radius = kernel_radius / stride;
+	let mut kernel = (-grid_radius..=grid_radius)
+		.flat_map(|y| (-grid_radius..=grid_radius).map(move |x| (x * stride, y * stride)))
+		.map(|(x, y)| ((x, y), bokeh_distance(DVec2::new(x as f64, y as f64), shape, rotation)))
+		.filter(|&(_, distance)| distance <= radius)
+		.collect::<Vec<_>>();
+	kernel.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+	for y in 0..height {
+		for x in 0..width {
+			let pixel_radius = blur_amounts.map_or(radius, |amounts| amounts[(x + y * width) as usize] as f64 * radius);
+			let kernel_end = kernel.partition_point(|&(_, distance)| distance <= pixel_radius.max(0.5));
+
+			let (mut r_sum, mut g_sum, mut b_sum, mut a_sum, mut weight_sum) = (0., 0., 0., 0., 0.);
+			for &((dx, dy), _) in &kernel[..kernel_end] {
+				let (sample_x, sample_y) = (x as i32 + dx, y as i32 + dy);
+				if sample_x < 0 || sample_y < 0 || sample_x >= width as i32 || sample_y >= height as i32 {
+					continue;
+				}
+
+				if let Some(px) = original_buffer.get_pixel(sample_x as u32, sample_y as u32) {
+					r_sum += px.r() as f64;
+					g_sum += px.g() as f64;
+					b_sum += px.b() as f64;
+					a_sum += px.a() as f64;
+					weight_sum += 1.;
+				}
+			}
+
+			let px = if weight_sum > 0. {
+				Color::from_rgbaf32_unchecked((r_sum / weight_sum) as f32, (g_sum / weight_sum) as f32, (b_sum / weight_sum) as f32, (a_sum / weight_sum) as f32)
+			} else {
+				original_buffer.get_pixel(x, y).unwrap_or_default()
+			};
+			output.set_pixel(x, y, px);
+		}
+	}
+
+	if gamma {
+		output.map_pixels(|px| px.to_linear_srgb().to_unassociated_alpha());
+	} else {
+		output.map_pixels(|px| px.to_unassociated_alpha());
+	}
+
+	output
+}
+
 fn median_filter_algorithm(original_buffer: Image<Color>, radius: u32) -> Image<Color> {
 	let (width, height) = original_buffer.dimensions();
 	let mut output = Image::new(width, height, Color::TRANSPARENT);
@@ -262,3 +653,86 @@ fn median_quickselect(values: &mut [f32]) -> f32 {
 	// Use total_cmp for safe NaN handling instead of partial_cmp().unwrap()
 	*values.select_nth_unstable_by(mid, |a, b| a.total_cmp(b)).1
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn flat_image(color: Color) -> Table<Raster<CPU>> {
+		let (width, height) = (16, 12);
+		let mut image_frame = Table::new_from_element(Raster::new_cpu(Image::new(width, height, color)));
+		*image_frame.get_mut(0).unwrap().transform = DAffine2::from_scale(DVec2::new(width as f64, height as f64));
+		image_frame
+	}
+
+	/// An image with a distinct color at each pixel, so any blurring is detectable.
+	fn gradient_image() -> Table<Raster<CPU>> {
+		let (width, height) = (16, 12);
+		let data = (0..width * height)
+			.map(|index| {
+				let (x, y) = (index % width, index / width);
+				Color::from_rgbaf32_unchecked(x as f32 / width as f32, y as f32 / height as f32, ((x * y) % 7) as f32 / 7., 1.)
+			})
+			.collect();
+
+		let mut image_frame = Table::new_from_element(Raster::new_cpu(Image {
+			width,
+			height,
+			data,
+			base64_string: None,
+		}));
+		*image_frame.get_mut(0).unwrap().transform = DAffine2::from_scale(DVec2::new(width as f64, height as f64));
+		image_frame
+	}
+
+	fn pixels(image_frame: &Table<Raster<CPU>>) -> Vec<Color> {
+		image_frame.iter().next().unwrap().element.data.clone()
+	}
+
+	fn assert_flat(image_frame: &Table<Raster<CPU>>, color: Color) {
+		for px in pixels(image_frame) {
+			let difference = [px.r() - color.r(), px.g() - color.g(), px.b() - color.b(), px.a() - color.a()];
+			assert!(difference.iter().all(|channel| channel.abs() < 1e-4), "Expected {color:?}, got {px:?}");
+		}
+	}
+
+	#[tokio::test]
+	async fn zero_amount_leaves_image_unchanged() {
+		let expected = pixels(&gradient_image());
+		let center = DVec2::new(8., 6.);
+
+		assert_eq!(pixels(&motion_blur((), gradient_image(), 30., 0., false).await), expected);
+		assert_eq!(pixels(&zoom_blur((), gradient_image(), center, 0., false).await), expected);
+		assert_eq!(pixels(&spin_blur((), gradient_image(), center, 0., false).await), expected);
+		assert_eq!(pixels(&lens_blur((), gradient_image(), Table::default(), 0., BokehShape::Circle, 0., 0., false, false).await), expected);
+	}
+
+	#[tokio::test]
+	async fn blurs_preserve_flat_image() {
+		let color = Color::from_rgbaf32_unchecked(0.2, 0.5, 0.8, 1.);
+		let center = DVec2::new(4., 3.);
+
+		assert_flat(&motion_blur((), flat_image(color), 30., 10., false).await, color);
+		assert_flat(&zoom_blur((), flat_image(color), center, 50., false).await, color);
+		assert_flat(&spin_blur((), flat_image(color), center, 45., false).await, color);
+		assert_flat(&lens_blur((), flat_image(color), Table::default(), 5., BokehShape::Hexagon, 0., 0., false, false).await, color);
+	}
+
+	#[tokio::test]
+	async fn blurs_change_detailed_image() {
+		let original = pixels(&gradient_image());
+		let center = DVec2::new(4., 3.);
+
+		assert_ne!(pixels(&motion_blur((), gradient_image(), 30., 10., false).await), original);
+		assert_ne!(pixels(&zoom_blur((), gradient_image(), center, 50., false).await), original);
+		assert_ne!(pixels(&spin_blur((), gradient_image(), center, 45., false).await), original);
+		assert_ne!(pixels(&lens_blur((), gradient_image(), Table::default(), 3., BokehShape::Circle, 0., 0., false, false).await), original);
+	}
+
+	#[tokio::test]
+	async fn lens_blur_caps_kernel_size() {
+		// A radius far beyond the image still averages a bounded number of offsets, and keeps a flat image flat
+		let color = Color::from_rgbaf32_unchecked(0.3, 0.3, 0.3, 1.);
+		assert_flat(&lens_blur((), flat_image(color), Table::default(), 10_000., BokehShape::Circle, 0., 0., false, false).await, color);
+	}
+}
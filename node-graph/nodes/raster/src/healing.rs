@@ -0,0 +1,468 @@
+use core_types::color::Color;
+use core_types::context::Ctx;
+use core_types::table::Table;
+use dyn_any::DynAny;
+use glam::{DAffine2, DVec2, IVec2};
+use raster_types::Image;
+use raster_types::{CPU, Raster};
+use std::hash::{Hash, Hasher};
+
+/// The number of directions searched around a blemish for a source area to heal it from.
+const SOURCE_SEARCH_DIRECTIONS: usize = 16;
+/// The distances, as multiples of the blemish's size, searched for a source area to heal it from.
+const SOURCE_SEARCH_DISTANCES: [f64; 3] = [1.25, 1.75, 2.5];
+/// The width in pixels of the band around a blemish whose colors are compared against those around each candidate source area.
+const SOURCE_SEARCH_BAND: i32 = 3;
+/// The limit on conjugate gradient iterations spent solving for the seamless blend of a region.
+const SOLVER_MAX_ITERATIONS: usize = 2000;
+
+/// A stroke painted by the Healing tool over a blemish, which is replaced by texture from a nearby area that is found automatically.
+#[derive(Clone, Debug, PartialEq, Default, DynAny, serde::Serialize, serde::Deserialize)]
+pub struct HealStroke {
+	/// The points along the stroke in layer space.
+	pub trace: Vec<DVec2>,
+	/// The diameter of the stroke in layer space.
+	pub diameter: f64,
+}
+
+impl Hash for HealStroke {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.trace.len().hash(state);
+		self.trace.iter().for_each(|point| {
+			point.x.to_bits().hash(state);
+			point.y.to_bits().hash(state);
+		});
+		self.diameter.to_bits().hash(state);
+	}
+}
+
+/// A region selected with the Patch tool and dragged onto the source area whose texture replaces it.
+#[derive(Clone, Debug, PartialEq, Default, DynAny, serde::Serialize, serde::Deserialize)]
+pub struct PatchRegion {
+	/// The closed outline of the region being repaired, in layer space.
+	pub outline: Vec<DVec2>,
+	/// The displacement in layer space from the region being repaired to the source area it was dragged onto.
+	pub source_offset: DVec2,
+}
+
+impl Hash for PatchRegion {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.outline.len().hash(state);
+		self.outline.iter().for_each(|point| {
+			point.x.to_bits().hash(state);
+			point.y.to_bits().hash(state);
+		});
+		self.source_offset.x.to_bits().hash(state);
+		self.source_offset.y.to_bits().hash(state);
+	}
+}
+
+/// The pixels of an image covered by a region being repaired, stored within the region's bounding box.
+struct RegionMask {
+	/// The top left pixel of the bounding box.
+	min: IVec2,
+	/// The pixel just beyond the bottom right of the bounding box.
+	max: IVec2,
+	coverage: Vec<f32>,
+}
+
+impl RegionMask {
+	/// Rasterizes the region by evaluating the coverage at the layer space center of each pixel within the layer space bounds.
+	fn new(image: &Image<Color>, pixel_to_layer: DAffine2, layer_bounds: [DVec2; 2], coverage_at: impl Fn(DVec2) -> f32) -> Option<Self> {
+		let [start, end] = layer_bounds;
+		let corners = [start, end, DVec2::new(start.x, end.y), DVec2::new(end.x, start.y)].map(|corner| pixel_to_layer.inverse().transform_point2(corner));
+		let min = corners.into_iter().reduce(DVec2::min)?.floor().as_ivec2().max(IVec2::ZERO);
+		let max = corners.into_iter().reduce(DVec2::max)?.ceil().as_ivec2().min(IVec2::new(image.width as i32, image.height as i32));
+		if min.x >= max.x || min.y >= max.y {
+			return None;
+		}
+
+		let coverage = (min.y..max.y)
+			.flat_map(|y| (min.x..max.x).map(move |x| IVec2::new(x, y)))
+			.map(|pixel| coverage_at(pixel_to_layer.transform_point2(pixel.as_dvec2() + 0.5)))
+			.collect::<Vec<_>>();
+		if coverage.iter().all(|&coverage| coverage <= 0.) {
+			return None;
+		}
+
+		Some(Self { min, max, coverage })
+	}
+
+	fn from_stroke(image: &Image<Color>, pixel_to_layer: DAffine2, stroke: &HealStroke) -> Option<Self> {
+		let radius = stroke.diameter / 2.;
+		let start = stroke.trace.iter().copied().reduce(DVec2::min)? - radius;
+		let end = stroke.trace.iter().copied().reduce(DVec2::max)? + radius;
+
+		// Antialias the edge across the width of one pixel
+		let pixel_size = (pixel_to_layer.matrix2.x_axis.length() + pixel_to_layer.matrix2.y_axis.length()) / 2.;
+
+		Self::new(image, pixel_to_layer, [start, end], |point| {
+			let distance = match stroke.trace.as_slice() {
+				[only] => only.distance(point),
+				trace => trace.windows(2).map(|segment| distance_to_segment(point, segment[0], segment[1])).fold(f64::INFINITY, f64::min),
+			};
+			((radius - distance) / pixel_size + 0.5).clamp(0., 1.) as f32
+		})
+	}
+
+	fn from_outline(image: &Image<Color>, pixel_to_layer: DAffine2, outline: &[DVec2]) -> Option<Self> {
+		if outline.len() < 3 {
+			return None;
+		}
+		let start = outline.iter().copied().reduce(DVec2::min)?;
+		let end = outline.iter().copied().reduce(DVec2::max)?;
+
+		Self::new(image, pixel_to_layer, [start, end], |point| if point_in_polygon(point, outline) { 1. } else { 0. })
+	}
+
+	fn size(&self) -> IVec2 {
+		self.max - self.min
+	}
+
+	fn coverage(&self, pixel: IVec2) -> f32 {
+		let local = pixel - self.min;
+		let size = self.size();
+		if local.x < 0 || local.y < 0 || local.x >= size.x || local.y >= size.y {
+			return 0.;
+		}
+		self.coverage[(local.y * size.x + local.x) as usize]
+	}
+
+	fn pixels(&self) -> impl Iterator<Item = IVec2> + '_ {
+		(self.min.y..self.max.y).flat_map(move |y| (self.min.x..self.max.x).map(move |x| IVec2::new(x, y)))
+	}
+}
+
+fn distance_to_segment(point: DVec2, start: DVec2, end: DVec2) -> f64 {
+	let direction = end - start;
+	let length_squared = direction.length_squared();
+	if length_squared == 0. {
+		return point.distance(start);
+	}
+	let t = ((point - start).dot(direction) / length_squared).clamp(0., 1.);
+	point.distance(start + direction * t)
+}
+
+/// Tests whether the point is inside the polygon using the even-odd rule.
+pub fn point_in_polygon(point: DVec2, polygon: &[DVec2]) -> bool {
+	let mut inside = false;
+	for (index, &current) in polygon.iter().enumerate() {
+		let previous = polygon[(index + polygon.len() - 1) % polygon.len()];
+		if (current.y > point.y) != (previous.y > point.y) && point.x < (previous.x - current.x) * (point.y - current.y) / (previous.y - current.y) + current.x {
+			inside = !inside;
+		}
+	}
+	inside
+}
+
+fn pixel(image: &Image<Color>, pixel: IVec2) -> Option<Color> {
+	if pixel.x < 0 || pixel.y < 0 || pixel.x >= image.width as i32 || pixel.y >= image.height as i32 {
+		return None;
+	}
+	Some(image.data[pixel.y as usize * image.width as usize + pixel.x as usize])
+}
+
+fn channels(color: Color) -> [f64; 4] {
+	[color.r() as f64, color.g() as f64, color.b() as f64, color.a() as f64]
+}
+
+/// Finds the offset to the area around a region whose surroundings best match those of the region, so its texture can be used to heal the region.
+///
+/// Candidate areas lie in a ring of directions and distances around the region, far enough away that they don't overlap it.
+fn find_healing_source(image: &Image<Color>, region: &RegionMask) -> Option<IVec2> {
+	let size = region.size().max_element() as f64;
+	let image_size = IVec2::new(image.width as i32, image.height as i32);
+
+	// The band of pixels surrounding the region, which are compared against those surrounding each candidate
+	let band_min = region.min - SOURCE_SEARCH_BAND;
+	let band_max = region.max + SOURCE_SEARCH_BAND;
+	let band = (band_min.y..band_max.y)
+		.flat_map(|y| (band_min.x..band_max.x).map(move |x| IVec2::new(x, y)))
+		.filter(|&pixel| region.coverage(pixel) <= 0.)
+		.filter_map(|position| pixel(image, position).map(|color| (position, channels(color))))
+		.collect::<Vec<_>>();
+
+	let candidates = SOURCE_SEARCH_DISTANCES.into_iter().flat_map(|distance| {
+		(0..SOURCE_SEARCH_DIRECTIONS).map(move |direction| {
+			let angle = direction as f64 / SOURCE_SEARCH_DIRECTIONS as f64 * std::f64::consts::TAU;
+			(DVec2::from_angle(angle) * (distance * size + SOURCE_SEARCH_BAND as f64)).round().as_ivec2()
+		})
+	});
+
+	candidates
+		// The whole source area must lie within the image
+		.filter(|&offset| (region.min + offset).cmpge(IVec2::ZERO).all() && (region.max + offset).cmple(image_size).all())
+		.map(|offset| {
+			let (difference, count) = band.iter().fold((0., 0_usize), |(difference, count), &(position, destination)| match pixel(image, position + offset) {
+				Some(source) => {
+					let source = channels(source);
+					let squared = (0..4).map(|channel| (destination[channel] - source[channel]).powi(2)).sum::<f64>();
+					(difference + squared, count + 1)
+				}
+				None => (difference, count),
+			});
+			(offset, if count == 0 { f64::INFINITY } else { difference / count as f64 })
+		})
+		.filter(|(_, cost)| cost.is_finite())
+		.min_by(|(_, a), (_, b)| a.total_cmp(b))
+		.map(|(offset, _)| offset)
+}
+
+/// Replaces the region with the pixels at the offset source area, blended seamlessly into its surroundings.
+///
+/// This solves the Poisson equation over the region so the result keeps the gradients (the texture) of the source area,
+/// while matching the colors of the pixels around the region's border so the lighting and color of the destination carry through.
+/// The equivalent problem that is solved is for a smooth correction membrane added to the source, which interpolates the
+/// difference between the destination and source along the border.
+fn poisson_blend(image: &mut Image<Color>, region: &RegionMask, offset: IVec2) {
+	let original = image.clone();
+	let source = |position: IVec2| pixel(&original, position + offset).or_else(|| pixel(&original, position)).map(channels).unwrap_or_default();
+
+	// Number the unknown pixels covered by the region
+	let size = region.size();
+	let mut indices = vec![None; (size.x * size.y) as usize];
+	let mut unknowns = Vec::new();
+	for position in region.pixels() {
+		if region.coverage(position) > 0. {
+			let local = position - region.min;
+			indices[(local.y * size.x + local.x) as usize] = Some(unknowns.len());
+			unknowns.push(position);
+		}
+	}
+	let index_of = |position: IVec2| {
+		let local = position - region.min;
+		if local.x < 0 || local.y < 0 || local.x >= size.x || local.y >= size.y {
+			return None;
+		}
+		indices[(local.y * size.x + local.x) as usize]
+	};
+
+	// Assemble the discrete Laplacian, whose neighbors outside the image are left out so the image edge acts as a free boundary,
+	// and the right hand side of the border's difference between the destination and source
+	let neighbor_offsets = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+	let mut neighbors = Vec::with_capacity(unknowns.len());
+	let mut degrees = Vec::with_capacity(unknowns.len());
+	let mut rhs = vec![[0.; 4]; unknowns.len()];
+	for (index, &position) in unknowns.iter().enumerate() {
+		let mut unknown_neighbors = [None; 4];
+		let mut degree = 0.;
+		for (neighbor_index, neighbor_offset) in neighbor_offsets.into_iter().enumerate() {
+			let neighbor = position + neighbor_offset;
+			let Some(destination) = pixel(&original, neighbor) else { continue };
+			degree += 1.;
+
+			match index_of(neighbor) {
+				Some(unknown) => unknown_neighbors[neighbor_index] = Some(unknown),
+				None => {
+					let (destination, source) = (channels(destination), source(neighbor));
+					(0..4).for_each(|channel| rhs[index][channel] += destination[channel] - source[channel]);
+				}
+			}
+		}
+		neighbors.push(unknown_neighbors);
+		degrees.push(degree);
+	}
+
+	let laplacian = |vector: &[f64], result: &mut [f64]| {
+		for index in 0..vector.len() {
+			let neighbor_sum: f64 = neighbors[index].iter().flatten().map(|&neighbor| vector[neighbor]).sum();
+			result[index] = degrees[index] * vector[index] - neighbor_sum;
+		}
+	};
+
+	let mut corrections = vec![[0.; 4]; unknowns.len()];
+	for channel in 0..4 {
+		let rhs = rhs.iter().map(|values| values[channel]).collect::<Vec<_>>();
+		let solution = conjugate_gradient(laplacian, &rhs);
+		corrections.iter_mut().zip(solution).for_each(|(correction, value)| correction[channel] = value);
+	}
+
+	for (&position, correction) in unknowns.iter().zip(corrections) {
+		let source = source(position);
+		let healed = [0, 1, 2, 3].map(|channel| source[channel] + correction[channel]);
+
+		let coverage = region.coverage(position) as f64;
+		let destination = channels(original.data[position.y as usize * image.width as usize + position.x as usize]);
+		let [r, g, b, a] = [0, 1, 2, 3].map(|channel| destination[channel] + (healed[channel] - destination[channel]) * coverage);
+
+		// Keep the premultiplied color channels within the alpha
+		let a = a.clamp(0., 1.) as f32;
+		let [r, g, b] = [r, g, b].map(|channel| (channel as f32).clamp(0., a));
+		image.data[position.y as usize * image.width as usize + position.x as usize] = Color::from_rgbaf32_unchecked(r, g, b, a);
+	}
+}
+
+/// Solves the symmetric positive definite system given by the matrix-vector product function, starting from zero.
+fn conjugate_gradient(matrix: impl Fn(&[f64], &mut [f64]), rhs: &[f64]) -> Vec<f64> {
+	let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(a, b)| a * b).sum::<f64>();
+
+	let mut solution = vec![0.; rhs.len()];
+	let mut residual = rhs.to_vec();
+	let mut direction = residual.clone();
+	let mut product = vec![0.; rhs.len()];
+	let mut residual_squared = dot(&residual, &residual);
+	let tolerance = 1e-10 * dot(rhs, rhs).max(f64::MIN_POSITIVE);
+
+	for _ in 0..SOLVER_MAX_ITERATIONS {
+		if residual_squared <= tolerance {
+			break;
+		}
+
+		matrix(&direction, &mut product);
+		let step = residual_squared / dot(&direction, &product);
+		solution.iter_mut().zip(&direction).for_each(|(value, direction)| *value += step * direction);
+		residual.iter_mut().zip(&product).for_each(|(value, product)| *value -= step * product);
+
+		let next_residual_squared = dot(&residual, &residual);
+		let ratio = next_residual_squared / residual_squared;
+		direction.iter_mut().zip(&residual).for_each(|(direction, residual)| *direction = residual + ratio * *direction);
+		residual_squared = next_residual_squared;
+	}
+
+	solution
+}
+
+/// Applies the repair to every image in the table, with the region given in layer space.
+fn repair_each_image(image_frame: Table<Raster<CPU>>, repair: impl Fn(&mut Image<Color>, DAffine2)) -> Table<Raster<CPU>> {
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			let mut image = row.element.clone().into_data();
+			if image.width == 0 || image.height == 0 {
+				return row;
+			}
+
+			let pixel_to_layer = row.transform * DAffine2::from_scale(DVec2::new(1. / image.width as f64, 1. / image.height as f64));
+			repair(&mut image, pixel_to_layer);
+
+			row.element = Raster::new_cpu(image);
+			row
+		})
+		.collect()
+}
+
+/// Removes blemishes painted over by the Healing tool, replacing each with texture from a nearby area that best matches its surroundings.
+///
+/// The texture is blended into the color and lighting around each blemish so the repair is seamless.
+#[node_macro::node(category("Raster"))]
+async fn spot_healing(
+	_: impl Ctx,
+	/// The image to be repaired.
+	image_frame: Table<Raster<CPU>>,
+	/// The strokes painted by the Healing tool over the blemishes to be removed.
+	strokes: Vec<HealStroke>,
+) -> Table<Raster<CPU>> {
+	if strokes.is_empty() {
+		return image_frame;
+	}
+
+	repair_each_image(image_frame, |image, pixel_to_layer| {
+		for stroke in &strokes {
+			let Some(region) = RegionMask::from_stroke(image, pixel_to_layer, stroke) else { continue };
+			let Some(offset) = find_healing_source(image, &region) else { continue };
+			poisson_blend(image, &region, offset);
+		}
+	})
+}
+
+/// Repairs the regions selected with the Patch tool, replacing each with texture from the source area it was dragged onto.
+///
+/// The texture is blended into the color and lighting around each region so the repair is seamless.
+#[node_macro::node(category("Raster"))]
+async fn patch(
+	_: impl Ctx,
+	/// The image to be repaired.
+	image_frame: Table<Raster<CPU>>,
+	/// The regions selected with the Patch tool, each with the offset to its source area.
+	patches: Vec<PatchRegion>,
+) -> Table<Raster<CPU>> {
+	if patches.is_empty() {
+		return image_frame;
+	}
+
+	repair_each_image(image_frame, |image, pixel_to_layer| {
+		for patch in &patches {
+			let Some(region) = RegionMask::from_outline(image, pixel_to_layer, &patch.outline) else {
+				continue;
+			};
+			let offset = pixel_to_layer.inverse().transform_vector2(patch.source_offset).round().as_ivec2();
+			poisson_blend(image, &region, offset);
+		}
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// An image whose brightness ramps from left to right, with a dark blemish in a square around (8, 8).
+	fn blemished_ramp() -> Table<Raster<CPU>> {
+		let (width, height) = (32, 16);
+		let data = (0..width * height)
+			.map(|index| {
+				let (x, y) = (index % width, index / width);
+				if (6..11).contains(&x) && (6..11).contains(&y) {
+					return Color::BLACK;
+				}
+				let value = 0.2 + 0.5 * x as f32 / width as f32;
+				Color::from_rgbaf32_unchecked(value, value, value, 1.)
+			})
+			.collect();
+
+		let mut image_frame = Table::new_from_element(Raster::new_cpu(Image {
+			width,
+			height,
+			data,
+			base64_string: None,
+		}));
+		*image_frame.get_mut(0).unwrap().transform = DAffine2::from_scale(DVec2::new(width as f64, height as f64));
+		image_frame
+	}
+
+	fn ramp_value(x: u32) -> f32 {
+		0.2 + 0.5 * x as f32 / 32.
+	}
+
+	#[test]
+	fn poisson_blend_matches_surrounding_lighting() {
+		// Copying a brighter area of the ramp over the blemish restores the ramp, since the lighting comes from the region's surroundings
+		let mut image = blemished_ramp().iter().next().unwrap().element.data().clone();
+		let outline = [DVec2::new(5., 5.), DVec2::new(12., 5.), DVec2::new(12., 12.), DVec2::new(5., 12.)];
+		let region = RegionMask::from_outline(&image, DAffine2::IDENTITY, &outline).unwrap();
+
+		poisson_blend(&mut image, &region, IVec2::new(16, 0));
+
+		for x in 5..12 {
+			let value = image.data[8 * 32 + x as usize].r();
+			assert!((value - ramp_value(x)).abs() < 0.01, "Expected {} at x = {x}, got {value}", ramp_value(x));
+		}
+	}
+
+	#[tokio::test]
+	async fn spot_healing_removes_blemish() {
+		let stroke = HealStroke {
+			trace: vec![DVec2::new(8.5, 8.5)],
+			diameter: 9.,
+		};
+		let result = spot_healing((), blemished_ramp(), vec![stroke]).await;
+		let image = result.iter().next().unwrap().element;
+
+		let value = image.data[8 * 32 + 8].r();
+		assert!((value - ramp_value(8)).abs() < 0.02, "Expected the blemish to be healed to {}, got {value}", ramp_value(8));
+	}
+
+	#[tokio::test]
+	async fn patch_replaces_region_from_source() {
+		let patch_region = PatchRegion {
+			outline: vec![DVec2::new(5., 5.), DVec2::new(12., 5.), DVec2::new(12., 12.), DVec2::new(5., 12.)],
+			source_offset: DVec2::new(12., 0.),
+		};
+		let result = patch((), blemished_ramp(), vec![patch_region]).await;
+		let image = result.iter().next().unwrap().element;
+
+		let value = image.data[8 * 32 + 8].r();
+		assert!((value - ramp_value(8)).abs() < 0.01, "Expected the region to be patched to {}, got {value}", ramp_value(8));
+		// Pixels outside of the region are unchanged
+		assert_eq!(image.data[2 * 32 + 2].r(), ramp_value(2));
+	}
+}
@@ -0,0 +1,252 @@
+use crate::adjust::Adjust;
+use crate::adjustments::levels;
+use core_types::color::Color;
+use core_types::context::Ctx;
+use core_types::registry::types::{Percentage, PercentageF32};
+use core_types::table::Table;
+use dyn_any::DynAny;
+use raster_types::{CPU, Raster};
+
+/// The channel of an image that is measured when computing its statistics.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, DynAny, specta::Type, serde::Serialize, serde::Deserialize, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum HistogramChannel {
+	#[default]
+	Luminance,
+	Red,
+	Green,
+	Blue,
+	Alpha,
+}
+
+impl HistogramChannel {
+	/// Reads the measured value (in the range 0-1) of this channel from a pixel, or `None` if the pixel shouldn't be counted.
+	///
+	/// Color channels are measured in gamma (sRGB) space, matching the values shown by the Levels node, and fully transparent pixels are skipped.
+	pub fn value(self, pixel: Color) -> Option<f32> {
+		if self != HistogramChannel::Alpha && pixel.a() == 0. {
+			return None;
+		}
+
+		let color = pixel.to_gamma_srgb();
+		let value = match self {
+			HistogramChannel::Luminance => color.luminance_srgb(),
+			HistogramChannel::Red => color.r(),
+			HistogramChannel::Green => color.g(),
+			HistogramChannel::Blue => color.b(),
+			HistogramChannel::Alpha => color.a(),
+		};
+		Some(value.clamp(0., 1.))
+	}
+}
+
+/// Summary statistics of one channel of an image, with values in the range 0-1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelStatistics {
+	pub mean: f64,
+	pub minimum: f64,
+	pub maximum: f64,
+	/// The number of pixels which contributed to the statistics.
+	pub count: usize,
+}
+
+/// Iterates over the pixels of all the images in the table.
+pub fn table_pixels(image: &Table<Raster<CPU>>) -> impl Iterator<Item = Color> + '_ {
+	image.iter().flat_map(|row| row.element.data.iter().copied())
+}
+
+/// Counts how many of the pixels fall into each of `bins` equally sized buckets spanning 0-1.
+pub fn histogram_counts(pixels: impl IntoIterator<Item = Color>, channel: HistogramChannel, bins: usize) -> Vec<u64> {
+	let bins = bins.max(1);
+	let mut counts = vec![0; bins];
+
+	for value in pixels.into_iter().filter_map(|pixel| channel.value(pixel)) {
+		let bin = ((value * bins as f32) as usize).min(bins - 1);
+		counts[bin] += 1;
+	}
+
+	counts
+}
+
+/// Computes the mean, minimum, and maximum of a channel across the pixels, or `None` if no pixels were counted.
+pub fn channel_statistics(pixels: impl IntoIterator<Item = Color>, channel: HistogramChannel) -> Option<ChannelStatistics> {
+	let mut sum = 0.;
+	let mut minimum = f64::INFINITY;
+	let mut maximum = f64::NEG_INFINITY;
+	let mut count = 0;
+
+	for value in pixels.into_iter().filter_map(|pixel| channel.value(pixel)) {
+		let value = value as f64;
+		sum += value;
+		minimum = minimum.min(value);
+		maximum = maximum.max(value);
+		count += 1;
+	}
+
+	(count > 0).then(|| ChannelStatistics {
+		mean: sum / count as f64,
+		minimum,
+		maximum,
+		count,
+	})
+}
+
+/// The number of levels that percentiles are resolved to, which is finer than any 8- or 12-bit source.
+const PERCENTILE_LEVELS: usize = 4096;
+
+/// Finds the value (in the range 0-1) below which the given fraction (0-1) of the pixels lie in the channel.
+pub fn channel_percentile(pixels: impl IntoIterator<Item = Color>, channel: HistogramChannel, fraction: f64) -> Option<f64> {
+	percentile_of_counts(&histogram_counts(pixels, channel, PERCENTILE_LEVELS), fraction)
+}
+
+/// Finds the value (in the range 0-1) below which the given fraction (0-1) of the counts in the histogram lie, or `None` if the histogram is empty.
+pub fn percentile_of_counts(counts: &[u64], fraction: f64) -> Option<f64> {
+	let total: u64 = counts.iter().sum();
+	if total == 0 {
+		return None;
+	}
+
+	let target = (fraction.clamp(0., 1.) * total as f64).ceil().max(1.) as u64;
+	let mut accumulated = 0;
+	let bin = counts
+		.iter()
+		.position(|&count| {
+			accumulated += count;
+			accumulated >= target
+		})
+		.unwrap_or(counts.len() - 1);
+
+	Some((bin as f64 + 0.5) / counts.len() as f64)
+}
+
+/// Computes the histogram of a channel of the image, as the fraction of the counted pixels which fall into each of the equally sized bins spanning 0-1.
+///
+/// Fully transparent pixels are not counted, except when measuring the alpha channel.
+#[node_macro::node(category("Raster: Measure"))]
+fn histogram(
+	_: impl Ctx,
+	image: Table<Raster<CPU>>,
+	channel: HistogramChannel,
+	/// The number of buckets that the range of values is divided into.
+	#[default(256)]
+	#[hard_min(1.)]
+	#[soft_max(1024.)]
+	bins: u32,
+) -> Vec<f64> {
+	let counts = histogram_counts(table_pixels(&image), channel, bins as usize);
+	let total = counts.iter().sum::<u64>().max(1) as f64;
+
+	counts.into_iter().map(|count| count as f64 / total).collect()
+}
+
+/// The average value (0-1) of a channel across the image.
+#[node_macro::node(category("Raster: Measure"))]
+fn image_mean(_: impl Ctx, image: Table<Raster<CPU>>, channel: HistogramChannel) -> f64 {
+	channel_statistics(table_pixels(&image), channel).map_or(0., |statistics| statistics.mean)
+}
+
+/// The darkest value (0-1) of a channel across the image.
+#[node_macro::node(category("Raster: Measure"))]
+fn image_minimum(_: impl Ctx, image: Table<Raster<CPU>>, channel: HistogramChannel) -> f64 {
+	channel_statistics(table_pixels(&image), channel).map_or(0., |statistics| statistics.minimum)
+}
+
+/// The brightest value (0-1) of a channel across the image.
+#[node_macro::node(category("Raster: Measure"))]
+fn image_maximum(_: impl Ctx, image: Table<Raster<CPU>>, channel: HistogramChannel) -> f64 {
+	channel_statistics(table_pixels(&image), channel).map_or(0., |statistics| statistics.maximum)
+}
+
+/// The value (0-1) of a channel below which the given percentage of the image's pixels lie. For example, 50% gives the median.
+#[node_macro::node(category("Raster: Measure"))]
+fn image_percentile(
+	_: impl Ctx,
+	image: Table<Raster<CPU>>,
+	channel: HistogramChannel,
+	#[default(50.)]
+	#[range((0., 100.))]
+	percentile: Percentage,
+) -> f64 {
+	channel_percentile(table_pixels(&image), channel, percentile / 100.).unwrap_or(0.)
+}
+
+/// Stretches the tonal range of the image so its darkest and brightest values span the full range, by feeding the image's measured statistics into Levels.
+///
+/// When "Per Channel" is enabled, the red, green, and blue channels are each stretched independently, which also neutralizes color casts.
+#[node_macro::node(category("Raster: Adjustment"))]
+fn auto_levels(
+	_: impl Ctx,
+	mut image: Table<Raster<CPU>>,
+	/// Stretch each color channel independently instead of stretching the luminance of the whole image.
+	per_channel: bool,
+	/// The percentage of the darkest pixels which are allowed to be clipped to black, so outliers don't limit the stretch.
+	#[default(0.1)]
+	#[range((0., 10.))]
+	clip_shadows: Percentage,
+	/// The percentage of the brightest pixels which are allowed to be clipped to white, so outliers don't limit the stretch.
+	#[default(0.1)]
+	#[range((0., 10.))]
+	clip_highlights: Percentage,
+) -> Table<Raster<CPU>> {
+	let range_of = |channel| {
+		let counts = histogram_counts(table_pixels(&image), channel, PERCENTILE_LEVELS);
+		let shadows = percentile_of_counts(&counts, clip_shadows / 100.)?;
+		let highlights = percentile_of_counts(&counts, 1. - clip_highlights / 100.)?;
+		(highlights > shadows).then_some(((shadows * 100.) as PercentageF32, (highlights * 100.) as PercentageF32))
+	};
+
+	if !per_channel {
+		let Some((shadows, highlights)) = range_of(HistogramChannel::Luminance) else { return image };
+		return levels((), image, shadows, 50., highlights, 0., 100.);
+	}
+
+	let ranges = [HistogramChannel::Red, HistogramChannel::Green, HistogramChannel::Blue].map(range_of);
+	if ranges.iter().all(Option::is_none) {
+		return image;
+	}
+
+	let stretch = |color: Color, range: Option<(PercentageF32, PercentageF32)>| match range {
+		Some((shadows, highlights)) => levels((), color, shadows, 50., highlights, 0., 100.),
+		None => color,
+	};
+	image.adjust(|&color| {
+		let red = stretch(color, ranges[0]).r();
+		let green = stretch(color, ranges[1]).g();
+		let blue = stretch(color, ranges[2]).b();
+		Color::from_rgbaf32_unchecked(red, green, blue, color.a())
+	});
+	image
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use raster_types::Image;
+
+	fn gradient_image() -> Table<Raster<CPU>> {
+		let data = (0..256).map(|i| Color::from_luminance(i as f32 / 255.).to_linear_srgb()).collect();
+		Table::new_from_element(Raster::new_cpu(Image {
+			width: 256,
+			height: 1,
+			data,
+			base64_string: None,
+		}))
+	}
+
+	#[test]
+	fn histogram_and_statistics_of_gradient() {
+		let image = gradient_image();
+
+		let histogram = histogram((), image.clone(), HistogramChannel::Red, 4);
+		assert_eq!(histogram.len(), 4);
+		assert!(histogram.iter().all(|&fraction| (fraction - 0.25).abs() < 0.01), "{histogram:?}");
+
+		let statistics = channel_statistics(table_pixels(&image), HistogramChannel::Luminance).unwrap();
+		assert!(statistics.minimum < 0.001);
+		assert!(statistics.maximum > 0.999);
+		assert!((statistics.mean - 0.5).abs() < 0.01);
+
+		let median = channel_percentile(table_pixels(&image), HistogramChannel::Green, 0.5).unwrap();
+		assert!((median - 0.5).abs() < 0.01);
+	}
+}
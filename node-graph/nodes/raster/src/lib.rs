@@ -21,6 +21,16 @@ pub mod generate_curves;
 #[cfg(feature = "std")]
 pub mod gradient_map;
 #[cfg(feature = "std")]
+pub mod healing;
+#[cfg(feature = "std")]
 pub mod image_color_palette;
 #[cfg(feature = "std")]
+pub mod image_statistics;
+#[cfg(feature = "std")]
+pub mod morphology;
+#[cfg(feature = "std")]
+pub mod selection;
+#[cfg(feature = "std")]
+pub mod soft_proof;
+#[cfg(feature = "std")]
 pub mod std_nodes;
@@ -0,0 +1,464 @@
+use crate::filter::gaussian_kernel;
+use core_types::color::Color;
+use core_types::context::Ctx;
+use core_types::registry::types::{Percentage, PixelLength};
+use core_types::table::Table;
+use dyn_any::DynAny;
+use raster_types::Image;
+use raster_types::{Bitmap, BitmapMut};
+use raster_types::{CPU, Raster};
+
+/// The morphological operation to apply to each pixel's neighborhood.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, DynAny, specta::Type, serde::Serialize, serde::Deserialize, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum MorphologyOperation {
+	/// Grows bright regions by taking the maximum of each neighborhood.
+	#[default]
+	Dilate,
+	/// Shrinks bright regions by taking the minimum of each neighborhood.
+	Erode,
+	/// Erodes then dilates, removing bright specks smaller than the radius while keeping the size of larger regions.
+	Open,
+	/// Dilates then erodes, filling dark gaps smaller than the radius while keeping the size of larger regions.
+	Close,
+}
+
+/// The shape of the neighborhood considered around each pixel.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, DynAny, specta::Type, serde::Serialize, serde::Deserialize, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum MorphologyShape {
+	#[default]
+	Circle,
+	Square,
+	Diamond,
+}
+
+/// The channel which determines whether a pixel is considered inside of a mask.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, DynAny, specta::Type, serde::Serialize, serde::Deserialize, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum MaskChannel {
+	#[default]
+	Alpha,
+	Luminance,
+}
+
+impl MaskChannel {
+	fn coverage(&self, color: Color) -> f32 {
+		match self {
+			MaskChannel::Alpha => color.a(),
+			MaskChannel::Luminance => color.luminance_srgb(),
+		}
+	}
+}
+
+/// Grows, shrinks, or cleans up the bright regions of a mask using mathematical morphology on every channel.
+#[node_macro::node(category("Raster: Filter"), lru_cache)]
+async fn morphology(
+	_: impl Ctx,
+	/// The image or mask to be modified.
+	image_frame: Table<Raster<CPU>>,
+	/// Whether to grow or shrink the bright regions, or remove small features by combining both.
+	operation: MorphologyOperation,
+	/// The distance each edge is moved by.
+	#[default(5.)]
+	#[range((0., 100.))]
+	#[hard_min(0.)]
+	#[hard_max(100.)]
+	radius: PixelLength,
+	/// The shape that corners are rounded into as edges grow.
+	shape: MorphologyShape,
+) -> Table<Raster<CPU>> {
+	let radius = radius.round() as u32;
+
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			if radius == 0 {
+				return row;
+			}
+
+			let image = row.element.clone().into_data();
+			let image = match operation {
+				MorphologyOperation::Dilate => morphology_algorithm(image, radius, shape, f32::max),
+				MorphologyOperation::Erode => morphology_algorithm(image, radius, shape, f32::min),
+				MorphologyOperation::Open => morphology_algorithm(morphology_algorithm(image, radius, shape, f32::min), radius, shape, f32::max),
+				MorphologyOperation::Close => morphology_algorithm(morphology_algorithm(image, radius, shape, f32::max), radius, shape, f32::min),
+			};
+
+			row.element = Raster::new_cpu(image);
+			row
+		})
+		.collect()
+}
+
+/// Converts a mask into a grayscale signed distance field, where 50% gray lies on the mask's edge and brightness increases further inside.
+/// Thresholding, levels, or a gradient map applied to the result can then produce outlines, glows, and bevels.
+#[node_macro::node(category("Raster: Filter"), lru_cache)]
+async fn distance_field(
+	_: impl Ctx,
+	/// The mask to measure distances from.
+	image_frame: Table<Raster<CPU>>,
+	/// The channel that determines which pixels are inside the mask.
+	channel: MaskChannel,
+	/// The coverage above which a pixel is considered inside the mask.
+	#[default(50.)]
+	#[range((0., 100.))]
+	threshold: Percentage,
+	/// The distance from the edge at which the field reaches pure white inside, and pure black outside.
+	#[default(20.)]
+	#[range((1., 200.))]
+	#[hard_min(0.001)]
+	spread: PixelLength,
+) -> Table<Raster<CPU>> {
+	let threshold = (threshold / 100.) as f32;
+
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			let image = row.element.data();
+			let inside = image.data.iter().map(|&color| channel.coverage(color) >= threshold).collect::<Vec<_>>();
+
+			let distances = signed_distance_transform(&inside, image.width, image.height);
+			let data = distances
+				.into_iter()
+				.map(|distance| Color::from_luminance((0.5 + distance / (2. * spread)).clamp(0., 1.) as f32))
+				.collect();
+
+			row.element = Raster::new_cpu(Image {
+				width: image.width,
+				height: image.height,
+				data,
+				base64_string: None,
+			});
+			row
+		})
+		.collect()
+}
+
+/// Softens the edges of the image by blurring its alpha channel, leaving the colors of visible pixels unchanged.
+#[node_macro::node(category("Raster: Filter"), lru_cache)]
+async fn feather(
+	_: impl Ctx,
+	/// The image whose edges should be softened.
+	image_frame: Table<Raster<CPU>>,
+	/// The radius of the soft transition across each edge.
+	#[default(5.)]
+	#[range((0., 100.))]
+	#[hard_min(0.)]
+	radius: PixelLength,
+) -> Table<Raster<CPU>> {
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			// Minimum feather radius
+			if radius < 0.1 {
+				return row;
+			}
+
+			let image = row.element.clone().into_data();
+			row.element = Raster::new_cpu(feather_algorithm(image, radius));
+			row
+		})
+		.collect()
+}
+
+fn morphology_algorithm(original_buffer: Image<Color>, radius: u32, shape: MorphologyShape, combine: fn(f32, f32) -> f32) -> Image<Color> {
+	let (width, height) = original_buffer.dimensions();
+	let radius = radius as i32;
+
+	let combine_colors = |a: Color, b: Color| Color::from_rgbaf32_unchecked(combine(a.r(), b.r()), combine(a.g(), b.g()), combine(a.b(), b.b()), combine(a.a(), b.a()));
+
+	// A square neighborhood is separable into a horizontal then a vertical pass
+	if shape == MorphologyShape::Square {
+		let mut x_axis = Image::new(width, height, Color::TRANSPARENT);
+		let mut y_axis = Image::new(width, height, Color::TRANSPARENT);
+
+		for pass in [false, true] {
+			let (max, old_buffer, current_buffer) = match pass {
+				false => (width, &original_buffer, &mut x_axis),
+				true => (height, &x_axis, &mut y_axis),
+			};
+			let pass = pass as usize;
+
+			for y in 0..height {
+				for x in 0..width {
+					let i = [x, y][pass] as i32;
+					let mut result = old_buffer.get_pixel(x, y).unwrap_or_default();
+					for d in (i - radius).max(0)..=(i + radius).min(max as i32 - 1) {
+						if let Some(px) = old_buffer.get_pixel([d as u32, x][pass], [y, d as u32][pass]) {
+							result = combine_colors(result, px);
+						}
+					}
+					current_buffer.set_pixel(x, y, result);
+				}
+			}
+		}
+
+		return y_axis;
+	}
+
+	// For other shapes, each row of the neighborhood spans a horizontal extent depending on its distance from the center
+	let row_extents = (-radius..=radius)
+		.map(|dy| {
+			let extent = match shape {
+				MorphologyShape::Circle => ((radius * radius - dy * dy) as f64).sqrt().floor() as i32,
+				MorphologyShape::Diamond => radius - dy.abs(),
+				MorphologyShape::Square => radius,
+			};
+			(dy, extent)
+		})
+		.collect::<Vec<_>>();
+
+	let mut output = Image::new(width, height, Color::TRANSPARENT);
+	for y in 0..height as i32 {
+		for x in 0..width as i32 {
+			let mut result = original_buffer.get_pixel(x as u32, y as u32).unwrap_or_default();
+
+			for &(dy, extent) in &row_extents {
+				let sample_y = y + dy;
+				if sample_y < 0 || sample_y >= height as i32 {
+					continue;
+				}
+
+				for sample_x in (x - extent).max(0)..=(x + extent).min(width as i32 - 1) {
+					if let Some(px) = original_buffer.get_pixel(sample_x as u32, sample_y as u32) {
+						result = combine_colors(result, px);
+					}
+				}
+			}
+
+			output.set_pixel(x as u32, y as u32, result);
+		}
+	}
+
+	output
+}
+
+// A stand-in for infinity that keeps the squared distance arithmetic below free of NaNs
+const FAR_AWAY: f64 = 1e20;
+
+/// Computes the exact squared Euclidean distance from each sample to its nearest feature, given squared distances of 0 at features and [`FAR_AWAY`] elsewhere.
+///
+/// Algorithm from "Distance Transforms of Sampled Functions" by Felzenszwalb and Huttenlocher:
+/// <https://cs.brown.edu/people/pfelzens/papers/dt-final.pdf>
+fn squared_distance_transform_1d(f: &[f64], output: &mut [f64], parabola_locations: &mut [usize], boundaries: &mut [f64]) {
+	let n = f.len();
+	if n == 0 {
+		return;
+	}
+
+	// Build the lower envelope of the parabolas rooted at each sample
+	let mut k = 0;
+	parabola_locations[0] = 0;
+	boundaries[0] = -f64::INFINITY;
+	boundaries[1] = f64::INFINITY;
+	for q in 1..n {
+		let intersection = |v: usize| ((f[q] + (q * q) as f64) - (f[v] + (v * v) as f64)) / (2. * q as f64 - 2. * v as f64);
+
+		let mut s = intersection(parabola_locations[k]);
+		while s <= boundaries[k] {
+			k -= 1;
+			s = intersection(parabola_locations[k]);
+		}
+
+		k += 1;
+		parabola_locations[k] = q;
+		boundaries[k] = s;
+		boundaries[k + 1] = f64::INFINITY;
+	}
+
+	// Read off the lowest parabola at each sample
+	k = 0;
+	for (q, output) in output.iter_mut().enumerate() {
+		while boundaries[k + 1] < q as f64 {
+			k += 1;
+		}
+		let v = parabola_locations[k];
+		*output = (q as f64 - v as f64).powi(2) + f[v];
+	}
+}
+
+/// The Euclidean distance in pixels from each pixel to the nearest pixel where `features` is true.
+fn distance_transform(features: &[bool], width: u32, height: u32) -> Vec<f64> {
+	let (width, height) = (width as usize, height as usize);
+	let longest = width.max(height);
+
+	let mut grid = features.iter().map(|&feature| if feature { 0. } else { FAR_AWAY }).collect::<Vec<_>>();
+
+	// Reused scratch buffers for each line
+	let mut line = vec![0.; longest];
+	let mut transformed = vec![0.; longest];
+	let mut parabola_locations = vec![0; longest];
+	let mut boundaries = vec![0.; longest + 1];
+
+	// Columns
+	for x in 0..width {
+		for y in 0..height {
+			line[y] = grid[x + y * width];
+		}
+		squared_distance_transform_1d(&line[..height], &mut transformed[..height], &mut parabola_locations, &mut boundaries);
+		for y in 0..height {
+			grid[x + y * width] = transformed[y];
+		}
+	}
+
+	// Rows
+	for y in 0..height {
+		let row = &mut grid[y * width..(y + 1) * width];
+		line[..width].copy_from_slice(row);
+		squared_distance_transform_1d(&line[..width], &mut transformed[..width], &mut parabola_locations, &mut boundaries);
+		row.copy_from_slice(&transformed[..width]);
+	}
+
+	grid.into_iter().map(f64::sqrt).collect()
+}
+
+/// The distance in pixels from each pixel's center to the mask's edge, positive inside the mask and negative outside.
+fn signed_distance_transform(inside: &[bool], width: u32, height: u32) -> Vec<f64> {
+	let outside = inside.iter().map(|inside| !inside).collect::<Vec<_>>();
+	let distance_to_inside = distance_transform(inside, width, height);
+	let distance_to_outside = distance_transform(&outside, width, height);
+
+	// The edge lies halfway between the centers of neighboring inside and outside pixels
+	inside
+		.iter()
+		.zip(distance_to_inside.into_iter().zip(distance_to_outside))
+		.map(|(&inside, (to_inside, to_outside))| if inside { to_outside - 0.5 } else { -(to_inside - 0.5) })
+		.collect()
+}
+
+fn feather_algorithm(mut image: Image<Color>, radius: f64) -> Image<Color> {
+	let (width, height) = image.dimensions();
+	let (width, height) = (width as usize, height as usize);
+
+	let kernel = gaussian_kernel(radius);
+	let half_kernel = kernel.len() / 2;
+
+	// Blur with associated alpha so fully transparent pixels pick up the colors of the edge they now overlap
+	let mut channels = image
+		.data
+		.iter()
+		.map(|color| [color.r() * color.a(), color.g() * color.a(), color.b() * color.a(), color.a()].map(|c| c as f64))
+		.collect::<Vec<_>>();
+	let mut blurred = vec![[0.; 4]; channels.len()];
+
+	for pass in [false, true] {
+		let (max, stride_along, stride_across, count_across) = match pass {
+			false => (width, 1, width, height),
+			true => (height, width, 1, width),
+		};
+
+		for across in 0..count_across {
+			for along in 0..max {
+				let (mut sum, mut weight_sum) = ([0.; 4], 0.);
+				for (i, &weight) in kernel.iter().enumerate() {
+					let p = along as i64 + i as i64 - half_kernel as i64;
+					if p >= 0 && p < max as i64 {
+						let channels = channels[across * stride_across + p as usize * stride_along];
+						sum.iter_mut().zip(channels).for_each(|(sum, channel)| *sum += channel * weight);
+						weight_sum += weight;
+					}
+				}
+				blurred[across * stride_across + along * stride_along] = if weight_sum > 0. { sum.map(|sum| sum / weight_sum) } else { [0.; 4] };
+			}
+		}
+
+		std::mem::swap(&mut channels, &mut blurred);
+	}
+
+	// Keep the original colors wherever they were visible and only take the feathered alpha
+	for (color, [r, g, b, a]) in image.data.iter_mut().zip(channels) {
+		let alpha = a as f32;
+		*color = if color.a() > 0. || a <= 0. {
+			color.with_alpha(alpha)
+		} else {
+			Color::from_rgbaf32_unchecked((r / a) as f32, (g / a) as f32, (b / a) as f32, alpha)
+		};
+	}
+
+	image
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// A 5x5 image which is transparent except for the opaque white pixels in the given square around its center.
+	fn centered_mask(half_size: u32) -> Image<Color> {
+		let mut image = Image::new(5, 5, Color::TRANSPARENT);
+		for y in 2 - half_size..=2 + half_size {
+			for x in 2 - half_size..=2 + half_size {
+				image.set_pixel(x, y, Color::WHITE);
+			}
+		}
+		image
+	}
+
+	fn opaque_pixels(image: &Image<Color>) -> Vec<(u32, u32)> {
+		let (width, height) = image.dimensions();
+		(0..height)
+			.flat_map(|y| (0..width).map(move |x| (x, y)))
+			.filter(|&(x, y)| image.get_pixel(x, y).is_some_and(|color| color.a() == 1.))
+			.collect()
+	}
+
+	#[test]
+	fn dilate_grows_the_mask_into_the_shape() {
+		let square = morphology_algorithm(centered_mask(0), 1, MorphologyShape::Square, f32::max);
+		assert_eq!(opaque_pixels(&square), opaque_pixels(&centered_mask(1)));
+
+		let diamond = morphology_algorithm(centered_mask(0), 1, MorphologyShape::Diamond, f32::max);
+		assert_eq!(opaque_pixels(&diamond), vec![(2, 1), (1, 2), (2, 2), (3, 2), (2, 3)]);
+
+		// Rows of a circle with a radius of 2 extend 0, 1, 2, 1, and 0 pixels to each side
+		let circle = morphology_algorithm(centered_mask(0), 2, MorphologyShape::Circle, f32::max);
+		assert_eq!(opaque_pixels(&circle).len(), 1 + 3 + 5 + 3 + 1);
+	}
+
+	#[test]
+	fn erode_shrinks_the_mask() {
+		let eroded = morphology_algorithm(centered_mask(1), 1, MorphologyShape::Square, f32::min);
+		assert_eq!(opaque_pixels(&eroded), vec![(2, 2)]);
+
+		// Opening removes the features smaller than the radius entirely
+		let opened = morphology_algorithm(morphology_algorithm(centered_mask(0), 1, MorphologyShape::Square, f32::min), 1, MorphologyShape::Square, f32::max);
+		assert!(opaque_pixels(&opened).is_empty());
+	}
+
+	#[test]
+	fn feather_softens_alpha_and_keeps_colors() {
+		let mut image = Image::new(9, 9, Color::TRANSPARENT);
+		image.set_pixel(4, 4, Color::RED);
+
+		let feathered = feather_algorithm(image, 2.);
+		let center = feathered.get_pixel(4, 4).unwrap();
+		let neighbor = feathered.get_pixel(5, 4).unwrap();
+		assert!(center.a() < 1. && neighbor.a() > 0. && neighbor.a() < center.a());
+
+		// The previously transparent pixels take the color of the edge they now overlap
+		assert_eq!((center.r(), center.g()), (1., 0.));
+		assert!((neighbor.r() - 1.).abs() < 1e-5 && neighbor.g() == 0.);
+	}
+
+	#[test]
+	fn distance_transform_measures_euclidean_distance() {
+		// A single feature pixel in the top left corner of a 4x3 grid
+		let mut features = vec![false; 12];
+		features[0] = true;
+
+		let distances = distance_transform(&features, 4, 3);
+		assert_eq!(distances[0], 0.);
+		assert_eq!(distances[3], 3.);
+		assert_eq!(distances[8], 2.);
+		assert!((distances[11] - 13_f64.sqrt()).abs() < 1e-9);
+	}
+
+	#[test]
+	fn signed_distance_is_positive_inside() {
+		// The left half of a 4x1 strip is inside
+		let inside = [true, true, false, false];
+
+		let distances = signed_distance_transform(&inside, 4, 1);
+		assert_eq!(distances, vec![1.5, 0.5, -0.5, -1.5]);
+	}
+}
@@ -0,0 +1,826 @@
+use core_types::color::{Color, Luminance};
+use core_types::context::Ctx;
+use core_types::registry::types::Percentage;
+use core_types::table::{Table, TableRow};
+use dyn_any::DynAny;
+use glam::{DAffine2, DVec2, IVec2};
+use graphic_types::Vector;
+use raster_types::Bitmap;
+use raster_types::Image;
+use raster_types::{CPU, Raster};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use vector_types::subpath::Subpath;
+use vector_types::vector::PointId;
+
+/// The number of rows sampled within each pixel when anti-aliasing the edges of outlines.
+const ANTI_ALIASING_SUBSAMPLES: usize = 4;
+/// The maximum distance in pixels between a curve and the straight segments it's flattened into.
+const FLATTEN_TOLERANCE: f64 = 0.1;
+
+/// The region of an image added to or removed from a pixel selection by one step, in layer space.
+#[derive(Clone, Debug, PartialEq, DynAny, serde::Serialize, serde::Deserialize)]
+pub enum SelectionShape {
+	/// Every pixel of the image.
+	All,
+	/// The unit square mapped by the transform.
+	Rectangle { transform: DAffine2 },
+	/// The circle inscribed in the unit square mapped by the transform.
+	Ellipse { transform: DAffine2 },
+	/// Closed outlines, such as one drawn with a lasso or flattened from a vector path, filled with the even-odd rule.
+	Polygons { outlines: Vec<Vec<DVec2>> },
+	/// The pixels whose colors are within the tolerance (a percentage) of the color at the seed point.
+	/// If contiguous, only pixels connected to the seed point through other such pixels are selected.
+	MagicWand { seed: DVec2, tolerance: f64, contiguous: bool },
+}
+
+impl Hash for SelectionShape {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		core::mem::discriminant(self).hash(state);
+		match self {
+			SelectionShape::All => {}
+			SelectionShape::Rectangle { transform } | SelectionShape::Ellipse { transform } => transform.to_cols_array().iter().for_each(|value| value.to_bits().hash(state)),
+			SelectionShape::Polygons { outlines } => {
+				outlines.len().hash(state);
+				outlines.iter().for_each(|outline| {
+					outline.len().hash(state);
+					outline.iter().for_each(|point| {
+						point.x.to_bits().hash(state);
+						point.y.to_bits().hash(state);
+					});
+				});
+			}
+			SelectionShape::MagicWand { seed, tolerance, contiguous } => {
+				seed.x.to_bits().hash(state);
+				seed.y.to_bits().hash(state);
+				tolerance.to_bits().hash(state);
+				contiguous.hash(state);
+			}
+		}
+	}
+}
+
+impl SelectionShape {
+	/// Maps the shape from its current space into another.
+	pub fn transformed(&self, transform: DAffine2) -> Self {
+		match self {
+			SelectionShape::All => SelectionShape::All,
+			SelectionShape::Rectangle { transform: shape_transform } => SelectionShape::Rectangle {
+				transform: transform * *shape_transform,
+			},
+			SelectionShape::Ellipse { transform: shape_transform } => SelectionShape::Ellipse {
+				transform: transform * *shape_transform,
+			},
+			SelectionShape::Polygons { outlines } => SelectionShape::Polygons {
+				outlines: outlines.iter().map(|outline| outline.iter().map(|&point| transform.transform_point2(point)).collect()).collect(),
+			},
+			&SelectionShape::MagicWand { seed, tolerance, contiguous } => SelectionShape::MagicWand {
+				seed: transform.transform_point2(seed),
+				tolerance,
+				contiguous,
+			},
+		}
+	}
+
+	/// The closed outlines of the shape, or `None` if the shape depends on the image it selects from.
+	pub fn outlines(&self) -> Option<Vec<Vec<DVec2>>> {
+		match self {
+			SelectionShape::Rectangle { transform } => Some(vec![[DVec2::ZERO, DVec2::X, DVec2::ONE, DVec2::Y].map(|corner| transform.transform_point2(corner)).to_vec()]),
+			SelectionShape::Ellipse { transform } => {
+				// Use enough segments that each spans only a few units of the ellipse's circumference
+				let circumference = std::f64::consts::PI * (transform.matrix2.x_axis.length() + transform.matrix2.y_axis.length()) / 2.;
+				let segments = ((circumference / 2.).ceil() as usize).clamp(16, 1024);
+
+				let outline = (0..segments)
+					.map(|index| {
+						let angle = index as f64 / segments as f64 * std::f64::consts::TAU;
+						transform.transform_point2(DVec2::splat(0.5) + DVec2::from_angle(angle) * 0.5)
+					})
+					.collect();
+				Some(vec![outline])
+			}
+			SelectionShape::Polygons { outlines } => Some(outlines.clone()),
+			SelectionShape::All | SelectionShape::MagicWand { .. } => None,
+		}
+	}
+}
+
+/// How the region of a selection step is combined with the selection made by the preceding steps.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, DynAny, specta::Type, serde::Serialize, serde::Deserialize)]
+pub enum SelectionCombine {
+	/// Discards the preceding selection.
+	#[default]
+	Replace,
+	Add,
+	Subtract,
+	Intersect,
+	/// Keeps the areas covered by exactly one of the two, so combining with everything inverts the selection.
+	Exclude,
+}
+
+impl SelectionCombine {
+	fn combine(&self, selected: f32, shape: f32) -> f32 {
+		match self {
+			SelectionCombine::Replace => shape,
+			SelectionCombine::Add => selected.max(shape),
+			SelectionCombine::Subtract => selected * (1. - shape),
+			SelectionCombine::Intersect => selected.min(shape),
+			SelectionCombine::Exclude => selected + shape - 2. * selected * shape,
+		}
+	}
+}
+
+/// One of the steps which together build up a pixel selection, such as dragging a marquee or clicking with the magic wand.
+#[derive(Clone, Debug, PartialEq, Hash, DynAny, serde::Serialize, serde::Deserialize)]
+pub struct SelectionStep {
+	pub shape: SelectionShape,
+	pub combine: SelectionCombine,
+	/// Whether the edges of the region are smoothed with partially selected pixels.
+	pub anti_aliasing: bool,
+}
+
+impl SelectionStep {
+	/// Maps the step from its current space into another.
+	pub fn transformed(&self, transform: DAffine2) -> Self {
+		Self {
+			shape: self.shape.transformed(transform),
+			..self.clone()
+		}
+	}
+}
+
+/// A solid color filled into a pixel selection with the Fill tool.
+#[derive(Clone, Debug, PartialEq, Hash, Default, DynAny, serde::Serialize, serde::Deserialize)]
+pub struct SelectionFill {
+	pub color: Color,
+	/// The steps which build up the filled selection, in layer space.
+	pub selection: Vec<SelectionStep>,
+}
+
+/// How much each pixel of an image is covered by a pixel selection, from 0 (unselected) to 1 (fully selected).
+pub struct SelectionMask {
+	width: u32,
+	height: u32,
+	coverage: Vec<f32>,
+	/// Maps layer space into the pixel space of the image.
+	layer_to_pixel: DAffine2,
+}
+
+impl SelectionMask {
+	/// Rasterizes the selection steps, given in layer space, onto the pixel grid of the image. The image's colors are sampled by the magic wand.
+	pub fn new(image: &Image<Color>, pixel_to_layer: DAffine2, selection: &[SelectionStep]) -> Self {
+		let (width, height) = (image.width, image.height);
+		let layer_to_pixel = pixel_to_layer.inverse();
+
+		let mut coverage = vec![0.; width as usize * height as usize];
+		for step in selection {
+			let shape_coverage = match &step.shape {
+				SelectionShape::All => vec![1.; coverage.len()],
+				SelectionShape::MagicWand { seed, tolerance, contiguous } => {
+					let seed = layer_to_pixel.transform_point2(*seed).floor().as_ivec2();
+					magic_wand(image, seed, *tolerance, *contiguous, step.anti_aliasing)
+				}
+				shape => {
+					let outlines = shape.outlines().unwrap_or_default();
+					let outlines = outlines
+						.iter()
+						.map(|outline| outline.iter().map(|&point| layer_to_pixel.transform_point2(point)).collect())
+						.collect::<Vec<_>>();
+					rasterize_outlines(&outlines, width, height, step.anti_aliasing)
+				}
+			};
+
+			coverage.iter_mut().zip(shape_coverage).for_each(|(selected, shape)| *selected = step.combine.combine(*selected, shape));
+		}
+
+		Self {
+			width,
+			height,
+			coverage,
+			layer_to_pixel,
+		}
+	}
+
+	/// The selection of a pixel in the image.
+	pub fn coverage(&self, x: u32, y: u32) -> f32 {
+		if x >= self.width || y >= self.height {
+			return 0.;
+		}
+		self.coverage[y as usize * self.width as usize + x as usize]
+	}
+
+	/// The selection of the pixel of the image at a point in layer space, which is unselected outside of the image.
+	pub fn coverage_at(&self, layer_point: DVec2) -> f32 {
+		let pixel = self.layer_to_pixel.transform_point2(layer_point).floor();
+		if pixel.x < 0. || pixel.y < 0. {
+			return 0.;
+		}
+		self.coverage(pixel.x as u32, pixel.y as u32)
+	}
+
+	/// A grayscale image of the selection, which is white where selected and black elsewhere.
+	pub fn to_image(&self) -> Image<Color> {
+		Image {
+			width: self.width,
+			height: self.height,
+			data: self.coverage.iter().map(|&coverage| coverage_to_color(coverage)).collect(),
+			base64_string: None,
+		}
+	}
+}
+
+/// The gray of a mask pixel with the given coverage. Coverage is the gray's linear value, which is how the Mask node reads a stencil.
+fn coverage_to_color(coverage: f32) -> Color {
+	Color::from_luminance(coverage)
+}
+
+/// The coverage of a mask pixel, the inverse of [`coverage_to_color`].
+fn color_to_coverage(color: Color) -> f32 {
+	color.l()
+}
+
+/// Maps the pixel space of an image row, with pixels of size 1, into its layer space.
+fn pixel_to_layer(row: &TableRow<Raster<CPU>>) -> DAffine2 {
+	row.transform * DAffine2::from_scale(DVec2::new(1. / row.element.width as f64, 1. / row.element.height as f64))
+}
+
+/// Rasterizes closed outlines given in pixel space with the even-odd rule, returning the coverage of each pixel.
+fn rasterize_outlines(outlines: &[Vec<DVec2>], width: u32, height: u32, anti_aliasing: bool) -> Vec<f32> {
+	let (width, height) = (width as usize, height as usize);
+	let mut coverage = vec![0_f32; width * height];
+
+	let edges = outlines
+		.iter()
+		.filter(|outline| outline.len() >= 3)
+		.flat_map(|outline| outline.iter().zip(outline.iter().cycle().skip(1)).map(|(&start, &end)| (start, end)))
+		.filter(|(start, end)| start.y != end.y)
+		.collect::<Vec<_>>();
+	if edges.is_empty() {
+		return coverage;
+	}
+
+	let samples = if anti_aliasing { ANTI_ALIASING_SUBSAMPLES } else { 1 };
+	let sample_weight = 1. / samples as f32;
+	let mut crossings = Vec::new();
+
+	for (y, row) in coverage.chunks_exact_mut(width.max(1)).enumerate().take(height) {
+		for sample in 0..samples {
+			let sample_y = y as f64 + (sample as f64 + 0.5) / samples as f64;
+
+			crossings.clear();
+			crossings.extend(
+				edges
+					.iter()
+					.filter(|(start, end)| (start.y > sample_y) != (end.y > sample_y))
+					.map(|(start, end)| start.x + (sample_y - start.y) * (end.x - start.x) / (end.y - start.y)),
+			);
+			crossings.sort_by(f64::total_cmp);
+
+			for span in crossings.chunks_exact(2) {
+				let (start, end) = (span[0].max(0.), span[1].min(width as f64));
+				if start >= end {
+					continue;
+				}
+
+				if anti_aliasing {
+					// Add the horizontal extent of the span within each pixel it overlaps
+					for (x, pixel) in row.iter_mut().enumerate().take(end.ceil() as usize).skip(start.floor() as usize) {
+						let overlap = end.min(x as f64 + 1.) - start.max(x as f64);
+						*pixel += overlap as f32 * sample_weight;
+					}
+				} else {
+					// Select the pixels whose centers are within the span
+					let first = (start - 0.5).ceil() as usize;
+					let last = ((end - 0.5).ceil() as usize).min(width);
+					row.iter_mut().take(last).skip(first).for_each(|pixel| *pixel = 1.);
+				}
+			}
+		}
+	}
+
+	coverage.iter_mut().for_each(|pixel| *pixel = pixel.min(1.));
+	coverage
+}
+
+/// The largest difference between any channel of two colors, compared without premultiplied alpha in gamma space so it matches their perceived difference.
+fn color_difference(a: Color, b: Color) -> f32 {
+	let (a, b) = (a.to_unassociated_alpha().to_gamma_srgb(), b.to_unassociated_alpha().to_gamma_srgb());
+	(a.r() - b.r()).abs().max((a.g() - b.g()).abs()).max((a.b() - b.b()).abs()).max((a.a() - b.a()).abs())
+}
+
+/// Selects the pixels similar in color to the seed pixel, returning the coverage of each pixel.
+fn magic_wand(image: &Image<Color>, seed: IVec2, tolerance: f64, contiguous: bool, anti_aliasing: bool) -> Vec<f32> {
+	let (width, height) = (image.width as i32, image.height as i32);
+	let mut selected = vec![false; image.data.len()];
+	if seed.x < 0 || seed.y < 0 || seed.x >= width || seed.y >= height {
+		return vec![0.; image.data.len()];
+	}
+
+	let index = |pixel: IVec2| (pixel.y * width + pixel.x) as usize;
+	let seed_color = image.data[index(seed)];
+	let tolerance = (tolerance / 100.) as f32;
+	let similar = |color: Color| color_difference(color, seed_color) <= tolerance;
+
+	if contiguous {
+		// Flood fill outward from the seed through similar pixels
+		let mut stack = vec![seed];
+		selected[index(seed)] = true;
+		while let Some(pixel) = stack.pop() {
+			for neighbor in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y].map(|offset| pixel + offset) {
+				if neighbor.x < 0 || neighbor.y < 0 || neighbor.x >= width || neighbor.y >= height || selected[index(neighbor)] {
+					continue;
+				}
+				if similar(image.data[index(neighbor)]) {
+					selected[index(neighbor)] = true;
+					stack.push(neighbor);
+				}
+			}
+		}
+	} else {
+		selected.iter_mut().zip(&image.data).for_each(|(selected, &color)| *selected = similar(color));
+	}
+
+	if !anti_aliasing {
+		return selected.into_iter().map(|selected| if selected { 1. } else { 0. }).collect();
+	}
+
+	// Partially select the unselected pixels bordering the selection, in proportion to how many of their neighbors are selected
+	(0..height)
+		.flat_map(|y| (0..width).map(move |x| IVec2::new(x, y)))
+		.map(|pixel| {
+			if selected[index(pixel)] {
+				return 1.;
+			}
+			let neighbors = (-1..=1)
+				.flat_map(|dy| (-1..=1).map(move |dx| pixel + IVec2::new(dx, dy)))
+				.filter(|&neighbor| neighbor != pixel && neighbor.x >= 0 && neighbor.y >= 0 && neighbor.x < width && neighbor.y < height)
+				.filter(|&neighbor| selected[index(neighbor)])
+				.count();
+			neighbors as f32 / 16.
+		})
+		.collect()
+}
+
+/// Flattens a vector path into closed outlines made of straight segments, after mapping it by the transform.
+pub fn flatten_bezpath(bezpath: &kurbo::BezPath, transform: DAffine2) -> Vec<Vec<DVec2>> {
+	let mut bezpath = bezpath.clone();
+	bezpath.apply_affine(kurbo::Affine::new(transform.to_cols_array()));
+
+	let mut outlines = Vec::new();
+	let mut outline = Vec::new();
+	kurbo::flatten(bezpath.iter(), FLATTEN_TOLERANCE, |element| match element {
+		kurbo::PathEl::MoveTo(point) => {
+			if outline.len() >= 3 {
+				outlines.push(std::mem::take(&mut outline));
+			}
+			outline = vec![DVec2::new(point.x, point.y)];
+		}
+		kurbo::PathEl::LineTo(point) => outline.push(DVec2::new(point.x, point.y)),
+		_ => {}
+	});
+	if outline.len() >= 3 {
+		outlines.push(outline);
+	}
+
+	outlines
+}
+
+/// Identifies the point where an outline crosses the edge between two neighboring pixel centers.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum GridEdge {
+	/// The edge from the pixel center to the one on its right.
+	Horizontal(i32, i32),
+	/// The edge from the pixel center to the one below it.
+	Vertical(i32, i32),
+}
+
+/// Traces the outlines of the regions of a mask with a coverage of at least 50% using marching squares, returning closed outlines in pixel space.
+/// Outer boundaries and holes wind in opposite directions, so they can be filled with either the nonzero or even-odd rule.
+fn trace_outlines(coverage: impl Fn(i32, i32) -> f32, width: u32, height: u32) -> Vec<Vec<DVec2>> {
+	const THRESHOLD: f32 = 0.5;
+	let (width, height) = (width as i32, height as i32);
+
+	// Pixels beyond the image are unselected, so every outline is closed
+	let value = |x: i32, y: i32| if x < 0 || y < 0 || x >= width || y >= height { 0. } else { coverage(x, y) };
+	let center = |x: i32, y: i32| DVec2::new(x as f64 + 0.5, y as f64 + 0.5);
+	let crossing = |a: (i32, i32), b: (i32, i32)| {
+		let (value_a, value_b) = (value(a.0, a.1), value(b.0, b.1));
+		let t = ((THRESHOLD - value_a) / (value_b - value_a)).clamp(0., 1.) as f64;
+		center(a.0, a.1).lerp(center(b.0, b.1), t)
+	};
+
+	let mut next = HashMap::new();
+	let mut positions = HashMap::new();
+
+	for y in -1..height {
+		for x in -1..width {
+			let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+			let inside = corners.map(|(x, y)| value(x, y) >= THRESHOLD);
+
+			// The edges between the corners, in order around the cell
+			let edges = [GridEdge::Horizontal(x, y), GridEdge::Vertical(x + 1, y), GridEdge::Horizontal(x, y + 1), GridEdge::Vertical(x, y)];
+			let crossed = (0..4).filter(|&edge| inside[edge] != inside[(edge + 1) % 4]).collect::<Vec<_>>();
+
+			// Pair up the crossed edges, each pair cutting off the corner between them
+			let pairs = match crossed[..] {
+				[a, b] => vec![(a, b)],
+				[_, _, _, _] => {
+					// At a saddle, the corners matching the center of the cell are connected through it and the other two are cut off
+					let center_inside = corners.iter().map(|&(x, y)| value(x, y)).sum::<f32>() / 4. >= THRESHOLD;
+					if inside[0] == center_inside { vec![(0, 1), (2, 3)] } else { vec![(3, 0), (1, 2)] }
+				}
+				_ => continue,
+			};
+
+			for (a, b) in pairs {
+				let points = [a, b].map(|edge| crossing(corners[edge], corners[(edge + 1) % 4]));
+				positions.insert(edges[a], points[0]);
+				positions.insert(edges[b], points[1]);
+
+				// Orient each segment with the selected side on its left
+				let reference_corner = if b == (a + 1) % 4 { b } else { a };
+				let reference_inside = inside[reference_corner];
+				let reference_point = center(corners[reference_corner].0, corners[reference_corner].1);
+				let direction = points[1] - points[0];
+				let on_left = direction.perp_dot(reference_point - points[0]) < 0.;
+				if on_left == reference_inside {
+					next.insert(edges[a], edges[b]);
+				} else {
+					next.insert(edges[b], edges[a]);
+				}
+			}
+		}
+	}
+
+	// Follow the segments around each outline
+	let mut outlines = Vec::new();
+	while let Some(&start) = next.keys().next() {
+		let mut outline = Vec::new();
+		let mut edge = start;
+		while let Some(following) = next.remove(&edge) {
+			outline.push(positions[&edge]);
+			edge = following;
+		}
+		outline = remove_collinear_points(outline);
+		if outline.len() >= 3 {
+			outlines.push(outline);
+		}
+	}
+
+	outlines
+}
+
+/// Removes points lying on the straight line between their neighbors in a closed outline.
+fn remove_collinear_points(outline: Vec<DVec2>) -> Vec<DVec2> {
+	let count = outline.len();
+	if count < 3 {
+		return outline;
+	}
+
+	(0..count)
+		.filter(|&index| {
+			let previous = outline[(index + count - 1) % count];
+			let next = outline[(index + 1) % count];
+			(outline[index] - previous).perp_dot(next - outline[index]).abs() > 1e-9
+		})
+		.map(|index| outline[index])
+		.collect()
+}
+
+/// Converts each image into a grayscale mask of the pixels selected by the selection steps, which is white where selected and black elsewhere.
+///
+/// The steps are built up with the Pixel Selection tool, and the magic wand selects from the colors of the image.
+#[node_macro::node(category("Raster: Selection"))]
+async fn pixel_selection(
+	_: impl Ctx,
+	/// The image to select pixels from.
+	image_frame: Table<Raster<CPU>>,
+	/// The steps which build up the selection, in layer space.
+	selection: Vec<SelectionStep>,
+) -> Table<Raster<CPU>> {
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			let mask = SelectionMask::new(row.element.data(), pixel_to_layer(&row), &selection);
+			row.element = Raster::new_cpu(mask.to_image());
+			row
+		})
+		.collect()
+}
+
+/// Converts each image into a grayscale mask of the pixels whose colors are similar to the chosen color, which is white where selected and black elsewhere.
+#[node_macro::node(category("Raster: Selection"))]
+async fn color_range(
+	_: impl Ctx,
+	/// The image to select pixels from.
+	image_frame: Table<Raster<CPU>>,
+	/// The color of the pixels to select.
+	#[default(Color::WHITE)]
+	color: Color,
+	/// How different a pixel's color can be from the chosen color while still being partially selected, with more similar colors being more selected.
+	#[default(20.)]
+	#[range((0., 100.))]
+	fuzziness: Percentage,
+) -> Table<Raster<CPU>> {
+	let fuzziness = (fuzziness / 100.) as f32;
+
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			let image = row.element.data();
+			let data = image
+				.data
+				.iter()
+				.map(|&pixel| {
+					let difference = color_difference(pixel, color);
+					let coverage = if fuzziness > 0. {
+						(1. - difference / fuzziness).clamp(0., 1.)
+					} else {
+						(difference == 0.) as u8 as f32
+					};
+					coverage_to_color(coverage)
+				})
+				.collect();
+
+			row.element = Raster::new_cpu(Image {
+				width: image.width,
+				height: image.height,
+				data,
+				base64_string: None,
+			});
+			row
+		})
+		.collect()
+}
+
+/// Limits the changes made to an image to the pixels of a selection, such as those made by adjustments placed between the original image and this node.
+/// Unselected pixels are restored from the original image, and partially selected pixels are blended between the two.
+#[node_macro::node(category("Raster: Selection"))]
+async fn confine_to_selection(
+	_: impl Ctx,
+	/// The image with the changes to be confined.
+	image_frame: Table<Raster<CPU>>,
+	/// The image before the changes were made, which the magic wand also selects from.
+	#[expose]
+	original: Table<Raster<CPU>>,
+	/// The steps which build up the selection, in layer space. Without any steps, the changes are kept everywhere.
+	selection: Vec<SelectionStep>,
+) -> Table<Raster<CPU>> {
+	if selection.is_empty() {
+		return image_frame;
+	}
+
+	image_frame
+		.into_iter()
+		.enumerate()
+		.map(|(index, mut row)| {
+			let Some(original_row) = original.get(index) else { return row };
+			let original_row = original_row.into_cloned();
+			let original_image = original_row.element.data();
+			let mask = SelectionMask::new(original_image, pixel_to_layer(&original_row), &selection);
+
+			let row_pixel_to_layer = pixel_to_layer(&row);
+			let layer_to_original_pixel = pixel_to_layer(&original_row).inverse();
+			let mut image = row.element.clone().into_data();
+			for y in 0..image.height {
+				for x in 0..image.width {
+					let layer_point = row_pixel_to_layer.transform_point2(DVec2::new(x as f64 + 0.5, y as f64 + 0.5));
+					let coverage = mask.coverage_at(layer_point);
+					if coverage >= 1. {
+						continue;
+					}
+
+					let original_pixel = layer_to_original_pixel.transform_point2(layer_point).floor();
+					let original_color = if original_pixel.x >= 0. && original_pixel.y >= 0. {
+						original_image.get_pixel(original_pixel.x as u32, original_pixel.y as u32).unwrap_or_default()
+					} else {
+						Color::TRANSPARENT
+					};
+
+					let index = (y * image.width + x) as usize;
+					image.data[index] = original_color.lerp(&image.data[index], coverage);
+				}
+			}
+
+			row.element = Raster::new_cpu(image);
+			row
+		})
+		.collect()
+}
+
+/// Fills the pixel selections made with the Fill tool with their colors.
+#[node_macro::node(category("Raster: Selection"))]
+async fn selection_fill(
+	_: impl Ctx,
+	/// The image to be filled.
+	image_frame: Table<Raster<CPU>>,
+	/// The colors filled into each selection, in the order they were filled.
+	fills: Vec<SelectionFill>,
+) -> Table<Raster<CPU>> {
+	if fills.is_empty() {
+		return image_frame;
+	}
+
+	image_frame
+		.into_iter()
+		.map(|mut row| {
+			let pixel_to_layer = pixel_to_layer(&row);
+			let mut image = row.element.clone().into_data();
+
+			for fill in &fills {
+				let mask = SelectionMask::new(&image, pixel_to_layer, &fill.selection);
+				for (pixel, &coverage) in image.data.iter_mut().zip(&mask.coverage) {
+					*pixel = pixel.lerp(&fill.color, coverage);
+				}
+			}
+
+			row.element = Raster::new_cpu(image);
+			row
+		})
+		.collect()
+}
+
+/// Traces the outline of the selected regions of a grayscale mask into a vector path, following where the mask is 50% gray.
+#[node_macro::node(category("Raster: Selection"))]
+async fn selection_to_path(
+	_: impl Ctx,
+	/// The mask of the selection, which is white where selected.
+	mask: Table<Raster<CPU>>,
+) -> Table<Vector> {
+	mask.into_iter()
+		.map(|row| {
+			let image = row.element.data();
+			let outlines = trace_outlines(|x, y| image.get_pixel(x as u32, y as u32).map(color_to_coverage).unwrap_or_default(), image.width, image.height);
+
+			let pixel_to_layer = pixel_to_layer(&row);
+			let subpaths = outlines
+				.into_iter()
+				.map(|outline| Subpath::<PointId>::from_anchors(outline.into_iter().map(|point| pixel_to_layer.transform_point2(point)), true));
+
+			TableRow {
+				element: Vector::from_subpaths(subpaths, false),
+				alpha_blending: row.alpha_blending,
+				source_node_id: row.source_node_id,
+				..Default::default()
+			}
+		})
+		.collect()
+}
+
+/// Converts each image into a grayscale mask of the pixels within the filled area of a vector path, which is white where selected and black elsewhere.
+#[node_macro::node(category("Raster: Selection"))]
+async fn path_to_selection(
+	_: impl Ctx,
+	/// The image whose pixels are selected.
+	image_frame: Table<Raster<CPU>>,
+	/// The path whose filled area is selected, using the even-odd rule.
+	#[expose]
+	path: Table<Vector>,
+	/// Whether the edges of the selection are smoothed with partially selected pixels.
+	#[default(true)]
+	anti_aliasing: bool,
+) -> Table<Raster<CPU>> {
+	let outlines = path
+		.iter()
+		.flat_map(|row| row.element.stroke_bezpath_iter().flat_map(|bezpath| flatten_bezpath(&bezpath, *row.transform)).collect::<Vec<_>>())
+		.collect::<Vec<_>>();
+	let selection = vec![SelectionStep {
+		shape: SelectionShape::Polygons { outlines },
+		combine: SelectionCombine::Replace,
+		anti_aliasing,
+	}];
+
+	pixel_selection((), image_frame, selection).await
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use raster_types::BitmapMut;
+
+	/// A 16x16 image which is black with a white square from (4, 4) to (12, 12), covering a layer space square from 0 to 16.
+	fn white_square() -> TableRow<Raster<CPU>> {
+		let mut image = Image::new(16, 16, Color::BLACK);
+		for y in 4..12 {
+			for x in 4..12 {
+				image.set_pixel(x, y, Color::WHITE);
+			}
+		}
+		TableRow {
+			element: Raster::new_cpu(image),
+			transform: DAffine2::from_scale(DVec2::splat(16.)),
+			..Default::default()
+		}
+	}
+
+	fn selected_count(mask: &SelectionMask) -> usize {
+		mask.coverage.iter().filter(|&&coverage| coverage >= 0.5).count()
+	}
+
+	#[test]
+	fn steps_combine_in_order() {
+		let row = white_square();
+		let rectangle = |min: DVec2, max: DVec2, combine| SelectionStep {
+			shape: SelectionShape::Rectangle {
+				transform: DAffine2::from_scale_angle_translation(max - min, 0., min),
+			},
+			combine,
+			anti_aliasing: false,
+		};
+
+		let selection = [
+			rectangle(DVec2::ZERO, DVec2::splat(8.), SelectionCombine::Replace),
+			rectangle(DVec2::new(8., 0.), DVec2::new(16., 8.), SelectionCombine::Add),
+			rectangle(DVec2::new(0., 0.), DVec2::new(4., 4.), SelectionCombine::Subtract),
+		];
+		let mask = SelectionMask::new(row.element.data(), pixel_to_layer(&row), &selection);
+		assert_eq!(selected_count(&mask), 16 * 8 - 4 * 4);
+		assert_eq!(mask.coverage(1, 1), 0.);
+		assert_eq!(mask.coverage_at(DVec2::new(12.5, 2.5)), 1.);
+
+		// Excluding everything inverts the selection
+		let inverted = [
+			selection.to_vec(),
+			vec![SelectionStep {
+				shape: SelectionShape::All,
+				combine: SelectionCombine::Exclude,
+				anti_aliasing: false,
+			}],
+		]
+		.concat();
+		let mask = SelectionMask::new(row.element.data(), pixel_to_layer(&row), &inverted);
+		assert_eq!(selected_count(&mask), 16 * 16 - (16 * 8 - 4 * 4));
+	}
+
+	#[test]
+	fn magic_wand_selects_similar_colors() {
+		let mut row = white_square();
+		// A second white area which isn't connected to the square
+		let mut image = row.element.clone().into_data();
+		image.set_pixel(0, 0, Color::WHITE);
+		row.element = Raster::new_cpu(image);
+
+		let wand = |contiguous| SelectionStep {
+			shape: SelectionShape::MagicWand {
+				seed: DVec2::new(8.5, 8.5),
+				tolerance: 10.,
+				contiguous,
+			},
+			combine: SelectionCombine::Replace,
+			anti_aliasing: false,
+		};
+
+		let contiguous = SelectionMask::new(row.element.data(), pixel_to_layer(&row), &[wand(true)]);
+		assert_eq!(selected_count(&contiguous), 64);
+		assert_eq!(contiguous.coverage(0, 0), 0.);
+
+		let everywhere = SelectionMask::new(row.element.data(), pixel_to_layer(&row), &[wand(false)]);
+		assert_eq!(selected_count(&everywhere), 65);
+	}
+
+	#[test]
+	fn anti_aliased_edges_are_partially_selected() {
+		// A square covering half of the pixels along its edges
+		let outline = vec![DVec2::new(2.5, 2.5), DVec2::new(6.5, 2.5), DVec2::new(6.5, 6.5), DVec2::new(2.5, 6.5)];
+
+		let coverage = rasterize_outlines(std::slice::from_ref(&outline), 8, 8, true);
+		assert_eq!(coverage[3 * 8 + 3], 1.);
+		assert!((coverage[3 * 8 + 2] - 0.5).abs() < 1e-6);
+		assert!((coverage[2 * 8 + 2] - 0.25).abs() < 1e-6);
+
+		let aliased = rasterize_outlines(&[outline], 8, 8, false);
+		assert_eq!(aliased.iter().sum::<f32>(), 16.);
+	}
+
+	#[test]
+	fn mask_image_keeps_coverage() {
+		let mask = SelectionMask {
+			width: 4,
+			height: 1,
+			coverage: vec![0., 0.25, 0.5, 1.],
+			layer_to_pixel: DAffine2::IDENTITY,
+		};
+
+		// Reading the mask back, like the Selection to Path node does, gives the coverage it was made from
+		let image = mask.to_image();
+		for (&color, &coverage) in image.data.iter().zip(&mask.coverage) {
+			assert!((color_to_coverage(color) - coverage).abs() < 1e-6);
+		}
+	}
+
+	#[test]
+	fn traced_outline_follows_mask_edge() {
+		let row = white_square();
+		let image = row.element.data();
+		let outlines = trace_outlines(|x, y| color_to_coverage(image.get_pixel(x as u32, y as u32).unwrap()), 16, 16);
+		assert_eq!(outlines.len(), 1);
+
+		// The outline passes halfway between the centers of the selected and unselected pixels, cutting the corners
+		let outline = &outlines[0];
+		assert!(outline.iter().all(|point| (4. ..=12.).contains(&point.x) && (4. ..=12.).contains(&point.y)));
+
+		// Rasterizing the outline again selects the same pixels
+		let coverage = rasterize_outlines(&outlines, 16, 16, false);
+		assert_eq!(coverage.iter().sum::<f32>(), 64.);
+	}
+}
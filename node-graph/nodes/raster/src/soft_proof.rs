@@ -0,0 +1,26 @@
+use core_types::context::Ctx;
+use core_types::table::Table;
+use raster_types::color_management::{self, ColorSpace, RenderingIntent};
+use raster_types::{CPU, Raster};
+
+/// Previews how the image will look once reproduced in a narrower color space, such as when exported for the web or for print.
+///
+/// Colors are converted from the working space into the proof color space, where out-of-gamut colors are brought into range, and then back again.
+#[node_macro::node(category("Raster: Adjustment"))]
+fn soft_proof(
+	_: impl Ctx,
+	mut image: Table<Raster<CPU>>,
+	/// The color space that the document's raster data is stored in. The editor sets this to the document's working color space unless it's chosen here.
+	working_space: ColorSpace,
+	/// The color space of the output that is being previewed.
+	proof_space: ColorSpace,
+	/// How colors outside the gamut of the proof color space are brought into it.
+	rendering_intent: RenderingIntent,
+) -> Table<Raster<CPU>> {
+	for row in image.iter_mut() {
+		// Transforms between the built-in profiles don't fail, but if one did, the image would be left as it was
+		let _ = color_management::soft_proof(&mut row.element.data_mut().data, working_space, proof_space, rendering_intent);
+	}
+
+	image
+}
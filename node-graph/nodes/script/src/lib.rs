@@ -0,0 +1,342 @@
+use core_types::registry::types::TextArea;
+use core_types::table::{Table, TableRowMut};
+use core_types::{Color, Ctx};
+use glam::{DAffine2, DVec2};
+use graphic_types::Vector;
+use log::warn;
+use rhai::{AST, Array, Dynamic, Engine, Map, Scope};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use vector_types::subpath::BezierHandles;
+use vector_types::vector::style::{Fill, Stroke};
+
+/// The most operations that a script may perform for each element.
+///
+/// Counting operations rather than measuring time bounds scripts the same way on every machine, so a script that finishes on one always finishes on all others.
+pub const SCRIPT_OPERATIONS_MAX: u64 = 1_000_000;
+
+/// The most operations that a script may perform for all elements together, after which the remaining elements are left unchanged.
+pub const SCRIPT_EVALUATION_OPERATIONS_MAX: u64 = 10_000_000;
+
+/// Runs a script written in the Rhai language for each element of the vector content, which can read and modify the element's points, segments, transform, and colors.
+///
+/// The script reads and assigns these variables:
+/// `points` is an array of `#{ x, y }` positions.
+/// `segments` is an array of `#{ start, end, handles }` curves, where `start` and `end` are indices into `points`, and `handles` is an array of zero, one, or two `#{ x, y }` positions for a line, a quadratic, or a cubic curve.
+/// `transform` is the `[a, b, c, d, e, f]` array of the element's affine transform.
+/// `fill` and `stroke` are `#{ r, g, b, a }` colors with channels from 0 to 1, or `()` for none, and `stroke_width` is the thickness of the stroke.
+///
+/// It can also read `index` and `count`, the index of the element and the number of elements, as well as the *A* and *B* inputs as `a` and `b`.
+///
+/// Rhai's `for` loops iterate over copies of the array items, so changes must be written through an index, such as `for i in 0..points.len() { points[i].x += a; }`.
+///
+/// Scripts can't access files or the time, and are stopped after a limited number of operations for each element and for all elements together, so they always produce the same output.
+/// When a script fails or writes back a number which isn't finite, the element it was run for is left unchanged.
+#[node_macro::node(category("Vector: Modifier"), path(core_types::vector))]
+fn script(
+	_: impl Ctx,
+	/// The vector elements to run the script for.
+	content: Table<Vector>,
+	/// The Rhai code run for each element.
+	source: TextArea,
+	/// A number read by the script as `a`.
+	a: f64,
+	/// A number read by the script as `b`.
+	b: f64,
+) -> Table<Vector> {
+	let mut engine = script_engine();
+	let ast = match engine.compile(&source) {
+		Ok(ast) => ast,
+		Err(error) => {
+			warn!("Failed to compile the script: {error}");
+			return content;
+		}
+	};
+
+	// The engine reports the operations performed by the current run, which are added up to stop the remaining elements once the budget is spent
+	let operations = Arc::new(AtomicU64::new(0));
+	engine.on_progress({
+		let operations = operations.clone();
+		move |count| {
+			operations.store(count, Ordering::Relaxed);
+			None
+		}
+	});
+	let mut remaining_operations = SCRIPT_EVALUATION_OPERATIONS_MAX;
+
+	let mut content = content;
+	let count = content.len();
+	for (index, row) in content.iter_mut().enumerate() {
+		// A limit of zero would remove the limit instead, so the remaining elements are skipped before that happens
+		if remaining_operations == 0 {
+			warn!("The script was stopped at element {index} after performing {SCRIPT_EVALUATION_OPERATIONS_MAX} operations");
+			break;
+		}
+		engine.set_max_operations(remaining_operations.min(SCRIPT_OPERATIONS_MAX));
+		operations.store(0, Ordering::Relaxed);
+
+		let inputs = ScriptInputs { index, count, a, b };
+		if let Err(error) = run_script(&engine, &ast, row, inputs) {
+			warn!("The script failed for element {index}: {error}");
+		}
+		remaining_operations = remaining_operations.saturating_sub(operations.load(Ordering::Relaxed));
+	}
+	content
+}
+
+fn script_engine() -> Engine {
+	let mut engine = Engine::new();
+	engine.set_max_operations(SCRIPT_OPERATIONS_MAX);
+	engine.set_max_call_levels(64);
+	engine.set_max_expr_depths(64, 32);
+	engine.set_max_string_size(1 << 16);
+	engine.set_max_array_size(1 << 20);
+	engine.set_max_map_size(1 << 16);
+
+	// Printing would otherwise write to the console of the application
+	engine.on_print(|_| {});
+	engine.on_debug(|_, _, _| {});
+
+	engine
+}
+
+/// The read-only values given to the script besides the attributes of the element.
+#[derive(Clone, Copy)]
+struct ScriptInputs {
+	index: usize,
+	count: usize,
+	a: f64,
+	b: f64,
+}
+
+/// Runs the script with the attributes of a single element, then writes the values the script left in its variables back into the element.
+///
+/// The element is only modified once the script has finished and all of its variables were found to be valid.
+fn run_script(engine: &Engine, ast: &AST, row: TableRowMut<'_, Vector>, inputs: ScriptInputs) -> Result<(), String> {
+	let vector = row.element;
+
+	let points: Array = vector.point_domain.positions().iter().map(|&position| position_to_script(position)).collect();
+	let segments: Array = (0..vector.segment_domain.ids().len())
+		.map(|index| {
+			let segment = &vector.segment_domain;
+			let handles: Array = match segment.handles()[index] {
+				BezierHandles::Linear => Array::new(),
+				BezierHandles::Quadratic { handle } => vec![position_to_script(handle)],
+				BezierHandles::Cubic { handle_start, handle_end } => vec![position_to_script(handle_start), position_to_script(handle_end)],
+			};
+
+			let mut map = Map::new();
+			map.insert("start".into(), Dynamic::from_int(segment.start_point()[index] as i64));
+			map.insert("end".into(), Dynamic::from_int(segment.end_point()[index] as i64));
+			map.insert("handles".into(), Dynamic::from_array(handles));
+			Dynamic::from_map(map)
+		})
+		.collect();
+	let transform: Array = row.transform.to_cols_array().into_iter().map(Dynamic::from_float).collect();
+	let fill = match vector.style.fill() {
+		Fill::Solid(color) => color_to_script(*color),
+		_ => Dynamic::UNIT,
+	};
+	let stroke = vector.style.stroke();
+	let stroke_color = stroke.as_ref().and_then(|stroke| stroke.color).map_or(Dynamic::UNIT, color_to_script);
+	let stroke_width = stroke.as_ref().map_or(0., |stroke| stroke.weight);
+
+	let mut scope = Scope::new();
+	scope.push("points", points);
+	scope.push("segments", segments);
+	scope.push("transform", transform);
+	scope.push_dynamic("fill", fill);
+	scope.push_dynamic("stroke", stroke_color);
+	scope.push("stroke_width", stroke_width);
+	scope.push_constant("index", inputs.index as i64);
+	scope.push_constant("count", inputs.count as i64);
+	scope.push_constant("a", inputs.a);
+	scope.push_constant("b", inputs.b);
+
+	engine.run_ast_with_scope(&mut scope, ast).map_err(|error| error.to_string())?;
+
+	// Read every variable back before modifying the element, so a mistake in one of them leaves the element untouched
+	let points = array_variable(&scope, "points")?
+		.iter()
+		.map(|point| position_from_script(point).ok_or("`points` must only contain `#{ x, y }` positions"))
+		.collect::<Result<Vec<_>, _>>()?;
+	if points.len() != vector.point_domain.positions().len() {
+		return Err("Points can't be added to or removed from `points`".into());
+	}
+
+	let segments = array_variable(&scope, "segments")?
+		.iter()
+		.map(|segment| segment_from_script(segment, points.len()))
+		.collect::<Result<Vec<_>, _>>()?;
+	if segments.len() != vector.segment_domain.ids().len() {
+		return Err("Segments can't be added to or removed from `segments`".into());
+	}
+
+	let transform = array_variable(&scope, "transform")?;
+	let transform: [f64; 6] = transform
+		.iter()
+		.map(number_from_script)
+		.collect::<Option<Vec<_>>>()
+		.and_then(|transform| transform.try_into().ok())
+		.ok_or("`transform` must be an array of six numbers")?;
+
+	let fill = optional_color_variable(&scope, "fill")?;
+	let stroke_color = optional_color_variable(&scope, "stroke")?;
+	let stroke_width = scope.get("stroke_width").and_then(number_from_script).ok_or("`stroke_width` must be a number")?;
+
+	for (index, position) in points.into_iter().enumerate() {
+		vector.point_domain.set_position(index, position);
+	}
+	for (index, (start, end, handles)) in segments.into_iter().enumerate() {
+		vector.segment_domain.set_start_point(index, start);
+		vector.segment_domain.set_end_point(index, end);
+		vector.segment_domain.set_handles(index, handles);
+	}
+	*row.transform = DAffine2::from_cols_array(&transform);
+
+	// Gradient fills aren't visible to the script, so they are only replaced when the script assigns a color
+	match fill {
+		Some(color) => vector.style.set_fill(Fill::Solid(color)),
+		None if matches!(vector.style.fill(), Fill::Solid(_)) => vector.style.set_fill(Fill::None),
+		None => {}
+	}
+	match (vector.style.stroke(), stroke_color) {
+		(Some(mut stroke), color) => {
+			stroke.color = color;
+			stroke.weight = stroke_width;
+			vector.style.set_stroke(stroke);
+		}
+		(None, Some(color)) => {
+			let mut stroke = Stroke::default().with_weight(stroke_width);
+			stroke.color = Some(color);
+			vector.style.set_stroke(stroke);
+		}
+		(None, None) => {}
+	}
+
+	Ok(())
+}
+
+fn array_variable(scope: &Scope, name: &str) -> Result<Array, String> {
+	scope.get_value::<Array>(name).ok_or_else(|| format!("`{name}` must be an array"))
+}
+
+fn optional_color_variable(scope: &Scope, name: &str) -> Result<Option<Color>, String> {
+	match scope.get(name) {
+		Some(value) if value.is_unit() => Ok(None),
+		Some(value) => color_from_script(value).map(Some).ok_or_else(|| format!("`{name}` must be an `#{{ r, g, b, a }}` color or `()`")),
+		None => Ok(None),
+	}
+}
+
+/// Reads an integer or a float, since scripts may write either one where a number is expected.
+/// Infinite and NaN values are rejected, since they would corrupt the geometry and bounds of the element.
+fn number_from_script(value: &Dynamic) -> Option<f64> {
+	value.as_float().ok().or_else(|| value.as_int().ok().map(|value| value as f64)).filter(|value| value.is_finite())
+}
+
+fn position_to_script(position: DVec2) -> Dynamic {
+	let mut map = Map::new();
+	map.insert("x".into(), Dynamic::from_float(position.x));
+	map.insert("y".into(), Dynamic::from_float(position.y));
+	Dynamic::from_map(map)
+}
+
+fn position_from_script(value: &Dynamic) -> Option<DVec2> {
+	let map = value.read_lock::<Map>()?;
+	Some(DVec2::new(number_from_script(map.get("x")?)?, number_from_script(map.get("y")?)?))
+}
+
+fn segment_from_script(value: &Dynamic, point_count: usize) -> Result<(usize, usize, BezierHandles), String> {
+	const MESSAGE: &str = "`segments` must only contain `#{ start, end, handles }` curves";
+
+	let map = value.read_lock::<Map>().ok_or(MESSAGE)?;
+	let point_index = |key: &str| {
+		let index = map.get(key).and_then(|index| index.as_int().ok()).ok_or(MESSAGE)?;
+		usize::try_from(index)
+			.ok()
+			.filter(|&index| index < point_count)
+			.ok_or("Segments must start and end at indices of existing points")
+	};
+	let start = point_index("start")?;
+	let end = point_index("end")?;
+
+	let handles = map.get("handles").and_then(|handles| handles.read_lock::<Array>()).ok_or(MESSAGE)?;
+	let handles = handles.iter().map(position_from_script).collect::<Option<Vec<_>>>().ok_or(MESSAGE)?;
+	let handles = match handles[..] {
+		[] => BezierHandles::Linear,
+		[handle] => BezierHandles::Quadratic { handle },
+		[handle_start, handle_end] => BezierHandles::Cubic { handle_start, handle_end },
+		_ => return Err("Segments can't have more than two handles".into()),
+	};
+
+	Ok((start, end, handles))
+}
+
+fn color_to_script(color: Color) -> Dynamic {
+	let mut map = Map::new();
+	for (channel, value) in [("r", color.r()), ("g", color.g()), ("b", color.b()), ("a", color.a())] {
+		map.insert(channel.into(), Dynamic::from_float(value as f64));
+	}
+	Dynamic::from_map(map)
+}
+
+fn color_from_script(value: &Dynamic) -> Option<Color> {
+	let map = value.read_lock::<Map>()?;
+	let channel = |name: &str| number_from_script(map.get(name)?).map(|value| value.clamp(0., 1.) as f32);
+	Some(Color::from_rgbaf32_unchecked(channel("r")?, channel("g")?, channel("b")?, channel("a")?))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use vector_types::subpath::Subpath;
+
+	fn square() -> Table<Vector> {
+		Table::new_from_element(Vector::from_subpath(Subpath::new_rectangle(DVec2::ZERO, DVec2::ONE)))
+	}
+
+	#[test]
+	fn moves_points() {
+		let source = "for i in 0..points.len() { points[i].x += a; points[i].y *= b; }".to_string();
+		let result = script((), square(), source, 10., 2.);
+
+		let positions = result.iter().next().unwrap().element.point_domain.positions().to_vec();
+		assert_eq!(positions.len(), 4);
+		assert!(positions.iter().all(|position| position.x >= 10. && position.x <= 11.));
+		assert!(positions.iter().any(|position| position.y == 2.));
+	}
+
+	#[test]
+	fn sets_colors_and_transform() {
+		let source = "fill = #{ r: 1.0, g: 0.0, b: 0.0, a: 1.0 }; stroke_width = 3; transform[4] = index + 5;".to_string();
+		let result = script((), square(), source, 0., 0.);
+
+		let row = result.iter().next().unwrap();
+		assert_eq!(row.element.style.fill(), &Fill::Solid(Color::RED));
+		assert_eq!(row.transform.translation, DVec2::new(5., 0.));
+	}
+
+	#[test]
+	fn failing_scripts_leave_content_unchanged() {
+		let original = square();
+		for source in ["points.push(#{ x: 0, y: 0 });", "loop {}", "this isn't valid", "points[0].x = 0.0 / 0.0;", "transform[4] = 1.0 / 0.0;"] {
+			let result = script((), original.clone(), source.to_string(), 0., 0.);
+			assert_eq!(
+				result.iter().next().unwrap().element.point_domain.positions(),
+				original.iter().next().unwrap().element.point_domain.positions()
+			);
+		}
+	}
+
+	#[test]
+	fn stops_once_the_evaluation_budget_is_spent() {
+		let elements = (SCRIPT_EVALUATION_OPERATIONS_MAX / SCRIPT_OPERATIONS_MAX + 1) as usize;
+		let content = (0..elements).flat_map(|_| square().into_iter()).collect::<Table<Vector>>();
+
+		// Each of the first elements spends the whole budget of an element, so none is left for the last one
+		let source = "if index < count - 1 { loop {} } transform[4] = 5;".to_string();
+		let result = script((), content, source, 0., 0.);
+		assert_eq!(result.iter().last().unwrap().transform.translation, DVec2::ZERO);
+	}
+}
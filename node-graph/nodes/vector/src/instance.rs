@@ -1,6 +1,7 @@
 use core_types::Color;
+use core_types::memo::MemoHash;
 use core_types::table::{Table, TableRowRef};
-use core_types::{CloneVarArgs, Context, Ctx, ExtractAll, ExtractIndex, ExtractVarArgs, InjectVarArgs, OwnedContextImpl};
+use core_types::{CloneVarArgs, Context, Ctx, ExtractAll, ExtractIndex, ExtractVarArgs, InjectVarArgs, OwnedContextImpl, parallel};
 use glam::DVec2;
 use graphic_types::Graphic;
 use graphic_types::Vector;
@@ -20,7 +21,7 @@ impl std::hash::Hash for HashableDVec2 {
 	}
 }
 
-#[node_macro::node(name("Instance on Points"), category("Instancing"), path(core_types::vector))]
+#[node_macro::node(name("Instance on Points"), category("Instancing"), path(core_types::vector), parallel)]
 async fn instance_on_points<T: Into<Graphic> + Default + Send + Clone + 'static>(
 	ctx: impl ExtractAll + CloneVarArgs + Sync + Ctx + InjectVarArgs,
 	points: Table<Vector>,
@@ -37,34 +38,32 @@ async fn instance_on_points<T: Into<Graphic> + Default + Send + Clone + 'static>
 	let mut result_table = Table::new();
 
 	for TableRowRef { element: points, transform, .. } in points.iter() {
-		let mut iteration = async |index, point| {
+		let mut range: Vec<_> = points.point_domain.positions().iter().copied().enumerate().collect();
+		if reverse {
+			range.reverse();
+		}
+
+		// The instances don't depend on each other, so they're generated in parallel and then collected in order
+		let generated_instances = parallel::join_all(range.into_iter().map(|(index, point)| {
 			let transformed_point = transform.transform_point2(point);
 
 			let new_ctx = OwnedContextImpl::from(ctx.clone()).with_index(index).with_vararg(Box::new(HashableDVec2(transformed_point)));
-			let generated_instance = instance.eval(new_ctx.into_context()).await;
+			async move { (transformed_point, instance.eval(new_ctx.into_context()).await) }
+		}))
+		.await;
 
+		for (transformed_point, generated_instance) in generated_instances {
 			for mut generated_row in generated_instance.into_iter() {
 				generated_row.transform.translation = transformed_point;
 				result_table.push(generated_row);
 			}
-		};
-
-		let range = points.point_domain.positions().iter().enumerate();
-		if reverse {
-			for (index, &point) in range.rev() {
-				iteration(index, point).await;
-			}
-		} else {
-			for (index, &point) in range {
-				iteration(index, point).await;
-			}
 		}
 	}
 
 	result_table
 }
 
-#[node_macro::node(category("Instancing"), path(core_types::vector))]
+#[node_macro::node(category("Instancing"), path(core_types::vector), parallel)]
 async fn instance_repeat<T: Into<Graphic> + Default + Send + Clone + 'static>(
 	ctx: impl ExtractAll + CloneVarArgs + Ctx,
 	#[implementations(
@@ -80,20 +79,15 @@ async fn instance_repeat<T: Into<Graphic> + Default + Send + Clone + 'static>(
 ) -> Table<T> {
 	let count = count.max(1) as usize;
 
-	let mut result_table = Table::new();
-
-	for index in 0..count {
+	let generated_instances = parallel::join_all((0..count).map(|index| {
 		let index = if reverse { count - index - 1 } else { index };
 
 		let new_ctx = OwnedContextImpl::from(ctx.clone()).with_index(index);
-		let generated_instance = instance.eval(new_ctx.into_context()).await;
-
-		for generated_row in generated_instance.into_iter() {
-			result_table.push(generated_row);
-		}
-	}
+		instance.eval(new_ctx.into_context())
+	}))
+	.await;
 
-	result_table
+	generated_instances.into_iter().flatten().collect()
 }
 
 #[node_macro::node(category("Instancing"), path(core_types::vector))]
@@ -131,12 +125,95 @@ async fn instance_index(
 	last as f64
 }
 
+/// Repeatedly evaluates the *Iteration* input, each time handing it the result of the previous iteration (starting with *Initial*), and produces the result of the final iteration.
+///
+/// Inside the iteration, *Previous Iteration* reads the result built up so far and *Instance Index* reads the iteration number. This allows for content which builds upon itself, such as recursive subdivision, L-systems, growth patterns, or repeated offsetting.
+///
+/// The body of the loop is the group of nodes feeding the *Iteration* input, from the *Previous Iteration* nodes it starts at to the *Iterate* node it ends at. The *Repeat Zone* node frames this body in a subgraph of its own.
+#[node_macro::node(category("Math: Logic"), path(core_types::vector))]
+async fn iterate<T: std::hash::Hash + Send + Sync + Clone + 'static>(
+	ctx: impl ExtractAll + CloneVarArgs + Ctx,
+	/// The content handed to the first iteration, which is also the result if *Count* is 0.
+	#[implementations(
+		Table<Graphic>,
+		Table<Vector>,
+		Table<Raster<CPU>>,
+		Table<Color>,
+		Table<GradientStops>,
+	)]
+	initial: T,
+	/// The content produced by each iteration, which usually reads the result of the previous iteration with a *Previous Iteration* node.
+	#[implementations(
+		Context -> Table<Graphic>,
+		Context -> Table<Vector>,
+		Context -> Table<Raster<CPU>>,
+		Context -> Table<Color>,
+		Context -> Table<GradientStops>,
+	)]
+	iteration: impl Node<'n, Context<'static>, Output = T>,
+	/// The number of times the iteration is evaluated.
+	#[default(1)]
+	#[hard_max(1000.)]
+	count: u64,
+) -> T {
+	// Every iteration's result follows from the initial content and the iteration number, so those are all the context needs to be told apart by
+	let initial_hash = MemoHash::new(&initial).hash_code();
+
+	let mut accumulator = initial;
+
+	// Each iteration depends on the one before it, so unlike *Instance Repeat*, these can't be evaluated in parallel
+	for index in 0..count as usize {
+		let previous = PreviousIteration { content: accumulator, initial_hash };
+		let new_ctx = OwnedContextImpl::from(ctx.clone()).with_index(index).with_vararg(Box::new(previous));
+		accumulator = iteration.eval(new_ctx.into_context()).await;
+	}
+
+	accumulator
+}
+
+/// The result of the previous iteration, which *Iterate* hands to its iteration through the context.
+///
+/// Rather than hashing the content again in every iteration, it's hashed by the initial content, alongside the iteration number in the context's index.
+struct PreviousIteration<T> {
+	content: T,
+	initial_hash: u64,
+}
+
+impl<T> std::hash::Hash for PreviousIteration<T> {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.initial_hash.hash(state);
+	}
+}
+
+/// Produces the result of the previous iteration of the innermost loop by reading from the evaluation context, which is supplied by a downstream *Iterate* node.
+///
+/// In the first iteration, this is the *Initial* value given to *Iterate*. When not evaluated inside of an *Iterate* node (or when its content is of another type), the *Fallback* is produced instead.
+#[node_macro::node(category("Math: Logic"), path(core_types::vector))]
+async fn previous_iteration<T: Send + Sync + Clone + 'static>(
+	ctx: impl Ctx + ExtractVarArgs,
+	/// The content produced when there is no previous iteration to read from.
+	#[implementations(
+		Table<Graphic>,
+		Table<Vector>,
+		Table<Raster<CPU>>,
+		Table<Color>,
+		Table<GradientStops>,
+	)]
+	fallback: T,
+) -> T {
+	match ctx.vararg(0).map(|dynamic| dynamic.downcast_ref::<PreviousIteration<T>>()) {
+		Ok(Some(previous)) => previous.content.clone(),
+		_ => fallback,
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
 	use crate::generator_nodes::RectangleNode;
 	use core_types::Ctx;
 	use core_types::Node;
+	use core_types::table::TableRow;
 	use glam::DVec2;
 	use graphene_core::extract_xy::{ExtractXyNode, XY};
 	use graphic_types::Vector;
@@ -177,4 +254,64 @@ mod test {
 			assert_eq!((bounds[1] - bounds[0]).x, position.y);
 		}
 	}
+
+	#[tokio::test]
+	async fn iterate_test() {
+		let owned = OwnedContextImpl::default().into_context();
+		let previous = PreviousIterationNode::new(FutureWrapperNode(Table::<Vector>::new()));
+		let append = AppendRowNode(previous);
+
+		let initial = Table::new_from_element(Vector::default());
+		let result = super::iterate(owned.clone(), initial.clone(), &append, 3).await;
+		assert_eq!(result.len(), 4);
+
+		let result = super::iterate(owned.clone(), initial, &append, 0).await;
+		assert_eq!(result.len(), 1);
+
+		// Outside of an iteration, the fallback is produced
+		assert_eq!(previous_iteration(owned, Table::<Vector>::new()).await.len(), 0);
+	}
+
+	#[tokio::test]
+	async fn iterate_context_hash_is_deterministic() {
+		let owned = OwnedContextImpl::default().into_context();
+		let recorder = ContextHashRecorderNode::default();
+		let initial = Table::new_from_element(Vector::default());
+
+		super::iterate(owned.clone(), initial.clone(), &recorder, 3).await;
+		super::iterate(owned.clone(), initial, &recorder, 3).await;
+		let hashes = recorder.0.lock().unwrap().clone();
+		assert_eq!(hashes[..3], hashes[3..]);
+		assert!(hashes[0] != hashes[1] && hashes[1] != hashes[2]);
+
+		// Different initial content gives each iteration a different context
+		let other_initial = Table::from_iter([TableRow::new_from_element(Vector::default()), TableRow::new_from_element(Vector::default())]);
+		super::iterate(owned, other_initial, &recorder, 1).await;
+		assert_ne!(recorder.0.lock().unwrap()[6], hashes[0]);
+	}
+
+	#[derive(Default)]
+	pub struct ContextHashRecorderNode(std::sync::Mutex<Vec<u64>>);
+
+	impl<'i> Node<'i, Context<'i>> for ContextHashRecorderNode {
+		type Output = Pin<Box<dyn Future<Output = Table<Vector>> + 'i + Send>>;
+		fn eval(&'i self, input: Context<'i>) -> Self::Output {
+			self.0.lock().unwrap().push(MemoHash::new(&input).hash_code());
+			Box::pin(async move { previous_iteration(input, Table::new()).await })
+		}
+	}
+
+	#[derive(Clone)]
+	pub struct AppendRowNode<N>(N);
+
+	impl<'i, N: for<'a> Node<'a, Context<'a>, Output = Pin<Box<dyn Future<Output = Table<Vector>> + 'a + Send>>> + Sync> Node<'i, Context<'i>> for AppendRowNode<N> {
+		type Output = Pin<Box<dyn Future<Output = Table<Vector>> + 'i + Send>>;
+		fn eval(&'i self, input: Context<'i>) -> Self::Output {
+			Box::pin(async move {
+				let mut table = self.0.eval(input).await;
+				table.push(TableRow::new_from_element(Vector::default()));
+				table
+			})
+		}
+	}
 }
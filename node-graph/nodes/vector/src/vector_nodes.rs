@@ -4,7 +4,7 @@ use core_types::bounds::{BoundingBox, RenderBoundingBox};
 use core_types::registry::types::{Angle, IntegerCount, Length, Multiplier, Percentage, PixelLength, PixelSize, Progression, SeedValue};
 use core_types::table::{Table, TableRow, TableRowMut};
 use core_types::transform::{Footprint, Transform};
-use core_types::{CloneVarArgs, Color, Context, Ctx, ExtractAll, ExtractVarArgs, OwnedContextImpl};
+use core_types::{CloneVarArgs, Color, Context, Ctx, ExtractAll, ExtractVarArgs, OwnedContextImpl, parallel};
 use glam::{DAffine2, DVec2};
 use graphic_types::Vector;
 use graphic_types::raster_types::{CPU, GPU, Raster};
@@ -1208,21 +1208,16 @@ fn instance_vector(ctx: impl Ctx + ExtractVarArgs) -> Table<Vector> {
 	var_arg.downcast_ref().cloned().unwrap_or_default()
 }
 
-#[node_macro::node(category("Vector: Modifier"), path(graphene_core::vector))]
+#[node_macro::node(category("Vector: Modifier"), path(graphene_core::vector), parallel)]
 async fn instance_map(ctx: impl Ctx + CloneVarArgs + ExtractAll, content: Table<Vector>, mapped: impl Node<Context<'static>, Output = Table<Vector>>) -> Table<Vector> {
-	let mut rows = Vec::new();
-
-	for (i, row) in content.into_iter().enumerate() {
+	let tables = parallel::join_all(content.into_iter().enumerate().map(|(i, row)| {
 		let owned_ctx = OwnedContextImpl::from(ctx.clone());
 		let owned_ctx = owned_ctx.with_vararg(Box::new(Table::new_from_row(row))).with_index(i);
-		let table = mapped.eval(owned_ctx.into_context()).await;
-
-		for inner_row in table {
-			rows.push(inner_row);
-		}
-	}
+		mapped.eval(owned_ctx.into_context())
+	}))
+	.await;
 
-	rows.into_iter().collect()
+	tables.into_iter().flatten().collect()
 }
 
 #[node_macro::node(category("Vector"), path(graphene_core::vector))]